@@ -0,0 +1,193 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use sagascript_core::audio::decoder::decode_audio_file_with_fallback;
+use sagascript_core::error::DictationError;
+use sagascript_core::settings::Language;
+use sagascript_core::transcription::benchmark::{self, BenchmarkRun};
+use sagascript_core::transcription::{catalog, model, TranscribeOptions};
+
+use crate::transcribe::{model_id_string, parse_language, parse_model};
+
+/// The clip used without a FILE: 8 seconds of Norwegian parliament speech
+/// (NPSC, CC0), the same as `test-audio/norwegian-medium-8s.mp3`.
+const REFERENCE_CLIP: &[u8] = include_bytes!("../../../../test-audio/norwegian-medium-8s.mp3");
+
+#[derive(Args)]
+pub struct BenchmarkArgs {
+    /// Audio or video file to time the models on (default: a bundled
+    /// 8-second clip of Norwegian speech)
+    pub file: Option<PathBuf>,
+
+    /// Comma-separated model IDs to time (default: every downloaded model)
+    /// [see: sagascript list-models]
+    #[arg(short, long, value_name = "MODEL_IDS", value_delimiter = ',')]
+    pub models: Vec<String>,
+
+    /// Language code (en, sv, no, auto). Defaults to Norwegian for the
+    /// bundled clip and to the saved setting for a FILE
+    #[arg(short, long)]
+    pub language: Option<String>,
+
+    /// Output JSON: per-model timings, memory and text
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run(args: BenchmarkArgs) -> Result<(), DictationError> {
+    let stored = sagascript_core::settings::store::load();
+    let language = match (&args.language, &args.file) {
+        (Some(l), _) => parse_language(l)?,
+        (None, Some(_)) => stored.language,
+        (None, None) => Language::Norwegian,
+    };
+    let models = if args.models.is_empty() {
+        catalog::all_models()
+            .into_iter()
+            .filter(|&m| model::is_model_downloaded(m))
+            .collect()
+    } else {
+        let mut models = Vec::new();
+        for id in &args.models {
+            let model = parse_model(id.trim())?;
+            if !model::is_model_downloaded(model) {
+                return Err(DictationError::TranscriptionFailed(format!(
+                    "{id} is not downloaded. Run: sagascript download-model {id}"
+                )));
+            }
+            models.push(model);
+        }
+        models
+    };
+    if models.is_empty() {
+        return Err(DictationError::TranscriptionFailed(
+            "No models downloaded. Run: sagascript download-model <MODEL_ID>".to_string(),
+        ));
+    }
+    let models = benchmark::run_order(&models);
+
+    let (clip_name, audio) = match &args.file {
+        Some(file) => (
+            file.display().to_string(),
+            decode_audio_file_with_fallback(file, stored.ffmpeg_fallback, |_| {})?,
+        ),
+        None => (
+            "bundled reference clip".to_string(),
+            decode_reference_clip()?,
+        ),
+    };
+    let duration = audio.len() as f64 / 16_000.0;
+    eprintln!("Audio: {clip_name}, {duration:.1}s");
+
+    // Decoded as a dictation is, with the saved beam and fallback settings.
+    let opts = TranscribeOptions {
+        beam_size: stored.beam_size,
+        temperature_fallback: stored.temperature_fallback,
+        model_overrides: stored.model_overrides.clone(),
+        ..Default::default()
+    };
+    let mut runs = Vec::with_capacity(models.len());
+    for model in models {
+        eprintln!("Benchmarking {}...", model.display_name());
+        runs.push(benchmark::run(
+            model,
+            &audio,
+            language,
+            &opts,
+            stored.use_gpu,
+        )?);
+    }
+
+    if args.json {
+        let results: Vec<serde_json::Value> = runs
+            .iter()
+            .map(|r| {
+                serde_json::json!({
+                    "model": model_id_string(r.model),
+                    "load_ms": r.load_ms,
+                    "transcribe_ms": r.transcribe_ms,
+                    "real_time_factor": r.real_time_factor,
+                    "peak_memory_bytes": r.peak_memory_bytes,
+                    "text": r.text,
+                })
+            })
+            .collect();
+        let json = serde_json::json!({
+            "file": args.file.as_ref().map(|f| f.display().to_string()),
+            "language": language,
+            "duration_seconds": duration,
+            "use_gpu": stored.use_gpu,
+            "results": results,
+        });
+        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        return Ok(());
+    }
+
+    println!(
+        "{:<22} {:>8} {:>11} {:>6} {:>9}",
+        "MODEL", "LOAD", "TRANSCRIBE", "RTF", "MEMORY"
+    );
+    println!("{}", "-".repeat(60));
+    for r in &runs {
+        println!("{}", row(r));
+    }
+    println!("\nRTF below 1.0 transcribes faster than real time. MEMORY is the peak");
+    println!("resident size of the process; models run smallest first.");
+    Ok(())
+}
+
+fn row(r: &BenchmarkRun) -> String {
+    format!(
+        "{:<22} {:>7.1}s {:>10.1}s {:>6} {:>9}",
+        model_id_string(r.model),
+        r.load_ms as f64 / 1000.0,
+        r.transcribe_ms as f64 / 1000.0,
+        r.real_time_factor
+            .map_or("-".to_string(), |rtf| format!("{rtf:.2}")),
+        r.peak_memory_bytes
+            .map_or("-".to_string(), |bytes| format!("{} MB", bytes / 1_000_000)),
+    )
+}
+
+/// Decode [`REFERENCE_CLIP`] through a temporary file, as the decoder reads
+/// from paths.
+fn decode_reference_clip() -> Result<Vec<f32>, DictationError> {
+    let path =
+        std::env::temp_dir().join(format!("sagascript-benchmark-{}.mp3", uuid::Uuid::new_v4()));
+    std::fs::write(&path, REFERENCE_CLIP).map_err(|e| {
+        DictationError::FileDecodeError(format!("Cannot write {}: {e}", path.display()))
+    })?;
+    let audio = decode_audio_file_with_fallback(&path, false, |_| {});
+    let _ = std::fs::remove_file(&path);
+    audio
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sagascript_core::settings::WhisperModel;
+
+    #[test]
+    fn reference_clip_decodes() {
+        let audio = decode_reference_clip().unwrap();
+        let secs = audio.len() as f64 / 16_000.0;
+        assert!((7.0..9.0).contains(&secs), "{secs}");
+    }
+
+    #[test]
+    fn row_shows_missing_numbers_as_dashes() {
+        let r = BenchmarkRun {
+            model: WhisperModel::BaseEn,
+            load_ms: 420,
+            transcribe_ms: 1_300,
+            real_time_factor: None,
+            peak_memory_bytes: None,
+            text: String::new(),
+        };
+        assert_eq!(
+            row(&r),
+            "base.en                    0.4s        1.3s      -         -"
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+
+use sagascript_core::error::DictationError;
+use sagascript_core::transcription::{bundle, catalog, model};
+
+use super::transcribe::{parse_language, parse_model};
+
+#[derive(Args)]
+pub struct BundleArgs {
+    #[command(subcommand)]
+    pub action: BundleAction,
+}
+
+#[derive(Subcommand)]
+pub enum BundleAction {
+    /// Pack downloaded models into a tar archive
+    Export {
+        /// Archive to write (e.g. models.tar)
+        path: PathBuf,
+
+        /// Model to include; repeat for several (default: every downloaded model)
+        #[arg(short, long = "model", value_name = "MODEL")]
+        models: Vec<String>,
+
+        /// Include only downloaded models for this language
+        #[arg(short, long, value_name = "LANG", conflicts_with = "models")]
+        language: Option<String>,
+
+        /// Leave out the VAD model even if it is downloaded
+        #[arg(long)]
+        no_vad: bool,
+    },
+    /// Install the models from a bundle into the models directory
+    Import {
+        /// Archive written by `bundle export`
+        path: PathBuf,
+    },
+}
+
+pub fn run(args: BundleArgs) -> Result<(), DictationError> {
+    match args.action {
+        BundleAction::Export {
+            path,
+            models,
+            language,
+            no_vad,
+        } => export(path, &models, language.as_deref(), no_vad),
+        BundleAction::Import { path } => import(path),
+    }
+}
+
+fn export(
+    path: PathBuf,
+    models: &[String],
+    language: Option<&str>,
+    no_vad: bool,
+) -> Result<(), DictationError> {
+    let selected = if models.is_empty() {
+        let filter = catalog::ModelFilter {
+            language: language.map(parse_language).transpose()?,
+            downloaded_only: true,
+            max_size_mb: None,
+        };
+        catalog::list_models(&filter, None)
+            .iter()
+            .map(|info| parse_model(&info.id))
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        models
+            .iter()
+            .map(|m| parse_model(m))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    if selected.is_empty() {
+        return Err(DictationError::BundleError(
+            "No downloaded models to bundle. Download some first: sagascript download-model"
+                .to_string(),
+        ));
+    }
+    let include_vad = !no_vad && model::is_vad_model_downloaded();
+
+    eprintln!("Verifying and packing {} model(s)...", selected.len());
+    let manifest = bundle::export_bundle(&path, &selected, include_vad)?;
+    let total: u64 = manifest.files.iter().map(|f| f.size).sum();
+    for file in &manifest.files {
+        eprintln!("  {}", file.model.as_deref().unwrap_or(&file.name));
+    }
+    eprintln!(
+        "Wrote {} file(s), {:.0} MB.",
+        manifest.files.len(),
+        total as f64 / 1_048_576.0
+    );
+    println!("{}", path.display());
+    Ok(())
+}
+
+fn import(path: PathBuf) -> Result<(), DictationError> {
+    eprintln!("Importing {}...", path.display());
+    let summary = bundle::import_bundle(&path)?;
+    for name in &summary.installed {
+        eprintln!("  installed {name}");
+    }
+    for name in &summary.already_present {
+        eprintln!("  {name} already present");
+    }
+    eprintln!("Models are in {}", model::models_dir().display());
+    Ok(())
+}
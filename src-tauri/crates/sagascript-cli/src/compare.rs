@@ -0,0 +1,296 @@
+use std::path::PathBuf;
+use std::time::Instant;
+
+use clap::Args;
+use indicatif::{ProgressBar, ProgressStyle};
+
+use sagascript_core::audio::decoder::decode_audio_file_with_fallback;
+use sagascript_core::error::DictationError;
+use sagascript_core::settings::WhisperModel;
+use sagascript_core::transcription::model;
+use sagascript_core::transcription::rtf::real_time_factor;
+use sagascript_core::transcription::{TranscribeOptions, WhisperBackend, FILE_TRANSCRIBE_BEAM};
+
+use crate::transcribe::{model_id_string, parse_language, parse_model, resolve_effective_prompt};
+
+/// Word diffs are quadratic; past this many word pairs only timings and full
+/// texts are shown.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Args)]
+pub struct CompareArgs {
+    /// Audio or video file to transcribe with every model
+    pub file: PathBuf,
+
+    /// Comma-separated model IDs to compare; the first is the baseline the
+    /// others are diffed against [see: sagascript list-models]
+    #[arg(
+        short,
+        long,
+        value_name = "MODEL_IDS",
+        value_delimiter = ',',
+        required = true
+    )]
+    pub models: Vec<String>,
+
+    /// Language code (en, sv, no, auto). Defaults to the saved setting
+    #[arg(short, long)]
+    pub language: Option<String>,
+
+    /// Hint the decoder with domain-specific vocabulary (Whisper initial
+    /// prompt), the same for every model. Defaults to the saved prompt
+    #[arg(long, visible_alias = "hint", value_name = "TEXT")]
+    pub prompt: Option<String>,
+
+    /// Output JSON: per-model timings and text
+    #[arg(long)]
+    pub json: bool,
+}
+
+struct Run {
+    model: WhisperModel,
+    load_secs: f64,
+    transcribe_secs: f64,
+    rtf: Option<f32>,
+    text: String,
+}
+
+pub fn run(args: CompareArgs) -> Result<(), DictationError> {
+    let stored = sagascript_core::settings::store::load();
+    let language = match &args.language {
+        Some(l) => parse_language(l)?,
+        None => stored.language,
+    };
+    let mut models = Vec::new();
+    for id in &args.models {
+        let model = parse_model(id.trim())?;
+        if !models.contains(&model) {
+            models.push(model);
+        }
+    }
+    if models.len() < 2 {
+        return Err(DictationError::SettingsError(
+            "Give at least two different models, e.g. --models base.en,small.en".to_string(),
+        ));
+    }
+    let missing: Vec<&str> = models
+        .iter()
+        .filter(|&&m| !model::is_model_downloaded(m))
+        .map(|&m| model_id_string(m))
+        .collect();
+    if !missing.is_empty() {
+        return Err(DictationError::TranscriptionFailed(format!(
+            "Not downloaded: {}. Run: sagascript download-model {}",
+            missing.join(", "),
+            missing[0]
+        )));
+    }
+
+    eprintln!("Decoding {}...", args.file.display());
+    let pb = ProgressBar::new(100);
+    pb.set_style(ProgressStyle::with_template("  Decoding [{bar:40}] {pos}%").unwrap());
+    let audio = decode_audio_file_with_fallback(&args.file, stored.ffmpeg_fallback, |pct| {
+        pb.set_position(pct.into())
+    })?;
+    pb.finish_and_clear();
+    let duration = audio.len() as f64 / 16_000.0;
+    eprintln!("Audio: {duration:.1}s");
+
+    // Same decoding as `transcribe`, so the numbers carry over to real use.
+    let opts = TranscribeOptions {
+        prompt: resolve_effective_prompt(args.prompt.as_deref(), None, &stored.initial_prompt)?,
+        keywords: stored.keywords.clone(),
+        beam_size: if stored.beam_size >= 2 {
+            stored.beam_size
+        } else {
+            FILE_TRANSCRIBE_BEAM
+        },
+        temperature_fallback: stored.temperature_fallback,
+        localize_numbers: stored.localize_numbers,
+        model_overrides: stored.model_overrides.clone(),
+        ..Default::default()
+    };
+
+    let mut runs = Vec::with_capacity(models.len());
+    for model in models {
+        eprintln!("Transcribing with {}...", model.display_name());
+        let backend = WhisperBackend::with_gpu(stored.use_gpu);
+        let started = Instant::now();
+        backend.load_model(model)?;
+        let load_secs = started.elapsed().as_secs_f64();
+        let started = Instant::now();
+        let text = backend
+            .transcribe_sync_with_options(&audio, language, &opts, |_| {})?
+            .into_text();
+        let elapsed = started.elapsed();
+        runs.push(Run {
+            model,
+            load_secs,
+            transcribe_secs: elapsed.as_secs_f64(),
+            rtf: real_time_factor(duration as f32, elapsed),
+            text,
+        });
+    }
+
+    let baseline = &runs[0];
+    if args.json {
+        let results: Vec<serde_json::Value> = runs
+            .iter()
+            .map(|r| {
+                serde_json::json!({
+                    "model": model_id_string(r.model),
+                    "load_seconds": r.load_secs,
+                    "transcribe_seconds": r.transcribe_secs,
+                    "real_time_factor": r.rtf,
+                    "words_changed": changed_words(&baseline.text, &r.text),
+                    "text": r.text,
+                })
+            })
+            .collect();
+        let json = serde_json::json!({
+            "file": args.file.display().to_string(),
+            "language": language,
+            "duration_seconds": duration,
+            "baseline": model_id_string(baseline.model),
+            "results": results,
+        });
+        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        return Ok(());
+    }
+
+    println!(
+        "{:<22} {:>8} {:>11} {:>6} {:>8}",
+        "MODEL", "LOAD", "TRANSCRIBE", "RTF", "CHANGED"
+    );
+    println!("{}", "-".repeat(59));
+    for r in &runs {
+        println!(
+            "{:<22} {:>7.1}s {:>10.1}s {:>6} {:>8}",
+            model_id_string(r.model),
+            r.load_secs,
+            r.transcribe_secs,
+            r.rtf.map_or("-".to_string(), |rtf| format!("{rtf:.2}")),
+            changed_words(&baseline.text, &r.text).map_or("-".to_string(), |n| n.to_string()),
+        );
+    }
+
+    println!(
+        "\n== {} (baseline)\n{}",
+        model_id_string(baseline.model),
+        baseline.text
+    );
+    for r in &runs[1..] {
+        println!(
+            "\n== {} vs {}",
+            model_id_string(r.model),
+            model_id_string(baseline.model)
+        );
+        match word_diff(&baseline.text, &r.text) {
+            Some(diff) => println!("{}", render(&diff)),
+            None => println!("(too long to diff)\n{}", r.text),
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+enum Edit<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Compare words ignoring case and surrounding punctuation, so a model that
+/// only punctuates differently isn't reported as hearing something else.
+fn normalized(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+/// Word-level diff of `new` against `old` (longest common subsequence).
+/// `None` when the texts are too long to diff.
+fn word_diff<'a>(old: &'a str, new: &'a str) -> Option<Vec<Edit<'a>>> {
+    let a: Vec<&str> = old.split_whitespace().collect();
+    let b: Vec<&str> = new.split_whitespace().collect();
+    if (a.len() + 1) * (b.len() + 1) > MAX_DIFF_CELLS {
+        return None;
+    }
+    let (na, nb): (Vec<String>, Vec<String>) = (
+        a.iter().map(|w| normalized(w)).collect(),
+        b.iter().map(|w| normalized(w)).collect(),
+    );
+
+    // lcs[i][j] = LCS length of a[i..] and b[j..].
+    let width = b.len() + 1;
+    let mut lcs = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i * width + j] = if na[i] == nb[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut edits = Vec::with_capacity(a.len().max(b.len()));
+    while i < a.len() && j < b.len() {
+        if na[i] == nb[j] {
+            edits.push(Edit::Same(b[j]));
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            edits.push(Edit::Removed(a[i]));
+            i += 1;
+        } else {
+            edits.push(Edit::Added(b[j]));
+            j += 1;
+        }
+    }
+    edits.extend(a[i..].iter().map(|w| Edit::Removed(w)));
+    edits.extend(b[j..].iter().map(|w| Edit::Added(w)));
+    Some(edits)
+}
+
+/// Words added or removed relative to the baseline.
+fn changed_words(old: &str, new: &str) -> Option<usize> {
+    word_diff(old, new).map(|diff| diff.iter().filter(|e| !matches!(e, Edit::Same(_))).count())
+}
+
+/// `git diff --word-diff=plain` style: `[-removed-]{+added+}`.
+fn render(diff: &[Edit<'_>]) -> String {
+    diff.iter()
+        .map(|e| match e {
+            Edit::Same(w) => w.to_string(),
+            Edit::Removed(w) => format!("[-{w}-]"),
+            Edit::Added(w) => format!("{{+{w}+}}"),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_marks_substituted_words() {
+        let diff = word_diff("the cat sat", "the bat sat").unwrap();
+        assert_eq!(render(&diff), "the [-cat-] {+bat+} sat");
+        assert_eq!(changed_words("the cat sat", "the bat sat"), Some(2));
+    }
+
+    #[test]
+    fn diff_ignores_case_and_punctuation() {
+        assert_eq!(changed_words("Hello, world.", "hello world"), Some(0));
+    }
+
+    #[test]
+    fn diff_handles_appended_and_dropped_words() {
+        let diff = word_diff("one two", "one two three").unwrap();
+        assert_eq!(diff.last(), Some(&Edit::Added("three")));
+        let diff = word_diff("one two three", "two three").unwrap();
+        assert_eq!(diff.first(), Some(&Edit::Removed("one")));
+    }
+}
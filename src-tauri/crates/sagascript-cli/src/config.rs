@@ -1,7 +1,21 @@
 use clap::{Args, Subcommand};
 
+use sagascript_core::credentials;
 use sagascript_core::error::DictationError;
-use sagascript_core::settings::{self, HotkeyMode, Language, Settings, WhisperModel};
+use sagascript_core::llm;
+use sagascript_core::mqtt;
+use sagascript_core::output::{format_sinks, parse_sinks, wants_paste};
+use sagascript_core::paste_format::{self, AppCategory};
+use sagascript_core::replacements::ReplacementRule;
+use sagascript_core::settings::{
+    self, format_tray_menu, parse_tray_menu, BackendKind, CaptureSource, HotkeyMode, Language, ModelOverrides, ModelPin, Profile,
+    Settings, SettingsTab, SoundCue, WhileBusy, WhisperModel, MAX_MONITOR_VOLUME,
+    MAX_RECORDING_MINUTES, MAX_SILENCE_STOP_SECS,
+};
+use sagascript_core::transcription::{backend, keywords, runtime};
+
+use crate::porcelain;
+use crate::table::{Column, Table, TableArgs};
 
 #[derive(Args)]
 pub struct ConfigArgs {
@@ -17,8 +31,41 @@ Show all settings in a table with their current values and defaults.
 
 Valid keys: language, whisper_model, hotkey_mode, show_overlay, \
 auto_paste, auto_select_model, hotkey, initial_prompt, \
-beam_size, temperature_fallback, vad_enabled")]
-    List,
+beam_size, temperature_fallback, vad_enabled, adaptive_model, \
+adaptive_short_clip_secs, adaptive_long_clip_secs, accumulate_mode, \
+flush_hotkey, flush_phrase, output_sinks, model_update_check, \
+monitor_input, monitor_volume, ffmpeg_fallback, while_busy, keywords, \
+localize_numbers, voice_commands, paste_formatting, tray_visible, tray_status_text, \
+tray_menu, telemetry, telemetry_endpoint, overlay_high_contrast, \
+overlay_large_text, overlay_reduced_motion, announce_state, \
+settings_hotkey, settings_hotkey_tab, toggle_hotkey, \
+max_recording_minutes, silence_stop_secs, paste_confirm_chars, capture_source, \
+standby_stream, split_channels, post_process_command, \
+post_process_prompt, post_process_model, transcription_backend, \
+openai_model, azure_region, event_stream_port, use_gpu, punctuate, \
+custom_model, draft_pass, replace_draft, dedupe_dictations, network_proxy, \
+model_mirror, dry_run, preload_model
+
+--porcelain prints one `setting` record per key (key, current value, \
+default) in the stable format described under 'transcribe --help'.
+
+The table fits the terminal width, cutting long values with '…'. Use \
+--columns to pick columns (key, current, default), --no-header to drop \
+the header, and --width 0 to never cut.")]
+    List {
+        /// Print stable, versioned `setting` records for scripts (VERSION: 1)
+        #[arg(
+            long,
+            value_name = "VERSION",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "1"
+        )]
+        porcelain: Option<porcelain::Version>,
+
+        #[command(flatten)]
+        table: TableArgs,
+    },
 
     /// Get a single setting value
     #[command(
@@ -27,7 +74,20 @@ Print the current value of a single setting to stdout.
 
 Valid keys: language, whisper_model, hotkey_mode, show_overlay, \
 auto_paste, auto_select_model, hotkey, initial_prompt, \
-beam_size, temperature_fallback, vad_enabled",
+beam_size, temperature_fallback, vad_enabled, adaptive_model, \
+adaptive_short_clip_secs, adaptive_long_clip_secs, accumulate_mode, \
+flush_hotkey, flush_phrase, output_sinks, model_update_check, \
+monitor_input, monitor_volume, ffmpeg_fallback, while_busy, keywords, \
+localize_numbers, voice_commands, paste_formatting, tray_visible, tray_status_text, \
+tray_menu, telemetry, telemetry_endpoint, overlay_high_contrast, \
+overlay_large_text, overlay_reduced_motion, announce_state, \
+settings_hotkey, settings_hotkey_tab, toggle_hotkey, \
+max_recording_minutes, silence_stop_secs, paste_confirm_chars, capture_source, \
+standby_stream, split_channels, post_process_command, \
+post_process_prompt, post_process_model, transcription_backend, \
+openai_model, azure_region, event_stream_port, use_gpu, punctuate, \
+custom_model, draft_pass, replace_draft, dedupe_dictations, network_proxy, \
+model_mirror, dry_run, preload_model",
         after_long_help = "\
 EXAMPLES:
   sagascript config get language
@@ -35,15 +95,27 @@ EXAMPLES:
   sagascript config get initial_prompt"
     )]
     Get {
-        /// Setting key [possible values: language, whisper_model, hotkey_mode, show_overlay, auto_paste, auto_select_model, hotkey, initial_prompt, beam_size, temperature_fallback, vad_enabled]
+        /// Setting key [possible values: language, whisper_model, hotkey_mode, show_overlay, auto_paste, auto_select_model, hotkey, initial_prompt, beam_size, temperature_fallback, vad_enabled, adaptive_model, adaptive_short_clip_secs, adaptive_long_clip_secs, accumulate_mode, flush_hotkey, flush_phrase, output_sinks, model_update_check, monitor_input, monitor_volume, ffmpeg_fallback, while_busy, keywords, localize_numbers, voice_commands, paste_formatting, tray_visible, tray_status_text, tray_menu, telemetry, telemetry_endpoint, overlay_high_contrast, overlay_large_text, overlay_reduced_motion, announce_state, settings_hotkey, settings_hotkey_tab, toggle_hotkey, max_recording_minutes, silence_stop_secs, paste_confirm_chars, capture_source, standby_stream, split_channels, post_process_command, post_process_prompt, post_process_model, transcription_backend, openai_model, azure_region, event_stream_port, use_gpu, punctuate, custom_model, draft_pass, replace_draft, dedupe_dictations, network_proxy, model_mirror, dry_run, preload_model]
         key: String,
+        /// Print a stable, versioned `setting` record (key, value, default)
+        /// instead of the bare value (VERSION: 1)
+        #[arg(
+            long,
+            value_name = "VERSION",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "1"
+        )]
+        porcelain: Option<porcelain::Version>,
     },
 
-    /// Set a setting value
+    /// Set one or more setting values
     #[command(
         long_about = "\
-Update a setting. The new value takes effect immediately — the GUI \
-hot-reloads changes made via CLI.
+Update a setting, or several at once as KEY=VALUE pairs. The new values \
+take effect immediately — the GUI hot-reloads changes made via CLI. \
+Several pairs are checked first and saved together: if one is invalid, \
+nothing changes.
 
 Valid values per key:
   language             en, sv, no, auto (auto uses a generic model — less accurate)
@@ -52,26 +124,123 @@ Valid values per key:
                        nb-whisper-base, nb-whisper-small
   hotkey_mode          push, toggle
   show_overlay         true, false
-  auto_paste           true, false (enabling requires Accessibility approval for the installed GUI)
+  auto_paste           true, false (enabling requires Accessibility approval for the installed GUI;
+                       adds or removes the paste entry in output_sinks)
   auto_select_model    true, false
   hotkey               Modifier+Key (e.g. Control+Shift+Space, Option+Space)
   initial_prompt       Any string (e.g. names, jargon, preferred spellings)
   beam_size            Integer >= 0 (0 = greedy/fast, 5 = beam search/accurate)
   temperature_fallback true, false
-  vad_enabled          true, false",
+  vad_enabled          true, false
+  adaptive_model       true, false (pick a fast or accurate model from clip length)
+  adaptive_short_clip_secs Seconds; shorter clips use the fast model when adaptive_model is on
+  adaptive_long_clip_secs Seconds; clips this long or longer use the accurate model
+  accumulate_mode      true, false (buffer dictations in the overlay, paste on flush)
+  flush_hotkey         Modifier+Key; pastes the accumulate_mode buffer
+  flush_phrase         Spoken phrase that pastes the buffer (empty = off)
+  output_sinks         Comma-separated: paste, clipboard, file:PATH, pipe:PATH, webhook:URL, mqtt://HOST/TOPIC,
+                       daily-note:VAULT[;file=YYYY-MM-DD.md][;heading=## Dictations] (default paste;
+                       empty = none). paste pastes into the focused app (the GUI only).
+                       File, pipe and webhook sinks take ;template=... last, with {text}, {timestamp},
+                       {language}, {model} and {app}; write \\n for a line break and \\, for a comma
+  model_update_check   Daily background check for newer model revisions (true/false)
+  monitor_input        Play the mic back on headphones while recording (true/false)
+  monitor_volume       Monitor gain, 0.0 to 2.0 (default 0.5)
+  ffmpeg_fallback      Retry undecodable files through an installed ffmpeg (true/false)
+  while_busy           reject, queue, cancel-and-restart (hotkey press while transcribing)
+  keywords             Comma-separated words to bias decoding toward (empty = none)
+  localize_numbers     Write spoken numbers and dates as digits for the language (true/false)
+  voice_commands       Type spoken punctuation and line breaks as characters (true/false)
+  paste_formatting     Format auto-paste for the frontmost app: terminals, mail (true/false)
+  tray_visible         Show the tray / menu-bar icon (true/false)
+  tray_status_text     Show state and last transcription in the tray (true/false)
+  tray_menu            Comma-separated tray menu items: status, settings, transcribe-file,
+                       capture-source, install-cli
+  telemetry            Queue anonymous usage counts locally (true/false)
+  telemetry_endpoint   URL that 'sagascript telemetry send' uploads to
+  overlay_high_contrast Solid black-and-white overlay (true/false)
+  overlay_large_text   Draw the overlay at 1.5x size (true/false)
+  overlay_reduced_motion Don't animate the overlay (true/false)
+  announce_state       Announce state changes to screen readers (true/false)
+  settings_hotkey      Modifier+Key that opens the window at settings_hotkey_tab (empty = none)
+  settings_hotkey_tab  dictate, transcribe, settings, models, history, diagnostics
+  toggle_hotkey        Second hotkey that always toggles (empty: none)
+  max_recording_minutes Longest dictation, 1-120 minutes (~11.5 MB/min)
+  silence_stop_secs    Toggle mode: stop after this many seconds of silence, 0-60 (0: off)
+  paste_confirm_chars  Ask before auto-pasting more than this many characters (0: never ask)
+  capture_source       microphone, system (record what the machine is playing)
+  standby_stream       Keep the input open between recordings for instant capture (true/false)
+  split_channels       Transcribe each channel of a file separately, labelled CH1, CH2 (true/false)
+  post_process_command Clean up each dictation with a language model (empty = off): a shell
+                       command that reads the prompt and transcript on stdin ({prompt} and
+                       {text} pass them as arguments instead), or the URL of an
+                       OpenAI-compatible chat completions endpoint. On failure the raw
+                       transcript is kept
+  post_process_prompt  Instructions for the model (empty = fix grammar and punctuation)
+  post_process_model   Model the endpoint should use (e.g. llama3.2, gpt-4o-mini)
+  transcription_backend local, openai, azure (dictations only; sends the audio to OpenAI or
+                       to your Azure Speech resource, with the key from
+                       'sagascript config set-api-key' or OPENAI_API_KEY / AZURE_SPEECH_KEY)
+  openai_model         Model the OpenAI backend uses (e.g. whisper-1, gpt-4o-transcribe)
+  azure_region         Region of the Azure Speech resource (e.g. westeurope). Azure needs a
+                       language, so auto-detect is transcribed as English
+  event_stream_port    Serve state changes, results and errors as server-sent events at
+                       http://127.0.0.1:PORT/events (0 = off; applies when the app starts).
+                       Clients send 'Authorization: Bearer TOKEN', with the token in
+                       event-stream-token in the app data directory (also in Settings)
+  use_gpu              Run local models on the GPU (Metal, or CUDA in cuda builds) when
+                       the build has one (default true; false = CPU only)
+  punctuate            true, false (false = strip punctuation from dictations, e.g. for
+                       terminals; spoken punctuation from voice_commands is kept)
+  custom_model         Name of a model registered with 'sagascript models add' to use
+                       instead of the built-in ones (empty = built-in)
+  draft_pass           true, false (paste a tiny-model draft right away, then the result of the
+                       selected model; local models only)
+  replace_draft        true, false (swap the pasted draft for the final text when it is
+                       ready; macOS, needs the Accessibility permission)
+  dedupe_dictations    true, false (skip a dictation whose audio repeats the previous one's,
+                       e.g. from an accidental double press)
+  network_proxy        Proxy URL for downloads, cloud backends, post-processing endpoints
+                       and update checks (http://proxy.corp:3128, socks5h://host:1080);
+                       empty uses HTTPS_PROXY
+  model_mirror         Base URL of a Hugging Face mirror to download models from first
+                       (http://models.lan/hf); empty downloads from Hugging Face
+  dry_run              true, false (run dictations but only save them to the history and
+                       send events: no paste, clipboard or output sinks)
+  preload_model        true, false (load the model when the app starts, so the first
+                       dictation doesn't wait for it; read at startup)",
         after_long_help = "\
 EXAMPLES:
   sagascript config set language sv
+  sagascript config set language=sv auto_paste=false hotkey='Option+Space'
   sagascript config set whisper_model kb-whisper-base
   sagascript config set hotkey 'Option+Space'
   sagascript config set auto_paste false
-  sagascript config set initial_prompt 'Sagascript, Tauri, whisper-rs'"
+  sagascript config set initial_prompt 'Sagascript, Tauri, whisper-rs'
+  sagascript config set output_sinks 'paste,file:~/notes/dictation.md'
+  sagascript config set output_sinks 'pipe:/tmp/sagascript.fifo'
+  sagascript config set output_sinks 'mqtt://homeassistant.local/sagascript'
+  sagascript config set output_sinks 'daily-note:~/Obsidian/Vault;file=Daily/YYYY-MM-DD.md'
+  sagascript config set output_sinks 'file:~/log.md;template=- {timestamp} [{app}] {text}'
+  sagascript config set output_sinks 'webhook:https://discord.com/api/webhooks/ID;template={\"content\": \"{text}\"}'
+  sagascript config set keywords 'Alpha-7,SKU 4411,metoprolol'
+  sagascript config set post_process_command 'ollama run llama3.2'
+  sagascript config set post_process_command=http://localhost:11434/v1/chat/completions post_process_model=llama3.2"
     )]
     Set {
-        /// Setting key [possible values: language, whisper_model, hotkey_mode, show_overlay, auto_paste, auto_select_model, hotkey, initial_prompt, beam_size, temperature_fallback, vad_enabled]
-        key: String,
-        /// New value for the setting
-        value: String,
+        /// KEY VALUE, or one or more KEY=VALUE [keys: see `config get --help`]
+        #[arg(required = true, value_name = "KEY=VALUE")]
+        pairs: Vec<String>,
+        /// Print the stored result as a stable, versioned `setting` record
+        /// (key, value, default) on stdout (VERSION: 1)
+        #[arg(
+            long,
+            value_name = "VERSION",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "1"
+        )]
+        porcelain: Option<porcelain::Version>,
     },
 
     /// Reset one or all settings to defaults
@@ -94,11 +263,356 @@ EXAMPLES:
         key: Option<String>,
     },
 
+    /// Reset one setting to its default (same as `config reset KEY`)
+    #[command(after_long_help = "\
+EXAMPLES:
+  sagascript config unset initial_prompt")]
+    Unset {
+        /// Setting key to reset
+        key: String,
+    },
+
     /// Print the settings file path
     #[command(long_about = "\
 Print the absolute path to the settings JSON file. Useful for manual \
 editing or backup.")]
     Path,
+
+    /// Check the settings file for errors
+    #[command(
+        long_about = "\
+Check the settings file after editing it by hand. A file that isn't valid \
+JSON, or has a setting with a wrong value, is not loaded: Sagascript uses \
+the defaults until it is fixed. This prints where the problem is (line, \
+column and setting) and exits non-zero.
+
+Keys that are not settings, usually misspelled ones, are listed as \
+warnings; they are ignored when the file is loaded.",
+        after_long_help = "\
+EXAMPLES:
+  sagascript config validate
+  \"$EDITOR\" \"$(sagascript config path)\" && sagascript config validate"
+    )]
+    Validate,
+
+    /// Show recently used initial prompts
+    #[command(
+        long_about = "\
+Print the initial prompts you have set recently, newest first, one per \
+line. Every 'config set initial_prompt' (or change in the GUI) is \
+remembered, up to the last 10 distinct prompts.
+
+Each dictation profile also keeps its own list: prompts passed to \
+'record --profile NAME --prompt TEXT', or set in the GUI after dictating \
+with the profile. --profile prints that list, or the global one while the \
+profile has none.",
+        after_long_help = "\
+EXAMPLES:
+  sagascript config prompt-history
+  sagascript config prompt-history --profile Medical
+
+  # Re-apply the second most recent prompt
+  sagascript config set initial_prompt \"$(sagascript config prompt-history | sed -n 2p)\"
+
+  # Forget the prompts of one profile, or all remembered prompts
+  sagascript config prompt-history --profile Medical --clear
+  sagascript config prompt-history --clear"
+    )]
+    PromptHistory {
+        /// Show (or clear) this profile's prompts instead of the global ones
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+
+        /// Forget the remembered prompts: all of them, or the profile's
+        #[arg(long)]
+        clear: bool,
+    },
+
+    /// Show or change per-model decoding overrides
+    #[command(
+        long_about = "\
+Override decoding settings for one model, on top of the built-in defaults. \
+Useful when a microphone makes a model drop quiet speech or invent text \
+in silence: raise the no-speech threshold to keep more, lower it (or set \
+0 to disable the filter) when real words go missing.
+
+Without flags, prints the effective values for MODEL (or every override \
+when MODEL is omitted). Unset values follow the model's default no-speech \
+threshold and the global beam_size and temperature_fallback settings.",
+        after_long_help = "\
+EXAMPLES:
+  sagascript config model-override small.en --no-speech-threshold 0.5
+  sagascript config model-override kb-whisper-base --beam-size 5
+  sagascript config model-override small.en
+  sagascript config model-override
+  sagascript config model-override small.en --clear"
+    )]
+    ModelOverride {
+        /// Model ID [see: sagascript list-models]
+        model: Option<String>,
+        /// Drop segments whisper rates as more likely silence than this (0.0-1.0; 0 = off)
+        #[arg(long, value_name = "X", requires = "model")]
+        no_speech_threshold: Option<f32>,
+        /// Beam width for this model (0 = greedy, 2-8 = beam search)
+        #[arg(long, value_name = "N", requires = "model")]
+        beam_size: Option<u32>,
+        /// Temperature fallback for this model
+        #[arg(long, value_name = "BOOL", requires = "model")]
+        temperature_fallback: Option<bool>,
+        /// Remove every override for the model
+        #[arg(
+            long,
+            requires = "model",
+            conflicts_with_all = ["no_speech_threshold", "beam_size", "temperature_fallback"]
+        )]
+        clear: bool,
+    },
+
+    /// Show or change per-model checksum and runtime pins
+    #[command(
+        long_about = "\
+Pin a model to one exact file and a minimum runtime, for machines where \
+only approved models may run. A pinned model whose file has a different \
+SHA-256, or that would run on an older whisper-rs-sys (the crate that \
+bundles whisper.cpp; see 'sagascript runtime'), is refused when it is \
+loaded, by the app and the CLI alike.
+
+Every model is already checked against its built-in checksum; a SHA-256 \
+pin additionally rejects any later revision of the model until the pin \
+is changed. Without flags, prints the pin for MODEL (or every pin when \
+MODEL is omitted).",
+        after_long_help = "\
+EXAMPLES:
+  sagascript config model-pin kb-whisper-base --sha256 <64 hex digits>
+  sagascript config model-pin large-v3-turbo --min-runtime 0.15.0
+  sagascript config model-pin
+  sagascript config model-pin kb-whisper-base --clear"
+    )]
+    ModelPin {
+        /// Model ID [see: sagascript list-models]
+        model: Option<String>,
+        /// SHA-256 the model's file must have
+        #[arg(long, value_name = "HEX", requires = "model")]
+        sha256: Option<String>,
+        /// Oldest whisper-rs-sys version the model may run on
+        #[arg(long, value_name = "VERSION", requires = "model")]
+        min_runtime: Option<String>,
+        /// Remove the model's pin
+        #[arg(long, requires = "model", conflicts_with_all = ["sha256", "min_runtime"])]
+        clear: bool,
+    },
+    /// Show or change which apps get terminal or email paste formatting
+    #[command(
+        long_about = "\
+Set how auto-paste formats text for one app. APP is a macOS bundle ID \
+(com.apple.Terminal), a Windows executable (WindowsTerminal.exe), a Linux \
+WM_CLASS (konsole) or the app's display name; matching ignores case.
+
+  terminal  one line, trailing period removed
+  email     paragraph breaks kept, extra blank lines collapsed
+  other     pasted as transcribed
+
+Rules take precedence over the built-in list of terminals and mail \
+clients; map a built-in app to 'other' to turn its formatting off. \
+With only APP, prints the category it gets. Without arguments, prints \
+your rules followed by the built-in list. Formatting only applies while paste_formatting is true.",
+        after_long_help = "\
+EXAMPLES:
+  sagascript config paste-rule co.zeit.hyper terminal
+  sagascript config paste-rule com.apple.Terminal other
+  sagascript config paste-rule
+  sagascript config paste-rule co.zeit.hyper --clear"
+    )]
+    PasteRule {
+        /// Bundle ID, executable, WM_CLASS or app name
+        app: Option<String>,
+        /// terminal, email or other
+        #[arg(requires = "app", conflicts_with = "clear")]
+        category: Option<AppCategory>,
+        /// Remove the rule for the app
+        #[arg(long, requires = "app")]
+        clear: bool,
+    },
+    /// Show or change dictation profiles (language, model, hotkey, overlay color, sound)
+    #[command(
+        long_about = "\
+Set up a dictation profile: a named language and model with its own \
+hotkey, so e.g. English and Swedish dictation are one keypress apart. \
+The hotkey behaves like the main one under hotkey_mode. While a profile \
+records, the overlay shows its name in its color and its sound cue \
+plays (low, mid or high; none for silence).
+
+With --schedule the running app makes the profile's language and model \
+the global ones while the schedule is due: days, a time range or both \
+(mon-fri 09:00-17:00, sat,sun, 22:00-06:00), several separated by ';'. \
+A profile scheduled 'otherwise' takes over when no other one is due. \
+Changing the language or model by hand lasts until the next switch.
+
+--output-sinks gives the profile's dictations their own output sinks, in \
+the output_sinks syntax (e.g. a notes profile that appends to a file \
+instead of pasting); default follows the global ones.
+
+Setting any flag creates the profile if it doesn't exist yet, starting \
+from the current language. --model auto follows the language's \
+recommended model; --hotkey \"\" leaves the profile CLI-only \
+(sagascript record --profile NAME). With only NAME, prints the profile. \
+Without arguments, lists every profile.",
+        after_long_help = "\
+EXAMPLES:
+  sagascript config profile Svenska --language sv --hotkey Alt+Shift+S
+  sagascript config profile Svenska --overlay-color '#ffcd00' --sound high
+  sagascript config profile Meetings --language en --model small.en --hotkey \"\"
+  sagascript config profile Work --language en --schedule 'mon-fri 09:00-17:00'
+  sagascript config profile Svenska --schedule otherwise
+  sagascript config profile Notes --output-sinks 'daily-note:~/Obsidian/Vault'
+  sagascript config profile
+  sagascript config profile Svenska --remove"
+    )]
+    Profile {
+        /// Profile name
+        name: Option<String>,
+        /// Language to dictate in (en, sv, no, auto)
+        #[arg(long, value_name = "LANG", requires = "name")]
+        language: Option<String>,
+        /// Model ID, or auto for the language's recommended model
+        #[arg(long, value_name = "MODEL", requires = "name")]
+        model: Option<String>,
+        /// Hotkey that starts this profile; empty for none
+        #[arg(long, value_name = "HOTKEY", requires = "name")]
+        hotkey: Option<String>,
+        /// Overlay color while recording, as #rrggbb; empty for the default
+        #[arg(long, value_name = "COLOR", requires = "name")]
+        overlay_color: Option<String>,
+        /// Sound cue when recording starts: none, low, mid or high
+        #[arg(long, value_name = "CUE", requires = "name")]
+        sound: Option<String>,
+        /// When the profile becomes the default, e.g. 'mon-fri 09:00-17:00'
+        /// or 'otherwise'; empty for never
+        #[arg(long, value_name = "WHEN", requires = "name")]
+        schedule: Option<String>,
+        /// Output sinks for the profile's dictations, as in output_sinks;
+        /// default for the global ones
+        #[arg(long, value_name = "SINKS", requires = "name")]
+        output_sinks: Option<String>,
+        /// Delete the profile
+        #[arg(
+            long,
+            requires = "name",
+            conflicts_with_all = [
+                "language",
+                "model",
+                "hotkey",
+                "overlay_color",
+                "sound",
+                "schedule",
+                "output_sinks",
+            ]
+        )]
+        remove: bool,
+    },
+    /// Store the password MQTT output sinks log in with
+    #[command(
+        long_about = "\
+Store the broker password for MQTT output sinks whose URL names a user \
+(mqtt://USER@HOST/TOPIC). It is read from the first line of stdin and kept \
+in the credential store, never in the settings file.",
+        after_long_help = "\
+EXAMPLES:
+  sagascript config set output_sinks 'mqtt://sensor@homeassistant.local/sagascript'
+  printf '%s' \"$MQTT_PASSWORD\" | sagascript config mqtt-password
+  sagascript config mqtt-password --clear"
+    )]
+    MqttPassword {
+        /// Remove the stored password
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Store the API key a remote transcription backend uses
+    #[command(
+        long_about = "\
+Store the API key used when transcription_backend is openai, or with \
+--backend azure the Azure Speech resource key. It is read from the first \
+line of stdin, so it stays out of shell history and process lists, and kept \
+in the credential store (the macOS Keychain or the Secret Service where \
+available), never in the settings file. Without a stored key, the \
+OPENAI_API_KEY or AZURE_SPEECH_KEY environment variable is used.",
+        after_long_help = "\
+EXAMPLES:
+  printf '%s' \"$OPENAI_API_KEY\" | sagascript config set-api-key
+  sagascript config set transcription_backend openai
+  printf '%s' \"$KEY\" | sagascript config set-api-key --backend azure
+  sagascript config set-api-key --clear"
+    )]
+    SetApiKey {
+        /// Backend the key is for: openai or azure
+        #[arg(long, value_name = "BACKEND", default_value = "openai")]
+        backend: String,
+        /// Remove the stored key
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Store the API key a post-processing endpoint needs
+    #[command(
+        long_about = "\
+Store the API key sent as a bearer token when post_process_command is an \
+OpenAI-compatible endpoint. It is read from the first line of stdin and \
+kept in the credential store, never in the settings file. Local servers \
+(Ollama, llama.cpp) usually need none.",
+        after_long_help = "\
+EXAMPLES:
+  sagascript config set post_process_command=https://api.openai.com/v1/chat/completions post_process_model=gpt-4o-mini
+  printf '%s' \"$OPENAI_API_KEY\" | sagascript config post-process-key
+  sagascript config post-process-key --clear"
+    )]
+    PostProcessKey {
+        /// Remove the stored key
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Show or change text replacement rules
+    #[command(
+        long_about = "\
+Fix words Whisper keeps getting wrong. Each rule replaces text in every \
+finished transcript, dictated or from a file, before it is pasted, saved \
+or routed. Rules run in the order listed.
+
+A literal rule matches whole words and ignores case. With --regex, FIND \
+is a regular expression and REPLACE may use its groups ($1, ${name}).",
+        after_long_help = "\
+EXAMPLES:
+  sagascript config rules add \"saga script\" Sagascript
+  sagascript config rules add --regex '(\\d+) procent' '$1 %'
+  sagascript config rules list
+  sagascript config rules remove 2"
+    )]
+    Rules {
+        #[command(subcommand)]
+        action: RulesAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RulesAction {
+    /// Add a rule after the existing ones
+    Add {
+        /// Text to find, or a pattern with --regex
+        find: String,
+        /// Text to put in its place
+        replace: String,
+        /// Treat FIND as a regular expression
+        #[arg(long)]
+        regex: bool,
+    },
+    /// List the rules in the order they run
+    List {
+        #[command(flatten)]
+        table: TableArgs,
+    },
+    /// Remove a rule by its number in `config rules list`
+    Remove {
+        /// Rule number, starting at 1
+        number: usize,
+    },
 }
 
 const VALID_KEYS: &[&str] = &[
@@ -113,114 +627,296 @@ const VALID_KEYS: &[&str] = &[
     "beam_size",
     "temperature_fallback",
     "vad_enabled",
+    "adaptive_model",
+    "adaptive_short_clip_secs",
+    "adaptive_long_clip_secs",
+    "accumulate_mode",
+    "flush_hotkey",
+    "flush_phrase",
+    "output_sinks",
+    "model_update_check",
+    "monitor_input",
+    "monitor_volume",
+    "ffmpeg_fallback",
+    "while_busy",
+    "keywords",
+    "localize_numbers",
+    "voice_commands",
+    "paste_formatting",
+    "tray_visible",
+    "tray_status_text",
+    "tray_menu",
+    "telemetry",
+    "telemetry_endpoint",
+    "overlay_high_contrast",
+    "overlay_large_text",
+    "overlay_reduced_motion",
+    "announce_state",
+    "settings_hotkey",
+    "settings_hotkey_tab",
+    "toggle_hotkey",
+    "max_recording_minutes",
+    "silence_stop_secs",
+    "paste_confirm_chars",
+    "capture_source",
+    "standby_stream",
+    "split_channels",
+    "post_process_command",
+    "post_process_prompt",
+    "post_process_model",
+    "transcription_backend",
+    "openai_model",
+    "azure_region",
+    "event_stream_port",
+    "use_gpu",
+    "punctuate",
+    "custom_model",
+    "draft_pass",
+    "replace_draft",
+    "dedupe_dictations",
+    "network_proxy",
+    "model_mirror",
+    "dry_run",
+    "preload_model",
 ];
 
+impl ConfigAction {
+    /// The `--porcelain` version requested, for actions that support it.
+    pub fn porcelain(&self) -> Option<porcelain::Version> {
+        match self {
+            ConfigAction::List { porcelain, .. }
+            | ConfigAction::Get { porcelain, .. }
+            | ConfigAction::Set { porcelain, .. } => *porcelain,
+            _ => None,
+        }
+    }
+}
+
 pub fn run(args: ConfigArgs) -> Result<(), DictationError> {
     match args.action {
-        ConfigAction::List => cmd_list(),
-        ConfigAction::Get { key } => cmd_get(&key),
-        ConfigAction::Set { key, value } => cmd_set(&key, &value),
+        ConfigAction::List { porcelain, table } => cmd_list(porcelain, &table),
+        ConfigAction::Get { key, porcelain } => cmd_get(&key, porcelain),
+        ConfigAction::Set { pairs, porcelain } => cmd_set(&pairs, porcelain),
         ConfigAction::Reset { key } => cmd_reset(key.as_deref()),
+        ConfigAction::Unset { key } => cmd_reset(Some(&key)),
         ConfigAction::Path => cmd_path(),
+        ConfigAction::Validate => cmd_validate(),
+        ConfigAction::PromptHistory { profile, clear } => {
+            cmd_prompt_history(profile.as_deref(), clear)
+        }
+        ConfigAction::MqttPassword { clear } => cmd_mqtt_password(clear),
+        ConfigAction::PostProcessKey { clear } => cmd_post_process_key(clear),
+        ConfigAction::SetApiKey { backend, clear } => cmd_set_api_key(&backend, clear),
+        ConfigAction::Rules { action } => cmd_rules(action),
+        ConfigAction::ModelOverride {
+            model,
+            no_speech_threshold,
+            beam_size,
+            temperature_fallback,
+            clear,
+        } => cmd_model_override(
+            model.as_deref(),
+            ModelOverrides {
+                no_speech_threshold,
+                beam_size,
+                temperature_fallback,
+            },
+            clear,
+        ),
+        ConfigAction::ModelPin {
+            model,
+            sha256,
+            min_runtime,
+            clear,
+        } => cmd_model_pin(
+            model.as_deref(),
+            ModelPin {
+                sha256,
+                min_runtime,
+            },
+            clear,
+        ),
+        ConfigAction::PasteRule {
+            app,
+            category,
+            clear,
+        } => cmd_paste_rule(app.as_deref(), category, clear),
+        ConfigAction::Profile {
+            name,
+            language,
+            model,
+            hotkey,
+            overlay_color,
+            sound,
+            schedule,
+            output_sinks,
+            remove,
+        } => cmd_profile(
+            name.as_deref(),
+            ProfileChanges {
+                language,
+                model,
+                hotkey,
+                overlay_color,
+                sound,
+                schedule,
+                output_sinks,
+            },
+            remove,
+        ),
     }
 }
 
-fn cmd_list() -> Result<(), DictationError> {
+/// Columns of the `config list` table.
+const LIST_COLUMNS: [Column; 3] = [
+    Column::left("key", "KEY"),
+    Column::left("current", "CURRENT"),
+    Column::left("default", "DEFAULT"),
+];
+
+fn cmd_list(
+    porcelain: Option<porcelain::Version>,
+    table: &TableArgs,
+) -> Result<(), DictationError> {
     let current = settings::store::load();
     let defaults = Settings::default();
+    if let Err(issue) = settings::store::validate(&settings::store::settings_path()) {
+        eprintln!(
+            "Warning: the settings file doesn't load ({issue}), showing defaults. \
+             See 'sagascript config validate'."
+        );
+    }
 
-    println!("{:<20} {:<24} DEFAULT", "KEY", "CURRENT");
-    println!("{:<20} {:<24} -------", "---", "-------");
-    println!(
-        "{:<20} {:<24} {}",
-        "language",
-        format_language(current.language),
-        format_language(defaults.language)
-    );
-    println!(
-        "{:<20} {:<24} {}",
-        "whisper_model",
-        format_model(current.whisper_model),
-        format_model(defaults.whisper_model)
-    );
-    println!(
-        "{:<20} {:<24} {}",
-        "hotkey_mode",
-        format_hotkey_mode(current.hotkey_mode),
-        format_hotkey_mode(defaults.hotkey_mode)
-    );
-    println!(
-        "{:<20} {:<24} {}",
-        "show_overlay",
-        current.show_overlay,
-        defaults.show_overlay
-    );
-    println!(
-        "{:<20} {:<24} {}",
-        "auto_paste",
-        current.auto_paste,
-        defaults.auto_paste
-    );
-    println!(
-        "{:<20} {:<24} {}",
-        "auto_select_model",
-        current.auto_select_model,
-        defaults.auto_select_model
-    );
-    println!(
-        "{:<20} {:<24} {}",
-        "hotkey", current.hotkey, defaults.hotkey
-    );
-    println!(
-        "{:<20} {:<24} {}",
-        "initial_prompt", current.initial_prompt, defaults.initial_prompt
-    );
-    println!(
-        "{:<20} {:<24} {}",
-        "beam_size", current.beam_size, defaults.beam_size
-    );
-    println!(
-        "{:<20} {:<24} {}",
-        "temperature_fallback", current.temperature_fallback, defaults.temperature_fallback
-    );
-    println!(
-        "{:<20} {:<24} {}",
-        "vad_enabled", current.vad_enabled, defaults.vad_enabled
-    );
-    Ok(())
+    if let Some(version) = porcelain {
+        let mut out = porcelain::Output::new(version);
+        for key in VALID_KEYS {
+            setting_record(&mut out, key, &current, &defaults);
+        }
+        out.print();
+        return Ok(());
+    }
+
+    let mut rows = Table::new(&LIST_COLUMNS);
+    for key in VALID_KEYS {
+        rows.row(vec![
+            key.to_string(),
+            get_setting_value(&current, key),
+            get_setting_value(&defaults, key),
+        ]);
+    }
+    rows.print(table)
 }
 
-fn cmd_get(key: &str) -> Result<(), DictationError> {
+fn cmd_get(key: &str, porcelain: Option<porcelain::Version>) -> Result<(), DictationError> {
     validate_key(key)?;
     let settings = settings::store::load();
+    if let Some(version) = porcelain {
+        let mut out = porcelain::Output::new(version);
+        setting_record(&mut out, key, &settings, &Settings::default());
+        out.print();
+        return Ok(());
+    }
     let value = get_setting_value(&settings, key);
     println!("{value}");
     Ok(())
 }
 
-fn cmd_set(key: &str, value: &str) -> Result<(), DictationError> {
-    validate_key(key)?;
-    // Parse before acquiring the settings lock so invalid input never writes.
+/// The porcelain `setting` record: key, current value, default value.
+fn setting_record(out: &mut porcelain::Output, key: &str, current: &Settings, defaults: &Settings) {
+    out.record(
+        "setting",
+        &[
+            key,
+            &get_setting_value(current, key),
+            &get_setting_value(defaults, key),
+        ],
+    );
+}
+
+/// `config set` arguments as (key, value) pairs: a single `KEY VALUE`, or
+/// any number of `KEY=VALUE`.
+fn parse_assignments(args: &[String]) -> Result<Vec<(&str, &str)>, DictationError> {
+    if let [key, value] = args {
+        if !key.contains('=') {
+            return Ok(vec![(key.as_str(), value.as_str())]);
+        }
+    }
+    args.iter()
+        .map(|arg| {
+            arg.split_once('=')
+                .map(|(key, value)| (key.trim(), value))
+                .ok_or_else(|| {
+                    DictationError::SettingsError(format!(
+                        "Expected KEY=VALUE, got '{arg}' (or give a single KEY VALUE)"
+                    ))
+                })
+        })
+        .collect()
+}
+
+fn cmd_set(args: &[String], porcelain: Option<porcelain::Version>) -> Result<(), DictationError> {
+    let assignments = parse_assignments(args)?;
+    // Parse before acquiring the settings lock so invalid input never writes,
+    // and every pair is saved in the same write.
     let mut validation_target = Settings::default();
-    apply_setting_value(&mut validation_target, key, value)?;
+    for (key, value) in &assignments {
+        validate_key(key)?;
+        apply_setting_value(&mut validation_target, key, value)?;
+    }
     let settings = settings::store::update(|settings| {
-        apply_setting_value(settings, key, value)
-            .expect("setting value was validated before acquiring the lock");
+        for (key, value) in &assignments {
+            apply_setting_value(settings, key, value)
+                .expect("setting value was validated before acquiring the lock");
+        }
     })
     .map_err(DictationError::SettingsError)?;
 
-    eprintln!("Set {key} = {}", get_setting_value(&settings, key));
-    if let Some(warning) = setting_warning(key, &settings) {
-        eprintln!("Warning: {warning}");
+    let mut out = porcelain.map(porcelain::Output::new);
+    for (key, _) in &assignments {
+        eprintln!("Set {key} = {}", get_setting_value(&settings, key));
+        if let Some(warning) = setting_warning(key, &settings) {
+            eprintln!("Warning: {warning}");
+        }
+        if let Some(out) = &mut out {
+            setting_record(out, key, &settings, &Settings::default());
+        }
+    }
+    if let Some(out) = out {
+        out.print();
     }
     Ok(())
 }
 
 fn setting_warning(key: &str, settings: &Settings) -> Option<&'static str> {
-    (key == "auto_paste" && settings.auto_paste).then_some(
-        "auto-paste requires Accessibility approval for the installed Sagascript app; \
-         until it is granted, the GUI will keep or reset auto-paste to false",
-    )
+    match key {
+        "auto_paste" => wants_paste(&settings.output_sinks).then_some(
+            "auto-paste requires Accessibility approval for the installed Sagascript app; \
+             until it is granted, the GUI will keep or reset auto-paste to false",
+        ),
+        "output_sinks" => wants_paste(&settings.output_sinks).then_some(
+            "the paste sink requires Accessibility approval for the installed Sagascript app; \
+             until it is granted, the GUI removes it",
+        ),
+        "post_process_command" => llm::is_endpoint(&settings.post_process_command).then_some(
+            "every dictation is sent to this endpoint; store its API key, if it needs one, \
+             with 'sagascript config post-process-key'",
+        ),
+        "transcription_backend" => match settings.transcription_backend {
+            BackendKind::Local => None,
+            BackendKind::OpenAi => {
+                Some("dictations are sent to OpenAI to be transcribed; files stay local")
+            }
+            BackendKind::Azure => Some(
+                "dictations are sent to your Azure Speech resource to be transcribed; \
+                 files stay local. Set azure_region if you haven't",
+            ),
+        },
+        "event_stream_port" => (settings.event_stream_port != 0).then_some(
+            "programs holding the token in event-stream-token can read the stream, \
+             transcripts included; restart Sagascript to apply",
+        ),
+        _ => None,
+    }
 }
 
 fn apply_setting_value(
@@ -242,7 +938,7 @@ fn apply_setting_value(
             settings.show_overlay = parse_bool(value, "show_overlay")?;
         }
         "auto_paste" => {
-            settings.auto_paste = parse_bool(value, "auto_paste")?;
+            settings.set_auto_paste(parse_bool(value, "auto_paste")?);
         }
         "auto_select_model" => {
             settings.auto_select_model = parse_bool(value, "auto_select_model")?;
@@ -251,7 +947,10 @@ fn apply_setting_value(
             validate_hotkey(value)?;
             settings.hotkey = value.to_string();
         }
-        "initial_prompt" => settings.initial_prompt = value.to_string(),
+        "initial_prompt" => {
+            settings.initial_prompt = value.to_string();
+            settings.remember_initial_prompt(None, value);
+        }
         "beam_size" => {
             settings.beam_size = value.parse::<u32>().map_err(|_| {
                 DictationError::SettingsError(format!(
@@ -265,6 +964,217 @@ fn apply_setting_value(
         "vad_enabled" => {
             settings.vad_enabled = parse_bool(value, "vad_enabled")?;
         }
+        "adaptive_model" => {
+            settings.adaptive_model = parse_bool(value, "adaptive_model")?;
+        }
+        "adaptive_short_clip_secs" => {
+            settings.adaptive_short_clip_secs = value.parse::<u32>().map_err(|_| {
+                DictationError::SettingsError(format!(
+                    "adaptive_short_clip_secs must be a non-negative integer, got '{value}'"
+                ))
+            })?;
+        }
+        "adaptive_long_clip_secs" => {
+            settings.adaptive_long_clip_secs = value.parse::<u32>().map_err(|_| {
+                DictationError::SettingsError(format!(
+                    "adaptive_long_clip_secs must be a non-negative integer, got '{value}'"
+                ))
+            })?;
+        }
+        "accumulate_mode" => {
+            settings.accumulate_mode = parse_bool(value, "accumulate_mode")?;
+        }
+        "flush_hotkey" => {
+            validate_hotkey(value)?;
+            settings.flush_hotkey = value.to_string();
+        }
+        "flush_phrase" => settings.flush_phrase = value.to_string(),
+        "output_sinks" => {
+            settings.output_sinks = parse_sinks(value)?;
+        }
+        "model_update_check" => {
+            settings.model_update_check = parse_bool(value, "model_update_check")?;
+        }
+        "monitor_input" => {
+            settings.monitor_input = parse_bool(value, "monitor_input")?;
+        }
+        "monitor_volume" => {
+            settings.monitor_volume = value
+                .parse::<f32>()
+                .ok()
+                .filter(|v| (0.0..=MAX_MONITOR_VOLUME).contains(v))
+                .ok_or_else(|| {
+                    DictationError::SettingsError(format!(
+                        "monitor_volume must be a number from 0.0 to {MAX_MONITOR_VOLUME}, got '{value}'"
+                    ))
+                })?;
+        }
+        "ffmpeg_fallback" => {
+            settings.ffmpeg_fallback = parse_bool(value, "ffmpeg_fallback")?;
+        }
+        "while_busy" => {
+            settings.while_busy = parse_enum_value::<WhileBusy>(value, "while_busy")?;
+        }
+        "capture_source" => {
+            settings.capture_source = parse_enum_value::<CaptureSource>(value, "capture_source")?;
+        }
+        "standby_stream" => {
+            settings.standby_stream = parse_bool(value, "standby_stream")?;
+        }
+        "split_channels" => {
+            settings.split_channels = parse_bool(value, "split_channels")?;
+        }
+        "keywords" => {
+            settings.keywords = keywords::parse_keywords(value)?;
+        }
+        "localize_numbers" => {
+            settings.localize_numbers = parse_bool(value, "localize_numbers")?;
+        }
+        "voice_commands" => {
+            settings.voice_commands = parse_bool(value, "voice_commands")?;
+        }
+        "paste_formatting" => {
+            settings.paste_formatting = parse_bool(value, "paste_formatting")?;
+        }
+        "tray_visible" => {
+            settings.tray_visible = parse_bool(value, "tray_visible")?;
+        }
+        "tray_status_text" => {
+            settings.tray_status_text = parse_bool(value, "tray_status_text")?;
+        }
+        "tray_menu" => {
+            settings.tray_menu = parse_tray_menu(value).map_err(DictationError::SettingsError)?;
+        }
+        "telemetry" => {
+            settings.telemetry = parse_bool(value, "telemetry")?;
+        }
+        "telemetry_endpoint" => settings.telemetry_endpoint = value.to_string(),
+        "overlay_high_contrast" => {
+            settings.overlay_high_contrast = parse_bool(value, "overlay_high_contrast")?;
+        }
+        "overlay_large_text" => {
+            settings.overlay_large_text = parse_bool(value, "overlay_large_text")?;
+        }
+        "overlay_reduced_motion" => {
+            settings.overlay_reduced_motion = parse_bool(value, "overlay_reduced_motion")?;
+        }
+        "announce_state" => {
+            settings.announce_state = parse_bool(value, "announce_state")?;
+        }
+        "settings_hotkey" => {
+            if !value.is_empty() {
+                validate_hotkey(value)?;
+            }
+            settings.settings_hotkey = value.to_string();
+        }
+        "settings_hotkey_tab" => {
+            settings.settings_hotkey_tab =
+                parse_enum_value::<SettingsTab>(value, "settings_hotkey_tab")?;
+        }
+        "toggle_hotkey" => {
+            if !value.is_empty() {
+                validate_hotkey(value)?;
+                if value.eq_ignore_ascii_case(&settings.hotkey) {
+                    return Err(DictationError::SettingsError(
+                        "toggle_hotkey must differ from hotkey".into(),
+                    ));
+                }
+            }
+            settings.toggle_hotkey = value.to_string();
+        }
+        "max_recording_minutes" => {
+            settings.max_recording_minutes = value
+                .parse::<u32>()
+                .ok()
+                .filter(|m| (1..=MAX_RECORDING_MINUTES).contains(m))
+                .ok_or_else(|| {
+                    DictationError::SettingsError(format!(
+                        "max_recording_minutes must be a whole number from 1 to \
+                         {MAX_RECORDING_MINUTES}, got '{value}'"
+                    ))
+                })?;
+        }
+        "silence_stop_secs" => {
+            settings.silence_stop_secs = value
+                .parse::<u32>()
+                .ok()
+                .filter(|s| *s <= MAX_SILENCE_STOP_SECS)
+                .ok_or_else(|| {
+                    DictationError::SettingsError(format!(
+                        "silence_stop_secs must be a whole number from 0 (off) to \
+                         {MAX_SILENCE_STOP_SECS}, got '{value}'"
+                    ))
+                })?;
+        }
+        "paste_confirm_chars" => {
+            settings.paste_confirm_chars = value.parse::<u32>().map_err(|_| {
+                DictationError::SettingsError(format!(
+                    "paste_confirm_chars must be a whole number of characters (0: never ask), \
+                     got '{value}'"
+                ))
+            })?;
+        }
+        "post_process_command" => settings.post_process_command = value.trim().to_string(),
+        "post_process_prompt" => settings.post_process_prompt = value.to_string(),
+        "post_process_model" => settings.post_process_model = value.trim().to_string(),
+        "transcription_backend" => {
+            settings.transcription_backend =
+                parse_enum_value::<BackendKind>(value, "transcription_backend")?;
+        }
+        "openai_model" => settings.openai_model = value.trim().to_string(),
+        "azure_region" => {
+            let region = value.trim().to_ascii_lowercase();
+            if !region.is_empty() {
+                backend::validate_azure_region(&region).map_err(DictationError::SettingsError)?;
+            }
+            settings.azure_region = region;
+        }
+        "event_stream_port" => {
+            settings.event_stream_port = value.parse::<u16>().map_err(|_| {
+                DictationError::SettingsError(
+                    "event_stream_port must be a port number, 1-65535 (0: off)".to_string(),
+                )
+            })?;
+        }
+        "use_gpu" => {
+            settings.use_gpu = parse_bool(value, "use_gpu")?;
+        }
+        "punctuate" => {
+            settings.punctuate = parse_bool(value, "punctuate")?;
+        }
+        "custom_model" => {
+            let name = value.trim();
+            if !name.is_empty() && !settings.custom_models.contains_key(name) {
+                return Err(DictationError::SettingsError(format!(
+                    "No custom model named '{name}'. Register it with 'sagascript models add'"
+                )));
+            }
+            settings.custom_model = name.to_string();
+        }
+        "draft_pass" => {
+            settings.draft_pass = parse_bool(value, "draft_pass")?;
+        }
+        "replace_draft" => {
+            settings.replace_draft = parse_bool(value, "replace_draft")?;
+        }
+        "dedupe_dictations" => {
+            settings.dedupe_dictations = parse_bool(value, "dedupe_dictations")?;
+        }
+        "network_proxy" => {
+            sagascript_core::net::check_proxy(value).map_err(DictationError::SettingsError)?;
+            settings.network_proxy = value.to_string();
+        }
+        "model_mirror" => {
+            sagascript_core::download::check_mirror(value)
+                .map_err(DictationError::SettingsError)?;
+            settings.model_mirror = value.trim_end_matches('/').to_string();
+        }
+        "dry_run" => {
+            settings.dry_run = parse_bool(value, "dry_run")?;
+        }
+        "preload_model" => {
+            settings.preload_model = parse_bool(value, "preload_model")?;
+        }
         _ => unreachable!(), // validate_key already checked
     }
     Ok(())
@@ -279,13 +1189,72 @@ fn cmd_reset(key: Option<&str>) -> Result<(), DictationError> {
             "whisper_model" => settings.whisper_model = defaults.whisper_model,
             "hotkey_mode" => settings.hotkey_mode = defaults.hotkey_mode,
             "show_overlay" => settings.show_overlay = defaults.show_overlay,
-            "auto_paste" => settings.auto_paste = defaults.auto_paste,
+            "auto_paste" => settings.set_auto_paste(wants_paste(&defaults.output_sinks)),
             "auto_select_model" => settings.auto_select_model = defaults.auto_select_model,
             "hotkey" => settings.hotkey = defaults.hotkey,
             "initial_prompt" => settings.initial_prompt = defaults.initial_prompt,
             "beam_size" => settings.beam_size = defaults.beam_size,
             "temperature_fallback" => settings.temperature_fallback = defaults.temperature_fallback,
             "vad_enabled" => settings.vad_enabled = defaults.vad_enabled,
+            "adaptive_model" => settings.adaptive_model = defaults.adaptive_model,
+            "adaptive_short_clip_secs" => settings.adaptive_short_clip_secs = defaults.adaptive_short_clip_secs,
+            "adaptive_long_clip_secs" => settings.adaptive_long_clip_secs = defaults.adaptive_long_clip_secs,
+            "accumulate_mode" => settings.accumulate_mode = defaults.accumulate_mode,
+            "flush_hotkey" => settings.flush_hotkey = defaults.flush_hotkey,
+            "flush_phrase" => settings.flush_phrase = defaults.flush_phrase,
+            "output_sinks" => settings.output_sinks = defaults.output_sinks,
+            "model_update_check" => settings.model_update_check = defaults.model_update_check,
+            "monitor_input" => settings.monitor_input = defaults.monitor_input,
+            "monitor_volume" => settings.monitor_volume = defaults.monitor_volume,
+            "ffmpeg_fallback" => settings.ffmpeg_fallback = defaults.ffmpeg_fallback,
+            "while_busy" => settings.while_busy = defaults.while_busy,
+            "keywords" => settings.keywords = defaults.keywords,
+            "localize_numbers" => settings.localize_numbers = defaults.localize_numbers,
+            "voice_commands" => settings.voice_commands = defaults.voice_commands,
+            "paste_formatting" => settings.paste_formatting = defaults.paste_formatting,
+            "tray_visible" => settings.tray_visible = defaults.tray_visible,
+            "tray_status_text" => settings.tray_status_text = defaults.tray_status_text,
+            "tray_menu" => settings.tray_menu = defaults.tray_menu,
+            "telemetry" => settings.telemetry = defaults.telemetry,
+            "telemetry_endpoint" => settings.telemetry_endpoint = defaults.telemetry_endpoint,
+            "overlay_high_contrast" => {
+                settings.overlay_high_contrast = defaults.overlay_high_contrast
+            }
+            "overlay_large_text" => settings.overlay_large_text = defaults.overlay_large_text,
+            "overlay_reduced_motion" => {
+                settings.overlay_reduced_motion = defaults.overlay_reduced_motion
+            }
+            "announce_state" => settings.announce_state = defaults.announce_state,
+            "settings_hotkey" => settings.settings_hotkey = defaults.settings_hotkey,
+            "settings_hotkey_tab" => settings.settings_hotkey_tab = defaults.settings_hotkey_tab,
+            "toggle_hotkey" => settings.toggle_hotkey = defaults.toggle_hotkey,
+            "max_recording_minutes" => {
+                settings.max_recording_minutes = defaults.max_recording_minutes
+            }
+            "silence_stop_secs" => settings.silence_stop_secs = defaults.silence_stop_secs,
+            "paste_confirm_chars" => settings.paste_confirm_chars = defaults.paste_confirm_chars,
+            "capture_source" => settings.capture_source = defaults.capture_source,
+            "standby_stream" => settings.standby_stream = defaults.standby_stream,
+            "split_channels" => settings.split_channels = defaults.split_channels,
+            "post_process_command" => settings.post_process_command = defaults.post_process_command,
+            "post_process_prompt" => settings.post_process_prompt = defaults.post_process_prompt,
+            "post_process_model" => settings.post_process_model = defaults.post_process_model,
+            "transcription_backend" => {
+                settings.transcription_backend = defaults.transcription_backend
+            }
+            "openai_model" => settings.openai_model = defaults.openai_model,
+            "azure_region" => settings.azure_region = defaults.azure_region,
+            "event_stream_port" => settings.event_stream_port = defaults.event_stream_port,
+            "use_gpu" => settings.use_gpu = defaults.use_gpu,
+            "punctuate" => settings.punctuate = defaults.punctuate,
+            "custom_model" => settings.custom_model = defaults.custom_model,
+            "draft_pass" => settings.draft_pass = defaults.draft_pass,
+            "replace_draft" => settings.replace_draft = defaults.replace_draft,
+            "dedupe_dictations" => settings.dedupe_dictations = defaults.dedupe_dictations,
+            "network_proxy" => settings.network_proxy = defaults.network_proxy,
+            "model_mirror" => settings.model_mirror = defaults.model_mirror,
+            "dry_run" => settings.dry_run = defaults.dry_run,
+            "preload_model" => settings.preload_model = defaults.preload_model,
             _ => unreachable!(),
         })
         .map_err(DictationError::SettingsError)?;
@@ -303,6 +1272,545 @@ fn cmd_path() -> Result<(), DictationError> {
     Ok(())
 }
 
+fn cmd_validate() -> Result<(), DictationError> {
+    let path = settings::store::settings_path();
+    let unknown = settings::store::validate(&path).map_err(|issue| {
+        DictationError::SettingsError(format!(
+            "{}: {issue}. The defaults are used until this is fixed.",
+            path.display()
+        ))
+    })?;
+    for key in &unknown {
+        eprintln!("Warning: '{key}' is not a setting and is ignored");
+    }
+    println!("{}: OK", path.display());
+    Ok(())
+}
+
+fn cmd_prompt_history(profile: Option<&str>, clear: bool) -> Result<(), DictationError> {
+    let stored = settings::store::load();
+    if let Some(name) = profile {
+        if stored.profile(name).is_none() {
+            return Err(DictationError::SettingsError(format!(
+                "No profile named '{name}'. Run 'sagascript config profile' to list them."
+            )));
+        }
+    }
+    if clear {
+        settings::store::update(|s| s.clear_prompt_history(profile))
+            .map_err(DictationError::SettingsError)?;
+        match profile {
+            Some(name) => eprintln!("Prompt history of '{name}' cleared"),
+            None => eprintln!("Prompt history cleared"),
+        }
+        return Ok(());
+    }
+    for prompt in stored.prompt_history_for(profile) {
+        println!("{prompt}");
+    }
+    Ok(())
+}
+
+fn cmd_mqtt_password(clear: bool) -> Result<(), DictationError> {
+    store_secret(mqtt::PASSWORD_SECRET, "MQTT password", "password", clear)
+}
+
+fn cmd_post_process_key(clear: bool) -> Result<(), DictationError> {
+    store_secret(llm::API_KEY_SECRET, "Post-processing API key", "key", clear)
+}
+
+fn cmd_set_api_key(kind: &str, clear: bool) -> Result<(), DictationError> {
+    let kind = parse_enum_value::<BackendKind>(kind, "--backend").map_err(|_| {
+        DictationError::SettingsError(format!("Unknown backend '{kind}'; use openai or azure"))
+    })?;
+    let Some(secret) = backend::api_key_secret(kind) else {
+        return Err(DictationError::SettingsError(
+            "Local transcription needs no API key".to_string(),
+        ));
+    };
+    let label = format!("{} key", kind.display_name());
+    store_secret(secret, &label, "API key", clear)
+}
+
+/// Store the first line of stdin as the secret `name`, or remove it with
+/// `clear`. `label` names it in messages, `noun` in the empty-input error.
+fn store_secret(name: &str, label: &str, noun: &str, clear: bool) -> Result<(), DictationError> {
+    let store = credentials::default_store()?;
+    if clear {
+        store.delete(name)?;
+        eprintln!("{label} removed");
+        return Ok(());
+    }
+    let mut secret = String::new();
+    std::io::stdin()
+        .read_line(&mut secret)
+        .map_err(|e| DictationError::CredentialError(format!("Cannot read stdin: {e}")))?;
+    let secret = secret.trim_end_matches(['\r', '\n']);
+    if secret.is_empty() {
+        return Err(DictationError::CredentialError(format!(
+            "No {noun} on stdin (use --clear to remove the stored one)"
+        )));
+    }
+    store.set(name, secret)?;
+    eprintln!("{label} stored ({})", store.backend());
+    Ok(())
+}
+
+fn cmd_model_override(
+    model: Option<&str>,
+    changes: ModelOverrides,
+    clear: bool,
+) -> Result<(), DictationError> {
+    let Some(model) = model else {
+        for (id, overrides) in settings::store::load().model_overrides {
+            println!("{id}: {}", format_overrides(&overrides));
+        }
+        return Ok(());
+    };
+    let model = parse_enum_value::<WhisperModel>(model, "model")?;
+    let id = format_model(model);
+
+    if clear {
+        settings::store::update(|s| {
+            s.model_overrides.remove(&id);
+        })
+        .map_err(DictationError::SettingsError)?;
+        eprintln!("Overrides for {id} removed");
+        return Ok(());
+    }
+
+    let stored = settings::store::load();
+    if changes.is_empty() {
+        let current = stored.model_overrides.get(&id).cloned().unwrap_or_default();
+        print_effective(&stored, model, &current);
+        return Ok(());
+    }
+
+    let mut merged = stored.model_overrides.get(&id).cloned().unwrap_or_default();
+    merge_overrides(&mut merged, changes);
+    merged.validate().map_err(DictationError::SettingsError)?;
+    let saved = settings::store::update(|s| {
+        s.model_overrides.insert(id.clone(), merged.clone());
+    })
+    .map_err(DictationError::SettingsError)?;
+    eprintln!("Overrides for {id} updated");
+    print_effective(&saved, model, &merged);
+    Ok(())
+}
+
+fn merge_overrides(into: &mut ModelOverrides, changes: ModelOverrides) {
+    if changes.no_speech_threshold.is_some() {
+        into.no_speech_threshold = changes.no_speech_threshold;
+    }
+    if changes.beam_size.is_some() {
+        into.beam_size = changes.beam_size;
+    }
+    if changes.temperature_fallback.is_some() {
+        into.temperature_fallback = changes.temperature_fallback;
+    }
+}
+
+fn print_effective(stored: &Settings, model: WhisperModel, overrides: &ModelOverrides) {
+    let mark = |set: bool| if set { "" } else { " (default)" };
+    println!(
+        "no_speech_threshold  {}{}",
+        overrides
+            .no_speech_threshold
+            .unwrap_or_else(|| model.no_speech_threshold()),
+        mark(overrides.no_speech_threshold.is_some())
+    );
+    println!(
+        "beam_size            {}{}",
+        overrides.beam_size.unwrap_or(stored.beam_size),
+        mark(overrides.beam_size.is_some())
+    );
+    println!(
+        "temperature_fallback {}{}",
+        overrides
+            .temperature_fallback
+            .unwrap_or(stored.temperature_fallback),
+        mark(overrides.temperature_fallback.is_some())
+    );
+}
+
+fn format_overrides(overrides: &ModelOverrides) -> String {
+    let mut parts = Vec::new();
+    if let Some(t) = overrides.no_speech_threshold {
+        parts.push(format!("no_speech_threshold={t}"));
+    }
+    if let Some(b) = overrides.beam_size {
+        parts.push(format!("beam_size={b}"));
+    }
+    if let Some(f) = overrides.temperature_fallback {
+        parts.push(format!("temperature_fallback={f}"));
+    }
+    parts.join(" ")
+}
+
+fn cmd_model_pin(
+    model: Option<&str>,
+    changes: ModelPin,
+    clear: bool,
+) -> Result<(), DictationError> {
+    let Some(model) = model else {
+        for (id, pin) in settings::store::load().model_pins {
+            println!("{id}: {}", format_pin(&pin));
+        }
+        return Ok(());
+    };
+    let model = parse_enum_value::<WhisperModel>(model, "model")?;
+    let id = format_model(model);
+
+    if clear {
+        settings::store::update(|s| {
+            s.model_pins.remove(&id);
+        })
+        .map_err(DictationError::SettingsError)?;
+        eprintln!("Pin for {id} removed");
+        return Ok(());
+    }
+
+    let stored = settings::store::load();
+    let mut pin = stored.model_pins.get(&id).cloned().unwrap_or_default();
+    if changes.is_empty() {
+        if pin.is_empty() {
+            eprintln!("{id} is not pinned");
+        } else {
+            println!("{}", format_pin(&pin));
+        }
+        return Ok(());
+    }
+
+    if let Some(sha256) = changes.sha256 {
+        pin.sha256 = Some(sha256.trim().to_ascii_lowercase());
+    }
+    if let Some(min_runtime) = changes.min_runtime {
+        pin.min_runtime = Some(min_runtime.trim().to_string());
+    }
+    pin.validate().map_err(DictationError::SettingsError)?;
+    settings::store::update(|s| {
+        s.model_pins.insert(id.clone(), pin.clone());
+    })
+    .map_err(DictationError::SettingsError)?;
+    eprintln!("Pin for {id} updated");
+    println!("{}", format_pin(&pin));
+    // Say now, not at the next dictation, when the pin already rules the
+    // model out.
+    if let Err(why) = pin.check(
+        model.download_integrity().sha256,
+        runtime::WHISPER_RS_SYS_VERSION,
+    ) {
+        eprintln!("Warning: {id} will not load: {why}");
+    }
+    Ok(())
+}
+
+fn format_pin(pin: &ModelPin) -> String {
+    let mut parts = Vec::new();
+    if let Some(sha) = &pin.sha256 {
+        parts.push(format!("sha256={sha}"));
+    }
+    if let Some(min) = &pin.min_runtime {
+        parts.push(format!("min_runtime={min}"));
+    }
+    parts.join(" ")
+}
+
+fn cmd_paste_rule(
+    app: Option<&str>,
+    category: Option<AppCategory>,
+    clear: bool,
+) -> Result<(), DictationError> {
+    let Some(app) = app.map(str::trim).filter(|a| !a.is_empty()) else {
+        for (app, category) in settings::store::load().paste_app_rules {
+            println!("{app:<32} {category}");
+        }
+        for (app, category) in paste_format::BUILT_IN_APPS {
+            println!("{app:<32} {category} (built-in)");
+        }
+        return Ok(());
+    };
+
+    if clear {
+        settings::store::update(|s| {
+            s.paste_app_rules
+                .retain(|key, _| !key.eq_ignore_ascii_case(app));
+        })
+        .map_err(DictationError::SettingsError)?;
+        eprintln!("Paste rule for {app} removed");
+        return Ok(());
+    }
+
+    let Some(category) = category else {
+        let stored = settings::store::load();
+        let found = paste_format::FrontmostApp {
+            id: app.to_string(),
+            name: app.to_string(),
+        };
+        println!(
+            "{}",
+            paste_format::category_for(&found, &stored.paste_app_rules)
+        );
+        return Ok(());
+    };
+
+    settings::store::update(|s| {
+        s.paste_app_rules
+            .retain(|key, _| !key.eq_ignore_ascii_case(app));
+        s.paste_app_rules.insert(app.to_string(), category);
+    })
+    .map_err(DictationError::SettingsError)?;
+    eprintln!("Paste rule for {app} set to {category}");
+    Ok(())
+}
+
+/// Columns of the `config rules list` table.
+const RULES_COLUMNS: [Column; 4] = [
+    Column::right("number", "#"),
+    Column::left("type", "TYPE"),
+    Column::left("find", "FIND"),
+    Column::left("replace", "REPLACE"),
+];
+
+fn cmd_rules(action: RulesAction) -> Result<(), DictationError> {
+    match action {
+        RulesAction::Add {
+            find,
+            replace,
+            regex,
+        } => {
+            let rule = ReplacementRule::new(&find, &replace, regex)?;
+            let saved = settings::store::update(|s| s.replacement_rules.push(rule.clone()))
+                .map_err(DictationError::SettingsError)?;
+            eprintln!(
+                "Rule {} added: {} → {}",
+                saved.replacement_rules.len(),
+                rule.find,
+                rule.replace
+            );
+        }
+        RulesAction::List { table } => {
+            let stored = settings::store::load();
+            if stored.replacement_rules.is_empty() {
+                eprintln!("No replacement rules");
+                return Ok(());
+            }
+            let mut rows = Table::new(&RULES_COLUMNS);
+            for (index, rule) in stored.replacement_rules.iter().enumerate() {
+                rows.row(vec![
+                    (index + 1).to_string(),
+                    rule.kind().to_string(),
+                    rule.find.clone(),
+                    rule.replace.clone(),
+                ]);
+            }
+            rows.print(&table)?;
+        }
+        RulesAction::Remove { number } => {
+            let mut removed = None;
+            settings::store::update(|s| {
+                if (1..=s.replacement_rules.len()).contains(&number) {
+                    removed = Some(s.replacement_rules.remove(number - 1));
+                }
+            })
+            .map_err(DictationError::SettingsError)?;
+            let rule = removed.ok_or_else(|| {
+                DictationError::SettingsError(format!(
+                    "No rule {number}; see `sagascript config rules list`"
+                ))
+            })?;
+            eprintln!("Rule {number} removed: {} → {}", rule.find, rule.replace);
+        }
+    }
+    Ok(())
+}
+
+/// Flags given to `config profile`, still unparsed.
+#[derive(Default)]
+struct ProfileChanges {
+    language: Option<String>,
+    model: Option<String>,
+    hotkey: Option<String>,
+    overlay_color: Option<String>,
+    sound: Option<String>,
+    schedule: Option<String>,
+    output_sinks: Option<String>,
+}
+
+impl ProfileChanges {
+    fn is_empty(&self) -> bool {
+        self.language.is_none()
+            && self.model.is_none()
+            && self.hotkey.is_none()
+            && self.overlay_color.is_none()
+            && self.sound.is_none()
+            && self.schedule.is_none()
+            && self.output_sinks.is_none()
+    }
+
+    fn apply(self, profile: &mut Profile) -> Result<(), DictationError> {
+        if let Some(language) = self.language {
+            profile.language = parse_enum_value::<Language>(&language, "language")?;
+        }
+        if let Some(model) = self.model {
+            profile.whisper_model = match model.as_str() {
+                "auto" => None,
+                id => Some(parse_enum_value::<WhisperModel>(id, "model")?),
+            };
+        }
+        if let Some(hotkey) = self.hotkey {
+            let hotkey = hotkey.trim().to_string();
+            if !hotkey.is_empty() {
+                validate_hotkey(&hotkey)?;
+            }
+            profile.hotkey = hotkey;
+        }
+        if let Some(color) = self.overlay_color {
+            profile.overlay_color = color.trim().to_string();
+        }
+        if let Some(sound) = self.sound {
+            profile.sound_cue = SoundCue::parse(&sound).ok_or_else(|| {
+                DictationError::SettingsError(format!(
+                    "Invalid sound '{sound}'. Valid: none, low, mid, high"
+                ))
+            })?;
+        }
+        if let Some(schedule) = self.schedule {
+            profile.schedule = schedule.trim().to_string();
+        }
+        if let Some(sinks) = self.output_sinks {
+            profile.output_sinks = match sinks.trim() {
+                "default" => None,
+                sinks => Some(parse_sinks(sinks)?),
+            };
+        }
+        Ok(())
+    }
+}
+
+fn cmd_profile(
+    name: Option<&str>,
+    changes: ProfileChanges,
+    remove: bool,
+) -> Result<(), DictationError> {
+    let stored = settings::store::load();
+    let Some(name) = name.map(str::trim).filter(|n| !n.is_empty()) else {
+        for profile in &stored.profiles {
+            println!("{}", format_profile(profile));
+        }
+        return Ok(());
+    };
+
+    if remove {
+        if stored.profile(name).is_none() {
+            return Err(DictationError::SettingsError(format!(
+                "No profile named '{name}'"
+            )));
+        }
+        settings::store::update(|s| {
+            s.profiles
+                .retain(|p| !p.name.trim().eq_ignore_ascii_case(name));
+        })
+        .map_err(DictationError::SettingsError)?;
+        eprintln!("Profile {name} removed");
+        return Ok(());
+    }
+
+    if changes.is_empty() {
+        let profile = stored.profile(name).ok_or_else(|| {
+            DictationError::SettingsError(format!(
+                "No profile named '{name}'. Set --language, --hotkey or another flag to create it."
+            ))
+        })?;
+        println!("{}", format_profile(profile));
+        return Ok(());
+    }
+
+    let profiles = updated_profiles(&stored, name, changes)?;
+    let saved = settings::store::update(|s| s.profiles = profiles.clone())
+        .map_err(DictationError::SettingsError)?;
+    eprintln!("Profile {name} saved");
+    if let Some(profile) = saved.profile(name) {
+        println!("{}", format_profile(profile));
+    }
+    Ok(())
+}
+
+/// `stored.profiles` with `changes` applied to the profile called `name`
+/// (created from the current language if missing), checked as a whole.
+fn updated_profiles(
+    stored: &Settings,
+    name: &str,
+    changes: ProfileChanges,
+) -> Result<Vec<Profile>, DictationError> {
+    let mut profiles = stored.profiles.clone();
+    let index = match profiles
+        .iter()
+        .position(|p| p.name.trim().eq_ignore_ascii_case(name))
+    {
+        Some(index) => index,
+        None => {
+            profiles.push(Profile {
+                name: name.to_string(),
+                language: stored.language,
+                ..Default::default()
+            });
+            profiles.len() - 1
+        }
+    };
+    changes.apply(&mut profiles[index])?;
+    settings::profiles::validate_profiles(&profiles).map_err(DictationError::SettingsError)?;
+
+    if let Some(hotkey) = profiles[index].shortcut() {
+        // The flush hotkey is only registered in accumulate mode.
+        let flush = stored.accumulate_mode.then_some(&stored.flush_hotkey);
+        let others = [
+            Some(&stored.hotkey),
+            flush,
+            Some(&stored.toggle_hotkey),
+            Some(&stored.settings_hotkey),
+        ];
+        if others
+            .into_iter()
+            .flatten()
+            .any(|other| other.trim().eq_ignore_ascii_case(hotkey))
+        {
+            return Err(DictationError::SettingsError(format!(
+                "Hotkey '{hotkey}' is already the dictation, flush, toggle or settings hotkey"
+            )));
+        }
+    }
+    Ok(profiles)
+}
+
+fn format_profile(profile: &Profile) -> String {
+    format!(
+        "{:<16} language={} model={} hotkey={} overlay_color={} sound={} schedule={} \
+         output_sinks={}",
+        profile.name,
+        format_language(profile.language),
+        profile
+            .whisper_model
+            .map_or_else(|| "auto".to_string(), format_model),
+        profile.shortcut().unwrap_or("none"),
+        if profile.overlay_color.is_empty() {
+            "default"
+        } else {
+            &profile.overlay_color
+        },
+        profile.sound_cue.as_str(),
+        if profile.schedule.is_empty() {
+            "none"
+        } else {
+            &profile.schedule
+        },
+        profile
+            .output_sinks
+            .as_deref()
+            .map_or_else(|| "default".to_string(), format_sinks)
+    )
+}
+
 // -- Helpers --
 
 fn validate_key(key: &str) -> Result<(), DictationError> {
@@ -320,15 +1828,66 @@ fn get_setting_value(settings: &Settings, key: &str) -> String {
     match key {
         "language" => format_language(settings.language),
         "whisper_model" => format_model(settings.whisper_model),
-        "hotkey_mode" => format_hotkey_mode(settings.hotkey_mode),
+        "hotkey_mode" => format_enum_value(settings.hotkey_mode),
         "show_overlay" => settings.show_overlay.to_string(),
-        "auto_paste" => settings.auto_paste.to_string(),
+        "auto_paste" => wants_paste(&settings.output_sinks).to_string(),
         "auto_select_model" => settings.auto_select_model.to_string(),
         "hotkey" => settings.hotkey.clone(),
         "initial_prompt" => settings.initial_prompt.clone(),
         "beam_size" => settings.beam_size.to_string(),
         "temperature_fallback" => settings.temperature_fallback.to_string(),
         "vad_enabled" => settings.vad_enabled.to_string(),
+        "adaptive_model" => settings.adaptive_model.to_string(),
+        "adaptive_short_clip_secs" => settings.adaptive_short_clip_secs.to_string(),
+        "adaptive_long_clip_secs" => settings.adaptive_long_clip_secs.to_string(),
+        "accumulate_mode" => settings.accumulate_mode.to_string(),
+        "flush_hotkey" => settings.flush_hotkey.clone(),
+        "flush_phrase" => settings.flush_phrase.clone(),
+        "output_sinks" => format_sinks(&settings.output_sinks),
+        "model_update_check" => settings.model_update_check.to_string(),
+        "monitor_input" => settings.monitor_input.to_string(),
+        "monitor_volume" => settings.monitor_volume.to_string(),
+        "ffmpeg_fallback" => settings.ffmpeg_fallback.to_string(),
+        "while_busy" => format_enum_value(settings.while_busy),
+        "keywords" => keywords::format_keywords(&settings.keywords),
+        "localize_numbers" => settings.localize_numbers.to_string(),
+        "voice_commands" => settings.voice_commands.to_string(),
+        "paste_formatting" => settings.paste_formatting.to_string(),
+        "tray_visible" => settings.tray_visible.to_string(),
+        "tray_status_text" => settings.tray_status_text.to_string(),
+        "tray_menu" => format_tray_menu(&settings.tray_menu),
+        "telemetry" => settings.telemetry.to_string(),
+        "telemetry_endpoint" => settings.telemetry_endpoint.clone(),
+        "overlay_high_contrast" => settings.overlay_high_contrast.to_string(),
+        "overlay_large_text" => settings.overlay_large_text.to_string(),
+        "overlay_reduced_motion" => settings.overlay_reduced_motion.to_string(),
+        "announce_state" => settings.announce_state.to_string(),
+        "settings_hotkey" => settings.settings_hotkey.clone(),
+        "settings_hotkey_tab" => format_enum_value(settings.settings_hotkey_tab),
+        "toggle_hotkey" => settings.toggle_hotkey.clone(),
+        "max_recording_minutes" => settings.max_recording_minutes.to_string(),
+        "silence_stop_secs" => settings.silence_stop_secs.to_string(),
+        "paste_confirm_chars" => settings.paste_confirm_chars.to_string(),
+        "capture_source" => format_enum_value(settings.capture_source),
+        "standby_stream" => settings.standby_stream.to_string(),
+        "split_channels" => settings.split_channels.to_string(),
+        "post_process_command" => settings.post_process_command.clone(),
+        "post_process_prompt" => settings.post_process_prompt.clone(),
+        "post_process_model" => settings.post_process_model.clone(),
+        "transcription_backend" => format_enum_value(settings.transcription_backend),
+        "openai_model" => settings.openai_model.clone(),
+        "azure_region" => settings.azure_region.clone(),
+        "event_stream_port" => settings.event_stream_port.to_string(),
+        "use_gpu" => settings.use_gpu.to_string(),
+        "punctuate" => settings.punctuate.to_string(),
+        "custom_model" => settings.custom_model.clone(),
+        "draft_pass" => settings.draft_pass.to_string(),
+        "replace_draft" => settings.replace_draft.to_string(),
+        "dedupe_dictations" => settings.dedupe_dictations.to_string(),
+        "network_proxy" => settings.network_proxy.clone(),
+        "model_mirror" => settings.model_mirror.clone(),
+        "dry_run" => settings.dry_run.to_string(),
+        "preload_model" => settings.preload_model.to_string(),
         _ => "unknown".to_string(),
     }
 }
@@ -345,10 +1904,11 @@ fn format_model(model: WhisperModel) -> String {
         .unwrap_or_else(|_| format!("{:?}", model))
 }
 
-fn format_hotkey_mode(mode: HotkeyMode) -> String {
-    serde_json::to_value(mode)
+/// The serde name of a unit enum setting (`push`, `cancel-and-restart`).
+pub(crate) fn format_enum_value<T: serde::Serialize + std::fmt::Debug>(value: T) -> String {
+    serde_json::to_value(&value)
         .and_then(serde_json::from_value::<String>)
-        .unwrap_or_else(|_| format!("{:?}", mode))
+        .unwrap_or_else(|_| format!("{:?}", value))
 }
 
 fn parse_enum_value<T: serde::de::DeserializeOwned>(
@@ -598,8 +2158,22 @@ mod tests {
     #[test]
     fn valid_keys_count_matches_settings_struct() {
         // Internal fields that are serialized but not user-configurable via `config`.
-        // These have dedicated CLI commands instead (e.g. `reset-onboarding`).
-        const INTERNAL_FIELDS: &[&str] = &["has_completed_onboarding"];
+        // These have dedicated CLI commands instead (e.g. `reset-onboarding`,
+        // `config prompt-history`, `config model-override`, `config model-pin`,
+        // `config paste-rule`, `config profile`, `config rules`, `models add`,
+        // `models url`).
+        const INTERNAL_FIELDS: &[&str] = &[
+            "has_completed_onboarding",
+            "prompt_history",
+            "profile_prompt_history",
+            "model_overrides",
+            "model_pins",
+            "custom_models",
+            "model_urls",
+            "paste_app_rules",
+            "profiles",
+            "replacement_rules",
+        ];
 
         let settings = Settings::default();
         let json = serde_json::to_value(&settings).unwrap();
@@ -613,6 +2187,29 @@ mod tests {
         );
     }
 
+    // -- parse_assignments --
+
+    #[test]
+    fn parse_assignments_takes_a_pair_or_key_value_args() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let single = args(&["initial_prompt", "a=b"]);
+        assert_eq!(
+            parse_assignments(&single).unwrap(),
+            [("initial_prompt", "a=b")]
+        );
+        let several = args(&["language=sv", "hotkey=Option+Space", "flush_phrase="]);
+        assert_eq!(
+            parse_assignments(&several).unwrap(),
+            [
+                ("language", "sv"),
+                ("hotkey", "Option+Space"),
+                ("flush_phrase", "")
+            ]
+        );
+        assert!(parse_assignments(&args(&["language=sv", "auto_paste"])).is_err());
+        assert!(parse_assignments(&args(&["language"])).is_err());
+    }
+
     // -- parse_enum_value --
 
     #[test]
@@ -664,6 +2261,118 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn while_busy_round_trips_through_set_and_get() {
+        let mut settings = Settings::default();
+        assert_eq!(get_setting_value(&settings, "while_busy"), "reject");
+        apply_setting_value(&mut settings, "while_busy", "cancel-and-restart").unwrap();
+        assert_eq!(settings.while_busy, WhileBusy::CancelAndRestart);
+        assert_eq!(get_setting_value(&settings, "while_busy"), "cancel-and-restart");
+        assert!(apply_setting_value(&mut settings, "while_busy", "wait").is_err());
+    }
+
+    // -- profiles --
+
+    #[test]
+    fn profile_changes_create_then_update_a_profile() {
+        let stored = Settings {
+            language: Language::Norwegian,
+            ..Default::default()
+        };
+        let changes = ProfileChanges {
+            hotkey: Some("Alt+Shift+S".into()),
+            sound: Some("high".into()),
+            ..Default::default()
+        };
+        let profiles = updated_profiles(&stored, "Svenska", changes).unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].language, Language::Norwegian);
+        assert_eq!(profiles[0].sound_cue, SoundCue::High);
+
+        let stored = Settings { profiles, ..stored };
+        let changes = ProfileChanges {
+            language: Some("sv".into()),
+            model: Some("kb-whisper-small".into()),
+            ..Default::default()
+        };
+        let profiles = updated_profiles(&stored, "svenska", changes).unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].language, Language::Swedish);
+        assert_eq!(
+            profiles[0].whisper_model,
+            Some(WhisperModel::KbWhisperSmall)
+        );
+        assert_eq!(profiles[0].hotkey, "Alt+Shift+S");
+        assert_eq!(profiles[0].output_sinks, None);
+
+        let stored = Settings { profiles, ..stored };
+        let changes = ProfileChanges {
+            output_sinks: Some("clipboard,file:~/notes.md".into()),
+            ..Default::default()
+        };
+        let profiles = updated_profiles(&stored, "Svenska", changes).unwrap();
+        assert_eq!(
+            profiles[0].output_sinks.as_deref().map(format_sinks),
+            Some("clipboard,file:~/notes.md".to_string())
+        );
+
+        let stored = Settings { profiles, ..stored };
+        let changes = ProfileChanges {
+            output_sinks: Some("default".into()),
+            ..Default::default()
+        };
+        let profiles = updated_profiles(&stored, "Svenska", changes).unwrap();
+        assert_eq!(profiles[0].output_sinks, None);
+    }
+
+    #[test]
+    fn profile_hotkey_cannot_reuse_the_main_hotkeys() {
+        let stored = Settings::default();
+        let changes = ProfileChanges {
+            hotkey: Some(stored.hotkey.to_lowercase()),
+            ..Default::default()
+        };
+        assert!(updated_profiles(&stored, "Work", changes).is_err());
+
+        let changes = ProfileChanges {
+            overlay_color: Some("red".into()),
+            ..Default::default()
+        };
+        assert!(updated_profiles(&stored, "Work", changes).is_err());
+
+        let changes = ProfileChanges {
+            schedule: Some("weekdays".into()),
+            ..Default::default()
+        };
+        assert!(updated_profiles(&stored, "Work", changes).is_err());
+    }
+
+    // -- model overrides --
+
+    #[test]
+    fn merge_overrides_keeps_unchanged_fields() {
+        let mut current = ModelOverrides {
+            no_speech_threshold: Some(0.5),
+            beam_size: Some(4),
+            temperature_fallback: None,
+        };
+        merge_overrides(
+            &mut current,
+            ModelOverrides {
+                beam_size: Some(0),
+                temperature_fallback: Some(false),
+                ..Default::default()
+            },
+        );
+        assert_eq!(current.no_speech_threshold, Some(0.5));
+        assert_eq!(current.beam_size, Some(0));
+        assert_eq!(current.temperature_fallback, Some(false));
+        assert_eq!(
+            format_overrides(&current),
+            "no_speech_threshold=0.5 beam_size=0 temperature_fallback=false"
+        );
+    }
+
     // -- parse_bool --
 
     #[test]
@@ -706,6 +2415,67 @@ mod tests {
         assert_eq!(get_setting_value(&settings, "beam_size"), "0");
         assert_eq!(get_setting_value(&settings, "temperature_fallback"), "true");
         assert_eq!(get_setting_value(&settings, "vad_enabled"), "false");
+        assert_eq!(get_setting_value(&settings, "adaptive_model"), "false");
+        assert_eq!(get_setting_value(&settings, "adaptive_short_clip_secs"), "10");
+        assert_eq!(get_setting_value(&settings, "adaptive_long_clip_secs"), "120");
+        assert_eq!(get_setting_value(&settings, "accumulate_mode"), "false");
+        assert_eq!(get_setting_value(&settings, "flush_hotkey"), "Control+Shift+Enter");
+        assert_eq!(get_setting_value(&settings, "flush_phrase"), "paste that");
+        assert_eq!(get_setting_value(&settings, "output_sinks"), "paste");
+        assert_eq!(get_setting_value(&settings, "model_update_check"), "false");
+        assert_eq!(get_setting_value(&settings, "monitor_input"), "false");
+        assert_eq!(get_setting_value(&settings, "monitor_volume"), "0.5");
+        assert_eq!(get_setting_value(&settings, "ffmpeg_fallback"), "false");
+        assert_eq!(get_setting_value(&settings, "keywords"), "");
+        assert_eq!(get_setting_value(&settings, "localize_numbers"), "false");
+        assert_eq!(get_setting_value(&settings, "voice_commands"), "false");
+        assert_eq!(get_setting_value(&settings, "tray_visible"), "true");
+        assert_eq!(get_setting_value(&settings, "tray_status_text"), "true");
+        assert_eq!(
+            get_setting_value(&settings, "tray_menu"),
+            "status,settings,transcribe-file,capture-source,install-cli"
+        );
+        assert_eq!(get_setting_value(&settings, "telemetry"), "false");
+        assert_eq!(get_setting_value(&settings, "telemetry_endpoint"), "");
+        assert_eq!(
+            get_setting_value(&settings, "overlay_high_contrast"),
+            "false"
+        );
+        assert_eq!(get_setting_value(&settings, "overlay_large_text"), "false");
+        assert_eq!(
+            get_setting_value(&settings, "overlay_reduced_motion"),
+            "false"
+        );
+        assert_eq!(get_setting_value(&settings, "announce_state"), "false");
+        assert_eq!(get_setting_value(&settings, "settings_hotkey"), "");
+        assert_eq!(
+            get_setting_value(&settings, "settings_hotkey_tab"),
+            "settings"
+        );
+        assert_eq!(get_setting_value(&settings, "toggle_hotkey"), "");
+        assert_eq!(get_setting_value(&settings, "max_recording_minutes"), "15");
+        assert_eq!(get_setting_value(&settings, "silence_stop_secs"), "0");
+        assert_eq!(get_setting_value(&settings, "paste_confirm_chars"), "0");
+        assert_eq!(get_setting_value(&settings, "capture_source"), "microphone");
+        assert_eq!(get_setting_value(&settings, "standby_stream"), "false");
+        assert_eq!(get_setting_value(&settings, "split_channels"), "false");
+        assert_eq!(get_setting_value(&settings, "post_process_command"), "");
+        assert_eq!(get_setting_value(&settings, "post_process_prompt"), "");
+        assert_eq!(get_setting_value(&settings, "post_process_model"), "");
+        assert_eq!(get_setting_value(&settings, "transcription_backend"), "local");
+        assert_eq!(get_setting_value(&settings, "openai_model"), "whisper-1");
+        assert_eq!(get_setting_value(&settings, "azure_region"), "");
+        assert_eq!(get_setting_value(&settings, "event_stream_port"), "0");
+        assert_eq!(get_setting_value(&settings, "use_gpu"), "true");
+        assert_eq!(get_setting_value(&settings, "punctuate"), "true");
+        assert_eq!(get_setting_value(&settings, "custom_model"), "");
+        assert_eq!(get_setting_value(&settings, "draft_pass"), "false");
+        assert_eq!(get_setting_value(&settings, "replace_draft"), "true");
+        assert_eq!(get_setting_value(&settings, "dedupe_dictations"), "true");
+        assert_eq!(get_setting_value(&settings, "network_proxy"), "");
+        assert_eq!(get_setting_value(&settings, "model_mirror"), "");
+        assert_eq!(get_setting_value(&settings, "dry_run"), "false");
+        assert_eq!(get_setting_value(&settings, "preload_model"), "true");
     }
 
     #[test]
@@ -714,6 +2484,79 @@ mod tests {
         assert_eq!(get_setting_value(&settings, "nonexistent"), "unknown");
     }
 
+    #[test]
+    fn setting_initial_prompt_records_history() {
+        let mut settings = Settings::default();
+        apply_setting_value(&mut settings, "initial_prompt", "Tauri, whisper-rs").unwrap();
+        apply_setting_value(&mut settings, "initial_prompt", "").unwrap();
+        assert_eq!(settings.initial_prompt, "");
+        assert_eq!(settings.prompt_history, vec!["Tauri, whisper-rs"]);
+    }
+
+    #[test]
+    fn monitor_volume_must_be_in_range() {
+        let mut settings = Settings::default();
+        apply_setting_value(&mut settings, "monitor_volume", "1.5").unwrap();
+        assert_eq!(settings.monitor_volume, 1.5);
+        assert!(apply_setting_value(&mut settings, "monitor_volume", "2.5").is_err());
+        assert!(apply_setting_value(&mut settings, "monitor_volume", "-0.1").is_err());
+        assert!(apply_setting_value(&mut settings, "monitor_volume", "loud").is_err());
+        assert_eq!(settings.monitor_volume, 1.5);
+    }
+
+    #[test]
+    fn max_recording_minutes_must_be_in_range() {
+        let mut settings = Settings::default();
+        apply_setting_value(&mut settings, "max_recording_minutes", "60").unwrap();
+        assert_eq!(settings.max_recording_minutes, 60);
+        assert!(apply_setting_value(&mut settings, "max_recording_minutes", "0").is_err());
+        assert!(apply_setting_value(&mut settings, "max_recording_minutes", "121").is_err());
+        assert!(apply_setting_value(&mut settings, "max_recording_minutes", "1.5").is_err());
+        assert_eq!(settings.max_recording_minutes, 60);
+    }
+
+    #[test]
+    fn silence_stop_secs_is_off_or_up_to_a_minute() {
+        let mut settings = Settings::default();
+        apply_setting_value(&mut settings, "silence_stop_secs", "3").unwrap();
+        assert_eq!(settings.silence_stop_secs, 3);
+        apply_setting_value(&mut settings, "silence_stop_secs", "0").unwrap();
+        assert_eq!(settings.silence_stop_secs, 0);
+        assert!(apply_setting_value(&mut settings, "silence_stop_secs", "61").is_err());
+        assert!(apply_setting_value(&mut settings, "silence_stop_secs", "-1").is_err());
+    }
+
+    #[test]
+    fn capture_source_round_trips_through_set_and_get() {
+        let mut settings = Settings::default();
+        apply_setting_value(&mut settings, "capture_source", "system").unwrap();
+        assert_eq!(settings.capture_source, CaptureSource::System);
+        assert_eq!(get_setting_value(&settings, "capture_source"), "system");
+        assert!(apply_setting_value(&mut settings, "capture_source", "speakers").is_err());
+    }
+
+    #[test]
+    fn paste_confirm_chars_takes_a_character_count() {
+        let mut settings = Settings::default();
+        apply_setting_value(&mut settings, "paste_confirm_chars", "500").unwrap();
+        assert_eq!(settings.paste_confirm_chars, 500);
+        apply_setting_value(&mut settings, "paste_confirm_chars", "0").unwrap();
+        assert_eq!(settings.paste_confirm_chars, 0);
+        assert!(apply_setting_value(&mut settings, "paste_confirm_chars", "lots").is_err());
+    }
+
+    #[test]
+    fn toggle_hotkey_must_differ_from_hotkey() {
+        let mut settings = Settings::default();
+        settings.hotkey = "Control+Shift+Space".into();
+        apply_setting_value(&mut settings, "toggle_hotkey", "Control+Alt+Space").unwrap();
+        assert!(
+            apply_setting_value(&mut settings, "toggle_hotkey", "control+shift+space").is_err()
+        );
+        apply_setting_value(&mut settings, "toggle_hotkey", "").unwrap();
+        assert!(settings.toggle_hotkey.is_empty());
+    }
+
     #[test]
     fn enabling_auto_paste_warns_about_gui_accessibility_requirement() {
         let settings = Settings::default();
@@ -722,10 +2565,72 @@ mod tests {
         assert!(warning.contains("reset auto-paste to false"));
 
         let disabled = Settings {
-            auto_paste: false,
+            output_sinks: Vec::new(),
             ..Default::default()
         };
         assert!(setting_warning("auto_paste", &disabled).is_none());
+        assert!(setting_warning("output_sinks", &disabled).is_none());
+        assert!(setting_warning("output_sinks", &settings).is_some());
         assert!(setting_warning("show_overlay", &Settings::default()).is_none());
     }
+
+    #[test]
+    fn post_processing_endpoints_warn_that_dictations_leave_the_machine() {
+        let mut settings = Settings::default();
+        apply_setting_value(
+            &mut settings,
+            "post_process_command",
+            " https://api.openai.com/v1/chat/completions ",
+        )
+        .unwrap();
+        assert_eq!(
+            settings.post_process_command,
+            "https://api.openai.com/v1/chat/completions"
+        );
+        assert!(setting_warning("post_process_command", &settings)
+            .unwrap()
+            .contains("post-process-key"));
+
+        apply_setting_value(&mut settings, "post_process_command", "ollama run llama3.2").unwrap();
+        assert!(setting_warning("post_process_command", &settings).is_none());
+    }
+
+    #[test]
+    fn openai_backend_warns_that_dictations_leave_the_machine() {
+        let mut settings = Settings::default();
+        assert!(setting_warning("transcription_backend", &settings).is_none());
+        apply_setting_value(&mut settings, "transcription_backend", "openai").unwrap();
+        assert_eq!(settings.transcription_backend, BackendKind::OpenAi);
+        assert_eq!(get_setting_value(&settings, "transcription_backend"), "openai");
+        assert!(setting_warning("transcription_backend", &settings)
+            .unwrap()
+            .contains("OpenAI"));
+        assert!(apply_setting_value(&mut settings, "transcription_backend", "cloud").is_err());
+    }
+
+    #[test]
+    fn azure_backend_takes_a_region_name() {
+        let mut settings = Settings::default();
+        apply_setting_value(&mut settings, "transcription_backend", "azure").unwrap();
+        assert_eq!(settings.transcription_backend, BackendKind::Azure);
+        assert!(setting_warning("transcription_backend", &settings)
+            .unwrap()
+            .contains("Azure"));
+        apply_setting_value(&mut settings, "azure_region", " WestEurope ").unwrap();
+        assert_eq!(get_setting_value(&settings, "azure_region"), "westeurope");
+        assert!(apply_setting_value(&mut settings, "azure_region", "example.com/x").is_err());
+    }
+
+    #[test]
+    fn custom_model_must_be_registered() {
+        let mut settings = Settings::default();
+        assert!(apply_setting_value(&mut settings, "custom_model", "my-model").is_err());
+        settings
+            .custom_models
+            .insert("my-model".to_string(), "/models/my.bin".into());
+        apply_setting_value(&mut settings, "custom_model", "my-model").unwrap();
+        assert_eq!(get_setting_value(&settings, "custom_model"), "my-model");
+        apply_setting_value(&mut settings, "custom_model", "").unwrap();
+        assert!(settings.custom_model.is_empty());
+    }
 }
@@ -0,0 +1,81 @@
+use clap::Args;
+
+use sagascript_core::audio::capture::{self, InputDeviceInfo};
+use sagascript_core::error::DictationError;
+
+use super::table::{Column, Table, TableArgs};
+
+const COLUMNS: [Column; 4] = [
+    Column::left("name", "DEVICE"),
+    Column::right("rate", "SAMPLE RATE"),
+    Column::right("channels", "CHANNELS"),
+    Column::left("default", "DEFAULT"),
+];
+
+#[derive(Args)]
+pub struct ListDevicesArgs {
+    /// Output a JSON array of `{name, sample_rate, channels, is_default}`
+    #[arg(long)]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub table: TableArgs,
+}
+
+pub fn list(args: ListDevicesArgs) -> Result<(), DictationError> {
+    let devices = capture::list_input_devices()?;
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&devices)
+            .map_err(|e| DictationError::SettingsError(format!("JSON error: {e}")))?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    if devices.is_empty() {
+        eprintln!("No input devices found");
+        return Ok(());
+    }
+    let mut table = Table::new(&COLUMNS);
+    for device in &devices {
+        table.row(row(device));
+    }
+    table.print(&args.table)
+}
+
+fn row(device: &InputDeviceInfo) -> Vec<String> {
+    vec![
+        device.name.clone(),
+        device
+            .sample_rate
+            .map_or_else(|| "—".to_string(), |rate| format!("{rate} Hz")),
+        device
+            .channels
+            .map_or_else(|| "—".to_string(), |ch| ch.to_string()),
+        if device.is_default { "*" } else { "" }.to_string(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn row_marks_the_default_and_unknown_configs() {
+        let device = InputDeviceInfo {
+            name: "USB Mic".into(),
+            sample_rate: Some(48_000),
+            channels: Some(2),
+            is_default: true,
+        };
+        assert_eq!(row(&device), ["USB Mic", "48000 Hz", "2", "*"]);
+
+        let device = InputDeviceInfo {
+            name: "Loopback".into(),
+            sample_rate: None,
+            channels: None,
+            is_default: false,
+        };
+        assert_eq!(row(&device), ["Loopback", "—", "—", ""]);
+    }
+}
@@ -0,0 +1,368 @@
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand, ValueEnum};
+
+use sagascript_core::error::DictationError;
+use sagascript_core::history::{self, HistoryEntry, HistorySource};
+use sagascript_core::transcription::catalog;
+
+use super::table::{Column, Table, TableArgs};
+
+#[derive(Args)]
+pub struct HistoryArgs {
+    #[command(subcommand)]
+    pub action: HistoryAction,
+}
+
+#[derive(Subcommand)]
+pub enum HistoryAction {
+    /// List recent transcriptions, newest first
+    List {
+        /// Number of transcriptions to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+
+        /// Output a JSON array (the same entries the GUI shows)
+        #[arg(long)]
+        json: bool,
+
+        /// Only transcriptions from SOURCE [possible values: dictation,
+        /// file, cli]
+        #[arg(long, value_name = "SOURCE", value_parser = parse_source)]
+        source: Option<HistorySource>,
+
+        #[command(flatten)]
+        table: TableArgs,
+    },
+    /// Print one transcription in full
+    Show {
+        /// Entry ID, as shown by `history list` (e.g. h-1a2b3c4d)
+        id: String,
+
+        /// Output the entry as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List transcriptions whose text or file name contains QUERY
+    Search {
+        /// Text to look for (case-insensitive)
+        query: String,
+
+        /// Number of matches to show, newest first
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+
+        /// Output a JSON array
+        #[arg(long)]
+        json: bool,
+
+        /// Only transcriptions from SOURCE [possible values: dictation,
+        /// file, cli]
+        #[arg(long, value_name = "SOURCE", value_parser = parse_source)]
+        source: Option<HistorySource>,
+
+        #[command(flatten)]
+        table: TableArgs,
+    },
+    /// Write the whole history, oldest first, as JSON or CSV
+    Export {
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+
+        /// File to write (default: stdout)
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+    /// Remove one transcription from the history
+    Delete {
+        /// Entry ID, as shown by `history list`
+        id: String,
+    },
+    /// Remove every transcription from the history
+    Clear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+/// Columns of the `history list` and `history search` tables.
+const LIST_COLUMNS: [Column; 6] = [
+    Column::left("id", "ID"),
+    Column::left("time", "TIME (UTC)"),
+    Column::left("source", "SOURCE"),
+    Column::right("duration", "DURATION"),
+    Column::left("model", "MODEL"),
+    Column::left("text", "TEXT"),
+];
+
+fn parse_source(s: &str) -> Result<HistorySource, String> {
+    HistorySource::ALL
+        .into_iter()
+        .find(|source| source.as_str() == s)
+        .ok_or_else(|| format!("'{s}' is not a source (expected dictation, file or cli)"))
+}
+
+pub fn run(args: HistoryArgs) -> Result<(), DictationError> {
+    let path = history::history_path();
+    let entries = history::load(&path);
+    match args.action {
+        HistoryAction::List {
+            limit,
+            json,
+            source,
+            table,
+        } => {
+            if entries.is_empty() && !json {
+                eprintln!("No transcriptions in {}", path.display());
+                return Ok(());
+            }
+            let entries: Vec<HistoryEntry> = entries
+                .into_iter()
+                .filter(|e| source.is_none_or(|s| e.source == s))
+                .take(limit)
+                .collect();
+            list(&entries, json, &table)
+        }
+        HistoryAction::Show { id, json } => {
+            let entry = entries
+                .iter()
+                .find(|e| e.id == id)
+                .ok_or_else(|| no_entry(&id))?;
+            if json {
+                println!("{}", to_json(entry)?);
+            } else {
+                show(entry);
+            }
+            Ok(())
+        }
+        HistoryAction::Search {
+            query,
+            limit,
+            json,
+            source,
+            table,
+        } => {
+            let found: Vec<HistoryEntry> = entries
+                .into_iter()
+                .filter(|e| matches(e, &query) && source.is_none_or(|s| e.source == s))
+                .take(limit)
+                .collect();
+            if found.is_empty() && !json {
+                eprintln!("No transcriptions contain '{query}'");
+                return Ok(());
+            }
+            list(&found, json, &table)
+        }
+        HistoryAction::Export { format, output } => {
+            let mut entries = entries;
+            entries.reverse();
+            let contents = match format {
+                ExportFormat::Json => to_json(&entries)? + "\n",
+                ExportFormat::Csv => to_csv(&entries),
+            };
+            match output {
+                Some(output) => {
+                    std::fs::write(&output, contents).map_err(|e| {
+                        DictationError::OutputError(format!(
+                            "Cannot write {}: {e}",
+                            output.display()
+                        ))
+                    })?;
+                    eprintln!(
+                        "Exported {} transcription(s) to {}",
+                        entries.len(),
+                        output.display()
+                    );
+                }
+                None => print!("{contents}"),
+            }
+            Ok(())
+        }
+        HistoryAction::Delete { id } => {
+            let deleted = history::delete(&path, &id).map_err(|e| {
+                DictationError::SettingsError(format!("Failed to delete {id}: {e}"))
+            })?;
+            if !deleted {
+                return Err(no_entry(&id));
+            }
+            eprintln!("Deleted {id}");
+            Ok(())
+        }
+        HistoryAction::Clear => {
+            history::clear(&path).map_err(|e| {
+                DictationError::SettingsError(format!("Failed to clear {}: {e}", path.display()))
+            })?;
+            eprintln!("History cleared.");
+            Ok(())
+        }
+    }
+}
+
+fn no_entry(id: &str) -> DictationError {
+    DictationError::SettingsError(format!(
+        "No transcription with ID '{id}' (see sagascript history list)"
+    ))
+}
+
+fn to_json<T: serde::Serialize + ?Sized>(value: &T) -> Result<String, DictationError> {
+    serde_json::to_string_pretty(value)
+        .map_err(|e| DictationError::SettingsError(format!("JSON error: {e}")))
+}
+
+fn list(entries: &[HistoryEntry], json: bool, args: &TableArgs) -> Result<(), DictationError> {
+    if json {
+        println!("{}", to_json(entries)?);
+        return Ok(());
+    }
+    let mut table = Table::new(&LIST_COLUMNS);
+    for entry in entries {
+        table.row(vec![
+            entry.id.clone(),
+            history::format_timestamp(entry.recorded_at),
+            source_label(entry),
+            format!("{:.1}s", entry.duration_secs),
+            catalog::model_id(entry.model),
+            one_line(&entry.text),
+        ]);
+    }
+    table.print(args)
+}
+
+fn show(entry: &HistoryEntry) {
+    println!("ID:        {}", entry.id);
+    println!(
+        "Time:      {} UTC",
+        history::format_timestamp(entry.recorded_at)
+    );
+    println!("Source:    {}", source_label(entry));
+    if let Some(device) = &entry.device {
+        println!("Device:    {device}");
+    }
+    println!("Duration:  {:.1}s", entry.duration_secs);
+    println!("Model:     {}", catalog::model_id(entry.model));
+    println!(
+        "Language:  {}",
+        entry.language.whisper_code().unwrap_or("auto")
+    );
+    println!();
+    println!("{}", entry.text);
+}
+
+/// `dictation`, the name of the transcribed file, or either with `cli: `
+/// in front for the CLI's transcriptions.
+fn source_label(entry: &HistoryEntry) -> String {
+    match (&entry.source, &entry.file) {
+        (HistorySource::File, Some(file)) => file.clone(),
+        (HistorySource::Cli, Some(file)) => format!("cli: {file}"),
+        (source, _) => source.as_str().to_string(),
+    }
+}
+
+/// The text with its line breaks folded, for a table cell.
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn matches(entry: &HistoryEntry, query: &str) -> bool {
+    let query = query.to_lowercase();
+    entry.text.to_lowercase().contains(&query)
+        || entry
+            .file
+            .as_deref()
+            .is_some_and(|file| file.to_lowercase().contains(&query))
+}
+
+/// RFC 4180 CSV with a header row.
+fn to_csv(entries: &[HistoryEntry]) -> String {
+    let mut csv = String::from("id,time,source,file,device,duration_secs,model,language,text\r\n");
+    for entry in entries {
+        let fields = [
+            entry.id.clone(),
+            history::format_timestamp(entry.recorded_at),
+            entry.source.as_str().to_string(),
+            entry.file.clone().unwrap_or_default(),
+            entry.device.clone().unwrap_or_default(),
+            format!("{:.1}", entry.duration_secs),
+            catalog::model_id(entry.model),
+            entry.language.whisper_code().unwrap_or("auto").to_string(),
+            entry.text.clone(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sagascript_core::settings::{Language, WhisperModel};
+
+    fn entry(text: &str, file: Option<&str>) -> HistoryEntry {
+        let entry = HistoryEntry {
+            id: "h-1a2b3c4d".to_string(),
+            recorded_at: 1_792_149_296,
+            source: HistorySource::Dictation,
+            file: None,
+            device: None,
+            duration_secs: 4.3,
+            model: WhisperModel::KbWhisperBase,
+            language: Language::Swedish,
+            text: text.to_string(),
+        };
+        match file {
+            Some(file) => HistoryEntry {
+                source: HistorySource::File,
+                ..entry.with_file(file)
+            },
+            None => entry,
+        }
+    }
+
+    #[test]
+    fn search_matches_text_and_file_name_ignoring_case() {
+        assert!(matches(&entry("Ring Anna om budgeten", None), "anna"));
+        assert!(matches(&entry("Hello", Some("Standup.m4a")), "standup"));
+        assert!(!matches(&entry("Hello", None), "standup"));
+    }
+
+    #[test]
+    fn csv_quotes_fields_that_need_it() {
+        let csv = to_csv(&[entry("Hi, \"team\"\nsecond line", Some("call.m4a"))]);
+        assert_eq!(
+            csv,
+            "id,time,source,file,device,duration_secs,model,language,text\r\n\
+             h-1a2b3c4d,2026-10-16 11:14:56,file,call.m4a,,4.3,kb-whisper-base,sv,\
+             \"Hi, \"\"team\"\"\nsecond line\"\r\n"
+        );
+    }
+
+    #[test]
+    fn sources_parse_and_label() {
+        assert_eq!(parse_source("cli"), Ok(HistorySource::Cli));
+        assert!(parse_source("api").is_err());
+        let cli = HistoryEntry {
+            source: HistorySource::Cli,
+            ..entry("Hello", Some("memo.m4a"))
+        };
+        assert_eq!(source_label(&cli), "cli: memo.m4a");
+        assert_eq!(source_label(&entry("Hello", None)), "dictation");
+    }
+
+    #[test]
+    fn table_text_is_one_line() {
+        assert_eq!(one_line(" first\nsecond  third "), "first second third");
+    }
+}
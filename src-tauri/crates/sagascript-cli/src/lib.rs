@@ -1,10 +1,24 @@
+pub mod benchmark;
+pub mod bundle;
+pub mod compare;
 pub mod config;
+#[cfg(feature = "record")]
+pub mod devices;
+pub mod history;
 pub mod models;
+pub mod porcelain;
 // Live recording is optional (`record` feature, on by default) so a pure
 // batch-transcribe build (`--no-default-features`) carries no audio-capture
 // stack — on Linux, no cpal/ALSA.
 #[cfg(feature = "record")]
 pub mod record;
+pub mod recover;
+pub mod runtime;
+pub mod self_update;
+pub mod sessions;
+pub mod support_bundle;
+pub mod table;
+pub mod telemetry;
 pub mod transcribe;
 
 use std::io::{self, Write};
@@ -99,7 +113,11 @@ EXAMPLES:
   sagascript completions zsh > ~/.zfunc/_sagascript
 
 ENVIRONMENT:
-  RUST_LOG    Set log level (default: warn for CLI). Example: RUST_LOG=info";
+  RUST_LOG              Set log level (default: warn for CLI). Example: RUST_LOG=info
+  SAGASCRIPT_LANGUAGE   Default --language for 'transcribe'
+  SAGASCRIPT_MODEL      Default --model for 'transcribe'
+  SAGASCRIPT_NO_CONFIG  Set to 1 to make 'transcribe' ignore persisted settings
+  SAGASCRIPT_FFMPEG     ffmpeg binary for the decode fallback (default: search PATH)";
 
 #[cfg(not(feature = "record"))]
 const ROOT_AFTER_LONG_HELP: &str = "\
@@ -123,7 +141,11 @@ EXAMPLES:
   sagascript completions zsh > ~/.zfunc/_sagascript
 
 ENVIRONMENT:
-  RUST_LOG    Set log level (default: warn for CLI). Example: RUST_LOG=info";
+  RUST_LOG              Set log level (default: warn for CLI). Example: RUST_LOG=info
+  SAGASCRIPT_LANGUAGE   Default --language for 'transcribe'
+  SAGASCRIPT_MODEL      Default --model for 'transcribe'
+  SAGASCRIPT_NO_CONFIG  Set to 1 to make 'transcribe' ignore persisted settings
+  SAGASCRIPT_FFMPEG     ffmpeg binary for the decode fallback (default: search PATH)";
 
 #[derive(Parser)]
 #[command(
@@ -141,7 +163,7 @@ pub struct Cli {
 
 #[derive(Subcommand)]
 pub enum Command {
-    /// Transcribe an audio/video file
+    /// Transcribe audio/video files
     #[command(
         long_about = "\
 Transcribe an audio or video file to text using a local Whisper model.
@@ -150,7 +172,50 @@ The file is decoded to 16 kHz mono PCM, then processed by the selected \
 Whisper model. Supports WAV, MP3, M4A, AAC, MP4, MOV, QTA, OGG, WebM, and FLAC.
 
 By default, uses the language and model from your persisted settings \
-(see 'sagascript config list'). Override with --language and --model.
+(see 'sagascript config list'). Override with --language and --model, or \
+the SAGASCRIPT_LANGUAGE and SAGASCRIPT_MODEL environment variables.
+
+--no-config (or SAGASCRIPT_NO_CONFIG=1) ignores the settings file entirely: \
+only flags, environment variables and built-in defaults apply, so a script \
+or CI job behaves the same on every machine.
+
+If a file fails to decode (some m4a/webm variants), pass --ffmpeg or set \
+ffmpeg_fallback to retry it through an installed ffmpeg.
+
+Files longer than 30 minutes are decoded and transcribed in chunks of about \
+two minutes, so memory use stays flat however long the recording is \
+(except with --diarize or --split-channels, which need the whole recording \
+at once).
+
+--split-channels transcribes each channel of the file on its own, for call \
+recordings that put each side of the call on its own channel, and merges \
+the turns in time order as [CH1] / [CH2] lines. A mono file is \
+transcribed as usual.
+
+--minutes (with --diarize or --split-channels) prints meeting minutes in \
+Markdown instead: participants with their speaking time, action items \
+picked out by keyword rules (\"I'll\", \"we need to\", \"follow up\", \
+\"jag ska\", \"vi skal\", ...) as a checklist, and the transcript with \
+timestamps.
+
+Given several files (or glob patterns, which are expanded for shells that \
+don't), the model is loaded once and the files are transcribed in turn, or \
+--jobs at a time. Each transcript is written next to its file as FILE.txt \
+(FILE.json with --json), or into --output-dir, and a summary table is \
+printed at the end. A file that fails doesn't stop the others, but the \
+command exits non-zero. To keep dictation responsive, a batch pauses \
+before each file (and each chunk of a long one) while the app is \
+dictating, and for up to a minute at a time while the system's load per \
+core is above --max-load.
+
+--json output may gain or rename fields between releases. For scripts \
+that must keep working, use --porcelain: a header line 'porcelain<TAB>1', \
+then tab-separated records (file, language, model, duration, one segment \
+per segment with start, end, speaker and text, then text). Tabs, newlines \
+and backslashes in values are escaped as \\t, \\n and \\\\. On failure \
+stdout stays empty and the last stderr line is an 'error' record with \
+kind, message and suggestion. The format only changes with a new version \
+(--porcelain=2); unknown record types should be skipped.
 
 NOTE: --language auto uses a generic multilingual model which is less \
 accurate than the dedicated language models. For best results, specify \
@@ -169,19 +234,98 @@ EXAMPLES:
   # Transcribe and copy to clipboard
   sagascript transcribe note.wav --clipboard
 
+  # Send the result to the configured output sinks (file, webhook, ...)
+  sagascript transcribe note.wav --route
+
   # Pipe-friendly: JSON to jq
-  sagascript transcribe call.wav --json | jq -r .text"
+  sagascript transcribe call.wav --json | jq -r .text
+
+  # Stable output for scripts that must survive upgrades
+  sagascript transcribe call.wav --porcelain | awk -F'\\t' '$1 == \"text\" { print $2 }'
+
+  # A call recorded with each side on its own channel
+  sagascript transcribe support-call.wav --split-channels
+
+  # Meeting minutes with action items, as Markdown
+  sagascript transcribe standup.m4a --diarize --minutes > standup.md
+
+  # Decode an unusual container through an installed ffmpeg
+  sagascript transcribe call.webm --ffmpeg
+
+  # Reproducible in CI: ignore local settings
+  SAGASCRIPT_MODEL=base.en sagascript transcribe call.wav --no-config --json
+
+  # A folder of calls, two at a time, transcripts into transcripts/
+  sagascript transcribe 'calls/*.m4a' --jobs 2 --output-dir transcripts"
     )]
     Transcribe(transcribe::TranscribeArgs),
 
+    /// Transcribe a file with several models and diff the results
+    #[command(
+        long_about = "\
+Transcribe the same audio or video file with each of the given models and \
+print how long each took to load and transcribe, its real-time factor, and \
+a word diff of its transcript against the first model's.
+
+Differences in case and punctuation are ignored when diffing, so the diff \
+shows words the models actually heard differently. Decoding matches \
+'transcribe', using the saved prompt, keywords and beam settings. All \
+models must be downloaded first.",
+        after_long_help = "\
+EXAMPLES:
+  # Is small.en worth the extra time over base.en?
+  sagascript compare meeting.m4a --models base.en,small.en
+
+  # Swedish models, machine-readable
+  sagascript compare intervju.mp3 -l sv -m kb-whisper-base,kb-whisper-small --json"
+    )]
+    Compare(compare::CompareArgs),
+
+    /// Time the downloaded models on a reference clip
+    #[command(
+        long_about = "\
+Transcribe a short reference clip with each downloaded model (or the ones \
+given with --models) and report how long it took to load, its real-time \
+factor (transcription time over clip length; below 1.0 keeps up with \
+speech) and the peak memory use.
+
+Without a FILE a bundled 8-second clip of Norwegian speech is used; the \
+timings hold for any language. Models run smallest first with the saved \
+GPU, beam and fallback settings, so the numbers match dictation on this \
+machine. Use 'compare' to see how their transcripts differ.",
+        after_long_help = "\
+EXAMPLES:
+  # Every downloaded model
+  sagascript benchmark
+
+  # kb-whisper-base or small on this machine?
+  sagascript benchmark --models kb-whisper-base,kb-whisper-small
+
+  # Your own recording, machine-readable
+  sagascript benchmark memo.m4a -l en --json"
+    )]
+    Benchmark(benchmark::BenchmarkArgs),
+
     /// Record from microphone and transcribe
     #[cfg(feature = "record")]
     #[command(
         long_about = "\
-Record audio from the default microphone and transcribe it.
+Record audio from the default microphone and transcribe it. With \
+--source system (or the capture_source setting) it records what the \
+machine is playing instead, such as a call or a video: on Windows the \
+default output device, elsewhere a loopback input device like BlackHole \
+(macOS) or a PulseAudio/PipeWire monitor (Linux).
 
 Recording continues until you press Ctrl+C, or until --duration seconds \
-have elapsed. The captured audio is then transcribed using the selected model.
+have elapsed. With --stop-on-silence (or the silence_stop_secs setting) it \
+also stops once you have been quiet that long after speaking. The captured \
+audio is then transcribed using the selected model.
+
+While recording, a level meter and the elapsed time are shown on stderr \
+(when it is a terminal; --quiet hides them), and a warning is printed if \
+the input stays silent for the first seconds, as from a muted microphone. \
+A recording in which no speech is detected fails with an error rather \
+than printing an empty transcript.
 
 Use --output to save the raw audio as a WAV file without transcribing \
 (useful for capturing audio to process later with 'sagascript transcribe').
@@ -197,17 +341,66 @@ EXAMPLES:
   # Record for 10 seconds in Norwegian
   sagascript record --duration 10 --language no
 
+  # Stop on its own after 3 seconds of silence
+  sagascript record --stop-on-silence 3
+
+  # Dictate with the language and model of the 'Svenska' profile
+  sagascript record --profile Svenska
+
   # Save raw audio without transcribing
   sagascript record --output capture.wav
 
+  # Save a compact Ogg Opus file instead (~10x smaller; --features opus builds)
+  sagascript record --output capture.opus
+
   # Record, transcribe, and copy to clipboard
   sagascript record --clipboard
 
+  # Record and send the result to the configured output sinks
+  sagascript record --route
+
+  # Try a model or replacement rules without copying or routing the result
+  sagascript record --clipboard --route --dry-run
+
   # Record with JSON output
-  sagascript record --duration 5 --json"
+  sagascript record --duration 5 --json
+
+  # Hear yourself on headphones while recording, at 80% volume
+  sagascript record --monitor --monitor-volume 0.8
+
+  # Record from a specific microphone [see: sagascript list-devices]
+  sagascript record --device 'USB Audio Device'
+
+  # Transcribe the call or video playing on this machine
+  sagascript record --source system"
     )]
     Record(record::RecordArgs),
 
+    /// List audio input devices
+    #[cfg(feature = "record")]
+    #[command(
+        long_about = "\
+List the audio input devices 'sagascript record --device' can record \
+from, with each one's default sample rate and channel count. The system's \
+default input, which recordings use unless --device says otherwise, is \
+marked in the DEFAULT column.
+
+A device that won't report its configuration shows — for rate and \
+channels; recording from it usually fails too, which makes this a quick \
+first check when capture misbehaves.",
+        after_long_help = "\
+EXAMPLES:
+  # Which microphones are there, and which is the default?
+  sagascript list-devices
+
+  # Just the device names
+  sagascript list-devices --columns name --no-header
+
+  # Name of the default input, for scripts
+  sagascript list-devices --json | jq -r '.[] | select(.is_default) | .name'"
+    )]
+    ListDevices(devices::ListDevicesArgs),
+
     /// List available whisper models
     #[command(
         long_about = "\
@@ -217,7 +410,15 @@ Models are organized by language. English uses OpenAI Whisper models, \
 Swedish uses KBLab models, and Norwegian uses NbAiLab models. \
 Use --language to filter the list.
 
-The DOWNLOADED column shows whether each model is already available locally.",
+The DOWNLOADED column shows whether each model is already available locally. \
+--json prints the same catalog the GUI uses, one entry per model. \
+--porcelain prints one stable 'model' record per model (id, languages, \
+size in MB, downloaded, recommended, active) in the format described \
+under 'transcribe --help'.
+
+The table fits the terminal width, cutting long cells with '…'. Use \
+--columns to pick columns (id, name, size, downloaded, language), \
+--no-header to drop headers, and --width 0 to never cut.",
         after_long_help = "\
 EXAMPLES:
   # List all models
@@ -227,7 +428,16 @@ EXAMPLES:
   sagascript list-models --language sv
 
   # List English models
-  sagascript list-models --language en"
+  sagascript list-models --language en
+
+  # Downloaded models no larger than 200 MB
+  sagascript list-models --downloaded --size-max 200
+
+  # Just the IDs of downloaded models
+  sagascript list-models --downloaded --columns id --no-header
+
+  # Machine-readable metadata for scripts
+  sagascript list-models --json | jq -r '.[] | select(.recommended) | .id'"
     )]
     ListModels(models::ListModelsArgs),
 
@@ -239,6 +449,17 @@ Download a Whisper model from HuggingFace to the local model directory.
 Models are stored in ~/.sagascript/models/. If the model is already \
 downloaded, prints its path and exits without re-downloading.
 
+With --all or --recommended, downloads every model (or the recommended \
+one) for --language, skipping those already present, with one combined \
+progress line. The VAD model is fetched too when VAD is enabled. The \
+recommendation depends on this machine: the language's base model on a \
+typical laptop, its small model with a GPU, 16 GB of memory and 8 cores, \
+and its tiny model without a GPU on 8 GB or fewer than 4 cores.
+
+--setup (with --recommended) does what the app's onboarding does: it also \
+saves the language with the recommended model as the configured one \
+and checks that the model loads.
+
 A progress indicator shows download progress. On success, prints the \
 path to the downloaded model file on stdout.",
         after_long_help = "\
@@ -252,6 +473,15 @@ EXAMPLES:
   # Download and verify
   sagascript download-model nb-whisper-small && echo 'Done!'
 
+  # Provision every Swedish model before going offline
+  sagascript download-model --language sv --all
+
+  # Just the recommended model for the configured language
+  sagascript download-model --recommended
+
+  # Set up for Norwegian in one step, like the app's onboarding
+  sagascript download-model --language no --recommended --setup
+
 AVAILABLE MODELS:
   English:    tiny.en, base.en
   Swedish:    kb-whisper-tiny, kb-whisper-base, kb-whisper-small
@@ -270,7 +500,8 @@ DIARIZATION MODELS (requires --features diarization):
         long_about = "\
 Delete a previously downloaded Whisper model from disk.
 
-Frees up disk space by removing the model file. The model can be \
+Frees up disk space by removing the model file and its CoreML encoder. \
+The model in use is kept unless --force is given. The model can be \
 re-downloaded later with 'sagascript download-model'.",
         after_long_help = "\
 EXAMPLES:
@@ -282,6 +513,303 @@ EXAMPLES:
     )]
     DeleteModel(models::DeleteModelArgs),
 
+    /// Register local GGML files as models; show and prune disk usage; set
+    /// download URLs
+    #[command(
+        long_about = "\
+Register local GGML model files under a name, so models that aren't in \
+the catalog (a fine-tune converted with whisper.cpp, a different \
+quantisation) can be used.
+
+A registered model is used with '--model NAME' on transcribe and record, \
+or for everything, dictations included, with 'sagascript config set \
+custom_model NAME' (empty goes back to the built-in models). The file \
+stays where it is; Sagascript only checks that it is a GGML file, since \
+there is no published checksum to verify it against. Decoding defaults \
+come from the built-in model that would otherwise be used.
+
+'models usage' lists the downloaded built-in models with their size on \
+disk and when each was last loaded for a dictation or transcription. \
+'models prune' deletes the ones neither the settings nor a profile use; \
+with --keep-recommended it also keeps the recommended model of each \
+configured language. Models in the shared models directory are never \
+deleted.
+
+'models url' sets a URL to download a model from before any other source, \
+for networks that block huggingface.co: a local artifact server or a \
+mirror with its own layout (see also 'config set model_mirror'). The file \
+is still checked against the model's pinned SHA-256.",
+        after_long_help = "\
+EXAMPLES:
+  # Register a fine-tune and use it for everything
+  sagascript models add ./my-finetune.bin --name my-model --use
+
+  # See what the models take up, then free what isn't needed
+  sagascript models usage
+  sagascript models prune --keep-recommended --dry-run
+
+  # Download base.en from an internal artifact server
+  sagascript models url base.en https://artifacts.corp/whisper/ggml-base.en.bin
+
+  # Transcribe one file with it
+  sagascript transcribe meeting.m4a --model my-model
+
+  # Back to the built-in models, then forget it
+  sagascript config set custom_model ''
+  sagascript models remove my-model"
+    )]
+    Models(models::ModelsArgs),
+
+    /// Re-verify downloaded models and check HuggingFace for newer revisions
+    #[command(
+        long_about = "\
+Bring downloaded models up to date and report newer upstream revisions.
+
+Each downloaded model is hashed against the revision this Sagascript \
+build pins; a file from an older pin, or a damaged one, is downloaded \
+again. Then HuggingFace is asked what each model's main branch serves \
+now. Newer upstream revisions are reported but not installed: models are \
+only installed from checksummed revisions, which ship with Sagascript \
+updates. The revision of every installed model is recorded in \
+model-revisions.json in the models directory.
+
+The app can run the same check once a day in the background; enable it \
+with 'sagascript config set model_update_check true'.",
+        after_long_help = "\
+EXAMPLES:
+  # Re-verify models and check for newer revisions
+  sagascript update-models
+
+  # Only check, without hashing or downloading anything
+  sagascript update-models --check"
+    )]
+    UpdateModels(models::UpdateModelsArgs),
+
+    /// Check downloaded models against their pinned checksums
+    #[command(
+        long_about = "\
+Hash each downloaded model file and compare it with the SHA-256 and size \
+of the revision it was downloaded from.
+
+A truncated or damaged file otherwise only shows up as a failed model \
+load at the next dictation. Nothing is changed: download a model that \
+fails again with 'sagascript download-model ID', which replaces the file. \
+Exits with an error if any file fails.",
+        after_long_help = "\
+EXAMPLES:
+  # Verify every downloaded model
+  sagascript verify-models
+
+  # Verify one model
+  sagascript verify-models kb-whisper-small"
+    )]
+    VerifyModels(models::VerifyModelsArgs),
+
+    /// Update this standalone CLI binary to the latest release
+    #[command(
+        long_about = "\
+Replace this sagascript binary with the latest release's CLI build.
+
+The release's binary for this platform is downloaded, its size and \
+SHA-256 are checked against the release's SHA256SUMS, and on macOS it \
+must carry a valid Developer ID signature from the Sagascript release \
+team. Only then is it moved over the running binary; on any failure the \
+installed binary is left as it was.
+
+Only standalone CLI installs are updated. The binary inside the app \
+(including a link to it made with 'sagascript install-cli') updates with \
+the app, a 'cargo install' build with cargo, and a Homebrew install with \
+'brew upgrade'. With --check, prints the newer version on stdout, if \
+there is one, and changes nothing.",
+        after_long_help = "\
+EXAMPLES:
+  # See whether a newer release exists
+  sagascript self-update --check
+
+  # Update a binary in a root-owned directory
+  sudo sagascript self-update"
+    )]
+    SelfUpdate(self_update::SelfUpdateArgs),
+
+    /// Export or import an offline bundle of downloaded models
+    #[command(
+        long_about = "\
+Pack downloaded models into a tar archive, or install them from one, for \
+machines that cannot reach HuggingFace.
+
+'export' verifies each model before packing it and writes a manifest as \
+the first entry. 'import' only accepts files Sagascript itself would \
+download and checks every one against the built-in checksums before \
+installing it, so a bundle from an untrusted source cannot plant a \
+modified model. Models already present are left alone.",
+        after_long_help = "\
+EXAMPLES:
+  # Bundle every downloaded model (plus the VAD model, if present)
+  sagascript bundle export models.tar
+
+  # Only the Swedish models
+  sagascript bundle export sv-models.tar --language sv
+
+  # Pick models explicitly
+  sagascript bundle export models.tar -m kb-whisper-base -m base.en
+
+  # On the offline machine
+  sagascript bundle import models.tar"
+    )]
+    Bundle(bundle::BundleArgs),
+
+    /// Show recent dictation sessions and how they ended
+    #[command(
+        long_about = "\
+List the app's recent dictation sessions, newest first, with how long each \
+recorded, how long it took end to end, the speaking pace in words per \
+minute, the model used, and how it ended: \
+completed, empty (no speech recognised), failed, cancelled, or unfinished \
+(the app quit mid-session).
+
+Failed sessions show the error, which is usually the quickest way to find \
+out why a dictation produced nothing. The timeline is rebuilt from the \
+app's structured log, so it covers the last few megabytes of activity. \
+The average pace of the completed sessions shown is printed at the end.",
+        after_long_help = "\
+EXAMPLES:
+  # The last 20 sessions
+  sagascript sessions
+
+  # Only the ones that went wrong
+  sagascript sessions --failed
+
+  # Machine-readable, as shown in the settings timeline
+  sagascript sessions --json -n 100"
+    )]
+    Sessions(sessions::SessionsArgs),
+
+    /// List, search and export past transcriptions
+    #[command(
+        long_about = "\
+Every transcription the app finishes — hotkey dictations and files from the \
+Transcribe tab — and those of 'sagascript record' and 'sagascript \
+transcribe' (unless --no-history) is kept in a history on this machine, \
+with where it came from, the input device it was recorded from, when it \
+finished, how long the audio was, the model and language, and the text.
+
+'list' shows the most recent ones and 'search' those whose text or file \
+name contains a query; --source keeps those from one source. 'show' prints one in full. 'export' writes the \
+whole history, oldest first, as JSON or CSV. 'delete' and 'clear' remove \
+entries for good. Times are in UTC.",
+        after_long_help = "\
+EXAMPLES:
+  # What did I dictate recently?
+  sagascript history list
+
+  # Find yesterday's note about the budget
+  sagascript history search budget
+
+  # Only what came from the command line
+  sagascript history list --source cli
+
+  # Print it in full, or copy it
+  sagascript history show h-1a2b3c4d
+  sagascript history show h-1a2b3c4d --json
+
+  # Everything, for a spreadsheet
+  sagascript history export --format csv -o history.csv"
+    )]
+    History(history::HistoryArgs),
+
+    /// Show or discard the dictation a crash left behind
+    #[command(
+        long_about = "\
+While the app records or transcribes a dictation it keeps a copy of the \
+audio. If the app crashes, the copy survives; the next launch offers to \
+transcribe it again from the main window. This command shows that \
+interrupted dictation: when it stopped, how much audio was saved, and the \
+language and model it was using.
+
+--path prints just the saved audio file, to transcribe it with \
+'sagascript transcribe'. --discard deletes it.",
+        after_long_help = "\
+EXAMPLES:
+  # What was interrupted?
+  sagascript recover
+
+  # Transcribe it from the terminal
+  sagascript transcribe \"$(sagascript recover --path)\"
+
+  # Throw it away
+  sagascript recover --discard"
+    )]
+    Recover(recover::RecoverArgs),
+
+    /// Show the whisper.cpp build, backends, threads and model in use
+    #[command(
+        long_about = "\
+Report what the transcription engine is running with: the whisper-rs and \
+whisper.cpp (whisper-rs-sys) versions, the compute backends compiled in \
+(Metal, CoreML, CPU), the CPU instruction sets used (AVX, NEON, ...), the \
+number of CPU threads given to each inference, and the model that would \
+be loaded, with its path and size on disk.
+
+Include this output when reporting that transcription is slow.",
+        after_long_help = "\
+EXAMPLES:
+  # The configured model
+  sagascript runtime
+
+  # A specific model, as JSON
+  sagascript runtime -m small.en --json"
+    )]
+    Runtime(runtime::RuntimeArgs),
+
+    /// Write a redacted diagnostics archive to attach to a bug report
+    #[command(
+        long_about = "\
+Gather what a bug report needs into one zip archive: platform and \
+whisper.cpp runtime details, the settings, the installed models, the \
+recent dictation sessions with their errors, and the app's logs.
+
+Nothing you said goes in. Transcripts and history are not included, \
+settings that hold your words (prompts, keywords, replacement rules) only \
+say whether they are set, output sinks keep only their type, and paths \
+under your home directory and URLs are shortened. API keys and passwords \
+live in the credential store, which is not read. The archive is plain \
+files: unzip it to check before you send it.",
+        after_long_help = "\
+EXAMPLES:
+  # Write the bundle, then look inside
+  sagascript support-bundle ~/Desktop/sagascript-support.zip
+  unzip -l ~/Desktop/sagascript-support.zip"
+    )]
+    SupportBundle(support_bundle::SupportBundleArgs),
+
+    /// Show, clear or send the opt-in telemetry queue
+    #[command(
+        long_about = "\
+Inspect and manage the local telemetry queue.
+
+Telemetry is off unless you turn it on ('sagascript config set telemetry \
+true'). When on, the app and the CLI keep per-day counts of dictations by \
+outcome, the models used, file transcriptions and error kinds. Never \
+transcript text, file names or audio. The counts stay on this machine \
+until you run 'sagascript telemetry send', which uploads exactly what \
+'telemetry show --json' prints to the 'telemetry_endpoint' setting.",
+        after_long_help = "\
+EXAMPLES:
+  # See what has been counted
+  sagascript telemetry show
+
+  # The exact payload 'send' would upload
+  sagascript telemetry show --json
+
+  # Throw the queue away
+  sagascript telemetry clear
+
+  # Upload it
+  sagascript config set telemetry_endpoint https://example.org/sagascript
+  sagascript telemetry send"
+    )]
+    Telemetry(telemetry::TelemetryArgs),
+
     /// Reset first-launch onboarding (re-run setup wizard on next launch)
     #[command(
         long_about = "\
@@ -291,7 +819,7 @@ Useful for testing or if you want to re-configure language and permissions."
     )]
     ResetOnboarding,
 
-    /// Manage settings (list, get, set, reset, path)
+    /// Manage settings (list, get, set, reset, path, prompt-history, model-override)
     #[command(
         long_about = "\
 View and modify Sagascript settings. Settings are persisted to a JSON file \
@@ -302,9 +830,12 @@ Available setting keys:
   whisper_model      Whisper model ID (e.g. base.en, kb-whisper-base)
   hotkey_mode        Hotkey behavior: push (push-to-talk) or toggle
   show_overlay       Show recording overlay (true/false)
-  auto_paste         Auto-paste transcription result (true/false)
+  auto_paste         Auto-paste transcription result (true/false; the
+                     paste entry in output_sinks)
   auto_select_model  Auto-select best model for language (true/false)
-  hotkey             Global hotkey shortcut (e.g. Control+Shift+Space)",
+  hotkey             Global hotkey shortcut (e.g. Control+Shift+Space)
+  toggle_hotkey      Second hotkey that always toggles, alongside a
+                     push-to-talk hotkey (empty: none)",
         after_long_help = "\
 EXAMPLES:
   # Show all settings with current and default values
@@ -319,6 +850,10 @@ EXAMPLES:
   # Change the global hotkey
   sagascript config set hotkey 'Option+Space'
 
+  # Hold Option+Space for short phrases, tap Option+Shift+Space for long ones
+  sagascript config set hotkey_mode push
+  sagascript config set toggle_hotkey 'Option+Shift+Space'
+
   # Reset a single setting to its default
   sagascript config reset language
 
@@ -326,7 +861,13 @@ EXAMPLES:
   sagascript config reset
 
   # Print the settings file path (for manual editing)
-  sagascript config path"
+  sagascript config path
+
+  # List recently used initial prompts
+  sagascript config prompt-history
+
+  # Keep more quiet speech on small.en
+  sagascript config model-override small.en --no-speech-threshold 0.5"
     )]
     Config(config::ConfigArgs),
 
@@ -339,6 +880,55 @@ subcommand and the GUI file-drop feature."
     )]
     Formats,
 
+    /// Print the events the app sends, with their payloads
+    #[command(
+        long_about = "\
+Print the schema of the events the app sends its windows and the event \
+stream (event_stream_port): the schema version, each event's name and the \
+TypeScript type of its payload, as JSON. The version goes up when an event \
+is renamed or removed or a payload changes shape, so a tool can check it \
+before relying on the events. The running app serves the same JSON at \
+GET /events/schema, to clients sending its event stream token.
+
+With --typescript, print the definitions in src/lib/events.ts instead.",
+        after_long_help = "\
+EXAMPLES:
+  # The schema version
+  sagascript event-schema | jq .version
+
+  # Regenerate the frontend's definitions
+  sagascript event-schema --typescript > src/lib/events.ts"
+    )]
+    EventSchema {
+        /// Print TypeScript definitions instead of JSON
+        #[arg(long)]
+        typescript: bool,
+    },
+
+    /// Link this binary onto PATH as `sagascript`
+    #[command(
+        long_about = "\
+Symlink this binary as 'sagascript' in /usr/local/bin, or in ~/.local/bin \
+when /usr/local/bin isn't writable, so a GUI install gets the command on \
+PATH. The link points into the app, so app upgrades need no reinstall. An \
+existing link is replaced; an existing regular file is left alone.
+
+Run it by full path the first time. Prints the link's path on success. \
+Not available on Windows.",
+        after_long_help = "\
+EXAMPLES:
+  # From the macOS app bundle
+  /Applications/Sagascript.app/Contents/MacOS/sagascript install-cli
+
+  # Into a directory of your choice
+  ./sagascript install-cli --dir ~/bin"
+    )]
+    InstallCli {
+        /// Directory to link into (default: /usr/local/bin, else ~/.local/bin)
+        #[arg(short, long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+    },
+
     /// Generate shell completions
     #[command(
         long_about = "\
@@ -391,6 +981,19 @@ EXAMPLES:
     },
 }
 
+impl Command {
+    /// Whether `--porcelain` was given, so a failure is reported as an
+    /// `error` record rather than for humans.
+    fn porcelain(&self) -> bool {
+        match self {
+            Command::Transcribe(args) => args.porcelain.is_some(),
+            Command::ListModels(args) => args.porcelain.is_some(),
+            Command::Config(args) => args.action.porcelain().is_some(),
+            _ => false,
+        }
+    }
+}
+
 /// Try to parse CLI args. Returns Some(Cli) if a subcommand was given, None for bare invocation (GUI mode).
 pub fn try_parse() -> Option<Cli> {
     let cli = Cli::parse();
@@ -404,14 +1007,24 @@ pub fn try_parse() -> Option<Cli> {
 /// Run the CLI subcommand. Blocks until complete, then exits.
 pub fn run(cli: Cli) {
     let rt = tokio::runtime::Runtime::new().expect("failed to create tokio runtime");
+    let porcelain = cli.command.as_ref().is_some_and(Command::porcelain);
+    sagascript_core::telemetry::set_enabled(sagascript_core::settings::store::load().telemetry);
 
     let result = match cli.command.unwrap() {
         Command::Transcribe(args) => transcribe::run(args),
+        Command::Compare(args) => compare::run(args),
+        Command::Benchmark(args) => benchmark::run(args),
         #[cfg(feature = "record")]
-        Command::Record(args) => record::run(args),
+        Command::Record(args) => record::run(args, &rt),
+        #[cfg(feature = "record")]
+        Command::ListDevices(args) => devices::list(args),
         Command::ListModels(args) => models::list(args),
         Command::DownloadModel(args) => rt.block_on(models::download(args)),
         Command::DeleteModel(args) => models::delete(args),
+        Command::Models(args) => models::run(args),
+        Command::UpdateModels(args) => rt.block_on(models::update(args)),
+        Command::VerifyModels(args) => models::verify(args),
+        Command::SelfUpdate(args) => rt.block_on(self_update::run(args)),
         Command::ResetOnboarding => {
             sagascript_core::settings::store::update(|settings| {
                 settings.has_completed_onboarding = false;
@@ -421,11 +1034,23 @@ pub fn run(cli: Cli) {
                     eprintln!("Onboarding reset. The setup wizard will run on next launch.");
                 })
         }
+        Command::Bundle(args) => bundle::run(args),
+        Command::Sessions(args) => sessions::run(args),
+        Command::History(args) => history::run(args),
+        Command::Recover(args) => recover::run(args),
+        Command::Runtime(args) => runtime::run(args),
+        Command::SupportBundle(args) => support_bundle::run(args),
+        Command::Telemetry(args) => rt.block_on(telemetry::run(args)),
         Command::Config(args) => config::run(args),
         Command::Formats => {
             formats();
             Ok(())
         }
+        Command::EventSchema { typescript } => {
+            event_schema(typescript);
+            Ok(())
+        }
+        Command::InstallCli { dir } => install_cli(dir),
         Command::Completions { shell } => {
             generate_completions(shell);
             Ok(())
@@ -434,7 +1059,15 @@ pub fn run(cli: Cli) {
     };
 
     if let Err(e) = result {
-        eprintln!("Error: {e}");
+        sagascript_core::telemetry::record(sagascript_core::telemetry::TelemetryEvent::Error {
+            kind: e.kind(),
+        });
+        if porcelain {
+            eprintln!("{}", porcelain::error_record(&e));
+        } else {
+            eprintln!("Error: {e}");
+            eprintln!("Hint: {}", e.suggestion());
+        }
         std::process::exit(1);
     }
 }
@@ -448,6 +1081,49 @@ fn formats() {
     }
 }
 
+fn event_schema(typescript: bool) {
+    use sagascript_core::events;
+
+    if typescript {
+        print!("{}", events::typescript());
+    } else {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&events::schema()).unwrap()
+        );
+    }
+}
+
+fn install_cli(dir: Option<PathBuf>) -> Result<(), sagascript_core::error::DictationError> {
+    use sagascript_core::cli_install::{self, CliInstall};
+    use sagascript_core::error::DictationError;
+
+    let target = cli_install::app_binary()
+        .map_err(|e| DictationError::InstallError(format!("can't locate this binary: {e}")))?;
+    let installed = match dir {
+        Some(dir) => cli_install::link_into(&dir, &target)
+            .map(|link| CliInstall::new(link, &target))
+            .map_err(|e| DictationError::InstallError(e.to_string()))?,
+        None => cli_install::install(&target)?,
+    };
+
+    eprintln!(
+        "Linked {} -> {}",
+        installed.link.display(),
+        installed.target.display()
+    );
+    if !installed.on_path {
+        if let Some(dir) = installed.link.parent() {
+            eprintln!(
+                "Note: {} is not on your PATH; add it in your shell profile.",
+                dir.display()
+            );
+        }
+    }
+    println!("{}", installed.link.display());
+    Ok(())
+}
+
 fn generate_completions<G: Generator>(gen: G) {
     clap_complete::generate(gen, &mut Cli::command(), "sagascript", &mut io::stdout());
 }
@@ -730,16 +1406,88 @@ mod tests {
         let cli = Cli::try_parse_from(["sagascript", "transcribe", "file.wav"]).unwrap();
         match cli.command.unwrap() {
             Command::Transcribe(args) => {
-                assert_eq!(args.file, PathBuf::from("file.wav"));
+                assert_eq!(args.files, [PathBuf::from("file.wav")]);
+                assert_eq!(args.jobs, 1);
+                assert_eq!(args.max_load, 1.5);
                 assert!(args.language.is_none());
                 assert!(args.model.is_none());
                 assert!(!args.json);
                 assert!(!args.clipboard);
+                assert!(!args.route);
             }
             other => panic!("expected Transcribe, got {:?}", std::mem::discriminant(&other)),
         }
     }
 
+    #[test]
+    fn parse_transcribe_batch() {
+        let cli = Cli::try_parse_from([
+            "sagascript", "transcribe", "a.wav", "b.m4a", "--jobs", "2", "--output-dir", "out",
+            "--max-load", "0",
+        ]).unwrap();
+        match cli.command.unwrap() {
+            Command::Transcribe(args) => {
+                assert_eq!(args.files, [PathBuf::from("a.wav"), PathBuf::from("b.m4a")]);
+                assert_eq!(args.jobs, 2);
+                assert_eq!(args.max_load, 0.0);
+                assert_eq!(args.output_dir, Some(PathBuf::from("out")));
+            }
+            _ => panic!("expected Transcribe"),
+        }
+        assert!(Cli::try_parse_from(["sagascript", "transcribe"]).is_err());
+        assert!(Cli::try_parse_from(["sagascript", "transcribe", "a.wav", "--jobs", "0"]).is_err());
+    }
+
+    #[test]
+    fn parse_transcribe_no_config() {
+        let cli = Cli::try_parse_from(["sagascript", "transcribe", "a.wav", "--no-config"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Transcribe(args) => assert!(args.no_config),
+            _ => panic!("expected Transcribe"),
+        }
+        // Routing needs the configured sinks, which --no-config ignores.
+        assert!(
+            Cli::try_parse_from(["sagascript", "transcribe", "a.wav", "--no-config", "--route"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn parse_transcribe_ffmpeg() {
+        let cli = Cli::try_parse_from(["sagascript", "transcribe", "call.webm", "--ffmpeg"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Transcribe(args) => assert!(args.ffmpeg),
+            _ => panic!("expected Transcribe"),
+        }
+    }
+
+    #[test]
+    fn parse_transcribe_split_channels() {
+        let cli =
+            Cli::try_parse_from(["sagascript", "transcribe", "call.wav", "--split-channels"])
+                .unwrap();
+        match cli.command.unwrap() {
+            Command::Transcribe(args) => assert!(args.split_channels),
+            _ => panic!("expected Transcribe"),
+        }
+    }
+
+    #[test]
+    fn parse_transcribe_minutes() {
+        let cli = Cli::try_parse_from([
+            "sagascript", "transcribe", "call.wav", "--split-channels", "--minutes",
+        ])
+        .unwrap();
+        match cli.command.unwrap() {
+            Command::Transcribe(args) => assert!(args.minutes),
+            _ => panic!("expected Transcribe"),
+        }
+        assert!(Cli::try_parse_from([
+            "sagascript", "transcribe", "call.wav", "--minutes", "--json",
+        ])
+        .is_err());
+    }
+
     #[test]
     fn parse_transcribe_all_flags() {
         let cli = Cli::try_parse_from([
@@ -752,7 +1500,7 @@ mod tests {
         ]).unwrap();
         match cli.command.unwrap() {
             Command::Transcribe(args) => {
-                assert_eq!(args.file, PathBuf::from("meeting.mp3"));
+                assert_eq!(args.files, [PathBuf::from("meeting.mp3")]);
                 assert_eq!(args.language.as_deref(), Some("sv"));
                 assert_eq!(args.model.as_deref(), Some("kb-whisper-base"));
                 assert!(args.json);
@@ -829,8 +1577,10 @@ mod tests {
                 assert!(args.output.is_none());
                 assert!(!args.json);
                 assert!(!args.clipboard);
+                assert!(!args.dry_run);
                 assert!(args.prompt.is_none());
                 assert!(args.prompt_file.is_none());
+                assert!(!args.quiet);
             }
             _ => panic!("expected Record"),
         }
@@ -838,62 +1588,591 @@ mod tests {
 
     #[cfg(feature = "record")]
     #[test]
-    fn parse_record_all_flags() {
+    fn parse_record_monitor() {
         let cli = Cli::try_parse_from([
-            "sagascript", "record",
-            "--language", "no",
-            "--model", "nb-whisper-base",
-            "--duration", "30.5",
-            "--output", "capture.wav",
-            "--json",
-            "--clipboard",
-            "--hint", "Notre Dame, Sara",
+            "sagascript", "record", "--monitor", "--monitor-volume", "0.8",
         ]).unwrap();
         match cli.command.unwrap() {
             Command::Record(args) => {
-                assert_eq!(args.language.as_deref(), Some("no"));
-                assert_eq!(args.model.as_deref(), Some("nb-whisper-base"));
-                assert!((args.duration.unwrap() - 30.5).abs() < f64::EPSILON);
-                assert_eq!(args.output.as_deref(), Some("capture.wav"));
-                assert!(args.json);
-                assert!(args.clipboard);
-                // --hint populates the same `prompt` field as --prompt.
-                assert_eq!(args.prompt.as_deref(), Some("Notre Dame, Sara"));
+                assert!(args.monitor);
+                assert_eq!(args.monitor_volume, Some(0.8));
             }
             _ => panic!("expected Record"),
         }
+        assert!(Cli::try_parse_from(["sagascript", "record", "--monitor-volume", "3"]).is_err());
+        assert!(Cli::try_parse_from(["sagascript", "record", "--monitor", "--no-monitor"]).is_err());
     }
 
+    #[cfg(feature = "record")]
     #[test]
-    fn parse_list_models_with_language() {
-        let cli = Cli::try_parse_from(["sagascript", "list-models", "-l", "sv"]).unwrap();
+    fn parse_record_stop_on_silence() {
+        let cli = Cli::try_parse_from(["sagascript", "record", "--stop-on-silence", "3"]).unwrap();
         match cli.command.unwrap() {
-            Command::ListModels(args) => {
-                assert_eq!(args.language.as_deref(), Some("sv"));
-            }
-            _ => panic!("expected ListModels"),
+            Command::Record(args) => assert_eq!(args.stop_on_silence, Some(3)),
+            _ => panic!("expected Record"),
         }
+        assert!(Cli::try_parse_from(["sagascript", "record", "--stop-on-silence", "61"]).is_err());
     }
 
+    #[cfg(feature = "record")]
     #[test]
-    fn parse_download_model() {
-        let cli = Cli::try_parse_from(["sagascript", "download-model", "base.en"]).unwrap();
+    fn parse_record_profile() {
+        let cli = Cli::try_parse_from(["sagascript", "record", "--profile", "Svenska"]).unwrap();
         match cli.command.unwrap() {
-            Command::DownloadModel(args) => {
-                assert_eq!(args.model, "base.en");
-            }
-            _ => panic!("expected DownloadModel"),
+            Command::Record(args) => assert_eq!(args.profile.as_deref(), Some("Svenska")),
+            _ => panic!("expected Record"),
         }
     }
 
+    #[cfg(feature = "record")]
     #[test]
-    fn parse_config_set() {
-        let cli = Cli::try_parse_from(["sagascript", "config", "set", "language", "sv"]).unwrap();
+    fn parse_record_device() {
+        let cli = Cli::try_parse_from(["sagascript", "record", "--device", "USB Mic"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Record(args) => assert_eq!(args.device.as_deref(), Some("USB Mic")),
+            _ => panic!("expected Record"),
+        }
+    }
+
+    #[cfg(feature = "record")]
+    #[test]
+    fn parse_record_source() {
+        use sagascript_core::settings::CaptureSource;
+
+        let cli = Cli::try_parse_from(["sagascript", "record", "--source", "system"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Record(args) => assert_eq!(args.source, Some(CaptureSource::System)),
+            _ => panic!("expected Record"),
+        }
+        assert!(Cli::try_parse_from(["sagascript", "record", "--source", "speakers"]).is_err());
+        assert!(Cli::try_parse_from([
+            "sagascript", "record", "--source", "system", "--device", "USB Mic",
+        ]).is_err());
+    }
+
+    #[cfg(feature = "record")]
+    #[test]
+    fn parse_list_devices() {
+        let cli = Cli::try_parse_from([
+            "sagascript", "list-devices", "--columns", "name", "--no-header",
+        ]).unwrap();
+        match cli.command.unwrap() {
+            Command::ListDevices(args) => {
+                assert!(!args.json);
+                assert_eq!(args.table.columns, ["name"]);
+                assert!(args.table.no_header);
+            }
+            _ => panic!("expected ListDevices"),
+        }
+    }
+
+    #[test]
+    fn parse_config_profile() {
+        let args = "sagascript config profile Svenska --language sv --hotkey Alt+Shift+S \
+                    --overlay-color #ffcd00 --sound high";
+        let cli = Cli::try_parse_from(args.split_whitespace()).unwrap();
         match cli.command.unwrap() {
             Command::Config(args) => match args.action {
-                config::ConfigAction::Set { key, value } => {
-                    assert_eq!(key, "language");
-                    assert_eq!(value, "sv");
+                config::ConfigAction::Profile {
+                    name,
+                    language,
+                    hotkey,
+                    sound,
+                    remove,
+                    ..
+                } => {
+                    assert_eq!(name.as_deref(), Some("Svenska"));
+                    assert_eq!(language.as_deref(), Some("sv"));
+                    assert_eq!(hotkey.as_deref(), Some("Alt+Shift+S"));
+                    assert_eq!(sound.as_deref(), Some("high"));
+                    assert!(!remove);
+                }
+                _ => panic!("expected ConfigAction::Profile"),
+            },
+            _ => panic!("expected Config"),
+        }
+        // Flags need a profile name, and --remove takes no others.
+        let args = "sagascript config profile --language sv";
+        assert!(Cli::try_parse_from(args.split_whitespace()).is_err());
+        let args = "sagascript config profile x --remove --sound low";
+        assert!(Cli::try_parse_from(args.split_whitespace()).is_err());
+    }
+
+    #[cfg(feature = "record")]
+    #[test]
+    fn parse_record_all_flags() {
+        let cli = Cli::try_parse_from([
+            "sagascript", "record",
+            "--language", "no",
+            "--model", "nb-whisper-base",
+            "--duration", "30.5",
+            "--output", "capture.wav",
+            "--json",
+            "--clipboard",
+            "--dry-run",
+            "--hint", "Notre Dame, Sara",
+            "--quiet",
+        ]).unwrap();
+        match cli.command.unwrap() {
+            Command::Record(args) => {
+                assert_eq!(args.language.as_deref(), Some("no"));
+                assert_eq!(args.model.as_deref(), Some("nb-whisper-base"));
+                assert!((args.duration.unwrap() - 30.5).abs() < f64::EPSILON);
+                assert_eq!(args.output.as_deref(), Some("capture.wav"));
+                assert!(args.json);
+                assert!(args.clipboard);
+                assert!(args.dry_run);
+                // --hint populates the same `prompt` field as --prompt.
+                assert_eq!(args.prompt.as_deref(), Some("Notre Dame, Sara"));
+                assert!(args.quiet);
+            }
+            _ => panic!("expected Record"),
+        }
+    }
+
+    #[test]
+    fn parse_list_models_with_language() {
+        let cli = Cli::try_parse_from(["sagascript", "list-models", "-l", "sv"]).unwrap();
+        match cli.command.unwrap() {
+            Command::ListModels(args) => {
+                assert_eq!(args.language.as_deref(), Some("sv"));
+            }
+            _ => panic!("expected ListModels"),
+        }
+    }
+
+    #[test]
+    fn parse_list_models_filters() {
+        let cli = Cli::try_parse_from([
+            "sagascript",
+            "list-models",
+            "--downloaded",
+            "--size-max",
+            "200",
+            "--json",
+        ])
+        .unwrap();
+        match cli.command.unwrap() {
+            Command::ListModels(args) => {
+                assert!(args.downloaded);
+                assert_eq!(args.size_max, Some(200));
+                assert!(args.json);
+            }
+            _ => panic!("expected ListModels"),
+        }
+    }
+
+    #[test]
+    fn parse_list_models_table_options() {
+        let cli = Cli::try_parse_from([
+            "sagascript",
+            "list-models",
+            "--no-header",
+            "--columns",
+            "id,size",
+            "--width",
+            "0",
+        ])
+        .unwrap();
+        match cli.command.unwrap() {
+            Command::ListModels(args) => {
+                assert!(args.table.no_header);
+                assert_eq!(args.table.columns, ["id", "size"]);
+                assert_eq!(args.table.width, Some(0));
+            }
+            _ => panic!("expected ListModels"),
+        }
+    }
+
+    #[test]
+    fn parse_download_model() {
+        let cli = Cli::try_parse_from(["sagascript", "download-model", "base.en"]).unwrap();
+        match cli.command.unwrap() {
+            Command::DownloadModel(args) => {
+                assert_eq!(args.model.as_deref(), Some("base.en"));
+                assert!(!args.all && !args.recommended);
+            }
+            _ => panic!("expected DownloadModel"),
+        }
+    }
+
+    #[test]
+    fn parse_download_model_all_for_language() {
+        let cli =
+            Cli::try_parse_from(["sagascript", "download-model", "--language", "sv", "--all"])
+                .unwrap();
+        match cli.command.unwrap() {
+            Command::DownloadModel(args) => {
+                assert_eq!(args.model, None);
+                assert_eq!(args.language.as_deref(), Some("sv"));
+                assert!(args.all);
+            }
+            _ => panic!("expected DownloadModel"),
+        }
+    }
+
+    #[test]
+    fn download_model_requires_model_or_bulk_flag() {
+        assert!(Cli::try_parse_from(["sagascript", "download-model"]).is_err());
+        assert!(
+            Cli::try_parse_from(["sagascript", "download-model", "--all", "--recommended"])
+                .is_err()
+        );
+        assert!(Cli::try_parse_from(["sagascript", "download-model", "base.en", "--all"]).is_err());
+    }
+
+    #[test]
+    fn download_model_setup_requires_recommended() {
+        let cli = Cli::try_parse_from([
+            "sagascript",
+            "download-model",
+            "--language",
+            "no",
+            "--recommended",
+            "--setup",
+        ])
+        .unwrap();
+        match cli.command.unwrap() {
+            Command::DownloadModel(args) => assert!(args.recommended && args.setup),
+            _ => panic!("expected DownloadModel"),
+        }
+        assert!(Cli::try_parse_from(["sagascript", "download-model", "--all", "--setup"]).is_err());
+    }
+
+    #[test]
+    fn parse_compare_splits_models() {
+        let cli = Cli::try_parse_from([
+            "sagascript",
+            "compare",
+            "talk.wav",
+            "--models",
+            "base.en,small.en",
+        ])
+        .unwrap();
+        match cli.command.unwrap() {
+            Command::Compare(args) => {
+                assert_eq!(args.file, PathBuf::from("talk.wav"));
+                assert_eq!(args.models, ["base.en", "small.en"]);
+            }
+            _ => panic!("expected Compare"),
+        }
+    }
+
+    #[test]
+    fn parse_history_source_filter() {
+        let cli =
+            Cli::try_parse_from(["sagascript", "history", "list", "--source", "cli"]).unwrap();
+        match cli.command.unwrap() {
+            Command::History(args) => assert!(matches!(
+                args.action,
+                history::HistoryAction::List {
+                    source: Some(sagascript_core::history::HistorySource::Cli),
+                    ..
+                }
+            )),
+            _ => panic!("expected History"),
+        }
+        assert!(Cli::try_parse_from(["sagascript", "history", "list", "--source", "api"]).is_err());
+    }
+
+    #[test]
+    fn parse_event_schema() {
+        let cli = Cli::try_parse_from(["sagascript", "event-schema", "--typescript"]).unwrap();
+        assert!(matches!(
+            cli.command.unwrap(),
+            Command::EventSchema { typescript: true }
+        ));
+    }
+
+    #[test]
+    fn parse_benchmark_defaults_to_the_bundled_clip() {
+        let cli = Cli::try_parse_from(["sagascript", "benchmark"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Benchmark(args) => {
+                assert!(args.file.is_none());
+                assert!(args.models.is_empty());
+            }
+            _ => panic!("expected Benchmark"),
+        }
+        let cli = Cli::try_parse_from([
+            "sagascript",
+            "benchmark",
+            "memo.m4a",
+            "--models",
+            "kb-whisper-base,kb-whisper-small",
+        ])
+        .unwrap();
+        match cli.command.unwrap() {
+            Command::Benchmark(args) => {
+                assert_eq!(args.file, Some(PathBuf::from("memo.m4a")));
+                assert_eq!(args.models, ["kb-whisper-base", "kb-whisper-small"]);
+            }
+            _ => panic!("expected Benchmark"),
+        }
+    }
+
+    #[test]
+    fn parse_runtime_with_model_json() {
+        let cli =
+            Cli::try_parse_from(["sagascript", "runtime", "-m", "small.en", "--json"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Runtime(args) => {
+                assert_eq!(args.model.as_deref(), Some("small.en"));
+                assert!(args.json);
+            }
+            _ => panic!("expected Runtime"),
+        }
+    }
+
+    #[test]
+    fn parse_support_bundle() {
+        let cli = Cli::try_parse_from(["sagascript", "support-bundle", "out.zip"]).unwrap();
+        match cli.command.unwrap() {
+            Command::SupportBundle(args) => assert_eq!(args.path, PathBuf::from("out.zip")),
+            _ => panic!("expected SupportBundle"),
+        }
+    }
+
+    #[test]
+    fn parse_install_cli_dir() {
+        let cli = Cli::try_parse_from(["sagascript", "install-cli", "--dir", "/tmp/bin"]).unwrap();
+        match cli.command.unwrap() {
+            Command::InstallCli { dir } => assert_eq!(dir, Some(PathBuf::from("/tmp/bin"))),
+            _ => panic!("expected InstallCli"),
+        }
+    }
+
+    #[test]
+    fn parse_self_update_check() {
+        let cli = Cli::try_parse_from(["sagascript", "self-update", "--check"]).unwrap();
+        match cli.command.unwrap() {
+            Command::SelfUpdate(args) => assert!(args.check),
+            _ => panic!("expected SelfUpdate"),
+        }
+    }
+
+    #[test]
+    fn parse_telemetry_show_json() {
+        let cli = Cli::try_parse_from(["sagascript", "telemetry", "show", "--json"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Telemetry(args) => {
+                assert!(matches!(
+                    args.action,
+                    telemetry::TelemetryAction::Show { json: true }
+                ))
+            }
+            _ => panic!("expected Telemetry"),
+        }
+    }
+
+    #[test]
+    fn parse_update_models_check() {
+        let cli = Cli::try_parse_from(["sagascript", "update-models", "--check"]).unwrap();
+        match cli.command.unwrap() {
+            Command::UpdateModels(args) => assert!(args.check),
+            _ => panic!("expected UpdateModels"),
+        }
+    }
+
+    #[test]
+    fn parse_verify_models() {
+        let cli = Cli::try_parse_from(["sagascript", "verify-models", "base.en"]).unwrap();
+        match cli.command.unwrap() {
+            Command::VerifyModels(args) => assert_eq!(args.model.as_deref(), Some("base.en")),
+            _ => panic!("expected VerifyModels"),
+        }
+    }
+
+    #[test]
+    fn parse_sessions_failed_with_limit() {
+        let cli = Cli::try_parse_from(["sagascript", "sessions", "--failed", "-n", "5"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Sessions(args) => {
+                assert!(args.failed);
+                assert_eq!(args.limit, 5);
+                assert!(!args.json);
+            }
+            _ => panic!("expected Sessions"),
+        }
+    }
+
+    #[test]
+    fn parse_history_search_and_export() {
+        let cli =
+            Cli::try_parse_from(["sagascript", "history", "search", "budget", "-n", "5"]).unwrap();
+        match cli.command.unwrap() {
+            Command::History(args) => match args.action {
+                history::HistoryAction::Search { query, limit, .. } => {
+                    assert_eq!(query, "budget");
+                    assert_eq!(limit, 5);
+                }
+                _ => panic!("expected history search"),
+            },
+            _ => panic!("expected History"),
+        }
+
+        let cli =
+            Cli::try_parse_from(["sagascript", "history", "export", "--format", "csv", "-o", "x"])
+                .unwrap();
+        match cli.command.unwrap() {
+            Command::History(args) => match args.action {
+                history::HistoryAction::Export { format, output } => {
+                    assert_eq!(format, history::ExportFormat::Csv);
+                    assert_eq!(output, Some(std::path::PathBuf::from("x")));
+                }
+                _ => panic!("expected history export"),
+            },
+            _ => panic!("expected History"),
+        }
+        assert!(
+            Cli::try_parse_from(["sagascript", "history", "export", "--format", "xml"]).is_err()
+        );
+    }
+
+    #[test]
+    fn parse_recover_path_excludes_discard() {
+        let cli = Cli::try_parse_from(["sagascript", "recover", "--path"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Recover(args) => assert!(args.path && !args.discard),
+            _ => panic!("expected Recover"),
+        }
+        assert!(Cli::try_parse_from(["sagascript", "recover", "--path", "--discard"]).is_err());
+    }
+
+    #[test]
+    fn parse_bundle_export_with_models() {
+        let cli = Cli::try_parse_from([
+            "sagascript",
+            "bundle",
+            "export",
+            "models.tar",
+            "-m",
+            "base.en",
+            "-m",
+            "kb-whisper-base",
+        ])
+        .unwrap();
+        match cli.command.unwrap() {
+            Command::Bundle(args) => match args.action {
+                bundle::BundleAction::Export {
+                    path,
+                    models,
+                    language,
+                    no_vad,
+                } => {
+                    assert_eq!(path, PathBuf::from("models.tar"));
+                    assert_eq!(models, vec!["base.en", "kb-whisper-base"]);
+                    assert_eq!(language, None);
+                    assert!(!no_vad);
+                }
+                _ => panic!("expected Export"),
+            },
+            _ => panic!("expected Bundle"),
+        }
+    }
+
+    #[test]
+    fn parse_models_add() {
+        let cli = Cli::try_parse_from([
+            "sagascript",
+            "models",
+            "add",
+            "./my-finetune.bin",
+            "--name",
+            "my-model",
+        ])
+        .unwrap();
+        match cli.command.unwrap() {
+            Command::Models(args) => match args.action {
+                models::ModelsAction::Add {
+                    path,
+                    name,
+                    use_now,
+                } => {
+                    assert_eq!(path, PathBuf::from("./my-finetune.bin"));
+                    assert_eq!(name.as_deref(), Some("my-model"));
+                    assert!(!use_now);
+                }
+                _ => panic!("expected Add"),
+            },
+            _ => panic!("expected Models"),
+        }
+    }
+
+    #[test]
+    fn parse_models_usage_and_prune() {
+        let cli = Cli::try_parse_from(["sagascript", "models", "usage", "--json"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Models(args) => {
+                assert!(matches!(
+                    args.action,
+                    models::ModelsAction::Usage { json: true, .. }
+                ))
+            }
+            _ => panic!("expected Models"),
+        }
+        let cli = Cli::try_parse_from(["sagascript", "models", "prune", "--keep-recommended"])
+            .unwrap();
+        match cli.command.unwrap() {
+            Command::Models(args) => match args.action {
+                models::ModelsAction::Prune {
+                    keep_recommended,
+                    dry_run,
+                } => {
+                    assert!(keep_recommended);
+                    assert!(!dry_run);
+                }
+                _ => panic!("expected Prune"),
+            },
+            _ => panic!("expected Models"),
+        }
+    }
+
+    #[test]
+    fn parse_models_url() {
+        let cli = Cli::try_parse_from([
+            "sagascript",
+            "models",
+            "url",
+            "base.en",
+            "http://models.lan/base.bin",
+        ])
+        .unwrap();
+        match cli.command.unwrap() {
+            Command::Models(args) => match args.action {
+                models::ModelsAction::Url { model, url, clear } => {
+                    assert_eq!(model.as_deref(), Some("base.en"));
+                    assert_eq!(url.as_deref(), Some("http://models.lan/base.bin"));
+                    assert!(!clear);
+                }
+                _ => panic!("expected Url"),
+            },
+            _ => panic!("expected Models"),
+        }
+        assert!(Cli::try_parse_from(["sagascript", "models", "url", "--clear"]).is_err());
+    }
+
+    #[test]
+    fn parse_bundle_import() {
+        let cli = Cli::try_parse_from(["sagascript", "bundle", "import", "models.tar"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Bundle(args) => match args.action {
+                bundle::BundleAction::Import { path } => {
+                    assert_eq!(path, PathBuf::from("models.tar"));
+                }
+                _ => panic!("expected Import"),
+            },
+            _ => panic!("expected Bundle"),
+        }
+    }
+
+    #[test]
+    fn parse_config_set() {
+        let cli = Cli::try_parse_from(["sagascript", "config", "set", "language", "sv"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Config(args) => match args.action {
+                config::ConfigAction::Set { pairs, .. } => {
+                    assert_eq!(pairs, ["language", "sv"]);
                 }
                 _ => panic!("expected ConfigAction::Set"),
             },
@@ -901,6 +2180,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_config_set_several_pairs() {
+        let cli = Cli::try_parse_from([
+            "sagascript",
+            "config",
+            "set",
+            "language=sv",
+            "auto_paste=false",
+            "hotkey=Option+Space",
+        ])
+        .unwrap();
+        match cli.command.unwrap() {
+            Command::Config(args) => match args.action {
+                config::ConfigAction::Set { pairs, .. } => {
+                    assert_eq!(pairs, ["language=sv", "auto_paste=false", "hotkey=Option+Space"]);
+                }
+                _ => panic!("expected ConfigAction::Set"),
+            },
+            _ => panic!("expected Config"),
+        }
+    }
+
+    #[test]
+    fn parse_config_unset_requires_a_key() {
+        let cli = Cli::try_parse_from(["sagascript", "config", "unset", "hotkey"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Config(args) => match args.action {
+                config::ConfigAction::Unset { key } => assert_eq!(key, "hotkey"),
+                _ => panic!("expected ConfigAction::Unset"),
+            },
+            _ => panic!("expected Config"),
+        }
+        assert!(Cli::try_parse_from(["sagascript", "config", "unset"]).is_err());
+    }
+
+    #[test]
+    fn parse_config_validate() {
+        let cli = Cli::try_parse_from(["sagascript", "config", "validate"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Config(args) => {
+                assert!(matches!(args.action, config::ConfigAction::Validate))
+            }
+            _ => panic!("expected Config"),
+        }
+    }
+
     #[test]
     fn parse_config_reset_all() {
         let cli = Cli::try_parse_from(["sagascript", "config", "reset"]).unwrap();
@@ -915,6 +2240,219 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_config_prompt_history_clear() {
+        let cli =
+            Cli::try_parse_from(["sagascript", "config", "prompt-history", "--clear"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Config(args) => match args.action {
+                config::ConfigAction::PromptHistory { profile, clear } => {
+                    assert!(clear);
+                    assert_eq!(profile, None);
+                }
+                _ => panic!("expected ConfigAction::PromptHistory"),
+            },
+            _ => panic!("expected Config"),
+        }
+    }
+
+    #[test]
+    fn parse_config_prompt_history_profile() {
+        let cli = Cli::try_parse_from([
+            "sagascript",
+            "config",
+            "prompt-history",
+            "--profile",
+            "Medical",
+        ])
+        .unwrap();
+        match cli.command.unwrap() {
+            Command::Config(args) => match args.action {
+                config::ConfigAction::PromptHistory { profile, clear } => {
+                    assert!(!clear);
+                    assert_eq!(profile.as_deref(), Some("Medical"));
+                }
+                _ => panic!("expected ConfigAction::PromptHistory"),
+            },
+            _ => panic!("expected Config"),
+        }
+    }
+
+    #[test]
+    fn parse_config_mqtt_password_clear() {
+        let cli =
+            Cli::try_parse_from(["sagascript", "config", "mqtt-password", "--clear"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Config(args) => match args.action {
+                config::ConfigAction::MqttPassword { clear } => assert!(clear),
+                _ => panic!("expected ConfigAction::MqttPassword"),
+            },
+            _ => panic!("expected Config"),
+        }
+    }
+
+    #[test]
+    fn parse_config_post_process_key_clear() {
+        let cli =
+            Cli::try_parse_from(["sagascript", "config", "post-process-key", "--clear"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Config(args) => match args.action {
+                config::ConfigAction::PostProcessKey { clear } => assert!(clear),
+                _ => panic!("expected ConfigAction::PostProcessKey"),
+            },
+            _ => panic!("expected Config"),
+        }
+    }
+
+    #[test]
+    fn parse_config_set_api_key() {
+        let cli = Cli::try_parse_from(["sagascript", "config", "set-api-key"]).unwrap();
+        match cli.command.unwrap() {
+            Command::Config(args) => match args.action {
+                config::ConfigAction::SetApiKey { backend, clear } => {
+                    assert_eq!(backend, "openai");
+                    assert!(!clear);
+                }
+                _ => panic!("expected ConfigAction::SetApiKey"),
+            },
+            _ => panic!("expected Config"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "sagascript",
+            "config",
+            "set-api-key",
+            "--backend",
+            "azure",
+            "--clear",
+        ])
+        .unwrap();
+        match cli.command.unwrap() {
+            Command::Config(args) => match args.action {
+                config::ConfigAction::SetApiKey { backend, clear } => {
+                    assert_eq!(backend, "azure");
+                    assert!(clear);
+                }
+                _ => panic!("expected ConfigAction::SetApiKey"),
+            },
+            _ => panic!("expected Config"),
+        }
+    }
+
+    #[test]
+    fn parse_config_rules_add_regex() {
+        let cli = Cli::try_parse_from([
+            "sagascript",
+            "config",
+            "rules",
+            "add",
+            "--regex",
+            r"(\d+) procent",
+            "$1 %",
+        ])
+        .unwrap();
+        match cli.command.unwrap() {
+            Command::Config(args) => match args.action {
+                config::ConfigAction::Rules {
+                    action:
+                        config::RulesAction::Add {
+                            find,
+                            replace,
+                            regex,
+                        },
+                } => {
+                    assert_eq!(find, r"(\d+) procent");
+                    assert_eq!(replace, "$1 %");
+                    assert!(regex);
+                }
+                _ => panic!("expected ConfigAction::Rules add"),
+            },
+            _ => panic!("expected Config"),
+        }
+    }
+
+    #[test]
+    fn parse_config_model_override() {
+        let cli = Cli::try_parse_from([
+            "sagascript",
+            "config",
+            "model-override",
+            "small.en",
+            "--no-speech-threshold",
+            "0.5",
+            "--temperature-fallback",
+            "false",
+        ])
+        .unwrap();
+        match cli.command.unwrap() {
+            Command::Config(args) => match args.action {
+                config::ConfigAction::ModelOverride {
+                    model,
+                    no_speech_threshold,
+                    beam_size,
+                    temperature_fallback,
+                    clear,
+                } => {
+                    assert_eq!(model.as_deref(), Some("small.en"));
+                    assert_eq!(no_speech_threshold, Some(0.5));
+                    assert_eq!(beam_size, None);
+                    assert_eq!(temperature_fallback, Some(false));
+                    assert!(!clear);
+                }
+                _ => panic!("expected ConfigAction::ModelOverride"),
+            },
+            _ => panic!("expected Config"),
+        }
+        assert!(Cli::try_parse_from([
+            "sagascript",
+            "config",
+            "model-override",
+            "--beam-size",
+            "4"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parse_config_model_pin() {
+        let cli = Cli::try_parse_from([
+            "sagascript",
+            "config",
+            "model-pin",
+            "kb-whisper-base",
+            "--min-runtime",
+            "0.15.0",
+        ])
+        .unwrap();
+        match cli.command.unwrap() {
+            Command::Config(args) => match args.action {
+                config::ConfigAction::ModelPin {
+                    model,
+                    sha256,
+                    min_runtime,
+                    clear,
+                } => {
+                    assert_eq!(model.as_deref(), Some("kb-whisper-base"));
+                    assert_eq!(sha256, None);
+                    assert_eq!(min_runtime.as_deref(), Some("0.15.0"));
+                    assert!(!clear);
+                }
+                _ => panic!("expected ConfigAction::ModelPin"),
+            },
+            _ => panic!("expected Config"),
+        }
+        assert!(Cli::try_parse_from([
+            "sagascript",
+            "config",
+            "model-pin",
+            "kb-whisper-base",
+            "--clear",
+            "--min-runtime",
+            "0.15.0"
+        ])
+        .is_err());
+    }
+
     #[test]
     fn parse_completions() {
         let cli = Cli::try_parse_from(["sagascript", "completions", "zsh"]).unwrap();
@@ -1,61 +1,209 @@
-use clap::Args;
+use std::path::PathBuf;
 
+use clap::{Args, Subcommand};
+
+use sagascript_core::download::{verify_file, DownloadIntegrity};
 use sagascript_core::error::DictationError;
+use sagascript_core::history;
 use sagascript_core::settings::{Language, WhisperModel};
-use sagascript_core::transcription::model;
+use sagascript_core::transcription::catalog::{self, ModelFilter, ALL_LANGUAGES};
+use sagascript_core::transcription::recommend::{self, Hardware, Recommendation};
+use sagascript_core::transcription::{model, updates, usage, WhisperBackend};
 
-use super::transcribe::{model_id_string, parse_language, parse_model};
+use super::config::format_enum_value;
+use super::porcelain;
+use super::table::{Column, Table, TableArgs};
+use super::transcribe::{parse_language, parse_model};
 
 #[derive(Args)]
 pub struct ListModelsArgs {
     /// Filter by language [possible values: en, sv, no, auto (less accurate)]
     #[arg(short, long, value_name = "LANG")]
     pub language: Option<String>,
+
+    /// Only list models that are already downloaded
+    #[arg(long)]
+    pub downloaded: bool,
+
+    /// Only list models with a download of at most this many MB
+    #[arg(long, value_name = "MB")]
+    pub size_max: Option<u32>,
+
+    /// Output a JSON array of whisper models (the same metadata the GUI uses)
+    #[arg(long)]
+    pub json: bool,
+
+    /// Print stable, versioned records for scripts: one `model` record per
+    /// whisper model (id, languages, size MB, downloaded, recommended,
+    /// active). The format only changes with a new VERSION (currently 1).
+    #[arg(
+        long,
+        value_name = "VERSION",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "1",
+        conflicts_with = "json"
+    )]
+    pub porcelain: Option<porcelain::Version>,
+
+    #[command(flatten)]
+    pub table: TableArgs,
+}
+
+/// Columns of the `list-models` tables.
+const LIST_COLUMNS: [Column; 5] = [
+    Column::left("id", "MODEL ID"),
+    Column::left("name", "NAME"),
+    Column::right("size", "SIZE"),
+    Column::left("downloaded", "DOWNLOADED"),
+    Column::left("language", "LANGUAGE"),
+];
+
+#[derive(Args)]
+pub struct UpdateModelsArgs {
+    /// Only check HuggingFace for newer revisions; don't re-verify or re-download
+    #[arg(long)]
+    pub check: bool,
+}
+
+#[derive(Args)]
+pub struct VerifyModelsArgs {
+    /// Only verify this model [see: sagascript list-models]
+    pub model: Option<String>,
 }
 
 #[derive(Args)]
 pub struct DownloadModelArgs {
     /// Model ID to download [see: sagascript list-models]
-    pub model: String,
+    #[arg(required_unless_present_any = ["all", "recommended"])]
+    pub model: Option<String>,
+
+    /// Language for --all/--recommended (default: the configured language)
+    #[arg(short, long, value_name = "LANG", conflicts_with = "model")]
+    pub language: Option<String>,
+
+    /// Download every model for the language
+    #[arg(long, conflicts_with_all = ["model", "recommended"])]
+    pub all: bool,
+
+    /// Download the model recommended for the language on this machine,
+    /// going by its memory, CPU cores and GPU
+    #[arg(long, conflicts_with = "model")]
+    pub recommended: bool,
+
+    /// With --recommended: also make the language and the recommended model
+    /// the configured ones, and check the model loads
+    #[arg(long, requires = "recommended")]
+    pub setup: bool,
 }
 
 pub fn list(args: ListModelsArgs) -> Result<(), DictationError> {
-    let languages: Vec<Language> = if let Some(lang_str) = &args.language {
-        vec![parse_language(lang_str)?]
-    } else {
-        vec![
-            Language::English,
-            Language::Swedish,
-            Language::Norwegian,
-            Language::Auto,
-        ]
+    let language = args.language.as_deref().map(parse_language).transpose()?;
+    let languages: Vec<Language> = match language {
+        Some(l) => vec![l],
+        None => ALL_LANGUAGES.to_vec(),
     };
+    let active = sagascript_core::settings::store::load().effective_model();
 
-    // Header
-    println!(
-        "{:<20} {:<10} {:<8} {:<12} {:<12}",
-        "MODEL ID", "NAME", "SIZE", "DOWNLOADED", "LANGUAGE"
-    );
-    println!("{}", "-".repeat(62));
+    if let Some(version) = args.porcelain {
+        let filter = ModelFilter {
+            language,
+            downloaded_only: args.downloaded,
+            max_size_mb: args.size_max,
+        };
+        let mut out = porcelain::Output::new(version);
+        for info in catalog::list_models(&filter, Some(active)) {
+            let languages: Vec<String> = info
+                .languages
+                .iter()
+                .map(|&l| format_enum_value(l))
+                .collect();
+            out.record(
+                "model",
+                &[
+                    &info.id,
+                    &languages.join(","),
+                    &info.size_mb.to_string(),
+                    &info.downloaded.to_string(),
+                    &info.recommended.to_string(),
+                    &info.active.to_string(),
+                ],
+            );
+        }
+        out.print();
+        return Ok(());
+    }
 
+    if args.json {
+        let filter = ModelFilter {
+            language,
+            downloaded_only: args.downloaded,
+            max_size_mb: args.size_max,
+        };
+        let infos = catalog::list_models(&filter, Some(active));
+        let json = serde_json::to_string_pretty(&infos)
+            .map_err(|e| DictationError::SettingsError(format!("JSON error: {e}")))?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    // One section per language, so a model offered for several is listed
+    // under each.
+    let mut table = Table::new(&LIST_COLUMNS);
     for lang in &languages {
-        let models = WhisperModel::models_for_language(*lang);
-        for &m in models {
-            let downloaded = if model::is_model_downloaded(m) {
-                "yes"
-            } else {
-                "no"
-            };
+        let filter = ModelFilter {
+            language: Some(*lang),
+            downloaded_only: args.downloaded,
+            max_size_mb: args.size_max,
+        };
+        for info in catalog::list_models(&filter, Some(active)) {
+            table.row(vec![
+                info.id,
+                info.display_name,
+                format!("{} MB", info.size_mb),
+                yes_no(info.downloaded),
+                lang.display_name().to_string(),
+            ]);
+        }
+    }
+    table.print(&args.table)?;
+    if let Some(dir) = model::shared_models_dir().filter(|_| !args.table.no_header) {
+        println!("Models in {} are shared by all users.", dir.display());
+    }
+
+    // Community models from the user's models.json
+    let community = catalog::user_entries()?;
+    if !community.is_empty() {
+        let mut table = Table::new(&LIST_COLUMNS);
+        for entry in community {
+            let downloaded = model::models_dir().join(entry.local_file()).exists();
+            let size_mb = (entry.size / 1_048_576) as u32;
+            if (args.downloaded && !downloaded) || args.size_max.is_some_and(|max| size_mb > max) {
+                continue;
+            }
+            let other_language = language
+                .and_then(|l| l.whisper_code())
+                .is_some_and(|code| !entry.languages.iter().any(|c| c == code));
+            if other_language {
+                continue;
+            }
+            table.row(vec![
+                entry.id.clone(),
+                entry.display_name().to_string(),
+                format!("{size_mb} MB"),
+                yes_no(downloaded),
+                entry.languages.join(", "),
+            ]);
+        }
 
+        if !args.table.no_header {
+            println!();
             println!(
-                "{:<20} {:<10} {:>5} MB  {:<12} {:<12}",
-                model_id_string(m),
-                m.display_name(),
-                m.size_mb(),
-                downloaded,
-                lang.display_name(),
+                "Community models ({}):",
+                catalog::user_catalog_path().display()
             );
         }
+        table.print(&args.table)?;
     }
 
     // Diarization models section (only when no language filter, or always show)
@@ -64,84 +212,353 @@ pub fn list(args: ListModelsArgs) -> Result<(), DictationError> {
         use sagascript_core::diarization::model as diar_model;
         use sagascript_core::diarization::model::DiarizationModel;
 
-        println!();
-        println!("Diarization models (speaker identification):");
-        println!("{}", "-".repeat(62));
-
+        let mut table = Table::new(&LIST_COLUMNS);
         for &m in DiarizationModel::ALL {
-            let downloaded = if diar_model::is_model_downloaded(m) {
-                "yes"
-            } else {
-                "no"
+            let is_downloaded = diar_model::is_model_downloaded(m);
+            if (args.downloaded && !is_downloaded)
+                || args.size_max.is_some_and(|max| m.size_mb() > max)
+            {
+                continue;
+            }
+            table.row(vec![
+                m.model_id().to_string(),
+                m.display_name().to_string(),
+                format!("{} MB", m.size_mb()),
+                yes_no(is_downloaded),
+                "—".to_string(),
+            ]);
+        }
+
+        if !args.table.no_header {
+            println!();
+            println!("Diarization models (speaker identification):");
+        }
+        table.print(&args.table)?;
+    }
+
+    Ok(())
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_string()
+}
+
+#[derive(Args)]
+pub struct ModelsArgs {
+    #[command(subcommand)]
+    pub action: ModelsAction,
+}
+
+#[derive(Subcommand)]
+pub enum ModelsAction {
+    /// Register a local GGML file as a custom model
+    Add {
+        /// GGML model file (e.g. a fine-tune converted with whisper.cpp)
+        path: PathBuf,
+
+        /// Name to use it by with --model and `config set custom_model`
+        /// (default: the file name without its extension)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Also make it the model dictations and transcriptions use
+        #[arg(long = "use")]
+        use_now: bool,
+    },
+    /// Forget a custom model; its file is left where it is
+    Remove {
+        /// Name the model was registered under
+        name: String,
+    },
+    /// List the registered custom models
+    List,
+    /// Show the disk space each downloaded model takes and when it was last used
+    Usage {
+        /// Output a JSON array of `{model, display_name, bytes, last_used}`,
+        /// `last_used` in Unix seconds or null
+        #[arg(long)]
+        json: bool,
+
+        #[command(flatten)]
+        table: TableArgs,
+    },
+    /// Delete downloaded models that neither the settings nor a profile use
+    Prune {
+        /// Also keep the recommended model of every configured language
+        #[arg(long)]
+        keep_recommended: bool,
+
+        /// Only list the models that would be deleted
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Download a model from a URL of your own first, such as a local
+    /// artifact server; without arguments, list the URLs set
+    Url {
+        /// Model ID, built-in or from your models.json
+        model: Option<String>,
+
+        /// URL of the model's GGML file (omit to show the current one)
+        url: Option<String>,
+
+        /// Go back to downloading the model from Hugging Face
+        #[arg(long, requires = "model", conflicts_with = "url")]
+        clear: bool,
+    },
+}
+
+/// Columns of the `models usage` table.
+const USAGE_COLUMNS: [Column; 4] = [
+    Column::left("id", "MODEL ID"),
+    Column::right("size", "SIZE"),
+    Column::left("last_used", "LAST USED"),
+    Column::left("in_use", "IN USE"),
+];
+
+pub fn run(args: ModelsArgs) -> Result<(), DictationError> {
+    match args.action {
+        ModelsAction::Add {
+            path,
+            name,
+            use_now,
+        } => {
+            let name = match name {
+                Some(name) => name,
+                None => path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
             };
+            let path = model::add_custom_model(&name, &path)?;
+            if use_now {
+                sagascript_core::settings::store::update(|s| s.custom_model = name.clone())
+                    .map_err(DictationError::SettingsError)?;
+            }
+            eprintln!("Added custom model '{name}': {}", path.display());
+            if !use_now {
+                eprintln!(
+                    "Use it with --model {name}, or for everything with \
+                     'sagascript config set custom_model {name}'."
+                );
+            }
+        }
+        ModelsAction::Remove { name } => {
+            if !model::remove_custom_model(&name)? {
+                return Err(DictationError::SettingsError(format!(
+                    "No custom model named '{name}'. See 'sagascript models list'."
+                )));
+            }
+            eprintln!("Removed custom model '{name}'.");
+        }
+        ModelsAction::List => {
+            let stored = sagascript_core::settings::store::load();
+            if stored.custom_models.is_empty() {
+                eprintln!("No custom models. Add one with 'sagascript models add FILE'.");
+            }
+            for (name, path) in &stored.custom_models {
+                let active = if *name == stored.custom_model {
+                    "  (in use)"
+                } else {
+                    ""
+                };
+                println!("{name}\t{}{active}", path.display());
+            }
+        }
+        ModelsAction::Usage { json, table } => show_usage(json, &table)?,
+        ModelsAction::Prune {
+            keep_recommended,
+            dry_run,
+        } => prune(keep_recommended, dry_run)?,
+        ModelsAction::Url { model, url, clear } => {
+            model_url(model.as_deref(), url.as_deref(), clear)?
+        }
+    }
+    Ok(())
+}
+
+fn show_usage(json: bool, args: &TableArgs) -> Result<(), DictationError> {
+    let downloaded = usage::downloaded();
+    if json {
+        let json = serde_json::to_string_pretty(&downloaded)
+            .map_err(|e| DictationError::SettingsError(format!("JSON error: {e}")))?;
+        println!("{json}");
+        return Ok(());
+    }
 
+    let in_use = usage::needed_models(&sagascript_core::settings::store::load(), false);
+    let mut table = Table::new(&USAGE_COLUMNS);
+    for entry in &downloaded {
+        table.row(vec![
+            catalog::model_id(entry.model),
+            megabytes(entry.bytes),
+            entry
+                .last_used
+                .map_or_else(|| "—".to_string(), history::format_timestamp),
+            yes_no(in_use.contains(&entry.model)),
+        ]);
+    }
+    table.print(args)?;
+    if !args.no_header {
+        let total = downloaded.iter().map(|entry| entry.bytes).sum();
+        println!("{} in {}", megabytes(total), model::models_dir().display());
+    }
+    Ok(())
+}
+
+/// Delete the downloaded models `usage::needed_models` doesn't list.
+fn prune(keep_recommended: bool, dry_run: bool) -> Result<(), DictationError> {
+    let keep = usage::needed_models(&sagascript_core::settings::store::load(), keep_recommended);
+    let unneeded: Vec<_> = usage::downloaded()
+        .into_iter()
+        .filter(|entry| !keep.contains(&entry.model))
+        .collect();
+    if unneeded.is_empty() {
+        eprintln!("Nothing to prune: every downloaded model is in use.");
+        return Ok(());
+    }
+    let mut freed = 0;
+    for entry in &unneeded {
+        if dry_run {
             println!(
-                "{:<20} {:<10} {:>5} MB  {:<12} {:<12}",
-                m.model_id(),
-                m.display_name(),
-                m.size_mb(),
-                downloaded,
-                "—",
+                "Would delete {} ({})",
+                entry.display_name,
+                megabytes(entry.bytes)
             );
+            freed += entry.bytes;
+        } else {
+            let bytes = model::delete_model(entry.model)?;
+            eprintln!("Deleted {} ({})", entry.display_name, megabytes(bytes));
+            freed += bytes;
         }
     }
+    let verb = if dry_run { "Would free" } else { "Freed" };
+    eprintln!("{verb} {}.", megabytes(freed));
+    Ok(())
+}
+
+/// List, show, set or clear the `model_urls` setting's entries.
+fn model_url(model: Option<&str>, url: Option<&str>, clear: bool) -> Result<(), DictationError> {
+    let stored = sagascript_core::settings::store::load();
+    let Some(model) = model else {
+        if stored.model_urls.is_empty() {
+            eprintln!("No download URLs set. Add one with 'sagascript models url ID URL'.");
+        }
+        for (id, url) in &stored.model_urls {
+            println!("{id}\t{url}");
+        }
+        return Ok(());
+    };
+    let (id, default_url) = match catalog::user_entry(model)? {
+        Some(entry) => (entry.id.clone(), entry.url()),
+        None => {
+            let m = parse_model(model)?;
+            (catalog::model_id(m), m.download_url())
+        }
+    };
 
+    if clear {
+        sagascript_core::settings::store::update(|s| {
+            s.model_urls.remove(&id);
+        })
+        .map_err(DictationError::SettingsError)?;
+        eprintln!("{id} downloads from {default_url} again.");
+        return Ok(());
+    }
+    let Some(url) = url else {
+        let current = stored
+            .model_urls
+            .get(&id)
+            .map_or(default_url, String::as_str);
+        println!("{current}");
+        return Ok(());
+    };
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return Err(DictationError::SettingsError(format!(
+            "'{url}' should be an http:// or https:// URL"
+        )));
+    }
+    sagascript_core::settings::store::update(|s| {
+        s.model_urls.insert(id.clone(), url.to_string());
+    })
+    .map_err(DictationError::SettingsError)?;
+    eprintln!("{id} now downloads from {url} first, then from {default_url}.");
+    eprintln!("The file must still match the pinned SHA-256.");
     Ok(())
 }
 
+fn megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / 1_048_576.0)
+}
+
 #[derive(Args)]
 pub struct DeleteModelArgs {
     /// Model ID to delete [see: sagascript list-models]
     pub model: String,
+
+    /// Delete it even if it is the model in use
+    #[arg(long)]
+    pub force: bool,
 }
 
 pub fn delete(args: DeleteModelArgs) -> Result<(), DictationError> {
     let whisper_model = parse_model(&args.model)?;
 
-    if !model::is_model_downloaded(whisper_model) {
+    let settings = sagascript_core::settings::store::load();
+    if settings.uses_model(whisper_model) && !args.force {
+        return Err(DictationError::SettingsError(format!(
+            "{} is the model in use. Select another model first, or pass --force.",
+            whisper_model.display_name()
+        )));
+    }
+
+    let freed = model::delete_model(whisper_model)?;
+    if freed == 0 {
         eprintln!(
             "Model '{}' is not downloaded.",
             whisper_model.display_name()
         );
         return Ok(());
     }
-
-    let path = model::model_path(whisper_model);
-    std::fs::remove_file(&path).map_err(|e| {
-        DictationError::SettingsError(format!(
-            "Failed to delete model file '{}': {e}",
-            path.display()
-        ))
-    })?;
-
     eprintln!(
-        "Deleted {} ({})",
+        "Deleted {} ({:.1} MB freed)",
         whisper_model.display_name(),
-        path.display()
+        freed as f64 / 1_048_576.0
     );
     Ok(())
 }
 
 pub async fn download(args: DownloadModelArgs) -> Result<(), DictationError> {
+    let Some(model_id) = args.model.as_deref() else {
+        download_for_language(&args).await?;
+        if args.setup {
+            setup_recommended(target_language(&args)?)?;
+        }
+        return Ok(());
+    };
+
     // Try diarization model IDs first (when feature is enabled)
     #[cfg(feature = "diarization")]
     {
         use sagascript_core::diarization::model::DiarizationModel;
 
         // "diarization" meta-ID downloads both models
-        if DiarizationModel::is_meta_id(&args.model) {
+        if DiarizationModel::is_meta_id(model_id) {
             for &m in DiarizationModel::ALL {
                 download_diarization_model(m).await?;
             }
             return Ok(());
         }
 
-        if let Some(diar) = DiarizationModel::from_id(&args.model) {
+        if let Some(diar) = DiarizationModel::from_id(model_id) {
             return download_diarization_model(diar).await;
         }
     }
 
-    let whisper_model = parse_model(&args.model)?;
+    if let Some(entry) = catalog::user_entry(model_id)? {
+        return download_catalog_model(entry).await;
+    }
+
+    let whisper_model = parse_model(model_id)?;
     let was_present = model::is_model_downloaded(whisper_model);
     if was_present {
         eprintln!("Verifying {}...", whisper_model.display_name());
@@ -153,18 +570,7 @@ pub async fn download(args: DownloadModelArgs) -> Result<(), DictationError> {
         );
     }
 
-    let path = model::download_model(whisper_model, |downloaded, total| {
-        if total > 0 {
-            let pct = (downloaded as f64 / total as f64 * 100.0) as u32;
-            let mb_done = downloaded as f64 / 1_048_576.0;
-            let mb_total = total as f64 / 1_048_576.0;
-            eprint!("\r  {:.1}/{:.1} MB ({pct}%)", mb_done, mb_total);
-        } else {
-            let mb_done = downloaded as f64 / 1_048_576.0;
-            eprint!("\r  {:.1} MB downloaded", mb_done);
-        }
-    })
-    .await?;
+    let path = model::download_model(whisper_model, print_progress).await?;
 
     eprintln!(); // newline after progress (or verification message)
     eprintln!("Model ready.");
@@ -172,6 +578,310 @@ pub async fn download(args: DownloadModelArgs) -> Result<(), DictationError> {
     Ok(())
 }
 
+fn print_progress(downloaded: u64, total: u64) {
+    if total > 0 {
+        let pct = (downloaded as f64 / total as f64 * 100.0) as u32;
+        let mb_done = downloaded as f64 / 1_048_576.0;
+        let mb_total = total as f64 / 1_048_576.0;
+        eprint!("\r  {:.1}/{:.1} MB ({pct}%)", mb_done, mb_total);
+    } else {
+        let mb_done = downloaded as f64 / 1_048_576.0;
+        eprint!("\r  {:.1} MB downloaded", mb_done);
+    }
+}
+
+/// Download a community model from the user's `models.json`; it's
+/// registered as a custom model of the same ID.
+async fn download_catalog_model(
+    entry: &'static catalog::CatalogEntry,
+) -> Result<(), DictationError> {
+    eprintln!(
+        "Downloading {} from {} (~{} MB)...",
+        entry.display_name(),
+        entry.repo,
+        entry.size / 1_048_576
+    );
+    let path = model::download_catalog_model(entry, print_progress).await?;
+    eprintln!();
+    eprintln!(
+        "Model ready. Use it with --model {id}, or for everything with \
+         'sagascript config set custom_model {id}'.",
+        id = entry.id
+    );
+    println!("{}", path.display());
+    Ok(())
+}
+
+/// The whisper models `download-model --all`/`--recommended` fetch.
+fn models_to_provision(language: Language, all: bool, use_gpu: bool) -> Vec<WhisperModel> {
+    if all {
+        WhisperModel::models_for_language(language).to_vec()
+    } else {
+        vec![recommend_for_this_machine(language, use_gpu).model]
+    }
+}
+
+/// [`recommend::recommend`] for this machine, telling the user why.
+fn recommend_for_this_machine(language: Language, use_gpu: bool) -> Recommendation {
+    let recommendation = recommend::recommend(language, Hardware::detect(use_gpu));
+    eprintln!(
+        "Recommended: {}. {}",
+        recommendation.display_name, recommendation.reason
+    );
+    recommendation
+}
+
+/// `download-model --all`/`--recommended`: fetch a language's models in one
+/// go (for provisioning a machine before going offline), plus the VAD model
+/// when the settings have VAD enabled, since dictation needs it too.
+/// The language for --all/--recommended: --language, else the configured one.
+fn target_language(args: &DownloadModelArgs) -> Result<Language, DictationError> {
+    match &args.language {
+        Some(l) => parse_language(l),
+        None => Ok(sagascript_core::settings::store::load().language),
+    }
+}
+
+async fn download_for_language(args: &DownloadModelArgs) -> Result<(), DictationError> {
+    let stored = sagascript_core::settings::store::load();
+    let language = target_language(args)?;
+
+    let wanted = models_to_provision(language, args.all, stored.use_gpu);
+    let missing: Vec<WhisperModel> = wanted
+        .iter()
+        .copied()
+        .filter(|&m| !model::is_model_downloaded(m))
+        .collect();
+    let need_vad = stored.vad_enabled && !model::is_vad_model_downloaded();
+
+    for m in wanted.iter().filter(|m| !missing.contains(m)) {
+        eprintln!("{} already downloaded", m.display_name());
+    }
+    if missing.is_empty() && !need_vad {
+        eprintln!("All {} models are ready.", language.display_name());
+        return Ok(());
+    }
+
+    let total_mb: u32 = missing.iter().map(|m| m.size_mb()).sum();
+    eprintln!(
+        "Downloading {} {} model(s) (~{total_mb} MB){}...",
+        missing.len(),
+        language.display_name(),
+        if need_vad { " and the VAD model" } else { "" }
+    );
+
+    // Combined progress: bytes of finished models plus the current one, over
+    // the pinned artifact sizes of everything still to fetch.
+    let grand_total: u64 = missing.iter().map(|m| m.download_integrity().size).sum();
+    let mut finished: u64 = 0;
+    let count = missing.len();
+    for (i, &m) in missing.iter().enumerate() {
+        let label = format!("[{}/{count}] {}", i + 1, m.display_name());
+        let done_before = finished;
+        let path = model::download_model(m, move |downloaded, _total| {
+            let overall = done_before + downloaded;
+            let pct = (overall as f64 / grand_total.max(1) as f64 * 100.0).min(100.0) as u32;
+            eprint!(
+                "\r  {label}: {:.1} MB — overall {:.0}/{:.0} MB ({pct}%)",
+                downloaded as f64 / 1_048_576.0,
+                overall as f64 / 1_048_576.0,
+                grand_total as f64 / 1_048_576.0,
+            );
+        })
+        .await?;
+        finished += m.download_integrity().size;
+        eprintln!();
+        println!("{}", path.display());
+    }
+
+    if need_vad {
+        eprintln!("Downloading VAD model...");
+        let path = model::download_vad_model(|_, _| {}).await?;
+        println!("{}", path.display());
+    }
+
+    eprintln!("All {} models are ready.", language.display_name());
+    Ok(())
+}
+
+/// `--setup`: the app's onboarding preset. Saves `language` with the model
+/// recommended for this machine (auto-selected from then on when it is the
+/// language's usual one) and loads the model once, so a file that
+/// downloaded but won't load is reported now.
+fn setup_recommended(language: Language) -> Result<(), DictationError> {
+    let use_gpu = sagascript_core::settings::store::load().use_gpu;
+    let recommended = recommend::recommend(language, Hardware::detect(use_gpu)).model;
+    let settings = sagascript_core::settings::store::update(|s| {
+        s.apply_recommendation(language, recommended);
+    })
+    .map_err(DictationError::SettingsError)?;
+    let model = settings.whisper_model;
+
+    eprintln!("Loading model: {}...", model.display_name());
+    WhisperBackend::with_gpu(settings.use_gpu).load_model(model)?;
+    eprintln!(
+        "Set up: {} with {}{}",
+        language.display_name(),
+        model.display_name(),
+        if settings.auto_select_model {
+            " (auto-selected)"
+        } else {
+            ""
+        }
+    );
+    Ok(())
+}
+
+/// A downloaded file `verify-models` checks, and how.
+struct ModelFile {
+    name: String,
+    path: PathBuf,
+    integrity: Option<DownloadIntegrity>,
+}
+
+impl ModelFile {
+    fn verify(&self) -> Result<(), DictationError> {
+        match self.integrity {
+            Some(integrity) => verify_file(&self.path, integrity),
+            None => model::verify_vad_model(&self.path),
+        }
+    }
+}
+
+/// The downloaded model files to verify: `model` alone, or every whisper
+/// model, community model and the VAD model present on disk.
+fn files_to_verify(model_id: Option<&str>) -> Result<Vec<ModelFile>, DictationError> {
+    let builtin = |m: WhisperModel| ModelFile {
+        name: catalog::model_id(m),
+        path: model::model_path(m),
+        integrity: Some(m.download_integrity()),
+    };
+    let community = |entry: &'static catalog::CatalogEntry| ModelFile {
+        name: entry.id.clone(),
+        path: model::models_dir().join(entry.local_file()),
+        integrity: Some(entry.integrity()),
+    };
+    if let Some(id) = model_id {
+        let file = match catalog::user_entry(id)? {
+            Some(entry) => community(entry),
+            None => builtin(parse_model(id)?),
+        };
+        return Ok(vec![file]);
+    }
+
+    let mut files: Vec<ModelFile> = updates::installed_models()
+        .into_iter()
+        .map(builtin)
+        .collect();
+    files.extend(catalog::user_entries()?.iter().map(community));
+    files.push(ModelFile {
+        name: "silero-vad".to_string(),
+        path: model::vad_model_path(),
+        integrity: None,
+    });
+    files.retain(|f| f.path.exists());
+    Ok(files)
+}
+
+/// `verify-models`: hash downloaded model files against their pinned
+/// SHA-256, so a truncated or damaged file is found before it fails to load.
+pub fn verify(args: VerifyModelsArgs) -> Result<(), DictationError> {
+    let files = files_to_verify(args.model.as_deref())?;
+    if files.is_empty() {
+        eprintln!("No models downloaded. Download one with: sagascript download-model");
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for file in &files {
+        if !file.path.exists() {
+            println!("{:<22} missing", file.name);
+            failed += 1;
+            continue;
+        }
+        match file.verify() {
+            Ok(()) => println!("{:<22} ok", file.name),
+            Err(e) => {
+                println!("{:<22} FAILED  {e}", file.name);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(DictationError::ModelDownloadFailed(format!(
+            "{failed} model file(s) failed verification. 'sagascript download-model ID' \
+             replaces a damaged file with a verified copy."
+        )));
+    }
+    Ok(())
+}
+
+pub async fn update(args: UpdateModelsArgs) -> Result<(), DictationError> {
+    let installed = updates::installed_models();
+    if installed.is_empty() {
+        eprintln!("No models downloaded. Download one with: sagascript download-model");
+        return Ok(());
+    }
+
+    // Bring every installed file to the revision this build pins. A file
+    // from an older pin (or a damaged one) fails verification and is
+    // replaced; current files are only hashed.
+    if !args.check {
+        for &m in &installed {
+            let path = model::model_path(m);
+            if sagascript_core::download::verify_file(&path, m.download_integrity()).is_ok() {
+                updates::record_installed(m);
+                continue;
+            }
+            eprintln!("Updating {} to the pinned revision...", m.display_name());
+            model::download_model(m, |downloaded, total| {
+                if total > 0 {
+                    let pct = (downloaded as f64 / total as f64 * 100.0) as u32;
+                    eprint!(
+                        "\r  {:.1}/{:.1} MB ({pct}%)",
+                        downloaded as f64 / 1_048_576.0,
+                        total as f64 / 1_048_576.0
+                    );
+                }
+            })
+            .await?;
+            eprintln!();
+        }
+    }
+
+    eprintln!("Checking HuggingFace for newer revisions...");
+    let results = updates::check_installed().await?;
+    println!(
+        "{:<22} {:<10} {:<10} STATUS",
+        "MODEL", "INSTALLED", "UPSTREAM"
+    );
+    for u in &results {
+        let status = if u.update_available {
+            "newer upstream"
+        } else {
+            "up to date"
+        };
+        println!(
+            "{:<22} {:<10} {:<10} {status}",
+            u.model,
+            short_commit(&u.installed_commit),
+            short_commit(u.latest_commit.as_deref().unwrap_or("?")),
+        );
+    }
+    if results.iter().any(|u| u.update_available) {
+        eprintln!();
+        eprintln!(
+            "Newer upstream revisions are not installed automatically: Sagascript only \
+             installs checksummed revisions, so they arrive with a Sagascript update."
+        );
+    }
+    Ok(())
+}
+
+fn short_commit(commit: &str) -> &str {
+    commit.get(..8).unwrap_or(commit)
+}
+
 #[cfg(feature = "diarization")]
 async fn download_diarization_model(
     model: sagascript_core::diarization::model::DiarizationModel,
@@ -0,0 +1,140 @@
+//! `--porcelain`: stable, versioned output for scripts.
+//!
+//! The human output of `transcribe`, `list-models` and `config` (tables,
+//! wording, column widths) may change in any release. Porcelain output does
+//! not: within a version, record names, field order and encoding are fixed.
+//! Later releases may add new record types, so consumers must skip records
+//! they don't recognize.
+//!
+//! Format v1:
+//!
+//! - stdout starts with the header `porcelain<TAB>1`, followed by one record
+//!   per line. A record is its name followed by tab-separated fields.
+//! - Backslash, tab, CR and LF inside a field are escaped as `\\`, `\t`,
+//!   `\r` and `\n`. Nothing else is escaped.
+//! - Booleans are `true`/`false`. Times and durations are seconds with three
+//!   decimals. An absent value is an empty field.
+//! - On failure nothing is written to stdout, the exit status is non-zero,
+//!   and the last line on stderr is `error<TAB><kind><TAB><message><TAB>
+//!   <suggestion>`, with `kind` as in the app's error events. Any other
+//!   stderr output is progress for humans and is not part of the format.
+
+use clap::ValueEnum;
+
+use sagascript_core::error::DictationError;
+
+/// Porcelain format version, selected with `--porcelain[=VERSION]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Version {
+    #[value(name = "1")]
+    V1,
+}
+
+impl Version {
+    pub fn number(&self) -> u32 {
+        match self {
+            Version::V1 => 1,
+        }
+    }
+}
+
+/// Collects the records of one command's output and prints them, after the
+/// header, only once the command has succeeded.
+pub struct Output {
+    version: Version,
+    lines: Vec<String>,
+}
+
+impl Output {
+    pub fn new(version: Version) -> Self {
+        Self {
+            version,
+            lines: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, name: &str, fields: &[&str]) {
+        self.lines.push(record(name, fields));
+    }
+
+    pub fn print(self) {
+        println!("{}", self.render());
+    }
+
+    /// The header and records as printed, without the final newline.
+    pub fn render(&self) -> String {
+        std::iter::once(format!("porcelain\t{}", self.version.number()))
+            .chain(self.lines.iter().cloned())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// One record line: `name`, then each field escaped, tab-separated.
+pub fn record(name: &str, fields: &[&str]) -> String {
+    std::iter::once(name.to_string())
+        .chain(fields.iter().map(|f| escape(f)))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+/// The stderr record for a failed command.
+pub fn error_record(error: &DictationError) -> String {
+    record(
+        "error",
+        &[error.kind(), &error.to_string(), error.suggestion()],
+    )
+}
+
+/// Seconds in the fixed three-decimal form used for times and durations.
+pub fn seconds(secs: f64) -> String {
+    format!("{secs:.3}")
+}
+
+fn escape(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_joins_escaped_fields_with_tabs() {
+        assert_eq!(
+            record("text", &["two\tcols", "line\nbreak", "back\\slash"]),
+            "text\ttwo\\tcols\tline\\nbreak\tback\\\\slash"
+        );
+        assert_eq!(record("segment", &["0.000", ""]), "segment\t0.000\t");
+    }
+
+    #[test]
+    fn escaped_record_is_one_line() {
+        let line = record("text", &["a\r\nb"]);
+        assert!(!line.contains('\n') && !line.contains('\r'));
+    }
+
+    #[test]
+    fn error_record_carries_kind_and_message() {
+        let err = DictationError::FileDecodeError("bad header".into());
+        let line = error_record(&err);
+        assert!(line.starts_with(&format!("error\t{}\t", err.kind())));
+        assert!(line.contains("bad header"));
+    }
+
+    #[test]
+    fn seconds_use_three_decimals() {
+        assert_eq!(seconds(1.5), "1.500");
+        assert_eq!(seconds(0.0), "0.000");
+    }
+}
@@ -1,19 +1,31 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use clap::Args;
 use indicatif::{ProgressBar, ProgressStyle};
 
+use sagascript_core::audio::level;
+use sagascript_core::audio::capture::limit_remaining;
 use sagascript_core::audio::AudioCaptureService;
 use sagascript_core::audio::resample::TARGET_SAMPLE_RATE;
 use sagascript_core::error::DictationError;
+use sagascript_core::history::{HistoryEntry, HistorySource};
+use sagascript_core::llm;
+use sagascript_core::output;
+use sagascript_core::replacements;
+use sagascript_core::settings::{
+    CaptureSource, Settings, WhisperModel, MAX_MONITOR_VOLUME, MAX_SILENCE_STOP_SECS,
+};
+use sagascript_core::transcription::backend;
 use sagascript_core::transcription::model;
-use sagascript_core::transcription::WhisperBackend;
+use sagascript_core::transcription::rtf::{real_time_factor, RTF_SUGGESTION_THRESHOLD};
+use sagascript_core::transcription::timing::{self, Timing};
+use sagascript_core::transcription::{TranscribeOptions, WhisperBackend};
 
 use super::transcribe::{
-    copy_to_clipboard, model_id_string, parse_language, resolve_effective_model,
-    resolve_effective_prompt,
+    adapt_model_to_clip, add_to_history, copy_to_clipboard, model_id_string, parse_language,
+    resolve_custom_model, resolve_effective_model, resolve_effective_prompt, route_output,
 };
 
 #[derive(Args)]
@@ -26,15 +38,18 @@ pub struct RecordArgs {
     #[arg(short, long, value_name = "MODEL_ID")]
     pub model: Option<String>,
 
-    /// Max recording duration in seconds (default: record until Ctrl+C)
+    /// Max recording duration in seconds (default: record until Ctrl+C; the
+    /// recording stops at `max_recording_minutes` either way)
     #[arg(short, long, value_name = "SECONDS")]
     pub duration: Option<f64>,
 
-    /// Save audio to WAV file instead of transcribing
+    /// Save audio to a file instead of transcribing: WAV, or Ogg Opus when
+    /// PATH ends in .opus or .ogg (builds with the `opus` feature)
     #[arg(short, long, value_name = "PATH")]
     pub output: Option<String>,
 
-    /// Output result as JSON (includes text, language, model, duration)
+    /// Output result as JSON (includes text, language, model, duration, a
+    /// `words` array of `{text, start, end}` and a `timing` breakdown)
     #[arg(long)]
     pub json: bool,
 
@@ -42,6 +57,21 @@ pub struct RecordArgs {
     #[arg(long)]
     pub clipboard: bool,
 
+    /// Also send the result to the configured output sinks (clipboard, file,
+    /// named pipe, daily note, webhook, MQTT) — see
+    /// `sagascript config set output_sinks`
+    #[arg(long)]
+    pub route: bool,
+
+    /// Run the whole pipeline but only print the result: --clipboard and
+    /// --route are skipped. Defaults to the `dry_run` setting.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Don't add the transcript to the history [see: sagascript history]
+    #[arg(long)]
+    pub no_history: bool,
+
     /// Hint the decoder with domain-specific vocabulary (Whisper initial prompt).
     /// Reduces mishearings of proper nouns, foreign names, and jargon.
     /// Example: --hint "Notre Dame, Sara, Grimnir"
@@ -52,10 +82,94 @@ pub struct RecordArgs {
     /// Mutually exclusive with --hint/--prompt.
     #[arg(long, visible_alias = "hint-file", value_name = "PATH", conflicts_with = "prompt")]
     pub prompt_file: Option<PathBuf>,
+
+    /// Play the microphone back through the default output while recording
+    /// (use headphones). Defaults to the `monitor_input` setting.
+    #[arg(long, conflicts_with = "no_monitor")]
+    pub monitor: bool,
+
+    /// Don't monitor, even if the `monitor_input` setting is on
+    #[arg(long)]
+    pub no_monitor: bool,
+
+    /// Monitor gain, 0.0–2.0 (default: the `monitor_volume` setting)
+    #[arg(long, value_name = "GAIN", value_parser = parse_monitor_volume)]
+    pub monitor_volume: Option<f32>,
+
+    /// Stop once this many seconds pass without speech after you start
+    /// talking, 0 to never (default: the `silence_stop_secs` setting)
+    #[arg(long, value_name = "SECONDS", value_parser = parse_silence_stop)]
+    pub stop_on_silence: Option<u32>,
+
+    /// Dictation profile whose language, model and sound cue to use
+    /// [see: sagascript config profile]; --language and --model still win.
+    /// A --prompt given with it joins the profile's prompt history
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Record from this input device instead of the system default
+    /// [see: sagascript list-devices]
+    #[arg(long, value_name = "NAME")]
+    pub device: Option<String>,
+
+    /// Record the microphone or what the machine is playing (calls, videos)
+    /// [possible values: microphone, system] (default: the `capture_source`
+    /// setting)
+    #[arg(long, value_name = "SOURCE", value_parser = parse_source, conflicts_with = "device")]
+    pub source: Option<CaptureSource>,
+
+    /// Don't show the input level meter and elapsed time while recording
+    #[arg(short, long)]
+    pub quiet: bool,
+}
+
+/// Width of the level meter's bar, in characters.
+const METER_WIDTH: usize = 24;
+
+/// How long a recording may hear nothing before the user is warned that
+/// the input may be muted.
+const SILENT_INPUT_WARNING: std::time::Duration = std::time::Duration::from_secs(5);
+
+fn parse_monitor_volume(s: &str) -> Result<f32, String> {
+    let v: f32 = s.parse().map_err(|_| format!("'{s}' is not a valid number"))?;
+    if (0.0..=MAX_MONITOR_VOLUME).contains(&v) {
+        Ok(v)
+    } else {
+        Err(format!("monitor volume must be between 0.0 and {MAX_MONITOR_VOLUME}"))
+    }
 }
 
-pub fn run(args: RecordArgs) -> Result<(), DictationError> {
+fn parse_source(s: &str) -> Result<CaptureSource, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "microphone" | "mic" => Ok(CaptureSource::Microphone),
+        "system" => Ok(CaptureSource::System),
+        _ => Err(format!("'{s}' is not a capture source (expected microphone or system)")),
+    }
+}
+
+fn parse_silence_stop(s: &str) -> Result<u32, String> {
+    let v: u32 = s.parse().map_err(|_| format!("'{s}' is not a whole number of seconds"))?;
+    if v <= MAX_SILENCE_STOP_SECS {
+        Ok(v)
+    } else {
+        Err(format!("silence stop must be at most {MAX_SILENCE_STOP_SECS} seconds"))
+    }
+}
+
+pub fn run(args: RecordArgs, rt: &tokio::runtime::Runtime) -> Result<(), DictationError> {
     let stored = sagascript_core::settings::store::load();
+    let profile = match &args.profile {
+        Some(name) => Some(stored.profile(name).cloned().ok_or_else(|| {
+            DictationError::SettingsError(format!(
+                "No profile named '{name}'. Run 'sagascript config profile' to list them."
+            ))
+        })?),
+        None => None,
+    };
+    let stored = match &profile {
+        Some(profile) => stored.with_profile(profile),
+        None => stored,
+    };
     let language = match &args.language {
         Some(l) => parse_language(l)?,
         None => stored.language,
@@ -74,16 +188,35 @@ pub fn run(args: RecordArgs) -> Result<(), DictationError> {
             &stored.initial_prompt,
         )?
     };
+    // A prompt given for a profile joins that profile's prompt history, as
+    // setting one while dictating with it does in the GUI.
+    if let (Some(profile), Some(prompt)) = (&profile, &effective_prompt) {
+        if args.prompt.is_some() || args.prompt_file.is_some() {
+            if let Err(e) = sagascript_core::settings::store::update(|s| {
+                s.remember_initial_prompt(Some(&profile.name), prompt)
+            }) {
+                eprintln!("Warning: could not remember the prompt: {e}");
+            }
+        }
+    }
+
+    // A remote backend without an API key fails here, before recording.
+    let remote = if save_only {
+        None
+    } else {
+        backend::from_settings(&stored)?
+    };
 
     // Only validate model if we're going to transcribe
+    let custom = resolve_custom_model(args.model.as_deref(), &stored);
     let model = if !save_only {
         let m = resolve_effective_model(
-            args.model.as_deref(),
+            args.model.as_deref().filter(|_| custom.is_none()),
             language,
             stored.auto_select_model,
             stored.whisper_model,
         )?;
-        if !model::is_model_downloaded(m) {
+        if remote.is_none() && custom.is_none() && !model::is_model_downloaded(m) {
             return Err(DictationError::TranscriptionFailed(format!(
                 "Model '{}' is not downloaded. Run: sagascript download-model {}",
                 m.display_name(),
@@ -102,16 +235,59 @@ pub fn run(args: RecordArgs) -> Result<(), DictationError> {
 
     // Start recording
     let mut capture = AudioCaptureService::new();
+    let source = args.source.unwrap_or(stored.capture_source);
+    // Monitoring system audio would play it back into itself.
+    let monitor = !args.no_monitor
+        && (args.monitor || stored.monitor_input)
+        && (source == CaptureSource::Microphone || args.device.is_some());
+    if monitor {
+        capture.set_monitor(Some(args.monitor_volume.unwrap_or(stored.monitor_volume)));
+        eprintln!("Monitoring input on the default output device — use headphones.");
+    }
+    let max_recording = stored.max_recording();
+    capture.set_max_recording(max_recording);
+    let silence_stop = match args.stop_on_silence {
+        Some(0) => None,
+        Some(secs) => Some(std::time::Duration::from_secs(secs.into())),
+        None => stored.silence_stop(),
+    };
+    capture.set_silence_stop(silence_stop);
+    capture.set_device(args.device.clone());
+    capture.set_source(source);
     capture.start_capture()?;
+    if let Some(device) = &args.device {
+        eprintln!("Input: {device}");
+    } else if source == CaptureSource::System {
+        eprintln!("Input: system audio");
+    }
+    if let Some(profile) = &profile {
+        eprintln!("Profile: {}", profile.name);
+        sagascript_core::audio::cue::play(profile.sound_cue);
+    }
 
     if let Some(secs) = args.duration {
         eprintln!("Recording for {secs}s... (press Ctrl+C to stop early)");
     } else {
         eprintln!("Recording... press Ctrl+C to stop");
     }
+    if let Some(silence) = silence_stop {
+        eprintln!("Stops after {}s of silence.", silence.as_secs());
+    }
 
-    // Wait for duration or Ctrl+C
+    // Wait for duration or Ctrl+C, or until the buffer is full
     let start = std::time::Instant::now();
+    let limit_mins = max_recording.as_secs() / 60;
+    let mut warned = false;
+    let mut truncated = false;
+    // Live level meter and elapsed time; drawn only when stderr is a terminal.
+    let meter = if args.quiet {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new_spinner()
+    };
+    meter.set_style(ProgressStyle::with_template("{msg}").unwrap());
+    let mut heard = false;
+    let mut warned_silent = false;
     loop {
         std::thread::sleep(std::time::Duration::from_millis(50));
         if !running.load(Ordering::Relaxed) {
@@ -122,76 +298,268 @@ pub fn run(args: RecordArgs) -> Result<(), DictationError> {
                 break;
             }
         }
+        let peak = capture.recent_peak(std::time::Duration::from_millis(100));
+        heard |= peak.is_some_and(level::is_audible);
+        meter.set_message(meter_line(start.elapsed(), peak));
+        if !heard && !warned_silent && start.elapsed() >= SILENT_INPUT_WARNING {
+            warned_silent = true;
+            meter.suspend(|| {
+                eprintln!(
+                    "Warning: no sound from the input in {}s — is the microphone muted?",
+                    SILENT_INPUT_WARNING.as_secs()
+                )
+            });
+        }
+        if capture.silence_timed_out() {
+            meter.suspend(|| {
+                eprintln!(
+                    "No speech for {}s, stopping.",
+                    capture.trailing_silence().as_secs()
+                )
+            });
+            break;
+        }
+        match limit_remaining(start.elapsed(), max_recording) {
+            Some(left) if left.is_zero() => {
+                truncated = true;
+                break;
+            }
+            Some(left) if !warned => {
+                warned = true;
+                meter.suspend(|| {
+                    eprintln!(
+                        "{}s left before the {limit_mins}-minute recording limit.",
+                        left.as_secs()
+                    )
+                });
+            }
+            _ => {}
+        }
     }
+    meter.finish_and_clear();
 
+    let stop_started = std::time::Instant::now();
     let audio = capture.stop_capture()?;
+    let device = capture.device_name();
+    let mut timing = Timing {
+        capture_ms: timing::ms_since(start),
+        decode_ms: timing::ms_since(stop_started),
+        ..Timing::default()
+    };
+    // Still transcribed, but say the recording was cut short.
+    if truncated {
+        let err = DictationError::BufferFull(format!("stopped at the {limit_mins}-minute limit"));
+        eprintln!("Warning: {err}");
+        eprintln!("Hint: {}", err.suggestion());
+    }
     let duration = audio.len() as f64 / TARGET_SAMPLE_RATE as f64;
     eprintln!("Captured {:.1}s of audio ({} samples)", duration, audio.len());
 
     if audio.is_empty() {
         return Err(DictationError::NoAudioCaptured);
     }
+    if let Some(warning) = level::analyze(&audio) {
+        eprintln!("Warning: {}", warning.message());
+    }
 
-    // Save WAV if requested
+    // Save audio if requested
     if let Some(output_path) = &args.output {
-        let wav_bytes = sagascript_core::audio::wav::encode_wav(&audio);
-        std::fs::write(output_path, &wav_bytes).map_err(|e| {
-            DictationError::FileDecodeError(format!("Failed to write WAV: {e}"))
+        let bytes = encode_for_path(output_path, &audio)?;
+        std::fs::write(output_path, &bytes).map_err(|e| {
+            DictationError::FileDecodeError(format!("Failed to write {output_path}: {e}"))
         })?;
         eprintln!("Saved to {output_path}");
         return Ok(());
     }
 
     // Transcribe
-    let model = model.unwrap();
-    eprintln!("Loading model: {}...", model.display_name());
-    let backend = WhisperBackend::new();
-    backend.load_model(model)?;
+    let model = adapt_model_to_clip(
+        args.model.as_deref(),
+        language,
+        &stored,
+        model.unwrap(),
+        duration,
+        model::is_model_downloaded,
+    );
 
-    let prompt = effective_prompt.as_deref();
-    let text = if duration > 10.0 {
+    // Same decoding as dictation: the hint, saved keywords, number style and
+    // voice commands.
+    let opts = TranscribeOptions {
+        prompt: effective_prompt,
+        keywords: stored.keywords.clone(),
+        localize_numbers: stored.localize_numbers,
+        voice_commands: stored.voice_commands,
+        strip_punctuation: !stored.punctuate,
+        word_timestamps: args.json,
+        ..Default::default()
+    };
+    let inference_started = std::time::Instant::now();
+    let transcript = if let Some(remote) = &remote {
+        eprintln!("Transcribing with {}...", remote.name());
+        let transcript = rt.block_on(remote.transcribe(&audio, language))?;
+        backend::apply_text_options(transcript, &opts, language)
+    } else if duration > 10.0 {
+        let load_started = std::time::Instant::now();
+        let backend = load_backend(model, &stored, custom.clone())?;
+        timing.model_load_ms = timing::ms_since(load_started);
         let pb = ProgressBar::new(100);
         pb.set_style(
             ProgressStyle::with_template("  Transcribing [{bar:40}] {pos}%")
                 .unwrap(),
         );
         let pb_cb = pb.clone();
-        let text = backend.transcribe_sync_with_progress_and_prompt(
-            &audio,
-            language,
-            prompt,
-            move |pct| {
+        let transcript =
+            backend.transcribe_sync_with_options(&audio, language, &opts, move |pct| {
                 crate::set_transcription_progress(&pb_cb, pct);
-            },
-        )?;
+            })?;
         pb.finish_and_clear();
-        text
+        transcript
     } else {
+        let load_started = std::time::Instant::now();
+        let backend = load_backend(model, &stored, custom.clone())?;
+        timing.model_load_ms = timing::ms_since(load_started);
         eprintln!("Transcribing...");
-        backend.transcribe_sync_with_progress_and_prompt(&audio, language, prompt, |_| {})?
+        backend.transcribe_sync_with_options(&audio, language, &opts, |_| {})?
+    };
+    timing.inference_ms = timing::ms_since(inference_started) - timing.model_load_ms;
+    if transcript.is_silent() {
+        return Err(DictationError::NoSpeechDetected);
+    }
+    let post_process_started = std::time::Instant::now();
+    let text = match llm::PostProcess::from_settings(&stored) {
+        Some(step) if !transcript.text.trim().is_empty() => {
+            eprintln!("Post-processing...");
+            rt.block_on(step.run(&transcript.text)).unwrap_or_else(|e| {
+                eprintln!("Warning: {e}. Keeping the raw transcript.");
+                transcript.text.clone()
+            })
+        }
+        _ => transcript.text.clone(),
+    };
+    let text = replacements::apply(&text, &stored.replacement_rules);
+    timing.post_process_ms = timing::ms_since(post_process_started);
+    let timing = timing.finish();
+    eprintln!("Done in {}", timing.summary());
+    let rtf = real_time_factor(duration as f32, inference_started.elapsed());
+    let model_name = match &remote {
+        Some(remote) => remote.model().to_string(),
+        None if custom.is_some() => args
+            .model
+            .clone()
+            .unwrap_or_else(|| stored.custom_model.clone()),
+        None => model_id_string(model),
     };
 
+    // Same guardrail as the desktop app, but per run: the CLI has no history
+    // to smooth over, so it only hints and never changes the saved model.
+    if let (Some(rtf), Some(smaller), None) = (rtf, model.smaller(), &remote) {
+        if rtf > RTF_SUGGESTION_THRESHOLD {
+            eprintln!(
+                "Hint: transcription took {rtf:.1}x the recording length on {}. \
+                 For faster dictation try --model {}",
+                model.display_name(),
+                model_id_string(smaller)
+            );
+        }
+    }
+
     // Output
     if args.json {
         let json = serde_json::json!({
             "text": text,
             "language": language,
-            "model": model_id_string(model),
+            "model": model_name,
             "duration_seconds": duration,
+            "real_time_factor": rtf,
+            "timing": timing,
+            "words": transcript.segments.iter().flat_map(|s| &s.words).collect::<Vec<_>>(),
+            "source": HistorySource::Cli,
+            "device": device,
         });
         println!("{}", serde_json::to_string_pretty(&json).unwrap());
     } else {
         println!("{text}");
     }
 
-    if args.clipboard {
+    if !args.no_history {
+        add_to_history(
+            &HistoryEntry::new(HistorySource::Cli, duration as f32, model, language, &text)
+                .with_device(device),
+        );
+    }
+
+    let dry_run = args.dry_run || stored.dry_run;
+    if dry_run && (args.clipboard || args.route) {
+        eprintln!("Dry run: not copying or routing the result.");
+    }
+
+    if args.clipboard && !dry_run {
         copy_to_clipboard(&text)?;
         eprintln!("Copied to clipboard.");
     }
 
+    if args.route && !dry_run {
+        route_output(
+            &stored.output_sinks,
+            &output::Entry::new(text, language, model),
+        );
+    }
+
     Ok(())
 }
 
+/// One line of the level meter: elapsed time, a bar for the input's recent
+/// peak on a dBFS scale, and the level itself.
+fn meter_line(elapsed: std::time::Duration, peak: Option<f32>) -> String {
+    let secs = elapsed.as_secs();
+    let time = format!("{}:{:02}", secs / 60, secs % 60);
+    let Some(peak) = peak else {
+        return format!("{time}  waiting for the input...");
+    };
+    let db = level::peak_dbfs(peak);
+    let filled = ((1.0 - db / level::METER_FLOOR_DB) * METER_WIDTH as f32).round() as usize;
+    let bar = "█".repeat(filled) + &"░".repeat(METER_WIDTH - filled);
+    let clipping = if level::is_clipping(peak) {
+        "  clipping"
+    } else {
+        ""
+    };
+    format!("{time}  {bar} {db:>4.0} dB{clipping}")
+}
+
+fn load_backend(
+    model: WhisperModel,
+    stored: &Settings,
+    custom: Option<PathBuf>,
+) -> Result<WhisperBackend, DictationError> {
+    match &custom {
+        Some(path) => eprintln!("Loading custom model: {}...", path.display()),
+        None => eprintln!("Loading model: {}...", model.display_name()),
+    }
+    let backend = WhisperBackend::with_gpu(stored.use_gpu);
+    backend.set_custom_model(custom);
+    backend.load_model(model)?;
+    Ok(backend)
+}
+
+/// Encode the recording for `--output`, choosing Ogg Opus by file extension.
+fn encode_for_path(path: &str, audio: &[f32]) -> Result<Vec<u8>, DictationError> {
+    let ext = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match ext.as_deref() {
+        #[cfg(feature = "opus")]
+        Some("opus" | "ogg") => sagascript_core::audio::opus::encode_ogg_opus(audio),
+        #[cfg(not(feature = "opus"))]
+        Some("opus" | "ogg") => Err(DictationError::UnsupportedFormat(
+            "this build has no Opus encoder (rebuild with --features opus), save as .wav instead"
+                .to_string(),
+        )),
+        _ => Ok(sagascript_core::audio::wav::encode_wav(audio)),
+    }
+}
+
 fn ctrlc_handler(running: Arc<AtomicBool>) {
     let _ = ctrlc::set_handler(move || {
         running.store(false, Ordering::Relaxed);
@@ -0,0 +1,67 @@
+use clap::Args;
+
+use sagascript_core::error::DictationError;
+use sagascript_core::recovery::{self, RecoveryStage};
+use sagascript_core::transcription::catalog;
+
+#[derive(Args)]
+pub struct RecoverArgs {
+    /// Print only the path of the saved audio, for 'sagascript transcribe'
+    #[arg(long, conflicts_with_all = ["discard", "json"])]
+    pub path: bool,
+
+    /// Delete the saved session
+    #[arg(long, conflicts_with = "json")]
+    pub discard: bool,
+
+    /// Output the session as JSON (the same record the GUI uses)
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run(args: RecoverArgs) -> Result<(), DictationError> {
+    let dir = recovery::crashed_dir();
+    let session = recovery::load(&dir);
+
+    if args.discard {
+        recovery::clear(&dir)
+            .map_err(|e| DictationError::SettingsError(format!("Failed to discard: {e}")))?;
+        if session.is_some() {
+            eprintln!("Discarded the interrupted dictation");
+        }
+        return Ok(());
+    }
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&session)
+            .map_err(|e| DictationError::SettingsError(format!("JSON error: {e}")))?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    let Some(session) = session else {
+        eprintln!("No interrupted dictation to recover");
+        return Ok(());
+    };
+    let audio = recovery::audio_path(&dir);
+    if args.path {
+        println!("{}", audio.display());
+        return Ok(());
+    }
+
+    let stage = match session.stage {
+        RecoveryStage::Recording => "while recording",
+        RecoveryStage::Transcribing => "while transcribing",
+    };
+    let language = session.language.whisper_code().unwrap_or("auto");
+    let model = catalog::model_id(session.model);
+    println!("Interrupted {stage}: {:.1}s of audio", session.audio_secs);
+    println!("Language:  {language}");
+    println!("Model:     {model}");
+    println!("Audio:     {}", audio.display());
+    eprintln!(
+        "\nTranscribe it with: sagascript transcribe \"{}\" --language {language} --model {model}",
+        audio.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use clap::Args;
+
+use sagascript_core::error::DictationError;
+use sagascript_core::settings;
+use sagascript_core::transcription::{AccelerationInfo, RuntimeInfo};
+
+use crate::transcribe::parse_model;
+
+#[derive(Args)]
+pub struct RuntimeArgs {
+    /// Describe this model instead of the configured one
+    #[arg(short, long, value_name = "MODEL_ID")]
+    pub model: Option<String>,
+
+    /// Output JSON (the same object the GUI's diagnostics use)
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run(args: RuntimeArgs) -> Result<(), DictationError> {
+    let stored = settings::store::load();
+    let model = match args.model.as_deref() {
+        Some(id) => parse_model(id)?,
+        None => stored.effective_model(),
+    };
+    let info = RuntimeInfo::collect(model, false);
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&info)
+            .map_err(|e| DictationError::SettingsError(format!("JSON error: {e}")))?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    println!(
+        "whisper-rs      {} (whisper-rs-sys {})",
+        info.whisper_rs_version, info.whisper_rs_sys_version
+    );
+    println!("Backends        {}", list(&info.backends));
+    println!("CPU features    {}", list(&info.cpu_features));
+    println!("Threads         {}", info.threads);
+    let gpu = AccelerationInfo::collect(stored.use_gpu, None);
+    match (&gpu.gpu_backend, gpu.use_gpu) {
+        (Some(backend), true) => println!("GPU             {backend}"),
+        (Some(backend), false) => {
+            println!("GPU             off (use_gpu = false; {backend} available)")
+        }
+        (None, _) => println!("GPU             none in this build (CPU only)"),
+    }
+    println!();
+    let model = &info.model;
+    println!("Model           {}", model.name);
+    println!("Path            {}", model.path.display());
+    match model.size_bytes {
+        Some(bytes) => println!("Size            {:.1} MB", bytes as f64 / 1_000_000.0),
+        None => println!("Size            not downloaded"),
+    }
+    if cfg!(target_os = "macos") {
+        let coreml = if model.coreml_encoder { "yes" } else { "no" };
+        println!("CoreML encoder  {coreml}");
+    }
+    Ok(())
+}
+
+fn list(items: &[String]) -> String {
+    if items.is_empty() {
+        "-".to_string()
+    } else {
+        items.join(", ")
+    }
+}
@@ -0,0 +1,53 @@
+use clap::Args;
+
+use sagascript_core::cli_install;
+use sagascript_core::error::DictationError;
+use sagascript_core::self_update::{self, CURRENT_VERSION};
+
+#[derive(Args)]
+pub struct SelfUpdateArgs {
+    /// Only report whether a newer release exists; change nothing
+    #[arg(long)]
+    pub check: bool,
+}
+
+pub async fn run(args: SelfUpdateArgs) -> Result<(), DictationError> {
+    let exe = cli_install::app_binary()
+        .map_err(|e| DictationError::UpdateError(format!("can't locate this binary: {e}")))?;
+
+    let release = self_update::latest_release().await?;
+    if !release.is_update() {
+        eprintln!("sagascript {CURRENT_VERSION} is up to date.");
+        return Ok(());
+    }
+    eprintln!("Update available: {CURRENT_VERSION} -> {}", release.version);
+    if args.check {
+        if let Some(manager) = self_update::managed_by(&exe) {
+            eprintln!(
+                "This binary is managed by {}; {} instead.",
+                manager.name, manager.update
+            );
+        } else if !release.has_binary() {
+            eprintln!(
+                "The release has no {} build; download it from the releases page.",
+                self_update::asset_name()
+            );
+        }
+        // Machine-readable: just the new version on stdout.
+        println!("{}", release.version);
+        return Ok(());
+    }
+
+    self_update::install(&release, &exe, |downloaded, total| {
+        let pct = (downloaded as f64 / total.max(1) as f64 * 100.0).min(100.0) as u32;
+        eprint!(
+            "\r  Downloading: {:.1}/{:.1} MB ({pct}%)",
+            downloaded as f64 / 1_048_576.0,
+            total as f64 / 1_048_576.0
+        );
+    })
+    .await?;
+    eprintln!();
+    eprintln!("Updated {} to {}.", exe.display(), release.version);
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use clap::Args;
+
+use sagascript_core::error::DictationError;
+use sagascript_core::sessions::{self, SessionRecord};
+
+#[derive(Args)]
+pub struct SessionsArgs {
+    /// Number of sessions to show, newest first
+    #[arg(short = 'n', long, default_value_t = 20)]
+    pub limit: usize,
+
+    /// Only show sessions that failed or never finished
+    #[arg(long)]
+    pub failed: bool,
+
+    /// Output a JSON array (the same records the GUI timeline uses)
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run(args: SessionsArgs) -> Result<(), DictationError> {
+    let dir = sessions::log_directory();
+    let mut records = sessions::timeline(&dir, usize::MAX);
+    if args.failed {
+        records.retain(|r| {
+            matches!(
+                r.outcome,
+                sessions::SessionOutcome::Failed | sessions::SessionOutcome::Unfinished
+            )
+        });
+    }
+    records.truncate(args.limit);
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&records)
+            .map_err(|e| DictationError::SettingsError(format!("JSON error: {e}")))?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    if records.is_empty() {
+        eprintln!("No dictation sessions in {}", dir.display());
+        return Ok(());
+    }
+
+    println!(
+        "{:<20} {:<11} {:>9} {:>9} {:>5} {:<18} DETAIL",
+        "STARTED", "OUTCOME", "RECORDED", "TOTAL", "WPM", "MODEL"
+    );
+    println!("{}", "-".repeat(86));
+    for record in &records {
+        println!(
+            "{:<20} {:<11} {:>9} {:>9} {:>5} {:<18} {}",
+            started_label(&record.started_at),
+            record.outcome.label(),
+            format_ms(record.recording_ms),
+            format_ms(record.duration_ms),
+            format_wpm(record.wpm),
+            record.model.as_deref().unwrap_or("-"),
+            detail(record),
+        );
+    }
+    if let Some(pace) = sessions::pace_summary(&records) {
+        eprintln!(
+            "\nAverage pace: {:.0} words per minute over {} dictations",
+            pace.wpm, pace.sessions
+        );
+    }
+    Ok(())
+}
+
+/// `2026-03-01T10:00:00.000Z` -> `2026-03-01 10:00:00`.
+fn started_label(ts: &str) -> String {
+    ts.get(..19).unwrap_or(ts).replacen('T', " ", 1)
+}
+
+fn format_ms(ms: Option<u64>) -> String {
+    match ms {
+        Some(ms) => format!("{:.1}s", ms as f64 / 1000.0),
+        None => "-".to_string(),
+    }
+}
+
+fn format_wpm(wpm: Option<f32>) -> String {
+    match wpm {
+        Some(wpm) => format!("{wpm:.0}"),
+        None => "-".to_string(),
+    }
+}
+
+fn detail(record: &SessionRecord) -> String {
+    if let Some(error) = &record.error {
+        return error.clone();
+    }
+    match record.chars {
+        Some(chars) => format!("{chars} chars"),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sagascript_core::sessions::SessionOutcome;
+
+    fn record(error: Option<&str>, chars: Option<usize>) -> SessionRecord {
+        SessionRecord {
+            id: "dict-aaaaaaaa".to_string(),
+            started_at: "2026-03-01T10:00:00.000Z".to_string(),
+            ended_at: None,
+            outcome: SessionOutcome::Failed,
+            recording_ms: None,
+            duration_ms: None,
+            model: None,
+            chars,
+            words: None,
+            wpm: None,
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn started_label_trims_to_seconds() {
+        assert_eq!(
+            started_label("2026-03-01T10:00:00.000Z"),
+            "2026-03-01 10:00:00"
+        );
+        assert_eq!(started_label("short"), "short");
+    }
+
+    #[test]
+    fn format_ms_uses_seconds() {
+        assert_eq!(format_ms(Some(1530)), "1.5s");
+        assert_eq!(format_ms(None), "-");
+        assert_eq!(format_wpm(Some(142.6)), "143");
+        assert_eq!(format_wpm(None), "-");
+    }
+
+    #[test]
+    fn detail_prefers_error() {
+        assert_eq!(
+            detail(&record(Some("No audio captured"), Some(3))),
+            "No audio captured"
+        );
+        assert_eq!(detail(&record(None, Some(12))), "12 chars");
+        assert_eq!(detail(&record(None, None)), "");
+    }
+}
@@ -0,0 +1,21 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use sagascript_core::error::DictationError;
+use sagascript_core::support_bundle;
+
+#[derive(Args)]
+pub struct SupportBundleArgs {
+    /// Zip archive to write (replaced if it exists)
+    pub path: PathBuf,
+}
+
+pub fn run(args: SupportBundleArgs) -> Result<(), DictationError> {
+    let files = support_bundle::write_bundle(&args.path)?;
+    for file in &files {
+        eprintln!("  {file}");
+    }
+    eprintln!("Wrote {}", args.path.display());
+    Ok(())
+}
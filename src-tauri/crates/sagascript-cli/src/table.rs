@@ -0,0 +1,274 @@
+//! Human-readable tables shared by `config list` and `list-models`.
+//!
+//! Output fits the terminal: when stdout is a terminal, the widest columns
+//! are cut down (with `…`) until a row fits its width. Piped output is never
+//! truncated, and `--no-header` plus `--columns` make it easy to cut up
+//! with shell tools. Scripts that need a stable format should still use
+//! `--porcelain`.
+
+use std::io::IsTerminal;
+
+use clap::Args;
+
+use sagascript_core::error::DictationError;
+
+/// Narrowest a column is cut down to, ellipsis included.
+const MIN_WIDTH: usize = 4;
+
+/// Space between columns.
+const GAP: &str = "  ";
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct TableArgs {
+    /// Leave out the header and the rule under it
+    #[arg(long)]
+    pub no_header: bool,
+
+    /// Only show these columns, in this order (comma-separated, e.g. id,size)
+    #[arg(long, value_name = "COLUMNS", value_delimiter = ',')]
+    pub columns: Vec<String>,
+
+    /// Cut rows to this many characters (0: never). Defaults to the terminal
+    /// width when stdout is a terminal, otherwise rows are never cut
+    #[arg(long, value_name = "CHARS")]
+    pub width: Option<usize>,
+}
+
+impl TableArgs {
+    /// Width to fit rows into, if any.
+    fn max_width(&self) -> Option<usize> {
+        match self.width {
+            Some(0) => None,
+            Some(width) => Some(width),
+            None if std::io::stdout().is_terminal() => console::Term::stdout()
+                .size_checked()
+                .map(|(_, cols)| cols as usize),
+            None => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// A column: the name `--columns` selects it by, its header and alignment.
+pub struct Column {
+    pub id: &'static str,
+    pub header: &'static str,
+    pub align: Align,
+}
+
+impl Column {
+    pub const fn left(id: &'static str, header: &'static str) -> Self {
+        Self {
+            id,
+            header,
+            align: Align::Left,
+        }
+    }
+
+    pub const fn right(id: &'static str, header: &'static str) -> Self {
+        Self {
+            id,
+            header,
+            align: Align::Right,
+        }
+    }
+}
+
+pub struct Table<'a> {
+    columns: &'a [Column],
+    rows: Vec<Vec<String>>,
+}
+
+impl<'a> Table<'a> {
+    pub fn new(columns: &'a [Column]) -> Self {
+        Self {
+            columns,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Add a row; one cell per column, in column order.
+    pub fn row(&mut self, cells: Vec<String>) {
+        debug_assert_eq!(cells.len(), self.columns.len());
+        self.rows.push(cells);
+    }
+
+    pub fn print(&self, args: &TableArgs) -> Result<(), DictationError> {
+        let rendered = self.render(args, args.max_width())?;
+        if !rendered.is_empty() {
+            println!("{rendered}");
+        }
+        Ok(())
+    }
+
+    fn render(&self, args: &TableArgs, max_width: Option<usize>) -> Result<String, DictationError> {
+        let selected = self.select(&args.columns)?;
+        let mut widths: Vec<usize> = selected
+            .iter()
+            .map(|&c| {
+                self.rows
+                    .iter()
+                    .map(|row| row[c].chars().count())
+                    .chain((!args.no_header).then_some(self.columns[c].header.len()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        if let Some(max) = max_width {
+            fit(&mut widths, max);
+        }
+
+        let mut lines = Vec::with_capacity(self.rows.len() + 2);
+        if !args.no_header {
+            let headers: Vec<String> = selected
+                .iter()
+                .map(|&c| self.columns[c].header.to_string())
+                .collect();
+            lines.push(self.line(&selected, &widths, &headers));
+            let rule = widths.iter().sum::<usize>() + GAP.len() * widths.len().saturating_sub(1);
+            lines.push("-".repeat(rule));
+        }
+        for row in &self.rows {
+            lines.push(self.line(&selected, &widths, row));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// Indices of the columns to show, in `--columns` order (all by default).
+    fn select(&self, names: &[String]) -> Result<Vec<usize>, DictationError> {
+        if names.is_empty() {
+            return Ok((0..self.columns.len()).collect());
+        }
+        names
+            .iter()
+            .map(|name| {
+                let name = name.trim();
+                self.columns
+                    .iter()
+                    .position(|c| c.id.eq_ignore_ascii_case(name))
+                    .ok_or_else(|| {
+                        let valid: Vec<&str> = self.columns.iter().map(|c| c.id).collect();
+                        DictationError::SettingsError(format!(
+                            "Unknown column '{name}'. Valid: {}",
+                            valid.join(", ")
+                        ))
+                    })
+            })
+            .collect()
+    }
+
+    fn line(&self, selected: &[usize], widths: &[usize], cells: &[String]) -> String {
+        let last = selected.len().saturating_sub(1);
+        let parts: Vec<String> = selected
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(i, (&c, &width))| {
+                let cell = truncate(&cells[c], width);
+                match self.columns[c].align {
+                    // No trailing padding on the last column.
+                    Align::Left if i == last => cell,
+                    Align::Left => format!("{cell:<width$}"),
+                    Align::Right => format!("{cell:>width$}"),
+                }
+            })
+            .collect();
+        parts.join(GAP)
+    }
+}
+
+/// Narrow the widest columns, one character at a time, until a row fits in
+/// `max` or every column is down to [`MIN_WIDTH`].
+fn fit(widths: &mut [usize], max: usize) {
+    let gaps = GAP.len() * widths.len().saturating_sub(1);
+    while widths.iter().sum::<usize>() + gaps > max {
+        let Some(widest) = widths
+            .iter_mut()
+            .filter(|w| **w > MIN_WIDTH)
+            .max_by_key(|w| **w)
+        else {
+            break;
+        };
+        *widest -= 1;
+    }
+}
+
+fn truncate(cell: &str, width: usize) -> String {
+    if cell.chars().count() <= width {
+        return cell.to_string();
+    }
+    let mut cut: String = cell.chars().take(width.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLUMNS: [Column; 3] = [
+        Column::left("key", "KEY"),
+        Column::left("value", "VALUE"),
+        Column::right("size", "SIZE"),
+    ];
+
+    fn table() -> Table<'static> {
+        let mut table = Table::new(&COLUMNS);
+        table.row(vec!["language".into(), "en".into(), "1".into()]);
+        table.row(vec![
+            "initial_prompt".into(),
+            "Notre Dame, Sara, Estrid".into(),
+            "22".into(),
+        ]);
+        table
+    }
+
+    #[test]
+    fn renders_header_rule_and_aligned_rows() {
+        let out = table().render(&TableArgs::default(), None).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "KEY             VALUE                     SIZE");
+        assert_eq!(lines[1], "-".repeat(lines[0].len()));
+        assert_eq!(lines[2], "language        en                           1");
+    }
+
+    #[test]
+    fn selects_columns_in_the_given_order() {
+        let args = TableArgs {
+            no_header: true,
+            columns: vec!["SIZE".into(), "key".into()],
+            width: None,
+        };
+        let out = table().render(&args, None).unwrap();
+        assert_eq!(out, " 1  language\n22  initial_prompt");
+    }
+
+    #[test]
+    fn unknown_column_is_an_error() {
+        let args = TableArgs {
+            columns: vec!["colour".into()],
+            ..Default::default()
+        };
+        let err = table().render(&args, None).unwrap_err().to_string();
+        assert!(err.contains("key, value, size"), "{err}");
+    }
+
+    #[test]
+    fn narrow_width_truncates_the_widest_column() {
+        let args = TableArgs {
+            no_header: true,
+            ..Default::default()
+        };
+        let out = table().render(&args, Some(30)).unwrap();
+        for line in out.lines() {
+            assert!(line.chars().count() <= 30, "{line:?}");
+        }
+        assert!(out.contains('…'));
+        assert!(out.starts_with("language"));
+    }
+}
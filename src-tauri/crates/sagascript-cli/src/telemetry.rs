@@ -0,0 +1,88 @@
+use clap::{Args, Subcommand};
+
+use sagascript_core::error::DictationError;
+use sagascript_core::settings;
+use sagascript_core::telemetry::{self, DailyCounts};
+
+#[derive(Args)]
+pub struct TelemetryArgs {
+    #[command(subcommand)]
+    pub action: TelemetryAction,
+}
+
+#[derive(Subcommand)]
+pub enum TelemetryAction {
+    /// Show everything queued, exactly as it would be sent
+    Show {
+        /// Print the JSON payload `send` would upload
+        #[arg(long)]
+        json: bool,
+    },
+    /// Delete the queue without sending it
+    Clear,
+    /// Upload the queue to `telemetry_endpoint`, then drop what was sent
+    Send,
+}
+
+pub async fn run(args: TelemetryArgs) -> Result<(), DictationError> {
+    let path = telemetry::queue_path();
+    match args.action {
+        TelemetryAction::Show { json } => {
+            let queue = telemetry::load_queue(&path);
+            if json {
+                let json = serde_json::to_string_pretty(&queue)
+                    .map_err(|e| DictationError::SettingsError(format!("JSON error: {e}")))?;
+                println!("{json}");
+            } else {
+                show(&queue);
+            }
+            if !telemetry::is_enabled() {
+                eprintln!("Telemetry is off; nothing new is being counted.");
+            }
+            Ok(())
+        }
+        TelemetryAction::Clear => {
+            telemetry::clear_queue(&path).map_err(|e| {
+                DictationError::SettingsError(format!("Failed to clear {}: {e}", path.display()))
+            })?;
+            eprintln!("Telemetry queue cleared.");
+            Ok(())
+        }
+        TelemetryAction::Send => {
+            let endpoint = settings::store::load().telemetry_endpoint;
+            let sent = telemetry::send(&endpoint, &path).await?;
+            if sent == 0 {
+                eprintln!("Nothing queued.");
+            } else {
+                eprintln!("Sent {sent} day(s) of counts to {}.", endpoint.trim());
+            }
+            Ok(())
+        }
+    }
+}
+
+fn show(queue: &[DailyCounts]) {
+    if queue.is_empty() {
+        eprintln!("Nothing queued.");
+        return;
+    }
+    for day in queue {
+        println!("{}", day.day);
+        print_counts("dictations", day.dictations.iter());
+        print_counts("models", day.models.iter());
+        if day.file_transcriptions > 0 {
+            println!("  file transcriptions: {}", day.file_transcriptions);
+        }
+        print_counts("errors", day.errors.iter());
+    }
+}
+
+fn print_counts<'a>(label: &str, counts: impl Iterator<Item = (&'a String, &'a u64)>) {
+    let line = counts
+        .map(|(key, count)| format!("{key} {count}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if !line.is_empty() {
+        println!("  {label}: {line}");
+    }
+}
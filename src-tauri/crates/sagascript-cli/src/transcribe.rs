@@ -1,40 +1,88 @@
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
 
 use clap::Args;
 
 use indicatif::{ProgressBar, ProgressStyle};
 
-use sagascript_core::audio::decoder::decode_audio_file;
+use sagascript_core::audio::decoder::{
+    decode_audio_file_channels, decode_audio_file_with_fallback, probe_duration,
+    STREAM_DECODE_AFTER_SECS,
+};
 use sagascript_core::error::DictationError;
-use sagascript_core::settings::{Language, WhisperModel};
+use sagascript_core::history::{self, HistoryEntry, HistorySource};
+use sagascript_core::minutes::{self, Turn};
+use sagascript_core::output::{self, OutputSink};
+use sagascript_core::replacements;
+use sagascript_core::settings::{Language, Settings, WhisperModel};
+use sagascript_core::telemetry::{self, TelemetryEvent};
+use sagascript_core::transcription::channels::{channel_label, channel_transcript, merge_channels};
 use sagascript_core::transcription::model;
+use sagascript_core::transcription::throttle::{self, Throttle};
 use sagascript_core::transcription::{
-    TranscribeOptions, WhisperBackend, normalize_nonspeech_markers,
+    JobAbort, TranscribeOptions, WhisperBackend, WordTiming, normalize_nonspeech_markers,
 };
 
+use crate::config::format_enum_value;
+use crate::porcelain::{self, seconds};
+use crate::table::{Column, Table, TableArgs};
+
 #[derive(Args)]
 pub struct TranscribeArgs {
-    /// Path to the audio/video file to transcribe
-    pub file: PathBuf,
+    /// Audio/video files to transcribe. Glob patterns ('calls/*.m4a') are
+    /// expanded for shells that don't
+    #[arg(required = true, value_name = "FILE")]
+    pub files: Vec<PathBuf>,
 
     /// Language for transcription [possible values: en, sv, no, auto (less accurate)]
-    #[arg(short, long, value_name = "LANG")]
+    #[arg(short, long, value_name = "LANG", env = "SAGASCRIPT_LANGUAGE")]
     pub language: Option<String>,
 
     /// Whisper model ID to use [see: sagascript list-models]
-    #[arg(short, long, value_name = "MODEL_ID")]
+    #[arg(short, long, value_name = "MODEL_ID", env = "SAGASCRIPT_MODEL")]
     pub model: Option<String>,
 
+    /// Ignore the persisted settings and use only flags, environment
+    /// variables, and built-in defaults, so scripts and CI get the same
+    /// result whatever the local GUI user has configured
+    #[arg(long, env = "SAGASCRIPT_NO_CONFIG")]
+    pub no_config: bool,
+
     /// Output result as JSON: text, language, model, duration, and a
     /// `segments` array with per-segment timing and confidence
-    /// (avg_logprob, no_speech_prob) for flagging low-confidence spans.
+    /// (avg_logprob, no_speech_prob) for flagging low-confidence spans, plus
+    /// a `words` array of `{text, start, end}` for alignment and captions.
     #[arg(long)]
     pub json: bool,
 
+    /// Print stable, versioned records for scripts instead of text: file,
+    /// language, model, duration, one `segment` per segment, then `text`.
+    /// The format only changes with a new VERSION (currently 1).
+    #[arg(
+        long,
+        value_name = "VERSION",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "1",
+        conflicts_with = "json"
+    )]
+    pub porcelain: Option<porcelain::Version>,
+
     /// Copy transcription result to clipboard
     #[arg(long)]
     pub clipboard: bool,
 
+    /// Also send the result to the configured output sinks (clipboard, file,
+    /// named pipe, daily note, webhook, MQTT) — see
+    /// `sagascript config set output_sinks`
+    #[arg(long, conflicts_with = "no_config")]
+    pub route: bool,
+
+    /// Don't add the transcript to the history [see: sagascript history]
+    #[arg(long)]
+    pub no_history: bool,
+
     /// Enable speaker diarization (requires diarization models — run: sagascript download-model diarization)
     #[cfg(feature = "diarization")]
     #[arg(long)]
@@ -60,6 +108,13 @@ pub struct TranscribeArgs {
     #[arg(long, visible_alias = "hint-file", value_name = "PATH", conflicts_with = "prompt")]
     pub prompt_file: Option<PathBuf>,
 
+    /// Bias decoding toward WORD token by token — stronger than --hint for
+    /// call signs, SKUs and drug names. Repeatable. Replaces the saved
+    /// `keywords` setting for this run.
+    /// Example: --keyword Alpha-7 --keyword metoprolol
+    #[arg(long = "keyword", value_name = "WORD")]
+    pub keywords: Vec<String>,
+
     /// Enable voice activity detection (Silero VAD) to skip non-speech regions,
     /// reducing silence hallucination and repetition loops. Downloads a small
     /// model on first use. Overrides the `vad_enabled` setting.
@@ -76,23 +131,156 @@ pub struct TranscribeArgs {
     /// (pass --beam 0 to force greedy).
     #[arg(long = "beam", value_name = "N")]
     pub beam_size: Option<u32>,
+
+    /// If the built-in decoder can't read the file, retry through an installed
+    /// ffmpeg (found via SAGASCRIPT_FFMPEG, PATH, or Homebrew). Overrides the
+    /// `ffmpeg_fallback` setting for this run.
+    #[arg(long)]
+    pub ffmpeg: bool,
+
+    /// Write spoken numbers, decimals and dates the way the transcription
+    /// language does ("tjugofem komma fem" → "25,5"). Overrides the
+    /// `localize_numbers` setting for this run.
+    #[arg(long)]
+    pub localize_numbers: bool,
+
+    /// Transcribe each channel on its own and merge them in time order as
+    /// `[CH1] …` / `[CH2] …` lines, for call recordings with a speaker per
+    /// channel. Overrides the `split_channels` setting for this run.
+    #[arg(long)]
+    #[cfg_attr(feature = "diarization", arg(conflicts_with = "diarize"))]
+    pub split_channels: bool,
+
+    /// Print meeting minutes in Markdown instead of the transcript: who
+    /// spoke and for how long, action items found by keyword ("I'll",
+    /// "follow up", "jag ska", ...), and the timestamped transcript. Needs
+    /// --diarize or --split-channels
+    #[arg(long, conflicts_with_all = ["json", "porcelain"])]
+    pub minutes: bool,
+
+    /// With several files: write each transcript (FILE.txt, or FILE.json
+    /// with --json, FILE.md with --minutes) into DIR instead of next to its
+    /// file
+    #[arg(long, value_name = "DIR")]
+    pub output_dir: Option<PathBuf>,
+
+    /// With several files: transcribe N at a time. Each job keeps its own
+    /// copy of the model in memory
+    #[arg(short, long, value_name = "N", default_value_t = 1,
+          value_parser = clap::value_parser!(u16).range(1..=16))]
+    pub jobs: u16,
+
+    /// With several files: pause before each file or chunk while the
+    /// system's load average per core is above LOAD (for up to a minute at a
+    /// time); 0 to ignore load. A dictation in the app always pauses a batch
+    /// until it's done
+    #[arg(long, value_name = "LOAD", default_value_t = throttle::DEFAULT_MAX_LOAD)]
+    pub max_load: f64,
+}
+
+/// What every file of a run shares: settings, language, the model picked
+/// up front, hint and VAD model.
+struct Job {
+    stored: Settings,
+    language: Language,
+    model: WhisperModel,
+    /// Custom model file loaded in place of `model`'s (`--model` or the
+    /// `custom_model` setting).
+    custom: Option<PathBuf>,
+    prompt: Option<String>,
+    vad_model_path: Option<String>,
+}
+
+/// One file's result: the plain transcript (for the clipboard and sinks)
+/// and the output `--json`/`--porcelain` asked for.
+struct Transcribed {
+    text: String,
+    output: String,
+    model: WhisperModel,
+    duration: f64,
+}
+
+impl Transcribed {
+    /// The transcript as the output sinks get it.
+    fn entry(&self, language: Language) -> output::Entry {
+        output::Entry::new(self.text.clone(), language, self.model)
+    }
+
+    /// The transcript as the history keeps it, naming `file`.
+    fn history_entry(&self, file: &Path, language: Language) -> HistoryEntry {
+        let name = file.file_name().map_or_else(
+            || file.display().to_string(),
+            |n| n.to_string_lossy().into_owned(),
+        );
+        HistoryEntry::new(
+            HistorySource::Cli,
+            self.duration as f32,
+            self.model,
+            language,
+            &self.text,
+        )
+        .with_file(name)
+    }
 }
 
 pub fn run(args: TranscribeArgs) -> Result<(), DictationError> {
-    let stored = sagascript_core::settings::store::load();
+    let files = expand_files(&args.files)?;
+    if files.len() > 1 && args.clipboard {
+        return Err(DictationError::SettingsError(
+            "--clipboard takes a single file; with several, use --route or the per-file outputs"
+                .to_string(),
+        ));
+    }
+    let job = prepare(&args)?;
+
+    let [file] = files.as_slice() else {
+        return run_batch(&args, &job, &files);
+    };
+    let backend = WhisperBackend::with_gpu(job.stored.use_gpu);
+    backend.set_custom_model(job.custom.clone());
+    let done = transcribe_one(&args, &job, file, &backend, true)?;
+    println!("{}", done.output);
+
+    if args.clipboard {
+        copy_to_clipboard(&done.text)?;
+        eprintln!("Copied to clipboard.");
+    }
+    if args.route {
+        route_output(&job.stored.output_sinks, &done.entry(job.language));
+    }
+    if !args.no_history {
+        add_to_history(&done.history_entry(file, job.language));
+    }
+    Ok(())
+}
+
+/// Resolve what the files of a run share, failing before any decoding if
+/// the model isn't there or the hint file can't be read.
+fn prepare(args: &TranscribeArgs) -> Result<Job, DictationError> {
+    let stored = if args.no_config {
+        Settings::default()
+    } else {
+        sagascript_core::settings::store::load()
+    };
+    if args.minutes && !diarizing(args) && !args.split_channels && !stored.split_channels {
+        return Err(DictationError::SettingsError(
+            "--minutes needs speaker turns: add --diarize or --split-channels".to_string(),
+        ));
+    }
     let language = match &args.language {
         Some(l) => parse_language(l)?,
         None => stored.language,
     };
+    let custom = resolve_custom_model(args.model.as_deref(), &stored);
     let model = resolve_effective_model(
-        args.model.as_deref(),
+        args.model.as_deref().filter(|_| custom.is_none()),
         language,
         stored.auto_select_model,
         stored.whisper_model,
     )?;
 
     // Check model is downloaded
-    if !model::is_model_downloaded(model) {
+    if custom.is_none() && !model::is_model_downloaded(model) {
         return Err(DictationError::TranscriptionFailed(format!(
             "Model '{}' is not downloaded. Run: sagascript download-model {}",
             model.display_name(),
@@ -100,32 +288,130 @@ pub fn run(args: TranscribeArgs) -> Result<(), DictationError> {
         )));
     }
 
-    // Decode audio file
-    eprintln!("Decoding {}...", args.file.display());
-    let audio = decode_audio_file(&args.file)?;
-    let duration = audio.len() as f64 / 16_000.0;
-    eprintln!("Audio: {:.1}s, {} samples", duration, audio.len());
-
-    // Load model
-    eprintln!("Loading model: {}...", model.display_name());
-    let backend = WhisperBackend::new();
-    backend.load_model(model)?;
-
     // Effective hint/prompt: --prompt-file, else --hint/--prompt, else the saved
     // initial_prompt. Used by both the diarized and standard paths.
-    let effective_prompt = resolve_effective_prompt(
+    let prompt = resolve_effective_prompt(
         args.prompt.as_deref(),
         args.prompt_file.as_deref(),
         &stored.initial_prompt,
     )?;
 
+    // The diarized path doesn't use VAD; the standard one takes it from the
+    // saved settings, with CLI flags overriding.
+    let vad_enabled = !diarizing(args)
+        && if args.no_vad {
+            false
+        } else if args.vad {
+            true
+        } else {
+            stored.vad_enabled
+        };
+    let vad_model_path = if vad_enabled {
+        let path = model::vad_model_path();
+        eprintln!("Verifying Silero VAD model...");
+        tokio::runtime::Runtime::new()
+            .map_err(|e| DictationError::ModelDownloadFailed(format!("tokio runtime: {e}")))?
+            .block_on(model::download_vad_model(|_, _| {}))?;
+        path.to_str().map(str::to_string)
+    } else {
+        None
+    };
+
+    Ok(Job {
+        stored,
+        language,
+        model,
+        custom,
+        prompt,
+        vad_model_path,
+    })
+}
+
+#[cfg(feature = "diarization")]
+fn diarizing(args: &TranscribeArgs) -> bool {
+    args.diarize
+}
+
+#[cfg(not(feature = "diarization"))]
+fn diarizing(_args: &TranscribeArgs) -> bool {
+    false
+}
+
+/// Transcribe `file` with `backend`, loading the model only if it isn't
+/// loaded already. Progress bars are left out when several files are
+/// transcribed at once.
+fn transcribe_one(
+    args: &TranscribeArgs,
+    job: &Job,
+    file: &Path,
+    backend: &WhisperBackend,
+    progress: bool,
+) -> Result<Transcribed, DictationError> {
+    let Job {
+        stored, language, ..
+    } = job;
+    let language = *language;
+
+    let split_channels = (args.split_channels || stored.split_channels) && !diarizing(args);
+
+    // Decode audio file. Long files are decoded and transcribed chunk by
+    // chunk later on instead, keeping memory flat; `audio` stays empty. The
+    // diarized and split-channel paths need the whole recording at once, so
+    // they never stream. A file split into several channels leaves `audio`
+    // empty and fills `channels` instead.
+    let streamed_duration = probe_duration(file).filter(|&secs| {
+        secs >= STREAM_DECODE_AFTER_SECS && !diarizing(args) && !split_channels
+    });
+    let streamed = streamed_duration.is_some();
+    let (audio, channels, duration) = if let Some(duration) = streamed_duration {
+        eprintln!("Audio: {duration:.1}s — decoding and transcribing in chunks");
+        (Vec::new(), Vec::new(), duration)
+    } else {
+        eprintln!("Decoding {}...", file.display());
+        let pb = progress_bar(progress, "  Decoding [{bar:40}] {pos}%");
+        let mut decoded = if split_channels {
+            decode_audio_file_channels(file, |pct| pb.set_position(pct.into()))?
+        } else {
+            vec![decode_audio_file_with_fallback(
+                file,
+                args.ffmpeg || stored.ffmpeg_fallback,
+                |pct| pb.set_position(pct.into()),
+            )?]
+        };
+        pb.finish_and_clear();
+        let duration = decoded[0].len() as f64 / 16_000.0;
+        if decoded.len() == 1 {
+            let audio = decoded.remove(0);
+            eprintln!("Audio: {:.1}s, {} samples", duration, audio.len());
+            (audio, Vec::new(), duration)
+        } else {
+            eprintln!("Audio: {:.1}s, {} channels", duration, decoded.len());
+            (Vec::new(), decoded, duration)
+        }
+    };
+    let model = adapt_model_to_clip(
+        args.model.as_deref(),
+        language,
+        stored,
+        job.model,
+        duration,
+        model::is_model_downloaded,
+    );
+
+    // Load model
+    if backend.needs_reload(model) {
+        eprintln!("Loading model: {}...", model.display_name());
+        backend.load_model(model)?;
+    }
+
     // Diarization branch
     #[cfg(feature = "diarization")]
     if args.diarize {
         // The diarized path uses greedy timestamped decoding (DTW), so the
-        // beam/VAD options don't apply — warn rather than silently ignore them.
-        if args.beam_size.is_some() || args.vad || args.no_vad {
-            eprintln!("Note: --beam / --vad have no effect with --diarize.");
+        // beam/VAD/keyword options don't apply — warn rather than silently
+        // ignore them.
+        if args.beam_size.is_some() || args.vad || args.no_vad || !args.keywords.is_empty() {
+            eprintln!("Note: --beam / --vad / --keyword have no effect with --diarize.");
         }
         use sagascript_core::diarization::{
             DiarizeConfig, TimestampedSegment,
@@ -161,7 +447,7 @@ pub fn run(args: TranscribeArgs) -> Result<(), DictationError> {
         let raw_segments = backend.transcribe_sync_for_diarization(
             &audio,
             language,
-            effective_prompt.as_deref(),
+            job.prompt.as_deref(),
         )?;
         eprintln!("Got {} word/segment(s) for merging", raw_segments.len());
         if std::env::var("SAGA_DIAR_DEBUG").is_ok() {
@@ -178,10 +464,34 @@ pub fn run(args: TranscribeArgs) -> Result<(), DictationError> {
         let diarized = merge_with_transcript(&speaker_segments, &transcript);
         let mut consolidated = consolidate(&diarized);
         for segment in &mut consolidated {
-            segment.text = normalize_nonspeech_markers(&segment.text, language);
+            segment.text = replacements::apply(
+                &normalize_nonspeech_markers(&segment.text, language),
+                &stored.replacement_rules,
+            );
         }
-
-        if args.json {
+        telemetry::record(TelemetryEvent::FileTranscription { model });
+
+        let text = speaker_transcript(&consolidated);
+        let output = if args.minutes {
+            let turns: Vec<Turn> = consolidated.iter().map(Turn::from).collect();
+            minutes_markdown(file, duration, &turns, language)
+        } else if let Some(version) = args.porcelain {
+            let mut out = porcelain::Output::new(version);
+            porcelain_summary(&mut out, file, language, model, duration);
+            for seg in &consolidated {
+                out.record(
+                    "segment",
+                    &[
+                        &seconds(seg.start),
+                        &seconds(seg.end),
+                        &seg.speaker,
+                        seg.text.trim(),
+                    ],
+                );
+            }
+            out.record("text", &[&text]);
+            out.render()
+        } else if args.json {
             let speakers: Vec<String> = {
                 let mut seen = std::collections::HashSet::new();
                 consolidated.iter().map(|s| s.speaker.clone()).filter(|s| seen.insert(s.clone())).collect()
@@ -191,50 +501,37 @@ pub fn run(args: TranscribeArgs) -> Result<(), DictationError> {
                 "speakers": speakers,
                 "language": language,
                 "model": model_id_string(model),
-                "file": args.file.display().to_string(),
+                "file": file.display().to_string(),
                 "duration_seconds": duration,
             });
-            println!("{}", serde_json::to_string_pretty(&json).unwrap());
+            serde_json::to_string_pretty(&json).unwrap()
         } else {
-            for seg in &consolidated {
-                println!("[{}] {}", seg.speaker, seg.text.trim());
-            }
-        }
-
-        if args.clipboard {
-            let text: String = consolidated
-                .iter()
-                .map(|s| format!("[{}] {}", s.speaker, s.text.trim()))
-                .collect::<Vec<_>>()
-                .join("\n");
-            copy_to_clipboard(&text)?;
-            eprintln!("Copied to clipboard.");
-        }
-
-        return Ok(());
+            text.clone()
+        };
+        return Ok(Transcribed {
+            text,
+            output,
+            model,
+            duration,
+        });
     }
 
     // Standard (non-diarized) transcription. Build options from the saved
     // settings, with CLI flags overriding.
-    let vad_enabled = if args.no_vad {
-        false
-    } else if args.vad {
-        true
-    } else {
-        stored.vad_enabled
-    };
-    let vad_model_path = if vad_enabled {
-        let path = model::vad_model_path();
-        eprintln!("Verifying Silero VAD model...");
-        tokio::runtime::Runtime::new()
-            .map_err(|e| DictationError::ModelDownloadFailed(format!("tokio runtime: {e}")))?
-            .block_on(model::download_vad_model(|_, _| {}))?;
-        path.to_str().map(str::to_string)
-    } else {
-        None
-    };
+    let mut model_overrides = stored.model_overrides.clone();
+    if args.beam_size.is_some() {
+        // An explicit --beam beats a saved per-model beam width.
+        for o in model_overrides.values_mut() {
+            o.beam_size = None;
+        }
+    }
     let opts = TranscribeOptions {
-        prompt: effective_prompt,
+        prompt: job.prompt.clone(),
+        keywords: if args.keywords.is_empty() {
+            stored.keywords.clone()
+        } else {
+            args.keywords.clone()
+        },
         // File transcription isn't latency-sensitive, so default to beam search
         // (fewer repetition loops). Honor an explicit beam setting/flag.
         beam_size: args.beam_size.unwrap_or(if stored.beam_size >= 2 {
@@ -243,21 +540,95 @@ pub fn run(args: TranscribeArgs) -> Result<(), DictationError> {
             sagascript_core::transcription::FILE_TRANSCRIBE_BEAM
         }),
         temperature_fallback: stored.temperature_fallback,
-        vad_model_path,
-        segment_timestamps: args.json,
+        vad_model_path: job.vad_model_path.clone(),
+        segment_timestamps: args.json || args.minutes,
+        word_timestamps: args.json,
+        localize_numbers: args.localize_numbers || stored.localize_numbers,
+        model_overrides,
     };
     if opts.beam_size >= 2 {
         eprintln!("Beam search: width {}", opts.beam_size);
     }
-    if vad_enabled {
+    if opts.vad_model_path.is_some() {
         eprintln!("VAD: enabled");
     }
+    if !opts.keywords.is_empty() {
+        eprintln!("Keyword biasing: {} keyword(s)", opts.keywords.len());
+    }
 
-    let segments = if duration > 10.0 {
-        let pb = ProgressBar::new(100);
-        pb.set_style(
-            ProgressStyle::with_template("  Transcribing [{bar:40}] {pos}%").unwrap(),
-        );
+    if !channels.is_empty() {
+        let opts = TranscribeOptions {
+            segment_timestamps: true,
+            ..opts
+        };
+        let mut per_channel = Vec::with_capacity(channels.len());
+        for (index, channel) in channels.iter().enumerate() {
+            eprintln!("Transcribing {}...", channel_label(index));
+            let pb = progress_bar(progress, "  Transcribing [{bar:40}] {pos}%");
+            let pb_cb = pb.clone();
+            per_channel.push(backend.transcribe_sync_with_options_segments(
+                channel,
+                language,
+                &opts,
+                move |pct| crate::set_transcription_progress(&pb_cb, pct),
+            )?);
+            pb.finish_and_clear();
+        }
+        let mut merged = merge_channels(per_channel, language);
+        for segment in &mut merged {
+            segment.text = replacements::apply(&segment.text, &stored.replacement_rules);
+        }
+        telemetry::record(TelemetryEvent::FileTranscription { model });
+
+        let text = channel_transcript(&merged);
+        let output = if args.minutes {
+            let turns: Vec<Turn> = merged.iter().map(Turn::from).collect();
+            minutes_markdown(file, duration, &turns, language)
+        } else if let Some(version) = args.porcelain {
+            let mut out = porcelain::Output::new(version);
+            porcelain_summary(&mut out, file, language, model, duration);
+            for seg in &merged {
+                out.record(
+                    "segment",
+                    &[&seconds(seg.start), &seconds(seg.end), &seg.channel, &seg.text],
+                );
+            }
+            out.record("text", &[&text]);
+            out.render()
+        } else if args.json {
+            let json = serde_json::json!({
+                "segments": merged,
+                "channels": channels.len(),
+                "language": language,
+                "model": model_id_string(model),
+                "file": file.display().to_string(),
+                "duration_seconds": duration,
+            });
+            serde_json::to_string_pretty(&json).unwrap()
+        } else {
+            text.clone()
+        };
+        return Ok(Transcribed {
+            text,
+            output,
+            model,
+            duration,
+        });
+    }
+
+    let segments = if streamed {
+        let pb = progress_bar(progress, "  Transcribing [{bar:40}] {pos}%");
+        let segments = backend.transcribe_file_streaming(
+            file,
+            language,
+            &opts,
+            |pct| pb.set_position(pct.into()),
+            &JobAbort::new(),
+        )?;
+        pb.finish_and_clear();
+        segments
+    } else if duration > 10.0 {
+        let pb = progress_bar(progress, "  Transcribing [{bar:40}] {pos}%");
         let pb_cb = pb.clone();
         let segments = backend.transcribe_sync_with_options_segments(
             &audio,
@@ -281,10 +652,32 @@ pub fn run(args: TranscribeArgs) -> Result<(), DictationError> {
         .collect::<String>()
         .trim()
         .to_string();
-    let text = normalize_nonspeech_markers(&raw_text, language);
+    let text = replacements::apply(
+        &normalize_nonspeech_markers(&raw_text, language),
+        &stored.replacement_rules,
+    );
+    telemetry::record(TelemetryEvent::FileTranscription { model });
 
     // Output
-    if args.json {
+    let output = if args.minutes {
+        // A mono file with split channels on: one channel, one speaker.
+        let turns: Vec<Turn> = merge_channels(vec![segments], language)
+            .iter()
+            .map(Turn::from)
+            .collect();
+        minutes_markdown(file, duration, &turns, language)
+    } else if let Some(version) = args.porcelain {
+        let mut out = porcelain::Output::new(version);
+        porcelain_summary(&mut out, file, language, model, duration);
+        for s in &segments {
+            out.record(
+                "segment",
+                &[&seconds(s.start), &seconds(s.end), "", s.text.trim()],
+            );
+        }
+        out.record("text", &[&text]);
+        out.render()
+    } else if args.json {
         // Per-segment confidence (#81): avg_logprob is the mean token
         // log-probability (null when a segment has no scoreable tokens);
         // no_speech_prob near 1.0 flags likely-hallucinated segments.
@@ -302,28 +695,222 @@ pub fn run(args: TranscribeArgs) -> Result<(), DictationError> {
                 })
             })
             .collect();
+        let words: Vec<&WordTiming> = segments.iter().flat_map(|s| &s.words).collect();
         let json = serde_json::json!({
             "text": text,
             "segments": json_segments,
+            "words": words,
             "language": language,
             "model": model_id_string(model),
-            "file": args.file.display().to_string(),
+            "file": file.display().to_string(),
             "duration_seconds": duration,
         });
-        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        serde_json::to_string_pretty(&json).unwrap()
     } else {
-        println!("{text}");
+        text.clone()
+    };
+
+    Ok(Transcribed {
+        text,
+        output,
+        model,
+        duration,
+    })
+}
+
+fn progress_bar(visible: bool, template: &str) -> ProgressBar {
+    if !visible {
+        return ProgressBar::hidden();
     }
+    let pb = ProgressBar::new(100);
+    pb.set_style(ProgressStyle::with_template(template).unwrap());
+    pb
+}
 
-    // Clipboard
-    if args.clipboard {
-        copy_to_clipboard(&text)?;
-        eprintln!("Copied to clipboard.");
+const SUMMARY_COLUMNS: [Column; 4] = [
+    Column::left("file", "FILE"),
+    Column::right("duration", "DURATION"),
+    Column::left("model", "MODEL"),
+    Column::left("result", "RESULT"),
+];
+
+/// Transcribe several files, `--jobs` at a time, each worker loading the
+/// model once for all its files. Every transcript is written to its own
+/// file; a failed file doesn't stop the rest. Ends with a summary table.
+/// Files and chunks wait while the app dictates or the system is loaded
+/// (`--max-load`, see [`throttle`]).
+fn run_batch(args: &TranscribeArgs, job: &Job, files: &[PathBuf]) -> Result<(), DictationError> {
+    if let Some(dir) = &args.output_dir {
+        std::fs::create_dir_all(dir).map_err(|e| {
+            DictationError::SettingsError(format!("Can't create {}: {e}", dir.display()))
+        })?;
     }
+    let workers = usize::from(args.jobs).min(files.len());
+    let throttle = Throttle {
+        max_load: args.max_load,
+    };
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<(Transcribed, PathBuf), DictationError>>>> =
+        Mutex::new(files.iter().map(|_| None).collect());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                let backend = WhisperBackend::with_gpu(job.stored.use_gpu);
+                backend.set_custom_model(job.custom.clone());
+                backend.set_throttle(Some(throttle));
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(file) = files.get(i) else { break };
+                    if let Some((why, paused)) = throttle.wait() {
+                        eprintln!("Paused {:.0}s: {why}", paused.as_secs_f64());
+                    }
+                    eprintln!("[{}/{}] {}", i + 1, files.len(), file.display());
+                    let result = transcribe_one(args, job, file, &backend, workers == 1)
+                        .and_then(|done| {
+                            let path =
+                                output_path(file, args.output_dir.as_deref(), output_extension(args));
+                            std::fs::write(&path, format!("{}\n", done.output)).map_err(|e| {
+                                DictationError::SettingsError(format!(
+                                    "Failed to write {}: {e}",
+                                    path.display()
+                                ))
+                            })?;
+                            if args.route {
+                                route_output(
+                                    &job.stored.output_sinks,
+                                    &done.entry(job.language),
+                                );
+                            }
+                            if !args.no_history {
+                                add_to_history(&done.history_entry(file, job.language));
+                            }
+                            Ok((done, path))
+                        });
+                    if let Err(e) = &result {
+                        eprintln!("{}: {e}", file.display());
+                    }
+                    results.lock().unwrap()[i] = Some(result);
+                }
+            });
+        }
+    });
+
+    let mut failed = 0;
+    let mut table = Table::new(&SUMMARY_COLUMNS);
+    for (file, result) in files.iter().zip(results.into_inner().unwrap()) {
+        let row = match result {
+            Some(Ok((done, path))) => vec![
+                file.display().to_string(),
+                format!("{:.1}s", done.duration),
+                model_id_string(done.model).to_string(),
+                path.display().to_string(),
+            ],
+            Some(Err(e)) => {
+                failed += 1;
+                vec![
+                    file.display().to_string(),
+                    "—".to_string(),
+                    "—".to_string(),
+                    format!("failed: {e}"),
+                ]
+            }
+            None => unreachable!("every file is taken by a worker"),
+        };
+        table.row(row);
+    }
+    table.print(&TableArgs::default())?;
 
+    if failed > 0 {
+        return Err(DictationError::TranscriptionFailed(format!(
+            "{failed} of {} files failed",
+            files.len()
+        )));
+    }
     Ok(())
 }
 
+/// Where a batch run writes `file`'s transcript: `<stem>.txt` (or `.json`
+/// with `--json`) in `dir`, or next to the file.
+fn output_path(file: &Path, dir: Option<&Path>, extension: &str) -> PathBuf {
+    let name = file.with_extension(extension);
+    match (dir, name.file_name()) {
+        (Some(dir), Some(name)) => dir.join(name),
+        _ => name,
+    }
+}
+
+/// Extension of the per-file outputs of a batch.
+fn output_extension(args: &TranscribeArgs) -> &'static str {
+    if args.minutes {
+        "md"
+    } else if args.json {
+        "json"
+    } else {
+        "txt"
+    }
+}
+
+/// Expand the glob patterns among `args` (for shells that don't, like
+/// Windows'), keeping plain paths as given and in order. A pattern that
+/// matches nothing is an error rather than a silently shorter batch.
+fn expand_files(args: &[PathBuf]) -> Result<Vec<PathBuf>, DictationError> {
+    let mut files = Vec::new();
+    for arg in args {
+        let pattern = arg.to_string_lossy();
+        let is_pattern = pattern.contains(['*', '?', '[']);
+        if !is_pattern || arg.exists() {
+            files.push(arg.clone());
+            continue;
+        }
+        let matches = glob::glob(&pattern)
+            .map_err(|e| DictationError::SettingsError(format!("Bad pattern '{pattern}': {e}")))?
+            .filter_map(Result::ok)
+            .filter(|p| p.is_file())
+            .collect::<Vec<_>>();
+        if matches.is_empty() {
+            return Err(DictationError::FileDecodeError(format!(
+                "No files match '{pattern}'"
+            )));
+        }
+        files.extend(matches);
+    }
+    Ok(files)
+}
+
+/// The `file`, `language`, `model` and `duration` records that open
+/// `transcribe --porcelain` output.
+fn porcelain_summary(
+    out: &mut porcelain::Output,
+    file: &Path,
+    language: Language,
+    model: WhisperModel,
+    duration: f64,
+) {
+    out.record("file", &[&file.display().to_string()]);
+    out.record("language", &[&format_enum_value(language)]);
+    out.record("model", &[model_id_string(model)]);
+    out.record("duration", &[&seconds(duration)]);
+}
+
+/// Minutes of `file`'s transcription, titled with its file name.
+fn minutes_markdown(file: &Path, duration: f64, turns: &[Turn], language: Language) -> String {
+    let title = file
+        .file_name()
+        .map_or_else(|| file.display().to_string(), |n| n.to_string_lossy().into_owned());
+    minutes::render_markdown(&title, duration, turns, language)
+}
+
+/// Diarized segments as `[SPEAKER] text` lines, as copied or routed.
+#[cfg(feature = "diarization")]
+fn speaker_transcript(segments: &[sagascript_core::diarization::DiarizedSegment]) -> String {
+    segments
+        .iter()
+        .map(|s| format!("[{}] {}", s.speaker, s.text.trim()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 /// Validates a `--diarize-threshold` value: must parse as a finite f32 in the
 /// documented 0.0-2.0 range. NaN/infinite or out-of-range values silently
 /// produce degenerate agglomerative clustering downstream, so reject them at
@@ -389,6 +976,45 @@ pub fn resolve_effective_model(
     }
 }
 
+/// The custom model file a run loads: the registered model `--model` names,
+/// or without `--model` the configured `custom_model`. `None` means a
+/// built-in model, which [`resolve_effective_model`] then picks.
+pub fn resolve_custom_model(model_arg: Option<&str>, stored: &Settings) -> Option<PathBuf> {
+    match model_arg {
+        Some(name) => stored.custom_models.get(name).cloned(),
+        None => stored.custom_model_path(),
+    }
+}
+
+/// Applies the `adaptive_model` setting once the clip length is known, for
+/// runs without an explicit `--model`. `resolved` is what
+/// [`resolve_effective_model`] picked up front; it is kept when adaptive mode
+/// is off, when `--model` was given, or when the adaptive pick isn't
+/// downloaded (checked via `is_downloaded`). Shared by `transcribe` and
+/// `record` like the functions around it.
+pub fn adapt_model_to_clip(
+    model_arg: Option<&str>,
+    language: Language,
+    stored: &Settings,
+    resolved: WhisperModel,
+    clip_secs: f64,
+    is_downloaded: impl Fn(WhisperModel) -> bool,
+) -> WhisperModel {
+    if model_arg.is_some() || !stored.adaptive_model {
+        return resolved;
+    }
+    let for_run = Settings {
+        language,
+        ..stored.clone()
+    };
+    let adapted = for_run.effective_model_for_clip(clip_secs as f32);
+    if adapted != resolved && is_downloaded(adapted) {
+        adapted
+    } else {
+        resolved
+    }
+}
+
 /// Resolves the effective initial prompt (a.k.a. the "hint") for a run, in
 /// precedence order:
 ///   1. `--prompt-file` / `--hint-file` — the file's contents (trimmed;
@@ -446,7 +1072,8 @@ pub fn parse_model(s: &str) -> Result<WhisperModel, DictationError> {
         "large-v3-turbo" => Ok(WhisperModel::LargeV3Turbo),
         "large-v3-turbo-q8_0" => Ok(WhisperModel::LargeV3TurboQ8),
         other => Err(DictationError::SettingsError(format!(
-            "Unknown model '{other}'. Run 'sagascript list-models' to see available models."
+            "Unknown model '{other}'. Run 'sagascript list-models' to see available models, \
+             or 'sagascript models add' to register a local GGML file."
         ))),
     }
 }
@@ -476,6 +1103,46 @@ pub fn model_id_string(model: WhisperModel) -> &'static str {
     }
 }
 
+/// Deliver `entry` to the configured output sinks (`--route`). The text has
+/// already been printed, so a failing sink is reported on stderr rather than
+/// failing the command. Templates get an empty `{app}`: the CLI has no
+/// focused app of its own, so the paste sink is skipped too.
+pub fn route_output(sinks: &[OutputSink], entry: &output::Entry) {
+    if sinks.iter().all(|sink| *sink == OutputSink::Paste) {
+        eprintln!(
+            "No output sinks configured the CLI can use (sagascript config set output_sinks ...)."
+        );
+        return;
+    }
+    if output::wants_clipboard(sinks) {
+        match copy_to_clipboard(&entry.text) {
+            Ok(()) => eprintln!("Copied to clipboard."),
+            Err(e) => eprintln!("Output to clipboard failed: {e}"),
+        }
+    }
+    let rt = match tokio::runtime::Runtime::new() {
+        Ok(rt) => rt,
+        Err(e) => {
+            eprintln!("Output failed: could not start runtime: {e}");
+            return;
+        }
+    };
+    for (sink, error) in rt.block_on(output::deliver(sinks, entry)) {
+        eprintln!("Output to {} failed: {error}", sink.label());
+    }
+}
+
+/// Add a finished transcript to the history the app shows. The transcript
+/// has already been printed, so a failure is only reported on stderr.
+pub fn add_to_history(entry: &HistoryEntry) {
+    if entry.text.trim().is_empty() {
+        return;
+    }
+    if let Err(e) = history::append(&history::history_path(), entry) {
+        eprintln!("Warning: could not add the transcript to the history: {e}");
+    }
+}
+
 pub fn copy_to_clipboard(text: &str) -> Result<(), DictationError> {
     use arboard::Clipboard;
     let mut clipboard =
@@ -490,6 +1157,33 @@ pub fn copy_to_clipboard(text: &str) -> Result<(), DictationError> {
 mod tests {
     use super::*;
 
+    // -- batch runs --
+
+    #[test]
+    fn expand_files_globs_patterns_and_keeps_plain_paths() {
+        let dir = std::env::temp_dir().join(format!("sagascript_batch_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["a.wav", "b.wav", "c.mp3"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+
+        let got = expand_files(&[dir.join("*.wav"), PathBuf::from("missing.m4a")]).unwrap();
+        assert_eq!(got, [dir.join("a.wav"), dir.join("b.wav"), PathBuf::from("missing.m4a")]);
+        assert!(expand_files(&[dir.join("*.flac")]).is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn output_path_sits_next_to_the_file_or_in_the_dir() {
+        let file = Path::new("calls/monday.m4a");
+        assert_eq!(output_path(file, None, "txt"), PathBuf::from("calls/monday.txt"));
+        assert_eq!(
+            output_path(file, Some(Path::new("out")), "json"),
+            PathBuf::from("out/monday.json")
+        );
+    }
+
     // -- resolve_effective_prompt --
 
     #[test]
@@ -727,6 +1421,90 @@ mod tests {
         assert_eq!(result, WhisperModel::TinyEn);
     }
 
+    #[test]
+    fn custom_model_arg_resolves_to_its_file() {
+        let mut stored = Settings::default();
+        stored
+            .custom_models
+            .insert("my-model".to_string(), PathBuf::from("/models/my.bin"));
+        assert_eq!(
+            resolve_custom_model(Some("my-model"), &stored),
+            Some(PathBuf::from("/models/my.bin"))
+        );
+        assert_eq!(resolve_custom_model(Some("base"), &stored), None);
+        assert_eq!(resolve_custom_model(None, &stored), None);
+
+        stored.custom_model = "my-model".to_string();
+        assert_eq!(
+            resolve_custom_model(None, &stored),
+            Some(PathBuf::from("/models/my.bin"))
+        );
+        // An explicit built-in model wins over the configured custom one.
+        assert_eq!(resolve_custom_model(Some("base"), &stored), None);
+    }
+
+    // -- adapt_model_to_clip --
+
+    fn adaptive_settings() -> Settings {
+        Settings {
+            adaptive_model: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn adapt_model_to_clip_off_keeps_resolved() {
+        let stored = Settings::default();
+        let m = adapt_model_to_clip(
+            None,
+            Language::English,
+            &stored,
+            WhisperModel::BaseEn,
+            600.0,
+            |_| true,
+        );
+        assert_eq!(m, WhisperModel::BaseEn);
+    }
+
+    #[test]
+    fn adapt_model_to_clip_long_clip_uses_accurate_model_for_override_language() {
+        let m = adapt_model_to_clip(
+            None,
+            Language::Swedish,
+            &adaptive_settings(),
+            WhisperModel::KbWhisperBase,
+            600.0,
+            |_| true,
+        );
+        assert_eq!(m, WhisperModel::KbWhisperSmall);
+    }
+
+    #[test]
+    fn adapt_model_to_clip_explicit_model_wins() {
+        let m = adapt_model_to_clip(
+            Some("base.en"),
+            Language::English,
+            &adaptive_settings(),
+            WhisperModel::BaseEn,
+            600.0,
+            |_| true,
+        );
+        assert_eq!(m, WhisperModel::BaseEn);
+    }
+
+    #[test]
+    fn adapt_model_to_clip_missing_model_falls_back() {
+        let m = adapt_model_to_clip(
+            None,
+            Language::English,
+            &adaptive_settings(),
+            WhisperModel::BaseEn,
+            600.0,
+            |_| false,
+        );
+        assert_eq!(m, WhisperModel::BaseEn);
+    }
+
     #[test]
     fn resolve_effective_model_invalid_arg_errors() {
         assert!(
@@ -0,0 +1,188 @@
+//! Contract tests for `--porcelain` v1 (see `src/porcelain.rs`).
+//!
+//! These run the real binary and compare its stdout and stderr byte for
+//! byte. If one fails, the change broke scripts in the field: restore the
+//! old output rather than editing the expectation, or add a new porcelain
+//! version.
+
+// Settings and models are redirected through HOME/XDG_DATA_HOME, which
+// Windows ignores.
+#![cfg(unix)]
+
+use std::process::{Command, Output};
+
+use tempfile::TempDir;
+
+/// A fresh, empty home directory, so no test sees the user's settings or
+/// downloaded models (or another test's). Removed when dropped.
+fn scratch_home() -> TempDir {
+    tempfile::Builder::new()
+        .prefix("sagascript-porcelain-")
+        .tempdir()
+        .unwrap()
+}
+
+fn sagascript(home: &TempDir, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_sagascript"))
+        .args(args)
+        .env("HOME", home.path())
+        .env("XDG_DATA_HOME", home.path().join(".local/share"))
+        .env_remove("SAGASCRIPT_LANGUAGE")
+        .env_remove("SAGASCRIPT_MODEL")
+        .env_remove("SAGASCRIPT_NO_CONFIG")
+        .env_remove("RUST_LOG")
+        .output()
+        .expect("failed to run sagascript")
+}
+
+fn stdout(output: &Output) -> &str {
+    assert!(
+        output.status.success(),
+        "sagascript failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+fn fields(line: &str) -> Vec<&str> {
+    line.split('\t').collect()
+}
+
+#[test]
+fn list_models_records() {
+    let home = scratch_home();
+    let out = sagascript(&home, &["list-models", "--porcelain"]);
+    let mut lines = stdout(&out).lines();
+    assert_eq!(lines.next(), Some("porcelain\t1"));
+    let records: Vec<&str> = lines.collect();
+    assert!(!records.is_empty());
+    for record in &records {
+        let fields = fields(record);
+        assert_eq!(fields[0], "model", "{record}");
+        assert_eq!(fields.len(), 7, "{record}");
+    }
+    assert!(
+        records.contains(&"model\tbase.en\ten\t142\tfalse\ttrue\ttrue"),
+        "{records:?}"
+    );
+}
+
+#[test]
+fn list_models_filters_apply() {
+    let home = scratch_home();
+    let out = sagascript(
+        &home,
+        &[
+            "list-models",
+            "--porcelain",
+            "--language",
+            "sv",
+            "--downloaded",
+        ],
+    );
+    assert_eq!(stdout(&out), "porcelain\t1\n");
+}
+
+#[test]
+fn explicit_version_matches_default() {
+    let home = scratch_home();
+    let bare = sagascript(&home, &["config", "get", "language", "--porcelain"]);
+    let v1 = sagascript(&home, &["config", "get", "language", "--porcelain=1"]);
+    assert_eq!(stdout(&bare), stdout(&v1));
+}
+
+#[test]
+fn unknown_version_is_rejected() {
+    let home = scratch_home();
+    let out = sagascript(&home, &["list-models", "--porcelain=2"]);
+    assert!(!out.status.success());
+    assert!(out.stdout.is_empty());
+}
+
+#[test]
+fn config_list_has_one_setting_per_key() {
+    let home = scratch_home();
+    let out = sagascript(&home, &["config", "list", "--porcelain"]);
+    let mut lines = stdout(&out).lines();
+    assert_eq!(lines.next(), Some("porcelain\t1"));
+    let records: Vec<&str> = lines.collect();
+    for record in &records {
+        let fields = fields(record);
+        assert_eq!(fields[0], "setting", "{record}");
+        assert_eq!(fields.len(), 4, "{record}");
+    }
+    assert!(records.contains(&"setting\tlanguage\ten\ten"));
+    assert!(records.contains(&"setting\thotkey\tControl+Shift+Space\tControl+Shift+Space"));
+    // Empty values are empty fields, not missing ones.
+    assert!(records.contains(&"setting\tinitial_prompt\t\t"));
+}
+
+#[test]
+fn config_set_then_get() {
+    let home = scratch_home();
+    let set = sagascript(&home, &["config", "set", "beam_size", "5", "--porcelain"]);
+    assert_eq!(stdout(&set), "porcelain\t1\nsetting\tbeam_size\t5\t0\n");
+    let get = sagascript(&home, &["config", "get", "beam_size", "--porcelain"]);
+    assert_eq!(stdout(&get), "porcelain\t1\nsetting\tbeam_size\t5\t0\n");
+}
+
+#[test]
+fn config_set_several_pairs_is_all_or_nothing() {
+    let home = scratch_home();
+    let set = sagascript(
+        &home,
+        &["config", "set", "beam_size=5", "language=sv", "--porcelain"],
+    );
+    assert_eq!(
+        stdout(&set),
+        "porcelain\t1\nsetting\tbeam_size\t5\t0\nsetting\tlanguage\tsv\ten\n"
+    );
+    let rejected = sagascript(&home, &["config", "set", "beam_size=3", "language=xx"]);
+    assert!(!rejected.status.success());
+    let get = sagascript(&home, &["config", "get", "beam_size", "--porcelain"]);
+    assert_eq!(stdout(&get), "porcelain\t1\nsetting\tbeam_size\t5\t0\n");
+}
+
+#[test]
+fn values_are_escaped() {
+    let home = scratch_home();
+    let prompt = "Tauri\twhisper\\rs";
+    let out = sagascript(
+        &home,
+        &["config", "set", "initial_prompt", prompt, "--porcelain"],
+    );
+    assert_eq!(
+        stdout(&out),
+        "porcelain\t1\nsetting\tinitial_prompt\tTauri\\twhisper\\\\rs\t\n"
+    );
+}
+
+#[test]
+fn failure_is_an_error_record_on_stderr() {
+    let home = scratch_home();
+    let out = sagascript(
+        &home,
+        &["transcribe", "missing.wav", "--no-config", "--porcelain"],
+    );
+    assert_eq!(out.status.code(), Some(1));
+    assert!(out.stdout.is_empty(), "stdout must stay empty on failure");
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    let last = stderr.lines().last().unwrap();
+    let fields = fields(last);
+    assert_eq!(fields[0], "error", "{stderr}");
+    assert_eq!(fields.len(), 4, "{last}");
+    assert!(!fields[1].is_empty() && !fields[2].is_empty());
+}
+
+#[test]
+fn config_error_is_an_error_record() {
+    let home = scratch_home();
+    let out = sagascript(&home, &["config", "get", "nonexistent", "--porcelain"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(out.stdout.is_empty());
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(
+        stderr.lines().last().unwrap().starts_with("error\t"),
+        "{stderr}"
+    );
+}
@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+/// Version of `package` recorded in the workspace lockfile, if there is one.
+fn locked_version(lockfile: &str, package: &str) -> Option<String> {
+    let mut lines = lockfile.lines();
+    while let Some(line) = lines.next() {
+        if line.trim() == format!("name = \"{package}\"") {
+            return lines
+                .next()?
+                .trim()
+                .strip_prefix("version = \"")?
+                .strip_suffix('"')
+                .map(str::to_string);
+        }
+    }
+    None
+}
+
+fn main() {
+    // whisper.cpp is compiled in by whisper-rs-sys, which doesn't export its
+    // version, so report the crate versions the lockfile pinned instead.
+    let lock_path =
+        PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap()).join("../../Cargo.lock");
+    println!("cargo:rerun-if-changed={}", lock_path.display());
+    let lockfile = std::fs::read_to_string(&lock_path).unwrap_or_default();
+
+    for (package, key) in [
+        ("whisper-rs", "SAGASCRIPT_WHISPER_RS_VERSION"),
+        ("whisper-rs-sys", "SAGASCRIPT_WHISPER_RS_SYS_VERSION"),
+    ] {
+        let version = locked_version(&lockfile, package).unwrap_or_else(|| "unknown".to_string());
+        println!("cargo:rustc-env={key}={version}");
+    }
+}
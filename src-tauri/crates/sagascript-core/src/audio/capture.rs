@@ -1,18 +1,264 @@
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::Duration;
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::SampleFormat;
-use tracing::{error, info};
+use serde::Serialize;
+use tracing::{error, info, warn};
 
 use crate::error::DictationError;
+use crate::settings::CaptureSource;
+use super::chunk::{offset_at_16khz, quiet_split_point};
+use super::monitor::{self, MonitorBuffer, MonitorVolume};
 use super::resample::{resample_to_16khz, TARGET_SAMPLE_RATE};
 
-/// Maximum recording length: 15 minutes. Capped in device-rate samples while
-/// recording (the buffer holds raw mono at the device rate), then resampled to
-/// 16 kHz on stop.
-const MAX_BUFFER_SECONDS: usize = 60 * 15;
+/// Longest recording the buffer holds unless [`AudioCaptureService::set_max_recording`]
+/// says otherwise. Capped in device-rate samples while recording (the buffer
+/// holds raw mono at the device rate), then resampled to 16 kHz on stop.
+/// Audio past the cap is dropped, so callers stop recording when
+/// [`limit_remaining`] reaches zero.
+pub const DEFAULT_MAX_RECORDING: Duration = Duration::from_secs(60 * 15);
+
+/// How long before the recording limit to start counting down.
+pub const LIMIT_WARNING: Duration = Duration::from_secs(60);
+
+/// Time left before a buffer capped at `max` fills, once a recording that
+/// has run for `elapsed` is within [`LIMIT_WARNING`] of it.
+pub fn limit_remaining(elapsed: Duration, max: Duration) -> Option<Duration> {
+    let remaining = max.saturating_sub(elapsed);
+    (remaining <= LIMIT_WARNING).then_some(remaining)
+}
+
+/// Length of the frames the silence detector judges.
+const VAD_FRAME_MS: u32 = 30;
+
+/// Frame RMS below which input is never taken for speech (about -40 dBFS).
+const VAD_MIN_SPEECH_RMS: f32 = 0.01;
+
+/// How far above the noise floor a frame's RMS must be to count as speech
+/// (about 10 dB).
+const VAD_SPEECH_RATIO: f32 = 3.0;
+
+/// How quickly the noise floor follows non-speech frames.
+const VAD_FLOOR_SMOOTHING: f32 = 0.05;
+
+/// Energy-based voice activity detection on the capture callback, for
+/// stopping a toggle-mode recording once the speaker has gone quiet.
+///
+/// Frames well above a running noise-floor estimate count as speech, so a
+/// steady fan or hum doesn't keep a recording alive. Silence is only counted
+/// once speech has been heard: a pause before the first word never stops
+/// the recording.
+struct SilenceDetector {
+    frame_len: usize,
+    frame_pos: usize,
+    frame_energy: f32,
+    /// Running RMS of non-speech frames; `None` until the first frame.
+    noise_floor: Option<f32>,
+    heard_speech: bool,
+    silent_frames: u32,
+    /// Trailing silence in ms, published for [`AudioCaptureService::trailing_silence`].
+    silence_ms: Arc<AtomicU32>,
+}
+
+impl SilenceDetector {
+    fn new(device_rate: u32, silence_ms: Arc<AtomicU32>) -> Self {
+        Self {
+            frame_len: (device_rate * VAD_FRAME_MS / 1000).max(1) as usize,
+            frame_pos: 0,
+            frame_energy: 0.0,
+            noise_floor: None,
+            heard_speech: false,
+            silent_frames: 0,
+            silence_ms,
+        }
+    }
+
+    /// Start over for a new recording on a stream that stayed open.
+    fn reset(&mut self) {
+        self.frame_pos = 0;
+        self.frame_energy = 0.0;
+        self.noise_floor = None;
+        self.heard_speech = false;
+        self.silent_frames = 0;
+    }
+
+    /// Feed interleaved f32 input, downmixed the way the buffer is.
+    fn push(&mut self, data: &[f32], channels: u16) {
+        let channels = channels.max(1) as usize;
+        for frame in data.chunks(channels) {
+            self.sample(frame.iter().sum::<f32>() / channels as f32);
+        }
+    }
+
+    fn push_i16(&mut self, data: &[i16], channels: u16) {
+        let channels = channels.max(1) as usize;
+        for frame in data.chunks(channels) {
+            let sum = frame
+                .iter()
+                .map(|&s| s as f32 / i16::MAX as f32)
+                .sum::<f32>();
+            self.sample(sum / channels as f32);
+        }
+    }
+
+    fn sample(&mut self, s: f32) {
+        self.frame_energy += s * s;
+        self.frame_pos += 1;
+        if self.frame_pos < self.frame_len {
+            return;
+        }
+        let rms = (self.frame_energy / self.frame_len as f32).sqrt();
+        self.frame_pos = 0;
+        self.frame_energy = 0.0;
+
+        let floor = *self.noise_floor.get_or_insert(rms);
+        if rms >= VAD_MIN_SPEECH_RMS && rms >= floor * VAD_SPEECH_RATIO {
+            self.heard_speech = true;
+            self.silent_frames = 0;
+        } else {
+            self.noise_floor = Some(floor + (rms - floor) * VAD_FLOOR_SMOOTHING);
+            if self.heard_speech {
+                self.silent_frames = self.silent_frames.saturating_add(1);
+            }
+        }
+        self.silence_ms.store(
+            self.silent_frames.saturating_mul(VAD_FRAME_MS),
+            Ordering::Relaxed,
+        );
+    }
+}
+
+/// Memory a full buffer capped at `max` takes at `device_rate` (mono f32,
+/// before resampling), for showing what a longer limit costs.
+pub fn buffer_bytes(max: Duration, device_rate: u32) -> u64 {
+    max.as_secs() * u64::from(device_rate) * std::mem::size_of::<f32>() as u64
+}
+
+/// An input device as `sagascript list-devices` reports it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InputDeviceInfo {
+    pub name: String,
+    /// Default sample rate in Hz; `None` if the device won't report a config.
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u16>,
+    /// Whether this is the system's default input, which recordings use
+    /// unless told otherwise.
+    pub is_default: bool,
+}
+
+/// Enumerate the input devices of the default host.
+pub fn list_input_devices() -> Result<Vec<InputDeviceInfo>, DictationError> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+    let devices = host
+        .input_devices()
+        .map_err(|e| DictationError::AudioCaptureError(format!("Failed to list input devices: {e}")))?;
+    Ok(devices
+        .filter_map(|device| {
+            let name = device.name().ok()?;
+            let config = device.default_input_config().ok();
+            Some(InputDeviceInfo {
+                is_default: default_name.as_deref() == Some(name.as_str()),
+                sample_rate: config.as_ref().map(|c| c.sample_rate().0),
+                channels: config.as_ref().map(|c| c.channels()),
+                name,
+            })
+        })
+        .collect())
+}
+
+/// Name fragments (lowercase) of virtual devices that feed the system's
+/// output back in as an input: BlackHole, Soundflower and Rogue Amoeba's
+/// Loopback on macOS, PulseAudio/PipeWire monitor sources on Linux.
+const LOOPBACK_DEVICE_NAMES: [&str; 4] = ["blackhole", "soundflower", "loopback", "monitor of"];
+
+/// Whether an input device called `name` carries system audio rather than
+/// a microphone.
+pub fn is_loopback_device(name: &str) -> bool {
+    let name = name.to_lowercase();
+    LOOPBACK_DEVICE_NAMES.iter().any(|n| name.contains(n))
+}
+
+/// The device to record `source` from and its stream config. A device
+/// called `name` is used as is, whatever the source.
+fn open_input(
+    host: &cpal::Host,
+    name: Option<&str>,
+    source: CaptureSource,
+) -> Result<(cpal::Device, cpal::SupportedStreamConfig), DictationError> {
+    let device = match (name, source) {
+        (Some(name), _) => host
+            .input_devices()
+            .map_err(|e| DictationError::AudioCaptureError(format!("Failed to list input devices: {e}")))?
+            .find(|d| d.name().is_ok_and(|n| n == name))
+            .ok_or_else(|| {
+                DictationError::AudioCaptureError(format!(
+                    "No input device named '{name}'. Run: sagascript list-devices"
+                ))
+            })?,
+        (None, CaptureSource::Microphone) => host
+            .default_input_device()
+            .ok_or(DictationError::MicrophonePermissionDenied)?,
+        (None, CaptureSource::System) => return system_audio_input(host),
+    };
+    let config = device
+        .default_input_config()
+        .map_err(|e| DictationError::AudioCaptureError(format!("Failed to get input config: {e}")))?;
+    Ok((device, config))
+}
+
+/// WASAPI records an output device's mix when an input stream is built on
+/// it, so system audio is the default output in loopback.
+#[cfg(target_os = "windows")]
+fn system_audio_input(
+    host: &cpal::Host,
+) -> Result<(cpal::Device, cpal::SupportedStreamConfig), DictationError> {
+    let device = host.default_output_device().ok_or_else(|| {
+        DictationError::AudioCaptureError("No output device to record system audio from".into())
+    })?;
+    let config = device
+        .default_output_config()
+        .map_err(|e| DictationError::AudioCaptureError(format!("Failed to get output config: {e}")))?;
+    Ok((device, config))
+}
+
+/// Elsewhere cpal can't tap the output mix, so record from a loopback
+/// input device the user has installed.
+#[cfg(not(target_os = "windows"))]
+fn system_audio_input(
+    host: &cpal::Host,
+) -> Result<(cpal::Device, cpal::SupportedStreamConfig), DictationError> {
+    let device = host
+        .input_devices()
+        .map_err(|e| DictationError::AudioCaptureError(format!("Failed to list input devices: {e}")))?
+        .find(|d| d.name().is_ok_and(|n| is_loopback_device(&n)))
+        .ok_or_else(|| {
+            DictationError::AudioCaptureError(
+                "Recording system audio needs a loopback input device (BlackHole on macOS, a \
+                 PulseAudio/PipeWire monitor on Linux). Install one, or pick it with --device."
+                    .into(),
+            )
+        })?;
+    let config = device
+        .default_input_config()
+        .map_err(|e| DictationError::AudioCaptureError(format!("Failed to get input config: {e}")))?;
+    Ok((device, config))
+}
+
+/// What a capture thread's stream was opened with. A standby stream is
+/// reopened when the next recording wants something else.
+#[derive(Debug, Clone, PartialEq)]
+struct StreamConfig {
+    device: Option<String>,
+    source: CaptureSource,
+    monitor: bool,
+    max_recording: Duration,
+    /// Runs the silence detector.
+    silence: bool,
+}
 
 /// Audio capture service using cpal
 /// The cpal::Stream is !Send, so we spawn a dedicated thread to own it.
@@ -24,9 +270,33 @@ pub struct AudioCaptureService {
     /// Device sample rate published by the capture thread once the input opens
     /// (0 until known). Read by `stop_capture` to resample the whole buffer.
     device_sample_rate: Arc<AtomicU32>,
+    /// Name of the device the capture thread opened, published with the
+    /// sample rate and kept after the stream closes.
+    opened_device: Arc<Mutex<Option<String>>>,
     capture_thread: Option<thread::JoinHandle<()>>,
     /// Retained audio from last capture for retry
     last_captured: Option<Vec<f32>>,
+    /// Play the input back through the default output device while
+    /// recording; `None` when monitoring is off.
+    monitor: Option<MonitorVolume>,
+    /// Recording length at which the buffer stops taking audio.
+    max_recording: Duration,
+    /// Silence after speech that ends a recording; `None` to never stop on
+    /// silence.
+    silence_stop: Option<Duration>,
+    /// Trailing silence after speech in the current recording, in ms.
+    silence_ms: Arc<AtomicU32>,
+    /// Input device to record from by name; `None` for the source's default.
+    device: Option<String>,
+    /// Record the microphone or what the machine is playing.
+    source: CaptureSource,
+    /// Keep the stream open between recordings (see [`Self::set_standby`]).
+    standby: bool,
+    /// Whether callbacks keep their samples; a standby stream discards
+    /// them until a recording starts.
+    recording: Arc<AtomicBool>,
+    /// What the running capture thread opened; `None` without one.
+    stream_config: Option<StreamConfig>,
 }
 
 // AudioCaptureService is Send+Sync because it doesn't hold cpal::Stream directly
@@ -45,44 +315,228 @@ impl AudioCaptureService {
             buffer: Arc::new(Mutex::new(Vec::new())),
             stop_signal: Arc::new(Mutex::new(false)),
             device_sample_rate: Arc::new(AtomicU32::new(0)),
+            opened_device: Arc::new(Mutex::new(None)),
             capture_thread: None,
             last_captured: None,
+            monitor: None,
+            max_recording: DEFAULT_MAX_RECORDING,
+            silence_stop: None,
+            silence_ms: Arc::new(AtomicU32::new(0)),
+            device: None,
+            source: CaptureSource::Microphone,
+            standby: false,
+            recording: Arc::new(AtomicBool::new(false)),
+            stream_config: None,
+        }
+    }
+
+    /// Keep the input stream open between recordings, discarding its
+    /// samples until `start_capture`, so a recording starts without the
+    /// delay of opening the device. Turning it on opens the stream right
+    /// away (the OS shows the microphone as in use from then on); turning
+    /// it off closes it unless a recording is running.
+    pub fn set_standby(&mut self, on: bool) -> Result<(), DictationError> {
+        self.standby = on;
+        let recording = self.recording.load(Ordering::SeqCst);
+        if on && !recording && !self.stream_ready() {
+            self.close_stream();
+            self.open_stream(false)?;
+            info!("Audio input on standby");
+        } else if !on && !recording {
+            self.close_stream();
+        }
+        Ok(())
+    }
+
+    /// Record from the microphone or the system's output. Takes effect from
+    /// the next `start_capture`.
+    pub fn set_source(&mut self, source: CaptureSource) {
+        self.source = source;
+    }
+
+    /// Record from the input device called `name`, or the system default
+    /// (`None`). Takes effect from the next `start_capture`.
+    pub fn set_device(&mut self, name: Option<String>) {
+        self.device = name;
+    }
+
+    /// Name of the input device the last recording came from, once its
+    /// stream has opened; the default device's actual name when none was
+    /// chosen.
+    pub fn device_name(&self) -> Option<String> {
+        self.opened_device.lock().unwrap().clone()
+    }
+
+    /// Cap recordings at `max`. Takes effect from the next `start_capture`.
+    pub fn set_max_recording(&mut self, max: Duration) {
+        self.max_recording = max;
+    }
+
+    /// The cap the current (or next) recording runs under.
+    pub fn max_recording(&self) -> Duration {
+        self.max_recording
+    }
+
+    /// Stop on `silence` after speech, or never (`None`). Takes effect from
+    /// the next `start_capture`; callers poll [`Self::silence_timed_out`].
+    pub fn set_silence_stop(&mut self, silence: Option<Duration>) {
+        self.silence_stop = silence;
+    }
+
+    /// How long the input has been silent since the last speech in the
+    /// current recording. Zero until someone has spoken, and always zero
+    /// while silence stop is off.
+    pub fn trailing_silence(&self) -> Duration {
+        Duration::from_millis(self.silence_ms.load(Ordering::Relaxed).into())
+    }
+
+    /// Whether the current recording has been silent long enough to stop.
+    pub fn silence_timed_out(&self) -> bool {
+        self.silence_stop
+            .is_some_and(|stop| self.trailing_silence() >= stop)
+    }
+
+    /// Whether the current recording has filled the buffer, so audio is
+    /// being dropped.
+    pub fn is_full(&self) -> bool {
+        let device_rate = self.device_sample_rate.load(Ordering::SeqCst);
+        device_rate != 0
+            && self.buffer.lock().unwrap().len() >= max_samples(self.max_recording, device_rate)
+    }
+
+    /// Peak magnitude of the last `window` of the current recording, for a
+    /// level meter. `None` until the input device has opened.
+    pub fn recent_peak(&self, window: Duration) -> Option<f32> {
+        let device_rate = self.device_sample_rate.load(Ordering::SeqCst);
+        if device_rate == 0 {
+            return None;
         }
+        let len = (window.as_secs_f32() * device_rate as f32) as usize;
+        let buf = self.buffer.lock().unwrap();
+        let recent = &buf[buf.len().saturating_sub(len)..];
+        Some(recent.iter().fold(0.0f32, |peak, s| peak.max(s.abs())))
     }
 
-    /// Start capturing audio from the default input device
+    /// Turn input monitoring on (with the given volume) or off. Takes effect
+    /// from the next `start_capture`.
+    pub fn set_monitor(&mut self, volume: Option<f32>) {
+        // Keep the handle an open standby stream's monitor reads from.
+        match (&self.monitor, volume) {
+            (Some(monitor), Some(volume)) => monitor.set(volume),
+            _ => self.monitor = volume.map(MonitorVolume::new),
+        }
+    }
+
+    /// Change the monitor volume, including during a recording. No-op while
+    /// monitoring is off.
+    pub fn set_monitor_volume(&self, volume: f32) {
+        if let Some(monitor) = &self.monitor {
+            monitor.set(volume);
+        }
+    }
+
+    /// Start capturing audio from the selected (or default) input device
     pub fn start_capture(&mut self) -> Result<(), DictationError> {
-        // Clear previous buffer and stop signal
-        {
-            let mut buf = self.buffer.lock().unwrap();
-            buf.clear();
+        self.buffer.lock().unwrap().clear();
+        self.silence_ms.store(0, Ordering::Relaxed);
+
+        if self.stream_ready() {
+            self.recording.store(true, Ordering::SeqCst);
+            info!("Audio capture started (standby stream)");
+            return Ok(());
+        }
+
+        self.close_stream();
+        self.open_stream(true)?;
+
+        // Give the capture thread a moment to initialize
+        thread::sleep(std::time::Duration::from_millis(50));
+
+        info!("Audio capture started");
+        Ok(())
+    }
+
+    /// The config the next recording's stream is opened with.
+    fn stream_config(&self) -> StreamConfig {
+        StreamConfig {
+            device: self.device.clone(),
+            source: self.source,
+            monitor: self.monitor.is_some(),
+            max_recording: self.max_recording,
+            // A standby stream serves recordings with and without silence stop.
+            silence: self.standby || self.silence_stop.is_some(),
         }
-        {
-            let mut stop = self.stop_signal.lock().unwrap();
-            *stop = false;
+    }
+
+    /// Whether a standby stream is open with the config the next recording
+    /// wants.
+    fn stream_ready(&self) -> bool {
+        self.standby
+            && self
+                .capture_thread
+                .as_ref()
+                .is_some_and(|t| !t.is_finished())
+            && self.stream_config.as_ref() == Some(&self.stream_config())
+    }
+
+    /// Spawn the capture thread, keeping samples from the start if
+    /// `recording`.
+    fn open_stream(&mut self, recording: bool) -> Result<(), DictationError> {
+        // The capture thread only logs its errors, so catch a device that
+        // isn't there before starting it.
+        if self.device.is_some() || self.source == CaptureSource::System {
+            open_input(&cpal::default_host(), self.device.as_deref(), self.source)?;
         }
 
+        *self.stop_signal.lock().unwrap() = false;
+        self.recording.store(recording, Ordering::SeqCst);
+
         let buffer = Arc::clone(&self.buffer);
         let stop_signal = Arc::clone(&self.stop_signal);
         let device_sample_rate = Arc::clone(&self.device_sample_rate);
         device_sample_rate.store(0, Ordering::SeqCst);
+        let opened_device = Arc::clone(&self.opened_device);
+        let gate = Arc::clone(&self.recording);
+        let monitor = self.monitor.clone();
+        let max_recording = self.max_recording;
+        let device = self.device.clone();
+        let source = self.source;
+        let config = self.stream_config();
+        let silence_ms = config.silence.then(|| Arc::clone(&self.silence_ms));
 
         // Spawn a thread that owns the cpal::Stream
         let handle = thread::spawn(move || {
-            if let Err(e) = run_capture(buffer, stop_signal, device_sample_rate) {
+            if let Err(e) = run_capture(
+                buffer,
+                stop_signal,
+                device_sample_rate,
+                opened_device,
+                gate,
+                monitor,
+                max_recording,
+                silence_ms,
+                device.as_deref(),
+                source,
+            ) {
                 error!("Audio capture thread error: {e}");
             }
         });
 
         self.capture_thread = Some(handle);
-
-        // Give the capture thread a moment to initialize
-        thread::sleep(std::time::Duration::from_millis(50));
-
-        info!("Audio capture started");
+        self.stream_config = Some(config);
         Ok(())
     }
 
+    /// Stop the capture thread, if one is running, and wait for it.
+    fn close_stream(&mut self) {
+        *self.stop_signal.lock().unwrap() = true;
+        if let Some(handle) = self.capture_thread.take() {
+            let _ = handle.join();
+        }
+        self.stream_config = None;
+        self.recording.store(false, Ordering::SeqCst);
+    }
+
     /// Stop capturing and return the captured 16 kHz samples.
     ///
     /// On resample failure this returns `Err` (finding 4) rather than an empty
@@ -90,15 +544,12 @@ impl AudioCaptureService {
     /// error as empty made a real failure indistinguishable from silence (and
     /// surfaced the misleading "No audio captured" to the user).
     pub fn stop_capture(&mut self) -> Result<Vec<f32>, DictationError> {
-        // Signal the capture thread to stop
-        {
-            let mut stop = self.stop_signal.lock().unwrap();
-            *stop = true;
-        }
-
-        // Wait for the capture thread to finish
-        if let Some(handle) = self.capture_thread.take() {
-            let _ = handle.join();
+        // On standby the stream stays open and goes back to discarding;
+        // otherwise the capture thread is stopped.
+        if self.stream_ready() {
+            self.recording.store(false, Ordering::SeqCst);
+        } else {
+            self.close_stream();
         }
 
         let raw = {
@@ -136,6 +587,49 @@ impl AudioCaptureService {
         Ok(samples)
     }
 
+    /// Copy out and resample the audio captured after `from` while recording
+    /// continues, for transcribing long dictations in the background.
+    ///
+    /// Returns `Ok(None)` until at least `min_secs` of new audio is buffered.
+    /// The chunk ends at the quietest point within the last `search_secs` so
+    /// words aren't cut in half; the second value is the device-rate offset
+    /// to pass as `from` next time (and to [`Self::offset_at_16khz`] on stop).
+    pub fn chunk_since(
+        &self,
+        from: usize,
+        min_secs: f32,
+        search_secs: f32,
+    ) -> Result<Option<(Vec<f32>, usize)>, DictationError> {
+        let device_rate = self.device_sample_rate.load(Ordering::SeqCst);
+        if device_rate == 0 {
+            return Ok(None);
+        }
+        let min_len = (min_secs * device_rate as f32) as usize;
+        let search_len = (search_secs * device_rate as f32) as usize;
+
+        let raw = {
+            let buf = self.buffer.lock().unwrap();
+            if buf.len() < from + min_len {
+                return Ok(None);
+            }
+            let search_from = buf.len().saturating_sub(search_len).max(from + min_len / 2);
+            let end = quiet_split_point(&buf, search_from, device_rate);
+            buf[from..end].to_vec()
+        };
+        let end = from + raw.len();
+
+        let samples = resample_to_16khz(raw, device_rate).map_err(|e| {
+            DictationError::AudioCaptureError(format!("Resample failed: {e}"))
+        })?;
+        Ok(Some((samples, end)))
+    }
+
+    /// Map a device-rate offset returned by [`Self::chunk_since`] onto the
+    /// 16 kHz samples returned by [`Self::stop_capture`].
+    pub fn offset_at_16khz(&self, offset: usize) -> usize {
+        offset_at_16khz(offset, self.device_sample_rate.load(Ordering::SeqCst))
+    }
+
     /// Get the last captured audio for retry
     #[allow(dead_code)]
     pub fn last_captured_audio(&self) -> Option<&Vec<f32>> {
@@ -148,26 +642,36 @@ impl AudioCaptureService {
     }
 }
 
+impl Drop for AudioCaptureService {
+    fn drop(&mut self) {
+        self.close_stream();
+    }
+}
+
 /// Run audio capture on a dedicated thread (owns the !Send cpal::Stream)
+#[allow(clippy::too_many_arguments)]
 fn run_capture(
     buffer: Arc<Mutex<Vec<f32>>>,
     stop_signal: Arc<Mutex<bool>>,
     device_sample_rate_out: Arc<AtomicU32>,
+    opened_device_out: Arc<Mutex<Option<String>>>,
+    recording: Arc<AtomicBool>,
+    monitor: Option<MonitorVolume>,
+    max_recording: Duration,
+    silence_ms: Option<Arc<AtomicU32>>,
+    device_name: Option<&str>,
+    source: CaptureSource,
 ) -> Result<(), DictationError> {
     let host = cpal::default_host();
-    let device = host
-        .default_input_device()
-        .ok_or(DictationError::MicrophonePermissionDenied)?;
-
-    let config = device
-        .default_input_config()
-        .map_err(|e| DictationError::AudioCaptureError(format!("Failed to get input config: {e}")))?;
+    let (device, config) = open_input(&host, device_name, source)?;
 
     let device_sample_rate = config.sample_rate().0;
     let device_channels = config.channels();
+    let max_samples = max_samples(max_recording, device_sample_rate);
 
     // Publish the rate so stop_capture can resample the buffer.
     device_sample_rate_out.store(device_sample_rate, Ordering::SeqCst);
+    *opened_device_out.lock().unwrap() = device.name().ok();
 
     info!(
         "Audio input: {} Hz, {} ch, {:?}",
@@ -181,6 +685,12 @@ fn run_capture(
     };
 
     let buf_clone = Arc::clone(&buffer);
+    let monitor_buffer = monitor
+        .as_ref()
+        .map(|_| Arc::new(MonitorBuffer::new(device_sample_rate)));
+    let monitor_in = monitor_buffer.clone();
+    let mut vad = silence_ms.map(|ms| SilenceDetector::new(device_sample_rate, ms));
+    let mut was_recording = false;
 
     let stream = match config.sample_format() {
         SampleFormat::F32 => {
@@ -189,7 +699,16 @@ fn run_capture(
                 .build_input_stream(
                     &config,
                     move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                        process_samples(data, device_channels, device_sample_rate, &buf_clone);
+                        if !take_samples(&recording, &mut was_recording, &mut vad) {
+                            return;
+                        }
+                        process_samples(data, device_channels, max_samples, &buf_clone);
+                        if let Some(vad) = &mut vad {
+                            vad.push(data, device_channels);
+                        }
+                        if let Some(monitor) = &monitor_in {
+                            monitor.push(data, device_channels);
+                        }
                     },
                     err_fn,
                     None,
@@ -204,12 +723,16 @@ fn run_capture(
                 .build_input_stream(
                     &config,
                     move |data: &[i16], _: &cpal::InputCallbackInfo| {
-                        process_samples_i16(
-                            data,
-                            device_channels,
-                            device_sample_rate,
-                            &buf_clone,
-                        );
+                        if !take_samples(&recording, &mut was_recording, &mut vad) {
+                            return;
+                        }
+                        process_samples_i16(data, device_channels, max_samples, &buf_clone);
+                        if let Some(vad) = &mut vad {
+                            vad.push_i16(data, device_channels);
+                        }
+                        if let Some(monitor) = &monitor_in {
+                            monitor.push_i16(data, device_channels);
+                        }
                     },
                     err_fn,
                     None,
@@ -229,6 +752,21 @@ fn run_capture(
         .play()
         .map_err(|e| DictationError::AudioCaptureError(format!("Failed to start stream: {e}")))?;
 
+    // Monitoring is best-effort: a missing or busy output device must not
+    // cost the user their recording.
+    let _monitor_stream = match (monitor_buffer, monitor) {
+        (Some(buffer), Some(volume)) => {
+            match monitor::start_output(buffer, device_sample_rate, volume) {
+                Ok(stream) => Some(stream),
+                Err(e) => {
+                    warn!("Input monitoring unavailable: {e}");
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
     // Spin until stop signal (the stream callback fills the buffer)
     loop {
         thread::sleep(std::time::Duration::from_millis(10));
@@ -238,20 +776,41 @@ fn run_capture(
         }
     }
 
-    // Stream is dropped here, stopping capture
+    // Streams are dropped here, stopping capture and monitoring
     Ok(())
 }
 
+/// Whether a callback keeps its samples: only while recording, with the
+/// silence detector starting over at each recording's first callback.
+fn take_samples(
+    recording: &AtomicBool,
+    was_recording: &mut bool,
+    vad: &mut Option<SilenceDetector>,
+) -> bool {
+    let now = recording.load(Ordering::Relaxed);
+    if now && !*was_recording {
+        if let Some(vad) = vad {
+            vad.reset();
+        }
+    }
+    *was_recording = now;
+    now
+}
+
+/// The buffer cap for `max` of audio at `device_rate`.
+fn max_samples(max: Duration, device_rate: u32) -> usize {
+    (device_rate as usize).saturating_mul(max.as_secs() as usize)
+}
+
 fn process_samples(
     data: &[f32],
     channels: u16,
-    device_rate: u32,
+    max_samples: usize,
     buffer: &Arc<Mutex<Vec<f32>>>,
 ) {
     // Realtime-safe hot path: downmix to mono and append raw device-rate samples
     // with a length cap. No resampling and no per-callback allocation here —
     // resampling to 16 kHz happens once on stop (see stop_capture).
-    let max_samples = (device_rate as usize).saturating_mul(MAX_BUFFER_SECONDS);
     let channels = channels.max(1) as usize;
 
     let mut buf = buffer.lock().unwrap();
@@ -279,10 +838,9 @@ fn process_samples(
 fn process_samples_i16(
     data: &[i16],
     channels: u16,
-    device_rate: u32,
+    max_samples: usize,
     buffer: &Arc<Mutex<Vec<f32>>>,
 ) {
-    let max_samples = (device_rate as usize).saturating_mul(MAX_BUFFER_SECONDS);
     let channels = channels.max(1) as usize;
 
     let mut buf = buffer.lock().unwrap();
@@ -313,6 +871,13 @@ fn process_samples_i16(
 mod tests {
     use super::*;
 
+    #[test]
+    fn loopback_devices_are_recognised_by_name() {
+        assert!(is_loopback_device("BlackHole 2ch"));
+        assert!(is_loopback_device("Monitor of Built-in Audio Analog Stereo"));
+        assert!(!is_loopback_device("MacBook Pro Microphone"));
+    }
+
     fn buf() -> Arc<Mutex<Vec<f32>>> {
         Arc::new(Mutex::new(Vec::new()))
     }
@@ -358,19 +923,46 @@ mod tests {
     #[test]
     fn cap_enforced_f32() {
         let b = buf();
-        let cap = MAX_BUFFER_SECONDS; // rate = 1 → cap = MAX_BUFFER_SECONDS samples
-        process_samples(&vec![0.0f32; cap + 100], 1, 1, &b);
+        let cap = max_samples(DEFAULT_MAX_RECORDING, 1); // rate = 1 → one sample per second
+        process_samples(&vec![0.0f32; cap + 100], 1, cap, &b);
         assert_eq!(b.lock().unwrap().len(), cap);
     }
 
     #[test]
     fn cap_enforced_i16() {
         let b = buf();
-        let cap = MAX_BUFFER_SECONDS;
-        process_samples_i16(&vec![0i16; cap + 100], 1, 1, &b);
+        let cap = max_samples(DEFAULT_MAX_RECORDING, 1);
+        process_samples_i16(&vec![0i16; cap + 100], 1, cap, &b);
         assert_eq!(b.lock().unwrap().len(), cap);
     }
 
+    #[test]
+    fn limit_countdown_covers_the_last_minute() {
+        let max = DEFAULT_MAX_RECORDING;
+        assert_eq!(limit_remaining(Duration::ZERO, max), None);
+        assert_eq!(
+            limit_remaining(max - LIMIT_WARNING - Duration::from_secs(1), max),
+            None
+        );
+        assert_eq!(
+            limit_remaining(max - Duration::from_secs(10), max),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(limit_remaining(max, max), Some(Duration::ZERO));
+        assert_eq!(limit_remaining(max * 2, max), Some(Duration::ZERO));
+        // A limit under a minute counts down from the start.
+        assert_eq!(
+            limit_remaining(Duration::ZERO, Duration::from_secs(30)),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn buffer_memory_estimate() {
+        // 15 minutes of mono f32 at 48 kHz.
+        assert_eq!(buffer_bytes(DEFAULT_MAX_RECORDING, 48_000), 172_800_000);
+    }
+
     // Finding 4: stop_capture returns a Result so a real device/resample failure
     // is distinguishable from silence. With no capture ever started the buffer is
     // empty and the device rate unknown (0), so genuine silence must be
@@ -383,4 +975,117 @@ mod tests {
             .expect("silence should be Ok(empty), not Err");
         assert!(out.is_empty());
     }
+
+    #[test]
+    fn chunk_since_waits_for_minimum_audio() {
+        let svc = AudioCaptureService::new();
+        svc.device_sample_rate.store(16_000, Ordering::SeqCst);
+        svc.buffer.lock().unwrap().extend(vec![0.1f32; 8_000]);
+        assert!(svc.chunk_since(0, 1.0, 0.5).unwrap().is_none());
+    }
+
+    #[test]
+    fn chunk_since_returns_audio_up_to_split() {
+        let svc = AudioCaptureService::new();
+        svc.device_sample_rate.store(16_000, Ordering::SeqCst);
+        svc.buffer.lock().unwrap().extend(vec![0.1f32; 32_000]);
+        let (chunk, end) = svc.chunk_since(0, 1.0, 0.5).unwrap().unwrap();
+        assert!((24_000..=32_000).contains(&end));
+        assert_eq!(chunk.len(), end);
+
+        // The next chunk starts where the last one ended.
+        svc.buffer.lock().unwrap().extend(vec![0.1f32; 16_000]);
+        let (next, next_end) = svc.chunk_since(end, 1.0, 0.5).unwrap().unwrap();
+        assert_eq!(next.len(), next_end - end);
+        assert_eq!(svc.offset_at_16khz(next_end), next_end);
+    }
+
+    fn detector() -> SilenceDetector {
+        // 1 kHz: 30-sample frames.
+        SilenceDetector::new(1_000, Arc::new(AtomicU32::new(0)))
+    }
+
+    fn frames(detector: &mut SilenceDetector, level: f32, count: usize) {
+        let samples: Vec<f32> = (0..count * 30)
+            .map(|i| if i % 2 == 0 { level } else { -level })
+            .collect();
+        detector.push(&samples, 1);
+    }
+
+    #[test]
+    fn silence_before_speech_is_not_counted() {
+        let mut vad = detector();
+        frames(&mut vad, 0.001, 100);
+        assert_eq!(vad.silence_ms.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn silence_after_speech_accumulates_and_speech_resets_it() {
+        let mut vad = detector();
+        frames(&mut vad, 0.001, 10);
+        frames(&mut vad, 0.2, 10);
+        frames(&mut vad, 0.001, 50);
+        assert_eq!(vad.silence_ms.load(Ordering::Relaxed), 50 * VAD_FRAME_MS);
+        frames(&mut vad, 0.2, 1);
+        assert_eq!(vad.silence_ms.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn steady_background_noise_counts_as_silence() {
+        let mut vad = detector();
+        frames(&mut vad, 0.02, 20);
+        frames(&mut vad, 0.3, 10);
+        // Louder than the speech floor, but no louder than the room was.
+        frames(&mut vad, 0.02, 20);
+        assert_eq!(vad.silence_ms.load(Ordering::Relaxed), 20 * VAD_FRAME_MS);
+    }
+
+    #[test]
+    fn standby_discards_until_recording_and_restarts_the_detector() {
+        let recording = AtomicBool::new(false);
+        let mut was_recording = false;
+        let mut vad = Some(detector());
+        assert!(!take_samples(&recording, &mut was_recording, &mut vad));
+
+        recording.store(true, Ordering::Relaxed);
+        let v = vad.as_mut().unwrap();
+        frames(v, 0.2, 10);
+        frames(v, 0.001, 10);
+        assert!(v.heard_speech);
+        // The previous recording heard speech; the next one starts afresh.
+        recording.store(false, Ordering::Relaxed);
+        take_samples(&recording, &mut was_recording, &mut vad);
+        recording.store(true, Ordering::Relaxed);
+        assert!(take_samples(&recording, &mut was_recording, &mut vad));
+        assert!(!vad.as_ref().unwrap().heard_speech);
+    }
+
+    #[test]
+    fn standby_stream_is_reopened_for_a_different_config() {
+        let mut svc = AudioCaptureService::new();
+        svc.standby = true;
+        svc.set_monitor(Some(1.0));
+        let opened = svc.stream_config();
+        svc.set_monitor(Some(0.5));
+        assert_eq!(svc.stream_config(), opened, "volume changes reach the open stream");
+        svc.set_silence_stop(Some(Duration::from_secs(2)));
+        assert_eq!(svc.stream_config(), opened);
+        svc.set_device(Some("USB Mic".into()));
+        assert_ne!(svc.stream_config(), opened);
+        assert!(!svc.stream_ready(), "nothing is open yet");
+    }
+
+    #[test]
+    fn silence_stop_is_off_by_default() {
+        let svc = AudioCaptureService::new();
+        svc.silence_ms.store(60_000, Ordering::Relaxed);
+        assert!(!svc.silence_timed_out());
+    }
+
+    #[test]
+    fn chunk_since_unknown_rate_is_none() {
+        let svc = AudioCaptureService::new();
+        svc.buffer.lock().unwrap().extend(vec![0.1f32; 32_000]);
+        assert!(svc.chunk_since(0, 1.0, 0.5).unwrap().is_none());
+    }
 }
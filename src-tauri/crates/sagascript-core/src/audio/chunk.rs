@@ -0,0 +1,86 @@
+//! Helpers for splitting a still-growing recording into chunks that can be
+//! transcribed while capture continues.
+
+use super::resample::TARGET_SAMPLE_RATE;
+
+/// Length of the energy window used when looking for a pause (20 ms at 16 kHz,
+/// scaled to the actual sample rate by the caller).
+const QUIET_WINDOW_SECS: f32 = 0.02;
+
+/// Pick a split index in `samples[search_from..]` at the quietest short
+/// window, so a chunk boundary lands in a pause rather than mid-word.
+///
+/// Returns the middle of the lowest-energy window. If the search region is
+/// shorter than one window the end of the buffer is returned unchanged.
+pub fn quiet_split_point(samples: &[f32], search_from: usize, sample_rate: u32) -> usize {
+    let window = ((sample_rate as f32 * QUIET_WINDOW_SECS) as usize).max(1);
+    let start = search_from.min(samples.len());
+    if samples.len() - start < window {
+        return samples.len();
+    }
+
+    let mut best = start;
+    let mut best_energy = f32::INFINITY;
+    let mut pos = start;
+    while pos + window <= samples.len() {
+        let energy: f32 = samples[pos..pos + window].iter().map(|s| s * s).sum();
+        if energy < best_energy {
+            best_energy = energy;
+            best = pos;
+        }
+        pos += window;
+    }
+    best + window / 2
+}
+
+/// Map a sample offset at `device_rate` onto the 16 kHz timeline.
+///
+/// Used to cut the already-transcribed prefix off a recording that was
+/// resampled in one pass on stop. A rate of 0 (device never opened) maps to 0.
+pub fn offset_at_16khz(offset: usize, device_rate: u32) -> usize {
+    if device_rate == 0 {
+        return 0;
+    }
+    (offset as u64 * TARGET_SAMPLE_RATE as u64 / device_rate as u64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_lands_in_the_quiet_gap() {
+        let mut samples = vec![0.5f32; 16_000];
+        // 100 ms of silence starting at 0.6 s.
+        for s in &mut samples[9_600..11_200] {
+            *s = 0.0;
+        }
+        let split = quiet_split_point(&samples, 8_000, 16_000);
+        assert!((9_600..11_200).contains(&split), "split at {split}");
+    }
+
+    #[test]
+    fn split_outside_search_region_is_ignored() {
+        let mut samples = vec![0.5f32; 16_000];
+        for s in &mut samples[0..1_600] {
+            *s = 0.0;
+        }
+        let split = quiet_split_point(&samples, 8_000, 16_000);
+        assert!(split >= 8_000);
+    }
+
+    #[test]
+    fn short_search_region_returns_end() {
+        let samples = vec![0.1f32; 100];
+        assert_eq!(quiet_split_point(&samples, 90, 16_000), 100);
+        assert_eq!(quiet_split_point(&samples, 500, 16_000), 100);
+    }
+
+    #[test]
+    fn offset_scales_to_target_rate() {
+        assert_eq!(offset_at_16khz(48_000, 48_000), 16_000);
+        assert_eq!(offset_at_16khz(44_100 * 2, 44_100), 32_000);
+        assert_eq!(offset_at_16khz(16_000, 16_000), 16_000);
+        assert_eq!(offset_at_16khz(1_000, 0), 0);
+    }
+}
@@ -0,0 +1,142 @@
+//! Short tones played on the default output device, so a dictation profile
+//! can be recognized by ear as its recording starts.
+
+use std::thread;
+use std::time::Duration;
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::SampleFormat;
+use tracing::{error, warn};
+
+use crate::error::DictationError;
+use crate::settings::SoundCue;
+
+/// Length of a cue tone.
+const CUE_LENGTH: Duration = Duration::from_millis(120);
+
+/// Fade in and out over this long so the tone doesn't click.
+const FADE: Duration = Duration::from_millis(15);
+
+/// Peak amplitude; a cue should be noticeable, not startling.
+const CUE_VOLUME: f32 = 0.25;
+
+/// Play `cue` without blocking. Best-effort: a missing or busy output device
+/// is logged and otherwise ignored, since the recording matters more.
+pub fn play(cue: SoundCue) {
+    let Some(frequency) = cue.frequency() else {
+        return;
+    };
+    // The cpal stream is !Send, so it lives and dies on its own thread.
+    thread::spawn(move || {
+        if let Err(e) = play_blocking(frequency) {
+            warn!("Sound cue unavailable: {e}");
+        }
+    });
+}
+
+fn play_blocking(frequency: f32) -> Result<(), DictationError> {
+    let device = cpal::default_host()
+        .default_output_device()
+        .ok_or_else(|| DictationError::AudioCaptureError("No output device".into()))?;
+    let config = device.default_output_config().map_err(|e| {
+        DictationError::AudioCaptureError(format!("Failed to get output config: {e}"))
+    })?;
+    let channels = config.channels().max(1) as usize;
+    let samples = tone(frequency, config.sample_rate().0);
+    let mut position = 0;
+
+    let err_fn = |err: cpal::StreamError| error!("Sound cue stream error: {err}");
+    let build_err = |e: cpal::BuildStreamError| {
+        DictationError::AudioCaptureError(format!("Failed to build cue stream: {e}"))
+    };
+    let stream = match config.sample_format() {
+        SampleFormat::F32 => device
+            .build_output_stream(
+                &config.into(),
+                move |out: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                    fill(out, channels, &samples, &mut position, |s| s);
+                },
+                err_fn,
+                None,
+            )
+            .map_err(build_err)?,
+        SampleFormat::I16 => device
+            .build_output_stream(
+                &config.into(),
+                move |out: &mut [i16], _: &cpal::OutputCallbackInfo| {
+                    fill(out, channels, &samples, &mut position, |s| {
+                        (s * i16::MAX as f32) as i16
+                    });
+                },
+                err_fn,
+                None,
+            )
+            .map_err(build_err)?,
+        format => {
+            return Err(DictationError::AudioCaptureError(format!(
+                "Unsupported cue output format: {format:?}"
+            )));
+        }
+    };
+    stream.play().map_err(|e| {
+        DictationError::AudioCaptureError(format!("Failed to start cue stream: {e}"))
+    })?;
+    // Let the tone play out (plus a little for the device buffer) before the
+    // stream is dropped.
+    thread::sleep(CUE_LENGTH + FADE * 2);
+    Ok(())
+}
+
+/// Write the next frames of `samples` to every channel, then silence.
+fn fill<T: Copy>(
+    out: &mut [T],
+    channels: usize,
+    samples: &[f32],
+    position: &mut usize,
+    convert: impl Fn(f32) -> T,
+) {
+    for frame in out.chunks_mut(channels) {
+        frame.fill(convert(samples.get(*position).copied().unwrap_or(0.0)));
+        *position += 1;
+    }
+}
+
+/// A [`CUE_LENGTH`] sine at `frequency`, faded in and out.
+fn tone(frequency: f32, sample_rate: u32) -> Vec<f32> {
+    let samples_in = |d: Duration| (u128::from(sample_rate) * d.as_millis() / 1000) as usize;
+    let len = samples_in(CUE_LENGTH);
+    let fade = samples_in(FADE).max(1) as f32;
+    let rate = sample_rate.max(1) as f32;
+    (0..len)
+        .map(|i| {
+            let edge = i.min(len - 1 - i) as f32;
+            let envelope = (edge / fade).min(1.0);
+            let phase = 2.0 * std::f32::consts::PI * frequency * i as f32 / rate;
+            CUE_VOLUME * envelope * phase.sin()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tone_fades_in_and_out_and_stays_quiet() {
+        let samples = tone(660.0, 48_000);
+        assert_eq!(samples.len(), 5_760);
+        assert_eq!(samples[0], 0.0);
+        assert!(samples.last().unwrap().abs() < 1e-3);
+        assert!(samples.iter().all(|s| s.abs() <= CUE_VOLUME));
+        assert!(samples.iter().any(|s| s.abs() > CUE_VOLUME * 0.9));
+    }
+
+    #[test]
+    fn fill_repeats_each_sample_across_channels_then_goes_silent() {
+        let mut position = 0;
+        let mut out = [1.0f32; 6];
+        fill(&mut out, 2, &[0.1, 0.2], &mut position, |s| s);
+        assert_eq!(out, [0.1, 0.1, 0.2, 0.2, 0.0, 0.0]);
+        assert_eq!(position, 3);
+    }
+}
@@ -1,15 +1,18 @@
 use std::path::Path;
 
 use symphonia::core::audio::SampleBuffer;
-use symphonia::core::codecs::DecoderOptions;
-use symphonia::core::formats::FormatOptions;
+use symphonia::core::codecs::{Decoder, DecoderOptions};
+use symphonia::core::formats::{FormatOptions, FormatReader};
 use symphonia::core::io::MediaSourceStream;
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::probe::Hint;
-use tracing::info;
+use tracing::{info, warn};
 
-use super::resample::{mix_to_mono, resample_to_16khz};
+use super::chunk::quiet_split_point;
+use super::ffmpeg;
+use super::resample::{mix_to_mono, resample_to_16khz, split_channels, TARGET_SAMPLE_RATE};
 use crate::error::DictationError;
+use crate::progress::percent;
 
 /// Supported audio/video file extensions.
 pub const SUPPORTED_EXTENSIONS: &[&str] = &[
@@ -63,6 +66,200 @@ fn check_decode_size_cap(
 /// Uses symphonia to probe the file format, find the first audio track,
 /// decode all packets, then resample and mix to mono.
 pub fn decode_audio_file(path: &Path) -> Result<Vec<f32>, DictationError> {
+    decode_audio_file_with_progress(path, |_| {})
+}
+
+/// [`decode_audio_file_with_progress`], retrying through a user-installed
+/// ffmpeg (see [`super::ffmpeg`]) when `ffmpeg_fallback` is on and symphonia
+/// can't decode the file. The symphonia error is returned if no ffmpeg is
+/// found, or if the fallback is off.
+pub fn decode_audio_file_with_fallback(
+    path: &Path,
+    ffmpeg_fallback: bool,
+    mut on_progress: impl FnMut(u32),
+) -> Result<Vec<f32>, DictationError> {
+    let error = match decode_audio_file_with_progress(path, &mut on_progress) {
+        Err(error @ DictationError::FileDecodeError(_)) if ffmpeg_fallback => error,
+        result => return result,
+    };
+    let Some(ffmpeg) = ffmpeg::find_ffmpeg() else {
+        info!("Symphonia failed ({error}) and no ffmpeg was found for the fallback");
+        return Err(error);
+    };
+    warn!("Symphonia failed ({error}); falling back to ffmpeg");
+    let samples = ffmpeg::decode_with_ffmpeg(&ffmpeg, path)?;
+    on_progress(100);
+    Ok(samples)
+}
+
+/// [`decode_audio_file`], reporting decode progress (0–100) as packets are
+/// read. Progress is based on the track's frame count, so containers that
+/// don't declare one only report 100 once decoding finishes.
+pub fn decode_audio_file_with_progress(
+    path: &Path,
+    mut on_progress: impl FnMut(u32),
+) -> Result<Vec<f32>, DictationError> {
+    let (all_samples, actual_channels, sample_rate) =
+        decode_interleaved(path, &mut on_progress)?;
+
+    // Mix to mono and resample
+    let mono = mix_to_mono(&all_samples, actual_channels);
+    let resampled = resample_to_16khz(mono, sample_rate)
+        .map_err(|e| DictationError::TranscriptionFailed(format!("Resample failed: {e}")))?;
+
+    info!(
+        "Resampled to {} samples ({:.1}s at 16kHz)",
+        resampled.len(),
+        resampled.len() as f64 / 16_000.0
+    );
+
+    on_progress(100);
+    Ok(resampled)
+}
+
+/// Decode a file to one 16 kHz buffer per channel instead of mixing them,
+/// for recordings with a speaker on each channel. A mono file gives one.
+pub fn decode_audio_file_channels(
+    path: &Path,
+    mut on_progress: impl FnMut(u32),
+) -> Result<Vec<Vec<f32>>, DictationError> {
+    let (all_samples, actual_channels, sample_rate) =
+        decode_interleaved(path, &mut on_progress)?;
+    let channels = split_channels(&all_samples, actual_channels)
+        .into_iter()
+        .map(|channel| {
+            resample_to_16khz(channel, sample_rate).map_err(|e| {
+                DictationError::TranscriptionFailed(format!("Resample failed: {e}"))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    on_progress(100);
+    Ok(channels)
+}
+
+/// Decode the whole first audio track, returning its interleaved samples,
+/// channel count and sample rate.
+fn decode_interleaved(
+    path: &Path,
+    on_progress: &mut impl FnMut(u32),
+) -> Result<(Vec<f32>, usize, u32), DictationError> {
+    let mut track = open_track(path)?;
+    let sample_rate = track.sample_rate;
+    let mut all_samples: Vec<f32> = Vec::new();
+    let mut actual_channels: usize = track.codec_channels.max(1);
+
+    track.decode_packets(&mut on_progress, |samples, channels| {
+        actual_channels = channels;
+        all_samples.extend_from_slice(samples);
+
+        // Abort early (before accumulating further) if this file would decode
+        // to an unreasonably long clip. Checked every packet so we bail out
+        // during decode rather than after the huge buffer already exists.
+        check_decode_size_cap(all_samples.len(), actual_channels, sample_rate)
+    })?;
+
+    if all_samples.is_empty() {
+        return Err(DictationError::FileDecodeError(
+            "No audio samples decoded from file".to_string(),
+        ));
+    }
+
+    let duration_secs = all_samples.len() as f64 / (sample_rate as f64 * actual_channels as f64);
+    info!(
+        "Decoded {} raw samples ({:.1}s), {} ch at {} Hz",
+        all_samples.len(),
+        duration_secs,
+        actual_channels,
+        sample_rate,
+    );
+    Ok((all_samples, actual_channels, sample_rate))
+}
+
+/// Files at least this long (by their declared duration) are decoded with
+/// [`decode_audio_file_streaming`] rather than into one buffer.
+pub const STREAM_DECODE_AFTER_SECS: f64 = 30.0 * 60.0;
+
+/// Audio per streamed chunk. Long enough that chunk boundaries are rare next
+/// to whisper's own 30-second windows, short enough to keep memory flat
+/// (2 minutes of mono f32 at 48 kHz is ~23 MB).
+pub const STREAM_CHUNK_SECS: f32 = 120.0;
+
+/// How far back from a chunk's nominal end to look for a pause to split at.
+const STREAM_SPLIT_SEARCH_SECS: f32 = 5.0;
+
+/// Declared duration of the file's audio track in seconds, without decoding
+/// it. `None` if the file can't be probed or the container doesn't say.
+pub fn probe_duration(path: &Path) -> Option<f64> {
+    let track = open_track(path).ok()?;
+    let frames = track.total_frames?;
+    Some(frames as f64 / track.sample_rate.max(1) as f64)
+}
+
+/// Decode a file in chunks of roughly [`STREAM_CHUNK_SECS`], each handed to
+/// `on_chunk` as 16 kHz mono before the next one is decoded, so memory stays
+/// bounded however long the file is. Chunks are split in the quietest spot
+/// near their end to avoid cutting a word. Because nothing accumulates,
+/// [`MAX_DECODE_SAMPLES`] doesn't apply here.
+///
+/// Returns the total decoded duration in seconds.
+pub fn decode_audio_file_streaming(
+    path: &Path,
+    mut on_chunk: impl FnMut(Vec<f32>) -> Result<(), DictationError>,
+    mut on_progress: impl FnMut(u32),
+) -> Result<f64, DictationError> {
+    let mut track = open_track(path)?;
+    let sample_rate = track.sample_rate;
+    let chunk_len = (STREAM_CHUNK_SECS * sample_rate as f32) as usize;
+    let search = (STREAM_SPLIT_SEARCH_SECS * sample_rate as f32) as usize;
+    let mut pending: Vec<f32> = Vec::new();
+    let mut total_samples: u64 = 0;
+
+    let mut deliver = |mono: Vec<f32>| -> Result<(), DictationError> {
+        let resampled = resample_to_16khz(mono, sample_rate)
+            .map_err(|e| DictationError::TranscriptionFailed(format!("Resample failed: {e}")))?;
+        total_samples += resampled.len() as u64;
+        on_chunk(resampled)
+    };
+
+    track.decode_packets(&mut on_progress, |samples, channels| {
+        pending.extend(mix_to_mono(samples, channels));
+        if pending.len() < chunk_len {
+            return Ok(());
+        }
+        let split = quiet_split_point(&pending, chunk_len.saturating_sub(search), sample_rate);
+        let rest = pending.split_off(split);
+        deliver(std::mem::replace(&mut pending, rest))
+    })?;
+    if !pending.is_empty() {
+        deliver(pending)?;
+    }
+
+    if total_samples == 0 {
+        return Err(DictationError::FileDecodeError(
+            "No audio samples decoded from file".to_string(),
+        ));
+    }
+    on_progress(100);
+    Ok(total_samples as f64 / TARGET_SAMPLE_RATE as f64)
+}
+
+/// A probed file positioned at the start of its first audio track.
+struct OpenedTrack {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    sample_rate: u32,
+    /// Channel count from the codec parameters. Can be wrong (e.g. AAC stereo
+    /// reporting 1); the real count comes from each decoded frame.
+    codec_channels: usize,
+    /// Track length in frames, if the container declares it.
+    total_frames: Option<u64>,
+}
+
+/// Validate the extension, probe the container and set up a decoder for the
+/// first audio track.
+fn open_track(path: &Path) -> Result<OpenedTrack, DictationError> {
     // Validate extension
     let ext = path
         .extension()
@@ -102,7 +299,7 @@ pub fn decode_audio_file(path: &Path) -> Result<Vec<f32>, DictationError> {
             DictationError::FileDecodeError(format!("Failed to probe file format: {e}"))
         })?;
 
-    let mut format = probed.format;
+    let format = probed.format;
 
     // Find the first audio track
     let track = format
@@ -117,96 +314,87 @@ pub fn decode_audio_file(path: &Path) -> Result<Vec<f32>, DictationError> {
 
     let track_id = track.id;
     let sample_rate = track.codec_params.sample_rate.unwrap_or(44_100);
-    // channels from codec_params can be wrong (e.g. AAC stereo reporting 1).
-    // We'll detect the real channel count from the first decoded frame.
     let codec_channels = track.codec_params.channels.map(|c| c.count()).unwrap_or(0);
+    let total_frames = track.codec_params.n_frames;
 
     info!(
         "Decoding audio: {} Hz, codec_channels={}, codec {:?}",
         sample_rate, codec_channels, track.codec_params.codec
     );
 
-    let mut decoder = symphonia::default::get_codecs()
+    let decoder = symphonia::default::get_codecs()
         .make(&track.codec_params, &DecoderOptions::default())
         .map_err(|e| {
             DictationError::FileDecodeError(format!("Failed to create decoder: {e}"))
         })?;
 
-    let mut all_samples: Vec<f32> = Vec::new();
-    let mut actual_channels: usize = codec_channels.max(1);
-
-    // Decode all packets
-    loop {
-        let packet = match format.next_packet() {
-            Ok(p) => p,
-            Err(symphonia::core::errors::Error::IoError(ref e))
-                if e.kind() == std::io::ErrorKind::UnexpectedEof =>
-            {
-                break; // End of stream
-            }
-            Err(e) => {
-                // Log non-fatal decode errors and continue
-                info!("Decode warning (skipping packet): {e}");
-                continue;
-            }
-        };
-
-        // Skip packets from other tracks
-        if packet.track_id() != track_id {
-            continue;
-        }
+    Ok(OpenedTrack {
+        format,
+        decoder,
+        track_id,
+        sample_rate,
+        codec_channels,
+        total_frames,
+    })
+}
 
-        let decoded = match decoder.decode(&packet) {
-            Ok(d) => d,
-            Err(e) => {
-                info!("Decode warning (skipping packet): {e}");
+impl OpenedTrack {
+    /// Decode every packet of the track, passing each one's interleaved
+    /// samples and actual channel count to `on_packet`. Undecodable packets
+    /// are logged and skipped; an error from `on_packet` stops decoding.
+    fn decode_packets(
+        &mut self,
+        on_progress: &mut impl FnMut(u32),
+        mut on_packet: impl FnMut(&[f32], usize) -> Result<(), DictationError>,
+    ) -> Result<(), DictationError> {
+        let mut last_pct = None;
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(p) => p,
+                Err(symphonia::core::errors::Error::IoError(ref e))
+                    if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    break; // End of stream
+                }
+                Err(e) => {
+                    // Log non-fatal decode errors and continue
+                    info!("Decode warning (skipping packet): {e}");
+                    continue;
+                }
+            };
+
+            // Skip packets from other tracks
+            if packet.track_id() != self.track_id {
                 continue;
             }
-        };
-
-        let spec = *decoded.spec();
-        // Use actual channel count from decoded frame spec (more reliable than codec_params)
-        actual_channels = spec.channels.count().max(1);
-        let num_frames = decoded.capacity();
-
-        let mut sample_buf = SampleBuffer::<f32>::new(num_frames as u64, spec);
-        sample_buf.copy_interleaved_ref(decoded);
-
-        all_samples.extend_from_slice(sample_buf.samples());
-
-        // Abort early (before accumulating further) if this file would decode
-        // to an unreasonably long clip. Checked every packet so we bail out
-        // during decode rather than after the huge buffer already exists.
-        check_decode_size_cap(all_samples.len(), actual_channels, sample_rate)?;
-    }
 
-    if all_samples.is_empty() {
-        return Err(DictationError::FileDecodeError(
-            "No audio samples decoded from file".to_string(),
-        ));
+            let decoded = match self.decoder.decode(&packet) {
+                Ok(d) => d,
+                Err(e) => {
+                    info!("Decode warning (skipping packet): {e}");
+                    continue;
+                }
+            };
+
+            let spec = *decoded.spec();
+            // Use actual channel count from decoded frame spec (more reliable than codec_params)
+            let channels = spec.channels.count().max(1);
+            let num_frames = decoded.capacity();
+
+            let mut sample_buf = SampleBuffer::<f32>::new(num_frames as u64, spec);
+            sample_buf.copy_interleaved_ref(decoded);
+
+            on_packet(sample_buf.samples(), channels)?;
+
+            if let Some(pct) = percent(packet.ts() + packet.dur(), self.total_frames) {
+                if last_pct != Some(pct) {
+                    last_pct = Some(pct);
+                    on_progress(pct);
+                }
+            }
+        }
+        Ok(())
     }
-
-    let duration_secs = all_samples.len() as f64 / (sample_rate as f64 * actual_channels as f64);
-    info!(
-        "Decoded {} raw samples ({:.1}s), {} ch at {} Hz, resampling to 16kHz mono",
-        all_samples.len(),
-        duration_secs,
-        actual_channels,
-        sample_rate,
-    );
-
-    // Mix to mono and resample
-    let mono = mix_to_mono(&all_samples, actual_channels);
-    let resampled = resample_to_16khz(mono, sample_rate)
-        .map_err(|e| DictationError::TranscriptionFailed(format!("Resample failed: {e}")))?;
-
-    info!(
-        "Resampled to {} samples ({:.1}s at 16kHz)",
-        resampled.len(),
-        resampled.len() as f64 / 16_000.0
-    );
-
-    Ok(resampled)
 }
 
 #[cfg(test)]
@@ -289,6 +477,77 @@ mod tests {
         );
     }
 
+    #[test]
+    fn streaming_decode_yields_bounded_chunks_covering_the_file() {
+        // 250 s at 16 kHz: two full chunks and a remainder.
+        let samples = vec![0.1f32; 250 * 16_000];
+        let tmp = std::env::temp_dir().join("sagascript_test_decode_streaming.wav");
+        std::fs::write(&tmp, crate::audio::wav::encode_wav(&samples)).unwrap();
+
+        assert_eq!(probe_duration(&tmp).map(|d| d.round()), Some(250.0));
+
+        let mut chunk_lens = Vec::new();
+        let result = decode_audio_file_streaming(
+            &tmp,
+            |chunk| {
+                chunk_lens.push(chunk.len());
+                Ok(())
+            },
+            |_| {},
+        );
+        let _ = std::fs::remove_file(&tmp);
+
+        let total = result.unwrap();
+        assert!((total - 250.0).abs() < 0.1, "decoded {total}s");
+        assert_eq!(chunk_lens.len(), 3, "{chunk_lens:?}");
+        let max_chunk = ((STREAM_CHUNK_SECS + 1.0) * 16_000.0) as usize;
+        assert!(chunk_lens.iter().all(|&n| n <= max_chunk), "{chunk_lens:?}");
+    }
+
+    #[test]
+    fn streaming_decode_stops_on_chunk_error() {
+        let samples = vec![0.1f32; 16_000];
+        let tmp = std::env::temp_dir().join("sagascript_test_decode_streaming_err.wav");
+        std::fs::write(&tmp, crate::audio::wav::encode_wav(&samples)).unwrap();
+        let result = decode_audio_file_streaming(
+            &tmp,
+            |_| Err(DictationError::TranscriptionFailed("aborted".into())),
+            |_| {},
+        );
+        let _ = std::fs::remove_file(&tmp);
+        assert!(matches!(result, Err(DictationError::TranscriptionFailed(_))));
+    }
+
+    #[test]
+    fn fallback_off_returns_the_symphonia_error() {
+        let tmp = std::env::temp_dir().join("sagascript_test_fallback_off.m4a");
+        std::fs::write(&tmp, b"not an mp4 container").unwrap();
+        let result = decode_audio_file_with_fallback(&tmp, false, |_| {});
+        let _ = std::fs::remove_file(&tmp);
+        assert!(matches!(result, Err(DictationError::FileDecodeError(_))));
+    }
+
+    #[test]
+    fn fallback_does_not_retry_unsupported_extensions() {
+        let result = decode_audio_file_with_fallback(Path::new("/tmp/file.xyz"), true, |_| {});
+        assert!(matches!(result, Err(DictationError::UnsupportedFormat(_))));
+    }
+
+    #[test]
+    fn decode_progress_is_monotonic_and_finishes_at_100() {
+        let samples = vec![0.1f32; 32_000];
+        let tmp = std::env::temp_dir().join("sagascript_test_decode_progress.wav");
+        std::fs::write(&tmp, crate::audio::wav::encode_wav(&samples)).unwrap();
+
+        let mut reports = Vec::new();
+        let result = decode_audio_file_with_progress(&tmp, |pct| reports.push(pct));
+        let _ = std::fs::remove_file(&tmp);
+
+        result.unwrap();
+        assert_eq!(reports.last(), Some(&100));
+        assert!(reports.windows(2).all(|w| w[0] <= w[1]), "{reports:?}");
+    }
+
     #[test]
     fn supported_extensions_no_duplicates() {
         let mut seen = std::collections::HashSet::new();
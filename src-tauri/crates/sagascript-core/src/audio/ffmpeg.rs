@@ -0,0 +1,137 @@
+//! Fallback decoding through a user-installed ffmpeg.
+//!
+//! Symphonia handles the common formats, but some real-world m4a/webm files
+//! (fragmented MP4, odd codec parameters) fail to probe. When the
+//! `ffmpeg_fallback` setting is on and an ffmpeg binary can be found, the
+//! file is piped through `ffmpeg -f f32le -ar 16000 -ac 1` instead. ffmpeg is
+//! never bundled or downloaded; it is only used if already installed.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use tracing::info;
+
+use super::decoder::MAX_DECODE_SAMPLES;
+use super::resample::TARGET_SAMPLE_RATE;
+use crate::error::DictationError;
+
+/// Environment variable naming an ffmpeg binary to use instead of searching.
+pub const FFMPEG_ENV: &str = "SAGASCRIPT_FFMPEG";
+
+/// Install locations checked after `PATH`. Apps launched from Finder don't
+/// inherit the shell's `PATH`, so Homebrew's prefixes are listed explicitly.
+const EXTRA_DIRS: &[&str] = &["/opt/homebrew/bin", "/usr/local/bin", "/usr/bin"];
+
+/// Locate an ffmpeg binary: `SAGASCRIPT_FFMPEG`, then `PATH`, then the
+/// common install prefixes.
+pub fn find_ffmpeg() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(FFMPEG_ENV) {
+        let path = PathBuf::from(path);
+        return path.is_file().then_some(path);
+    }
+    let exe = if cfg!(windows) {
+        "ffmpeg.exe"
+    } else {
+        "ffmpeg"
+    };
+    let path_dirs = std::env::var_os("PATH")
+        .map(|p| std::env::split_paths(&p).collect::<Vec<_>>())
+        .unwrap_or_default();
+    path_dirs
+        .into_iter()
+        .chain(EXTRA_DIRS.iter().map(PathBuf::from))
+        .map(|dir| dir.join(exe))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Decode `path` to 16 kHz mono f32 with the given ffmpeg binary.
+pub fn decode_with_ffmpeg(ffmpeg: &Path, path: &Path) -> Result<Vec<f32>, DictationError> {
+    info!("Decoding {} with {}", path.display(), ffmpeg.display());
+    let mut child = Command::new(ffmpeg)
+        .args(["-nostdin", "-hide_banner", "-loglevel", "error", "-i"])
+        .arg(path)
+        .args(["-vn", "-f", "f32le", "-ac", "1", "-ar"])
+        .arg(TARGET_SAMPLE_RATE.to_string())
+        .arg("-")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| DictationError::FileDecodeError(format!("Failed to run ffmpeg: {e}")))?;
+
+    // Read with the same duration cap as the symphonia path, so a huge or
+    // corrupt file can't grow the buffer without bound.
+    let max_bytes = (MAX_DECODE_SAMPLES * 4) as u64;
+    let mut bytes = Vec::new();
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let read = stdout.take(max_bytes + 1).read_to_end(&mut bytes);
+    if bytes.len() as u64 > max_bytes {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(DictationError::FileDecodeError(
+            "Audio file is too long to decode (exceeds the ~4 hour limit)".to_string(),
+        ));
+    }
+    read.map_err(|e| {
+        DictationError::FileDecodeError(format!("Failed to read ffmpeg output: {e}"))
+    })?;
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| DictationError::FileDecodeError(format!("ffmpeg did not exit: {e}")))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().last().unwrap_or("no error output").trim();
+        return Err(DictationError::FileDecodeError(format!(
+            "ffmpeg failed ({}): {reason}",
+            output.status
+        )));
+    }
+
+    let samples = parse_f32le(&bytes);
+    if samples.is_empty() {
+        return Err(DictationError::FileDecodeError(
+            "No audio samples decoded from file".to_string(),
+        ));
+    }
+    info!(
+        "ffmpeg decoded {} samples ({:.1}s at 16kHz)",
+        samples.len(),
+        samples.len() as f64 / TARGET_SAMPLE_RATE as f64
+    );
+    Ok(samples)
+}
+
+/// Little-endian f32 PCM to samples. A trailing partial sample is dropped.
+fn parse_f32le(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_little_endian_samples() {
+        let mut bytes: Vec<u8> = [0.5f32, -1.0]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        bytes.push(0xff); // torn final sample
+        assert_eq!(parse_f32le(&bytes), [0.5, -1.0]);
+    }
+
+    #[test]
+    fn missing_binary_is_a_decode_error() {
+        let err = decode_with_ffmpeg(
+            Path::new("/nonexistent/ffmpeg"),
+            Path::new("/nonexistent/input.m4a"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, DictationError::FileDecodeError(_)));
+    }
+}
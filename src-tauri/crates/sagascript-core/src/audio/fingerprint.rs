@@ -0,0 +1,132 @@
+//! Coarse audio fingerprints for spotting a repeated dictation.
+//!
+//! An accidental double press can hand the same captured audio to
+//! transcription twice, which pastes the same sentence twice. A fingerprint
+//! is the loudness envelope of a 16 kHz buffer in 20 ms frames; two
+//! recordings whose envelopes line up almost exactly are the same audio.
+//! Saying the same words again never matches this closely: pauses, pitch and
+//! pace all shift the envelope.
+
+/// Samples per envelope frame (20 ms at 16 kHz).
+const FRAME: usize = 320;
+
+/// Recordings shorter than this many frames (0.5 s) are never compared;
+/// short clicks and breaths look alike.
+const MIN_FRAMES: usize = 25;
+
+/// Largest offset tried when aligning two envelopes (0.5 s), for a press
+/// that started the capture a little earlier or later.
+const MAX_LAG: usize = 25;
+
+/// Lengths may differ by at most this fraction.
+const MAX_LENGTH_DIFFERENCE: f32 = 0.1;
+
+/// Correlation at or above which two envelopes count as the same audio.
+const MATCH_CORRELATION: f32 = 0.95;
+
+/// Loudness envelope of one recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fingerprint {
+    /// Log RMS per frame.
+    envelope: Vec<f32>,
+}
+
+impl Fingerprint {
+    /// Fingerprint of a 16 kHz mono buffer.
+    pub fn of(samples: &[f32]) -> Self {
+        let envelope = samples
+            .chunks_exact(FRAME)
+            .map(|frame| {
+                let energy = frame.iter().map(|s| s * s).sum::<f32>() / FRAME as f32;
+                (energy.sqrt() + 1e-4).ln()
+            })
+            .collect();
+        Self { envelope }
+    }
+
+    /// Whether `other` is, as far as the envelope can tell, the same audio.
+    pub fn matches(&self, other: &Fingerprint) -> bool {
+        self.similarity(other) >= MATCH_CORRELATION
+    }
+
+    /// Best correlation of the two envelopes over the offsets tried, from -1
+    /// to 1; 0 for recordings too short or too different in length to compare.
+    pub fn similarity(&self, other: &Fingerprint) -> f32 {
+        let (a, b) = (&self.envelope, &other.envelope);
+        let (shorter, longer) = (a.len().min(b.len()), a.len().max(b.len()));
+        let length_difference = (longer - shorter) as f32 / longer.max(1) as f32;
+        if shorter < MIN_FRAMES || length_difference > MAX_LENGTH_DIFFERENCE {
+            return 0.0;
+        }
+        let max_lag = MAX_LAG.min(shorter - MIN_FRAMES);
+        (0..=max_lag)
+            .flat_map(|lag| [correlation(&a[lag..], b), correlation(a, &b[lag..])])
+            .fold(0.0, f32::max)
+    }
+}
+
+/// Pearson correlation of the overlapping start of `a` and `b`. A flat
+/// envelope (digital silence) correlates with nothing.
+fn correlation(a: &[f32], b: &[f32]) -> f32 {
+    let n = a.len().min(b.len());
+    let (a, b) = (&a[..n], &b[..n]);
+    let mean_a = a.iter().sum::<f32>() / n as f32;
+    let mean_b = b.iter().sum::<f32>() / n as f32;
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        let (dx, dy) = (x - mean_a, y - mean_b);
+        cov += dx * dy;
+        var_a += dx * dx;
+        var_b += dy * dy;
+    }
+    if var_a <= f32::EPSILON || var_b <= f32::EPSILON {
+        return 0.0;
+    }
+    cov / (var_a * var_b).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two seconds of a tone with a syllable-like loudness contour; `seed`
+    /// sets the syllable rate.
+    fn speech_like(seed: u32) -> Vec<f32> {
+        (0..32_000)
+            .map(|i| {
+                let t = i as f32 / 16_000.0;
+                let rate = 3.0 + seed as f32;
+                let contour = ((t * rate).sin() * (t * 7.1).cos()).abs();
+                contour * (t * 220.0 * std::f32::consts::TAU).sin()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_audio_matches_even_when_shifted() {
+        let audio = speech_like(0);
+        let print = Fingerprint::of(&audio);
+        assert!(print.matches(&Fingerprint::of(&audio)));
+
+        let mut later = vec![0.0; 1_600];
+        later.extend_from_slice(&audio[..audio.len() - 1_600]);
+        assert!(print.matches(&Fingerprint::of(&later)));
+    }
+
+    #[test]
+    fn different_audio_does_not_match() {
+        let print = Fingerprint::of(&speech_like(0));
+        assert!(!print.matches(&Fingerprint::of(&speech_like(5))));
+        // Far longer or shorter recordings aren't compared at all.
+        let audio = speech_like(0);
+        assert_eq!(print.similarity(&Fingerprint::of(&audio[..16_000])), 0.0);
+    }
+
+    #[test]
+    fn silence_and_short_clips_never_match() {
+        let silence = Fingerprint::of(&vec![0.0; 32_000]);
+        assert!(!silence.matches(&silence));
+        let click = Fingerprint::of(&speech_like(0)[..4_000]);
+        assert!(!click.matches(&click));
+    }
+}
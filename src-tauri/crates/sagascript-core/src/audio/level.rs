@@ -0,0 +1,143 @@
+//! Input level checks on a captured buffer.
+//!
+//! A clipped or barely audible recording explains most "whisper heard
+//! something else" reports, and neither is obvious from the transcript.
+//! These checks run once on the finished 16 kHz buffer so the CLI and the
+//! app can say so next to the result. The per-peak helpers also drive the
+//! CLI's live level meter while `sagascript record` runs.
+
+use serde::Serialize;
+
+/// Samples at or above this magnitude count as clipped.
+const CLIP_LEVEL: f32 = 0.99;
+
+/// Fraction of clipped samples above which the recording is flagged.
+/// Occasional full-scale peaks are normal; sustained clipping is not.
+const CLIP_FRACTION: f32 = 0.001;
+
+/// Peak below which the recording is flagged as too quiet (about -36 dBFS).
+const QUIET_PEAK: f32 = 0.016;
+
+/// Something about the input level likely hurt transcription accuracy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LevelWarning {
+    /// A noticeable share of samples hit full scale.
+    Clipping,
+    /// The loudest sample was barely above the noise floor.
+    Quiet,
+}
+
+impl LevelWarning {
+    pub fn message(&self) -> &'static str {
+        match self {
+            LevelWarning::Clipping => "Input was clipping — consider lowering the microphone gain",
+            LevelWarning::Quiet => {
+                "Input was very quiet — move closer to the microphone or raise its gain"
+            }
+        }
+    }
+}
+
+/// Quietest level a meter shows; anything below reads as silence.
+pub const METER_FLOOR_DB: f32 = -60.0;
+
+/// Peak magnitude in dBFS, floored at [`METER_FLOOR_DB`].
+pub fn peak_dbfs(peak: f32) -> f32 {
+    (20.0 * peak.max(f32::MIN_POSITIVE).log10()).max(METER_FLOOR_DB)
+}
+
+/// Whether a peak is loud enough to be speech at a usable gain, the same
+/// bar [`analyze`] holds a whole recording to.
+pub fn is_audible(peak: f32) -> bool {
+    peak >= QUIET_PEAK
+}
+
+/// Whether a peak hit full scale.
+pub fn is_clipping(peak: f32) -> bool {
+    peak >= CLIP_LEVEL
+}
+
+/// Check a captured buffer for clipping or near-silence. Returns `None` for
+/// an empty buffer, which callers already report as "no audio captured".
+pub fn analyze(samples: &[f32]) -> Option<LevelWarning> {
+    if samples.is_empty() {
+        return None;
+    }
+    let mut peak = 0.0f32;
+    let mut clipped = 0usize;
+    for &s in samples {
+        let magnitude = s.abs();
+        peak = peak.max(magnitude);
+        if magnitude >= CLIP_LEVEL {
+            clipped += 1;
+        }
+    }
+    if clipped as f32 / samples.len() as f32 > CLIP_FRACTION {
+        Some(LevelWarning::Clipping)
+    } else if peak < QUIET_PEAK {
+        Some(LevelWarning::Quiet)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| amplitude * (i as f32 * 0.05).sin())
+            .collect()
+    }
+
+    #[test]
+    fn normal_speech_level_passes() {
+        assert_eq!(analyze(&sine(0.3, 16_000)), None);
+    }
+
+    #[test]
+    fn sustained_clipping_is_flagged() {
+        // Overdriven sine clamped to full scale, as an ADC would.
+        let samples: Vec<f32> = sine(3.0, 16_000)
+            .into_iter()
+            .map(|s| s.clamp(-1.0, 1.0))
+            .collect();
+        assert_eq!(analyze(&samples), Some(LevelWarning::Clipping));
+    }
+
+    #[test]
+    fn a_single_peak_is_not_clipping() {
+        let mut samples = sine(0.3, 16_000);
+        samples[100] = 1.0;
+        assert_eq!(analyze(&samples), None);
+    }
+
+    #[test]
+    fn near_silence_is_flagged() {
+        assert_eq!(analyze(&sine(0.005, 16_000)), Some(LevelWarning::Quiet));
+    }
+
+    #[test]
+    fn empty_buffer_has_no_warning() {
+        assert_eq!(analyze(&[]), None);
+    }
+
+    #[test]
+    fn peaks_map_to_dbfs_down_to_the_floor() {
+        assert_eq!(peak_dbfs(1.0), 0.0);
+        assert!((peak_dbfs(0.1) + 20.0).abs() < 1e-4);
+        assert_eq!(peak_dbfs(0.0), METER_FLOOR_DB);
+        assert!(is_audible(0.3) && !is_audible(0.005));
+        assert!(is_clipping(1.0) && !is_clipping(0.5));
+    }
+
+    #[test]
+    fn serializes_lowercase() {
+        assert_eq!(
+            serde_json::to_string(&LevelWarning::Clipping).unwrap(),
+            "\"clipping\""
+        );
+    }
+}
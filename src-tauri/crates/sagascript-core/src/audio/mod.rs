@@ -2,8 +2,20 @@
 // build carries no cpal — and on Linux, no ALSA.
 #[cfg(feature = "record")]
 pub mod capture;
+pub mod chunk;
+#[cfg(feature = "record")]
+pub mod cue;
 pub mod decoder;
+pub mod ffmpeg;
+pub mod fingerprint;
+pub mod level;
+#[cfg(feature = "record")]
+pub mod monitor;
+pub mod ogg;
+#[cfg(feature = "opus")]
+pub mod opus;
 pub mod resample;
+pub mod upload;
 pub mod wav;
 
 #[cfg(feature = "record")]
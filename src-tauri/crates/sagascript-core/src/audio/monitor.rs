@@ -0,0 +1,276 @@
+//! Input monitoring: plays the microphone back through the default output
+//! device while recording, so levels can be checked by ear.
+//!
+//! Meant for headphones — on speakers the microphone picks the playback up
+//! again and it feeds back. Capture never depends on the monitor: if the
+//! output device can't be opened, recording carries on without it.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use cpal::traits::{DeviceTrait, HostTrait};
+use cpal::SampleFormat;
+use tracing::{error, info};
+
+use crate::error::DictationError;
+use crate::settings::MAX_MONITOR_VOLUME;
+
+/// Most audio the monitor holds before dropping the oldest samples. Keeps the
+/// playback delay bounded when the output device consumes more slowly than
+/// the input produces (drifting clocks, a stalled callback).
+const MAX_LATENCY_MS: u32 = 60;
+
+/// Shared, lock-free monitor gain, adjustable while recording.
+#[derive(Debug, Clone)]
+pub struct MonitorVolume(Arc<AtomicU32>);
+
+impl MonitorVolume {
+    pub fn new(volume: f32) -> Self {
+        let v = Self(Arc::new(AtomicU32::new(0)));
+        v.set(volume);
+        v
+    }
+
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    /// Set the gain, clamped to `0.0..=MAX_MONITOR_VOLUME`.
+    pub fn set(&self, volume: f32) {
+        let volume = if volume.is_finite() {
+            volume.clamp(0.0, MAX_MONITOR_VOLUME)
+        } else {
+            0.0
+        };
+        self.0.store(volume.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// Mono samples at the input rate, queued between the input callback and the
+/// output callback.
+pub struct MonitorBuffer {
+    queue: Mutex<VecDeque<f32>>,
+    capacity: usize,
+}
+
+impl MonitorBuffer {
+    pub fn new(input_rate: u32) -> Self {
+        let capacity = (input_rate as usize * MAX_LATENCY_MS as usize / 1000).max(1);
+        Self {
+            queue: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    /// Queue interleaved f32 frames, downmixed to mono.
+    pub fn push(&self, data: &[f32], channels: u16) {
+        let channels = channels.max(1) as usize;
+        let mut queue = self.queue.lock().unwrap();
+        for frame in data.chunks(channels) {
+            Self::push_one(
+                &mut queue,
+                self.capacity,
+                frame.iter().sum::<f32>() / channels as f32,
+            );
+        }
+    }
+
+    /// Queue interleaved i16 frames, downmixed to mono.
+    pub fn push_i16(&self, data: &[i16], channels: u16) {
+        let channels = channels.max(1) as usize;
+        let mut queue = self.queue.lock().unwrap();
+        for frame in data.chunks(channels) {
+            let sum: f32 = frame.iter().map(|&s| s as f32 / i16::MAX as f32).sum();
+            Self::push_one(&mut queue, self.capacity, sum / channels as f32);
+        }
+    }
+
+    fn push_one(queue: &mut VecDeque<f32>, capacity: usize, sample: f32) {
+        if queue.len() >= capacity {
+            queue.pop_front();
+        }
+        queue.push_back(sample);
+    }
+
+    fn pop(&self) -> Option<f32> {
+        self.queue.lock().unwrap().pop_front()
+    }
+}
+
+/// Converts the input rate to the output rate by holding each input sample
+/// for as many output frames as it covers. Crude, but monitoring only has to
+/// be intelligible and cheap enough for the realtime callback.
+struct RateConverter {
+    step: f64,
+    phase: f64,
+    current: f32,
+}
+
+impl RateConverter {
+    fn new(input_rate: u32, output_rate: u32) -> Self {
+        Self {
+            step: input_rate as f64 / output_rate.max(1) as f64,
+            // Start "due" so the first output frame takes the first sample.
+            phase: 1.0,
+            current: 0.0,
+        }
+    }
+
+    /// Next output sample. On underrun the output goes silent rather than
+    /// repeating the last sample, which would turn into a tone.
+    fn next(&mut self, buffer: &MonitorBuffer) -> f32 {
+        while self.phase >= 1.0 {
+            self.phase -= 1.0;
+            self.current = buffer.pop().unwrap_or(0.0);
+        }
+        self.phase += self.step;
+        self.current
+    }
+
+    /// Fill interleaved output frames, writing the same sample to every channel.
+    fn fill<T: Copy>(
+        &mut self,
+        out: &mut [T],
+        channels: u16,
+        buffer: &MonitorBuffer,
+        volume: f32,
+        convert: impl Fn(f32) -> T,
+    ) {
+        for frame in out.chunks_mut(channels.max(1) as usize) {
+            let sample = convert((self.next(buffer) * volume).clamp(-1.0, 1.0));
+            frame.fill(sample);
+        }
+    }
+}
+
+/// Open and start the monitor output stream on the default output device.
+/// The returned stream plays until dropped; like the capture stream it is
+/// `!Send`, so it must live on the capture thread.
+pub(crate) fn start_output(
+    buffer: Arc<MonitorBuffer>,
+    input_rate: u32,
+    volume: MonitorVolume,
+) -> Result<cpal::Stream, DictationError> {
+    use cpal::traits::StreamTrait;
+
+    let device = cpal::default_host()
+        .default_output_device()
+        .ok_or_else(|| {
+            DictationError::AudioCaptureError("No output device for monitoring".into())
+        })?;
+    let config = device.default_output_config().map_err(|e| {
+        DictationError::AudioCaptureError(format!("Failed to get output config: {e}"))
+    })?;
+    let channels = config.channels();
+    let mut converter = RateConverter::new(input_rate, config.sample_rate().0);
+    info!(
+        "Monitor output: {} Hz, {} ch, {:?}",
+        config.sample_rate().0,
+        channels,
+        config.sample_format()
+    );
+
+    let err_fn = |err: cpal::StreamError| error!("Monitor stream error: {err}");
+    let build_err = |e: cpal::BuildStreamError| {
+        DictationError::AudioCaptureError(format!("Failed to build monitor stream: {e}"))
+    };
+
+    let stream = match config.sample_format() {
+        SampleFormat::F32 => device
+            .build_output_stream(
+                &config.into(),
+                move |out: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                    converter.fill(out, channels, &buffer, volume.get(), |s| s);
+                },
+                err_fn,
+                None,
+            )
+            .map_err(build_err)?,
+        SampleFormat::I16 => device
+            .build_output_stream(
+                &config.into(),
+                move |out: &mut [i16], _: &cpal::OutputCallbackInfo| {
+                    converter.fill(out, channels, &buffer, volume.get(), |s| {
+                        (s * i16::MAX as f32) as i16
+                    });
+                },
+                err_fn,
+                None,
+            )
+            .map_err(build_err)?,
+        format => {
+            return Err(DictationError::AudioCaptureError(format!(
+                "Unsupported monitor output format: {format:?}"
+            )));
+        }
+    };
+    stream.play().map_err(|e| {
+        DictationError::AudioCaptureError(format!("Failed to start monitor stream: {e}"))
+    })?;
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffer_downmixes_and_drops_oldest_past_latency_cap() {
+        // 1 kHz input -> capacity of MAX_LATENCY_MS samples.
+        let buffer = MonitorBuffer::new(1_000);
+        let frames: Vec<f32> = (0..100).flat_map(|i| [i as f32, i as f32]).collect();
+        buffer.push(&frames, 2);
+        assert_eq!(buffer.queue.lock().unwrap().len(), MAX_LATENCY_MS as usize);
+        // The oldest 40 frames were dropped.
+        assert_eq!(buffer.pop(), Some(40.0));
+    }
+
+    #[test]
+    fn i16_input_is_scaled_to_unit_range() {
+        let buffer = MonitorBuffer::new(16_000);
+        buffer.push_i16(&[i16::MAX, 0], 1);
+        assert!((buffer.pop().unwrap() - 1.0).abs() < 1e-4);
+        assert_eq!(buffer.pop(), Some(0.0));
+    }
+
+    #[test]
+    fn converter_upsamples_by_holding_samples() {
+        let buffer = MonitorBuffer::new(16_000);
+        buffer.push(&[0.1, 0.2], 1);
+        let mut conv = RateConverter::new(16_000, 32_000);
+        let out: Vec<f32> = (0..5).map(|_| conv.next(&buffer)).collect();
+        assert_eq!(out, [0.1, 0.1, 0.2, 0.2, 0.0]);
+    }
+
+    #[test]
+    fn converter_downsamples_by_skipping() {
+        let buffer = MonitorBuffer::new(16_000);
+        buffer.push(&[0.1, 0.2, 0.3, 0.4], 1);
+        let mut conv = RateConverter::new(32_000, 16_000);
+        assert_eq!(conv.next(&buffer), 0.1);
+        assert_eq!(conv.next(&buffer), 0.3);
+    }
+
+    #[test]
+    fn fill_applies_volume_to_every_channel() {
+        let buffer = MonitorBuffer::new(16_000);
+        buffer.push(&[0.8], 1);
+        let mut conv = RateConverter::new(16_000, 16_000);
+        let mut out = [0.0f32; 2];
+        conv.fill(&mut out, 2, &buffer, 0.5, |s| s);
+        assert_eq!(out, [0.4, 0.4]);
+    }
+
+    #[test]
+    fn volume_is_clamped() {
+        let v = MonitorVolume::new(5.0);
+        assert_eq!(v.get(), MAX_MONITOR_VOLUME);
+        v.set(-1.0);
+        assert_eq!(v.get(), 0.0);
+        v.set(f32::NAN);
+        assert_eq!(v.get(), 0.0);
+        v.set(0.7);
+        assert!((v.get() - 0.7).abs() < 1e-6);
+    }
+}
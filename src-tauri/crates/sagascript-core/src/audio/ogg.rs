@@ -0,0 +1,221 @@
+//! Minimal Ogg page writer (RFC 3533) for wrapping Opus packets.
+//!
+//! Only what an encoder needs: one logical stream, packets packed into pages
+//! of up to 255 lacing segments, BOS/EOS flags and the page checksum. Header
+//! packets are flushed onto their own pages as the Ogg Opus mapping requires.
+
+const HEADER_TYPE_CONTINUED: u8 = 0x01;
+const HEADER_TYPE_BOS: u8 = 0x02;
+const HEADER_TYPE_EOS: u8 = 0x04;
+
+const MAX_SEGMENTS: usize = 255;
+
+/// Writes a single logical Ogg bitstream into memory.
+pub struct OggWriter {
+    out: Vec<u8>,
+    serial: u32,
+    sequence: u32,
+    lacing: Vec<u8>,
+    body: Vec<u8>,
+    /// Granule of the last packet that ends on the page being built.
+    page_granule: Option<u64>,
+    /// Granule of the last packet written, for the EOS page.
+    last_granule: u64,
+    /// The page being built starts with the tail of a packet from the previous page.
+    continued: bool,
+}
+
+impl OggWriter {
+    pub fn new(serial: u32) -> Self {
+        Self {
+            out: Vec::new(),
+            serial,
+            sequence: 0,
+            lacing: Vec::new(),
+            body: Vec::new(),
+            page_granule: None,
+            last_granule: 0,
+            continued: false,
+        }
+    }
+
+    /// Append one packet. `granule` is the stream position once this packet
+    /// is decoded (for Opus: 48 kHz samples including pre-skip).
+    pub fn write_packet(&mut self, packet: &[u8], granule: u64) {
+        let mut remaining = packet;
+        loop {
+            if self.lacing.len() == MAX_SEGMENTS {
+                // The packet carries on into the next page.
+                self.write_page(false);
+                self.continued = true;
+            }
+            let len = remaining.len().min(255);
+            self.lacing.push(len as u8);
+            self.body.extend_from_slice(&remaining[..len]);
+            remaining = &remaining[len..];
+            // A 255-byte segment means "more follows", so a packet whose
+            // length is a multiple of 255 still needs a terminating 0 segment.
+            if len < 255 {
+                break;
+            }
+        }
+        self.page_granule = Some(granule);
+        self.last_granule = granule;
+    }
+
+    /// Close the current page so the next packet starts a fresh one.
+    pub fn flush_page(&mut self) {
+        if !self.lacing.is_empty() {
+            self.write_page(false);
+        }
+    }
+
+    /// Write the final page with the EOS flag and return the stream bytes.
+    pub fn finish(mut self) -> Vec<u8> {
+        self.write_page(true);
+        self.out
+    }
+
+    fn write_page(&mut self, eos: bool) {
+        // A page on which no packet ends is marked with granule -1. The EOS
+        // page always carries the final position so decoders can trim.
+        let granule = match self.page_granule {
+            Some(g) => g,
+            None if eos => self.last_granule,
+            None => u64::MAX,
+        };
+        let mut header_type = 0;
+        if self.continued {
+            header_type |= HEADER_TYPE_CONTINUED;
+        }
+        if self.sequence == 0 {
+            header_type |= HEADER_TYPE_BOS;
+        }
+        if eos {
+            header_type |= HEADER_TYPE_EOS;
+        }
+
+        let start = self.out.len();
+        self.out.extend_from_slice(b"OggS");
+        self.out.push(0); // stream structure version
+        self.out.push(header_type);
+        self.out.extend_from_slice(&granule.to_le_bytes());
+        self.out.extend_from_slice(&self.serial.to_le_bytes());
+        self.out.extend_from_slice(&self.sequence.to_le_bytes());
+        self.out.extend_from_slice(&0u32.to_le_bytes()); // checksum, filled below
+        self.out.push(self.lacing.len() as u8);
+        self.out.extend_from_slice(&self.lacing);
+        self.out.extend_from_slice(&self.body);
+
+        let crc = crc32(&self.out[start..]);
+        self.out[start + 22..start + 26].copy_from_slice(&crc.to_le_bytes());
+
+        self.sequence += 1;
+        self.lacing.clear();
+        self.body.clear();
+        self.page_granule = None;
+        self.continued = false;
+    }
+}
+
+/// Ogg's CRC-32: polynomial 0x04c11db7, zero initial value, no reflection.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0u32;
+    for &byte in data {
+        crc ^= (byte as u32) << 24;
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                (crc << 1) ^ 0x04c1_1db7
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Split a stream back into (header_type, granule, sequence, lacing, body).
+    fn pages(data: &[u8]) -> Vec<(u8, u64, u32, Vec<u8>, Vec<u8>)> {
+        let mut pages = Vec::new();
+        let mut pos = 0;
+        while pos < data.len() {
+            assert_eq!(&data[pos..pos + 4], b"OggS");
+            let header_type = data[pos + 5];
+            let granule = u64::from_le_bytes(data[pos + 6..pos + 14].try_into().unwrap());
+            let sequence = u32::from_le_bytes(data[pos + 18..pos + 22].try_into().unwrap());
+            let stored_crc = u32::from_le_bytes(data[pos + 22..pos + 26].try_into().unwrap());
+            let n = data[pos + 26] as usize;
+            let lacing = data[pos + 27..pos + 27 + n].to_vec();
+            let body_len: usize = lacing.iter().map(|&l| l as usize).sum();
+            let end = pos + 27 + n + body_len;
+            let body = data[pos + 27 + n..end].to_vec();
+
+            let mut zeroed = data[pos..end].to_vec();
+            zeroed[22..26].fill(0);
+            assert_eq!(crc32(&zeroed), stored_crc);
+
+            pages.push((header_type, granule, sequence, lacing, body));
+            pos = end;
+        }
+        pages
+    }
+
+    #[test]
+    fn header_page_then_packets_then_eos() {
+        let mut w = OggWriter::new(7);
+        w.write_packet(b"OpusHead", 0);
+        w.flush_page();
+        w.write_packet(&[1; 10], 960);
+        w.write_packet(&[2; 20], 1920);
+        let data = w.finish();
+
+        let pages = pages(&data);
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].0, HEADER_TYPE_BOS);
+        assert_eq!(pages[0].4, b"OpusHead");
+        assert_eq!(pages[1].0, HEADER_TYPE_EOS);
+        assert_eq!(pages[1].1, 1920);
+        assert_eq!(pages[1].2, 1);
+        assert_eq!(pages[1].3, vec![10, 20]);
+    }
+
+    #[test]
+    fn packet_of_255_bytes_gets_a_terminating_segment() {
+        let mut w = OggWriter::new(1);
+        w.write_packet(&[0; 255], 1);
+        let pages = pages(&w.finish());
+        assert_eq!(pages[0].3, vec![255, 0]);
+    }
+
+    #[test]
+    fn full_page_continues_packet_on_next_page() {
+        let mut w = OggWriter::new(1);
+        for i in 0..254 {
+            w.write_packet(&[0; 3], i);
+        }
+        // Needs two segments; only one fits on the first page.
+        w.write_packet(&[9; 300], 1000);
+        let pages = pages(&w.finish());
+
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].3.len(), MAX_SEGMENTS);
+        assert_eq!(pages[0].1, 253);
+        assert_eq!(pages[1].0, HEADER_TYPE_CONTINUED | HEADER_TYPE_EOS);
+        assert_eq!(pages[1].3, vec![45]);
+        assert_eq!(pages[1].1, 1000);
+    }
+
+    #[test]
+    fn page_without_a_finished_packet_has_no_granule() {
+        let mut w = OggWriter::new(1);
+        w.write_packet(&[0; 255 * 300], 5);
+        let pages = pages(&w.finish());
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].1, u64::MAX);
+        assert_eq!(pages[1].1, 5);
+    }
+}
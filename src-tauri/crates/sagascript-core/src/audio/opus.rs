@@ -0,0 +1,117 @@
+//! Ogg Opus encoding of 16 kHz mono recordings.
+//!
+//! Speech at 24 kbit/s Opus is roughly a tenth the size of the 16-bit WAV from
+//! [`super::wav::encode_wav`] with no measurable effect on Whisper accuracy,
+//! so it is the format to use when a clip has to be uploaded or archived.
+
+use ::opus::{Application, Bitrate, Channels, Encoder};
+
+use super::ogg::OggWriter;
+use super::resample::TARGET_SAMPLE_RATE;
+use crate::error::DictationError;
+
+/// Target bitrate: plenty for a single voice, well under WAV's 256 kbit/s.
+const OPUS_BITRATE: i32 = 24_000;
+
+/// 20 ms frames, the usual choice for speech.
+const FRAME_SAMPLES: usize = TARGET_SAMPLE_RATE as usize / 50;
+
+/// Ogg Opus granule positions always count 48 kHz samples.
+const GRANULE_PER_SAMPLE: u64 = 48_000 / TARGET_SAMPLE_RATE as u64;
+
+/// Largest packet libopus can produce for one frame.
+const MAX_PACKET_BYTES: usize = 1275;
+
+/// Encode f32 samples (16kHz mono) as an Ogg Opus file.
+pub fn encode_ogg_opus(samples: &[f32]) -> Result<Vec<u8>, DictationError> {
+    let opus_err = |e: ::opus::Error| DictationError::OutputError(format!("Opus encoder: {e}"));
+
+    let mut encoder =
+        Encoder::new(TARGET_SAMPLE_RATE, Channels::Mono, Application::Voip).map_err(opus_err)?;
+    encoder
+        .set_bitrate(Bitrate::Bits(OPUS_BITRATE))
+        .map_err(opus_err)?;
+    let lookahead = encoder.get_lookahead().map_err(opus_err)?.max(0) as u64;
+    let pre_skip = lookahead * GRANULE_PER_SAMPLE;
+
+    let mut ogg = OggWriter::new(uuid::Uuid::new_v4().as_u128() as u32);
+    ogg.write_packet(&opus_head(pre_skip as u16), 0);
+    ogg.flush_page();
+    ogg.write_packet(&opus_tags(), 0);
+    ogg.flush_page();
+
+    // The encoder delays output by `lookahead` samples; feed enough trailing
+    // silence that the last real samples make it into a packet.
+    let total = samples.len() + lookahead as usize;
+    let mut frame = vec![0.0f32; FRAME_SAMPLES];
+    let mut packet = vec![0u8; MAX_PACKET_BYTES];
+    let mut fed = 0;
+    while fed < total {
+        let start = fed.min(samples.len());
+        let end = (fed + FRAME_SAMPLES).min(samples.len());
+        frame.fill(0.0);
+        frame[..end - start].copy_from_slice(&samples[start..end]);
+        let len = encoder.encode_float(&frame, &mut packet).map_err(opus_err)?;
+        fed += FRAME_SAMPLES;
+
+        // Granules count decoded samples including pre-skip; capping the last
+        // one at pre-skip + clip length tells players to drop the padding.
+        let granule = fed.min(total) as u64 * GRANULE_PER_SAMPLE;
+        ogg.write_packet(&packet[..len], granule);
+    }
+
+    Ok(ogg.finish())
+}
+
+/// Identification header (RFC 7845 §5.1), mono, channel mapping family 0.
+fn opus_head(pre_skip: u16) -> Vec<u8> {
+    let mut head = Vec::with_capacity(19);
+    head.extend_from_slice(b"OpusHead");
+    head.push(1); // version
+    head.push(1); // channel count
+    head.extend_from_slice(&pre_skip.to_le_bytes());
+    head.extend_from_slice(&TARGET_SAMPLE_RATE.to_le_bytes());
+    head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+    head.push(0); // mapping family
+    head
+}
+
+/// Comment header (RFC 7845 §5.2) with a vendor string and no comments.
+fn opus_tags() -> Vec<u8> {
+    let vendor = concat!("sagascript ", env!("CARGO_PKG_VERSION"));
+    let mut tags = Vec::with_capacity(16 + vendor.len());
+    tags.extend_from_slice(b"OpusTags");
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor.as_bytes());
+    tags.extend_from_slice(&0u32.to_le_bytes());
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::wav::encode_wav;
+
+    #[test]
+    fn opus_head_layout() {
+        let head = opus_head(312);
+        assert_eq!(head.len(), 19);
+        assert_eq!(&head[..8], b"OpusHead");
+        assert_eq!(u16::from_le_bytes([head[10], head[11]]), 312);
+        assert_eq!(
+            u32::from_le_bytes(head[12..16].try_into().unwrap()),
+            TARGET_SAMPLE_RATE
+        );
+    }
+
+    #[test]
+    fn encodes_speech_band_audio_much_smaller_than_wav() {
+        // 5 s of a 220 Hz tone.
+        let samples: Vec<f32> = (0..5 * TARGET_SAMPLE_RATE as usize)
+            .map(|i| 0.3 * (i as f32 * 220.0 * std::f32::consts::TAU / 16_000.0).sin())
+            .collect();
+        let ogg = encode_ogg_opus(&samples).unwrap();
+        assert_eq!(&ogg[..4], b"OggS");
+        assert!(ogg.len() * 5 < encode_wav(&samples).len());
+    }
+}
@@ -15,6 +15,14 @@ pub fn mix_to_mono(data: &[f32], channels: usize) -> Vec<f32> {
         .collect()
 }
 
+/// Split interleaved audio into one buffer per channel.
+pub fn split_channels(data: &[f32], channels: usize) -> Vec<Vec<f32>> {
+    let channels = channels.max(1);
+    (0..channels)
+        .map(|c| data.iter().skip(c).step_by(channels).copied().collect())
+        .collect()
+}
+
 /// High-quality sinc resample from `source_rate` to `TARGET_SAMPLE_RATE` (16 kHz).
 /// Uses rubato's SincFixedIn with sinc interpolation.
 /// Returns the input unchanged if rates already match.
@@ -111,6 +119,13 @@ mod tests {
         assert!((result[0] - 0.6).abs() < 1e-6);
     }
 
+    #[test]
+    fn split_channels_deinterleaves() {
+        let result = split_channels(&[1.0, -1.0, 0.5, -0.5, 0.2, -0.2], 2);
+        assert_eq!(result, vec![vec![1.0, 0.5, 0.2], vec![-1.0, -0.5, -0.2]]);
+        assert_eq!(split_channels(&[0.1, 0.2], 1), vec![vec![0.1, 0.2]]);
+    }
+
     #[test]
     fn mix_to_mono_empty() {
         let result = mix_to_mono(&[], 2);
@@ -0,0 +1,260 @@
+//! Size planning for sending audio to a transcription API with an upload cap.
+//!
+//! Sagascript transcribes locally unless the user opts into a remote
+//! backend (see `transcription::backend`). OpenAI's `/audio/transcriptions`
+//! caps uploads at 25 MB and Azure's short-audio endpoint at 60 seconds, so
+//! these helpers check a clip before it is sent and
+//! split a long one at pauses instead of failing at the API with an opaque
+//! 413. Builds with the `opus` feature upload Ogg Opus ([`UploadFormat`]),
+//! which fits roughly ten times as much audio under the same cap as WAV.
+
+use std::ops::Range;
+
+use super::chunk::quiet_split_point;
+use super::resample::TARGET_SAMPLE_RATE;
+use crate::error::DictationError;
+
+/// Upload limit of the OpenAI transcription endpoint.
+pub const OPENAI_MAX_UPLOAD_BYTES: usize = 25 * 1024 * 1024;
+
+/// Azure Speech's short-audio REST endpoint accepts 60 seconds per request,
+/// whatever the format.
+pub const AZURE_MAX_UPLOAD_SAMPLES: usize = 60 * TARGET_SAMPLE_RATE as usize;
+
+/// RIFF/fmt/data header written by [`super::wav::encode_wav`].
+const WAV_HEADER_BYTES: usize = 44;
+
+/// 16-bit mono PCM.
+const WAV_BYTES_PER_SAMPLE: usize = 2;
+
+/// How far back from a chunk's size limit to look for a pause.
+const SPLIT_SEARCH_SECS: usize = 5;
+
+/// Upper bound on Ogg Opus bytes per second of audio: the encoder's 24 kbit/s
+/// with headroom for variable bitrate and Ogg framing.
+#[cfg(feature = "opus")]
+const OPUS_MAX_BYTES_PER_SEC: usize = 4_000;
+
+/// Ogg Opus identification and comment headers, with headroom.
+#[cfg(feature = "opus")]
+const OPUS_HEADER_BYTES: usize = 1_024;
+
+/// How audio is encoded for an upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadFormat {
+    /// 16-bit mono PCM.
+    Wav,
+    /// Ogg Opus at 24 kbit/s, about a tenth of the WAV size.
+    #[cfg(feature = "opus")]
+    OggOpus,
+}
+
+impl UploadFormat {
+    /// Ogg Opus where this build has the encoder, WAV otherwise.
+    pub fn best() -> Self {
+        #[cfg(feature = "opus")]
+        {
+            Self::OggOpus
+        }
+        #[cfg(not(feature = "opus"))]
+        {
+            Self::Wav
+        }
+    }
+
+    /// File name for a multipart upload; services read the format from it.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Wav => "audio.wav",
+            #[cfg(feature = "opus")]
+            Self::OggOpus => "audio.ogg",
+        }
+    }
+
+    /// MIME type of the encoded audio, for a multipart part's header.
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Wav => "audio/wav",
+            #[cfg(feature = "opus")]
+            Self::OggOpus => "audio/ogg",
+        }
+    }
+
+    /// Encoded size of `sample_count` 16 kHz samples: exact for WAV, an
+    /// upper bound for Ogg Opus.
+    pub fn max_size(self, sample_count: usize) -> usize {
+        match self {
+            Self::Wav => wav_size(sample_count),
+            #[cfg(feature = "opus")]
+            Self::OggOpus => {
+                OPUS_HEADER_BYTES
+                    + (sample_count * OPUS_MAX_BYTES_PER_SEC).div_ceil(TARGET_SAMPLE_RATE as usize)
+            }
+        }
+    }
+
+    /// The most samples whose encoding is sure to fit in `max_bytes`.
+    pub fn max_samples(self, max_bytes: usize) -> usize {
+        match self {
+            Self::Wav => max_wav_samples(max_bytes),
+            #[cfg(feature = "opus")]
+            Self::OggOpus => {
+                max_bytes.saturating_sub(OPUS_HEADER_BYTES) * TARGET_SAMPLE_RATE as usize
+                    / OPUS_MAX_BYTES_PER_SEC
+            }
+        }
+    }
+
+    /// Encode 16 kHz mono `samples` in this format.
+    pub fn encode(self, samples: &[f32]) -> Result<Vec<u8>, DictationError> {
+        match self {
+            Self::Wav => Ok(super::wav::encode_wav(samples)),
+            #[cfg(feature = "opus")]
+            Self::OggOpus => super::opus::encode_ogg_opus(samples),
+        }
+    }
+}
+
+/// Encoded size of `sample_count` 16 kHz samples as WAV.
+pub fn wav_size(sample_count: usize) -> usize {
+    WAV_HEADER_BYTES + sample_count * WAV_BYTES_PER_SAMPLE
+}
+
+/// The most samples that fit in one WAV upload of at most `max_bytes`.
+pub fn max_wav_samples(max_bytes: usize) -> usize {
+    max_bytes.saturating_sub(WAV_HEADER_BYTES) / WAV_BYTES_PER_SAMPLE
+}
+
+/// Split 16 kHz `samples` into ranges of at most `per_chunk` samples, e.g.
+/// [`UploadFormat::max_samples`] of a byte cap.
+///
+/// A clip that already fits comes back as a single range. Longer clips are
+/// cut at the quietest point in the last few seconds before each limit so a
+/// word isn't split between requests. More than `max_chunks` pieces is
+/// reported as an error naming the clip length, rather than silently sending
+/// dozens of requests.
+pub fn plan_upload(
+    samples: &[f32],
+    per_chunk: usize,
+    max_chunks: usize,
+) -> Result<Vec<Range<usize>>, DictationError> {
+    if per_chunk == 0 {
+        return Err(DictationError::TranscriptionFailed(
+            "Upload limit is too small for any audio".to_string(),
+        ));
+    }
+
+    let search = SPLIT_SEARCH_SECS * TARGET_SAMPLE_RATE as usize;
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    while samples.len() - start > per_chunk {
+        let limit = start + per_chunk;
+        let search_from = limit.saturating_sub(search).max(start + per_chunk / 2);
+        let split = quiet_split_point(&samples[..limit], search_from, TARGET_SAMPLE_RATE)
+            .clamp(start + 1, limit);
+        ranges.push(start..split);
+        start = split;
+    }
+    if start < samples.len() || ranges.is_empty() {
+        ranges.push(start..samples.len());
+    }
+
+    if ranges.len() > max_chunks {
+        let secs = samples.len() / TARGET_SAMPLE_RATE as usize;
+        let max_secs = max_chunks * per_chunk / TARGET_SAMPLE_RATE as usize;
+        return Err(DictationError::TranscriptionFailed(format!(
+            "Recording is {}m{:02}s long; uploads are limited to about {}m{:02}s. \
+             Transcribe it locally instead.",
+            secs / 60,
+            secs % 60,
+            max_secs / 60,
+            max_secs % 60
+        )));
+    }
+    Ok(ranges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::wav::encode_wav;
+
+    #[test]
+    fn wav_size_matches_encoder() {
+        assert_eq!(wav_size(1234), encode_wav(&vec![0.0; 1234]).len());
+        assert_eq!(max_wav_samples(wav_size(1000)), 1000);
+    }
+
+    #[test]
+    fn chunk_sizes_follow_the_format() {
+        let wav = UploadFormat::Wav;
+        assert_eq!(
+            wav.max_samples(OPENAI_MAX_UPLOAD_BYTES),
+            max_wav_samples(OPENAI_MAX_UPLOAD_BYTES)
+        );
+        assert_eq!(wav.encode(&[0.0; 100]).unwrap().len(), wav.max_size(100));
+        assert_eq!(wav.file_name(), "audio.wav");
+    }
+
+    #[cfg(feature = "opus")]
+    #[test]
+    fn opus_chunks_fit_their_estimate() {
+        let opus = UploadFormat::OggOpus;
+        assert_eq!(UploadFormat::best(), opus);
+        let wav_limit = max_wav_samples(OPENAI_MAX_UPLOAD_BYTES);
+        assert!(opus.max_samples(OPENAI_MAX_UPLOAD_BYTES) > 8 * wav_limit);
+        let speech: Vec<f32> = (0..10 * 16_000)
+            .map(|i| (i as f32 * 0.07).sin() * 0.4)
+            .collect();
+        let encoded = opus.encode(&speech).unwrap();
+        assert!(
+            encoded.len() <= opus.max_size(speech.len()),
+            "{}",
+            encoded.len()
+        );
+        assert!(opus.max_samples(opus.max_size(speech.len())) >= speech.len());
+    }
+
+    #[test]
+    fn short_clip_is_one_range() {
+        let samples = vec![0.1; 16_000];
+        assert_eq!(
+            plan_upload(&samples, AZURE_MAX_UPLOAD_SAMPLES, 1).unwrap(),
+            vec![0..16_000]
+        );
+        assert_eq!(
+            plan_upload(&[], AZURE_MAX_UPLOAD_SAMPLES, 1).unwrap(),
+            vec![0..0]
+        );
+    }
+
+    #[test]
+    fn long_clip_splits_within_limit_at_a_pause() {
+        // 30 s of tone with a silent gap around 8 s, limited to 10 s per upload.
+        let mut samples = vec![0.5f32; 30 * 16_000];
+        for s in &mut samples[8 * 16_000..8 * 16_000 + 3_200] {
+            *s = 0.0;
+        }
+        let max_bytes = wav_size(10 * 16_000);
+        let ranges = plan_upload(&samples, max_wav_samples(max_bytes), 10).unwrap();
+
+        assert!(ranges.len() >= 3);
+        assert_eq!(ranges[0].start, 0);
+        assert_eq!(ranges.last().unwrap().end, samples.len());
+        for pair in ranges.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+        for r in &ranges {
+            assert!(wav_size(r.len()) <= max_bytes);
+        }
+        let first_split = ranges[0].end;
+        assert!((8 * 16_000..8 * 16_000 + 3_200).contains(&first_split));
+    }
+
+    #[test]
+    fn too_many_chunks_is_a_clear_error() {
+        let samples = vec![0.5f32; 30 * 16_000];
+        let err = plan_upload(&samples, 10 * 16_000, 2).unwrap_err();
+        assert!(err.to_string().contains("0m30s"));
+    }
+}
@@ -0,0 +1,184 @@
+//! Putting the app's binary on PATH as `sagascript`.
+//!
+//! The desktop binary is a full CLI, but a GUI install leaves it inside the
+//! app bundle where no shell finds it. [`install`] symlinks it as
+//! `/usr/local/bin/sagascript` when that directory is writable, else as
+//! `~/.local/bin/sagascript`. Linking rather than copying means an app
+//! upgrade is picked up without reinstalling.
+//!
+//! An existing symlink is replaced (usually a previous install, possibly
+//! pointing at an old bundle), but a regular file is left alone: that's a
+//! separately installed CLI, e.g. from `cargo install`.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::error::DictationError;
+
+/// The command name the link is installed under.
+pub const COMMAND_NAME: &str = "sagascript";
+
+/// Tried first: on PATH everywhere, but usually needs admin rights.
+pub const SYSTEM_BIN_DIR: &str = "/usr/local/bin";
+
+/// The per-user fallback, `~/.local/bin`.
+pub fn user_bin_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".local").join("bin"))
+}
+
+/// The binary to link: the running executable with symlinks resolved (run
+/// through an earlier link, it would otherwise link to itself), or for an
+/// AppImage the image (`$APPIMAGE`), since the executable lives in a mount
+/// that goes away when the app quits.
+pub fn app_binary() -> io::Result<PathBuf> {
+    match std::env::var_os("APPIMAGE") {
+        Some(image) if !image.is_empty() => Ok(PathBuf::from(image)),
+        _ => std::env::current_exe().and_then(fs::canonicalize),
+    }
+}
+
+/// A completed install.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CliInstall {
+    /// The symlink that was created.
+    pub link: PathBuf,
+    /// The binary it points at.
+    pub target: PathBuf,
+    /// Whether the link's directory is on PATH: always for
+    /// [`SYSTEM_BIN_DIR`], else judged from this process's PATH. A GUI app
+    /// on macOS doesn't inherit the shell's PATH, so `false` there only means
+    /// "check your shell profile".
+    pub on_path: bool,
+}
+
+impl CliInstall {
+    pub fn new(link: PathBuf, target: &Path) -> Self {
+        let on_path = link.parent().is_some_and(|dir| {
+            dir == Path::new(SYSTEM_BIN_DIR)
+                || std::env::var_os("PATH").is_some_and(|p| dir_on_path(dir, &p))
+        });
+        Self {
+            link,
+            target: target.to_path_buf(),
+            on_path,
+        }
+    }
+}
+
+fn dir_on_path(dir: &Path, path_var: &std::ffi::OsStr) -> bool {
+    std::env::split_paths(path_var).any(|entry| entry == dir)
+}
+
+/// Symlink `target` as `dir/sagascript`, creating `dir` if needed. Errors
+/// keep their `io::ErrorKind` so callers can tell a permission failure
+/// (try elsewhere, or elevate) from anything else.
+pub fn link_into(dir: &Path, target: &Path) -> io::Result<PathBuf> {
+    let link = dir.join(COMMAND_NAME);
+    if let Ok(meta) = fs::symlink_metadata(&link) {
+        if !meta.file_type().is_symlink() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "{} already exists and isn't a link; remove it first",
+                    link.display()
+                ),
+            ));
+        }
+    }
+    fs::create_dir_all(dir)?;
+
+    // Link beside the final name and rename over it, so an existing link is
+    // replaced in one step rather than removed and recreated.
+    let staged = dir.join(format!(".{COMMAND_NAME}.{}", std::process::id()));
+    let _ = fs::remove_file(&staged);
+    symlink(target, &staged)?;
+    if let Err(e) = fs::rename(&staged, &link) {
+        let _ = fs::remove_file(&staged);
+        return Err(e);
+    }
+    Ok(link)
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(not(unix))]
+fn symlink(_target: &Path, _link: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "linking the CLI is only supported on macOS and Linux; add the install folder to PATH instead",
+    ))
+}
+
+/// Link `target` into [`SYSTEM_BIN_DIR`], or [`user_bin_dir`] if that's not
+/// writable.
+pub fn install(target: &Path) -> Result<CliInstall, DictationError> {
+    let mut dirs = vec![PathBuf::from(SYSTEM_BIN_DIR)];
+    dirs.extend(user_bin_dir());
+    for dir in &dirs {
+        match link_into(dir, target) {
+            Ok(link) => return Ok(CliInstall::new(link, target)),
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => continue,
+            Err(e) => return Err(DictationError::InstallError(e.to_string())),
+        }
+    }
+    Err(DictationError::InstallError(format!(
+        "no permission to write to {}",
+        dirs.iter()
+            .map(|d| d.display().to_string())
+            .collect::<Vec<_>>()
+            .join(" or ")
+    )))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("sagascript-cli-install-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn links_and_replaces_an_existing_link() {
+        let dir = temp_dir();
+        let bin = dir.join("bin");
+        let (old, new) = (dir.join("old"), dir.join("new"));
+
+        let link = link_into(&bin, &old).unwrap();
+        assert_eq!(link, bin.join(COMMAND_NAME));
+        assert_eq!(fs::read_link(&link).unwrap(), old);
+
+        link_into(&bin, &new).unwrap();
+        assert_eq!(fs::read_link(&link).unwrap(), new);
+        assert_eq!(fs::read_dir(&bin).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn leaves_a_regular_file_alone() {
+        let dir = temp_dir();
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(COMMAND_NAME), "#!/bin/sh\n").unwrap();
+
+        let err = link_into(&dir, Path::new("/Applications/Sagascript.app")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(
+            fs::read_to_string(dir.join(COMMAND_NAME)).unwrap(),
+            "#!/bin/sh\n"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn path_check_matches_whole_entries() {
+        let path_var = std::env::join_paths(["/usr/bin", "/home/a/.local/bin"]).unwrap();
+        assert!(dir_on_path(Path::new("/home/a/.local/bin"), &path_var));
+        assert!(!dir_on_path(Path::new("/home/a/.local"), &path_var));
+    }
+}
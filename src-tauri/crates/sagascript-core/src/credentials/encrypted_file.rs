@@ -0,0 +1,298 @@
+//! Encrypted-file credential backend.
+//!
+//! All secrets are kept as one JSON map encrypted with XChaCha20-Poly1305.
+//! The key is SHA-256 over the machine id, the user name and a random
+//! per-file salt, so the file is useless when copied to another machine or
+//! account. This protects against stray backups and synced folders, not
+//! against code running as the same user — that is what a keyring is for.
+//!
+//! File layout: `MAGIC | salt (16) | nonce (24) | ciphertext`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use sha2::{Digest, Sha256};
+
+use super::CredentialStore;
+use crate::error::DictationError;
+use crate::settings::store::app_data_dir;
+
+const CREDENTIALS_FILENAME: &str = "credentials.enc";
+const MAGIC: &[u8; 6] = b"SGCRD1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const KEY_CONTEXT: &[u8] = b"sagascript credential store v1";
+
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    machine_secret: Vec<u8>,
+    /// Serializes read-modify-write cycles within this process.
+    lock: Mutex<()>,
+}
+
+impl EncryptedFileStore {
+    /// The store at `<app data dir>/credentials.enc`, keyed to this machine.
+    pub fn open_default() -> Result<Self, DictationError> {
+        Ok(Self::with_secret(
+            app_data_dir().join(CREDENTIALS_FILENAME),
+            machine_secret()?,
+        ))
+    }
+
+    /// A store at `path` keyed with `machine_secret`. Test seam for
+    /// [`open_default`](Self::open_default).
+    pub fn with_secret(path: PathBuf, machine_secret: Vec<u8>) -> Self {
+        Self {
+            path,
+            machine_secret,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn derive_key(&self, salt: &[u8]) -> Key {
+        let digest = Sha256::new()
+            .chain_update(KEY_CONTEXT)
+            .chain_update(&self.machine_secret)
+            .chain_update(salt)
+            .finalize();
+        Key::clone_from_slice(&digest)
+    }
+
+    fn read_all(&self) -> Result<BTreeMap<String, String>, DictationError> {
+        let data = match std::fs::read(&self.path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => {
+                return Err(DictationError::CredentialError(format!(
+                    "Cannot read {}: {e}",
+                    self.path.display()
+                )))
+            }
+        };
+
+        let header = MAGIC.len() + SALT_LEN + NONCE_LEN;
+        if data.len() < header || &data[..MAGIC.len()] != MAGIC {
+            return Err(DictationError::CredentialError(format!(
+                "{} is not a Sagascript credential file",
+                self.path.display()
+            )));
+        }
+        let salt = &data[MAGIC.len()..MAGIC.len() + SALT_LEN];
+        let nonce = XNonce::from_slice(&data[MAGIC.len() + SALT_LEN..header]);
+        let cipher = XChaCha20Poly1305::new(&self.derive_key(salt));
+        let plain = cipher.decrypt(nonce, &data[header..]).map_err(|_| {
+            DictationError::CredentialError(format!(
+                "Cannot decrypt {} — it was created on another machine or account, or is damaged. \
+                 Delete it and store the credentials again.",
+                self.path.display()
+            ))
+        })?;
+        serde_json::from_slice(&plain)
+            .map_err(|e| DictationError::CredentialError(format!("Corrupt credential data: {e}")))
+    }
+
+    fn write_all(&self, secrets: &BTreeMap<String, String>) -> Result<(), DictationError> {
+        let plain = serde_json::to_vec(secrets)
+            .map_err(|e| DictationError::CredentialError(format!("Serialize error: {e}")))?;
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let cipher = XChaCha20Poly1305::new(&self.derive_key(&salt));
+        let ciphertext = cipher
+            .encrypt(&nonce, plain.as_slice())
+            .map_err(|_| DictationError::CredentialError("Encryption failed".to_string()))?;
+
+        let mut data = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+        data.extend_from_slice(MAGIC);
+        data.extend_from_slice(&salt);
+        data.extend_from_slice(&nonce);
+        data.extend_from_slice(&ciphertext);
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                DictationError::CredentialError(format!("Cannot create {}: {e}", dir.display()))
+            })?;
+        }
+        // Atomic write: .tmp + rename, owner-only from the moment it exists.
+        let tmp_path = self.path.with_extension("enc.tmp");
+        write_private(&tmp_path, &data).map_err(|e| {
+            DictationError::CredentialError(format!("Cannot write {}: {e}", tmp_path.display()))
+        })?;
+        std::fs::rename(&tmp_path, &self.path).map_err(|e| {
+            DictationError::CredentialError(format!("Cannot replace {}: {e}", self.path.display()))
+        })
+    }
+}
+
+impl CredentialStore for EncryptedFileStore {
+    fn backend(&self) -> &'static str {
+        "encrypted-file"
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>, DictationError> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(self.read_all()?.remove(name))
+    }
+
+    fn set(&self, name: &str, secret: &str) -> Result<(), DictationError> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut secrets = self.read_all()?;
+        secrets.insert(name.to_string(), secret.to_string());
+        self.write_all(&secrets)
+    }
+
+    fn delete(&self, name: &str) -> Result<(), DictationError> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut secrets = self.read_all()?;
+        if secrets.remove(name).is_some() {
+            self.write_all(&secrets)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(data)
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    std::fs::write(path, data)
+}
+
+/// Stable per-machine, per-user bytes to derive the file key from.
+fn machine_secret() -> Result<Vec<u8>, DictationError> {
+    let id = machine_id().ok_or_else(|| {
+        DictationError::CredentialError(
+            "Cannot determine a machine id to protect stored credentials".to_string(),
+        )
+    })?;
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default();
+    Ok(format!("{id}\n{user}").into_bytes())
+}
+
+#[cfg(target_os = "linux")]
+fn machine_id() -> Option<String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .filter_map(|p| std::fs::read_to_string(p).ok())
+        .map(|s| s.trim().to_string())
+        .find(|s| !s.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+fn machine_id() -> Option<String> {
+    let out = std::process::Command::new("ioreg")
+        .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+        .output()
+        .ok()?;
+    String::from_utf8(out.stdout)
+        .ok()?
+        .lines()
+        .find(|l| l.contains("IOPlatformUUID"))
+        .and_then(|l| l.split('"').nth(3))
+        .map(str::to_string)
+}
+
+#[cfg(target_os = "windows")]
+fn machine_id() -> Option<String> {
+    let out = std::process::Command::new("reg")
+        .args([
+            "query",
+            r"HKLM\SOFTWARE\Microsoft\Cryptography",
+            "/v",
+            "MachineGuid",
+        ])
+        .output()
+        .ok()?;
+    String::from_utf8(out.stdout)
+        .ok()?
+        .lines()
+        .find(|l| l.contains("MachineGuid"))
+        .and_then(|l| l.split_whitespace().last())
+        .map(str::to_string)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn machine_id() -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store(secret: &[u8]) -> (EncryptedFileStore, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("sagascript-creds-{}", uuid::Uuid::new_v4()));
+        let store = EncryptedFileStore::with_secret(dir.join(CREDENTIALS_FILENAME), secret.to_vec());
+        (store, dir)
+    }
+
+    #[test]
+    fn set_get_delete_roundtrip() {
+        let (store, dir) = temp_store(b"machine-a");
+        assert_eq!(store.get("token").unwrap(), None);
+        store.set("token", "s3cret").unwrap();
+        store.set("other", "x").unwrap();
+        assert_eq!(store.get("token").unwrap().as_deref(), Some("s3cret"));
+        store.delete("token").unwrap();
+        store.delete("missing").unwrap();
+        assert_eq!(store.get("token").unwrap(), None);
+        assert_eq!(store.get("other").unwrap().as_deref(), Some("x"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn file_does_not_contain_plaintext() {
+        let (store, dir) = temp_store(b"machine-a");
+        store.set("token", "plaintext-marker").unwrap();
+        let data = std::fs::read(store.path()).unwrap();
+        assert!(data.starts_with(MAGIC));
+        assert!(!data
+            .windows(b"plaintext-marker".len())
+            .any(|w| w == b"plaintext-marker"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn other_machine_cannot_decrypt() {
+        let (store, dir) = temp_store(b"machine-a");
+        store.set("token", "s3cret").unwrap();
+        let copied = EncryptedFileStore::with_secret(store.path().to_path_buf(), b"machine-b".to_vec());
+        let err = copied.get("token").unwrap_err();
+        assert!(err.to_string().contains("another machine"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (store, dir) = temp_store(b"machine-a");
+        store.set("token", "s3cret").unwrap();
+        let mode = std::fs::metadata(store.path()).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
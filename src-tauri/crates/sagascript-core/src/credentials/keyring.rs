@@ -0,0 +1,219 @@
+//! OS keyring credential backend.
+//!
+//! On macOS secrets go into the login keychain; on Linux into the Secret
+//! Service (GNOME Keyring, KWallet) through libsecret's `secret-tool`, with
+//! the secret passed on stdin rather than the command line. Each secret is
+//! an item for service `sagascript` whose account is the secret's name.
+//! Windows has no keyring backend yet and keeps the encrypted file.
+//!
+//! Secrets saved to the encrypted file before the keyring was available move
+//! into the keyring the first time they are read.
+
+use super::{CredentialStore, EncryptedFileStore};
+use crate::error::DictationError;
+
+/// Service the keyring items are filed under.
+const SERVICE: &str = "sagascript";
+
+pub struct KeyringStore {
+    /// The encrypted file, to migrate secrets from.
+    legacy: Option<EncryptedFileStore>,
+}
+
+impl KeyringStore {
+    /// The OS keyring, or `None` when this machine has none that answers.
+    pub fn open() -> Option<Self> {
+        os::available().then(|| Self {
+            legacy: EncryptedFileStore::open_default().ok(),
+        })
+    }
+
+    fn migrate(&self, name: &str) -> Result<Option<String>, DictationError> {
+        let Some(legacy) = &self.legacy else {
+            return Ok(None);
+        };
+        let Some(secret) = legacy.get(name)? else {
+            return Ok(None);
+        };
+        self.set(name, &secret)?;
+        legacy.delete(name)?;
+        tracing::info!("Moved credential '{name}' from the encrypted file to the keyring");
+        Ok(Some(secret))
+    }
+}
+
+impl CredentialStore for KeyringStore {
+    fn backend(&self) -> &'static str {
+        os::BACKEND
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>, DictationError> {
+        match os::get(name).map_err(failed)? {
+            Some(secret) => Ok(Some(secret)),
+            None => self.migrate(name),
+        }
+    }
+
+    fn set(&self, name: &str, secret: &str) -> Result<(), DictationError> {
+        os::set(name, secret).map_err(failed)
+    }
+
+    fn delete(&self, name: &str) -> Result<(), DictationError> {
+        os::delete(name).map_err(failed)?;
+        if let Some(legacy) = &self.legacy {
+            legacy.delete(name)?;
+        }
+        Ok(())
+    }
+}
+
+fn failed(why: String) -> DictationError {
+    DictationError::CredentialError(format!("{}: {why}", os::BACKEND))
+}
+
+#[cfg(target_os = "macos")]
+mod os {
+    use security_framework::passwords;
+
+    use super::SERVICE;
+
+    pub const BACKEND: &str = "macos-keychain";
+
+    /// `errSecItemNotFound`.
+    const ITEM_NOT_FOUND: i32 = -25300;
+
+    pub fn available() -> bool {
+        true
+    }
+
+    pub fn get(name: &str) -> Result<Option<String>, String> {
+        match passwords::get_generic_password(SERVICE, name) {
+            Ok(bytes) => String::from_utf8(bytes)
+                .map(Some)
+                .map_err(|_| format!("'{name}' is not text")),
+            Err(e) if e.code() == ITEM_NOT_FOUND => Ok(None),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    pub fn set(name: &str, secret: &str) -> Result<(), String> {
+        passwords::set_generic_password(SERVICE, name, secret.as_bytes()).map_err(|e| e.to_string())
+    }
+
+    pub fn delete(name: &str) -> Result<(), String> {
+        match passwords::delete_generic_password(SERVICE, name) {
+            Err(e) if e.code() != ITEM_NOT_FOUND => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod os {
+    use std::io::Write;
+    use std::process::{Command, Output, Stdio};
+    use std::sync::OnceLock;
+
+    use super::SERVICE;
+
+    pub const BACKEND: &str = "secret-service";
+
+    /// Whether `secret-tool` is installed and a Secret Service daemon
+    /// answers. A lookup that finds nothing fails silently; one that can't
+    /// reach the daemon says why on stderr.
+    pub fn available() -> bool {
+        static AVAILABLE: OnceLock<bool> = OnceLock::new();
+        *AVAILABLE.get_or_init(|| {
+            secret_tool(
+                &[
+                    "lookup",
+                    "service",
+                    SERVICE,
+                    "account",
+                    "availability-probe",
+                ],
+                None,
+            )
+            .is_ok_and(|out| out.status.success() || reason(&out).is_none())
+        })
+    }
+
+    pub fn get(name: &str) -> Result<Option<String>, String> {
+        let out = secret_tool(&["lookup", "service", SERVICE, "account", name], None)?;
+        if !out.status.success() {
+            return match reason(&out) {
+                None => Ok(None),
+                Some(why) => Err(why),
+            };
+        }
+        let secret = String::from_utf8(out.stdout).map_err(|_| format!("'{name}' is not text"))?;
+        Ok(Some(secret.trim_end_matches('\n').to_string()))
+    }
+
+    pub fn set(name: &str, secret: &str) -> Result<(), String> {
+        let label = format!("--label=Sagascript: {name}");
+        let out = secret_tool(
+            &["store", &label, "service", SERVICE, "account", name],
+            Some(secret),
+        )?;
+        if out.status.success() {
+            return Ok(());
+        }
+        Err(reason(&out).unwrap_or_else(|| "secret-tool store failed".to_string()))
+    }
+
+    pub fn delete(name: &str) -> Result<(), String> {
+        let out = secret_tool(&["clear", "service", SERVICE, "account", name], None)?;
+        match reason(&out) {
+            Some(why) if !out.status.success() => Err(why),
+            _ => Ok(()),
+        }
+    }
+
+    fn secret_tool(args: &[&str], input: Option<&str>) -> Result<Output, String> {
+        let mut child = Command::new("secret-tool")
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("cannot run secret-tool: {e}"))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            if let Some(input) = input {
+                stdin
+                    .write_all(input.as_bytes())
+                    .map_err(|e| format!("cannot write to secret-tool: {e}"))?;
+            }
+        }
+        child
+            .wait_with_output()
+            .map_err(|e| format!("secret-tool failed: {e}"))
+    }
+
+    fn reason(out: &Output) -> Option<String> {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        let stderr = stderr.trim();
+        (!stderr.is_empty()).then(|| stderr.to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+mod os {
+    pub const BACKEND: &str = "none";
+
+    pub fn available() -> bool {
+        false
+    }
+
+    pub fn get(_name: &str) -> Result<Option<String>, String> {
+        Err("no keyring on this platform".to_string())
+    }
+
+    pub fn set(_name: &str, _secret: &str) -> Result<(), String> {
+        Err("no keyring on this platform".to_string())
+    }
+
+    pub fn delete(_name: &str) -> Result<(), String> {
+        Err("no keyring on this platform".to_string())
+    }
+}
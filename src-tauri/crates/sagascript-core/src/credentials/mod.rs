@@ -0,0 +1,38 @@
+//! Storage for secrets such as API keys for opt-in remote services.
+//!
+//! Callers go through [`CredentialStore`] so they don't care where a secret
+//! lives. The OS keyring is the preferred backend; on machines without one
+//! (headless Linux with no Secret Service daemon) secrets fall back to
+//! [`EncryptedFileStore`], a file in the app data dir encrypted with a key
+//! derived from the machine identity. Secrets never go into settings.json.
+
+mod encrypted_file;
+mod keyring;
+
+pub use encrypted_file::EncryptedFileStore;
+pub use keyring::KeyringStore;
+
+use crate::error::DictationError;
+
+/// A place to keep named secrets. Names are short service identifiers such as
+/// `"webhook-token"`; values are opaque strings.
+pub trait CredentialStore: Send + Sync {
+    /// Backend name for logs and `sagascript` diagnostics.
+    fn backend(&self) -> &'static str;
+
+    fn get(&self, name: &str) -> Result<Option<String>, DictationError>;
+
+    fn set(&self, name: &str, secret: &str) -> Result<(), DictationError>;
+
+    /// Remove a secret. Removing one that doesn't exist is not an error.
+    fn delete(&self, name: &str) -> Result<(), DictationError>;
+}
+
+/// The store to use on this machine: the OS keyring when there is one that
+/// answers, otherwise the encrypted file (Windows, headless Linux).
+pub fn default_store() -> Result<Box<dyn CredentialStore>, DictationError> {
+    if let Some(keyring) = KeyringStore::open() {
+        return Ok(Box::new(keyring));
+    }
+    Ok(Box::new(EncryptedFileStore::open_default()?))
+}
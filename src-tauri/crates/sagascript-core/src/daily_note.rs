@@ -0,0 +1,193 @@
+//! Appending dictations to a daily note, for the `daily-note:` output sink.
+//!
+//! Note-taking apps such as Obsidian keep one Markdown file per day in a
+//! vault folder. Each dictation is added to today's note as a timestamped
+//! list item (`- 14:05 Call Anna about the budget`) under a heading, so the
+//! day's dictations collect in one section. The note and the heading are
+//! created when missing. Dates and times are local: a note is the user's day.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDateTime};
+
+use crate::error::DictationError;
+
+/// Note file name used when the sink doesn't name one, relative to the vault.
+pub const DEFAULT_FILE: &str = "YYYY-MM-DD.md";
+
+/// Heading the entries go under when the sink doesn't name one.
+pub const DEFAULT_HEADING: &str = "## Dictations";
+
+/// The note for `now`: `file` with `YYYY`, `MM` and `DD` filled in, under
+/// `vault`. `file` may contain folders (`Daily/YYYY/YYYY-MM-DD.md`).
+pub fn note_path(vault: &Path, file: &str, now: NaiveDateTime) -> PathBuf {
+    let name = file
+        .replace("YYYY", &now.format("%Y").to_string())
+        .replace("MM", &now.format("%m").to_string())
+        .replace("DD", &now.format("%d").to_string());
+    vault.join(name)
+}
+
+/// `text` as a list item stamped with the time of `now`. Line breaks in the
+/// text continue the item on indented lines.
+pub fn entry(text: &str, now: NaiveDateTime) -> String {
+    let mut lines = text.trim().lines().map(str::trim_end);
+    let mut item = format!("- {} {}", now.format("%H:%M"), lines.next().unwrap_or(""));
+    for line in lines {
+        item.push_str("\n  ");
+        item.push_str(line);
+    }
+    item
+}
+
+/// `note` with `entry` added as the last item under `heading`, or at the end
+/// of the note when `heading` is empty. A missing heading is added at the
+/// end. The section runs until the next heading of the same or a higher
+/// level.
+pub fn insert_entry(note: &str, heading: &str, entry: &str) -> String {
+    let heading = heading.trim();
+    let mut lines: Vec<&str> = note.lines().collect();
+    let at = if heading.is_empty() {
+        None
+    } else {
+        lines.iter().position(|line| line.trim() == heading)
+    };
+
+    let Some(at) = at else {
+        let mut out = note.trim_end().to_string();
+        if !out.is_empty() {
+            out.push_str("\n\n");
+        }
+        if !heading.is_empty() {
+            out.push_str(heading);
+            out.push('\n');
+        }
+        out.push_str(entry);
+        out.push('\n');
+        return out;
+    };
+
+    let level = heading_level(heading).unwrap_or(usize::MAX);
+    let end = lines[at + 1..]
+        .iter()
+        .position(|line| heading_level(line).is_some_and(|l| l <= level))
+        .map_or(lines.len(), |i| at + 1 + i);
+    // After the section's last non-blank line, so a blank line before the
+    // next heading stays where it is.
+    let last = (at..end)
+        .rev()
+        .find(|&i| !lines[i].trim().is_empty())
+        .unwrap_or(at);
+    lines.insert(last + 1, entry);
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/// The level of a Markdown ATX heading (`## Title` is 2).
+fn heading_level(line: &str) -> Option<usize> {
+    let hashes = line.chars().take_while(|&c| c == '#').count();
+    let rest = &line[hashes..];
+    ((1..=6).contains(&hashes) && (rest.is_empty() || rest.starts_with(' '))).then_some(hashes)
+}
+
+/// Add `text` to today's note in `vault`, creating the note, its folders and
+/// the heading as needed.
+pub fn append(vault: &Path, file: &str, heading: &str, text: &str) -> Result<(), DictationError> {
+    let now = Local::now().naive_local();
+    let path = note_path(vault, file, now);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            DictationError::OutputError(format!("Cannot create {}: {e}", parent.display()))
+        })?;
+    }
+    let note = match std::fs::read_to_string(&path) {
+        Ok(note) => note,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            return Err(DictationError::OutputError(format!(
+                "Cannot read {}: {e}",
+                path.display()
+            )))
+        }
+    };
+    let updated = insert_entry(&note, heading, &entry(text, now));
+    std::fs::write(&path, updated)
+        .map_err(|e| DictationError::OutputError(format!("Cannot write {}: {e}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("2026-10-16 {time}"), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn note_path_fills_in_the_date() {
+        assert_eq!(
+            note_path(Path::new("/vault"), DEFAULT_FILE, at("09:30")),
+            PathBuf::from("/vault/2026-10-16.md")
+        );
+        assert_eq!(
+            note_path(Path::new("/vault"), "Daily/YYYY/DD.MM.md", at("09:30")),
+            PathBuf::from("/vault/Daily/2026/16.10.md")
+        );
+    }
+
+    #[test]
+    fn entries_are_stamped_list_items() {
+        assert_eq!(
+            entry(" Call Anna.\nAbout the budget. ", at("14:05")),
+            "- 14:05 Call Anna.\n  About the budget."
+        );
+    }
+
+    #[test]
+    fn a_new_note_gets_the_heading() {
+        assert_eq!(
+            insert_entry("", DEFAULT_HEADING, "- 14:05 Hi"),
+            "## Dictations\n- 14:05 Hi\n"
+        );
+        assert_eq!(
+            insert_entry("# Friday\nTodo\n", DEFAULT_HEADING, "- 14:05 Hi"),
+            "# Friday\nTodo\n\n## Dictations\n- 14:05 Hi\n"
+        );
+    }
+
+    #[test]
+    fn entries_go_to_the_end_of_their_section() {
+        let note = "# Friday\n\n## Dictations\n- 09:00 First\n\n## Meetings\nStandup\n";
+        assert_eq!(
+            insert_entry(note, DEFAULT_HEADING, "- 14:05 Second"),
+            "# Friday\n\n## Dictations\n- 09:00 First\n- 14:05 Second\n\n## Meetings\nStandup\n"
+        );
+        let subsections = "## Dictations\n### Work\n- a\n## Other\n";
+        assert_eq!(
+            insert_entry(subsections, DEFAULT_HEADING, "- b"),
+            "## Dictations\n### Work\n- a\n- b\n## Other\n"
+        );
+    }
+
+    #[test]
+    fn an_empty_heading_appends_to_the_note() {
+        assert_eq!(
+            insert_entry("Notes\n\n", "", "- 14:05 Hi"),
+            "Notes\n\n- 14:05 Hi\n"
+        );
+    }
+
+    #[test]
+    fn append_creates_the_note_and_adds_to_it() {
+        let vault = std::env::temp_dir().join(format!("sagascript-vault-{}", uuid::Uuid::new_v4()));
+        append(&vault, "Daily/YYYY-MM-DD.md", DEFAULT_HEADING, "First").unwrap();
+        append(&vault, "Daily/YYYY-MM-DD.md", DEFAULT_HEADING, "Second").unwrap();
+        let path = note_path(&vault, "Daily/YYYY-MM-DD.md", Local::now().naive_local());
+        let note = std::fs::read_to_string(path).unwrap();
+        assert!(note.starts_with("## Dictations\n- "));
+        assert_eq!(note.lines().count(), 3);
+        assert!(note.trim_end().ends_with(" Second"));
+        let _ = std::fs::remove_dir_all(&vault);
+    }
+}
@@ -25,12 +25,14 @@ use std::time::{Duration, SystemTime};
 
 #[cfg(unix)]
 use std::collections::HashMap;
+use std::sync::Mutex;
 #[cfg(unix)]
-use std::sync::{Mutex, OnceLock};
+use std::sync::OnceLock;
 
 use futures_util::StreamExt;
 use sha2::{Digest, Sha256};
 use tokio::io::AsyncWriteExt;
+use tracing::warn;
 
 use crate::error::DictationError;
 
@@ -59,6 +61,10 @@ const MAGIC_PREFIX_LEN: usize = 8;
 /// long, so this is generous on purpose.
 const ORPHAN_TMP_MAX_AGE: Duration = Duration::from_secs(60 * 60);
 
+/// Where every pinned artifact is downloaded from, unless the
+/// `model_mirror` setting names a stand-in.
+const HUGGING_FACE: &str = "https://huggingface.co/";
+
 /// Immutable integrity metadata for a downloadable model artifact. Values are
 /// taken from the pinned Hugging Face revision's git-LFS metadata (`oid
 /// sha256` and `size`), not from a mutable branch or a locally computed guess.
@@ -97,6 +103,9 @@ enum VerificationFailure {
 /// On any failure the temp file is removed best-effort before the error is
 /// returned; `dest` itself is only ever touched by the final rename, so a
 /// failed download can never leave a partial or invalid file in its place.
+///
+/// With a `model_mirror` set, the mirror's copy of a Hugging Face URL is
+/// tried first (see [`download_sources`]).
 pub async fn download_to_path(
     url: &str,
     dest: &Path,
@@ -104,6 +113,30 @@ pub async fn download_to_path(
     integrity: DownloadIntegrity,
     expected_magic: Option<&[u8]>,
     progress_callback: impl Fn(u64, u64) + Send + 'static,
+) -> Result<(), DictationError> {
+    download_with_fallbacks(
+        url,
+        &[],
+        dest,
+        tmp_ext,
+        integrity,
+        expected_magic,
+        progress_callback,
+    )
+    .await
+}
+
+/// [`download_to_path`], falling back to each of `fallbacks` in turn when
+/// the download from `url` fails, whether on the network or in
+/// verification. Returns the last source's error if none works.
+pub async fn download_with_fallbacks(
+    url: &str,
+    fallbacks: &[String],
+    dest: &Path,
+    tmp_ext: &str,
+    integrity: DownloadIntegrity,
+    expected_magic: Option<&[u8]>,
+    progress_callback: impl Fn(u64, u64) + Send + 'static,
 ) -> Result<(), DictationError> {
     if let Some(dir) = dest.parent() {
         std::fs::create_dir_all(dir).map_err(|e| {
@@ -112,14 +145,27 @@ pub async fn download_to_path(
         sweep_orphaned_tmp_files(dir);
     }
 
-    let tmp_path = unique_tmp_path(dest, tmp_ext);
-
-    if let Err(e) =
-        fetch_to_tmp(url, &tmp_path, integrity, expected_magic, progress_callback).await
-    {
-        let _ = tokio::fs::remove_file(&tmp_path).await;
-        return Err(e);
-    }
+    let mirror = crate::settings::store::load().model_mirror;
+    let mut sources = download_sources(url, fallbacks, &mirror)
+        .into_iter()
+        .peekable();
+    // Shared by the attempts; a retry reports its progress from zero again.
+    let progress_callback = Mutex::new(progress_callback);
+    let tmp_path = loop {
+        let source = sources.next().expect("`url` is always a source");
+        let tmp_path = unique_tmp_path(dest, tmp_ext);
+        let progress = |downloaded, total| (progress_callback.lock().unwrap())(downloaded, total);
+        match fetch_to_tmp(&source, &tmp_path, integrity, expected_magic, progress).await {
+            Ok(()) => break tmp_path,
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&tmp_path).await;
+                if sources.peek().is_none() {
+                    return Err(e);
+                }
+                warn!("Download from {source} failed, trying the next source: {e}");
+            }
+        }
+    };
 
     if let Err(e) = tokio::fs::rename(&tmp_path, dest).await {
         // The download itself succeeded and was already validated — only the
@@ -142,17 +188,49 @@ pub async fn download_to_path(
     Ok(())
 }
 
+/// Where to fetch `url` from, in order: `url`, then `fallbacks`, each
+/// Hugging Face URL among them preceded by the `mirror`'s copy, for
+/// networks that can't reach Hugging Face. A mirror serves Hugging Face's
+/// paths, `<mirror>/<owner>/<repo>/resolve/<revision>/<file>`.
+pub fn download_sources(url: &str, fallbacks: &[String], mirror: &str) -> Vec<String> {
+    let mirror = mirror.trim_end_matches('/');
+    let mut sources = Vec::with_capacity(fallbacks.len() + 2);
+    for source in std::iter::once(url).chain(fallbacks.iter().map(String::as_str)) {
+        let on_mirror = source
+            .strip_prefix(HUGGING_FACE)
+            .filter(|_| !mirror.is_empty());
+        if let Some(path) = on_mirror {
+            sources.push(format!("{mirror}/{path}"));
+        }
+        sources.push(source.to_string());
+    }
+    sources
+}
+
+/// Check a `model_mirror` value: empty, or an `http(s)://` base URL.
+pub fn check_mirror(mirror: &str) -> Result<(), String> {
+    if mirror.is_empty() || mirror.starts_with("https://") || mirror.starts_with("http://") {
+        Ok(())
+    } else {
+        Err(format!(
+            "Mirror '{mirror}' should be a base URL like https://models.example.com"
+        ))
+    }
+}
+
 /// Do the actual network fetch + stream-to-file + validate, in one `Result`
-/// so `download_to_path` can clean up the temp file with a single arm
+/// so `download_with_fallbacks` can clean up the temp file with a single arm
 /// instead of repeating `let _ = remove_file(...).await;` after every `?`.
 async fn fetch_to_tmp(
     url: &str,
     tmp_path: &Path,
     integrity: DownloadIntegrity,
     expected_magic: Option<&[u8]>,
-    progress_callback: impl Fn(u64, u64) + Send + 'static,
+    progress_callback: impl Fn(u64, u64) + Send,
 ) -> Result<(), DictationError> {
-    let client = reqwest::Client::new();
+    let client = crate::net::client_builder()
+        .build()
+        .map_err(|e| DictationError::ModelDownloadFailed(format!("HTTP client error: {e}")))?;
     let response = client
         .get(url)
         .send()
@@ -832,6 +910,47 @@ mod tests {
         let _ = std::fs::remove_dir_all(dir);
     }
 
+    // -- download_sources --
+
+    #[test]
+    fn mirror_comes_first_and_fallbacks_last() {
+        let url = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin";
+        let fallbacks = vec!["https://example.org/ggml-base.bin".to_string()];
+        assert_eq!(
+            download_sources(url, &fallbacks, "http://mirror.lan/hf/"),
+            vec![
+                "http://mirror.lan/hf/ggerganov/whisper.cpp/resolve/main/ggml-base.bin",
+                url,
+                "https://example.org/ggml-base.bin",
+            ]
+        );
+        assert_eq!(download_sources(url, &[], ""), vec![url]);
+        // Only Hugging Face URLs have a counterpart on the mirror.
+        let other = "https://example.org/model.bin";
+        assert_eq!(
+            download_sources(other, &[], "http://mirror.lan"),
+            vec![other]
+        );
+        // A per-model URL goes first; Hugging Face is still mirrored after it.
+        let custom = "http://artifacts.lan/ggml-base.bin";
+        assert_eq!(
+            download_sources(custom, &[url.to_string()], "http://mirror.lan/hf"),
+            vec![
+                custom,
+                "http://mirror.lan/hf/ggerganov/whisper.cpp/resolve/main/ggml-base.bin",
+                url,
+            ]
+        );
+    }
+
+    #[test]
+    fn mirror_must_be_an_http_url() {
+        assert!(check_mirror("").is_ok());
+        assert!(check_mirror("https://models.example.com").is_ok());
+        assert!(check_mirror("models.example.com").is_err());
+        assert!(check_mirror("file:///srv/models").is_err());
+    }
+
     // -- unique_tmp_path --
 
     #[test]
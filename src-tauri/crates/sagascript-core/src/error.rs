@@ -1,9 +1,13 @@
-use serde::Serialize;
+use serde::ser::{Serialize, SerializeMap, Serializer};
 use thiserror::Error;
 
 /// Errors that can occur during dictation workflow
-#[derive(Error, Debug, Serialize, Clone)]
-#[serde(tag = "kind", content = "message")]
+///
+/// Serializes as `{ kind, message?, suggestion }`: `message` is the variant's
+/// detail string (absent for variants without one) and `suggestion` is
+/// [`DictationError::suggestion`], so the frontend can show both the error
+/// and what to do about it.
+#[derive(Error, Debug, Clone)]
 pub enum DictationError {
     #[error("Microphone permission is required. Please enable it in System Settings > Privacy & Security > Microphone.")]
     MicrophonePermissionDenied,
@@ -26,9 +30,18 @@ pub enum DictationError {
     #[error("No audio was captured. Please try again.")]
     NoAudioCaptured,
 
+    #[error("No speech was detected in the recording.")]
+    NoSpeechDetected,
+
+    #[error("Transcription was cancelled.")]
+    Cancelled,
+
     #[error("Audio capture error: {0}")]
     AudioCaptureError(String),
 
+    #[error("Recording buffer full: {0}")]
+    BufferFull(String),
+
     #[error("Model download failed: {0}")]
     ModelDownloadFailed(String),
 
@@ -44,11 +57,211 @@ pub enum DictationError {
     #[error("Unsupported format: {0}")]
     UnsupportedFormat(String),
 
+    #[error("Output error: {0}")]
+    OutputError(String),
+
+    #[error("Credential error: {0}")]
+    CredentialError(String),
+
+    #[error("Model bundle error: {0}")]
+    BundleError(String),
+
+    #[error("CLI install failed: {0}")]
+    InstallError(String),
+
+    #[error("Self-update failed: {0}")]
+    UpdateError(String),
+
+    #[error("Post-processing failed: {0}")]
+    PostProcessError(String),
+
+    #[error("Support bundle failed: {0}")]
+    SupportBundleError(String),
+
     #[cfg(feature = "diarization")]
     #[error("Diarization error: {0}")]
     DiarizationError(String),
 }
 
+impl DictationError {
+    /// Variant name, stable for the frontend to switch on.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DictationError::MicrophonePermissionDenied => "MicrophonePermissionDenied",
+            DictationError::AccessibilityPermissionDenied => "AccessibilityPermissionDenied",
+            DictationError::ModelNotLoaded => "ModelNotLoaded",
+            DictationError::TranscriptionFailed(_) => "TranscriptionFailed",
+            DictationError::ModelBusy => "ModelBusy",
+            DictationError::NoAudioCaptured => "NoAudioCaptured",
+            DictationError::NoSpeechDetected => "NoSpeechDetected",
+            DictationError::Cancelled => "Cancelled",
+            DictationError::AudioCaptureError(_) => "AudioCaptureError",
+            DictationError::BufferFull(_) => "BufferFull",
+            DictationError::ModelDownloadFailed(_) => "ModelDownloadFailed",
+            DictationError::SettingsError(_) => "SettingsError",
+            DictationError::PasteError(_) => "PasteError",
+            DictationError::FileDecodeError(_) => "FileDecodeError",
+            DictationError::UnsupportedFormat(_) => "UnsupportedFormat",
+            DictationError::OutputError(_) => "OutputError",
+            DictationError::CredentialError(_) => "CredentialError",
+            DictationError::BundleError(_) => "BundleError",
+            DictationError::InstallError(_) => "InstallError",
+            DictationError::UpdateError(_) => "UpdateError",
+            DictationError::PostProcessError(_) => "PostProcessError",
+            DictationError::SupportBundleError(_) => "SupportBundleError",
+            #[cfg(feature = "diarization")]
+            DictationError::DiarizationError(_) => "DiarizationError",
+        }
+    }
+
+    /// The variant's detail string, if it carries one.
+    fn detail(&self) -> Option<&str> {
+        match self {
+            DictationError::TranscriptionFailed(m)
+            | DictationError::AudioCaptureError(m)
+            | DictationError::BufferFull(m)
+            | DictationError::ModelDownloadFailed(m)
+            | DictationError::SettingsError(m)
+            | DictationError::PasteError(m)
+            | DictationError::FileDecodeError(m)
+            | DictationError::UnsupportedFormat(m)
+            | DictationError::OutputError(m)
+            | DictationError::CredentialError(m)
+            | DictationError::BundleError(m)
+            | DictationError::InstallError(m)
+            | DictationError::UpdateError(m)
+            | DictationError::PostProcessError(m)
+            | DictationError::SupportBundleError(m) => Some(m),
+            #[cfg(feature = "diarization")]
+            DictationError::DiarizationError(m) => Some(m),
+            DictationError::MicrophonePermissionDenied
+            | DictationError::AccessibilityPermissionDenied
+            | DictationError::ModelNotLoaded
+            | DictationError::ModelBusy
+            | DictationError::NoAudioCaptured
+            | DictationError::NoSpeechDetected
+            | DictationError::Cancelled => None,
+        }
+    }
+
+    /// An actionable next step for the user, shown under the error by the
+    /// CLI (`Hint:`) and the GUI.
+    ///
+    /// `TranscriptionFailed` covers several causes, so its message is
+    /// checked for the two that have a clear fix: a missing model and a
+    /// timeout.
+    pub fn suggestion(&self) -> &'static str {
+        match self {
+            DictationError::MicrophonePermissionDenied => {
+                "Grant microphone access in System Settings > Privacy & Security > Microphone, \
+                 then restart Sagascript."
+            }
+            DictationError::AccessibilityPermissionDenied => {
+                "Grant Accessibility access in System Settings > Privacy & Security > \
+                 Accessibility, or turn off auto-paste."
+            }
+            DictationError::ModelNotLoaded => {
+                "Wait for the model to finish loading. If it never does, re-download it: \
+                 sagascript download-model <model>"
+            }
+            DictationError::TranscriptionFailed(m) if m.contains("not downloaded") => {
+                "Download the model: sagascript download-model <model>, or pick it under \
+                 Settings > Model."
+            }
+            DictationError::TranscriptionFailed(m) if m.contains("timed out") => {
+                "Shorten the recording, or switch to a smaller, faster model."
+            }
+            DictationError::TranscriptionFailed(_) => {
+                "Try again. If it keeps failing, 'sagascript sessions --failed' shows the \
+                 recent errors."
+            }
+            DictationError::ModelBusy => {
+                "Wait for the current transcription to finish, then try again."
+            }
+            DictationError::NoAudioCaptured => {
+                "Check that the microphone is connected and not muted, and keep the hotkey \
+                 held while you speak."
+            }
+            DictationError::NoSpeechDetected => {
+                "Check that the right microphone is selected (sagascript list-devices), that \
+                 it isn't muted and its input level is up, then speak closer to it."
+            }
+            DictationError::Cancelled => "Start it again when you're ready.",
+            DictationError::AudioCaptureError(_) => {
+                "Check that a microphone is connected and not in exclusive use by another app."
+            }
+            DictationError::BufferFull(_) => {
+                "Raise the limit with 'sagascript config set max_recording_minutes <minutes>', \
+                 or stop and start a new dictation before it runs out."
+            }
+            DictationError::ModelDownloadFailed(_) => {
+                "Check your internet connection and free disk space, then retry the download."
+            }
+            DictationError::SettingsError(_) => {
+                "Check the settings file (sagascript config path) or restore the defaults \
+                 with: sagascript config reset"
+            }
+            DictationError::PasteError(_) => {
+                "Check Accessibility permission, or turn off auto-paste and copy the result \
+                 instead."
+            }
+            DictationError::FileDecodeError(_) => {
+                "Check that the file isn't truncated or corrupt. If it plays elsewhere, retry \
+                 with ffmpeg installed and 'sagascript config set ffmpeg_fallback true', or \
+                 convert it to WAV first."
+            }
+            DictationError::UnsupportedFormat(_) => {
+                "Convert the file to a supported format; 'sagascript formats' lists them."
+            }
+            DictationError::OutputError(_) => {
+                "Check the output destination (file path or webhook URL) in the output settings."
+            }
+            DictationError::CredentialError(_) => {
+                "Re-enter the credential; if the store is corrupt, remove it and set it again."
+            }
+            DictationError::BundleError(_) => {
+                "Re-create the bundle with 'sagascript bundle export' on a machine whose \
+                 models verify."
+            }
+            DictationError::InstallError(_) => {
+                "Link it by hand instead: sudo ln -sfn <path to the app's sagascript binary> \
+                 /usr/local/bin/sagascript"
+            }
+            DictationError::UpdateError(_) => {
+                "Download the latest release by hand from \
+                 https://github.com/Magnus-Gille/sagascript/releases"
+            }
+            DictationError::PostProcessError(_) => {
+                "The raw transcript was used instead. Check post_process_command \
+                 (sagascript config get post_process_command), or clear it to turn \
+                 post-processing off."
+            }
+            DictationError::SupportBundleError(_) => {
+                "Write the bundle somewhere you can create files, e.g. \
+                 sagascript support-bundle ~/Desktop/sagascript-support.zip"
+            }
+            #[cfg(feature = "diarization")]
+            DictationError::DiarizationError(_) => {
+                "Download the diarization models (see sagascript list-models), or transcribe \
+                 without --diarize."
+            }
+        }
+    }
+}
+
+impl Serialize for DictationError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let detail = self.detail();
+        let mut map = serializer.serialize_map(Some(2 + usize::from(detail.is_some())))?;
+        map.serialize_entry("kind", self.kind())?;
+        if let Some(detail) = detail {
+            map.serialize_entry("message", detail)?;
+        }
+        map.serialize_entry("suggestion", self.suggestion())?;
+        map.end()
+    }
+}
+
 // String conversion so callers (e.g. the app crate's Tauri command handlers,
 // which need error types convertible to a serializable form) can return
 // DictationError without depending on its variants.
@@ -82,6 +295,14 @@ mod tests {
         assert!(err.to_string().contains("not loaded"));
     }
 
+    #[test]
+    fn cancelled_has_no_detail() {
+        let err = DictationError::Cancelled;
+        assert_eq!(err.kind(), "Cancelled");
+        assert_eq!(err.detail(), None);
+        assert!(err.to_string().contains("cancelled"));
+    }
+
     #[test]
     fn display_with_message() {
         let err = DictationError::TranscriptionFailed("timeout".into());
@@ -98,6 +319,18 @@ mod tests {
 
         let err = DictationError::UnsupportedFormat(".xyz".into());
         assert_eq!(err.to_string(), "Unsupported format: .xyz");
+
+        let err = DictationError::OutputError("webhook returned 500".into());
+        assert_eq!(err.to_string(), "Output error: webhook returned 500");
+
+        let err = DictationError::CredentialError("locked".into());
+        assert_eq!(err.to_string(), "Credential error: locked");
+
+        let err = DictationError::BufferFull("stopped at the 15-minute limit".into());
+        assert_eq!(
+            err.to_string(),
+            "Recording buffer full: stopped at the 15-minute limit"
+        );
     }
 
     #[test]
@@ -107,6 +340,15 @@ mod tests {
         assert!(s.contains("No audio was captured"));
     }
 
+    #[test]
+    fn no_speech_is_its_own_kind() {
+        let err = DictationError::NoSpeechDetected;
+        assert!(err.to_string().contains("No speech"));
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["kind"], "NoSpeechDetected");
+        assert!(json["suggestion"].as_str().unwrap().contains("microphone"));
+    }
+
     #[test]
     fn serialize_unit_variant() {
         let err = DictationError::MicrophonePermissionDenied;
@@ -136,6 +378,45 @@ mod tests {
         assert_eq!(json["message"], "model error");
     }
 
+    #[test]
+    fn serialize_includes_suggestion() {
+        let json = serde_json::to_value(DictationError::NoAudioCaptured).unwrap();
+        assert_eq!(json["kind"], "NoAudioCaptured");
+        assert!(json.get("message").is_none());
+        assert!(json["suggestion"].as_str().unwrap().contains("microphone"));
+    }
+
+    #[test]
+    fn transcription_failed_suggestion_follows_cause() {
+        let missing = DictationError::TranscriptionFailed(
+            "Model 'Base' not downloaded. Please download it from Settings first.".into(),
+        );
+        assert!(missing.suggestion().contains("download-model"));
+
+        let timeout = DictationError::TranscriptionFailed(
+            "Transcription timed out after 30s (inference aborted)".into(),
+        );
+        assert!(timeout.suggestion().contains("Shorten"));
+
+        let other = DictationError::TranscriptionFailed("Whisper inference failed: -1".into());
+        assert!(other.suggestion().contains("sessions --failed"));
+    }
+
+    #[test]
+    fn kind_matches_serialized_kind() {
+        for err in [
+            DictationError::MicrophonePermissionDenied,
+            DictationError::ModelBusy,
+            DictationError::OutputError("x".into()),
+            DictationError::BundleError("x".into()),
+            DictationError::InstallError("x".into()),
+            DictationError::UpdateError("x".into()),
+        ] {
+            let json = serde_json::to_value(&err).unwrap();
+            assert_eq!(json["kind"], err.kind());
+        }
+    }
+
     #[test]
     fn clone_error() {
         let err = DictationError::SettingsError("bad config".into());
@@ -0,0 +1,267 @@
+//! Events the app sends its windows, and external tools through the event
+//! stream: their names and payload types, in one place.
+//!
+//! [`EVENTS`] gives each payload as a TypeScript type, from which
+//! `src/lib/events.ts` is generated (`sagascript event-schema
+//! --typescript`). A window or tool checks [`SCHEMA_VERSION`] at startup
+//! (`get_event_schema`, `GET /events/schema`) to tell whether it was built
+//! for the events this app sends.
+
+use serde::Serialize;
+
+/// Bumped when an event is renamed or removed or a payload changes shape.
+/// Adding an event doesn't bump it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Tauri event names emitted from backend to frontend
+pub mod event {
+    /// App state changed. Payload: `"idle"`, `"loading_model"`,
+    /// `"recording"` or `"transcribing"`, or `"settings_reloaded"` after the
+    /// settings file changed on disk.
+    pub const STATE_CHANGED: &str = "state-changed";
+    /// Transcription result ready
+    pub const TRANSCRIPTION_RESULT: &str = "transcription-result";
+    /// A dictation failed. Payload: the serialized `DictationError`,
+    /// `{ kind, message?, suggestion }`.
+    pub const ERROR: &str = "error";
+    /// Model download progress
+    pub const MODEL_DOWNLOAD_PROGRESS: &str = "model-download-progress";
+    /// Model ready
+    pub const MODEL_READY: &str = "model-ready";
+    /// File-transcription progress. Payload: `{ stage, pct }`, where `stage`
+    /// is `"decoding"`, `"loading_model"`, `"transcribing"` or
+    /// `"post_processing"` and `pct` is 0–100 within that stage.
+    pub const TRANSCRIPTION_PROGRESS: &str = "transcription-progress";
+    /// Hotkey registration health changed (registered OK <-> failed to
+    /// register). Payload: `{ ok: bool, error: string | null, shortcut: string }`.
+    pub const HOTKEY_REGISTRATION_CHANGED: &str = "hotkey-registration-changed";
+    /// The transcription timed out and only the text whisper finished before
+    /// the abort was kept. Sent alongside `transcription-result`; payload is
+    /// the same (partial) text.
+    pub const TRANSCRIPTION_PARTIAL: &str = "transcription-partial";
+    /// Dictation has been consistently slower than real time on the current
+    /// model. Payload: `{ current, suggested, rtf }`.
+    pub const MODEL_SUGGESTION: &str = "model-suggestion";
+    /// Accumulate-mode buffer changed. Payload: `{ text, count }`; an empty
+    /// text means the buffer was flushed or cleared.
+    pub const ACCUMULATED_CHANGED: &str = "accumulated-changed";
+    /// Accumulated dictations were flushed. Payload: the combined text.
+    pub const ACCUMULATED_FLUSHED: &str = "accumulated-flushed";
+    /// The opt-in daily check (`model_update_check`) found newer upstream
+    /// revisions of downloaded models. Payload: `[{ model, display_name,
+    /// installed_commit, latest_commit, update_available }]`.
+    pub const MODEL_UPDATES_AVAILABLE: &str = "model-updates-available";
+    /// The recording was clipping or nearly silent, which likely hurt accuracy.
+    /// Sent alongside `transcription-result`. Payload: `{ kind, message }` with
+    /// `kind` `"clipping"` or `"quiet"`.
+    pub const INPUT_LEVEL_WARNING: &str = "input-level-warning";
+    /// Emitted when the hotkey is pressed while a transcription is still running
+    /// and the press was rejected or queued. Payload: `{ whileBusy }`.
+    pub const BUSY: &str = "busy";
+    /// A file was handed to the app from outside (Services, "Open With") and is waiting
+    /// in the requested-file slot; the main window picks it up with `take_requested_file`.
+    pub const TRANSCRIBE_FILE_REQUESTED: &str = "transcribe-file-requested";
+    /// Overlay accessibility options changed (payload: `overlay::Appearance`).
+    pub const OVERLAY_APPEARANCE: &str = "overlay-appearance";
+    /// A state change to read out to screen readers, outside macOS (payload:
+    /// the text). The overlay renders it in a live region.
+    pub const ANNOUNCEMENT: &str = "announcement";
+    /// The dictation is within a minute of the recording length limit. Sent
+    /// about once a second; payload `{ remainingSecs, limitSecs }`. At zero the
+    /// recording is stopped and transcribed.
+    pub const RECORDING_LIMIT: &str = "recording-limit";
+    /// Structured dictation result, sent right after `transcription-result`.
+    /// Payload: `{ text, segments, partial }`, each segment with `start`, `end`,
+    /// `text`, `avg_logprob` and `no_speech_prob`. `segments` is empty when
+    /// the text was stitched together from background chunks.
+    pub const TRANSCRIPT: &str = "transcript";
+    /// Profile a hotkey recording was started with, for the overlay to tint
+    /// itself: `{ name, overlayColor }`, or null for the main hotkeys.
+    pub const DICTATION_PROFILE: &str = "dictation-profile";
+    /// A transcript over `paste_confirm_chars` is held back from auto-paste
+    /// until confirmed. Payload: `{ chars, preview }`, or null once it was
+    /// pasted or discarded.
+    pub const PASTE_CONFIRMATION: &str = "paste-confirmation";
+    /// `capture_source` changed, from the tray or the settings window.
+    /// Payload: `"microphone"` or `"system"`.
+    pub const CAPTURE_SOURCE_CHANGED: &str = "capture-source-changed";
+    /// The settings file doesn't parse, so defaults (at startup) or the
+    /// previous settings (on a hot reload) are in use. Payload: `{ line,
+    /// column, key, message }`, or null once the file loads again.
+    pub const SETTINGS_LOAD_WARNING: &str = "settings-load-warning";
+    /// Speaking pace of a dictation. Payload: `{ words, wpm, live }`, `wpm`
+    /// being words per minute of recording, or null under a few seconds.
+    /// Sent with `live: true` after each background chunk of a long
+    /// recording, and once more with the final transcript.
+    pub const DICTATION_PACE: &str = "dictation-pace";
+    /// A dictation was skipped because its audio repeated the previous one
+    /// (`dedupe_dictations`), e.g. after an accidental double press. Payload:
+    /// `{ sinceLastSecs }`, how long after the previous dictation it stopped.
+    pub const DICTATION_DEDUPED: &str = "dictation-deduped";
+    /// The app changed the settings on its own, so open windows should
+    /// reload them. Payload: `{ reason, profile }`; `reason` is `"schedule"`
+    /// when a profile's schedule made its language and model the global ones.
+    pub const SETTINGS_CHANGED: &str = "settings-changed";
+    /// A dictation finished, with where its time went. Sent after
+    /// `transcript`; payload: `transcription::timing::Timing`, `{ capture_ms,
+    /// decode_ms, model_load_ms, inference_ms, post_process_ms, paste_ms,
+    /// total_ms }`, `total_ms` counting from the end of the recording.
+    pub const TRANSCRIPTION_COMPLETE: &str = "transcription-complete";
+    /// The main window was asked to show a tab (the tray, the settings
+    /// hotkey). Payload: the tab, as `SettingsTab` serializes it.
+    pub const NAVIGATE_TAB: &str = "navigate-tab";
+}
+
+/// One event and the TypeScript type of its payload. Payload types name
+/// the interfaces in `src/lib/api.ts`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct EventSchema {
+    pub name: &'static str,
+    pub payload: &'static str,
+}
+
+const fn entry(name: &'static str, payload: &'static str) -> EventSchema {
+    EventSchema { name, payload }
+}
+
+/// Every event in [`event`].
+pub const EVENTS: &[EventSchema] = &[
+    entry(
+        event::STATE_CHANGED,
+        r#""idle" | "loading_model" | "recording" | "transcribing" | "settings_reloaded""#,
+    ),
+    entry(event::TRANSCRIPTION_RESULT, "string"),
+    entry(event::ERROR, "DictationErrorPayload | string"),
+    entry(
+        event::MODEL_DOWNLOAD_PROGRESS,
+        "{ model: string; downloaded: number; total: number; progress: number }",
+    ),
+    entry(event::MODEL_READY, "null"),
+    entry(event::TRANSCRIPTION_PROGRESS, "StageProgress"),
+    entry(event::HOTKEY_REGISTRATION_CHANGED, "HotkeyStatus"),
+    entry(event::TRANSCRIPTION_PARTIAL, "string"),
+    entry(event::MODEL_SUGGESTION, "ModelSuggestion"),
+    entry(event::ACCUMULATED_CHANGED, "AccumulatedSnapshot"),
+    entry(event::ACCUMULATED_FLUSHED, "string"),
+    entry(event::MODEL_UPDATES_AVAILABLE, "ModelUpdate[]"),
+    entry(event::INPUT_LEVEL_WARNING, "InputLevelWarning"),
+    entry(event::BUSY, "{ whileBusy: WhileBusy }"),
+    entry(event::TRANSCRIBE_FILE_REQUESTED, "null"),
+    entry(event::OVERLAY_APPEARANCE, "OverlayAppearance"),
+    entry(event::ANNOUNCEMENT, "string"),
+    entry(
+        event::RECORDING_LIMIT,
+        "{ remainingSecs: number; limitSecs: number }",
+    ),
+    entry(event::TRANSCRIPT, "Transcript"),
+    entry(
+        event::DICTATION_PROFILE,
+        "{ name: string; overlayColor: string | null } | null",
+    ),
+    entry(event::PASTE_CONFIRMATION, "PasteConfirmation | null"),
+    entry(event::CAPTURE_SOURCE_CHANGED, "CaptureSource"),
+    entry(event::SETTINGS_LOAD_WARNING, "SettingsLoadIssue | null"),
+    entry(
+        event::DICTATION_PACE,
+        "{ words: number; wpm: number | null; live: boolean }",
+    ),
+    entry(event::DICTATION_DEDUPED, "{ sinceLastSecs: number }"),
+    entry(
+        event::SETTINGS_CHANGED,
+        r#"{ reason: "schedule"; profile: string }"#,
+    ),
+    entry(event::TRANSCRIPTION_COMPLETE, "TranscriptionTiming"),
+    entry(event::NAVIGATE_TAB, "SettingsTab"),
+];
+
+/// What `get_event_schema` returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Schema {
+    pub version: u32,
+    pub events: &'static [EventSchema],
+}
+
+pub fn schema() -> Schema {
+    Schema {
+        version: SCHEMA_VERSION,
+        events: EVENTS,
+    }
+}
+
+/// `src/lib/events.ts`: the version, a constant per event name and a map
+/// from each name to its payload type.
+pub fn typescript() -> String {
+    let mut types: Vec<&str> = EVENTS
+        .iter()
+        .flat_map(|e| e.payload.split(|c: char| !c.is_ascii_alphanumeric()))
+        .filter(|word| word.starts_with(|c: char| c.is_ascii_uppercase()))
+        .collect();
+    types.sort_unstable();
+    types.dedup();
+
+    let mut ts = String::from(
+        "// Generated from sagascript-core's events module by\n\
+         // `sagascript event-schema --typescript`. Do not edit.\n\n",
+    );
+    ts.push_str("import type {\n");
+    for t in types {
+        ts.push_str(&format!("  {t},\n"));
+    }
+    ts.push_str("} from \"./api\";\n\n");
+    ts.push_str(&format!(
+        "export const EVENT_SCHEMA_VERSION = {SCHEMA_VERSION};\n\n"
+    ));
+    ts.push_str("export const EVENTS = {\n");
+    for e in EVENTS {
+        ts.push_str(&format!("  {}: \"{}\",\n", constant_name(e.name), e.name));
+    }
+    ts.push_str("} as const;\n\n");
+    ts.push_str("export type EventName = (typeof EVENTS)[keyof typeof EVENTS];\n\n");
+    ts.push_str("export interface EventPayloads {\n");
+    for e in EVENTS {
+        ts.push_str(&format!("  \"{}\": {};\n", e.name, e.payload));
+    }
+    ts.push_str("}\n");
+    ts
+}
+
+/// `state-changed` -> `STATE_CHANGED`, the name of its constant.
+fn constant_name(name: &str) -> String {
+    name.replace('-', "_").to_ascii_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_names_are_kebab_case() {
+        let events: Vec<&str> = EVENTS.iter().map(|e| e.name).collect();
+        for name in events {
+            assert!(!name.is_empty());
+            assert!(
+                !name.contains('_'),
+                "event '{name}' uses underscore instead of kebab-case"
+            );
+            assert!(!name.contains(' '), "event '{name}' contains spaces");
+        }
+    }
+
+    #[test]
+    fn event_names_are_unique() {
+        let events: Vec<&str> = EVENTS.iter().map(|e| e.name).collect();
+        for (i, a) in events.iter().enumerate() {
+            for (j, b) in events.iter().enumerate() {
+                if i != j {
+                    assert_ne!(a, b, "duplicate event name: {a}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn typescript_definition_is_up_to_date() {
+        // Regenerate with `sagascript event-schema --typescript > src/lib/events.ts`.
+        assert_eq!(typescript(), include_str!("../../../../src/lib/events.ts"));
+    }
+}
@@ -0,0 +1,270 @@
+//! Transcription history.
+//!
+//! Every finished transcription — hotkey dictations, files transcribed in
+//! the app and the CLI's `record` and `transcribe` — is appended as one JSON
+//! line to `history.jsonl` under the app data directory, so a transcript
+//! survives a restart and can be found again later, along with where it
+//! came from. The file stays on this machine; `clear` removes it.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::settings::{Language, WhisperModel};
+
+const HISTORY_FILENAME: &str = "history.jsonl";
+
+/// Where the running app keeps its transcription history.
+pub fn history_path() -> PathBuf {
+    crate::settings::store::app_data_dir().join(HISTORY_FILENAME)
+}
+
+/// Where a transcription came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistorySource {
+    /// A hotkey or test-button dictation.
+    Dictation,
+    /// A file transcribed from the Transcribe tab.
+    File,
+    /// `sagascript record` or `sagascript transcribe`.
+    Cli,
+}
+
+impl HistorySource {
+    pub const ALL: [HistorySource; 3] = [Self::Dictation, Self::File, Self::Cli];
+
+    /// The name it is stored and filtered by.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Dictation => "dictation",
+            Self::File => "file",
+            Self::Cli => "cli",
+        }
+    }
+}
+
+/// One transcription in the history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Short random ID (`h-xxxxxxxx`), used to delete the entry.
+    pub id: String,
+    /// When the transcription finished, in seconds since the Unix epoch.
+    pub recorded_at: u64,
+    pub source: HistorySource,
+    /// Name of the transcribed file, for [`HistorySource::File`] and files
+    /// transcribed with the CLI.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Input device the audio was recorded from, for recordings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    /// Length of the transcribed audio.
+    pub duration_secs: f32,
+    pub model: WhisperModel,
+    pub language: Language,
+    pub text: String,
+}
+
+impl HistoryEntry {
+    /// An entry for a transcription that finished just now.
+    pub fn new(
+        source: HistorySource,
+        duration_secs: f32,
+        model: WhisperModel,
+        language: Language,
+        text: &str,
+    ) -> Self {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        Self {
+            id: format!("h-{}", &id[..8]),
+            recorded_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            source,
+            file: None,
+            device: None,
+            duration_secs,
+            model,
+            language,
+            text: text.to_string(),
+        }
+    }
+
+    /// The same entry, naming the file it was transcribed from.
+    pub fn with_file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    /// The same entry, naming the input device it was recorded from.
+    pub fn with_device(mut self, device: Option<String>) -> Self {
+        self.device = device;
+        self
+    }
+}
+
+/// `recorded_at` as `YYYY-MM-DD HH:MM:SS` (UTC).
+pub fn format_timestamp(secs: u64) -> String {
+    let date = crate::telemetry::date_from_days((secs / 86_400) as i64);
+    let time = secs % 86_400;
+    format!(
+        "{date} {:02}:{:02}:{:02}",
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+/// Add `entry` to the end of the history at `path`.
+pub fn append(path: &Path, entry: &HistoryEntry) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
+
+/// Every entry in the history at `path`, newest first. Lines that don't
+/// parse (a torn write) are skipped.
+pub fn load(path: &Path) -> Vec<HistoryEntry> {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<HistoryEntry> = contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    entries.reverse();
+    entries
+}
+
+/// Remove the entry with `id`. Returns whether there was one.
+pub fn delete(path: &Path, id: &str) -> io::Result<bool> {
+    let mut entries = load(path);
+    let before = entries.len();
+    entries.retain(|entry| entry.id != id);
+    if entries.len() == before {
+        return Ok(false);
+    }
+    entries.reverse();
+    let mut contents = String::new();
+    for entry in &entries {
+        contents.push_str(&serde_json::to_string(entry)?);
+        contents.push('\n');
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)?;
+    Ok(true)
+}
+
+/// Remove the whole history.
+pub fn clear(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("sagascript-history-{}", uuid::Uuid::new_v4()))
+            .join(HISTORY_FILENAME)
+    }
+
+    fn entry(text: &str) -> HistoryEntry {
+        HistoryEntry::new(
+            HistorySource::Dictation,
+            2.5,
+            WhisperModel::BaseEn,
+            Language::English,
+            text,
+        )
+    }
+
+    #[test]
+    fn entries_load_newest_first() {
+        let path = temp_path();
+        assert!(load(&path).is_empty());
+
+        let first = entry("First note.");
+        let second = entry("Second note.").with_file("call.m4a");
+        append(&path, &first).unwrap();
+        append(&path, &second).unwrap();
+        assert_eq!(load(&path), vec![second, first]);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn a_torn_line_is_skipped() {
+        let path = temp_path();
+        let kept = entry("Kept.");
+        append(&path, &kept).unwrap();
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"id\":\"h-")
+            .unwrap();
+        assert_eq!(load(&path), vec![kept]);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn source_and_device_round_trip() {
+        let path = temp_path();
+        let recorded = HistoryEntry::new(
+            HistorySource::Cli,
+            2.5,
+            WhisperModel::BaseEn,
+            Language::English,
+            "From the terminal.",
+        )
+        .with_device(Some("USB Microphone".to_string()));
+        append(&path, &recorded).unwrap();
+        assert_eq!(load(&path), vec![recorded]);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+
+        // Entries written before devices were recorded still load.
+        let old = r#"{"id":"h-1","recorded_at":0,"source":"dictation","duration_secs":1.0,"model":"base.en","language":"en","text":"Hi"}"#;
+        let entry: HistoryEntry = serde_json::from_str(old).unwrap();
+        assert_eq!(entry.device, None);
+    }
+
+    #[test]
+    fn timestamps_format_as_utc() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+        assert_eq!(format_timestamp(1_792_149_296), "2026-10-16 11:14:56");
+    }
+
+    #[test]
+    fn delete_and_clear_remove_entries() {
+        let path = temp_path();
+        let (a, b, c) = (entry("A."), entry("B."), entry("C."));
+        for e in [&a, &b, &c] {
+            append(&path, e).unwrap();
+        }
+        assert!(delete(&path, &b.id).unwrap());
+        assert!(!delete(&path, &b.id).unwrap());
+        assert_eq!(load(&path), vec![c, a]);
+
+        clear(&path).unwrap();
+        assert!(load(&path).is_empty());
+        clear(&path).unwrap();
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
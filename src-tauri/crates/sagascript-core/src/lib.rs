@@ -4,9 +4,27 @@
 //! entry points and integrations on top.
 
 pub mod audio;
+pub mod cli_install;
+pub mod credentials;
+pub mod daily_note;
 pub mod download;
 pub mod error;
+pub mod events;
+pub mod history;
+pub mod llm;
+pub mod minutes;
+pub mod mqtt;
+pub mod net;
+pub mod output;
+pub mod paste_format;
+pub mod progress;
+pub mod recovery;
+pub mod replacements;
+pub mod self_update;
+pub mod sessions;
 pub mod settings;
+pub mod support_bundle;
+pub mod telemetry;
 pub mod transcription;
 
 #[cfg(feature = "diarization")]
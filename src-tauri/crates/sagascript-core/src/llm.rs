@@ -0,0 +1,380 @@
+//! Optional language-model cleanup of finished dictations.
+//!
+//! With `Settings::post_process_command` set, each dictation goes through a
+//! language model before it is pasted, to fix grammar or do whatever
+//! `post_process_prompt` asks. The command is one of:
+//!
+//! - a shell command (`ollama run llama3.2`, `llm -m gpt-4o-mini`). It gets
+//!   the prompt, a blank line and the transcript on stdin, and what it prints
+//!   is the result. Tools that take them as arguments can name `{prompt}` and
+//!   `{text}` in the command instead; they are filled in quoted, and stdin
+//!   then only carries what the command doesn't name. On Windows they reach
+//!   the command through the `SAGASCRIPT_PROMPT` and `SAGASCRIPT_TEXT`
+//!   environment variables, expanded only after `cmd` has parsed the line,
+//!   so nothing dictated is ever read as part of the command.
+//! - the URL of an OpenAI-compatible chat completions endpoint (Ollama,
+//!   llama.cpp, LM Studio, OpenAI), asked for `post_process_model`. An API
+//!   key, if the endpoint needs one, lives in the credential store.
+//!
+//! Post-processing never costs a dictation: callers keep the raw transcript
+//! when it fails, times out or comes back empty.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use regex::{Captures, Regex};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::warn;
+
+use crate::credentials;
+use crate::error::DictationError;
+use crate::settings::Settings;
+
+/// Prompt used when `post_process_prompt` is empty.
+pub const DEFAULT_PROMPT: &str = "Correct the grammar, punctuation and obvious transcription \
+    mistakes in this dictation. Keep its language, meaning and wording otherwise. Reply with \
+    the corrected text only.";
+
+/// Name of the endpoint API key in the credential store.
+pub const API_KEY_SECRET: &str = "post-process-api-key";
+
+/// How long the model may take before the raw transcript is used.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Environment variables holding the prompt and the dictation for the
+/// command (what `{prompt}` and `{text}` expand to on Windows).
+const PROMPT_VAR: &str = "SAGASCRIPT_PROMPT";
+const TEXT_VAR: &str = "SAGASCRIPT_TEXT";
+
+/// A configured post-processing step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PostProcess {
+    /// Run through the platform shell (`sh -c`, `cmd /C`).
+    Command { command: String, prompt: String },
+    /// POST to an OpenAI-compatible chat completions URL.
+    Endpoint {
+        url: String,
+        model: String,
+        prompt: String,
+    },
+}
+
+impl PostProcess {
+    /// The step `settings` configure, or `None` when post-processing is off.
+    pub fn from_settings(settings: &Settings) -> Option<Self> {
+        let command = settings.post_process_command.trim();
+        if command.is_empty() {
+            return None;
+        }
+        let prompt = match settings.post_process_prompt.trim() {
+            "" => DEFAULT_PROMPT,
+            prompt => prompt,
+        }
+        .to_string();
+        Some(if is_endpoint(command) {
+            PostProcess::Endpoint {
+                url: command.to_string(),
+                model: settings.post_process_model.trim().to_string(),
+                prompt,
+            }
+        } else {
+            PostProcess::Command {
+                command: command.to_string(),
+                prompt,
+            }
+        })
+    }
+
+    /// `text` as the model rewrote it.
+    pub async fn run(&self, text: &str) -> Result<String, DictationError> {
+        let output = match self {
+            PostProcess::Command { command, prompt } => {
+                tokio::time::timeout(TIMEOUT, run_command(command, prompt, text)).await
+            }
+            PostProcess::Endpoint { url, model, prompt } => {
+                tokio::time::timeout(TIMEOUT, ask_endpoint(url, model, prompt, text)).await
+            }
+        }
+        .map_err(|_| failed(format!("no answer within {} s", TIMEOUT.as_secs())))??;
+        let output = output.trim();
+        if output.is_empty() {
+            return Err(failed("the model returned nothing".to_string()));
+        }
+        Ok(output.to_string())
+    }
+}
+
+/// Whether a `post_process_command` names an endpoint rather than a command.
+pub fn is_endpoint(command: &str) -> bool {
+    command.starts_with("http://") || command.starts_with("https://")
+}
+
+fn failed(why: String) -> DictationError {
+    DictationError::PostProcessError(why)
+}
+
+async fn run_command(command: &str, prompt: &str, text: &str) -> Result<String, DictationError> {
+    let (line, input) = command_line(command, prompt, text);
+    let mut child = shell(&line)
+        .env(PROMPT_VAR, prompt)
+        .env(TEXT_VAR, text)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| failed(format!("cannot run '{command}': {e}")))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Written alongside reading the output, so a command that answers
+        // before it has read everything can't stall on a full pipe. One that
+        // doesn't read stdin at all closes it; that's not an error.
+        tokio::spawn(async move {
+            let _ = stdin.write_all(input.as_bytes()).await;
+        });
+    }
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| failed(format!("'{command}' failed: {e}")))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr
+            .lines()
+            .rev()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(|line| format!(": {line}"))
+            .unwrap_or_default();
+        return Err(failed(format!(
+            "'{command}' exited with {}{reason}",
+            output.status
+        )));
+    }
+    String::from_utf8(output.stdout).map_err(|_| failed(format!("'{command}' printed non-UTF-8")))
+}
+
+/// The command line to run, with `{prompt}` and `{text}` filled in, and what
+/// goes to its stdin.
+fn command_line(command: &str, prompt: &str, text: &str) -> (String, String) {
+    let placeholder = Regex::new(r"\{(prompt|text)\}").expect("valid placeholder pattern");
+    let line = placeholder
+        .replace_all(command, |caps: &Captures| {
+            if &caps[1] == "prompt" {
+                fill(PROMPT_VAR, prompt)
+            } else {
+                fill(TEXT_VAR, text)
+            }
+        })
+        .into_owned();
+    let input = match (command.contains("{prompt}"), command.contains("{text}")) {
+        (true, true) => String::new(),
+        (false, true) => prompt.to_string(),
+        (true, false) => text.to_string(),
+        (false, false) => format!("{prompt}\n\n{text}"),
+    };
+    (line, input)
+}
+
+/// A placeholder's replacement: `value`, single-quoted for `sh`.
+#[cfg(unix)]
+fn fill(_var: &str, value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// A placeholder's replacement: a reference to the environment variable
+/// `var`. `cmd` has no quoting that stops `%VAR%` expansion or a `"&` from
+/// ending the argument, so the value itself never goes on the line; `!var!`
+/// is expanded (with `/V:ON`) after the line is parsed.
+#[cfg(not(unix))]
+fn fill(var: &str, _value: &str) -> String {
+    format!("\"!{var}!\"")
+}
+
+#[cfg(unix)]
+fn shell(line: &str) -> Command {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(line);
+    command
+}
+
+#[cfg(windows)]
+fn shell(line: &str) -> Command {
+    let mut command = Command::new("cmd");
+    command.args(["/V:ON", "/C"]).raw_arg(line);
+    command
+}
+
+async fn ask_endpoint(
+    url: &str,
+    model: &str,
+    prompt: &str,
+    text: &str,
+) -> Result<String, DictationError> {
+    if model.is_empty() {
+        return Err(failed(
+            "set post_process_model to the model the endpoint should use".to_string(),
+        ));
+    }
+    let api_key = match credentials::default_store().and_then(|store| store.get(API_KEY_SECRET)) {
+        Ok(key) => key,
+        Err(e) => {
+            warn!("Post-processing without an API key: {e}");
+            None
+        }
+    };
+    let client = crate::net::client_builder()
+        .build()
+        .map_err(|e| failed(format!("HTTP client: {e}")))?;
+    let mut request = client.post(url).json(&serde_json::json!({
+        "model": model,
+        "temperature": 0,
+        "messages": [
+            { "role": "system", "content": prompt },
+            { "role": "user", "content": text },
+        ],
+    }));
+    if let Some(key) = api_key {
+        request = request.bearer_auth(key);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| failed(format!("{url}: {e}")))?;
+    if !response.status().is_success() {
+        return Err(failed(format!("{url} returned {}", response.status())));
+    }
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| failed(format!("{url} sent an invalid reply: {e}")))?;
+    reply_text(&body).ok_or_else(|| failed(format!("{url} sent no message")))
+}
+
+/// The assistant message in a chat completions reply.
+fn reply_text(body: &serde_json::Value) -> Option<String> {
+    body["choices"][0]["message"]["content"]
+        .as_str()
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(command: &str, prompt: &str) -> Settings {
+        Settings {
+            post_process_command: command.to_string(),
+            post_process_prompt: prompt.to_string(),
+            post_process_model: "llama3.2".to_string(),
+            ..Settings::default()
+        }
+    }
+
+    #[test]
+    fn settings_pick_a_command_or_an_endpoint() {
+        assert_eq!(PostProcess::from_settings(&settings(" ", "x")), None);
+        assert_eq!(
+            PostProcess::from_settings(&settings("ollama run llama3.2", "")),
+            Some(PostProcess::Command {
+                command: "ollama run llama3.2".to_string(),
+                prompt: DEFAULT_PROMPT.to_string(),
+            })
+        );
+        assert_eq!(
+            PostProcess::from_settings(&settings(
+                "http://localhost:11434/v1/chat/completions",
+                "Fix it"
+            )),
+            Some(PostProcess::Endpoint {
+                url: "http://localhost:11434/v1/chat/completions".to_string(),
+                model: "llama3.2".to_string(),
+                prompt: "Fix it".to_string(),
+            })
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn placeholders_are_quoted_and_taken_off_stdin() {
+        assert_eq!(
+            command_line("llm", "Fix", "hi"),
+            ("llm".to_string(), "Fix\n\nhi".to_string())
+        );
+        assert_eq!(
+            command_line("llm -s {prompt}", "Don't {text}", "hi"),
+            ("llm -s 'Don'\\''t {text}'".to_string(), "hi".to_string())
+        );
+        assert_eq!(
+            command_line("fix {text}", "Fix", "it's"),
+            ("fix 'it'\\''s'".to_string(), "Fix".to_string())
+        );
+        assert_eq!(
+            command_line("llm -s {prompt} {text}", "Fix", "hi"),
+            ("llm -s 'Fix' 'hi'".to_string(), String::new())
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn placeholders_stay_off_the_windows_command_line() {
+        let text = "%PATH% \" & echo pwned";
+        assert_eq!(
+            command_line("fix {text}", "Fix", text),
+            ("fix \"!SAGASCRIPT_TEXT!\"".to_string(), "Fix".to_string())
+        );
+    }
+
+    #[cfg(windows)]
+    #[tokio::test]
+    async fn dictation_cannot_run_commands_on_windows() {
+        let step = PostProcess::Command {
+            command: "echo {text}".to_string(),
+            prompt: "fix".to_string(),
+        };
+        let out = step.run("%PATH% \" & echo pwned").await.unwrap();
+        assert_eq!(out, "\"%PATH% \" & echo pwned\"");
+    }
+
+    #[test]
+    fn replies_are_read_from_the_first_choice() {
+        let body = serde_json::json!({
+            "choices": [{ "message": { "role": "assistant", "content": "Hello." } }]
+        });
+        assert_eq!(reply_text(&body).as_deref(), Some("Hello."));
+        assert_eq!(reply_text(&serde_json::json!({ "error": "x" })), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn commands_rewrite_the_dictation() {
+        let step = PostProcess::Command {
+            command: "tr a-z A-Z".to_string(),
+            prompt: "fix".to_string(),
+        };
+        assert_eq!(step.run("hello").await.unwrap(), "FIX\n\nHELLO");
+
+        let step = PostProcess::Command {
+            command: "printf '%s.' {text}".to_string(),
+            prompt: "fix".to_string(),
+        };
+        assert_eq!(step.run("it's fine").await.unwrap(), "it's fine.");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn failing_or_silent_commands_are_errors() {
+        let step = PostProcess::Command {
+            command: "echo 'model not found' >&2; exit 3".to_string(),
+            prompt: String::new(),
+        };
+        let err = step.run("hello").await.unwrap_err().to_string();
+        assert!(err.contains("model not found"), "{err}");
+
+        let step = PostProcess::Command {
+            command: "true".to_string(),
+            prompt: String::new(),
+        };
+        assert!(step.run("hello").await.is_err());
+    }
+}
@@ -0,0 +1,313 @@
+//! Meeting minutes from a speaker-attributed transcript.
+//!
+//! Renders the turns of a diarized (or channel-split) file transcription as
+//! a Markdown document: who took part and for how long, the action items,
+//! and the transcript with timestamps. Action items are found with keyword
+//! rules — a sentence is one when it contains a phrase such as "I'll" or
+//! "follow up" — so they need a human look before being sent round.
+
+use std::fmt::Write as _;
+
+use crate::settings::Language;
+use crate::transcription::channels::ChannelSegment;
+
+/// Phrases that make a sentence an action item, matched as whole words,
+/// case-insensitively.
+const ENGLISH_ACTION_PHRASES: &[&str] = &[
+    "action item",
+    "i will",
+    "i'll",
+    "we will",
+    "we'll",
+    "you will",
+    "you'll",
+    "let's",
+    "need to",
+    "needs to",
+    "follow up",
+    "to do",
+    "todo",
+    "deadline",
+    "by tomorrow",
+    "by monday",
+    "by friday",
+    "next week",
+];
+
+const SWEDISH_ACTION_PHRASES: &[&str] = &[
+    "jag ska",
+    "jag tar",
+    "vi ska",
+    "du ska",
+    "vi behöver",
+    "behöver vi",
+    "måste",
+    "följa upp",
+    "följer upp",
+    "att göra",
+    "till fredag",
+    "till måndag",
+    "nästa vecka",
+];
+
+const NORWEGIAN_ACTION_PHRASES: &[&str] = &[
+    "jeg skal",
+    "jeg tar",
+    "vi skal",
+    "du skal",
+    "vi må",
+    "må vi",
+    "følge opp",
+    "følger opp",
+    "å gjøre",
+    "til fredag",
+    "til mandag",
+    "neste uke",
+];
+
+/// One speaker's turn in the transcript.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Turn {
+    /// Start time in seconds
+    pub start: f64,
+    /// End time in seconds
+    pub end: f64,
+    /// Speaker label (e.g. "SPEAKER_0" or "CH1")
+    pub speaker: String,
+    pub text: String,
+}
+
+impl From<&ChannelSegment> for Turn {
+    fn from(seg: &ChannelSegment) -> Self {
+        Self {
+            start: seg.start,
+            end: seg.end,
+            speaker: seg.channel.clone(),
+            text: seg.text.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "diarization")]
+impl From<&crate::diarization::DiarizedSegment> for Turn {
+    fn from(seg: &crate::diarization::DiarizedSegment) -> Self {
+        Self {
+            start: seg.start,
+            end: seg.end,
+            speaker: seg.speaker.clone(),
+            text: seg.text.trim().to_string(),
+        }
+    }
+}
+
+/// A sentence flagged as an action item, with who said it and when.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionItem {
+    pub speaker: String,
+    pub at: f64,
+    pub text: String,
+}
+
+/// The action phrases for `language`; auto-detected transcripts get all of
+/// them.
+fn action_phrases(language: Language) -> Vec<&'static str> {
+    match language {
+        Language::English => ENGLISH_ACTION_PHRASES.to_vec(),
+        Language::Swedish => SWEDISH_ACTION_PHRASES.to_vec(),
+        Language::Norwegian => NORWEGIAN_ACTION_PHRASES.to_vec(),
+        Language::Auto => [
+            ENGLISH_ACTION_PHRASES,
+            SWEDISH_ACTION_PHRASES,
+            NORWEGIAN_ACTION_PHRASES,
+        ]
+        .concat(),
+    }
+}
+
+/// The sentences of `turns` that match an action phrase for `language`.
+pub fn action_items(turns: &[Turn], language: Language) -> Vec<ActionItem> {
+    let phrases: Vec<Vec<String>> = action_phrases(language).into_iter().map(words).collect();
+    turns
+        .iter()
+        .flat_map(|turn| sentences(&turn.text).map(move |sentence| (turn, sentence)))
+        .filter(|(_, sentence)| {
+            let sentence = words(sentence);
+            phrases.iter().any(|phrase| {
+                sentence
+                    .windows(phrase.len())
+                    .any(|window| window == phrase.as_slice())
+            })
+        })
+        .map(|(turn, sentence)| ActionItem {
+            speaker: turn.speaker.clone(),
+            at: turn.start,
+            text: sentence.to_string(),
+        })
+        .collect()
+}
+
+/// Render the minutes of the transcription titled `title`, lasting
+/// `duration` seconds, as Markdown.
+pub fn render_markdown(title: &str, duration: f64, turns: &[Turn], language: Language) -> String {
+    let mut md = String::new();
+    let _ = writeln!(md, "# Minutes: {title}\n");
+    let _ = writeln!(md, "- **Duration:** {}", clock(duration));
+
+    // Speakers in order of first appearance, with their talk time.
+    let mut speakers: Vec<(&str, f64, usize)> = Vec::new();
+    for turn in turns {
+        let talk = (turn.end - turn.start).max(0.0);
+        match speakers.iter_mut().find(|(s, _, _)| *s == turn.speaker) {
+            Some((_, secs, count)) => {
+                *secs += talk;
+                *count += 1;
+            }
+            None => speakers.push((&turn.speaker, talk, 1)),
+        }
+    }
+    let _ = writeln!(md, "- **Speakers:** {}\n", speakers.len());
+
+    md.push_str("## Participants\n\n");
+    md.push_str("| Speaker | Speaking time | Turns |\n");
+    md.push_str("| --- | --- | --- |\n");
+    for (speaker, secs, count) in &speakers {
+        let _ = writeln!(md, "| {speaker} | {} | {count} |", clock(*secs));
+    }
+
+    md.push_str("\n## Action items\n\n");
+    let items = action_items(turns, language);
+    if items.is_empty() {
+        md.push_str("_None found._\n");
+    }
+    for item in &items {
+        let _ = writeln!(
+            md,
+            "- [ ] **{}** ({}): {}",
+            item.speaker,
+            clock(item.at),
+            item.text
+        );
+    }
+
+    md.push_str("\n## Transcript\n");
+    for turn in turns {
+        let _ = write!(
+            md,
+            "\n**[{}] {}:** {}\n",
+            clock(turn.start),
+            turn.speaker,
+            turn.text
+        );
+    }
+    md
+}
+
+/// `seconds` as `h:mm:ss`, or `m:ss` under an hour.
+fn clock(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (h, m, s) = (total / 3600, total / 60 % 60, total % 60);
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m}:{s:02}")
+    }
+}
+
+/// Split `text` after sentence-ending punctuation.
+fn sentences(text: &str) -> impl Iterator<Item = &str> {
+    text.split_inclusive(['.', '!', '?'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// Lowercase words of `text`, punctuation other than apostrophes dropped.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '’'))
+        .filter(|w| !w.is_empty())
+        .map(|w| w.replace('’', "'").to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(start: f64, end: f64, speaker: &str, text: &str) -> Turn {
+        Turn {
+            start,
+            end,
+            speaker: speaker.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn action_items_match_whole_phrases_per_sentence() {
+        let turns = [
+            turn(
+                0.0,
+                5.0,
+                "SPEAKER_0",
+                "The numbers look fine. I'll send the report by Friday!",
+            ),
+            turn(5.0, 8.0, "SPEAKER_1", "Willow is a tree. Sounds good."),
+            turn(8.0, 10.0, "SPEAKER_1", "We need to follow up with legal."),
+        ];
+        let items = action_items(&turns, Language::English);
+        assert_eq!(
+            items,
+            vec![
+                ActionItem {
+                    speaker: "SPEAKER_0".into(),
+                    at: 0.0,
+                    text: "I'll send the report by Friday!".into(),
+                },
+                ActionItem {
+                    speaker: "SPEAKER_1".into(),
+                    at: 8.0,
+                    text: "We need to follow up with legal.".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn action_phrases_follow_the_language() {
+        let turns = [turn(0.0, 3.0, "CH1", "Jag tar det till fredag.")];
+        assert_eq!(action_items(&turns, Language::Swedish).len(), 1);
+        assert!(action_items(&turns, Language::English).is_empty());
+        assert_eq!(action_items(&turns, Language::Auto).len(), 1);
+    }
+
+    #[test]
+    fn minutes_list_participants_actions_and_transcript() {
+        let turns = [
+            turn(
+                2.0,
+                12.0,
+                "SPEAKER_0",
+                "Welcome. Let's start with the budget.",
+            ),
+            turn(12.5, 20.0, "SPEAKER_1", "It is on track."),
+            turn(3725.0, 3730.0, "SPEAKER_0", "Thanks all."),
+        ];
+        let md = render_markdown("standup.m4a", 3731.0, &turns, Language::English);
+        assert!(md.starts_with("# Minutes: standup.m4a\n"));
+        assert!(md.contains("- **Duration:** 1:02:11\n"));
+        assert!(md.contains("- **Speakers:** 2\n"));
+        assert!(md.contains("| SPEAKER_0 | 0:15 | 2 |\n"));
+        assert!(md.contains("- [ ] **SPEAKER_0** (0:02): Let's start with the budget.\n"));
+        assert!(md.contains("**[1:02:05] SPEAKER_0:** Thanks all.\n"));
+    }
+
+    #[test]
+    fn minutes_say_when_there_are_no_action_items() {
+        let md = render_markdown(
+            "x.wav",
+            1.0,
+            &[turn(0.0, 1.0, "CH1", "Hello.")],
+            Language::English,
+        );
+        assert!(md.contains("## Action items\n\n_None found._\n"));
+    }
+}
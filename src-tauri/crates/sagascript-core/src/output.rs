@@ -0,0 +1,916 @@
+//! Output routing: where a finished transcription goes besides the screen.
+//!
+//! The routing table is `Settings::output_sinks`, a list of sinks that are
+//! all applied to every dictation; a profile can bring its own table. The
+//! `paste` sink pastes into the focused app (the GUI only: it needs macOS
+//! Accessibility approval, which the app checks before keeping it). Paste
+//! and clipboard delivery are left to the caller (the GUI has to run them on
+//! the main thread, the CLI uses arboard directly); this module handles the
+//! file, named pipe, daily note, webhook and MQTT sinks. MQTT sinks also get
+//! the app's state changes (see [`publish_state`]).
+//!
+//! File, pipe and webhook sinks take an optional template that lays out each
+//! entry, e.g. `- {timestamp} {text}` or a chat service's JSON body. See
+//! [`render_template`] for the variables.
+
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::credentials;
+use crate::daily_note;
+use crate::error::DictationError;
+use crate::mqtt;
+use crate::settings::{Language, WhisperModel};
+
+/// How long a webhook may take before the delivery is reported as failed.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One destination for transcribed text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutputSink {
+    /// Leave the text on the system clipboard.
+    Clipboard,
+    /// Paste the text into the focused app, as if typed.
+    Paste,
+    /// Append the text as a line to a file (`~/` is expanded).
+    File {
+        path: String,
+        /// Layout of each entry; empty for the text alone.
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        template: String,
+    },
+    /// Write the text as a line to a named pipe (a FIFO made with `mkfifo`,
+    /// or `\\.\pipe\NAME` on Windows) for an editor or status bar to read.
+    /// Skipped while nothing has the pipe open for reading.
+    Pipe {
+        path: String,
+        /// Layout of each entry; empty for the text alone.
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        template: String,
+    },
+    /// Add the text as a timestamped item under `heading` in today's note in
+    /// a notes vault (e.g. Obsidian). `file` names the note relative to the
+    /// vault, with `YYYY`, `MM` and `DD` for the date.
+    DailyNote {
+        vault: String,
+        #[serde(default = "default_daily_note_file")]
+        file: String,
+        #[serde(default = "default_daily_note_heading")]
+        heading: String,
+    },
+    /// POST `{ "text", "timestamp" }` as JSON to an http(s) URL, or the
+    /// rendered template as the body when there is one.
+    Webhook {
+        url: String,
+        /// Request body; empty for the default JSON.
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        template: String,
+    },
+    /// Publish `{ "text", "timestamp" }` to the topic of an
+    /// `mqtt://[user@]host[:port]/topic` URL, and the app state to
+    /// `topic/state`. The password lives in the credential store.
+    Mqtt { url: String },
+}
+
+fn default_daily_note_file() -> String {
+    daily_note::DEFAULT_FILE.to_string()
+}
+
+fn default_daily_note_heading() -> String {
+    daily_note::DEFAULT_HEADING.to_string()
+}
+
+impl OutputSink {
+    /// A daily note sink for `vault` with the default file name and heading.
+    pub fn daily_note(vault: impl Into<String>) -> Self {
+        OutputSink::DailyNote {
+            vault: vault.into(),
+            file: default_daily_note_file(),
+            heading: default_daily_note_heading(),
+        }
+    }
+
+    /// Whether the sink's template names the focused app, which callers
+    /// only look up when needed.
+    pub fn uses_app(&self) -> bool {
+        match self {
+            OutputSink::File { template, .. }
+            | OutputSink::Pipe { template, .. }
+            | OutputSink::Webhook { template, .. } => template.contains("{app}"),
+            _ => false,
+        }
+    }
+
+    /// Short human-readable label for logs and error messages.
+    pub fn label(&self) -> String {
+        match self {
+            OutputSink::Clipboard => "clipboard".to_string(),
+            OutputSink::Paste => "paste".to_string(),
+            OutputSink::File { path, .. } => format!("file {path}"),
+            OutputSink::Pipe { path, .. } => format!("pipe {path}"),
+            OutputSink::DailyNote { vault, .. } => format!("daily note in {vault}"),
+            OutputSink::Webhook { url, .. } => format!("webhook {url}"),
+            OutputSink::Mqtt { url } => format!("mqtt {url}"),
+        }
+    }
+}
+
+/// The `config set output_sinks` syntax: `clipboard`, `paste`, `file:PATH`,
+/// `pipe:PATH`, `daily-note:VAULT[;file=NAME][;heading=HEADING]` (options
+/// left at their default are omitted), `webhook:URL`, and MQTT URLs as they
+/// are. File, pipe and webhook sinks end in `;template=TEMPLATE` when they
+/// have one, with `\n`, `\t`, `\,` and `\\` escapes.
+impl fmt::Display for OutputSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let template = |f: &mut fmt::Formatter<'_>, template: &str| {
+            if template.is_empty() {
+                Ok(())
+            } else {
+                write!(f, ";template={}", escape_template(template))
+            }
+        };
+        match self {
+            OutputSink::Clipboard => write!(f, "clipboard"),
+            OutputSink::Paste => write!(f, "paste"),
+            OutputSink::File { path, template: t } => {
+                write!(f, "file:{path}")?;
+                template(f, t)
+            }
+            OutputSink::Pipe { path, template: t } => {
+                write!(f, "pipe:{path}")?;
+                template(f, t)
+            }
+            OutputSink::DailyNote {
+                vault,
+                file,
+                heading,
+            } => {
+                write!(f, "daily-note:{vault}")?;
+                if file != daily_note::DEFAULT_FILE {
+                    write!(f, ";file={file}")?;
+                }
+                if heading != daily_note::DEFAULT_HEADING {
+                    write!(f, ";heading={heading}")?;
+                }
+                Ok(())
+            }
+            OutputSink::Webhook { url, template: t } => {
+                write!(f, "webhook:{url}")?;
+                template(f, t)
+            }
+            OutputSink::Mqtt { url } => write!(f, "{url}"),
+        }
+    }
+}
+
+impl FromStr for OutputSink {
+    type Err = DictationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "clipboard" {
+            return Ok(OutputSink::Clipboard);
+        }
+        if s == "paste" {
+            return Ok(OutputSink::Paste);
+        }
+        if let Some(spec) = s.strip_prefix("file:") {
+            let (path, template) = split_template(spec);
+            if path.is_empty() {
+                return Err(DictationError::SettingsError(
+                    "file sink needs a path, e.g. file:~/notes/dictation.md".to_string(),
+                ));
+            }
+            return Ok(OutputSink::File {
+                path: path.to_string(),
+                template,
+            });
+        }
+        if let Some(spec) = s.strip_prefix("pipe:") {
+            let (path, template) = split_template(spec);
+            if path.is_empty() {
+                return Err(DictationError::SettingsError(
+                    "pipe sink needs a path, e.g. pipe:/tmp/sagascript.fifo".to_string(),
+                ));
+            }
+            return Ok(OutputSink::Pipe {
+                path: path.to_string(),
+                template,
+            });
+        }
+        if let Some(spec) = s.strip_prefix("daily-note:") {
+            return parse_daily_note(spec);
+        }
+        if let Some(spec) = s.strip_prefix("webhook:") {
+            let (url, template) = split_template(spec);
+            if !(url.starts_with("https://") || url.starts_with("http://")) {
+                return Err(DictationError::SettingsError(format!(
+                    "webhook sink needs an http(s) URL, got '{url}'"
+                )));
+            }
+            return Ok(OutputSink::Webhook {
+                url: url.to_string(),
+                template,
+            });
+        }
+        if s.starts_with("mqtt://") || s.starts_with("mqtts://") {
+            mqtt::Broker::parse(s)?;
+            return Ok(OutputSink::Mqtt { url: s.to_string() });
+        }
+        Err(DictationError::SettingsError(format!(
+            "unknown output sink '{s}' (expected clipboard, paste, file:PATH, pipe:PATH, \
+             daily-note:VAULT, webhook:URL or mqtt://HOST/TOPIC)"
+        )))
+    }
+}
+
+/// `VAULT[;file=NAME][;heading=HEADING]`, the part after `daily-note:`.
+fn parse_daily_note(spec: &str) -> Result<OutputSink, DictationError> {
+    let invalid = |why: String| {
+        DictationError::SettingsError(format!(
+            "daily-note sink {why}, e.g. daily-note:~/Obsidian/Vault;file=Daily/YYYY-MM-DD.md"
+        ))
+    };
+    if spec.contains(',') {
+        return Err(invalid("can't contain commas".to_string()));
+    }
+    let mut parts = spec.split(';');
+    let vault = parts.next().unwrap_or("").trim();
+    if vault.is_empty() {
+        return Err(invalid("needs a vault folder".to_string()));
+    }
+    let mut file = default_daily_note_file();
+    let mut heading = default_daily_note_heading();
+    for option in parts {
+        match option.split_once('=') {
+            Some(("file", value)) if !value.trim().is_empty() => file = value.trim().to_string(),
+            Some(("heading", value)) => heading = value.trim().to_string(),
+            _ => return Err(invalid(format!("has an unknown option '{option}'"))),
+        }
+    }
+    Ok(OutputSink::DailyNote {
+        vault: vault.to_string(),
+        file,
+        heading,
+    })
+}
+
+/// `TARGET[;template=TEMPLATE]`, the part after `file:`, `pipe:` or
+/// `webhook:`. The template runs to the end, so it may contain `;`.
+fn split_template(spec: &str) -> (&str, String) {
+    match spec.split_once(";template=") {
+        Some((target, template)) => (target.trim(), unescape_template(template)),
+        None => (spec.trim(), String::new()),
+    }
+}
+
+/// `template` with the characters a sink list can't hold escaped.
+fn escape_template(template: &str) -> String {
+    let mut out = String::with_capacity(template.len());
+    for c in template.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            ',' => out.push_str("\\,"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// The inverse of [`escape_template`]. An unknown escape is kept as written.
+fn unescape_template(template: &str) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(c @ (',' | '\\')) => out.push(c),
+            Some(c) => {
+                out.push('\\');
+                out.push(c);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Parse a comma-separated sink list. An empty string is an empty table.
+/// Escaped commas (`\,`, in templates) don't separate sinks.
+pub fn parse_sinks(value: &str) -> Result<Vec<OutputSink>, DictationError> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ',' => {
+                parts.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+        .into_iter()
+        .filter(|part| !part.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Format a sink table in the syntax accepted by [`parse_sinks`].
+pub fn format_sinks(sinks: &[OutputSink]) -> String {
+    sinks
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Whether the table asks for the clipboard, which callers deliver themselves.
+pub fn wants_clipboard(sinks: &[OutputSink]) -> bool {
+    sinks.contains(&OutputSink::Clipboard)
+}
+
+/// Whether the table asks for a paste, which only the GUI can deliver.
+pub fn wants_paste(sinks: &[OutputSink]) -> bool {
+    sinks.contains(&OutputSink::Paste)
+}
+
+/// A finished dictation, with what sink templates can say about it.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub text: String,
+    pub language: Language,
+    pub model: WhisperModel,
+    /// Name of the app that had focus when the dictation finished; `None`
+    /// from the CLI, or when the platform can't tell.
+    pub app: Option<String>,
+}
+
+impl Entry {
+    pub fn new(text: impl Into<String>, language: Language, model: WhisperModel) -> Self {
+        Self {
+            text: text.into(),
+            language,
+            model,
+            app: None,
+        }
+    }
+
+    /// The value of template variable `name`; `None` when there is no such
+    /// variable.
+    fn variable(&self, name: &str, now: &DateTime<Local>) -> Option<String> {
+        Some(match name {
+            "text" => self.text.trim().to_string(),
+            "timestamp" => now.to_rfc3339_opts(SecondsFormat::Secs, false),
+            "language" => self.language.whisper_code().unwrap_or("auto").to_string(),
+            "model" => serde_json::to_value(self.model)
+                .ok()
+                .and_then(|id| id.as_str().map(str::to_string))
+                .unwrap_or_default(),
+            "app" => self.app.clone().unwrap_or_default(),
+            _ => return None,
+        })
+    }
+}
+
+/// `template` with `{text}`, `{timestamp}` (local time, RFC 3339),
+/// `{language}` (`sv`, or `auto`), `{model}` (the model ID) and `{app}` (the
+/// focused app, empty when unknown) filled in from `entry`. Other braces are
+/// kept as written, so a JSON template needs no escaping. With `json`, the
+/// values are escaped to sit inside a JSON string.
+pub fn render_template(template: &str, entry: &Entry, now: &DateTime<Local>, json: bool) -> String {
+    let mut out = String::with_capacity(template.len() + entry.text.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let found = after
+            .find('}')
+            .and_then(|close| Some((close, entry.variable(&after[..close], now)?)));
+        let Some((close, value)) = found else {
+            out.push('{');
+            rest = after;
+            continue;
+        };
+        if json {
+            let quoted = serde_json::Value::String(value).to_string();
+            out.push_str(&quoted[1..quoted.len() - 1]);
+        } else {
+            out.push_str(&value);
+        }
+        rest = &after[close + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .map(|home| home.join(rest))
+            .unwrap_or_else(|| PathBuf::from(path)),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Append `text` and a newline to `path`, creating the file and its parent
+/// directories if needed.
+pub fn append_to_file(path: &Path, text: &str) -> Result<(), DictationError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            DictationError::OutputError(format!("Cannot create {}: {e}", parent.display()))
+        })?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| DictationError::OutputError(format!("Cannot open {}: {e}", path.display())))?;
+    writeln!(file, "{}", text.trim())
+        .map_err(|e| DictationError::OutputError(format!("Cannot write {}: {e}", path.display())))
+}
+
+/// Write `text` and a newline to the named pipe at `path`. Returns `false`
+/// without writing when no reader has the pipe open, rather than blocking
+/// the dictation until one turns up.
+pub fn write_to_pipe(path: &Path, text: &str) -> Result<bool, DictationError> {
+    let mut pipe = match open_pipe(path) {
+        Ok(pipe) => pipe,
+        Err(e) if is_no_reader(&e) => return Ok(false),
+        Err(e) => {
+            return Err(DictationError::OutputError(format!(
+                "Cannot open pipe {}: {e}",
+                path.display()
+            )))
+        }
+    };
+    writeln!(pipe, "{}", text.trim())
+        .map(|()| true)
+        .map_err(|e| DictationError::OutputError(format!("Cannot write {}: {e}", path.display())))
+}
+
+/// Open a FIFO for writing without waiting for a reader, then switch it back
+/// to blocking writes so a long transcript isn't cut off by a full pipe.
+#[cfg(unix)]
+fn open_pipe(path: &Path) -> std::io::Result<std::fs::File> {
+    use std::os::unix::fs::{FileTypeExt, OpenOptionsExt};
+    use std::os::unix::io::AsRawFd;
+
+    if !std::fs::metadata(path)?.file_type().is_fifo() {
+        return Err(std::io::Error::other(
+            "not a named pipe (create one with mkfifo)",
+        ));
+    }
+    let pipe = OpenOptions::new()
+        .write(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(path)?;
+    // SAFETY: `pipe` owns a valid descriptor for the duration of both calls.
+    unsafe {
+        let flags = libc::fcntl(pipe.as_raw_fd(), libc::F_GETFL);
+        if flags == -1
+            || libc::fcntl(pipe.as_raw_fd(), libc::F_SETFL, flags & !libc::O_NONBLOCK) == -1
+        {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(pipe)
+}
+
+/// Windows named pipes don't block on open: a missing server is an error.
+#[cfg(not(unix))]
+fn open_pipe(path: &Path) -> std::io::Result<std::fs::File> {
+    OpenOptions::new().write(true).open(path)
+}
+
+/// Whether opening a pipe failed only because nothing is reading it.
+fn is_no_reader(e: &std::io::Error) -> bool {
+    #[cfg(unix)]
+    {
+        e.raw_os_error() == Some(libc::ENXIO)
+    }
+    #[cfg(not(unix))]
+    {
+        e.kind() == std::io::ErrorKind::NotFound
+    }
+}
+
+/// The JSON webhooks and MQTT sinks are sent.
+fn result_payload(text: &str) -> serde_json::Value {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    serde_json::json!({ "text": text, "timestamp": timestamp })
+}
+
+/// The body a webhook is sent for `entry`, and its content type: the
+/// default JSON, or the rendered template. A template that starts like JSON
+/// is sent as JSON, with the values escaped to fit.
+fn webhook_body(template: &str, entry: &Entry, now: &DateTime<Local>) -> (String, &'static str) {
+    if template.is_empty() {
+        return (
+            result_payload(entry.text.trim()).to_string(),
+            "application/json",
+        );
+    }
+    if template.trim_start().starts_with(['{', '[']) {
+        (
+            render_template(template, entry, now, true),
+            "application/json",
+        )
+    } else {
+        (
+            render_template(template, entry, now, false),
+            "text/plain; charset=utf-8",
+        )
+    }
+}
+
+/// POST `body` to a webhook.
+pub async fn post_webhook(
+    url: &str,
+    body: String,
+    content_type: &str,
+) -> Result<(), DictationError> {
+    let client = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .map_err(|e| DictationError::OutputError(format!("HTTP client error: {e}")))?;
+    let response = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, content_type)
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| DictationError::OutputError(format!("Webhook {url} failed: {e}")))?;
+    if !response.status().is_success() {
+        return Err(DictationError::OutputError(format!(
+            "Webhook {url} returned {}",
+            response.status()
+        )));
+    }
+    Ok(())
+}
+
+/// Publish the text to an MQTT sink's topic as JSON.
+pub async fn publish_mqtt(url: &str, text: &str) -> Result<(), DictationError> {
+    let broker = mqtt::Broker::parse(url)?;
+    let password = mqtt_password(&broker)?;
+    let payload = result_payload(text).to_string();
+    mqtt::publish(
+        &broker,
+        password.as_deref(),
+        &broker.topic,
+        payload.as_bytes(),
+        false,
+    )
+    .await
+}
+
+/// The stored broker password, if the sink logs in with a username.
+fn mqtt_password(broker: &mqtt::Broker) -> Result<Option<String>, DictationError> {
+    if broker.username.is_none() {
+        return Ok(None);
+    }
+    credentials::default_store()?.get(mqtt::PASSWORD_SECRET)
+}
+
+/// Publish the app state (`recording`, `transcribing`, `idle`) to
+/// `topic/state` of every MQTT sink, retained so a subscriber sees the
+/// current state straight away. Failures are returned as by [`deliver`].
+pub async fn publish_state(sinks: &[OutputSink], state: &str) -> Vec<(OutputSink, DictationError)> {
+    let mut failures = Vec::new();
+    for sink in sinks {
+        let OutputSink::Mqtt { url } = sink else {
+            continue;
+        };
+        let result = async {
+            let broker = mqtt::Broker::parse(url)?;
+            let password = mqtt_password(&broker)?;
+            let topic = format!("{}/state", broker.topic);
+            mqtt::publish(&broker, password.as_deref(), &topic, state.as_bytes(), true).await
+        };
+        if let Err(e) = result.await {
+            failures.push((sink.clone(), e));
+        }
+    }
+    failures
+}
+
+/// Deliver `entry` to every file, pipe, daily note, webhook and MQTT sink.
+/// Clipboard and paste entries are skipped (see the module docs). Every sink is attempted; failures are
+/// returned together so one bad webhook doesn't stop the file append.
+pub async fn deliver(sinks: &[OutputSink], entry: &Entry) -> Vec<(OutputSink, DictationError)> {
+    let mut failures = Vec::new();
+    let text = entry.text.trim();
+    if text.is_empty() {
+        return failures;
+    }
+    let now = Local::now();
+    let line = |template: &str| {
+        if template.is_empty() {
+            text.to_string()
+        } else {
+            render_template(template, entry, &now, false)
+        }
+    };
+    for sink in sinks {
+        let result = match sink {
+            OutputSink::Clipboard | OutputSink::Paste => continue,
+            OutputSink::File { path, template } => {
+                append_to_file(&expand_home(path), &line(template))
+            }
+            OutputSink::Pipe { path, template } => {
+                match write_to_pipe(&expand_home(path), &line(template)) {
+                    Ok(true) => Ok(()),
+                    Ok(false) => {
+                        info!("Nothing reading {}, skipped", sink.label());
+                        continue;
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            OutputSink::DailyNote {
+                vault,
+                file,
+                heading,
+            } => daily_note::append(&expand_home(vault), file, heading, text),
+            OutputSink::Webhook { url, template } => {
+                let (body, content_type) = webhook_body(template, entry, &now);
+                post_webhook(url, body, content_type).await
+            }
+            OutputSink::Mqtt { url } => publish_mqtt(url, text).await,
+        };
+        match result {
+            Ok(()) => info!("Output delivered to {}", sink.label()),
+            Err(e) => failures.push((sink.clone(), e)),
+        }
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn parse_sink_list() {
+        let sinks = parse_sinks(
+            "clipboard, file:~/notes/d.md ,pipe:/tmp/s.fifo,webhook:https://example.com/hook",
+        )
+        .unwrap();
+        assert_eq!(
+            sinks,
+            vec![
+                OutputSink::Clipboard,
+                OutputSink::File {
+                    path: "~/notes/d.md".to_string(),
+                    template: String::new(),
+                },
+                OutputSink::Pipe {
+                    path: "/tmp/s.fifo".to_string(),
+                    template: String::new(),
+                },
+                OutputSink::Webhook {
+                    url: "https://example.com/hook".to_string(),
+                    template: String::new(),
+                },
+            ]
+        );
+        assert_eq!(parse_sinks("").unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn format_round_trips_through_parse() {
+        let sinks = vec![
+            OutputSink::Paste,
+            OutputSink::File {
+                path: "/tmp/out.txt".to_string(),
+                template: String::new(),
+            },
+            OutputSink::Clipboard,
+        ];
+        assert_eq!(parse_sinks(&format_sinks(&sinks)).unwrap(), sinks);
+    }
+
+    #[test]
+    fn mqtt_sinks_are_written_as_their_url() {
+        let sinks = parse_sinks("clipboard,mqtt://sensor@ha.local:1883/sagascript").unwrap();
+        assert_eq!(
+            sinks[1],
+            OutputSink::Mqtt {
+                url: "mqtt://sensor@ha.local:1883/sagascript".to_string()
+            }
+        );
+        assert_eq!(parse_sinks(&format_sinks(&sinks)).unwrap(), sinks);
+    }
+
+    #[test]
+    fn daily_note_options_are_written_only_when_set() {
+        assert_eq!(
+            "daily-note:~/Vault".parse::<OutputSink>().unwrap(),
+            OutputSink::daily_note("~/Vault")
+        );
+        let sink: OutputSink = "daily-note:~/Vault;file=Daily/YYYY-MM-DD.md;heading=# Voice"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            sink,
+            OutputSink::DailyNote {
+                vault: "~/Vault".to_string(),
+                file: "Daily/YYYY-MM-DD.md".to_string(),
+                heading: "# Voice".to_string(),
+            }
+        );
+        assert_eq!(
+            parse_sinks(&format_sinks(&[sink.clone()])).unwrap(),
+            vec![sink]
+        );
+        assert_eq!(
+            OutputSink::daily_note("~/Vault").to_string(),
+            "daily-note:~/Vault"
+        );
+        let back: OutputSink =
+            serde_json::from_str(r#"{"type":"daily_note","vault":"/v"}"#).unwrap();
+        assert_eq!(back, OutputSink::daily_note("/v"));
+    }
+
+    #[test]
+    fn parse_rejects_bad_sinks() {
+        assert!(parse_sinks("paste").is_err());
+        assert!(parse_sinks("file:").is_err());
+        assert!(parse_sinks("pipe: ").is_err());
+        assert!(parse_sinks("webhook:ftp://example.com").is_err());
+        assert!(parse_sinks("mqtts://broker.local/voice").is_err());
+        assert!(parse_sinks("daily-note:;file=x.md").is_err());
+        assert!(parse_sinks("daily-note:~/Vault;folder=Daily").is_err());
+    }
+
+    #[test]
+    fn templates_round_trip_with_escapes() {
+        let sinks = parse_sinks(
+            r#"file:~/log.md;template=- {timestamp} {text}\n,webhook:https://chat.example/hook;template={"content": "{text}"\, "username": "{app}"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            sinks,
+            vec![
+                OutputSink::File {
+                    path: "~/log.md".to_string(),
+                    template: "- {timestamp} {text}\n".to_string(),
+                },
+                OutputSink::Webhook {
+                    url: "https://chat.example/hook".to_string(),
+                    template: r#"{"content": "{text}", "username": "{app}"}"#.to_string(),
+                },
+            ]
+        );
+        assert_eq!(parse_sinks(&format_sinks(&sinks)).unwrap(), sinks);
+        let back: OutputSink =
+            serde_json::from_str(r#"{"type":"pipe","path":"/tmp/s.fifo"}"#).unwrap();
+        assert_eq!(
+            back,
+            OutputSink::Pipe {
+                path: "/tmp/s.fifo".to_string(),
+                template: String::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn templates_fill_in_known_variables() {
+        let now = Local.with_ymd_and_hms(2026, 10, 16, 14, 5, 9).unwrap();
+        let mut entry = Entry::new(
+            " Hej \"Anna\" ",
+            Language::Swedish,
+            WhisperModel::KbWhisperSmall,
+        );
+        entry.app = Some("Slack".to_string());
+        assert_eq!(
+            render_template(
+                "[{language}/{model}] {app}: {text} {unknown}",
+                &entry,
+                &now,
+                false
+            ),
+            "[sv/kb-whisper-small] Slack: Hej \"Anna\" {unknown}"
+        );
+        assert!(
+            render_template("{timestamp}", &entry, &now, false).starts_with("2026-10-16T14:05:09")
+        );
+        let (body, content_type) = webhook_body(r#"{"content": "{text}"}"#, &entry, &now);
+        assert_eq!(body, r#"{"content": "Hej \"Anna\""}"#);
+        assert_eq!(content_type, "application/json");
+        assert_eq!(
+            webhook_body("{app}: {text}", &entry, &now).1,
+            "text/plain; charset=utf-8"
+        );
+    }
+
+    #[test]
+    fn sinks_serialize_tagged() {
+        let json = serde_json::to_string(&OutputSink::File {
+            path: "a.txt".to_string(),
+            template: String::new(),
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"type":"file","path":"a.txt"}"#);
+        let back: OutputSink = serde_json::from_str(r#"{"type":"clipboard"}"#).unwrap();
+        assert_eq!(back, OutputSink::Clipboard);
+    }
+
+    #[test]
+    fn append_to_file_adds_lines() {
+        let dir = std::env::temp_dir().join(format!("sagascript-output-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("nested").join("out.txt");
+        append_to_file(&path, "first ").unwrap();
+        append_to_file(&path, "second").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first\nsecond\n");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pipe_is_skipped_without_a_reader_and_written_with_one() {
+        use std::io::Read;
+
+        let dir = std::env::temp_dir().join(format!("sagascript-output-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("out.fifo");
+        let c_path = std::ffi::CString::new(path.to_str().unwrap()).unwrap();
+        // SAFETY: `c_path` is a valid NUL-terminated path.
+        assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+
+        assert!(!write_to_pipe(&path, "nobody listening").unwrap());
+
+        let reader = {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                let mut out = String::new();
+                std::fs::File::open(path)
+                    .unwrap()
+                    .read_to_string(&mut out)
+                    .unwrap();
+                out
+            })
+        };
+        // The reader's open blocks until a writer appears; retry until it is in.
+        while !write_to_pipe(&path, " hello ").unwrap() {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert_eq!(reader.join().unwrap(), "hello\n");
+
+        // A regular file isn't mistaken for a pipe.
+        let file = dir.join("plain.txt");
+        std::fs::write(&file, "").unwrap();
+        assert!(write_to_pipe(&file, "x").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn deliver_skips_clipboard_and_reports_failures() {
+        let dir = std::env::temp_dir().join(format!("sagascript-output-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("out.txt");
+        let sinks = vec![
+            OutputSink::Clipboard,
+            OutputSink::File {
+                path: path.to_string_lossy().into_owned(),
+                template: String::new(),
+            },
+            // A directory can't be opened for appending.
+            OutputSink::File {
+                path: dir.to_string_lossy().into_owned(),
+                template: String::new(),
+            },
+        ];
+        let entry = Entry::new("hello", Language::English, WhisperModel::Base);
+        let failures = deliver(&sinks, &entry).await;
+        assert_eq!(failures.len(), 1);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "hello\n");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
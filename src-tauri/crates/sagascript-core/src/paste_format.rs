@@ -0,0 +1,238 @@
+//! Paste formatting per target app.
+//!
+//! Dictated text suits a document but not every paste target: a newline
+//! pasted into a terminal runs whatever came before it, and a sentence-final
+//! period ends up as an argument. Before auto-paste, the desktop app looks up
+//! the frontmost app (bundle ID on macOS, executable on Windows, WM_CLASS on
+//! Linux), maps it to an [`AppCategory`] and reformats the text for it.
+//!
+//! The mapping is the user's rules (`Settings::paste_app_rules`, edited with
+//! `sagascript config paste-rule`) first, then a built-in table of common
+//! terminals and mail clients. A rule can map a built-in app to
+//! [`AppCategory::Other`] to turn its formatting off.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::DictationError;
+
+/// How text pasted into an app is formatted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AppCategory {
+    /// One line, no trailing period.
+    Terminal,
+    /// Paragraph breaks kept, extra blank lines collapsed.
+    Email,
+    /// Pasted as transcribed.
+    Other,
+}
+
+impl fmt::Display for AppCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AppCategory::Terminal => "terminal",
+            AppCategory::Email => "email",
+            AppCategory::Other => "other",
+        })
+    }
+}
+
+impl FromStr for AppCategory {
+    type Err = DictationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "terminal" => Ok(AppCategory::Terminal),
+            "email" => Ok(AppCategory::Email),
+            "other" => Ok(AppCategory::Other),
+            _ => Err(DictationError::SettingsError(format!(
+                "unknown app category '{s}' (expected terminal, email or other)"
+            ))),
+        }
+    }
+}
+
+/// The app that will receive a paste.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrontmostApp {
+    /// Bundle ID (macOS), executable name (Windows) or WM_CLASS (Linux).
+    pub id: String,
+    /// Display name, where the platform has one.
+    pub name: String,
+}
+
+/// Apps formatted without a user rule, by ID (compared case-insensitively).
+pub const BUILT_IN_APPS: &[(&str, AppCategory)] = &[
+    // macOS
+    ("com.apple.Terminal", AppCategory::Terminal),
+    ("com.googlecode.iterm2", AppCategory::Terminal),
+    ("dev.warp.Warp-Stable", AppCategory::Terminal),
+    ("net.kovidgoyal.kitty", AppCategory::Terminal),
+    ("com.mitchellh.ghostty", AppCategory::Terminal),
+    ("org.alacritty", AppCategory::Terminal),
+    ("com.github.wez.wezterm", AppCategory::Terminal),
+    ("com.apple.mail", AppCategory::Email),
+    ("com.microsoft.Outlook", AppCategory::Email),
+    ("com.readdle.smartemail-Mac", AppCategory::Email),
+    ("org.mozilla.thunderbird", AppCategory::Email),
+    // Windows
+    ("WindowsTerminal.exe", AppCategory::Terminal),
+    ("cmd.exe", AppCategory::Terminal),
+    ("powershell.exe", AppCategory::Terminal),
+    ("pwsh.exe", AppCategory::Terminal),
+    ("alacritty.exe", AppCategory::Terminal),
+    ("wezterm-gui.exe", AppCategory::Terminal),
+    ("OUTLOOK.EXE", AppCategory::Email),
+    ("thunderbird.exe", AppCategory::Email),
+    // Linux (WM_CLASS)
+    ("gnome-terminal-server", AppCategory::Terminal),
+    ("konsole", AppCategory::Terminal),
+    ("xterm", AppCategory::Terminal),
+    ("kitty", AppCategory::Terminal),
+    ("Alacritty", AppCategory::Terminal),
+    ("tilix", AppCategory::Terminal),
+    ("terminator", AppCategory::Terminal),
+    ("org.wezfurlong.wezterm", AppCategory::Terminal),
+    ("thunderbird", AppCategory::Email),
+    ("evolution", AppCategory::Email),
+    ("geary", AppCategory::Email),
+];
+
+/// The category for `app`: a user rule matching its ID or name, else the
+/// built-in table, else [`AppCategory::Other`].
+pub fn category_for(app: &FrontmostApp, rules: &BTreeMap<String, AppCategory>) -> AppCategory {
+    let matches =
+        |key: &str| key.eq_ignore_ascii_case(&app.id) || key.eq_ignore_ascii_case(&app.name);
+    rules
+        .iter()
+        .find(|(key, _)| matches(key))
+        .map(|(_, category)| *category)
+        .or_else(|| {
+            BUILT_IN_APPS
+                .iter()
+                .find(|(id, _)| id.eq_ignore_ascii_case(&app.id))
+                .map(|(_, category)| *category)
+        })
+        .unwrap_or(AppCategory::Other)
+}
+
+/// Format `text` for pasting into an app of `category`.
+pub fn format_for(text: &str, category: AppCategory) -> String {
+    match category {
+        AppCategory::Terminal => {
+            let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            line.trim_end_matches('.').trim_end().to_string()
+        }
+        AppCategory::Email => {
+            let mut out = String::with_capacity(text.len());
+            let mut blank_lines = 0;
+            for line in text.trim().lines() {
+                let line = line.trim_end();
+                if line.is_empty() {
+                    blank_lines += 1;
+                    continue;
+                }
+                if !out.is_empty() {
+                    out.push_str(if blank_lines > 0 { "\n\n" } else { "\n" });
+                }
+                blank_lines = 0;
+                out.push_str(line);
+            }
+            out
+        }
+        AppCategory::Other => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(id: &str, name: &str) -> FrontmostApp {
+        FrontmostApp {
+            id: id.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn terminal_text_is_one_line_without_trailing_period() {
+        assert_eq!(
+            format_for("git status\nand then   push.", AppCategory::Terminal),
+            "git status and then push"
+        );
+        assert_eq!(format_for("ls -la...\r\n", AppCategory::Terminal), "ls -la");
+        assert_eq!(
+            format_for("v1.2 is out", AppCategory::Terminal),
+            "v1.2 is out"
+        );
+    }
+
+    #[test]
+    fn email_keeps_paragraph_breaks() {
+        assert_eq!(
+            format_for(
+                "Hi Anna,\r\n\r\n\r\nThanks for the notes.\nBest  \n",
+                AppCategory::Email
+            ),
+            "Hi Anna,\n\nThanks for the notes.\nBest"
+        );
+    }
+
+    #[test]
+    fn other_is_unchanged() {
+        let text = "Line one.\n\n\nLine two. ";
+        assert_eq!(format_for(text, AppCategory::Other), text);
+    }
+
+    #[test]
+    fn built_in_apps_match_case_insensitively() {
+        let rules = BTreeMap::new();
+        assert_eq!(
+            category_for(&app("com.apple.terminal", "Terminal"), &rules),
+            AppCategory::Terminal
+        );
+        assert_eq!(
+            category_for(&app("OUTLOOK.EXE", "OUTLOOK"), &rules),
+            AppCategory::Email
+        );
+        assert_eq!(
+            category_for(&app("com.apple.TextEdit", "TextEdit"), &rules),
+            AppCategory::Other
+        );
+    }
+
+    #[test]
+    fn user_rules_win_and_match_id_or_name() {
+        let mut rules = BTreeMap::new();
+        rules.insert("com.apple.Terminal".to_string(), AppCategory::Other);
+        rules.insert("Hyper".to_string(), AppCategory::Terminal);
+        assert_eq!(
+            category_for(&app("com.apple.Terminal", "Terminal"), &rules),
+            AppCategory::Other
+        );
+        assert_eq!(
+            category_for(&app("co.zeit.hyper", "Hyper"), &rules),
+            AppCategory::Terminal
+        );
+    }
+
+    #[test]
+    fn category_round_trips_through_strings() {
+        for category in [
+            AppCategory::Terminal,
+            AppCategory::Email,
+            AppCategory::Other,
+        ] {
+            assert_eq!(
+                category.to_string().parse::<AppCategory>().unwrap(),
+                category
+            );
+        }
+        assert!("shell".parse::<AppCategory>().is_err());
+    }
+}
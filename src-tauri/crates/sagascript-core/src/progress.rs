@@ -0,0 +1,84 @@
+//! Stage-tagged progress for file transcription.
+//!
+//! A file goes through several phases before text comes out, and on a long
+//! video decoding alone can take a minute. Reporting which phase is running,
+//! not just one percentage, lets the UI say what is actually happening.
+
+use serde::Serialize;
+
+/// Phase of a file transcription, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    /// Demuxing, decoding and resampling the file to 16 kHz mono.
+    Decoding,
+    /// Loading the whisper model into memory (skipped when already warm).
+    LoadingModel,
+    /// Running inference.
+    Transcribing,
+    /// Normalizing, diarization merge and output of the finished text.
+    PostProcessing,
+}
+
+impl Stage {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Stage::Decoding => "Decoding",
+            Stage::LoadingModel => "Loading model",
+            Stage::Transcribing => "Transcribing",
+            Stage::PostProcessing => "Post-processing",
+        }
+    }
+}
+
+/// One progress report: the running stage and how far into it (0–100).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct StageProgress {
+    pub stage: Stage,
+    pub pct: u32,
+}
+
+impl StageProgress {
+    pub fn new(stage: Stage, pct: i64) -> Self {
+        Self {
+            stage,
+            pct: pct.clamp(0, 100) as u32,
+        }
+    }
+}
+
+/// Percentage of `done` out of `total`, or `None` when the total is unknown.
+pub fn percent(done: u64, total: Option<u64>) -> Option<u32> {
+    match total {
+        Some(total) if total > 0 => Some((done.min(total) * 100 / total) as u32),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_stage_and_pct() {
+        let json = serde_json::to_value(StageProgress::new(Stage::LoadingModel, 40)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "stage": "loading_model", "pct": 40 })
+        );
+    }
+
+    #[test]
+    fn pct_is_clamped() {
+        assert_eq!(StageProgress::new(Stage::Transcribing, 130).pct, 100);
+        assert_eq!(StageProgress::new(Stage::Transcribing, -5).pct, 0);
+    }
+
+    #[test]
+    fn percent_handles_unknown_and_overrun_totals() {
+        assert_eq!(percent(50, Some(200)), Some(25));
+        assert_eq!(percent(300, Some(200)), Some(100));
+        assert_eq!(percent(5, Some(0)), None);
+        assert_eq!(percent(5, None), None);
+    }
+}
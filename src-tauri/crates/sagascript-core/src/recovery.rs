@@ -0,0 +1,244 @@
+//! Crash recovery for hotkey dictations.
+//!
+//! While a dictation is recording, the app appends the audio captured so
+//! far to a 16 kHz WAV every few seconds, and the rest once it starts
+//! transcribing, next to a small JSON record in `recovery/` under the app
+//! data directory. Both are removed again once the dictation ends normally. If the app crashes in between, the files
+//! survive in `recovery/live`; the next launch moves them to
+//! `recovery/crashed` (so its own dictations can't overwrite them) and
+//! offers to transcribe the audio again.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::decoder;
+use crate::audio::resample::TARGET_SAMPLE_RATE;
+use crate::audio::wav::encode_wav;
+use crate::error::DictationError;
+use crate::settings::{Language, WhisperModel};
+
+const SESSION_FILENAME: &str = "session.json";
+const AUDIO_FILENAME: &str = "audio.wav";
+
+/// Size of the header `encode_wav` writes before the samples.
+const WAV_HEADER_LEN: u64 = 44;
+
+/// How far a dictation got before the app went away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecoveryStage {
+    /// Still recording; the audio runs up to the last snapshot.
+    Recording,
+    /// Recording had finished and transcription was running.
+    Transcribing,
+}
+
+/// The dictation left behind by a crash.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecoverableSession {
+    pub stage: RecoveryStage,
+    /// When the dictation started, in seconds since the Unix epoch.
+    pub started_at: u64,
+    pub language: Language,
+    pub model: WhisperModel,
+    /// Length of the saved audio.
+    pub audio_secs: f32,
+}
+
+impl RecoverableSession {
+    /// A session for a dictation that started just now.
+    pub fn new(stage: RecoveryStage, language: Language, model: WhisperModel) -> Self {
+        Self {
+            stage,
+            started_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            language,
+            model,
+            audio_secs: 0.0,
+        }
+    }
+}
+
+/// Where the running app snapshots its current dictation.
+pub fn live_dir() -> PathBuf {
+    crate::settings::store::app_data_dir().join("recovery").join("live")
+}
+
+/// Where a session left behind by a crash waits to be retried or discarded.
+pub fn crashed_dir() -> PathBuf {
+    crate::settings::store::app_data_dir().join("recovery").join("crashed")
+}
+
+/// Path of the saved audio in `dir`.
+pub fn audio_path(dir: &Path) -> PathBuf {
+    dir.join(AUDIO_FILENAME)
+}
+
+/// At launch: move a session the previous run left in `live` to `crashed`,
+/// replacing an older one there. Returns the moved session, if any.
+pub fn take_over(live: &Path, crashed: &Path) -> io::Result<Option<RecoverableSession>> {
+    let Some(session) = load(live) else {
+        clear(live)?;
+        return Ok(None);
+    };
+    clear(crashed)?;
+    fs::create_dir_all(crashed)?;
+    fs::rename(live.join(AUDIO_FILENAME), crashed.join(AUDIO_FILENAME))?;
+    fs::rename(live.join(SESSION_FILENAME), crashed.join(SESSION_FILENAME))?;
+    Ok(Some(session))
+}
+
+/// Begin saving a new dictation in `dir`, replacing whatever was there.
+pub fn start(dir: &Path, session: &RecoverableSession) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    clear(dir)?;
+    write_atomic(&dir.join(AUDIO_FILENAME), &encode_wav(&[]))?;
+    write_session(dir, session)
+}
+
+/// Add `samples` (16 kHz mono) to the end of the saved audio.
+///
+/// The samples are appended before the WAV header's sizes are updated, so
+/// a crash mid-write leaves a file that still decodes, up to the previous
+/// append.
+pub fn append(dir: &Path, samples: &[f32]) -> io::Result<()> {
+    if samples.is_empty() {
+        return Ok(());
+    }
+    let Some(mut session) = read_session(dir) else {
+        return Ok(());
+    };
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(dir.join(AUDIO_FILENAME))?;
+    let pcm = &encode_wav(samples)[WAV_HEADER_LEN as usize..];
+    let data_len = file.seek(SeekFrom::End(0))? - WAV_HEADER_LEN + pcm.len() as u64;
+    file.write_all(pcm)?;
+    file.sync_data()?;
+    let data_len = u32::try_from(data_len).map_err(io::Error::other)?;
+    file.seek(SeekFrom::Start(4))?;
+    file.write_all(&(data_len + 36).to_le_bytes())?;
+    file.seek(SeekFrom::Start(40))?;
+    file.write_all(&data_len.to_le_bytes())?;
+
+    session.audio_secs = (data_len / 2) as f32 / TARGET_SAMPLE_RATE as f32;
+    write_session(dir, &session)
+}
+
+/// Update the stage of the saved session without rewriting its audio.
+pub fn set_stage(dir: &Path, stage: RecoveryStage) -> io::Result<()> {
+    let Some(mut session) = read_session(dir) else {
+        return Ok(());
+    };
+    session.stage = stage;
+    write_session(dir, &session)
+}
+
+/// The session left behind, if any. A record whose audio is missing or
+/// empty, or that can't be parsed, counts as nothing to recover.
+pub fn load(dir: &Path) -> Option<RecoverableSession> {
+    let audio = fs::metadata(dir.join(AUDIO_FILENAME)).ok()?;
+    if audio.len() <= WAV_HEADER_LEN {
+        return None;
+    }
+    read_session(dir)
+}
+
+/// Decode the saved audio back to 16 kHz samples.
+pub fn load_audio(dir: &Path) -> Result<Vec<f32>, DictationError> {
+    decoder::decode_audio_file(&audio_path(dir))
+}
+
+/// Remove the saved session. Nothing saved is not an error.
+pub fn clear(dir: &Path) -> io::Result<()> {
+    for name in [SESSION_FILENAME, AUDIO_FILENAME] {
+        match fs::remove_file(dir.join(name)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn read_session(dir: &Path) -> Option<RecoverableSession> {
+    let json = fs::read_to_string(dir.join(SESSION_FILENAME)).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+fn write_session(dir: &Path, session: &RecoverableSession) -> io::Result<()> {
+    let json = serde_json::to_vec_pretty(session).map_err(io::Error::other)?;
+    write_atomic(&dir.join(SESSION_FILENAME), &json)
+}
+
+fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, bytes)?;
+    fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("sagascript-recovery-{}", uuid::Uuid::new_v4()))
+    }
+
+    fn session() -> RecoverableSession {
+        RecoverableSession::new(RecoveryStage::Recording, Language::Swedish, WhisperModel::BaseEn)
+    }
+
+    #[test]
+    fn appended_audio_loads_with_its_length() {
+        let dir = temp_dir();
+        start(&dir, &session()).unwrap();
+        assert_eq!(load(&dir), None, "nothing recorded yet");
+
+        append(&dir, &vec![0.1; TARGET_SAMPLE_RATE as usize]).unwrap();
+        append(&dir, &vec![0.2; TARGET_SAMPLE_RATE as usize]).unwrap();
+        let loaded = load(&dir).unwrap();
+        assert_eq!(loaded.stage, RecoveryStage::Recording);
+        assert_eq!(loaded.language, Language::Swedish);
+        assert_eq!(loaded.audio_secs, 2.0);
+        assert_eq!(load_audio(&dir).unwrap().len(), TARGET_SAMPLE_RATE as usize * 2);
+
+        set_stage(&dir, RecoveryStage::Transcribing).unwrap();
+        assert_eq!(load(&dir).unwrap().stage, RecoveryStage::Transcribing);
+
+        clear(&dir).unwrap();
+        assert_eq!(load(&dir), None);
+        clear(&dir).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn take_over_moves_a_left_behind_session() {
+        let root = temp_dir();
+        let (live, crashed) = (root.join("live"), root.join("crashed"));
+        assert_eq!(take_over(&live, &crashed).unwrap(), None);
+
+        start(&live, &session()).unwrap();
+        append(&live, &[0.1; 1600]).unwrap();
+        let moved = take_over(&live, &crashed).unwrap().unwrap();
+        assert_eq!(moved.language, Language::Swedish);
+        assert_eq!(load(&live), None);
+        assert_eq!(load(&crashed), Some(moved));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn nothing_to_append_to_without_a_started_session() {
+        let dir = temp_dir();
+        assert_eq!(load(&dir), None);
+        append(&dir, &[0.1; 1600]).unwrap();
+        set_stage(&dir, RecoveryStage::Transcribing).unwrap();
+        assert_eq!(load(&dir), None);
+    }
+}
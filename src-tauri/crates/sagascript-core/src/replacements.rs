@@ -0,0 +1,150 @@
+//! User-defined text replacement rules.
+//!
+//! Rules fix what Whisper keeps getting wrong for one user: a product name
+//! heard as two words ("saga script" → "Sagascript"), a colleague's name
+//! spelled the common way. They live in `Settings::replacement_rules`, are
+//! edited with `sagascript config rules`, and run in order over every
+//! finished transcript, after the built-in post-processing.
+//!
+//! A literal rule matches whole words and ignores case. A regex rule is a
+//! [`regex`] pattern, and its replacement may refer to groups (`$1`).
+
+use regex::{NoExpand, Regex};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::error::DictationError;
+
+/// One find-and-replace rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplacementRule {
+    /// Text, or a pattern when `regex` is set, to look for.
+    pub find: String,
+    /// What to put in its place.
+    pub replace: String,
+    /// `find` is a regular expression rather than literal text.
+    #[serde(default)]
+    pub regex: bool,
+}
+
+impl ReplacementRule {
+    /// A rule for `find` (a pattern when `regex`), checked so that it can
+    /// be applied.
+    pub fn new(find: &str, replace: &str, regex: bool) -> Result<Self, DictationError> {
+        let rule = Self {
+            find: if regex {
+                find.to_string()
+            } else {
+                find.trim().to_string()
+            },
+            replace: replace.to_string(),
+            regex,
+        };
+        if rule.find.is_empty() {
+            return Err(DictationError::SettingsError(
+                "A replacement rule needs text to find".to_string(),
+            ));
+        }
+        rule.pattern()?;
+        Ok(rule)
+    }
+
+    fn pattern(&self) -> Result<Regex, DictationError> {
+        let source = if self.regex {
+            self.find.clone()
+        } else {
+            // Word boundaries only where the text starts or ends with a word
+            // character: `\b` next to "." would never match.
+            let boundary = |c: Option<char>| match c {
+                Some(c) if c.is_alphanumeric() || c == '_' => r"\b",
+                _ => "",
+            };
+            format!(
+                "(?i){}{}{}",
+                boundary(self.find.chars().next()),
+                regex::escape(&self.find),
+                boundary(self.find.chars().last())
+            )
+        };
+        Regex::new(&source).map_err(|e| {
+            DictationError::SettingsError(format!("Invalid pattern '{}': {e}", self.find))
+        })
+    }
+
+    /// `literal` or `regex`, for listings.
+    pub fn kind(&self) -> &'static str {
+        if self.regex {
+            "regex"
+        } else {
+            "literal"
+        }
+    }
+}
+
+/// `text` with every rule applied, in order. A rule whose pattern no longer
+/// compiles (a hand-edited settings file) is skipped with a warning.
+pub fn apply(text: &str, rules: &[ReplacementRule]) -> String {
+    let mut text = text.to_string();
+    for rule in rules {
+        let pattern = match rule.pattern() {
+            Ok(pattern) => pattern,
+            Err(e) => {
+                warn!("Skipping replacement rule: {e}");
+                continue;
+            }
+        };
+        text = if rule.regex {
+            pattern.replace_all(&text, rule.replace.as_str())
+        } else {
+            pattern.replace_all(&text, NoExpand(&rule.replace))
+        }
+        .into_owned();
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal(find: &str, replace: &str) -> ReplacementRule {
+        ReplacementRule::new(find, replace, false).unwrap()
+    }
+
+    #[test]
+    fn literal_rules_match_whole_words_ignoring_case() {
+        let rules = [literal("saga script", "Sagascript")];
+        assert_eq!(
+            apply("Saga script is open. saga scripts too.", &rules),
+            "Sagascript is open. saga scripts too."
+        );
+    }
+
+    #[test]
+    fn literal_replacements_are_not_expanded() {
+        let rules = [literal("dollar", "$1"), literal("e.g.", "for example")];
+        assert_eq!(
+            apply("One dollar, e.g. now", &rules),
+            "One $1, for example now"
+        );
+    }
+
+    #[test]
+    fn regex_rules_can_use_groups() {
+        let rules = [ReplacementRule::new(r"(\d+) procent", "$1 %", true).unwrap()];
+        assert_eq!(apply("Upp 25 procent", &rules), "Upp 25 %");
+    }
+
+    #[test]
+    fn rules_apply_in_order() {
+        let rules = [literal("a", "b"), literal("b", "c")];
+        assert_eq!(apply("a b", &rules), "c c");
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        assert!(ReplacementRule::new("  ", "x", false).is_err());
+        assert!(ReplacementRule::new("(unclosed", "x", true).is_err());
+        assert!(ReplacementRule::new("(unclosed", "x", false).is_ok());
+    }
+}
@@ -0,0 +1,428 @@
+//! `sagascript self-update`: replace a standalone CLI binary with the latest
+//! release build.
+//!
+//! Each release carries a headless CLI binary per supported platform
+//! (`sagascript-cli-<os>-<arch>`, today only `macos-aarch64`) beside the app
+//! bundle, listed in the release's `SHA256SUMS`. An update downloads the
+//! binary, checks its size and SHA-256 against that list, on macOS also
+//! requires a valid Developer ID signature from the release team, and only
+//! then renames it over the running executable. Any failure leaves the
+//! installed binary untouched.
+//!
+//! Binaries that belong to something else are refused: the app bundle's
+//! binary (and the AppImage) update with the app, a `cargo install` build
+//! with cargo, and a Homebrew install with `brew upgrade`.
+
+use std::fs;
+use std::path::{Component, Path};
+
+use futures_util::StreamExt;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::error::DictationError;
+
+/// GitHub's "latest release" endpoint; it skips drafts and pre-releases.
+pub const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/Magnus-Gille/sagascript/releases/latest";
+
+/// The release asset listing every artifact's SHA-256.
+pub const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+
+/// The version of this build.
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Team ID of the Developer ID certificate releases are signed with.
+#[cfg(target_os = "macos")]
+const RELEASE_TEAM_ID: &str = "7C6WF6GFZ4";
+
+/// The release asset name of the CLI binary for this platform.
+pub fn asset_name() -> String {
+    format!(
+        "sagascript-cli-{}-{}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+    size: u64,
+}
+
+/// The latest published release, as far as this platform is concerned.
+#[derive(Debug, Clone)]
+pub struct LatestRelease {
+    /// Version without the tag's `v` prefix.
+    pub version: String,
+    binary: Option<GithubAsset>,
+    checksums: Option<GithubAsset>,
+}
+
+impl LatestRelease {
+    fn from_github(release: GithubRelease) -> Self {
+        let find = |name: &str| release.assets.iter().find(|a| a.name == name).cloned();
+        Self {
+            version: release.tag_name.trim_start_matches('v').to_string(),
+            binary: find(&asset_name()),
+            checksums: find(CHECKSUMS_ASSET),
+        }
+    }
+
+    /// Whether this is newer than the running build.
+    pub fn is_update(&self) -> bool {
+        is_newer(&self.version, CURRENT_VERSION)
+    }
+
+    /// Whether the release has a CLI binary for this platform.
+    pub fn has_binary(&self) -> bool {
+        self.binary.is_some() && self.checksums.is_some()
+    }
+}
+
+/// Whether version `candidate` is newer than `current`, comparing the dotted
+/// numeric parts (`1.10.0` > `1.9.2`). A pre-release suffix is ignored.
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    fn parts(version: &str) -> Vec<u64> {
+        version
+            .trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|p| p.parse().unwrap_or(0))
+            .collect()
+    }
+    let (mut a, mut b) = (parts(candidate), parts(current));
+    let len = a.len().max(b.len());
+    a.resize(len, 0);
+    b.resize(len, 0);
+    a > b
+}
+
+/// What installed a binary that self-update leaves alone (see
+/// [`managed_by`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Manager {
+    /// Who owns the binary, as in "managed by Homebrew".
+    pub name: &'static str,
+    /// What to do instead of self-update.
+    pub update: &'static str,
+}
+
+/// What `exe` is managed by, if it isn't a standalone CLI install that
+/// self-update may replace.
+pub fn managed_by(exe: &Path) -> Option<Manager> {
+    if std::env::var_os("APPIMAGE").is_some() {
+        return Some(Manager {
+            name: "the Sagascript AppImage",
+            update: "download the new AppImage",
+        });
+    }
+    let names: Vec<&str> = exe
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect();
+    if names
+        .windows(2)
+        .any(|w| w[0].ends_with(".app") && w[1] == "Contents")
+    {
+        return Some(Manager {
+            name: "the Sagascript app",
+            update: "update the app",
+        });
+    }
+    if names.windows(2).any(|w| w[0] == ".cargo" && w[1] == "bin") {
+        return Some(Manager {
+            name: "cargo install",
+            update: "run cargo install again",
+        });
+    }
+    // A formula's files live in the Cellar; `app_binary` resolves the
+    // bin/ symlink to it. The prefixes cover casks and other kegs.
+    if names.contains(&"Cellar")
+        || exe.starts_with("/opt/homebrew")
+        || exe.starts_with("/home/linuxbrew")
+    {
+        return Some(Manager {
+            name: "Homebrew",
+            update: "run 'brew upgrade sagascript'",
+        });
+    }
+    None
+}
+
+/// The SHA-256 listed for `name` in a `sha256sum`-format file.
+fn checksum_for(sums: &str, name: &str) -> Option<String> {
+    sums.lines().find_map(|line| {
+        let (hash, file) = line.split_once(char::is_whitespace)?;
+        // sha256sum marks binary-mode entries with a leading '*'.
+        (file.trim_start().trim_start_matches('*') == name).then(|| hash.to_ascii_lowercase())
+    })
+}
+
+fn update_err(msg: impl Into<String>) -> DictationError {
+    DictationError::UpdateError(msg.into())
+}
+
+fn client() -> Result<reqwest::Client, DictationError> {
+    // GitHub's API rejects requests without a User-Agent.
+    crate::net::client_builder()
+        .user_agent(concat!("sagascript/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| update_err(e.to_string()))
+}
+
+/// Look up the latest release.
+pub async fn latest_release() -> Result<LatestRelease, DictationError> {
+    let response = client()?
+        .get(LATEST_RELEASE_URL)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .map_err(|e| update_err(format!("release check failed: {e}")))?;
+    if !response.status().is_success() {
+        return Err(update_err(format!(
+            "release check failed: HTTP {}",
+            response.status()
+        )));
+    }
+    let release: GithubRelease = response
+        .json()
+        .await
+        .map_err(|e| update_err(format!("unexpected release data: {e}")))?;
+    Ok(LatestRelease::from_github(release))
+}
+
+/// Download the release's CLI binary, verify it, and replace `exe` with it.
+/// `progress` gets `(bytes_downloaded, total)`.
+pub async fn install(
+    release: &LatestRelease,
+    exe: &Path,
+    progress: impl Fn(u64, u64),
+) -> Result<(), DictationError> {
+    if let Some(manager) = managed_by(exe) {
+        return Err(update_err(format!(
+            "{} is managed by {}; {} instead",
+            exe.display(),
+            manager.name,
+            manager.update
+        )));
+    }
+    let (Some(binary), Some(checksums)) = (&release.binary, &release.checksums) else {
+        return Err(update_err(format!(
+            "release {} has no {} build",
+            release.version,
+            asset_name()
+        )));
+    };
+
+    let client = client()?;
+    let sums = client
+        .get(&checksums.browser_download_url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| update_err(format!("checksum download failed: {e}")))?
+        .text()
+        .await
+        .map_err(|e| update_err(format!("checksum download failed: {e}")))?;
+    let expected = checksum_for(&sums, &binary.name)
+        .ok_or_else(|| update_err(format!("{CHECKSUMS_ASSET} doesn't list {}", binary.name)))?;
+
+    let response = client
+        .get(&binary.browser_download_url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| update_err(format!("download failed: {e}")))?;
+    let mut bytes = Vec::with_capacity(binary.size as usize);
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| update_err(format!("download failed: {e}")))?;
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() as u64 > binary.size {
+            return Err(update_err(format!(
+                "download is larger than the {} bytes the release lists",
+                binary.size
+            )));
+        }
+        progress(bytes.len() as u64, binary.size);
+    }
+
+    if bytes.len() as u64 != binary.size {
+        return Err(update_err(format!(
+            "download truncated: got {} of {} bytes",
+            bytes.len(),
+            binary.size
+        )));
+    }
+    let actual = format!("{:x}", Sha256::digest(&bytes));
+    if actual != expected {
+        return Err(update_err(format!(
+            "checksum mismatch for {}: expected {expected}, got {actual}",
+            binary.name
+        )));
+    }
+
+    replace_executable(exe, &bytes)
+}
+
+/// Stage `bytes` beside `exe`, check the staged file, then rename it over
+/// `exe`. On Unix the rename is safe while `exe` is running: the running
+/// process keeps the old inode.
+#[cfg(unix)]
+fn replace_executable(exe: &Path, bytes: &[u8]) -> Result<(), DictationError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = exe
+        .parent()
+        .ok_or_else(|| update_err(format!("{} has no parent directory", exe.display())))?;
+    let staged = dir.join(format!(".sagascript-update.{}", std::process::id()));
+    let result = (|| {
+        fs::write(&staged, bytes).map_err(|e| match e.kind() {
+            std::io::ErrorKind::PermissionDenied => update_err(format!(
+                "no permission to write to {}; re-run with sudo",
+                dir.display()
+            )),
+            _ => update_err(format!("failed to write the update: {e}")),
+        })?;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))
+            .map_err(|e| update_err(format!("failed to make the update executable: {e}")))?;
+        #[cfg(target_os = "macos")]
+        verify_signature(&staged)?;
+        fs::rename(&staged, exe)
+            .map_err(|e| update_err(format!("failed to replace {}: {e}", exe.display())))
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&staged);
+    }
+    result
+}
+
+#[cfg(not(unix))]
+fn replace_executable(_exe: &Path, _bytes: &[u8]) -> Result<(), DictationError> {
+    Err(update_err(
+        "self-update is only supported on macOS and Linux",
+    ))
+}
+
+/// Require a valid Developer ID signature from the release team.
+#[cfg(target_os = "macos")]
+fn verify_signature(path: &Path) -> Result<(), DictationError> {
+    let requirement =
+        format!("=anchor apple generic and certificate leaf[subject.OU] = \"{RELEASE_TEAM_ID}\"");
+    let output = std::process::Command::new("codesign")
+        .args(["--verify", "--strict", "-R", &requirement])
+        .arg(path)
+        .output()
+        .map_err(|e| update_err(format!("failed to run codesign: {e}")))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(update_err(format!(
+            "the downloaded binary isn't signed by the Sagascript release team: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newer_compares_numeric_parts() {
+        assert!(is_newer("1.10.0", "1.9.2"));
+        assert!(is_newer("v2.0", "1.99.99"));
+        assert!(is_newer("1.2.1", "1.2"));
+        assert!(!is_newer("1.2.0", "1.2"));
+        assert!(!is_newer("1.2.0-beta.1", "1.2.0"));
+        assert!(!is_newer("0.9.0", "1.0.0"));
+    }
+
+    #[test]
+    fn checksum_lookup_matches_whole_names() {
+        let sums = "\
+ABC123  Sagascript.dmg
+def456 *sagascript-cli-macos-aarch64
+0f0f0f  sagascript-cli-macos-aarch64.sig
+";
+        assert_eq!(
+            checksum_for(sums, "sagascript-cli-macos-aarch64").as_deref(),
+            Some("def456")
+        );
+        assert_eq!(
+            checksum_for(sums, "Sagascript.dmg").as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(checksum_for(sums, "sagascript-cli-linux-x86_64"), None);
+    }
+
+    #[test]
+    fn app_and_cargo_binaries_are_managed_elsewhere() {
+        let owner = |path: &str| managed_by(Path::new(path)).map(|m| m.name);
+        assert_eq!(
+            owner("/Applications/Sagascript.app/Contents/MacOS/sagascript"),
+            Some("the Sagascript app")
+        );
+        assert_eq!(
+            owner("/home/a/.cargo/bin/sagascript"),
+            Some("cargo install")
+        );
+        if std::env::var_os("APPIMAGE").is_none() {
+            assert_eq!(owner("/usr/local/bin/sagascript"), None);
+        }
+    }
+
+    #[test]
+    fn homebrew_binaries_are_managed_elsewhere() {
+        if std::env::var_os("APPIMAGE").is_some() {
+            return;
+        }
+        for path in [
+            "/usr/local/Cellar/sagascript/1.0.1/bin/sagascript",
+            "/opt/homebrew/Cellar/sagascript/1.0.1/bin/sagascript",
+            "/opt/homebrew/bin/sagascript",
+            "/home/linuxbrew/.linuxbrew/bin/sagascript",
+        ] {
+            let manager = managed_by(Path::new(path)).expect(path);
+            assert_eq!(manager.name, "Homebrew");
+            assert!(manager.update.contains("brew upgrade"));
+        }
+    }
+
+    #[test]
+    fn release_picks_this_platforms_binary() {
+        let asset = |name: &str| GithubAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.invalid/{name}"),
+            size: 1,
+        };
+        let release = LatestRelease::from_github(GithubRelease {
+            tag_name: "v99.0.0".to_string(),
+            assets: vec![asset("Sagascript.dmg"), asset(CHECKSUMS_ASSET)],
+        });
+        assert_eq!(release.version, "99.0.0");
+        assert!(release.is_update());
+        assert!(!release.has_binary());
+
+        let release = LatestRelease::from_github(GithubRelease {
+            tag_name: "v0.0.1".to_string(),
+            assets: vec![asset(&asset_name()), asset(CHECKSUMS_ASSET)],
+        });
+        assert!(!release.is_update());
+        assert!(release.has_binary());
+    }
+}
@@ -0,0 +1,421 @@
+//! Dictation session timeline, rebuilt from the app's structured JSONL log.
+//!
+//! The app logs a `dictation_session_started` entry when recording begins
+//! and a `dictation_session_complete` entry, carrying the outcome, timings
+//! and any error, when the session ends. Grouping those entries by their
+//! `dictationSession` ID gives a per-dictation history without a second
+//! store to keep in sync. Only the CLI and the settings UI read it.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Event logged when a dictation starts recording.
+pub const STARTED_EVENT: &str = "dictation_session_started";
+
+/// Event logged when a dictation ends, successfully or not.
+pub const COMPLETE_EVENT: &str = "dictation_session_complete";
+
+/// Name of the live log file; rotated files are `sagascript.1.log` (newest)
+/// through `sagascript.<MAX_ROTATED_FILES>.log` (oldest).
+pub const LOG_FILE_NAME: &str = "sagascript.log";
+
+/// How many rotated log files are kept next to the live one.
+pub const MAX_ROTATED_FILES: u32 = 5;
+
+/// Recordings shorter than this get no words-per-minute figure; a two-word
+/// tap would otherwise read as hundreds.
+const MIN_PACE_MS: u64 = 3_000;
+
+/// Directory the app writes its structured log to.
+pub fn log_directory() -> PathBuf {
+    #[cfg(target_os = "macos")]
+    {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("Library/Logs/Sagascript")
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("Sagascript/Logs")
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("Sagascript/logs")
+    }
+}
+
+/// How a dictation session ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionOutcome {
+    /// Transcribed to non-empty text.
+    Completed,
+    /// Transcription ran but produced no text (silence or no speech).
+    Empty,
+    /// Capture, model loading or transcription failed; see `error`.
+    Failed,
+    /// Recording was cancelled before transcription.
+    Cancelled,
+    /// Skipped without transcribing: the audio repeated the previous
+    /// dictation's (`dedupe_dictations`).
+    Duplicate,
+    /// Started but never logged an end: still in progress, or the app quit
+    /// or crashed mid-session.
+    Unfinished,
+}
+
+impl SessionOutcome {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SessionOutcome::Completed => "completed",
+            SessionOutcome::Empty => "empty",
+            SessionOutcome::Failed => "failed",
+            SessionOutcome::Cancelled => "cancelled",
+            SessionOutcome::Duplicate => "duplicate",
+            SessionOutcome::Unfinished => "unfinished",
+        }
+    }
+}
+
+/// One dictation on the timeline.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionRecord {
+    /// Dictation session ID (`dict-xxxxxxxx`), as it appears in the log.
+    pub id: String,
+    /// RFC 3339 timestamp of the start entry.
+    pub started_at: String,
+    /// RFC 3339 timestamp of the end entry, if one was logged.
+    pub ended_at: Option<String>,
+    pub outcome: SessionOutcome,
+    /// How long the microphone was recording.
+    pub recording_ms: Option<u64>,
+    /// From the start of recording to the end of the session, including
+    /// model loading and transcription.
+    pub duration_ms: Option<u64>,
+    /// Model configured when the session ended.
+    pub model: Option<String>,
+    /// Length of the transcribed text.
+    pub chars: Option<usize>,
+    /// Words in the transcribed text.
+    pub words: Option<usize>,
+    /// `words` per minute of recording, see [`words_per_minute`].
+    pub wpm: Option<f32>,
+    pub error: Option<String>,
+}
+
+/// Words per minute across several sessions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PaceSummary {
+    /// Sessions with both a word count and a recording length.
+    pub sessions: usize,
+    pub words: usize,
+    pub recording_ms: u64,
+    pub wpm: f32,
+}
+
+/// Words in `text`, as whitespace-separated runs.
+pub fn word_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+/// Speaking rate of `words` over `recording_ms` of audio, or `None` for a
+/// recording too short to say.
+pub fn words_per_minute(words: usize, recording_ms: u64) -> Option<f32> {
+    (recording_ms >= MIN_PACE_MS).then(|| words as f32 * 60_000.0 / recording_ms as f32)
+}
+
+/// Overall pace of the completed sessions in `records`: their words over
+/// their combined recording time, so long dictations weigh more than short
+/// ones. `None` when no session has a pace.
+pub fn pace_summary(records: &[SessionRecord]) -> Option<PaceSummary> {
+    let (sessions, words, recording_ms) = records
+        .iter()
+        .filter(|r| r.outcome == SessionOutcome::Completed && r.wpm.is_some())
+        .filter_map(|r| Some((r.words?, r.recording_ms?)))
+        .fold((0, 0, 0), |(n, w, ms), (words, rec)| {
+            (n + 1, w + words, ms + rec)
+        });
+    Some(PaceSummary {
+        sessions,
+        words,
+        recording_ms,
+        wpm: words_per_minute(words, recording_ms)?,
+    })
+}
+
+/// Shape of the `data` payload of a [`COMPLETE_EVENT`] entry.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct CompleteData {
+    outcome: Option<SessionOutcome>,
+    recording_ms: Option<u64>,
+    duration_ms: Option<u64>,
+    model: Option<String>,
+    chars: Option<usize>,
+    words: Option<usize>,
+    error: Option<String>,
+}
+
+/// The fields of a log line the timeline needs.
+#[derive(Debug, Deserialize)]
+struct LogLine {
+    ts: String,
+    event: String,
+    #[serde(rename = "dictationSession")]
+    dictation_session: Option<String>,
+    #[serde(default)]
+    data: serde_json::Value,
+}
+
+/// The most recent `limit` sessions in `dir`'s logs, newest first.
+pub fn timeline(dir: &Path, limit: usize) -> Vec<SessionRecord> {
+    // Oldest rotated file first so sessions that straddle a rotation still
+    // see their start entry before their end entry.
+    let mut files: Vec<PathBuf> = (1..=MAX_ROTATED_FILES)
+        .rev()
+        .map(|i| dir.join(format!("sagascript.{i}.log")))
+        .collect();
+    files.push(dir.join(LOG_FILE_NAME));
+
+    let contents: Vec<String> = files
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .collect();
+    let mut sessions = collect_sessions(contents.iter().flat_map(|c| c.lines()));
+    sessions.reverse();
+    sessions.truncate(limit);
+    sessions
+}
+
+/// Group log lines, oldest first, into sessions in start order. Lines that
+/// aren't session events, or aren't valid JSON (a torn write), are skipped.
+fn collect_sessions<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<SessionRecord> {
+    let mut sessions: Vec<SessionRecord> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for line in lines {
+        let Ok(entry) = serde_json::from_str::<LogLine>(line) else {
+            continue;
+        };
+        let Some(id) = entry.dictation_session else {
+            continue;
+        };
+        if entry.event == STARTED_EVENT {
+            index.insert(id.clone(), sessions.len());
+            sessions.push(SessionRecord {
+                id,
+                started_at: entry.ts,
+                ended_at: None,
+                outcome: SessionOutcome::Unfinished,
+                recording_ms: None,
+                duration_ms: None,
+                model: None,
+                chars: None,
+                words: None,
+                wpm: None,
+                error: None,
+            });
+        } else if entry.event == COMPLETE_EVENT {
+            // An end without a start means the start was rotated away.
+            let Some(&i) = index.get(&id) else {
+                continue;
+            };
+            let data: CompleteData = serde_json::from_value(entry.data).unwrap_or_default();
+            let session = &mut sessions[i];
+            session.ended_at = Some(entry.ts);
+            session.outcome = data.outcome.unwrap_or(SessionOutcome::Unfinished);
+            session.recording_ms = data.recording_ms;
+            session.duration_ms = data.duration_ms;
+            session.model = data.model;
+            session.chars = data.chars;
+            session.words = data.words;
+            session.wpm = data
+                .words
+                .zip(data.recording_ms)
+                .and_then(|(words, ms)| words_per_minute(words, ms));
+            session.error = data.error;
+        }
+    }
+    sessions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn started(ts: &str, id: &str) -> String {
+        serde_json::json!({
+            "ts": ts,
+            "level": "info",
+            "appSession": "app-00000000",
+            "dictationSession": id,
+            "category": "App",
+            "event": STARTED_EVENT,
+            "data": { "dictationSessionId": id },
+        })
+        .to_string()
+    }
+
+    fn complete(ts: &str, id: &str, data: serde_json::Value) -> String {
+        serde_json::json!({
+            "ts": ts,
+            "level": "info",
+            "appSession": "app-00000000",
+            "dictationSession": id,
+            "category": "App",
+            "event": COMPLETE_EVENT,
+            "data": data,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn pairs_start_and_complete_entries() {
+        let lines = [
+            started("2026-03-01T10:00:00.000Z", "dict-aaaaaaaa"),
+            r#"{"ts":"2026-03-01T10:00:01.000Z","event":"app_started"}"#.to_string(),
+            complete(
+                "2026-03-01T10:00:04.000Z",
+                "dict-aaaaaaaa",
+                serde_json::json!({
+                    "outcome": "completed",
+                    "recordingMs": 3000,
+                    "durationMs": 4000,
+                    "model": "base.en",
+                    "chars": 42,
+                    "words": 8,
+                }),
+            ),
+        ];
+        let sessions = collect_sessions(lines.iter().map(String::as_str));
+        assert_eq!(sessions.len(), 1);
+        let s = &sessions[0];
+        assert_eq!(s.id, "dict-aaaaaaaa");
+        assert_eq!(s.outcome, SessionOutcome::Completed);
+        assert_eq!(s.ended_at.as_deref(), Some("2026-03-01T10:00:04.000Z"));
+        assert_eq!(s.recording_ms, Some(3000));
+        assert_eq!(s.duration_ms, Some(4000));
+        assert_eq!(s.model.as_deref(), Some("base.en"));
+        assert_eq!(s.chars, Some(42));
+        assert_eq!(s.words, Some(8));
+        assert_eq!(s.wpm, Some(160.0));
+        assert_eq!(s.error, None);
+    }
+
+    #[test]
+    fn pace_weighs_sessions_by_recording_length() {
+        assert_eq!(word_count("  Hello there,\nworld "), 3);
+        assert_eq!(words_per_minute(2, 1_000), None);
+        assert_eq!(words_per_minute(30, 12_000), Some(150.0));
+
+        let lines = [
+            started("2026-03-01T10:00:00.000Z", "dict-aaaaaaaa"),
+            complete(
+                "2026-03-01T10:00:10.000Z",
+                "dict-aaaaaaaa",
+                serde_json::json!({ "outcome": "completed", "recordingMs": 60000, "words": 120 }),
+            ),
+            started("2026-03-01T10:01:00.000Z", "dict-bbbbbbbb"),
+            complete(
+                "2026-03-01T10:01:10.000Z",
+                "dict-bbbbbbbb",
+                serde_json::json!({ "outcome": "completed", "recordingMs": 30000, "words": 90 }),
+            ),
+            started("2026-03-01T10:02:00.000Z", "dict-cccccccc"),
+            complete(
+                "2026-03-01T10:02:01.000Z",
+                "dict-cccccccc",
+                serde_json::json!({ "outcome": "completed", "recordingMs": 500, "words": 1 }),
+            ),
+        ];
+        let sessions = collect_sessions(lines.iter().map(String::as_str));
+        let summary = pace_summary(&sessions).unwrap();
+        assert_eq!(summary.sessions, 2);
+        assert_eq!(summary.words, 210);
+        assert_eq!(summary.wpm, 140.0);
+        assert!(pace_summary(&sessions[2..]).is_none());
+    }
+
+    #[test]
+    fn keeps_error_and_marks_missing_end_unfinished() {
+        let lines = [
+            started("2026-03-01T10:00:00.000Z", "dict-aaaaaaaa"),
+            complete(
+                "2026-03-01T10:00:02.000Z",
+                "dict-aaaaaaaa",
+                serde_json::json!({ "outcome": "failed", "error": "No audio captured" }),
+            ),
+            started("2026-03-01T10:01:00.000Z", "dict-bbbbbbbb"),
+            "{not json".to_string(),
+        ];
+        let sessions = collect_sessions(lines.iter().map(String::as_str));
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].outcome, SessionOutcome::Failed);
+        assert_eq!(sessions[0].error.as_deref(), Some("No audio captured"));
+        assert_eq!(sessions[1].outcome, SessionOutcome::Unfinished);
+        assert_eq!(sessions[1].ended_at, None);
+    }
+
+    #[test]
+    fn ignores_complete_without_start() {
+        let lines = [complete(
+            "2026-03-01T10:00:02.000Z",
+            "dict-rotated0",
+            serde_json::json!({ "outcome": "completed" }),
+        )];
+        assert!(collect_sessions(lines.iter().map(String::as_str)).is_empty());
+    }
+
+    #[test]
+    fn timeline_reads_rotated_files_newest_first() {
+        let dir =
+            std::env::temp_dir().join(format!("sagascript-sessions-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        // The session straddles a rotation: started in .1, ended in the live log.
+        fs::write(
+            dir.join("sagascript.2.log"),
+            started("2026-03-01T09:00:00.000Z", "dict-00000001") + "\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("sagascript.1.log"),
+            started("2026-03-01T10:00:00.000Z", "dict-00000002") + "\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join(LOG_FILE_NAME),
+            complete(
+                "2026-03-01T10:00:05.000Z",
+                "dict-00000002",
+                serde_json::json!({ "outcome": "cancelled" }),
+            ) + "\n",
+        )
+        .unwrap();
+
+        let sessions = timeline(&dir, 10);
+        let ids: Vec<&str> = sessions.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["dict-00000002", "dict-00000001"]);
+        assert_eq!(sessions[0].outcome, SessionOutcome::Cancelled);
+
+        assert_eq!(timeline(&dir, 1).len(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn outcome_serializes_lowercase() {
+        assert_eq!(
+            serde_json::to_string(&SessionOutcome::Cancelled).unwrap(),
+            "\"cancelled\""
+        );
+        assert_eq!(SessionOutcome::Empty.label(), "empty");
+    }
+}
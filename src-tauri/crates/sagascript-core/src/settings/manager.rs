@@ -1,6 +1,16 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
 use crate::download::DownloadIntegrity;
+use crate::output::{self, OutputSink};
+use crate::paste_format::AppCategory;
+use crate::replacements::ReplacementRule;
+use crate::transcription::catalog;
+
+use super::profiles::Profile;
 
 #[cfg(target_os = "macos")]
 const WHISPER_CPP_REVISION: &str = "5359861c739e955e79d9a303bcbc70fb988958b1";
@@ -200,80 +210,22 @@ impl WhisperModel {
 
     /// GGML model filename
     pub fn ggml_filename(&self) -> &'static str {
-        match self {
-            WhisperModel::TinyEn => "ggml-tiny.en.bin",
-            WhisperModel::Tiny => "ggml-tiny.bin",
-            WhisperModel::BaseEn => "ggml-base.en.bin",
-            WhisperModel::Base => "ggml-base.bin",
-            WhisperModel::KbWhisperTiny => "kb-whisper-tiny-q5_0.bin",
-            WhisperModel::KbWhisperBase => "kb-whisper-base-q5_0.bin",
-            WhisperModel::KbWhisperSmall => "kb-whisper-small-q5_0.bin",
-            WhisperModel::KbWhisperMedium => "kb-whisper-medium-q5_0.bin",
-            WhisperModel::KbWhisperLarge => "kb-whisper-large-q5_0.bin",
-            WhisperModel::NbWhisperTiny => "nb-whisper-tiny-q5_0.bin",
-            WhisperModel::NbWhisperBase => "nb-whisper-base-q5_0.bin",
-            WhisperModel::NbWhisperSmall => "nb-whisper-small-q5_0.bin",
-            WhisperModel::NbWhisperMedium => "nb-whisper-medium-q5_0.bin",
-            WhisperModel::NbWhisperLarge => "nb-whisper-large-q5_0.bin",
-            WhisperModel::SmallEn => "ggml-small.en.bin",
-            WhisperModel::Small => "ggml-small.bin",
-            WhisperModel::MediumEn => "ggml-medium.en.bin",
-            WhisperModel::Medium => "ggml-medium.bin",
-            WhisperModel::LargeV3Turbo => "ggml-large-v3-turbo.bin",
-            WhisperModel::LargeV3TurboQ8 => "ggml-large-v3-turbo-q8_0.bin",
-        }
-    }
-
-    /// HuggingFace download URL for model
+        &catalog::builtin_entry(*self).local_file
+    }
+
+    /// HuggingFace download URL for model, from the bundled `models.json`
     pub fn download_url(&self) -> &'static str {
-        match self {
-            WhisperModel::TinyEn => "https://huggingface.co/ggerganov/whisper.cpp/resolve/5359861c739e955e79d9a303bcbc70fb988958b1/ggml-tiny.en.bin",
-            WhisperModel::Tiny => "https://huggingface.co/ggerganov/whisper.cpp/resolve/5359861c739e955e79d9a303bcbc70fb988958b1/ggml-tiny.bin",
-            WhisperModel::BaseEn => "https://huggingface.co/ggerganov/whisper.cpp/resolve/5359861c739e955e79d9a303bcbc70fb988958b1/ggml-base.en.bin",
-            WhisperModel::Base => "https://huggingface.co/ggerganov/whisper.cpp/resolve/5359861c739e955e79d9a303bcbc70fb988958b1/ggml-base.bin",
-            WhisperModel::KbWhisperTiny => "https://huggingface.co/KBLab/kb-whisper-tiny/resolve/76d796af43a50fa34321efa562c9b9887a187463/ggml-model-q5_0.bin",
-            WhisperModel::KbWhisperBase => "https://huggingface.co/KBLab/kb-whisper-base/resolve/1499d2d2f0c7ed545bd6f2eec85287cf8d8c8b38/ggml-model-q5_0.bin",
-            WhisperModel::KbWhisperSmall => "https://huggingface.co/KBLab/kb-whisper-small/resolve/3564d61a42fc210ceaa55a22a96dd64478959c78/ggml-model-q5_0.bin",
-            WhisperModel::KbWhisperMedium => "https://huggingface.co/KBLab/kb-whisper-medium/resolve/0abe10b9d7f75d0902656e5c06c5c4d549604dc5/ggml-model-q5_0.bin",
-            WhisperModel::KbWhisperLarge => "https://huggingface.co/KBLab/kb-whisper-large/resolve/d5d5984b4d8f7c4847a8ea203f1976285fb28300/ggml-model-q5_0.bin",
-            WhisperModel::NbWhisperTiny => "https://huggingface.co/NbAiLab/nb-whisper-tiny/resolve/8b38492d0e4111d5d6ad825e979cb082a2da013a/ggml-model-q5_0.bin",
-            WhisperModel::NbWhisperBase => "https://huggingface.co/NbAiLab/nb-whisper-base/resolve/2ab372b6baa181a22f54f18030cae3703402c59e/ggml-model-q5_0.bin",
-            WhisperModel::NbWhisperSmall => "https://huggingface.co/NbAiLab/nb-whisper-small/resolve/e9bb5cb83cb74c96239fd506163aa97cff2fce4c/ggml-model-q5_0.bin",
-            WhisperModel::NbWhisperMedium => "https://huggingface.co/NbAiLab/nb-whisper-medium/resolve/0ed074d5985bd56ca4140159a9dbffbc3fb5117e/ggml-model-q5_0.bin",
-            WhisperModel::NbWhisperLarge => "https://huggingface.co/NbAiLab/nb-whisper-large/resolve/8c6249fdeeb4dcd05e5735a4c39640607eb6e4ac/ggml-model-q5_0.bin",
-            WhisperModel::SmallEn => "https://huggingface.co/ggerganov/whisper.cpp/resolve/5359861c739e955e79d9a303bcbc70fb988958b1/ggml-small.en.bin",
-            WhisperModel::Small => "https://huggingface.co/ggerganov/whisper.cpp/resolve/5359861c739e955e79d9a303bcbc70fb988958b1/ggml-small.bin",
-            WhisperModel::MediumEn => "https://huggingface.co/ggerganov/whisper.cpp/resolve/5359861c739e955e79d9a303bcbc70fb988958b1/ggml-medium.en.bin",
-            WhisperModel::Medium => "https://huggingface.co/ggerganov/whisper.cpp/resolve/5359861c739e955e79d9a303bcbc70fb988958b1/ggml-medium.bin",
-            WhisperModel::LargeV3Turbo => "https://huggingface.co/ggerganov/whisper.cpp/resolve/5359861c739e955e79d9a303bcbc70fb988958b1/ggml-large-v3-turbo.bin",
-            WhisperModel::LargeV3TurboQ8 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/5359861c739e955e79d9a303bcbc70fb988958b1/ggml-large-v3-turbo-q8_0.bin",
-        }
+        catalog::builtin_entry(*self).url()
+    }
+
+    /// URLs to try after [`Self::download_url`] fails, from `models.json`.
+    pub fn download_mirrors(&self) -> &'static [String] {
+        catalog::builtin_entry(*self).mirrors()
     }
 
     /// Exact git-LFS metadata for the artifact at [`Self::download_url`].
     pub fn download_integrity(&self) -> DownloadIntegrity {
-        match self {
-            WhisperModel::TinyEn => DownloadIntegrity { sha256: "921e4cf8686fdd993dcd081a5da5b6c365bfde1162e72b08d75ac75289920b1f", size: 77_704_715 },
-            WhisperModel::Tiny => DownloadIntegrity { sha256: "be07e048e1e599ad46341c8d2a135645097a538221678b7acdd1b1919c6e1b21", size: 77_691_713 },
-            WhisperModel::BaseEn => DownloadIntegrity { sha256: "a03779c86df3323075f5e796cb2ce5029f00ec8869eee3fdfb897afe36c6d002", size: 147_964_211 },
-            WhisperModel::Base => DownloadIntegrity { sha256: "60ed5bc3dd14eea856493d334349b405782ddcaf0028d4b5df4088345fba2efe", size: 147_951_465 },
-            WhisperModel::KbWhisperTiny => DownloadIntegrity { sha256: "98d46b7d23e5528d006e8a42e29eb0cb39b44bed94e1329f10f57d1fd15c658b", size: 29_875_738 },
-            WhisperModel::KbWhisperBase => DownloadIntegrity { sha256: "aead29b356bca8840e72a8dc2286e2d69e6702639751a1e60cb3c8eacefec546", size: 55_295_450 },
-            WhisperModel::KbWhisperSmall => DownloadIntegrity { sha256: "6768836a51abc902e420c613153e6d418c90ea2774e913274d02ab23170225b7", size: 175_209_680 },
-            WhisperModel::KbWhisperMedium => DownloadIntegrity { sha256: "7f8762e0ade9e0073674c0d5acae942a0b1ea98add9baa008ee89c94eaba43d0", size: 539_212_484 },
-            WhisperModel::KbWhisperLarge => DownloadIntegrity { sha256: "6d2863812d7410322bb7d8647a5c7260761300fa946714c9ed66d22bb30bcb19", size: 1_081_140_203 },
-            WhisperModel::NbWhisperTiny => DownloadIntegrity { sha256: "e5fb42192cdf31bea624a524d035e8895030b2bb4b31d4ea2a1ebf0ea8f57237", size: 29_875_738 },
-            WhisperModel::NbWhisperBase => DownloadIntegrity { sha256: "dcb9f3ab963cd288974c826c1519ff73b78b2372e80d388a6ce94f29c6a5b40f", size: 55_295_450 },
-            WhisperModel::NbWhisperSmall => DownloadIntegrity { sha256: "2a9025afb6e825fc4ae6a46671e0cb2f43e62f1dec87270deea6fe61b5285a20", size: 175_209_680 },
-            WhisperModel::NbWhisperMedium => DownloadIntegrity { sha256: "18733de634af639a43b0f8c5f5a2ea0920de4c5b32a5570ec130981581c0e5e7", size: 539_212_484 },
-            WhisperModel::NbWhisperLarge => DownloadIntegrity { sha256: "feb5951ae694a62cfeb81fb501f6cfa8cc50d96bcddb1e4e8215f7006bac23a2", size: 1_081_140_203 },
-            WhisperModel::SmallEn => DownloadIntegrity { sha256: "c6138d6d58ecc8322097e0f987c32f1be8bb0a18532a3f88f734d1bbf9c41e5d", size: 487_614_201 },
-            WhisperModel::Small => DownloadIntegrity { sha256: "1be3a9b2063867b937e64e2ec7483364a79917e157fa98c5d94b5c1fffea987b", size: 487_601_967 },
-            WhisperModel::MediumEn => DownloadIntegrity { sha256: "cc37e93478338ec7700281a7ac30a10128929eb8f427dda2e865faa8f6da4356", size: 1_533_774_781 },
-            WhisperModel::Medium => DownloadIntegrity { sha256: "6c14d5adee5f86394037b4e4e8b59f1673b6cee10e3cf0b11bbdbee79c156208", size: 1_533_763_059 },
-            WhisperModel::LargeV3Turbo => DownloadIntegrity { sha256: "1fc70f774d38eb169993ac391eea357ef47c88757ef72ee5943879b7e8e2bc69", size: 1_624_555_275 },
-            WhisperModel::LargeV3TurboQ8 => DownloadIntegrity { sha256: "317eb69c11673c9de1e1f0d459b253999804ec71ac4c23c17ecf5fbe24e259a1", size: 874_188_075 },
-        }
+        catalog::builtin_entry(*self).integrity()
     }
 
     /// CoreML encoder basename whisper.cpp derives from the GGML filename: strip
@@ -378,6 +330,26 @@ impl WhisperModel {
         }
     }
 
+    /// Accuracy-leaning model for long recordings in a given language
+    pub fn accurate(language: Language) -> WhisperModel {
+        match language {
+            Language::English => WhisperModel::SmallEn,
+            Language::Swedish => WhisperModel::KbWhisperSmall,
+            Language::Norwegian => WhisperModel::NbWhisperSmall,
+            Language::Auto => WhisperModel::Small,
+        }
+    }
+
+    /// Fastest model for a given language, used for draft text
+    pub fn draft(language: Language) -> WhisperModel {
+        match language {
+            Language::English => WhisperModel::TinyEn,
+            Language::Swedish => WhisperModel::KbWhisperTiny,
+            Language::Norwegian => WhisperModel::NbWhisperTiny,
+            Language::Auto => WhisperModel::Tiny,
+        }
+    }
+
     /// Recommended model for a given language
     pub fn recommended(language: Language) -> WhisperModel {
         match language {
@@ -421,6 +393,29 @@ impl WhisperModel {
             ],
         }
     }
+
+    /// Next lighter model in the same language family, if any.
+    ///
+    /// Walks back through `models_for_language` and returns the first entry
+    /// with a smaller download, so quantized variants step down to a genuinely
+    /// cheaper model rather than their unquantized sibling.
+    pub fn smaller(&self) -> Option<WhisperModel> {
+        let family = [
+            Language::English,
+            Language::Swedish,
+            Language::Norwegian,
+            Language::Auto,
+        ]
+        .into_iter()
+        .map(WhisperModel::models_for_language)
+        .find(|models| models.contains(self))?;
+        let idx = family.iter().position(|m| m == self)?;
+        family[..idx]
+            .iter()
+            .rev()
+            .find(|m| m.size_mb() < self.size_mb())
+            .copied()
+    }
 }
 
 /// Hotkey activation mode
@@ -444,6 +439,296 @@ impl HotkeyMode {
     }
 }
 
+/// Where recordings take their audio from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CaptureSource {
+    /// The default (or chosen) input device.
+    #[default]
+    Microphone,
+    /// Whatever the machine is playing: WASAPI loopback of the default
+    /// output on Windows, elsewhere a loopback input device (BlackHole,
+    /// Soundflower, a PulseAudio/PipeWire monitor).
+    System,
+}
+
+impl CaptureSource {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            CaptureSource::Microphone => "Microphone",
+            CaptureSource::System => "System audio",
+        }
+    }
+}
+
+/// What the dictation hotkey does while the previous dictation is still
+/// transcribing. Every option also emits a `busy` event so the press is
+/// never silently dropped.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WhileBusy {
+    /// Ignore the press (the overlay flashes "still transcribing").
+    #[default]
+    Reject,
+    /// Start the new recording as soon as the transcription finishes.
+    Queue,
+    /// Abort the transcription, discard its text and start recording.
+    CancelAndRestart,
+}
+
+impl WhileBusy {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            WhileBusy::Reject => "Ignore the press",
+            WhileBusy::Queue => "Record when done",
+            WhileBusy::CancelAndRestart => "Cancel and record again",
+        }
+    }
+}
+
+/// Where hotkey dictations are transcribed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendKind {
+    /// whisper.cpp on this machine.
+    #[default]
+    Local,
+    /// OpenAI's speech-to-text API. Sends the audio to OpenAI.
+    OpenAi,
+    /// Azure AI Speech in `azure_region`. Sends the audio to that resource.
+    Azure,
+}
+
+impl BackendKind {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            BackendKind::Local => "On this computer (Whisper)",
+            BackendKind::OpenAi => "OpenAI API",
+            BackendKind::Azure => "Azure Speech",
+        }
+    }
+}
+
+/// An optional entry in the tray menu. "Quit" is always shown, so the app
+/// can be closed even with every other item hidden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TrayMenuItem {
+    /// The disabled status line ("Sagascript - Idle", last transcription).
+    Status,
+    /// "Open Sagascript..."
+    Settings,
+    /// "Transcribe File..."
+    TranscribeFile,
+    /// "Record System Audio", a check item switching `capture_source`
+    CaptureSource,
+    /// "Install Command Line Tool..." (not shown on Windows)
+    InstallCli,
+}
+
+impl TrayMenuItem {
+    pub const ALL: [TrayMenuItem; 5] = [
+        TrayMenuItem::Status,
+        TrayMenuItem::Settings,
+        TrayMenuItem::TranscribeFile,
+        TrayMenuItem::CaptureSource,
+        TrayMenuItem::InstallCli,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrayMenuItem::Status => "status",
+            TrayMenuItem::Settings => "settings",
+            TrayMenuItem::TranscribeFile => "transcribe-file",
+            TrayMenuItem::CaptureSource => "capture-source",
+            TrayMenuItem::InstallCli => "install-cli",
+        }
+    }
+}
+
+/// Parse a comma-separated tray menu list, as stored by `config set
+/// tray_menu`. Order is ignored (the menu order is fixed) and duplicates are
+/// dropped; an empty string leaves only "Quit".
+pub fn parse_tray_menu(value: &str) -> Result<Vec<TrayMenuItem>, String> {
+    let mut items = Vec::new();
+    for name in value.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let item = TrayMenuItem::ALL
+            .into_iter()
+            .find(|i| i.as_str().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                format!(
+                    "unknown tray menu item '{name}' \
+                     (expected status, settings, transcribe-file, capture-source, install-cli)"
+                )
+            })?;
+        if !items.contains(&item) {
+            items.push(item);
+        }
+    }
+    items.sort_by_key(|i| TrayMenuItem::ALL.iter().position(|a| a == i));
+    Ok(items)
+}
+
+/// A place in the main window that `open_settings_tab` and the settings
+/// hotkey can open directly. The first three are the window's tabs; the rest
+/// are sections within them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SettingsTab {
+    Dictate,
+    Transcribe,
+    #[default]
+    Settings,
+    /// The model list on the Settings tab.
+    Models,
+    /// Recent sessions on the Dictate tab.
+    History,
+    /// The "Try it out" mic test on the Dictate tab.
+    Diagnostics,
+}
+
+impl SettingsTab {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SettingsTab::Dictate => "dictate",
+            SettingsTab::Transcribe => "transcribe",
+            SettingsTab::Settings => "settings",
+            SettingsTab::Models => "models",
+            SettingsTab::History => "history",
+            SettingsTab::Diagnostics => "diagnostics",
+        }
+    }
+}
+
+/// Format tray menu items in the syntax accepted by [`parse_tray_menu`].
+pub fn format_tray_menu(items: &[TrayMenuItem]) -> String {
+    items
+        .iter()
+        .map(|i| i.as_str())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Highest `monitor_volume` (linear gain). Above unity so a quiet microphone
+/// can still be heard; anything louder just clips.
+pub const MAX_MONITOR_VOLUME: f32 = 2.0;
+
+/// Highest `max_recording_minutes`: two hours is about 1.4 GB of buffer at
+/// 48 kHz.
+pub const MAX_RECORDING_MINUTES: u32 = 120;
+
+/// Highest `silence_stop_secs`. Longer pauses than this are better served by
+/// stopping by hand.
+pub const MAX_SILENCE_STOP_SECS: u32 = 60;
+
+/// Per-model decoding tweaks, merged over the built-in defaults at
+/// transcription time. Unset fields keep the default: the model's own
+/// no-speech threshold and the global `beam_size`/`temperature_fallback`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelOverrides {
+    /// Segments whisper rates above this no-speech probability are dropped
+    /// (0.0 disables the filter). See [`WhisperModel::no_speech_threshold`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_speech_threshold: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub beam_size: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature_fallback: Option<bool>,
+}
+
+impl ModelOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Reject values whisper would misbehave on.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(t) = self.no_speech_threshold {
+            if !(0.0..=1.0).contains(&t) {
+                return Err(format!("no_speech_threshold must be between 0.0 and 1.0, got {t}"));
+            }
+        }
+        if let Some(b) = self.beam_size {
+            if b > 8 {
+                return Err(format!("beam_size must be between 0 and 8, got {b}"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What a model's file and the runtime must match before the model is
+/// loaded, for managed machines that must not pick up a different model
+/// revision or run a model on a whisper.cpp build too old for it. Unset
+/// fields aren't checked.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelPin {
+    /// SHA-256 the GGML file must have. The built-in checksum is always
+    /// verified; a pin additionally refuses any other revision of the model.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Oldest whisper-rs-sys version (which fixes the bundled whisper.cpp
+    /// sources, see `sagascript runtime`) the model may run on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_runtime: Option<String>,
+}
+
+impl ModelPin {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Reject values no file or runtime could match.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(sha) = &self.sha256 {
+            if sha.len() != 64 || !sha.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(format!("sha256 must be 64 hex digits, got '{sha}'"));
+            }
+        }
+        if let Some(version) = &self.min_runtime {
+            if parse_version(version).is_none() {
+                return Err(format!(
+                    "min_runtime must be a version like 0.15.1, got '{version}'"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the pin against the SHA-256 `file_sha256` the model's file was
+    /// verified to have and the `runtime` whisper-rs-sys version.
+    pub fn check(&self, file_sha256: &str, runtime: &str) -> Result<(), String> {
+        if let Some(sha) = &self.sha256 {
+            if !sha.eq_ignore_ascii_case(file_sha256) {
+                return Err(format!(
+                    "the file is SHA-256 {file_sha256}, but the model is pinned to {sha}"
+                ));
+            }
+        }
+        if let Some(min) = &self.min_runtime {
+            let too_old = match (parse_version(runtime), parse_version(min)) {
+                (Some(runtime), Some(min)) => runtime < min,
+                _ => true,
+            };
+            if too_old {
+                return Err(format!(
+                    "it needs whisper-rs-sys {min} or newer, this build has {runtime}"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A dotted numeric version (`0.15.1`) as comparable parts; a pre-release
+/// or build suffix on the last part is ignored.
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let core = version.trim().split(['-', '+']).next()?;
+    core.split('.').map(|part| part.parse().ok()).collect()
+}
+
 /// All user-configurable settings, persisted as JSON
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -452,7 +737,6 @@ pub struct Settings {
     pub whisper_model: WhisperModel,
     pub hotkey_mode: HotkeyMode,
     pub show_overlay: bool,
-    pub auto_paste: bool,
     pub auto_select_model: bool,
     /// Hotkey shortcut string (e.g. "Control+Shift+Space")
     pub hotkey: String,
@@ -468,6 +752,211 @@ pub struct Settings {
     /// Skip non-speech regions with Silero VAD (reduces silence hallucination
     /// and speeds up clips with leading/trailing silence). Needs the VAD model.
     pub vad_enabled: bool,
+    /// Pick the model per clip from its length: short dictation uses a fast
+    /// model, long recordings and files an accurate one. Clips in between use
+    /// the normal selection.
+    pub adaptive_model: bool,
+    /// Clips shorter than this many seconds count as short in adaptive mode.
+    pub adaptive_short_clip_secs: u32,
+    /// Clips at least this many seconds long count as long in adaptive mode.
+    pub adaptive_long_clip_secs: u32,
+    /// Collect successive hotkey dictations in a buffer (shown in the overlay)
+    /// instead of pasting each one; the combined text is pasted on flush.
+    pub accumulate_mode: bool,
+    /// Shortcut that flushes the accumulate-mode buffer. Only registered while
+    /// `accumulate_mode` is on.
+    pub flush_hotkey: String,
+    /// Ending a dictation with this phrase flushes the accumulate-mode buffer
+    /// (case and punctuation are ignored). Empty disables the voice command.
+    pub flush_phrase: String,
+    /// Where transcriptions are sent: pasted into the focused app, the
+    /// clipboard, a file, a webhook and so on. All entries apply to every
+    /// dictation. Settings files from before the table keep their
+    /// `auto_paste` choice as a `paste` entry (see `store`).
+    pub output_sinks: Vec<OutputSink>,
+    /// Recently used initial prompts, newest first. Updated whenever
+    /// `initial_prompt` is set (see `prompt_history::remember_prompt`).
+    pub prompt_history: Vec<String>,
+    /// The same, per dictation profile, keyed by the profile's lowercased
+    /// name. Profiles without an entry offer `prompt_history` instead.
+    pub profile_prompt_history: BTreeMap<String, Vec<String>>,
+    /// Check HuggingFace once a day for newer revisions of the downloaded
+    /// models and tell the user. Off by default: it is network traffic the
+    /// user didn't ask for. `sagascript update-models` checks on demand.
+    pub model_update_check: bool,
+    /// Decoding overrides keyed by model ID (e.g. `"small.en"`), for tuning a
+    /// model to a difficult microphone. Models without an entry use the defaults.
+    pub model_overrides: BTreeMap<String, ModelOverrides>,
+    /// Checksum and runtime pins keyed by model ID (`sagascript config
+    /// model-pin`). A pinned model that doesn't match is not loaded.
+    pub model_pins: BTreeMap<String, ModelPin>,
+    /// Local GGML files registered by name (`sagascript models add`), such
+    /// as fine-tunes that aren't in the catalog.
+    pub custom_models: BTreeMap<String, PathBuf>,
+    /// Registered custom model to transcribe with instead of the built-in
+    /// one. Empty = the built-in model (`whisper_model` or auto-selection).
+    pub custom_model: String,
+    /// Two-stage dictation: paste a draft from the language's tiny model as
+    /// soon as it is ready, then transcribe with the selected model. Local
+    /// models only.
+    pub draft_pass: bool,
+    /// With `draft_pass`, replace the pasted draft with the final text when
+    /// it differs. Uses the accessibility API, so it is macOS only; elsewhere
+    /// (or when the draft was edited) the draft is left as it is.
+    pub replace_draft: bool,
+    /// Skip a hotkey dictation whose audio is a near-exact repeat of the one
+    /// just before it, so an accidental double press doesn't paste the same
+    /// sentence twice.
+    pub dedupe_dictations: bool,
+    /// Play the microphone back through the default output device while
+    /// recording, so levels can be checked on headphones. Off by default: on
+    /// speakers it feeds back.
+    pub monitor_input: bool,
+    /// Linear gain for input monitoring, `0.0..=MAX_MONITOR_VOLUME`.
+    pub monitor_volume: f32,
+    /// Retry files symphonia can't decode through a user-installed ffmpeg
+    /// (`SAGASCRIPT_FFMPEG`, `PATH`, or Homebrew's prefixes). Off by default;
+    /// ffmpeg is never bundled.
+    pub ffmpeg_fallback: bool,
+    /// What a hotkey press does while the previous dictation is still
+    /// transcribing.
+    pub while_busy: WhileBusy,
+    /// Words to bias decoding toward, token by token (call signs, SKUs,
+    /// medication names). Stronger than `initial_prompt`, which only primes
+    /// context. Empty = no biasing.
+    pub keywords: Vec<String>,
+    /// Write spoken numbers, decimals and dates the way the dictation language
+    /// does ("tjugofem komma fem" → "25,5"). Off by default: it rewrites the
+    /// transcript.
+    pub localize_numbers: bool,
+    /// Type spoken punctuation and line breaks in dictations: "comma",
+    /// "new line", "open quote" become `,`, a line break and `"`. Off by
+    /// default, as some commands ("period") are also ordinary words.
+    pub voice_commands: bool,
+    /// Keep whisper's punctuation in dictations. Off strips it, for
+    /// command-style dictation into terminals; spoken punctuation
+    /// (`voice_commands`) is still typed. File transcripts always keep it.
+    pub punctuate: bool,
+    /// Find-and-replace rules run over every finished transcript, in order
+    /// (`sagascript config rules`). Empty = none.
+    pub replacement_rules: Vec<ReplacementRule>,
+    /// Pass each dictation through a language model before it is pasted: a
+    /// shell command, or the URL of an OpenAI-compatible chat completions
+    /// endpoint. Empty = off. See [`crate::llm`].
+    pub post_process_command: String,
+    /// What the model is asked to do with the dictation. Empty = the
+    /// built-in cleanup prompt ([`crate::llm::DEFAULT_PROMPT`]).
+    pub post_process_prompt: String,
+    /// Model name sent to a `post_process_command` endpoint (`llama3.2`,
+    /// `gpt-4o-mini`). Unused for commands.
+    pub post_process_model: String,
+    /// Transcribe dictations locally or with a cloud API. File transcription
+    /// always runs locally. See [`crate::transcription::backend`].
+    pub transcription_backend: BackendKind,
+    /// Model asked for by the OpenAI backend (`whisper-1`,
+    /// `gpt-4o-transcribe`).
+    pub openai_model: String,
+    /// Region of the Azure Speech resource the Azure backend uses
+    /// (`westeurope`).
+    pub azure_region: String,
+    /// Port of the local server-sent events stream external tools can
+    /// follow (`GET /events` on 127.0.0.1, with the bearer token in the app
+    /// data directory); 0 turns it off. Read when the app starts.
+    pub event_stream_port: u16,
+    /// Run local Whisper inference on the GPU (Metal on macOS, CUDA in
+    /// `cuda` builds) when the build has one. Off = CPU only.
+    pub use_gpu: bool,
+    /// Reformat auto-pasted text for the frontmost app: one line without a
+    /// trailing period in terminals, paragraph breaks kept in mail clients
+    /// (see `paste_format`). Detection is local. Off by default, so paste
+    /// keeps inserting exactly what was transcribed until the user opts in.
+    pub paste_formatting: bool,
+    /// Paste format overrides keyed by app (bundle ID, executable or WM_CLASS,
+    /// or display name). Checked before the built-in table of terminals and
+    /// mail clients.
+    pub paste_app_rules: BTreeMap<String, AppCategory>,
+    /// Show the menu-bar / system-tray icon. With it hidden the app is reached
+    /// through the hotkey, by launching it again (macOS), or with the CLI.
+    pub tray_visible: bool,
+    /// Show the state next to the tray icon ("Rec", "...") and the last
+    /// transcription in the tray tooltip and status item. A broken hotkey is
+    /// shown either way.
+    pub tray_status_text: bool,
+    /// Optional tray menu entries, shown in a fixed order above "Quit".
+    pub tray_menu: Vec<TrayMenuItem>,
+    /// Count dictations, models used and error kinds into a local queue
+    /// (see `telemetry`). Never text. Off by default; nothing is sent
+    /// until the user runs `sagascript telemetry send`.
+    pub telemetry: bool,
+    /// Where `sagascript telemetry send` uploads the queue. Empty means
+    /// nowhere.
+    pub telemetry_endpoint: String,
+    /// Proxy for model downloads, the cloud backends, update checks and
+    /// telemetry (`http://proxy.corp:3128`, `socks5h://127.0.0.1:1080`).
+    /// Empty uses `HTTPS_PROXY` and friends from the environment, if set.
+    /// See [`crate::net`].
+    pub network_proxy: String,
+    /// Base URL of a Hugging Face mirror (`http://models.lan/hf`). Models
+    /// are fetched from `<mirror>/<owner>/<repo>/resolve/...` first, then
+    /// from Hugging Face itself. Empty means no mirror.
+    pub model_mirror: String,
+    /// Download URL per model ID (`sagascript models url`), tried before
+    /// every other source: a local artifact server or a mirror with its own
+    /// layout. The file must still match the model's pinned SHA-256.
+    pub model_urls: BTreeMap<String, String>,
+    /// Run dictations to the end but don't paste them, copy them or send
+    /// them to the output sinks; the text only reaches the history and the
+    /// events. For tuning models and replacement rules without typing into
+    /// the active app.
+    pub dry_run: bool,
+    /// Load and warm the dictation model in the background when the app
+    /// starts, so the first dictation doesn't wait for it. Off leaves the
+    /// memory free until the first dictation loads it. Read at startup.
+    pub preload_model: bool,
+    /// Draw the overlay in solid black and white with a thick border.
+    pub overlay_high_contrast: bool,
+    /// Draw the overlay at 1.5x size.
+    pub overlay_large_text: bool,
+    /// Don't pulse the overlay's recording dot.
+    pub overlay_reduced_motion: bool,
+    /// Announce recording, transcribing and ready to screen readers
+    /// (VoiceOver announcements on macOS).
+    pub announce_state: bool,
+    /// Global shortcut that opens the main window at `settings_hotkey_tab`.
+    /// Empty means none.
+    pub settings_hotkey: String,
+    /// Where `settings_hotkey` opens the main window.
+    pub settings_hotkey_tab: SettingsTab,
+    /// A second dictation hotkey that always toggles, whatever `hotkey_mode`
+    /// says: with the main hotkey on push-to-talk, hold that one for short
+    /// phrases and tap this one for long passages. Empty (the default) for
+    /// none.
+    pub toggle_hotkey: String,
+    /// Longest dictation, in minutes (`1..=MAX_RECORDING_MINUTES`). The
+    /// buffer holds raw audio at the device rate, about 11.5 MB a minute at
+    /// 48 kHz; a dictation that reaches the limit is stopped and transcribed.
+    pub max_recording_minutes: u32,
+    /// In toggle mode, stop and transcribe once this many seconds pass
+    /// without speech after the user has started talking (0: never, the
+    /// default). Capped at `MAX_SILENCE_STOP_SECS`.
+    pub silence_stop_secs: u32,
+    /// Dictation profiles, each started from its own hotkey with its own
+    /// language, model, overlay color and sound cue. See [`Profile`].
+    pub profiles: Vec<Profile>,
+    /// Auto-paste asks for confirmation before pasting a transcript longer
+    /// than this many characters; 0 pastes everything straight away.
+    pub paste_confirm_chars: u32,
+    /// Record from the microphone or from the audio the machine is playing
+    /// (calls, videos).
+    pub capture_source: CaptureSource,
+    /// Keep the input stream open between recordings, discarding what it
+    /// hears, so the hotkey starts capturing instantly. Costs a little idle
+    /// CPU, and the OS shows the microphone as in use.
+    pub standby_stream: bool,
+    /// Transcribe each channel of a file on its own and merge them in time
+    /// order with channel labels, for call recordings with a speaker per
+    /// channel, instead of mixing the channels to mono.
+    pub split_channels: bool,
     /// Whether the user has completed the first-launch onboarding
     #[serde(alias = "hasCompletedOnboarding")]
     pub has_completed_onboarding: bool,
@@ -480,19 +969,120 @@ impl Default for Settings {
             whisper_model: WhisperModel::default(),
             hotkey_mode: HotkeyMode::default(),
             show_overlay: true,
-            auto_paste: true,
             auto_select_model: true,
             hotkey: "Control+Shift+Space".to_string(),
             initial_prompt: String::new(),
             beam_size: 0,
             temperature_fallback: true,
             vad_enabled: false,
+            adaptive_model: false,
+            adaptive_short_clip_secs: 10,
+            adaptive_long_clip_secs: 120,
+            accumulate_mode: false,
+            flush_hotkey: "Control+Shift+Enter".to_string(),
+            flush_phrase: "paste that".to_string(),
+            output_sinks: vec![OutputSink::Paste],
+            prompt_history: Vec::new(),
+            profile_prompt_history: BTreeMap::new(),
+            model_update_check: false,
+            model_overrides: BTreeMap::new(),
+            model_pins: BTreeMap::new(),
+            custom_models: BTreeMap::new(),
+            custom_model: String::new(),
+            draft_pass: false,
+            replace_draft: true,
+            dedupe_dictations: true,
+            monitor_input: false,
+            monitor_volume: 0.5,
+            ffmpeg_fallback: false,
+            while_busy: WhileBusy::default(),
+            keywords: Vec::new(),
+            localize_numbers: false,
+            voice_commands: false,
+            punctuate: true,
+            replacement_rules: Vec::new(),
+            post_process_command: String::new(),
+            post_process_prompt: String::new(),
+            post_process_model: String::new(),
+            transcription_backend: BackendKind::Local,
+            openai_model: "whisper-1".to_string(),
+            azure_region: String::new(),
+            event_stream_port: 0,
+            use_gpu: true,
+            paste_formatting: false,
+            paste_app_rules: BTreeMap::new(),
+            tray_visible: true,
+            tray_status_text: true,
+            tray_menu: TrayMenuItem::ALL.to_vec(),
+            telemetry: false,
+            telemetry_endpoint: String::new(),
+            network_proxy: String::new(),
+            model_mirror: String::new(),
+            model_urls: BTreeMap::new(),
+            dry_run: false,
+            preload_model: true,
+            overlay_high_contrast: false,
+            overlay_large_text: false,
+            overlay_reduced_motion: false,
+            announce_state: false,
+            settings_hotkey: String::new(),
+            settings_hotkey_tab: SettingsTab::Settings,
+            toggle_hotkey: String::new(),
+            max_recording_minutes: 15,
+            silence_stop_secs: 0,
+            profiles: Vec::new(),
+            paste_confirm_chars: 0,
+            capture_source: CaptureSource::Microphone,
+            standby_stream: false,
+            split_channels: false,
             has_completed_onboarding: false,
         }
     }
 }
 
 impl Settings {
+    /// The onboarding preset: dictate in `language` with its recommended
+    /// model, and keep following the recommendation if the language changes.
+    /// Returns the model to download.
+    pub fn apply_recommended(&mut self, language: Language) -> WhisperModel {
+        self.language = language;
+        self.whisper_model = WhisperModel::recommended(language);
+        self.auto_select_model = true;
+        self.whisper_model
+    }
+
+    /// [`Self::apply_recommended`] with a model picked for this machine (see
+    /// `transcription::recommend`): the language's usual recommendation
+    /// stays auto-selected, any other model is selected as is.
+    pub fn apply_recommendation(
+        &mut self,
+        language: Language,
+        model: WhisperModel,
+    ) -> WhisperModel {
+        if self.apply_recommended(language) != model {
+            self.whisper_model = model;
+            self.auto_select_model = false;
+        }
+        self.whisper_model
+    }
+
+    /// These settings with a one-off `language` and/or `model` in place of
+    /// the saved ones, for a single transcription. A language on its own
+    /// keeps model auto-selection working for it; an explicit model is used
+    /// as is, whatever the clip length.
+    pub fn with_overrides(&self, language: Option<Language>, model: Option<WhisperModel>) -> Settings {
+        let mut settings = self.clone();
+        if let Some(language) = language {
+            settings.language = language;
+        }
+        if let Some(model) = model {
+            settings.whisper_model = model;
+            settings.auto_select_model = false;
+            settings.adaptive_model = false;
+        }
+        settings
+    }
+
     /// Returns the effective model considering auto-selection
     pub fn effective_model(&self) -> WhisperModel {
         if self.auto_select_model {
@@ -501,6 +1091,97 @@ impl Settings {
             self.whisper_model
         }
     }
+
+    /// File of the selected custom model (`custom_model`), if one is
+    /// selected and registered.
+    pub fn custom_model_path(&self) -> Option<PathBuf> {
+        self.custom_models.get(self.custom_model.trim()).cloned()
+    }
+
+    /// Whether dictations and transcriptions currently use built-in `model`.
+    pub fn uses_model(&self, model: WhisperModel) -> bool {
+        self.custom_model_path().is_none() && self.effective_model() == model
+    }
+
+    /// How long a dictation may run, with `max_recording_minutes` clamped to
+    /// `1..=MAX_RECORDING_MINUTES` for hand-edited settings files.
+    pub fn max_recording(&self) -> Duration {
+        let minutes = self.max_recording_minutes.clamp(1, MAX_RECORDING_MINUTES);
+        Duration::from_secs(u64::from(minutes) * 60)
+    }
+
+    /// How much silence ends a toggle-mode dictation, if any, with
+    /// `silence_stop_secs` capped at `MAX_SILENCE_STOP_SECS`.
+    pub fn silence_stop(&self) -> Option<Duration> {
+        (self.silence_stop_secs > 0).then(|| {
+            Duration::from_secs(u64::from(self.silence_stop_secs.min(MAX_SILENCE_STOP_SECS)))
+        })
+    }
+
+    /// Whether finished dictations are pasted: the output sinks include
+    /// `paste` and this isn't a `dry_run`.
+    pub fn auto_pastes(&self) -> bool {
+        output::wants_paste(&self.output_sinks) && !self.dry_run
+    }
+
+    /// Turn auto-paste on or off by adding the `paste` sink at the front of
+    /// the table or removing it.
+    pub fn set_auto_paste(&mut self, enabled: bool) {
+        if !enabled {
+            self.output_sinks.retain(|sink| *sink != OutputSink::Paste);
+        } else if !output::wants_paste(&self.output_sinks) {
+            self.output_sinks.insert(0, OutputSink::Paste);
+        }
+    }
+
+    /// Whether the global table or any profile's asks for a paste, which
+    /// needs Accessibility approval on macOS.
+    pub fn requests_paste(&self) -> bool {
+        output::wants_paste(&self.output_sinks)
+            || self
+                .profiles
+                .iter()
+                .any(|p| p.output_sinks.as_deref().is_some_and(output::wants_paste))
+    }
+
+    /// Remove the `paste` sink everywhere, for when pasting isn't allowed.
+    pub fn revoke_paste(&mut self) {
+        self.set_auto_paste(false);
+        for profile in &mut self.profiles {
+            if let Some(sinks) = &mut profile.output_sinks {
+                sinks.retain(|sink| *sink != OutputSink::Paste);
+            }
+        }
+    }
+
+    /// Whether auto-pasting `text` has to wait for the user to confirm it.
+    pub fn paste_needs_confirmation(&self, text: &str) -> bool {
+        self.paste_confirm_chars > 0 && text.chars().count() > self.paste_confirm_chars as usize
+    }
+
+    /// Effective model for a clip of the given length. With `adaptive_model`
+    /// off this is just [`Settings::effective_model`]. With it on, short clips
+    /// never use anything heavier than the recommended model and long clips
+    /// never anything lighter than the accurate one; a model the user picked
+    /// by hand still wins when it is already on the right side.
+    pub fn effective_model_for_clip(&self, clip_secs: f32) -> WhisperModel {
+        let base = self.effective_model();
+        if !self.adaptive_model {
+            return base;
+        }
+        if clip_secs < self.adaptive_short_clip_secs as f32 {
+            let fast = WhisperModel::recommended(self.language);
+            if fast.size_mb() < base.size_mb() {
+                return fast;
+            }
+        } else if clip_secs >= self.adaptive_long_clip_secs as f32 {
+            let accurate = WhisperModel::accurate(self.language);
+            if accurate.size_mb() > base.size_mb() {
+                return accurate;
+            }
+        }
+        base
+    }
 }
 
 #[cfg(test)]
@@ -773,6 +1454,18 @@ mod tests {
         assert_eq!(WhisperModel::recommended(Language::Auto), WhisperModel::Base);
     }
 
+    #[test]
+    fn draft_model_is_the_languages_smallest() {
+        let languages = [Language::English, Language::Swedish, Language::Norwegian, Language::Auto];
+        for lang in languages {
+            let draft = WhisperModel::draft(lang);
+            assert!(WhisperModel::models_for_language(lang).contains(&draft));
+            assert!(WhisperModel::models_for_language(lang)
+                .iter()
+                .all(|m| m.size_mb() >= draft.size_mb()));
+        }
+    }
+
     #[test]
     fn models_for_language_returns_correct_sets() {
         let en = WhisperModel::models_for_language(Language::English);
@@ -808,6 +1501,35 @@ mod tests {
         assert!(auto.contains(&WhisperModel::LargeV3TurboQ8));
     }
 
+    #[test]
+    fn smaller_steps_down_within_family() {
+        assert_eq!(WhisperModel::MediumEn.smaller(), Some(WhisperModel::SmallEn));
+        assert_eq!(WhisperModel::BaseEn.smaller(), Some(WhisperModel::TinyEn));
+        assert_eq!(
+            WhisperModel::KbWhisperSmall.smaller(),
+            Some(WhisperModel::KbWhisperBase)
+        );
+        assert_eq!(
+            WhisperModel::NbWhisperLarge.smaller(),
+            Some(WhisperModel::NbWhisperMedium)
+        );
+        assert_eq!(WhisperModel::Medium.smaller(), Some(WhisperModel::Small));
+    }
+
+    #[test]
+    fn smaller_skips_entries_that_are_not_lighter() {
+        // q8 turbo is smaller on disk than both turbo and medium
+        assert_eq!(WhisperModel::LargeV3TurboQ8.smaller(), Some(WhisperModel::Small));
+    }
+
+    #[test]
+    fn smaller_is_none_for_smallest_model() {
+        assert_eq!(WhisperModel::TinyEn.smaller(), None);
+        assert_eq!(WhisperModel::Tiny.smaller(), None);
+        assert_eq!(WhisperModel::KbWhisperTiny.smaller(), None);
+        assert_eq!(WhisperModel::NbWhisperTiny.smaller(), None);
+    }
+
     #[test]
     fn whisper_model_serde_roundtrip() {
         let model = WhisperModel::KbWhisperSmall;
@@ -862,6 +1584,27 @@ mod tests {
         assert_eq!(HotkeyMode::Toggle.display_name(), "Toggle");
     }
 
+    #[test]
+    fn while_busy_default_and_serde() {
+        assert_eq!(WhileBusy::default(), WhileBusy::Reject);
+        assert_eq!(
+            serde_json::to_string(&WhileBusy::CancelAndRestart).unwrap(),
+            "\"cancel-and-restart\""
+        );
+        let parsed: WhileBusy = serde_json::from_str("\"queue\"").unwrap();
+        assert_eq!(parsed, WhileBusy::Queue);
+    }
+
+    #[test]
+    fn capture_source_serde() {
+        assert_eq!(
+            serde_json::to_string(&CaptureSource::System).unwrap(),
+            "\"system\""
+        );
+        let parsed: CaptureSource = serde_json::from_str("\"microphone\"").unwrap();
+        assert_eq!(parsed, CaptureSource::Microphone);
+    }
+
     #[test]
     fn hotkey_mode_serde() {
         let json = serde_json::to_string(&HotkeyMode::PushToTalk).unwrap();
@@ -872,6 +1615,23 @@ mod tests {
 
     // -- Settings --
 
+    #[test]
+    fn settings_tab_names_match_serde() {
+        for tab in [
+            SettingsTab::Dictate,
+            SettingsTab::Transcribe,
+            SettingsTab::Settings,
+            SettingsTab::Models,
+            SettingsTab::History,
+            SettingsTab::Diagnostics,
+        ] {
+            assert_eq!(
+                serde_json::to_value(tab).unwrap(),
+                serde_json::json!(tab.as_str())
+            );
+        }
+    }
+
     #[test]
     fn settings_default_values() {
         let s = Settings::default();
@@ -879,13 +1639,72 @@ mod tests {
         assert_eq!(s.whisper_model, WhisperModel::Base);
         assert_eq!(s.hotkey_mode, HotkeyMode::PushToTalk);
         assert!(s.show_overlay);
-        assert!(s.auto_paste);
+        assert!(s.auto_pastes());
         assert!(s.auto_select_model);
         assert_eq!(s.hotkey, "Control+Shift+Space");
         assert_eq!(s.initial_prompt, "");
         assert_eq!(s.beam_size, 0);
         assert!(s.temperature_fallback);
         assert!(!s.vad_enabled);
+        assert!(!s.adaptive_model);
+        assert_eq!(s.adaptive_short_clip_secs, 10);
+        assert_eq!(s.adaptive_long_clip_secs, 120);
+        assert!(!s.accumulate_mode);
+        assert_eq!(s.flush_hotkey, "Control+Shift+Enter");
+        assert_eq!(s.flush_phrase, "paste that");
+        assert_eq!(s.output_sinks, vec![OutputSink::Paste]);
+        assert!(s.prompt_history.is_empty());
+        assert!(!s.model_update_check);
+        assert!(s.model_overrides.is_empty());
+        assert!(s.model_pins.is_empty());
+        assert!(s.custom_models.is_empty());
+        assert!(s.custom_model.is_empty());
+        assert!(!s.draft_pass);
+        assert!(s.replace_draft);
+        assert!(s.dedupe_dictations);
+        assert!(!s.monitor_input);
+        assert_eq!(s.monitor_volume, 0.5);
+        assert!(!s.ffmpeg_fallback);
+        assert_eq!(s.while_busy, WhileBusy::Reject);
+        assert!(s.keywords.is_empty());
+        assert!(!s.localize_numbers);
+        assert!(!s.voice_commands);
+        assert!(s.punctuate);
+        assert!(s.replacement_rules.is_empty());
+        assert!(s.post_process_command.is_empty());
+        assert!(s.post_process_prompt.is_empty());
+        assert!(s.post_process_model.is_empty());
+        assert_eq!(s.transcription_backend, BackendKind::Local);
+        assert_eq!(s.openai_model, "whisper-1");
+        assert!(s.azure_region.is_empty());
+        assert_eq!(s.event_stream_port, 0);
+        assert!(s.use_gpu);
+        assert!(!s.paste_formatting);
+        assert!(s.paste_app_rules.is_empty());
+        assert!(s.tray_visible);
+        assert!(s.tray_status_text);
+        assert_eq!(s.tray_menu, TrayMenuItem::ALL.to_vec());
+        assert!(!s.telemetry);
+        assert!(s.telemetry_endpoint.is_empty());
+        assert!(s.network_proxy.is_empty());
+        assert!(s.model_mirror.is_empty());
+        assert!(s.model_urls.is_empty());
+        assert!(!s.dry_run);
+        assert!(s.preload_model);
+        assert!(!s.overlay_high_contrast);
+        assert!(!s.overlay_large_text);
+        assert!(!s.overlay_reduced_motion);
+        assert!(!s.announce_state);
+        assert!(s.settings_hotkey.is_empty());
+        assert_eq!(s.settings_hotkey_tab, SettingsTab::Settings);
+        assert!(s.toggle_hotkey.is_empty());
+        assert_eq!(s.max_recording_minutes, 15);
+        assert_eq!(s.silence_stop_secs, 0);
+        assert!(s.profiles.is_empty());
+        assert_eq!(s.paste_confirm_chars, 0);
+        assert_eq!(s.capture_source, CaptureSource::Microphone);
+        assert!(!s.standby_stream);
+        assert!(!s.split_channels);
     }
 
     #[test]
@@ -912,6 +1731,76 @@ mod tests {
         assert_eq!(s.effective_model(), WhisperModel::KbWhisperSmall);
     }
 
+    #[test]
+    fn with_overrides_only_replaces_what_is_given() {
+        let s = Settings {
+            adaptive_model: true,
+            ..Default::default()
+        };
+        let same = s.with_overrides(None, None);
+        assert_eq!(same.language, s.language);
+        assert!(same.auto_select_model && same.adaptive_model);
+
+        let norwegian = s.with_overrides(Some(Language::Norwegian), None);
+        assert_eq!(norwegian.language, Language::Norwegian);
+        assert_eq!(norwegian.effective_model(), WhisperModel::recommended(Language::Norwegian));
+
+        let pinned = s.with_overrides(None, Some(WhisperModel::SmallEn));
+        assert_eq!(pinned.language, Language::English);
+        assert_eq!(pinned.effective_model_for_clip(2.0), WhisperModel::SmallEn);
+        assert_eq!(s.effective_model(), WhisperModel::BaseEn, "original untouched");
+    }
+
+    #[test]
+    fn a_selected_custom_model_frees_the_built_in_one() {
+        let mut s = Settings::default();
+        assert!(s.uses_model(WhisperModel::BaseEn));
+        assert!(!s.uses_model(WhisperModel::SmallEn));
+        s.custom_models.insert("tuned".to_string(), PathBuf::from("/models/tuned.bin"));
+        s.custom_model = "tuned".to_string();
+        assert!(!s.uses_model(WhisperModel::BaseEn));
+    }
+
+    #[test]
+    fn effective_model_for_clip_ignores_length_when_not_adaptive() {
+        let s = Settings::default();
+        assert_eq!(s.effective_model_for_clip(2.0), WhisperModel::BaseEn);
+        assert_eq!(s.effective_model_for_clip(600.0), WhisperModel::BaseEn);
+    }
+
+    #[test]
+    fn effective_model_for_clip_adaptive_thresholds() {
+        let s = Settings {
+            adaptive_model: true,
+            language: Language::Swedish,
+            ..Default::default()
+        };
+        assert_eq!(s.effective_model_for_clip(3.0), WhisperModel::KbWhisperBase);
+        assert_eq!(s.effective_model_for_clip(30.0), WhisperModel::KbWhisperBase);
+        assert_eq!(s.effective_model_for_clip(120.0), WhisperModel::KbWhisperSmall);
+    }
+
+    #[test]
+    fn effective_model_for_clip_adaptive_respects_pinned_model() {
+        let s = Settings {
+            adaptive_model: true,
+            auto_select_model: false,
+            whisper_model: WhisperModel::MediumEn,
+            ..Default::default()
+        };
+        // Short clips step down to the fast model...
+        assert_eq!(s.effective_model_for_clip(4.0), WhisperModel::BaseEn);
+        // ...but a pinned model already heavier than "accurate" is kept for long ones
+        assert_eq!(s.effective_model_for_clip(300.0), WhisperModel::MediumEn);
+
+        let tiny = Settings {
+            whisper_model: WhisperModel::TinyEn,
+            ..s
+        };
+        assert_eq!(tiny.effective_model_for_clip(4.0), WhisperModel::TinyEn);
+        assert_eq!(tiny.effective_model_for_clip(300.0), WhisperModel::SmallEn);
+    }
+
     #[test]
     fn settings_serde_roundtrip() {
         let original = Settings::default();
@@ -921,13 +1810,83 @@ mod tests {
         assert_eq!(deserialized.whisper_model, original.whisper_model);
         assert_eq!(deserialized.hotkey_mode, original.hotkey_mode);
         assert_eq!(deserialized.show_overlay, original.show_overlay);
-        assert_eq!(deserialized.auto_paste, original.auto_paste);
         assert_eq!(deserialized.auto_select_model, original.auto_select_model);
         assert_eq!(deserialized.hotkey, original.hotkey);
         assert_eq!(deserialized.initial_prompt, original.initial_prompt);
         assert_eq!(deserialized.beam_size, original.beam_size);
         assert_eq!(deserialized.temperature_fallback, original.temperature_fallback);
         assert_eq!(deserialized.vad_enabled, original.vad_enabled);
+        assert_eq!(deserialized.adaptive_model, original.adaptive_model);
+        assert_eq!(deserialized.adaptive_short_clip_secs, original.adaptive_short_clip_secs);
+        assert_eq!(deserialized.adaptive_long_clip_secs, original.adaptive_long_clip_secs);
+        assert_eq!(deserialized.accumulate_mode, original.accumulate_mode);
+        assert_eq!(deserialized.flush_hotkey, original.flush_hotkey);
+        assert_eq!(deserialized.flush_phrase, original.flush_phrase);
+        assert_eq!(deserialized.output_sinks, original.output_sinks);
+        assert_eq!(deserialized.prompt_history, original.prompt_history);
+        assert_eq!(deserialized.model_update_check, original.model_update_check);
+        assert_eq!(deserialized.model_overrides, original.model_overrides);
+        assert_eq!(deserialized.model_pins, original.model_pins);
+        assert_eq!(deserialized.custom_models, original.custom_models);
+        assert_eq!(deserialized.custom_model, original.custom_model);
+        assert_eq!(deserialized.draft_pass, original.draft_pass);
+        assert_eq!(deserialized.replace_draft, original.replace_draft);
+        assert_eq!(deserialized.dedupe_dictations, original.dedupe_dictations);
+        assert_eq!(deserialized.monitor_input, original.monitor_input);
+        assert_eq!(deserialized.monitor_volume, original.monitor_volume);
+        assert_eq!(deserialized.ffmpeg_fallback, original.ffmpeg_fallback);
+        assert_eq!(deserialized.while_busy, original.while_busy);
+        assert_eq!(deserialized.keywords, original.keywords);
+        assert_eq!(deserialized.localize_numbers, original.localize_numbers);
+        assert_eq!(deserialized.voice_commands, original.voice_commands);
+        assert_eq!(deserialized.punctuate, original.punctuate);
+        assert_eq!(deserialized.replacement_rules, original.replacement_rules);
+        assert_eq!(deserialized.post_process_command, original.post_process_command);
+        assert_eq!(deserialized.post_process_prompt, original.post_process_prompt);
+        assert_eq!(deserialized.post_process_model, original.post_process_model);
+        assert_eq!(deserialized.transcription_backend, original.transcription_backend);
+        assert_eq!(deserialized.openai_model, original.openai_model);
+        assert_eq!(deserialized.azure_region, original.azure_region);
+        assert_eq!(deserialized.event_stream_port, original.event_stream_port);
+        assert_eq!(deserialized.use_gpu, original.use_gpu);
+        assert_eq!(deserialized.paste_formatting, original.paste_formatting);
+        assert_eq!(deserialized.paste_app_rules, original.paste_app_rules);
+        assert_eq!(deserialized.tray_visible, original.tray_visible);
+        assert_eq!(deserialized.tray_status_text, original.tray_status_text);
+        assert_eq!(deserialized.tray_menu, original.tray_menu);
+        assert_eq!(deserialized.telemetry, original.telemetry);
+        assert_eq!(deserialized.telemetry_endpoint, original.telemetry_endpoint);
+        assert_eq!(deserialized.network_proxy, original.network_proxy);
+        assert_eq!(deserialized.model_mirror, original.model_mirror);
+        assert_eq!(deserialized.model_urls, original.model_urls);
+        assert_eq!(deserialized.dry_run, original.dry_run);
+        assert_eq!(deserialized.preload_model, original.preload_model);
+        assert_eq!(
+            deserialized.overlay_high_contrast,
+            original.overlay_high_contrast
+        );
+        assert_eq!(deserialized.overlay_large_text, original.overlay_large_text);
+        assert_eq!(
+            deserialized.overlay_reduced_motion,
+            original.overlay_reduced_motion
+        );
+        assert_eq!(deserialized.announce_state, original.announce_state);
+        assert_eq!(deserialized.settings_hotkey, original.settings_hotkey);
+        assert_eq!(
+            deserialized.settings_hotkey_tab,
+            original.settings_hotkey_tab
+        );
+        assert_eq!(deserialized.toggle_hotkey, original.toggle_hotkey);
+        assert_eq!(
+            deserialized.max_recording_minutes,
+            original.max_recording_minutes
+        );
+        assert_eq!(deserialized.silence_stop_secs, original.silence_stop_secs);
+        assert_eq!(deserialized.profiles, original.profiles);
+        assert_eq!(deserialized.paste_confirm_chars, original.paste_confirm_chars);
+        assert_eq!(deserialized.capture_source, original.capture_source);
+        assert_eq!(deserialized.standby_stream, original.standby_stream);
+        assert_eq!(deserialized.split_channels, original.split_channels);
         assert_eq!(
             deserialized.has_completed_onboarding,
             original.has_completed_onboarding
@@ -949,7 +1908,7 @@ mod tests {
         assert_eq!(s.language, Language::Swedish);
         // All other fields should be defaults
         assert_eq!(s.whisper_model, WhisperModel::Base);
-        assert!(s.auto_paste);
+        assert!(s.auto_pastes());
         assert_eq!(s.hotkey, "Control+Shift+Space");
     }
 
@@ -993,6 +1952,183 @@ mod tests {
         }
     }
 
+    #[test]
+    fn model_overrides_serialize_only_set_fields() {
+        let o = ModelOverrides {
+            no_speech_threshold: Some(0.6),
+            ..Default::default()
+        };
+        assert_eq!(serde_json::to_string(&o).unwrap(), r#"{"no_speech_threshold":0.6}"#);
+        let parsed: ModelOverrides = serde_json::from_str(r#"{"beam_size":4}"#).unwrap();
+        assert_eq!(parsed.beam_size, Some(4));
+        assert!(parsed.no_speech_threshold.is_none());
+        assert!(ModelOverrides::default().is_empty());
+    }
+
+    #[test]
+    fn model_overrides_validate_ranges() {
+        let bad_threshold = ModelOverrides {
+            no_speech_threshold: Some(1.5),
+            ..Default::default()
+        };
+        assert!(bad_threshold.validate().is_err());
+        let bad_beam = ModelOverrides {
+            beam_size: Some(20),
+            ..Default::default()
+        };
+        assert!(bad_beam.validate().is_err());
+        let ok = ModelOverrides {
+            no_speech_threshold: Some(0.0),
+            beam_size: Some(5),
+            temperature_fallback: Some(false),
+        };
+        assert!(ok.validate().is_ok());
+    }
+
+    #[test]
+    fn model_pins_check_checksum_and_runtime() {
+        let sha = "a".repeat(64);
+        let pin = ModelPin {
+            sha256: Some(sha.to_uppercase()),
+            min_runtime: Some("0.14.2".to_string()),
+        };
+        assert!(pin.validate().is_ok());
+        assert!(pin.check(&sha, "0.14.2").is_ok());
+        assert!(pin.check(&sha, "0.15.0").is_ok());
+        let old = pin.check(&sha, "0.14.1").unwrap_err();
+        assert!(old.contains("0.14.2 or newer"), "{old}");
+        assert!(pin.check(&sha, "unknown").is_err());
+        let other = pin.check(&"b".repeat(64), "0.15.0").unwrap_err();
+        assert!(other.contains("pinned"), "{other}");
+        assert!(ModelPin::default().check("", "unknown").is_ok());
+
+        let bad = ModelPin {
+            sha256: Some("abc".to_string()),
+            min_runtime: Some("latest".to_string()),
+        };
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn apply_recommended_selects_language_model() {
+        let mut s = Settings {
+            auto_select_model: false,
+            whisper_model: WhisperModel::Small,
+            ..Default::default()
+        };
+        let model = s.apply_recommended(Language::Swedish);
+        assert_eq!(model, WhisperModel::KbWhisperBase);
+        assert_eq!(s.language, Language::Swedish);
+        assert_eq!(s.effective_model(), model);
+        assert!(s.auto_select_model);
+    }
+
+    #[test]
+    fn silence_stop_is_off_by_default_and_capped() {
+        let mut s = Settings::default();
+        assert_eq!(s.silence_stop(), None);
+        s.silence_stop_secs = 4;
+        assert_eq!(s.silence_stop(), Some(Duration::from_secs(4)));
+        s.silence_stop_secs = 10_000;
+        assert_eq!(
+            s.silence_stop(),
+            Some(Duration::from_secs(MAX_SILENCE_STOP_SECS.into()))
+        );
+    }
+
+    #[test]
+    fn long_pastes_need_confirmation_once_a_limit_is_set() {
+        let mut s = Settings::default();
+        assert!(!s.paste_needs_confirmation(&"x".repeat(10_000)));
+        s.paste_confirm_chars = 5;
+        assert!(!s.paste_needs_confirmation("hallå"));
+        assert!(s.paste_needs_confirmation("hallå!"));
+    }
+
+    #[test]
+    fn hardware_recommendation_is_auto_selected_only_when_usual() {
+        let mut s = Settings::default();
+        s.apply_recommendation(Language::Swedish, WhisperModel::KbWhisperBase);
+        assert!(s.auto_select_model);
+        s.apply_recommendation(Language::English, WhisperModel::SmallEn);
+        assert_eq!(s.language, Language::English);
+        assert_eq!(s.effective_model(), WhisperModel::SmallEn);
+        assert!(!s.auto_select_model);
+    }
+
+    #[test]
+    fn dry_run_never_pastes() {
+        let mut s = Settings::default();
+        assert!(s.auto_pastes());
+        s.dry_run = true;
+        assert!(!s.auto_pastes());
+    }
+
+    #[test]
+    fn auto_paste_is_the_paste_sink() {
+        let mut s = Settings {
+            output_sinks: vec![OutputSink::Clipboard],
+            ..Default::default()
+        };
+        assert!(!s.auto_pastes());
+        s.set_auto_paste(true);
+        s.set_auto_paste(true);
+        assert_eq!(
+            s.output_sinks,
+            vec![OutputSink::Paste, OutputSink::Clipboard]
+        );
+        s.set_auto_paste(false);
+        assert_eq!(s.output_sinks, vec![OutputSink::Clipboard]);
+    }
+
+    #[test]
+    fn revoking_paste_covers_the_profiles() {
+        let mut s = Settings {
+            output_sinks: vec![OutputSink::Clipboard],
+            profiles: vec![Profile {
+                name: "Notes".to_string(),
+                output_sinks: Some(vec![OutputSink::Paste, OutputSink::Clipboard]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(!s.auto_pastes());
+        assert!(s.requests_paste());
+        s.revoke_paste();
+        assert!(!s.requests_paste());
+        assert_eq!(
+            s.profiles[0].output_sinks,
+            Some(vec![OutputSink::Clipboard])
+        );
+    }
+
+    #[test]
+    fn max_recording_is_clamped() {
+        let mut s = Settings::default();
+        assert_eq!(s.max_recording(), Duration::from_secs(15 * 60));
+        s.max_recording_minutes = 0;
+        assert_eq!(s.max_recording(), Duration::from_secs(60));
+        s.max_recording_minutes = 10_000;
+        assert_eq!(
+            s.max_recording(),
+            Duration::from_secs(u64::from(MAX_RECORDING_MINUTES) * 60)
+        );
+    }
+
+    #[test]
+    fn tray_menu_parses_in_fixed_order() {
+        assert_eq!(
+            parse_tray_menu("transcribe-file, status,status").unwrap(),
+            vec![TrayMenuItem::Status, TrayMenuItem::TranscribeFile]
+        );
+        assert!(parse_tray_menu("").unwrap().is_empty());
+        assert!(parse_tray_menu("quit").is_err());
+        assert_eq!(
+            format_tray_menu(&TrayMenuItem::ALL),
+            "status,settings,transcribe-file,capture-source,install-cli"
+        );
+    }
+
     #[test]
     fn recommended_model_is_in_models_for_language() {
         let languages = [Language::English, Language::Swedish, Language::Norwegian, Language::Auto];
@@ -1,4 +1,8 @@
 pub mod manager;
+pub mod profiles;
+pub mod prompt_history;
+pub mod schedule;
 pub mod store;
 
 pub use manager::*;
+pub use profiles::{Profile, SoundCue};
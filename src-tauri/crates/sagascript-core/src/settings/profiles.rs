@@ -0,0 +1,281 @@
+//! Dictation profiles: a named language and model, started from their own
+//! hotkey, so switching between e.g. English and Swedish dictation is one
+//! keypress instead of a trip to Settings.
+//!
+//! Each profile can also carry an overlay color and a sound cue, so it's
+//! clear which profile is listening before the first word is spoken, a
+//! schedule that makes it the default at set times (see [`super::schedule`]),
+//! and its own output sinks, e.g. a notes profile that appends to a file
+//! instead of pasting.
+
+use serde::{Deserialize, Serialize};
+
+use super::schedule::Schedule;
+use super::{Language, Settings, WhisperModel};
+use crate::output::OutputSink;
+
+/// Most profiles that can be configured.
+pub const MAX_PROFILES: usize = 9;
+
+/// Tone played when a profile's recording starts. The pitches are far enough
+/// apart to tell profiles apart by ear.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SoundCue {
+    #[default]
+    None,
+    Low,
+    Mid,
+    High,
+}
+
+impl SoundCue {
+    pub const ALL: [SoundCue; 4] = [SoundCue::None, SoundCue::Low, SoundCue::Mid, SoundCue::High];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SoundCue::None => "none",
+            SoundCue::Low => "low",
+            SoundCue::Mid => "mid",
+            SoundCue::High => "high",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|cue| cue.as_str().eq_ignore_ascii_case(s.trim()))
+    }
+
+    /// Pitch of the cue in Hz; `None` for no cue.
+    pub fn frequency(&self) -> Option<f32> {
+        match self {
+            SoundCue::None => None,
+            SoundCue::Low => Some(440.0),
+            SoundCue::Mid => Some(660.0),
+            SoundCue::High => Some(990.0),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    /// Shown in the overlay and used to pick the profile from the CLI.
+    pub name: String,
+    pub language: Language,
+    /// Model to dictate with; `None` follows the language's recommended one.
+    pub whisper_model: Option<WhisperModel>,
+    /// Dictation hotkey for this profile, behaving like the main one under
+    /// `hotkey_mode`. Empty for none (CLI-only profile).
+    pub hotkey: String,
+    /// Overlay accent while recording, as `#rrggbb`. Empty for the default.
+    pub overlay_color: String,
+    pub sound_cue: SoundCue,
+    /// When the profile's language and model become the global ones, e.g.
+    /// `mon-fri 09:00-17:00` or `otherwise`. Empty for never.
+    pub schedule: String,
+    /// Where this profile's dictations go; `None` follows the global
+    /// `output_sinks`.
+    pub output_sinks: Option<Vec<OutputSink>>,
+}
+
+impl Profile {
+    /// Check a profile before it's saved: it needs a name and, if set, a
+    /// `#rrggbb` overlay color. Hotkey syntax and clashes are checked by
+    /// the caller, which knows the other shortcuts.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Profile name can't be empty".to_string());
+        }
+        if !self.overlay_color.is_empty() && !is_hex_color(&self.overlay_color) {
+            return Err(format!(
+                "Overlay color must look like #3a7bd5, got '{}'",
+                self.overlay_color
+            ));
+        }
+        Schedule::parse(&self.schedule)?;
+        Ok(())
+    }
+
+    /// The configured hotkey, if any.
+    pub fn shortcut(&self) -> Option<&str> {
+        let hotkey = self.hotkey.trim();
+        (!hotkey.is_empty()).then_some(hotkey)
+    }
+}
+
+fn is_hex_color(s: &str) -> bool {
+    s.strip_prefix('#')
+        .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Check a whole profile list: each profile is valid, and names and hotkeys
+/// are unique (ignoring case).
+pub fn validate_profiles(profiles: &[Profile]) -> Result<(), String> {
+    if profiles.len() > MAX_PROFILES {
+        return Err(format!("At most {MAX_PROFILES} profiles can be set up"));
+    }
+    for (i, profile) in profiles.iter().enumerate() {
+        profile.validate()?;
+        let earlier = &profiles[..i];
+        if earlier
+            .iter()
+            .any(|p| p.name.trim().eq_ignore_ascii_case(profile.name.trim()))
+        {
+            return Err(format!(
+                "There is already a profile named '{}'",
+                profile.name
+            ));
+        }
+        if let Some(hotkey) = profile.shortcut() {
+            if earlier
+                .iter()
+                .any(|p| p.shortcut().is_some_and(|h| h.eq_ignore_ascii_case(hotkey)))
+            {
+                return Err(format!("Hotkey '{hotkey}' is used by two profiles"));
+            }
+        }
+    }
+    Ok(())
+}
+
+impl Settings {
+    /// Find a profile by name, ignoring case.
+    pub fn profile(&self, name: &str) -> Option<&Profile> {
+        self.profiles
+            .iter()
+            .find(|p| p.name.trim().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// These settings with `profile`'s language, model and output sinks in
+    /// place of the global ones, for a dictation started with that profile.
+    pub fn with_profile(&self, profile: &Profile) -> Settings {
+        let mut settings = self.clone();
+        settings.apply_profile(profile);
+        if let Some(sinks) = &profile.output_sinks {
+            settings.output_sinks = sinks.clone();
+        }
+        settings
+    }
+
+    /// Make `profile`'s language and model the global ones, as its schedule
+    /// does.
+    pub fn apply_profile(&mut self, profile: &Profile) {
+        self.language = profile.language;
+        match profile.whisper_model {
+            Some(model) => {
+                self.whisper_model = model;
+                self.auto_select_model = false;
+            }
+            None => self.auto_select_model = true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, hotkey: &str) -> Profile {
+        Profile {
+            name: name.to_string(),
+            hotkey: hotkey.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn profile_overrides_language_and_model() {
+        let settings = Settings::default();
+        let swedish = Profile {
+            language: Language::Swedish,
+            ..profile("Svenska", "")
+        };
+        let applied = settings.with_profile(&swedish);
+        assert_eq!(applied.language, Language::Swedish);
+        assert_eq!(
+            applied.effective_model(),
+            WhisperModel::recommended(Language::Swedish)
+        );
+
+        let pinned = Profile {
+            whisper_model: Some(WhisperModel::BaseEn),
+            ..profile("Fast", "")
+        };
+        assert_eq!(
+            settings.with_profile(&pinned).effective_model(),
+            WhisperModel::BaseEn
+        );
+    }
+
+    #[test]
+    fn profile_sinks_replace_the_global_ones() {
+        let settings = Settings::default();
+        let follows = profile("Work", "");
+        assert_eq!(
+            settings.with_profile(&follows).output_sinks,
+            settings.output_sinks
+        );
+
+        let notes = Profile {
+            output_sinks: Some(vec![OutputSink::Clipboard]),
+            ..profile("Notes", "")
+        };
+        let applied = settings.with_profile(&notes);
+        assert_eq!(applied.output_sinks, vec![OutputSink::Clipboard]);
+        assert!(!applied.auto_pastes());
+    }
+
+    #[test]
+    fn overlay_color_must_be_hex() {
+        let mut p = profile("Work", "");
+        p.overlay_color = "#3A7bd5".into();
+        assert!(p.validate().is_ok());
+        p.overlay_color = "blue".into();
+        assert!(p.validate().is_err());
+        p.overlay_color = "#3a7bd".into();
+        assert!(p.validate().is_err());
+    }
+
+    #[test]
+    fn schedule_must_parse() {
+        let mut p = profile("Work", "");
+        p.schedule = "mon-fri 09:00-17:00".into();
+        assert!(p.validate().is_ok());
+        p.schedule = "weekdays 9 to 5".into();
+        assert!(p.validate().is_err());
+    }
+
+    #[test]
+    fn names_and_hotkeys_must_be_unique() {
+        let ok = [
+            profile("en", "Alt+1"),
+            profile("sv", "Alt+2"),
+            profile("no", ""),
+        ];
+        assert!(validate_profiles(&ok).is_ok());
+        assert!(validate_profiles(&[profile("en", ""), profile("EN", "")]).is_err());
+        assert!(validate_profiles(&[profile("a", "Alt+1"), profile("b", "alt+1")]).is_err());
+        assert!(validate_profiles(&[profile(" ", "")]).is_err());
+    }
+
+    #[test]
+    fn sound_cue_round_trips_through_its_name() {
+        for cue in SoundCue::ALL {
+            assert_eq!(SoundCue::parse(cue.as_str()), Some(cue));
+        }
+        assert_eq!(SoundCue::parse("loud"), None);
+        assert_eq!(serde_json::to_string(&SoundCue::High).unwrap(), "\"high\"");
+    }
+
+    #[test]
+    fn lookup_by_name_ignores_case() {
+        let settings = Settings {
+            profiles: vec![profile("Svenska", "Alt+2")],
+            ..Default::default()
+        };
+        assert_eq!(settings.profile("svenska").unwrap().hotkey, "Alt+2");
+        assert!(settings.profile("English").is_none());
+    }
+}
@@ -0,0 +1,148 @@
+//! Recently used initial prompts, so domain context ("sprint planning,
+//! names: Magnus, Gille, Tauri") can be re-applied without retyping it.
+//!
+//! Besides the global list, each dictation profile keeps its own: a
+//! "Medical" profile offers drug names, a "Standup" profile the team's
+//! names. A profile with no history of its own offers the global list.
+
+use super::Settings;
+
+/// How many distinct prompts are remembered.
+pub const PROMPT_HISTORY_LEN: usize = 10;
+
+/// Record `prompt` as the most recent entry of `history` (newest first).
+///
+/// Blank prompts are ignored; an existing identical entry moves to the front
+/// instead of being duplicated, and the list is capped at
+/// [`PROMPT_HISTORY_LEN`].
+pub fn remember_prompt(history: &mut Vec<String>, prompt: &str) {
+    let prompt = prompt.trim();
+    if prompt.is_empty() {
+        return;
+    }
+    history.retain(|p| p != prompt);
+    history.insert(0, prompt.to_string());
+    history.truncate(PROMPT_HISTORY_LEN);
+}
+
+/// The key a profile's history is stored under: its name, trimmed and
+/// lowercased, since profile names are matched ignoring case.
+pub fn profile_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+impl Settings {
+    /// Record `prompt` in the global history and, when it was set for a
+    /// dictation with `profile`, in that profile's history too.
+    pub fn remember_initial_prompt(&mut self, profile: Option<&str>, prompt: &str) {
+        if prompt.trim().is_empty() {
+            return;
+        }
+        remember_prompt(&mut self.prompt_history, prompt);
+        if let Some(name) = profile {
+            let history = self
+                .profile_prompt_history
+                .entry(profile_key(name))
+                .or_default();
+            remember_prompt(history, prompt);
+        }
+    }
+
+    /// Prompts to offer for `profile`, newest first: its own history, or
+    /// the global one when it has none yet or no profile is given.
+    pub fn prompt_history_for(&self, profile: Option<&str>) -> &[String] {
+        profile
+            .and_then(|name| self.profile_prompt_history.get(&profile_key(name)))
+            .filter(|history| !history.is_empty())
+            .unwrap_or(&self.prompt_history)
+    }
+
+    /// Forget `profile`'s prompts, or every prompt (global and per
+    /// profile) when no profile is given.
+    pub fn clear_prompt_history(&mut self, profile: Option<&str>) {
+        match profile {
+            Some(name) => {
+                self.profile_prompt_history.remove(&profile_key(name));
+            }
+            None => {
+                self.prompt_history.clear();
+                self.profile_prompt_history.clear();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newest_first_without_duplicates() {
+        let mut history = Vec::new();
+        remember_prompt(&mut history, "Tauri, whisper-rs");
+        remember_prompt(&mut history, " sprint planning ");
+        remember_prompt(&mut history, "Tauri, whisper-rs");
+        assert_eq!(history, vec!["Tauri, whisper-rs", "sprint planning"]);
+    }
+
+    #[test]
+    fn blank_prompts_are_ignored() {
+        let mut history = vec!["kept".to_string()];
+        remember_prompt(&mut history, "   ");
+        assert_eq!(history, vec!["kept"]);
+    }
+
+    #[test]
+    fn history_is_capped() {
+        let mut history = Vec::new();
+        for i in 0..PROMPT_HISTORY_LEN + 3 {
+            remember_prompt(&mut history, &format!("prompt {i}"));
+        }
+        assert_eq!(history.len(), PROMPT_HISTORY_LEN);
+        assert_eq!(history[0], format!("prompt {}", PROMPT_HISTORY_LEN + 2));
+    }
+
+    #[test]
+    fn profiles_keep_their_own_history() {
+        let mut settings = Settings::default();
+        settings.remember_initial_prompt(None, "sprint planning");
+        settings.remember_initial_prompt(Some("Medical"), "metoprolol, warfarin");
+        assert_eq!(
+            settings.prompt_history_for(Some("medical ")),
+            ["metoprolol, warfarin"]
+        );
+        assert_eq!(
+            settings.prompt_history,
+            vec!["metoprolol, warfarin", "sprint planning"]
+        );
+    }
+
+    #[test]
+    fn profiles_without_history_fall_back_to_the_global_list() {
+        let mut settings = Settings::default();
+        settings.remember_initial_prompt(None, "sprint planning");
+        settings.remember_initial_prompt(Some("Medical"), "   ");
+        assert!(settings.profile_prompt_history.is_empty());
+        assert_eq!(
+            settings.prompt_history_for(Some("Medical")),
+            ["sprint planning"]
+        );
+        assert_eq!(settings.prompt_history_for(None), ["sprint planning"]);
+    }
+
+    #[test]
+    fn clearing_a_profile_keeps_the_rest() {
+        let mut settings = Settings::default();
+        settings.remember_initial_prompt(Some("Medical"), "metoprolol");
+        settings.remember_initial_prompt(Some("Standup"), "Anna, Björn");
+        settings.clear_prompt_history(Some("MEDICAL"));
+        assert_eq!(
+            settings.profile_prompt_history.keys().collect::<Vec<_>>(),
+            ["standup"]
+        );
+        assert_eq!(settings.prompt_history.len(), 2);
+        settings.clear_prompt_history(None);
+        assert!(settings.prompt_history.is_empty());
+        assert!(settings.profile_prompt_history.is_empty());
+    }
+}
@@ -0,0 +1,248 @@
+//! Profile schedules: a profile's language and model become the global ones
+//! at set times, e.g. a work profile on weekdays from 9 to 17 and a personal
+//! one otherwise.
+//!
+//! A schedule is written as one or more windows separated by `;`, each a
+//! set of days, a time range, or both:
+//!
+//! - `mon-fri 09:00-17:00`
+//! - `sat,sun`
+//! - `22:00-06:00` (every day; a range past midnight ends the next morning)
+//! - `mon-thu 09:00-17:00; fri 09:00-15:00`
+//!
+//! `otherwise` makes a profile the fallback, due whenever no other
+//! profile's schedule is.
+
+use chrono::{Datelike, NaiveDateTime, Timelike, Weekday};
+
+use super::profiles::Profile;
+use super::Settings;
+
+/// Schedule that makes a profile the fallback.
+pub const OTHERWISE: &str = "otherwise";
+
+/// When a profile applies.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    /// Inside any of these windows.
+    Windows(Vec<Window>),
+    /// Whenever no other profile's schedule applies.
+    Otherwise,
+}
+
+/// Days of the week plus a time range, in minutes since midnight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Window {
+    /// Indexed by `Weekday::num_days_from_monday`.
+    days: [bool; 7],
+    start: u32,
+    end: u32,
+}
+
+impl Schedule {
+    /// Parse a profile's `schedule`; `None` when it's empty (not scheduled).
+    pub fn parse(s: &str) -> Result<Option<Self>, String> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Ok(None);
+        }
+        if s.eq_ignore_ascii_case(OTHERWISE) {
+            return Ok(Some(Schedule::Otherwise));
+        }
+        let windows = s
+            .split(';')
+            .map(Window::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Some(Schedule::Windows(windows)))
+    }
+
+    /// Whether a window covers `at`. Always false for `Otherwise`.
+    pub fn covers(&self, at: NaiveDateTime) -> bool {
+        match self {
+            Schedule::Windows(windows) => windows.iter().any(|w| w.covers(at)),
+            Schedule::Otherwise => false,
+        }
+    }
+}
+
+impl Window {
+    fn parse(s: &str) -> Result<Self, String> {
+        let mut days = [true; 7];
+        let (mut start, mut end) = (0, 24 * 60);
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        if parts.is_empty() || parts.len() > 2 {
+            return Err(format!(
+                "Schedule window '{}' should be days, a time range or both, e.g. 'mon-fri 09:00-17:00'",
+                s.trim()
+            ));
+        }
+        for part in parts {
+            if part.contains(':') {
+                let (from, to) = part
+                    .split_once('-')
+                    .ok_or_else(|| format!("Time range '{part}' should look like 09:00-17:00"))?;
+                (start, end) = (parse_time(from)?, parse_time(to)?);
+                if start == end {
+                    return Err(format!("Time range '{part}' is empty"));
+                }
+            } else {
+                days = parse_days(part)?;
+            }
+        }
+        Ok(Window { days, start, end })
+    }
+
+    fn covers(&self, at: NaiveDateTime) -> bool {
+        let minute = at.hour() * 60 + at.minute();
+        let today = at.weekday();
+        if self.start < self.end {
+            self.on(today) && (self.start..self.end).contains(&minute)
+        } else {
+            // Past midnight: the evening of a listed day, or the morning after.
+            (self.on(today) && minute >= self.start) || (self.on(today.pred()) && minute < self.end)
+        }
+    }
+
+    fn on(&self, day: Weekday) -> bool {
+        self.days[day.num_days_from_monday() as usize]
+    }
+}
+
+/// `HH:MM`, as minutes since midnight; `24:00` ends a range at midnight.
+fn parse_time(s: &str) -> Result<u32, String> {
+    let invalid = || format!("'{s}' is not a time like 09:00");
+    let (h, m) = s.split_once(':').ok_or_else(invalid)?;
+    let (h, m): (u32, u32) = (
+        h.parse().map_err(|_| invalid())?,
+        m.parse().map_err(|_| invalid())?,
+    );
+    if m >= 60 || h > 24 || (h == 24 && m > 0) {
+        return Err(invalid());
+    }
+    Ok(h * 60 + m)
+}
+
+/// `mon-fri`, `sat,sun`, `fri-mon` or `daily`.
+fn parse_days(s: &str) -> Result<[bool; 7], String> {
+    if s.eq_ignore_ascii_case("daily") {
+        return Ok([true; 7]);
+    }
+    let day = |name: &str| {
+        name.parse::<Weekday>()
+            .map(|day| day.num_days_from_monday() as usize)
+            .map_err(|_| format!("'{name}' is not a day; use mon, tue, … sun"))
+    };
+    let mut days = [false; 7];
+    for item in s.split(',') {
+        match item.split_once('-') {
+            Some((from, to)) => {
+                let (mut d, to) = (day(from)?, day(to)?);
+                days[d] = true;
+                while d != to {
+                    d = (d + 1) % 7;
+                    days[d] = true;
+                }
+            }
+            None => days[day(item)?] = true,
+        }
+    }
+    Ok(days)
+}
+
+impl Settings {
+    /// The profile whose schedule covers `at`: the first one with a window
+    /// covering it, else the first `otherwise` profile. Profiles with an
+    /// empty or invalid schedule are never due.
+    pub fn scheduled_profile(&self, at: NaiveDateTime) -> Option<&Profile> {
+        let schedules: Vec<(&Profile, Schedule)> = self
+            .profiles
+            .iter()
+            .filter_map(|p| Some((p, Schedule::parse(&p.schedule).ok()??)))
+            .collect();
+        schedules
+            .iter()
+            .find(|(_, s)| s.covers(at))
+            .or_else(|| schedules.iter().find(|(_, s)| *s == Schedule::Otherwise))
+            .map(|&(p, _)| p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    /// 2026-10-12 is a Monday.
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn covers(schedule: &str, at: NaiveDateTime) -> bool {
+        Schedule::parse(schedule).unwrap().unwrap().covers(at)
+    }
+
+    #[test]
+    fn weekday_office_hours() {
+        let work = "mon-fri 09:00-17:00";
+        assert!(covers(work, at(12, 9, 0)));
+        assert!(covers(work, at(16, 16, 59)));
+        assert!(!covers(work, at(16, 17, 0)));
+        assert!(!covers(work, at(12, 8, 59)));
+        assert!(!covers(work, at(17, 12, 0)), "Saturday");
+    }
+
+    #[test]
+    fn ranges_past_midnight_and_around_the_week() {
+        let night = "fri 22:00-06:00";
+        assert!(covers(night, at(16, 23, 0)));
+        assert!(covers(night, at(17, 5, 59)), "Saturday morning");
+        assert!(!covers(night, at(16, 5, 0)), "Friday morning");
+        assert!(covers("sat-mon", at(12, 10, 0)));
+        assert!(!covers("sat-mon", at(13, 10, 0)));
+        assert!(covers("sat,sun; 18:00-24:00", at(14, 23, 30)));
+    }
+
+    #[test]
+    fn rejects_malformed_schedules() {
+        assert_eq!(Schedule::parse("  ").unwrap(), None);
+        assert_eq!(
+            Schedule::parse("Otherwise").unwrap(),
+            Some(Schedule::Otherwise)
+        );
+        for bad in [
+            "weekdays",
+            "mon-fri 9-17",
+            "09:00-09:00",
+            "25:00-26:00",
+            "mon 09:00-17:00 extra",
+            "mon;",
+        ] {
+            assert!(Schedule::parse(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn first_covering_profile_wins_then_the_fallback() {
+        let profile = |name: &str, schedule: &str| Profile {
+            name: name.to_string(),
+            schedule: schedule.to_string(),
+            ..Default::default()
+        };
+        let settings = Settings {
+            profiles: vec![
+                profile("Personal", OTHERWISE),
+                profile("Work", "mon-fri 09:00-17:00"),
+                profile("Meetings", "mon 10:00-11:00"),
+                profile("Manual", ""),
+            ],
+            ..Default::default()
+        };
+        let due = |at| settings.scheduled_profile(at).map(|p| p.name.as_str());
+        assert_eq!(due(at(12, 10, 30)), Some("Work"));
+        assert_eq!(due(at(12, 18, 0)), Some("Personal"));
+        assert_eq!(Settings::default().scheduled_profile(at(12, 10, 0)), None);
+    }
+}
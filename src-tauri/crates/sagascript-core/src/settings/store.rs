@@ -1,9 +1,12 @@
 use std::{
+    fmt,
     fs::OpenOptions,
     path::{Path, PathBuf},
 };
 
 use fs2::FileExt;
+use regex::Regex;
+use serde::Serialize;
 
 use crate::settings::Settings;
 
@@ -12,11 +15,46 @@ const LEGACY_APP_IDENTIFIERS: &[&str] = &["com.sagascript.app"];
 const SETTINGS_FILENAME: &str = "sagascript-settings.json";
 const LEGACY_ONBOARDING_KEY: &str = "hasCompletedOnboarding";
 const ONBOARDING_KEY: &str = "has_completed_onboarding";
+/// Auto-paste was a flag before it became the `paste` output sink.
+const LEGACY_AUTO_PASTE_KEY: &str = "auto_paste";
+const OUTPUT_SINKS_KEY: &str = "output_sinks";
 
 fn canonicalize_legacy_keys(map: &mut serde_json::Map<String, serde_json::Value>) {
     if let Some(legacy) = map.remove(LEGACY_ONBOARDING_KEY) {
         map.entry(ONBOARDING_KEY.to_string()).or_insert(legacy);
     }
+    if let Some(auto_paste) = map.remove(LEGACY_AUTO_PASTE_KEY) {
+        // An explicit table keeps a missing key from meaning the default
+        // one, which pastes.
+        let sinks = map
+            .entry(OUTPUT_SINKS_KEY.to_string())
+            .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+        if let serde_json::Value::Array(sinks) = sinks {
+            let paste = serde_json::json!({ "type": "paste" });
+            if auto_paste == serde_json::Value::Bool(true) && !sinks.contains(&paste) {
+                sinks.insert(0, paste);
+            }
+        }
+    }
+}
+
+/// Drop the `paste` sink from the global and profile tables of a settings
+/// object, leaving an explicit empty table rather than the default.
+fn revoke_paste(map: &mut serde_json::Map<String, serde_json::Value>) {
+    let is_paste = |sink: &serde_json::Value| sink["type"] == "paste";
+    let sinks = map
+        .entry(OUTPUT_SINKS_KEY.to_string())
+        .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+    if let serde_json::Value::Array(sinks) = sinks {
+        sinks.retain(|sink| !is_paste(sink));
+    }
+    if let Some(serde_json::Value::Array(profiles)) = map.get_mut("profiles") {
+        for profile in profiles {
+            if let Some(serde_json::Value::Array(sinks)) = profile.get_mut(OUTPUT_SINKS_KEY) {
+                sinks.retain(|sink| !is_paste(sink));
+            }
+        }
+    }
 }
 
 /// Returns the application data directory (platform-specific).
@@ -62,7 +100,7 @@ fn copy_legacy_settings(source: &Path, destination: &Path) -> Result<bool, Strin
     // Accessibility approval is tied to the signed bundle identity, not to
     // user preferences. The new identity must be approved explicitly before
     // auto-paste can be re-enabled.
-    object.insert("auto_paste".to_string(), serde_json::Value::Bool(false));
+    revoke_paste(object);
     let migrated = serde_json::to_string_pretty(&value)
         .map_err(|error| format!("Failed to serialize migrated settings: {error}"))?;
     let tmp_path = destination.with_extension("json.tmp");
@@ -155,6 +193,115 @@ pub fn load_from(path: &Path) -> Settings {
     }
 }
 
+/// Where and why a settings file can't be read as settings. `load` falls
+/// back to defaults then; this is what the user needs to fix the file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LoadIssue {
+    /// 1-based position of the problem; 0 when the file couldn't be read.
+    pub line: usize,
+    pub column: usize,
+    /// The setting with the wrong value, when the JSON itself is well-formed.
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl LoadIssue {
+    fn from_json(error: &serde_json::Error, contents: &str) -> Self {
+        let (line, column) = (error.line(), error.column());
+        let message = error.to_string();
+        let message = message
+            .strip_suffix(&format!(" at line {line} column {column}"))
+            .unwrap_or(&message)
+            .to_string();
+        let key = match error.classify() {
+            serde_json::error::Category::Data => key_before(contents, line, column),
+            _ => None,
+        };
+        Self {
+            line,
+            column,
+            key,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for LoadIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line > 0 {
+            write!(f, "line {}, column {}: ", self.line, self.column)?;
+        }
+        if let Some(key) = &self.key {
+            write!(f, "{key}: ")?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+/// The last object key before `line`/`column` in `contents`. serde_json
+/// reports a wrong-typed value where the value ends, so this is the setting
+/// it belongs to.
+fn key_before(contents: &str, line: usize, column: usize) -> Option<String> {
+    let line_start: usize = contents
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    let mut end = (line_start + column).min(contents.len());
+    while !contents.is_char_boundary(end) {
+        end -= 1;
+    }
+    let key = Regex::new(r#""((?:[^"\\]|\\.)*)"\s*:"#).expect("valid key pattern");
+    key.captures_iter(&contents[..end])
+        .last()
+        .map(|caps| caps[1].to_string())
+}
+
+/// Check the settings file at `path` the way `load_from` reads it. Returns
+/// the keys no setting uses (usually typos, which loading ignores), or why
+/// loading would fall back to defaults. A missing file is fine.
+pub fn validate(path: &Path) -> Result<Vec<String>, LoadIssue> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => validate_str(&contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(LoadIssue {
+            line: 0,
+            column: 0,
+            key: None,
+            message: format!("Cannot read {}: {e}", path.display()),
+        }),
+    }
+}
+
+/// [`validate`] for the contents of a settings file.
+pub fn validate_str(contents: &str) -> Result<Vec<String>, LoadIssue> {
+    let mut value: serde_json::Value =
+        serde_json::from_str(contents).map_err(|e| LoadIssue::from_json(&e, contents))?;
+    let mut unknown = Vec::new();
+    if let Some(map) = value.as_object_mut() {
+        canonicalize_legacy_keys(map);
+        let known = serde_json::to_value(Settings::default()).unwrap_or_default();
+        unknown = map
+            .keys()
+            .filter(|key| known.get(key.as_str()).is_none())
+            .cloned()
+            .collect();
+    }
+    if let Err(e) = serde_json::from_value::<Settings>(value) {
+        // Values carry no positions; reading the text again finds one.
+        return Err(match serde_json::from_str::<Settings>(contents) {
+            Err(positioned) => LoadIssue::from_json(&positioned, contents),
+            Ok(_) => LoadIssue {
+                line: 0,
+                column: 0,
+                key: None,
+                message: e.to_string(),
+            },
+        });
+    }
+    Ok(unknown)
+}
+
 /// Persist settings to disk using read-merge-write to preserve unknown or
 /// legacy keys while writing the canonical Settings fields.
 /// Uses atomic write: write to .tmp then rename.
@@ -301,6 +448,7 @@ fn save_to(path: &Path, settings: &Settings) -> Result<(), String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::output::OutputSink;
     use crate::settings::{HotkeyMode, Language, WhisperModel};
     use std::fs;
     use std::sync::mpsc;
@@ -337,7 +485,7 @@ mod tests {
         let migrated: serde_json::Value =
             serde_json::from_str(&fs::read_to_string(&destination).unwrap()).unwrap();
         assert_eq!(migrated["language"], "sv");
-        assert_eq!(migrated["auto_paste"], false);
+        assert_eq!(migrated["output_sinks"], serde_json::json!([]));
         assert!(
             legacy.exists(),
             "migration must leave rollback source intact"
@@ -363,14 +511,20 @@ mod tests {
         fs::create_dir_all(legacy.parent().unwrap()).unwrap();
         fs::write(
             &legacy,
-            r#"{"language":"sv","auto_paste":true,"hasCompletedOnboarding":true,"future_key":{"x":1}}"#,
+            r#"{"language":"sv","auto_paste":true,"hasCompletedOnboarding":true,"future_key":{"x":1},
+                "profiles":[{"name":"Notes","output_sinks":[{"type":"paste"},{"type":"clipboard"}]}]}"#,
         )
         .unwrap();
 
         assert!(copy_legacy_settings(&legacy, &destination).unwrap());
         let migrated: serde_json::Value =
             serde_json::from_str(&fs::read_to_string(&destination).unwrap()).unwrap();
-        assert_eq!(migrated["auto_paste"], false);
+        assert!(migrated.get("auto_paste").is_none());
+        assert_eq!(migrated["output_sinks"], serde_json::json!([]));
+        assert_eq!(
+            migrated["profiles"][0]["output_sinks"],
+            serde_json::json!([{ "type": "clipboard" }])
+        );
         assert_eq!(migrated["has_completed_onboarding"], true);
         assert!(migrated.get("hasCompletedOnboarding").is_none());
         assert_eq!(migrated["future_key"]["x"], 1);
@@ -436,12 +590,12 @@ mod tests {
         migration.join().unwrap().unwrap();
         let updated = update.join().unwrap().unwrap();
         assert_eq!(updated.language, Language::Norwegian);
-        assert!(!updated.auto_paste);
+        assert!(!updated.auto_pastes());
 
         let contents = fs::read_to_string(&destination).unwrap();
         let persisted: serde_json::Value = serde_json::from_str(&contents).unwrap();
         assert_eq!(persisted["language"], "no");
-        assert_eq!(persisted["auto_paste"], false);
+        assert_eq!(persisted["output_sinks"], serde_json::json!([]));
         assert_eq!(persisted["future_key"]["x"], 1);
         assert!(!destination.with_extension("json.tmp").exists());
         let _ = fs::remove_dir_all(root);
@@ -511,9 +665,44 @@ mod tests {
     #[test]
     fn update_uses_a_cross_process_lock_file() {
         with_temp_settings(|path| {
-            update_at(&path, |settings| settings.auto_paste = false).unwrap();
+            update_at(&path, |settings| settings.set_auto_paste(false)).unwrap();
             assert!(path.with_extension("json.lock").exists());
-            assert!(!load_from(&path).auto_paste);
+            assert!(!load_from(&path).auto_pastes());
+        });
+    }
+
+    #[test]
+    fn paste_formatting_stays_off_for_existing_files() {
+        with_temp_settings(|path| {
+            fs::write(&path, r#"{"language":"sv","auto_paste":true}"#).unwrap();
+            assert!(!load_from(&path).paste_formatting);
+        });
+    }
+
+    #[test]
+    fn legacy_auto_paste_becomes_the_paste_sink() {
+        with_temp_settings(|path| {
+            fs::write(
+                &path,
+                r#"{"auto_paste":true,"output_sinks":[{"type":"clipboard"}]}"#,
+            )
+            .unwrap();
+            assert_eq!(
+                load_from(&path).output_sinks,
+                vec![OutputSink::Paste, OutputSink::Clipboard]
+            );
+
+            fs::write(&path, r#"{"language":"sv","auto_paste":false}"#).unwrap();
+            assert!(load_from(&path).output_sinks.is_empty());
+
+            // Saving drops the old key, so removing the sink sticks.
+            fs::write(&path, r#"{"auto_paste":true}"#).unwrap();
+            update_at(&path, |settings| settings.set_auto_paste(false)).unwrap();
+            let raw: serde_json::Value =
+                serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+            assert!(raw.get("auto_paste").is_none());
+            assert!(!load_from(&path).auto_pastes());
+            assert_eq!(validate(&path), Ok(Vec::new()));
         });
     }
 
@@ -575,7 +764,7 @@ mod tests {
         assert_eq!(s.whisper_model, WhisperModel::Base);
         assert_eq!(s.hotkey_mode, HotkeyMode::PushToTalk);
         assert!(s.show_overlay);
-        assert!(s.auto_paste);
+        assert!(s.auto_pastes());
         assert!(s.auto_select_model);
     }
 
@@ -691,6 +880,44 @@ mod tests {
         });
     }
 
+    // -- validate --
+
+    #[test]
+    fn validate_reports_syntax_errors_with_their_position() {
+        let issue = validate_str("{\n  \"language\": \"sv\",\n}").unwrap_err();
+        assert_eq!((issue.line, issue.column), (3, 1));
+        assert_eq!(issue.key, None);
+        assert_eq!(issue.message, "trailing comma");
+        assert_eq!(issue.to_string(), "line 3, column 1: trailing comma");
+    }
+
+    #[test]
+    fn validate_names_the_setting_with_a_wrong_value() {
+        let issue =
+            validate_str("{\n  \"language\": \"sv\",\n  \"beam_size\": \"five\"\n}").unwrap_err();
+        assert_eq!(issue.line, 3);
+        assert_eq!(issue.key.as_deref(), Some("beam_size"));
+        assert!(issue.message.starts_with("invalid type"), "{issue}");
+
+        let issue = validate_str(r#"{"language":"de"}"#).unwrap_err();
+        assert_eq!(issue.key.as_deref(), Some("language"));
+    }
+
+    #[test]
+    fn validate_lists_keys_no_setting_uses() {
+        let unknown =
+            validate_str(r#"{"langauge":"sv","hasCompletedOnboarding":true,"hotkey":"Alt+Space"}"#)
+                .unwrap();
+        assert_eq!(unknown, vec!["langauge".to_string()]);
+    }
+
+    #[test]
+    fn validate_accepts_a_missing_file() {
+        with_temp_settings(|path| {
+            assert_eq!(validate(&path), Ok(Vec::new()));
+        });
+    }
+
     #[test]
     fn save_to_no_existing_file_does_not_create_bak() {
         with_temp_settings(|path| {
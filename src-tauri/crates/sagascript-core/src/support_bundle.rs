@@ -0,0 +1,370 @@
+//! Support bundles: one zip archive with what a bug report needs, for
+//! `sagascript support-bundle`.
+//!
+//! The bundle only reads: the structured logs, the settings, the model
+//! list, platform and runtime details, and the recent dictation sessions
+//! with their errors. Nothing the user said goes in. Settings that hold
+//! their words (prompts, keywords, replacement rules) are left out, output
+//! sinks keep only their type, no transcript or history is included, and
+//! paths under the home directory and URLs are shortened everywhere.
+//! Secrets never were in the settings file; they live in the credential
+//! store, which is not read.
+
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Datelike, Local, Timelike};
+use regex::{Captures, Regex};
+use serde_json::{json, Value};
+
+use crate::error::DictationError;
+use crate::sessions::{self, LOG_FILE_NAME, MAX_ROTATED_FILES};
+use crate::settings::{store, Settings};
+use crate::transcription::catalog::{self, ModelFilter};
+use crate::transcription::RuntimeInfo;
+
+/// Settings whose values are the user's own words. A bundle only says
+/// whether they are set.
+const PRIVATE_SETTINGS: &[&str] = &[
+    "initial_prompt",
+    "prompt_history",
+    "profile_prompt_history",
+    "keywords",
+    "flush_phrase",
+    "replacement_rules",
+    "post_process_prompt",
+];
+
+/// How many recent dictation sessions a bundle lists.
+const SESSIONS: usize = 50;
+
+/// Placeholder for a value left out of the bundle.
+const REDACTED: &str = "<redacted>";
+
+/// Write a support bundle to `dest`. Returns the names of the files in it.
+pub fn write_bundle(dest: &Path) -> Result<Vec<String>, DictationError> {
+    let home = dirs::home_dir().map(|h| h.display().to_string());
+    let redactor = Redactor::new(home.as_deref());
+    let now = Local::now();
+
+    let mut files: Vec<(String, Vec<u8>)> = vec![
+        ("system.json".to_string(), to_json(&system_info(&now))?),
+        (
+            "settings.json".to_string(),
+            to_json(&redacted_settings(&store::load(), &redactor))?,
+        ),
+        (
+            "models.json".to_string(),
+            to_json(&catalog::list_models(&ModelFilter::default(), None))?,
+        ),
+        (
+            "sessions.json".to_string(),
+            to_json(&recent_sessions(&redactor))?,
+        ),
+    ];
+    let log_dir = sessions::log_directory();
+    let log_names = std::iter::once(LOG_FILE_NAME.to_string())
+        .chain((1..=MAX_ROTATED_FILES).map(|i| format!("sagascript.{i}.log")));
+    for name in log_names {
+        if let Ok(bytes) = fs::read(log_dir.join(&name)) {
+            let log = redactor.text(&String::from_utf8_lossy(&bytes));
+            files.push((format!("logs/{name}"), log.into_bytes()));
+        }
+    }
+
+    let mut zip = ZipWriter::new(&now);
+    for (name, contents) in &files {
+        zip.add(name, contents);
+    }
+    fs::write(dest, zip.finish())
+        .map_err(|e| bundle_err(format!("Cannot write {}: {e}", dest.display())))?;
+    Ok(files.into_iter().map(|(name, _)| name).collect())
+}
+
+fn bundle_err(msg: String) -> DictationError {
+    DictationError::SupportBundleError(msg)
+}
+
+fn to_json(value: &impl serde::Serialize) -> Result<Vec<u8>, DictationError> {
+    serde_json::to_vec_pretty(value).map_err(|e| bundle_err(format!("JSON error: {e}")))
+}
+
+/// Version, platform and whisper runtime, and whether the settings file
+/// loads.
+fn system_info(now: &DateTime<Local>) -> Value {
+    let settings = store::load();
+    json!({
+        "created_by": format!("sagascript {}", env!("CARGO_PKG_VERSION")),
+        "created_at": now.to_rfc3339(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "cpus": num_cpus::get(),
+        "runtime": RuntimeInfo::collect(settings.effective_model(), false),
+        "settings_load_issue": store::validate(&store::settings_path()).err(),
+    })
+}
+
+/// `settings` as JSON, without the user's words or sink targets.
+fn redacted_settings(settings: &Settings, redactor: &Redactor) -> Value {
+    let mut value = serde_json::to_value(settings).unwrap_or_default();
+    let Some(map) = value.as_object_mut() else {
+        return value;
+    };
+    for key in PRIVATE_SETTINGS {
+        if let Some(v) = map.get_mut(*key) {
+            let set = match v {
+                Value::String(s) => !s.is_empty(),
+                Value::Array(a) => !a.is_empty(),
+                Value::Object(o) => !o.is_empty(),
+                _ => true,
+            };
+            if set {
+                *v = Value::String(REDACTED.to_string());
+            }
+        }
+    }
+    if let Some(Value::Array(sinks)) = map.get_mut("output_sinks") {
+        for sink in sinks {
+            *sink = json!({ "type": sink["type"].clone() });
+        }
+    }
+    redactor.value(&mut value);
+    value
+}
+
+/// The most recent sessions, with paths and URLs in their errors shortened.
+fn recent_sessions(redactor: &Redactor) -> Vec<sessions::SessionRecord> {
+    let mut records = sessions::timeline(&sessions::log_directory(), SESSIONS);
+    for record in &mut records {
+        if let Some(error) = &record.error {
+            record.error = Some(redactor.text(error));
+        }
+    }
+    records
+}
+
+/// Shortens what could identify the user in free text: the home directory
+/// becomes `~`, and a URL keeps only its scheme and host.
+struct Redactor {
+    /// The home directory as written in text and inside JSON strings.
+    homes: Vec<String>,
+    url: Regex,
+}
+
+impl Redactor {
+    fn new(home: Option<&str>) -> Self {
+        let homes = home
+            .filter(|h| h.len() > 1)
+            .map(|h| {
+                let escaped = h.replace('\\', "\\\\");
+                if escaped == h {
+                    vec![h.to_string()]
+                } else {
+                    vec![escaped, h.to_string()]
+                }
+            })
+            .unwrap_or_default();
+        Self {
+            homes,
+            url: Regex::new(
+                r#"\b([a-zA-Z][a-zA-Z0-9+.-]*)://(?:[^/\s@"'<>]*@)?([^/\s?#"'<>]*)([^\s"'<>]*)"#,
+            )
+            .expect("valid URL pattern"),
+        }
+    }
+
+    fn text(&self, text: &str) -> String {
+        let mut text = self
+            .url
+            .replace_all(text, |caps: &Captures| {
+                let rest = if caps[3].is_empty() { "" } else { "/…" };
+                format!("{}://{}{rest}", &caps[1], &caps[2])
+            })
+            .into_owned();
+        for home in &self.homes {
+            text = text.replace(home.as_str(), "~");
+        }
+        text
+    }
+
+    fn value(&self, value: &mut Value) {
+        match value {
+            Value::String(s) => *s = self.text(s),
+            Value::Array(items) => items.iter_mut().for_each(|v| self.value(v)),
+            Value::Object(map) => map.values_mut().for_each(|v| self.value(v)),
+            _ => {}
+        }
+    }
+}
+
+// -- Minimal zip writer: stored (uncompressed) files only, which any unzip
+// reads.
+
+struct ZipWriter {
+    out: Vec<u8>,
+    central: Vec<u8>,
+    entries: u16,
+    time: u16,
+    date: u16,
+}
+
+impl ZipWriter {
+    fn new(now: &DateTime<Local>) -> Self {
+        // MS-DOS date and time, the only timestamps the base format has.
+        let time = ((now.hour() << 11) | (now.minute() << 5) | (now.second() / 2)) as u16;
+        let year = now.year().clamp(1980, 2107) as u32 - 1980;
+        let date = ((year << 9) | (now.month() << 5) | now.day()) as u16;
+        Self {
+            out: Vec::new(),
+            central: Vec::new(),
+            entries: 0,
+            time,
+            date,
+        }
+    }
+
+    fn add(&mut self, name: &str, contents: &[u8]) {
+        let offset = self.out.len() as u32;
+        let crc = crc32(contents);
+        let size = contents.len() as u32;
+        let (time, date) = (self.time, self.date);
+        // Version 2.0, UTF-8 names, stored.
+        let fields = |out: &mut Vec<u8>| {
+            for v in [20u16, 0x0800, 0, time, date] {
+                out.extend_from_slice(&v.to_le_bytes());
+            }
+            for v in [crc, size, size] {
+                out.extend_from_slice(&v.to_le_bytes());
+            }
+            out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            out.extend_from_slice(&0u16.to_le_bytes());
+        };
+
+        self.out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        fields(&mut self.out);
+        self.out.extend_from_slice(name.as_bytes());
+        self.out.extend_from_slice(contents);
+
+        self.central
+            .extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        self.central.extend_from_slice(&20u16.to_le_bytes());
+        fields(&mut self.central);
+        // No comment, disk 0, no attributes, then where the file starts.
+        self.central.extend_from_slice(&[0u8; 10]);
+        self.central.extend_from_slice(&offset.to_le_bytes());
+        self.central.extend_from_slice(name.as_bytes());
+        self.entries += 1;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let central_offset = self.out.len() as u32;
+        let central_size = self.central.len() as u32;
+        self.out.append(&mut self.central);
+        self.out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        self.out.extend_from_slice(&[0u8; 4]);
+        self.out.extend_from_slice(&self.entries.to_le_bytes());
+        self.out.extend_from_slice(&self.entries.to_le_bytes());
+        self.out.extend_from_slice(&central_size.to_le_bytes());
+        self.out.extend_from_slice(&central_offset.to_le_bytes());
+        self.out.extend_from_slice(&0u16.to_le_bytes());
+        self.out
+    }
+}
+
+/// CRC-32 (IEEE), as zip stores it.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::OutputSink;
+    use crate::replacements::ReplacementRule;
+    use chrono::TimeZone;
+
+    #[test]
+    fn crc32_matches_the_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn redactor_shortens_urls_and_home_paths() {
+        let redactor = Redactor::new(Some("/home/anna"));
+        assert_eq!(
+            redactor.text("Cannot read /home/anna/notes.md: denied"),
+            "Cannot read ~/notes.md: denied"
+        );
+        assert_eq!(
+            redactor.text("POST https://discord.com/api/webhooks/1/secret failed"),
+            "POST https://discord.com/… failed"
+        );
+        assert_eq!(
+            redactor.text("mqtt://sensor@homeassistant.local/sagascript"),
+            "mqtt://homeassistant.local/…"
+        );
+        assert_eq!(
+            redactor.text("http://localhost:11434"),
+            "http://localhost:11434"
+        );
+
+        let windows = Redactor::new(Some(r"C:\Users\anna"));
+        assert_eq!(
+            windows.text(r#"{"error":"C:\\Users\\anna\\a.wav"}"#),
+            r#"{"error":"~\\a.wav"}"#
+        );
+    }
+
+    #[test]
+    fn settings_lose_private_values_and_sink_targets() {
+        let settings = Settings {
+            initial_prompt: "Anna, Björn".to_string(),
+            keywords: vec!["metoprolol".to_string()],
+            replacement_rules: vec![ReplacementRule::new("a", "b", false).unwrap()],
+            output_sinks: vec![OutputSink::daily_note("/home/anna/Vault")],
+            telemetry_endpoint: "https://example.com/collect?key=1".to_string(),
+            ..Settings::default()
+        };
+        let value = redacted_settings(&settings, &Redactor::new(Some("/home/anna")));
+        assert_eq!(value["initial_prompt"], REDACTED);
+        assert_eq!(value["keywords"], REDACTED);
+        assert_eq!(value["replacement_rules"], REDACTED);
+        assert_eq!(value["prompt_history"], json!([]));
+        assert_eq!(value["profile_prompt_history"], json!({}));
+        assert_eq!(value["output_sinks"], json!([{ "type": "daily_note" }]));
+        assert_eq!(value["telemetry_endpoint"], "https://example.com/…");
+        assert_eq!(value["language"], "en");
+    }
+
+    #[test]
+    fn zip_layout_has_headers_directory_and_end_record() {
+        let now = Local.with_ymd_and_hms(2026, 10, 16, 14, 5, 30).unwrap();
+        let mut zip = ZipWriter::new(&now);
+        zip.add("a.txt", b"hello");
+        zip.add("logs/b.log", b"");
+        let bytes = zip.finish();
+
+        assert_eq!(&bytes[..4], b"PK\x03\x04");
+        assert_eq!(&bytes[30..35], b"a.txt");
+        assert_eq!(&bytes[35..40], b"hello");
+        let end = bytes.len() - 22;
+        assert_eq!(&bytes[end..end + 4], b"PK\x05\x06");
+        assert_eq!(u16::from_le_bytes([bytes[end + 10], bytes[end + 11]]), 2);
+        let central = u32::from_le_bytes(bytes[end + 16..end + 20].try_into().unwrap()) as usize;
+        assert_eq!(&bytes[central..central + 4], b"PK\x01\x02");
+        // 14:05:30 and 2026-10-16 in MS-DOS form.
+        assert_eq!(&bytes[10..14], &[0xAF, 0x70, 0x50, 0x5D]);
+    }
+}
@@ -0,0 +1,315 @@
+//! Opt-in usage counts, queued locally until the user sends them.
+//!
+//! Off unless `Settings::telemetry` is on. When on, the app and the CLI add
+//! to per-day counters in `telemetry-queue.json` in the app data directory:
+//! dictations by outcome, transcriptions by model, file transcriptions, and
+//! error kinds. Events carry only enum values, so transcript text, file
+//! names and audio can't reach the queue. Nothing leaves the machine on its
+//! own: `sagascript telemetry show` prints exactly what is queued, and only
+//! `sagascript telemetry send` uploads it, to `Settings::telemetry_endpoint`.
+
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::error::DictationError;
+use crate::sessions::SessionOutcome;
+use crate::settings::WhisperModel;
+use crate::transcription::catalog;
+
+const QUEUE_FILENAME: &str = "telemetry-queue.json";
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Mirrors `Settings::telemetry` for this process, so recording sites don't
+/// need the settings at hand. Off until set.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Something worth counting. Deliberately only enums and static strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TelemetryEvent {
+    /// A dictation ended.
+    Dictation {
+        model: WhisperModel,
+        outcome: SessionOutcome,
+    },
+    /// A file was transcribed.
+    FileTranscription { model: WhisperModel },
+    /// An error reached the user, by `DictationError::kind`.
+    Error { kind: &'static str },
+}
+
+/// One day's counts (UTC), as queued and as sent.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyCounts {
+    /// `YYYY-MM-DD`.
+    pub day: String,
+    /// Dictations by outcome (`completed`, `empty`, `failed`, ...).
+    #[serde(default)]
+    pub dictations: BTreeMap<String, u64>,
+    /// Dictations and file transcriptions by model ID.
+    #[serde(default)]
+    pub models: BTreeMap<String, u64>,
+    #[serde(default)]
+    pub file_transcriptions: u64,
+    /// Errors by kind (`ModelBusy`, `NoAudioCaptured`, ...).
+    #[serde(default)]
+    pub errors: BTreeMap<String, u64>,
+}
+
+impl DailyCounts {
+    fn add(&mut self, event: TelemetryEvent) {
+        match event {
+            TelemetryEvent::Dictation { model, outcome } => {
+                *self
+                    .dictations
+                    .entry(outcome.label().to_string())
+                    .or_default() += 1;
+                *self.models.entry(catalog::model_id(model)).or_default() += 1;
+            }
+            TelemetryEvent::FileTranscription { model } => {
+                self.file_transcriptions += 1;
+                *self.models.entry(catalog::model_id(model)).or_default() += 1;
+            }
+            TelemetryEvent::Error { kind } => {
+                *self.errors.entry(kind.to_string()).or_default() += 1;
+            }
+        }
+    }
+}
+
+/// Where the queue is kept.
+pub fn queue_path() -> PathBuf {
+    crate::settings::store::app_data_dir().join(QUEUE_FILENAME)
+}
+
+/// Count `event` in today's entry, if telemetry is enabled. Best-effort: a
+/// failure is logged and otherwise ignored.
+pub fn record(event: TelemetryEvent) {
+    if !is_enabled() {
+        return;
+    }
+    if let Err(e) = record_at(&queue_path(), &today(), event) {
+        warn!("Failed to queue telemetry: {e}");
+    }
+}
+
+fn record_at(path: &Path, day: &str, event: TelemetryEvent) -> io::Result<()> {
+    with_locked_queue(path, |queue| {
+        match queue.iter_mut().find(|d| d.day == day) {
+            Some(counts) => counts.add(event),
+            None => {
+                let mut counts = DailyCounts {
+                    day: day.to_string(),
+                    ..Default::default()
+                };
+                counts.add(event);
+                queue.push(counts);
+            }
+        }
+    })
+}
+
+/// The queued days, oldest first. A missing or unreadable queue is empty.
+pub fn load_queue(path: &Path) -> Vec<DailyCounts> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// Drop everything queued.
+pub fn clear_queue(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Upload the queue to `endpoint` as a JSON array of [`DailyCounts`] and
+/// drop what was sent. Returns how many days were sent.
+pub async fn send(endpoint: &str, path: &Path) -> Result<usize, DictationError> {
+    if !is_enabled() {
+        return Err(DictationError::SettingsError(
+            "telemetry is off; turn it on with 'sagascript config set telemetry true'".into(),
+        ));
+    }
+    let endpoint = endpoint.trim();
+    if endpoint.is_empty() {
+        return Err(DictationError::SettingsError(
+            "no telemetry endpoint set (sagascript config set telemetry_endpoint <url>)".into(),
+        ));
+    }
+    let queue = load_queue(path);
+    if queue.is_empty() {
+        return Ok(0);
+    }
+
+    let client = crate::net::client_builder()
+        .timeout(SEND_TIMEOUT)
+        .build()
+        .map_err(|e| DictationError::OutputError(format!("HTTP client error: {e}")))?;
+    let response = client
+        .post(endpoint)
+        .json(&queue)
+        .send()
+        .await
+        .map_err(|e| DictationError::OutputError(format!("Telemetry upload failed: {e}")))?;
+    if !response.status().is_success() {
+        return Err(DictationError::OutputError(format!(
+            "Telemetry endpoint returned {}",
+            response.status()
+        )));
+    }
+
+    // Counts may have grown while uploading; keep any day that changed.
+    with_locked_queue(path, |current| current.retain(|d| !queue.contains(d)))
+        .map_err(|e| DictationError::OutputError(format!("Failed to update queue: {e}")))?;
+    Ok(queue.len())
+}
+
+/// Read-modify-write the queue under an exclusive lock, so the app and a
+/// CLI run don't lose each other's counts.
+fn with_locked_queue(path: &Path, update: impl FnOnce(&mut Vec<DailyCounts>)) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+    file.lock_exclusive()?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    let mut queue: Vec<DailyCounts> = serde_json::from_str(&contents).unwrap_or_default();
+    update(&mut queue);
+
+    let json = serde_json::to_string_pretty(&queue).map_err(io::Error::other)?;
+    file.set_len(0)?;
+    file.rewind()?;
+    file.write_all(json.as_bytes())?;
+    file.unlock()
+}
+
+/// Today's date (UTC) as `YYYY-MM-DD`.
+fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86_400)
+        .unwrap_or(0);
+    date_from_days(days as i64)
+}
+
+/// Civil date for a day count since 1970-01-01 (Howard Hinnant's
+/// `civil_from_days`).
+pub(crate) fn date_from_days(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_queue() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("sagascript-telemetry-{}", uuid::Uuid::new_v4()))
+            .join(QUEUE_FILENAME)
+    }
+
+    #[test]
+    fn dates_from_day_counts() {
+        assert_eq!(date_from_days(0), "1970-01-01");
+        assert_eq!(date_from_days(11_017), "2000-03-01");
+        assert_eq!(date_from_days(19_782), "2024-02-29");
+        assert_eq!(date_from_days(20_742), "2026-10-16");
+    }
+
+    #[test]
+    fn events_accumulate_per_day() {
+        let path = temp_queue();
+        let dictation = TelemetryEvent::Dictation {
+            model: WhisperModel::BaseEn,
+            outcome: SessionOutcome::Completed,
+        };
+        record_at(&path, "2026-10-15", dictation).unwrap();
+        record_at(&path, "2026-10-16", dictation).unwrap();
+        record_at(&path, "2026-10-16", dictation).unwrap();
+        record_at(
+            &path,
+            "2026-10-16",
+            TelemetryEvent::Error { kind: "ModelBusy" },
+        )
+        .unwrap();
+        record_at(
+            &path,
+            "2026-10-16",
+            TelemetryEvent::FileTranscription {
+                model: WhisperModel::BaseEn,
+            },
+        )
+        .unwrap();
+
+        let queue = load_queue(&path);
+        assert_eq!(queue.len(), 2);
+        let today = &queue[1];
+        assert_eq!(today.day, "2026-10-16");
+        assert_eq!(today.dictations["completed"], 2);
+        assert_eq!(today.models["base.en"], 3);
+        assert_eq!(today.file_transcriptions, 1);
+        assert_eq!(today.errors["ModelBusy"], 1);
+
+        clear_queue(&path).unwrap();
+        assert!(load_queue(&path).is_empty());
+        clear_queue(&path).unwrap();
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn queue_holds_only_counts() {
+        let mut counts = DailyCounts {
+            day: "2026-10-16".into(),
+            ..Default::default()
+        };
+        counts.add(TelemetryEvent::Dictation {
+            model: WhisperModel::KbWhisperBase,
+            outcome: SessionOutcome::Failed,
+        });
+        let json = serde_json::to_value(&counts).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "day": "2026-10-16",
+                "dictations": { "failed": 1 },
+                "models": { "kb-whisper-base": 1 },
+                "file_transcriptions": 0,
+                "errors": {},
+            })
+        );
+    }
+}
@@ -0,0 +1,532 @@
+//! Remote speech-to-text services that can transcribe dictations instead of
+//! whisper.cpp (`Settings::transcription_backend`).
+//!
+//! Local transcription keeps going through [`super::WhisperBackend`]
+//! directly: it loads models, aborts on timeout and keeps partial results,
+//! none of which a remote API has. A remote backend implements
+//! [`TranscriptionBackend`] and is built for each dictation from the
+//! settings, so a changed backend or model applies to the next one.
+
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+
+use super::{
+    apply_voice_commands, localize_numbers, strip_punctuation, TranscribeOptions, Transcript,
+};
+use crate::audio::upload::{
+    plan_upload, UploadFormat, AZURE_MAX_UPLOAD_SAMPLES, OPENAI_MAX_UPLOAD_BYTES,
+};
+use crate::credentials;
+use crate::error::DictationError;
+use crate::settings::{BackendKind, Language, Settings};
+
+/// Name of the OpenAI API key in the credential store.
+pub const OPENAI_API_KEY_SECRET: &str = "openai-api-key";
+
+/// Environment variable read when no OpenAI key is stored.
+pub const OPENAI_API_KEY_ENV: &str = "OPENAI_API_KEY";
+
+/// Name of the Azure Speech resource key in the credential store.
+pub const AZURE_SPEECH_KEY_SECRET: &str = "azure-speech-key";
+
+/// Environment variable read when no Azure key is stored.
+pub const AZURE_SPEECH_KEY_ENV: &str = "AZURE_SPEECH_KEY";
+
+const OPENAI_URL: &str = "https://api.openai.com/v1/audio/transcriptions";
+
+/// Most uploads one dictation may be split into (about 13 minutes each).
+const OPENAI_MAX_UPLOADS: usize = 8;
+
+/// Most Azure requests one dictation may be split into (a minute each).
+const AZURE_MAX_UPLOADS: usize = 15;
+
+/// Time each request gets for the service to answer, on top of its upload.
+const REQUEST_TIMEOUT_SECS: u64 = 60;
+
+/// Slowest uplink an upload's time is planned for: 1 Mbit/s.
+const MIN_UPLINK_BYTES_PER_SEC: usize = 125_000;
+
+/// A service that turns 16 kHz mono audio into text.
+pub trait TranscriptionBackend: Send + Sync {
+    /// Name for logs and errors.
+    fn name(&self) -> &'static str;
+
+    /// Model recorded with the transcript in history and `--json` output.
+    fn model(&self) -> &str;
+
+    /// How long transcribing `sample_count` samples may take before it is
+    /// given up on; grows with the uploads it needs.
+    fn timeout(&self, sample_count: usize) -> Duration;
+
+    /// Transcribe `audio` in `language`, in as many uploads as the service's
+    /// limits need.
+    fn transcribe<'a>(
+        &'a self,
+        audio: &'a [f32],
+        language: Language,
+    ) -> BoxFuture<'a, Result<Transcript, DictationError>>;
+}
+
+/// The remote backend `settings` pick, or `None` for local whisper.cpp.
+pub fn from_settings(
+    settings: &Settings,
+) -> Result<Option<Box<dyn TranscriptionBackend>>, DictationError> {
+    Ok(match settings.transcription_backend {
+        BackendKind::Local => None,
+        BackendKind::OpenAi => Some(Box::new(OpenAiBackend::from_settings(settings)?)),
+        BackendKind::Azure => Some(Box::new(AzureBackend::from_settings(settings)?)),
+    })
+}
+
+/// Name of the credential holding the API key `kind` needs, if any.
+pub fn api_key_secret(kind: BackendKind) -> Option<&'static str> {
+    match kind {
+        BackendKind::Local => None,
+        BackendKind::OpenAi => Some(OPENAI_API_KEY_SECRET),
+        BackendKind::Azure => Some(AZURE_SPEECH_KEY_SECRET),
+    }
+}
+
+/// The key stored as `secret`, or else the one in the environment variable
+/// `env`. When there is neither, the error names `service` and the
+/// `set-api-key` arguments that store it.
+fn api_key(secret: &str, env: &str, service: &str, flag: &str) -> Result<String, DictationError> {
+    let stored = credentials::default_store()?.get(secret)?;
+    stored
+        .or_else(|| std::env::var(env).ok())
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty())
+        .ok_or_else(|| {
+            DictationError::CredentialError(format!(
+                "No {service} key. Store one with 'sagascript config set-api-key{flag}' \
+                 or set {env}"
+            ))
+        })
+}
+
+/// Apply the text options whisper.cpp decoding applies itself, number style,
+/// punctuation and voice commands, to a remote transcript.
+pub fn apply_text_options(
+    transcript: Transcript,
+    opts: &TranscribeOptions,
+    language: Language,
+) -> Transcript {
+    let mut text = transcript.text;
+    if opts.localize_numbers {
+        text = localize_numbers(&text, language);
+    }
+    if opts.strip_punctuation {
+        text = strip_punctuation(&text);
+    }
+    if opts.voice_commands {
+        text = apply_voice_commands(&text, language);
+    }
+    Transcript::from_text(text, transcript.partial)
+}
+
+/// OpenAI's `/v1/audio/transcriptions`.
+pub struct OpenAiBackend {
+    api_key: String,
+    model: String,
+}
+
+impl OpenAiBackend {
+    /// A backend that sends uploads to `model` with `api_key`.
+    pub fn new(api_key: String, model: String) -> Self {
+        Self { api_key, model }
+    }
+
+    /// The backend for `settings.openai_model`, with the stored API key or
+    /// `OPENAI_API_KEY`.
+    pub fn from_settings(settings: &Settings) -> Result<Self, DictationError> {
+        let api_key = api_key(OPENAI_API_KEY_SECRET, OPENAI_API_KEY_ENV, "OpenAI API", "")?;
+        let model = match settings.openai_model.trim() {
+            "" => "whisper-1",
+            model => model,
+        };
+        Ok(Self::new(api_key, model.to_string()))
+    }
+
+    async fn upload(
+        &self,
+        client: &reqwest::Client,
+        format: UploadFormat,
+        audio: Vec<u8>,
+        language: Language,
+    ) -> Result<String, DictationError> {
+        let boundary = format!("sagascript-{}", uuid::Uuid::new_v4().simple());
+        let body = multipart_body(
+            &boundary,
+            &self.model,
+            language.whisper_code(),
+            format,
+            audio,
+        );
+        let response = client
+            .post(OPENAI_URL)
+            .bearer_auth(&self.api_key)
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={boundary}"),
+            )
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| failed("OpenAI", format!("request failed: {e}")))?;
+        let status = response.status();
+        let reply: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| failed("OpenAI", format!("invalid reply ({status}): {e}")))?;
+        if !status.is_success() {
+            let message = reply["error"]["message"].as_str().unwrap_or("no details");
+            return Err(failed("OpenAI", format!("{status}: {message}")));
+        }
+        reply["text"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| failed("OpenAI", "reply has no text".to_string()))
+    }
+}
+
+impl TranscriptionBackend for OpenAiBackend {
+    fn name(&self) -> &'static str {
+        "OpenAI"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn timeout(&self, sample_count: usize) -> Duration {
+        let format = UploadFormat::best();
+        upload_timeout(
+            sample_count,
+            format.max_samples(OPENAI_MAX_UPLOAD_BYTES),
+            format,
+        )
+    }
+
+    fn transcribe<'a>(
+        &'a self,
+        audio: &'a [f32],
+        language: Language,
+    ) -> BoxFuture<'a, Result<Transcript, DictationError>> {
+        Box::pin(async move {
+            let client = http_client()?;
+            let format = UploadFormat::best();
+            let per_chunk = format.max_samples(OPENAI_MAX_UPLOAD_BYTES);
+            let mut texts = Vec::new();
+            for range in plan_upload(audio, per_chunk, OPENAI_MAX_UPLOADS)? {
+                if range.is_empty() {
+                    continue;
+                }
+                let encoded = format.encode(&audio[range])?;
+                if encoded.len() > OPENAI_MAX_UPLOAD_BYTES {
+                    return Err(failed(
+                        "OpenAI",
+                        format!("upload of {} bytes is over the 25 MB limit", encoded.len()),
+                    ));
+                }
+                let text = self.upload(&client, format, encoded, language).await?;
+                push_text(&mut texts, &text);
+            }
+            Ok(Transcript::from_text(texts.join(" "), false))
+        })
+    }
+}
+
+/// Azure AI Speech's short-audio REST endpoint, for organisations that may
+/// only send audio to their own Azure resource.
+///
+/// The endpoint needs a locale, so auto-detect is sent as `en-US`; it takes
+/// a minute of audio per request, so longer dictations go up in pieces.
+/// Those go up as Ogg Opus where the build has the encoder.
+pub struct AzureBackend {
+    key: String,
+    region: String,
+}
+
+impl AzureBackend {
+    /// A backend that sends uploads to the Speech resource in `region`
+    /// with its resource `key`.
+    pub fn new(key: String, region: String) -> Self {
+        Self { key, region }
+    }
+
+    /// The backend for `settings.azure_region`, with the stored resource key
+    /// or `AZURE_SPEECH_KEY`.
+    pub fn from_settings(settings: &Settings) -> Result<Self, DictationError> {
+        let region = settings.azure_region.trim();
+        if region.is_empty() {
+            return Err(DictationError::SettingsError(
+                "No Azure region. Set azure_region to your Speech resource's region \
+                 (e.g. westeurope)"
+                    .to_string(),
+            ));
+        }
+        validate_azure_region(region).map_err(DictationError::SettingsError)?;
+        let key = api_key(
+            AZURE_SPEECH_KEY_SECRET,
+            AZURE_SPEECH_KEY_ENV,
+            "Azure Speech",
+            " --backend azure",
+        )?;
+        Ok(Self::new(key, region.to_ascii_lowercase()))
+    }
+
+    async fn upload(
+        &self,
+        client: &reqwest::Client,
+        format: UploadFormat,
+        audio: Vec<u8>,
+        language: Language,
+    ) -> Result<String, DictationError> {
+        let url = format!(
+            "https://{}.stt.speech.microsoft.com/speech/recognition/conversation/cognitiveservices/v1",
+            self.region
+        );
+        let response = client
+            .post(url)
+            .query(&[("language", azure_locale(language)), ("format", "simple")])
+            .header("Ocp-Apim-Subscription-Key", &self.key)
+            .header(reqwest::header::CONTENT_TYPE, azure_content_type(format))
+            .body(audio)
+            .send()
+            .await
+            .map_err(|e| failed("Azure", format!("request failed: {e}")))?;
+        let status = response.status();
+        if !status.is_success() {
+            // Azure answers auth and quota errors with an empty body.
+            let body = response.text().await.unwrap_or_default();
+            let body = body.trim();
+            let details = if body.is_empty() { "no details" } else { body };
+            return Err(failed("Azure", format!("{status}: {details}")));
+        }
+        let reply: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| failed("Azure", format!("invalid reply: {e}")))?;
+        azure_text(&reply).map_err(|why| failed("Azure", why))
+    }
+}
+
+impl TranscriptionBackend for AzureBackend {
+    fn name(&self) -> &'static str {
+        "Azure Speech"
+    }
+
+    fn model(&self) -> &str {
+        "azure-speech"
+    }
+
+    fn timeout(&self, sample_count: usize) -> Duration {
+        upload_timeout(sample_count, AZURE_MAX_UPLOAD_SAMPLES, UploadFormat::best())
+    }
+
+    fn transcribe<'a>(
+        &'a self,
+        audio: &'a [f32],
+        language: Language,
+    ) -> BoxFuture<'a, Result<Transcript, DictationError>> {
+        Box::pin(async move {
+            let client = http_client()?;
+            let format = UploadFormat::best();
+            let mut texts = Vec::new();
+            for range in plan_upload(audio, AZURE_MAX_UPLOAD_SAMPLES, AZURE_MAX_UPLOADS)? {
+                if range.is_empty() {
+                    continue;
+                }
+                let encoded = format.encode(&audio[range])?;
+                let text = self.upload(&client, format, encoded, language).await?;
+                push_text(&mut texts, &text);
+            }
+            Ok(Transcript::from_text(texts.join(" "), false))
+        })
+    }
+}
+
+/// Check that `region` is an Azure region name such as `westeurope`. It
+/// becomes part of the endpoint's host name.
+pub fn validate_azure_region(region: &str) -> Result<(), String> {
+    if region.is_empty() || !region.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!(
+            "'{region}' is not an Azure region; use a name like westeurope or eastus"
+        ));
+    }
+    Ok(())
+}
+
+/// The `Content-Type` Azure reads an upload in `format` as.
+fn azure_content_type(format: UploadFormat) -> &'static str {
+    match format {
+        UploadFormat::Wav => "audio/wav; codecs=audio/pcm; samplerate=16000",
+        #[cfg(feature = "opus")]
+        UploadFormat::OggOpus => "audio/ogg; codecs=opus",
+    }
+}
+
+/// The BCP-47 locale Azure transcribes `language` in.
+fn azure_locale(language: Language) -> &'static str {
+    match language {
+        Language::English | Language::Auto => "en-US",
+        Language::Swedish => "sv-SE",
+        Language::Norwegian => "nb-NO",
+    }
+}
+
+/// The text of an Azure `format=simple` reply. Audio with no recognisable
+/// speech is an empty transcript, as it is locally.
+fn azure_text(reply: &serde_json::Value) -> Result<String, String> {
+    match reply["RecognitionStatus"].as_str() {
+        Some("Success") => reply["DisplayText"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "reply has no text".to_string()),
+        Some("NoMatch" | "InitialSilenceTimeout" | "BabbleTimeout") => Ok(String::new()),
+        Some(status) => Err(format!("recognition failed: {status}")),
+        None => Err("reply has no recognition status".to_string()),
+    }
+}
+
+/// [`REQUEST_TIMEOUT_SECS`] for each request `sample_count` samples are
+/// split into at `per_chunk`, plus sending them in `format` over a slow
+/// uplink.
+fn upload_timeout(sample_count: usize, per_chunk: usize, format: UploadFormat) -> Duration {
+    let requests = sample_count.div_ceil(per_chunk.max(1)).max(1) as u64;
+    let upload_secs = format.max_size(sample_count) / MIN_UPLINK_BYTES_PER_SEC;
+    Duration::from_secs(requests * REQUEST_TIMEOUT_SECS + upload_secs as u64)
+}
+
+fn push_text(texts: &mut Vec<String>, text: &str) {
+    let text = text.trim();
+    if !text.is_empty() {
+        texts.push(text.to_string());
+    }
+}
+
+fn failed(service: &str, why: String) -> DictationError {
+    DictationError::TranscriptionFailed(format!("{service}: {why}"))
+}
+
+/// Client for the uploads, through the `network_proxy` if one is set.
+fn http_client() -> Result<reqwest::Client, DictationError> {
+    crate::net::client_builder()
+        .build()
+        .map_err(|e| DictationError::TranscriptionFailed(format!("HTTP client error: {e}")))
+}
+
+/// A `multipart/form-data` request for one upload of `audio` in `format`.
+fn multipart_body(
+    boundary: &str,
+    model: &str,
+    language: Option<&str>,
+    format: UploadFormat,
+    audio: Vec<u8>,
+) -> Vec<u8> {
+    let mut body = Vec::with_capacity(audio.len() + 512);
+    let mut field = |name: &str, value: &str| {
+        body.extend_from_slice(
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+            )
+            .as_bytes(),
+        );
+    };
+    field("model", model);
+    field("response_format", "json");
+    if let Some(language) = language {
+        field("language", language);
+    }
+    body.extend_from_slice(
+        format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; \
+             filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+            format.file_name(),
+            format.mime_type()
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(&audio);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_backend_is_not_remote() {
+        assert!(from_settings(&Settings::default()).unwrap().is_none());
+    }
+
+    #[test]
+    fn multipart_body_names_each_field() {
+        let body = multipart_body(
+            "b",
+            "whisper-1",
+            Some("sv"),
+            UploadFormat::Wav,
+            b"RIFF".to_vec(),
+        );
+        let body = String::from_utf8(body).unwrap();
+        assert_eq!(
+            body,
+            "--b\r\nContent-Disposition: form-data; name=\"model\"\r\n\r\nwhisper-1\r\n\
+             --b\r\nContent-Disposition: form-data; name=\"response_format\"\r\n\r\njson\r\n\
+             --b\r\nContent-Disposition: form-data; name=\"language\"\r\n\r\nsv\r\n\
+             --b\r\nContent-Disposition: form-data; name=\"file\"; filename=\"audio.wav\"\r\n\
+             Content-Type: audio/wav\r\n\r\nRIFF\r\n--b--\r\n"
+        );
+
+        let auto = multipart_body("b", "m", None, UploadFormat::Wav, Vec::new());
+        let auto = String::from_utf8(auto).unwrap();
+        assert!(!auto.contains("name=\"language\""));
+    }
+
+    #[test]
+    fn timeout_grows_with_the_uploads() {
+        let minute = 60 * 16_000;
+        let short = upload_timeout(minute, AZURE_MAX_UPLOAD_SAMPLES, UploadFormat::Wav);
+        assert_eq!(short, Duration::from_secs(REQUEST_TIMEOUT_SECS + 15));
+        let long = upload_timeout(10 * minute, AZURE_MAX_UPLOAD_SAMPLES, UploadFormat::Wav);
+        assert_eq!(long.as_secs(), 10 * REQUEST_TIMEOUT_SECS + 153);
+        // Eight full 25 MB uploads: time to send 200 MB on a slow uplink.
+        let per_chunk = UploadFormat::Wav.max_samples(OPENAI_MAX_UPLOAD_BYTES);
+        let full = upload_timeout(8 * per_chunk, per_chunk, UploadFormat::Wav);
+        assert!(full.as_secs() > 8 * REQUEST_TIMEOUT_SECS + 1600, "{full:?}");
+    }
+
+    #[test]
+    fn azure_locales_and_regions() {
+        assert_eq!(azure_locale(Language::Swedish), "sv-SE");
+        assert_eq!(azure_locale(Language::Norwegian), "nb-NO");
+        assert_eq!(azure_locale(Language::Auto), "en-US");
+
+        assert!(validate_azure_region("westeurope").is_ok());
+        assert!(validate_azure_region("").is_err());
+        assert!(validate_azure_region("evil.example.com/").is_err());
+    }
+
+    #[test]
+    fn azure_content_type_names_the_codec() {
+        assert!(azure_content_type(UploadFormat::Wav).starts_with("audio/wav"));
+        #[cfg(feature = "opus")]
+        assert_eq!(
+            azure_content_type(UploadFormat::OggOpus),
+            "audio/ogg; codecs=opus"
+        );
+    }
+
+    #[test]
+    fn azure_reply_text() {
+        let ok = serde_json::json!({"RecognitionStatus": "Success", "DisplayText": "Hej."});
+        assert_eq!(azure_text(&ok).unwrap(), "Hej.");
+        let silent = serde_json::json!({"RecognitionStatus": "InitialSilenceTimeout"});
+        assert_eq!(azure_text(&silent).unwrap(), "");
+        let error = serde_json::json!({"RecognitionStatus": "Error"});
+        assert!(azure_text(&error).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+//! Timing the downloaded models on one reference clip, for
+//! `sagascript benchmark`: how long each takes to load, how fast it
+//! transcribes and how much memory it needs.
+//!
+//! Memory is the process's peak resident size, which only ever grows, so
+//! [`run_order`] puts the smallest model first: each peak then belongs to
+//! the model just run rather than a larger one before it.
+
+use std::time::Instant;
+
+use serde::Serialize;
+
+use super::rtf::real_time_factor;
+use super::{timing, TranscribeOptions, WhisperBackend};
+use crate::error::DictationError;
+use crate::settings::{Language, WhisperModel};
+
+/// One model's numbers.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchmarkRun {
+    pub model: WhisperModel,
+    pub load_ms: u64,
+    pub transcribe_ms: u64,
+    /// Transcription time divided by the clip's length; below 1.0 keeps up
+    /// with speech.
+    pub real_time_factor: Option<f32>,
+    /// Peak resident memory of the process after the run, where the system
+    /// reports it.
+    pub peak_memory_bytes: Option<u64>,
+    pub text: String,
+}
+
+/// `models` without repeats, smallest download first.
+pub fn run_order(models: &[WhisperModel]) -> Vec<WhisperModel> {
+    let mut ordered: Vec<WhisperModel> = Vec::with_capacity(models.len());
+    for &m in models {
+        if !ordered.contains(&m) {
+            ordered.push(m);
+        }
+    }
+    ordered.sort_by_key(|m| m.download_integrity().size);
+    ordered
+}
+
+/// Load `model` into a fresh backend and transcribe `audio` (16 kHz mono)
+/// with it. The backend is dropped before returning, so the next model
+/// starts from the same baseline.
+pub fn run(
+    model: WhisperModel,
+    audio: &[f32],
+    language: Language,
+    opts: &TranscribeOptions,
+    use_gpu: bool,
+) -> Result<BenchmarkRun, DictationError> {
+    let backend = WhisperBackend::with_gpu(use_gpu);
+    let started = Instant::now();
+    backend.load_model(model)?;
+    let load_ms = timing::ms_since(started);
+    let started = Instant::now();
+    let text = backend
+        .transcribe_sync_with_options(audio, language, opts, |_| {})?
+        .into_text();
+    let elapsed = started.elapsed();
+    Ok(BenchmarkRun {
+        model,
+        load_ms,
+        transcribe_ms: timing::ms(elapsed),
+        real_time_factor: real_time_factor(audio.len() as f32 / 16_000.0, elapsed),
+        peak_memory_bytes: peak_memory_bytes(),
+        text,
+    })
+}
+
+/// The most memory this process has had resident so far.
+pub fn peak_memory_bytes() -> Option<u64> {
+    #[cfg(unix)]
+    {
+        // SAFETY: getrusage only writes into the struct it is given.
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        if unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } != 0 {
+            return None;
+        }
+        let max_rss = u64::try_from(usage.ru_maxrss).ok()?;
+        // Bytes on macOS, kilobytes elsewhere.
+        if cfg!(target_os = "macos") {
+            Some(max_rss)
+        } else {
+            Some(max_rss * 1024)
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smallest_model_runs_first() {
+        let order = run_order(&[
+            WhisperModel::SmallEn,
+            WhisperModel::TinyEn,
+            WhisperModel::BaseEn,
+            WhisperModel::TinyEn,
+        ]);
+        assert_eq!(
+            order,
+            [
+                WhisperModel::TinyEn,
+                WhisperModel::BaseEn,
+                WhisperModel::SmallEn
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reports_peak_memory() {
+        assert!(peak_memory_bytes().is_some_and(|bytes| bytes > 0));
+    }
+}
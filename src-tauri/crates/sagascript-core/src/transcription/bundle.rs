@@ -0,0 +1,527 @@
+//! Offline model bundles: downloaded GGML files packed into a plain tar
+//! archive for carrying to a machine that cannot reach HuggingFace.
+//!
+//! The archive starts with a `sagascript-bundle.json` manifest followed by
+//! the model files, so `tar -tf` shows what is inside. Import only accepts
+//! files the app itself would download and checks every byte against the
+//! pinned integrity metadata — the manifest's own hashes are informational
+//! and never trusted on their own.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::{catalog, model};
+use crate::download::{
+    DownloadIntegrity, ExistingArtifact, prepare_existing_artifact, verify_file,
+};
+use crate::error::DictationError;
+use crate::settings::WhisperModel;
+
+/// Name of the manifest entry, always the first file in the archive.
+pub const MANIFEST_NAME: &str = "sagascript-bundle.json";
+const FORMAT_VERSION: u32 = 1;
+const BLOCK: usize = 512;
+/// Largest size a ustar header can describe (11 octal digits).
+const MAX_ENTRY_SIZE: u64 = 0o77777777777;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format: u32,
+    /// Sagascript version that wrote the bundle.
+    pub created_by: String,
+    pub files: Vec<BundleFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleFile {
+    /// File name inside the archive and in the models directory.
+    pub name: String,
+    /// Whisper model ID, or `None` for the VAD model.
+    #[serde(default)]
+    pub model: Option<String>,
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// What an import did with each file in the bundle.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ImportSummary {
+    pub installed: Vec<String>,
+    /// Files already present and verified, left untouched.
+    pub already_present: Vec<String>,
+}
+
+/// A file the app knows how to verify, keyed by its models-dir file name.
+#[derive(Debug, Clone, Copy)]
+struct Artifact {
+    name: &'static str,
+    model: Option<WhisperModel>,
+    integrity: DownloadIntegrity,
+}
+
+fn whisper_artifact(m: WhisperModel) -> Artifact {
+    Artifact {
+        name: m.ggml_filename(),
+        model: Some(m),
+        integrity: m.download_integrity(),
+    }
+}
+
+fn vad_artifact() -> Artifact {
+    Artifact {
+        name: model::VAD_MODEL_FILENAME,
+        model: None,
+        integrity: model::VAD_MODEL_INTEGRITY,
+    }
+}
+
+/// Every artifact an import may install.
+fn known_artifact(name: &str) -> Option<Artifact> {
+    catalog::all_models()
+        .into_iter()
+        .map(whisper_artifact)
+        .chain(std::iter::once(vad_artifact()))
+        .find(|a| a.name == name)
+}
+
+/// Pack `models` (and the VAD model, if `include_vad`) from the models
+/// directory into a tar archive at `dest`. Every model must already be
+/// downloaded; each one is verified before it is packed so a damaged file
+/// is never carried to another machine.
+pub fn export_bundle(
+    dest: &Path,
+    models: &[WhisperModel],
+    include_vad: bool,
+) -> Result<BundleManifest, DictationError> {
+    let mut artifacts: Vec<Artifact> = models.iter().map(|&m| whisper_artifact(m)).collect();
+    if include_vad {
+        artifacts.push(vad_artifact());
+    }
+    export_from(&model::models_dir(), dest, &artifacts)
+}
+
+fn export_from(
+    dir: &Path,
+    dest: &Path,
+    artifacts: &[Artifact],
+) -> Result<BundleManifest, DictationError> {
+    if artifacts.is_empty() {
+        return Err(bundle_err("No models selected for the bundle"));
+    }
+    for artifact in artifacts {
+        let path = dir.join(artifact.name);
+        if !path.exists() {
+            return Err(bundle_err(format!(
+                "{} is not downloaded (expected {})",
+                artifact_label(artifact),
+                path.display()
+            )));
+        }
+        verify_file(&path, artifact.integrity)?;
+    }
+
+    let manifest = BundleManifest {
+        format: FORMAT_VERSION,
+        created_by: format!("sagascript {}", env!("CARGO_PKG_VERSION")),
+        files: artifacts
+            .iter()
+            .map(|a| BundleFile {
+                name: a.name.to_string(),
+                model: a.model.map(catalog::model_id),
+                sha256: a.integrity.sha256.to_string(),
+                size: a.integrity.size,
+            })
+            .collect(),
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| bundle_err(format!("Cannot serialize manifest: {e}")))?;
+
+    let file = File::create(dest)
+        .map_err(|e| bundle_err(format!("Cannot create {}: {e}", dest.display())))?;
+    let mut out = BufWriter::new(file);
+    let write_err = |e: std::io::Error| bundle_err(format!("Cannot write {}: {e}", dest.display()));
+
+    write_entry(
+        &mut out,
+        MANIFEST_NAME,
+        manifest_json.len() as u64,
+        &mut manifest_json.as_slice(),
+    )
+    .map_err(write_err)?;
+    for artifact in artifacts {
+        let path = dir.join(artifact.name);
+        let mut src = File::open(&path)
+            .map_err(|e| bundle_err(format!("Cannot read {}: {e}", path.display())))?;
+        write_entry(&mut out, artifact.name, artifact.integrity.size, &mut src)
+            .map_err(write_err)?;
+        info!("Bundled {}", artifact.name);
+    }
+    // End of archive: two zero blocks.
+    out.write_all(&[0u8; BLOCK * 2]).map_err(write_err)?;
+    out.flush().map_err(write_err)?;
+    Ok(manifest)
+}
+
+/// Install the models in the bundle at `src` into the models directory.
+pub fn import_bundle(src: &Path) -> Result<ImportSummary, DictationError> {
+    import_into(src, &model::models_dir(), known_artifact)
+}
+
+fn import_into(
+    src: &Path,
+    dir: &Path,
+    lookup: impl Fn(&str) -> Option<Artifact>,
+) -> Result<ImportSummary, DictationError> {
+    let file =
+        File::open(src).map_err(|e| bundle_err(format!("Cannot open {}: {e}", src.display())))?;
+    let mut input = BufReader::new(file);
+    let read_err = |e: std::io::Error| bundle_err(format!("Cannot read {}: {e}", src.display()));
+
+    let manifest = match read_header(&mut input).map_err(read_err)? {
+        Some(h) if h.name == MANIFEST_NAME && h.size <= 1_048_576 => {
+            let mut json = vec![0u8; h.size as usize];
+            input.read_exact(&mut json).map_err(read_err)?;
+            skip_padding(&mut input, h.size).map_err(read_err)?;
+            serde_json::from_slice::<BundleManifest>(&json)
+                .map_err(|e| bundle_err(format!("Invalid bundle manifest: {e}")))?
+        }
+        _ => {
+            return Err(bundle_err(format!(
+                "{} is not a Sagascript model bundle (no {MANIFEST_NAME})",
+                src.display()
+            )));
+        }
+    };
+    if manifest.format > FORMAT_VERSION {
+        return Err(bundle_err(format!(
+            "Bundle format {} was written by a newer Sagascript ({}); update to import it",
+            manifest.format, manifest.created_by
+        )));
+    }
+
+    std::fs::create_dir_all(dir)
+        .map_err(|e| bundle_err(format!("Cannot create {}: {e}", dir.display())))?;
+
+    let mut summary = ImportSummary::default();
+    while let Some(header) = read_header(&mut input).map_err(read_err)? {
+        if !header.is_file {
+            skip_bytes(&mut input, header.size).map_err(read_err)?;
+            skip_padding(&mut input, header.size).map_err(read_err)?;
+            continue;
+        }
+        // Only names the app would download itself: no paths, no surprises.
+        let artifact = lookup(&header.name)
+            .filter(|_| manifest.files.iter().any(|f| f.name == header.name))
+            .ok_or_else(|| bundle_err(format!("Unexpected file in bundle: {}", header.name)))?;
+        if header.size != artifact.integrity.size {
+            return Err(bundle_err(format!(
+                "{} has the wrong size ({} bytes, expected {})",
+                artifact.name, header.size, artifact.integrity.size
+            )));
+        }
+
+        let dest = dir.join(artifact.name);
+        if prepare_existing_artifact(&dest, artifact.integrity)? == ExistingArtifact::Verified {
+            skip_bytes(&mut input, header.size).map_err(read_err)?;
+            summary.already_present.push(artifact.name.to_string());
+        } else {
+            install_entry(&mut input, &dest, artifact)?;
+            info!("Imported {}", artifact.name);
+            summary.installed.push(artifact.name.to_string());
+        }
+        skip_padding(&mut input, header.size).map_err(read_err)?;
+    }
+
+    if summary.installed.is_empty() && summary.already_present.is_empty() {
+        return Err(bundle_err("The bundle contains no model files"));
+    }
+    Ok(summary)
+}
+
+/// Copy one entry to a temp file next to `dest`, verify it, then rename it
+/// into place. The temp file is removed on any failure.
+fn install_entry(
+    input: &mut impl Read,
+    dest: &Path,
+    artifact: Artifact,
+) -> Result<(), DictationError> {
+    let tmp = dest.with_extension(format!("bin.{}.tmp", uuid::Uuid::new_v4()));
+    let result = (|| {
+        let mut out = File::create(&tmp)
+            .map_err(|e| bundle_err(format!("Cannot create {}: {e}", tmp.display())))?;
+        let copied = std::io::copy(&mut input.take(artifact.integrity.size), &mut out)
+            .map_err(|e| bundle_err(format!("Cannot extract {}: {e}", artifact.name)))?;
+        if copied != artifact.integrity.size {
+            return Err(bundle_err(format!(
+                "Bundle is truncated inside {}",
+                artifact.name
+            )));
+        }
+        out.sync_all()
+            .map_err(|e| bundle_err(format!("Cannot write {}: {e}", tmp.display())))?;
+        verify_file(&tmp, artifact.integrity)?;
+        std::fs::rename(&tmp, dest)
+            .map_err(|e| bundle_err(format!("Cannot install {}: {e}", dest.display())))
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+fn artifact_label(artifact: &Artifact) -> String {
+    match artifact.model {
+        Some(m) => m.display_name().to_string(),
+        None => "VAD model".to_string(),
+    }
+}
+
+fn bundle_err(msg: impl Into<String>) -> DictationError {
+    DictationError::BundleError(msg.into())
+}
+
+// -- Minimal ustar reader/writer: regular files only, which is all a bundle
+// holds. Readable by any `tar`.
+
+struct TarHeader {
+    name: String,
+    size: u64,
+    is_file: bool,
+}
+
+fn write_entry(
+    out: &mut impl Write,
+    name: &str,
+    size: u64,
+    data: &mut impl Read,
+) -> std::io::Result<()> {
+    if name.len() > 100 || size > MAX_ENTRY_SIZE {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("{name} cannot be stored in a tar archive"),
+        ));
+    }
+    let mut header = [0u8; BLOCK];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    write_octal(&mut header[100..108], 0o644);
+    write_octal(&mut header[108..116], 0);
+    write_octal(&mut header[116..124], 0);
+    write_octal(&mut header[124..136], size);
+    write_octal(&mut header[136..148], 0);
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    let checksum = header_checksum(&header);
+    write_octal(&mut header[148..155], checksum);
+    header[155] = b' ';
+    out.write_all(&header)?;
+
+    let copied = std::io::copy(&mut data.take(size), out)?;
+    if copied != size {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            format!("{name} shrank while being bundled"),
+        ));
+    }
+    let pad = padding(size);
+    out.write_all(&[0u8; BLOCK][..pad])
+}
+
+/// Read the next header, or `None` at the end-of-archive marker.
+fn read_header(input: &mut impl Read) -> std::io::Result<Option<TarHeader>> {
+    let mut block = [0u8; BLOCK];
+    match input.read_exact(&mut block) {
+        Ok(()) => {}
+        // Tolerate archives without the trailing zero blocks.
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    if block.iter().all(|&b| b == 0) {
+        return Ok(None);
+    }
+    let invalid = |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, msg.to_string());
+
+    let stored = parse_octal(&block[148..156]).ok_or_else(|| invalid("bad tar header"))?;
+    if stored != header_checksum(&block) {
+        return Err(invalid("tar header checksum mismatch"));
+    }
+    let size = parse_octal(&block[124..136]).ok_or_else(|| invalid("bad tar entry size"))?;
+    let mut name = c_string(&block[..100]);
+    if &block[257..262] == b"ustar" {
+        let prefix = c_string(&block[345..500]);
+        if !prefix.is_empty() {
+            name = format!("{prefix}/{name}");
+        }
+    }
+    Ok(Some(TarHeader {
+        name,
+        size,
+        is_file: matches!(block[156], b'0' | 0),
+    }))
+}
+
+fn header_checksum(header: &[u8; BLOCK]) -> u64 {
+    header
+        .iter()
+        .enumerate()
+        .map(|(i, &b)| u64::from(if (148..156).contains(&i) { b' ' } else { b }))
+        .sum()
+}
+
+/// Zero-padded octal, NUL-terminated, filling `field`.
+fn write_octal(field: &mut [u8], value: u64) {
+    let digits = format!("{value:0width$o}", width = field.len() - 1);
+    field[..digits.len()].copy_from_slice(digits.as_bytes());
+    field[digits.len()] = 0;
+}
+
+fn parse_octal(field: &[u8]) -> Option<u64> {
+    let text = std::str::from_utf8(field).ok()?;
+    let digits = text.trim_matches(|c: char| c == '\0' || c == ' ');
+    if digits.is_empty() {
+        return Some(0);
+    }
+    u64::from_str_radix(digits, 8).ok()
+}
+
+fn c_string(field: &[u8]) -> String {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+fn padding(size: u64) -> usize {
+    (BLOCK - (size % BLOCK as u64) as usize) % BLOCK
+}
+
+fn skip_bytes(input: &mut impl Read, n: u64) -> std::io::Result<()> {
+    let skipped = std::io::copy(&mut input.take(n), &mut std::io::sink())?;
+    if skipped != n {
+        return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
+
+fn skip_padding(input: &mut impl Read, size: u64) -> std::io::Result<()> {
+    skip_bytes(input, padding(size) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // sha256("verified model")
+    const TEST_INTEGRITY: DownloadIntegrity = DownloadIntegrity {
+        sha256: "6c736b3dfa943bf4e7c61df78d1dfcad9a3d8b56369f0559670497b19127e74d",
+        size: 14,
+    };
+
+    fn test_artifact() -> Artifact {
+        Artifact {
+            name: "ggml-test.bin",
+            model: None,
+            integrity: TEST_INTEGRITY,
+        }
+    }
+
+    fn lookup(name: &str) -> Option<Artifact> {
+        (name == "ggml-test.bin").then(test_artifact)
+    }
+
+    fn temp_dir() -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("sagascript-bundle-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn exported(dir: &Path) -> std::path::PathBuf {
+        let src = dir.join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("ggml-test.bin"), b"verified model").unwrap();
+        let tar = dir.join("models.tar");
+        export_from(&src, &tar, &[test_artifact()]).unwrap();
+        tar
+    }
+
+    #[test]
+    fn export_then_import_roundtrip() {
+        let dir = temp_dir();
+        let tar = exported(&dir);
+        let data = std::fs::read(&tar).unwrap();
+        assert_eq!(data.len() % BLOCK, 0);
+        assert_eq!(c_string(&data[..100]), MANIFEST_NAME);
+
+        let dest = dir.join("dest");
+        let summary = import_into(&tar, &dest, lookup).unwrap();
+        assert_eq!(summary.installed, vec!["ggml-test.bin"]);
+        assert_eq!(
+            std::fs::read(dest.join("ggml-test.bin")).unwrap(),
+            b"verified model"
+        );
+
+        let again = import_into(&tar, &dest, lookup).unwrap();
+        assert!(again.installed.is_empty());
+        assert_eq!(again.already_present, vec!["ggml-test.bin"]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn export_refuses_missing_or_damaged_models() {
+        let dir = temp_dir();
+        let err = export_from(&dir, &dir.join("x.tar"), &[test_artifact()]).unwrap_err();
+        assert!(err.to_string().contains("not downloaded"), "{err}");
+
+        std::fs::write(dir.join("ggml-test.bin"), b"tampered model").unwrap();
+        assert!(export_from(&dir, &dir.join("x.tar"), &[test_artifact()]).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn import_rejects_tampered_payload_without_installing() {
+        let dir = temp_dir();
+        let tar = exported(&dir);
+        let mut data = std::fs::read(&tar).unwrap();
+        let pos = data
+            .windows(14)
+            .position(|w| w == b"verified model")
+            .unwrap();
+        data[pos..pos + 8].copy_from_slice(b"tampered");
+        std::fs::write(&tar, &data).unwrap();
+
+        let dest = dir.join("dest");
+        assert!(import_into(&tar, &dest, lookup).is_err());
+        assert_eq!(std::fs::read_dir(&dest).unwrap().count(), 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn import_rejects_unknown_files_and_non_bundles() {
+        let dir = temp_dir();
+        let tar = exported(&dir);
+        let err = import_into(&tar, &dir.join("dest"), |_| None).unwrap_err();
+        assert!(err.to_string().contains("Unexpected file"), "{err}");
+
+        let plain = dir.join("plain.tar");
+        let mut out = Vec::new();
+        write_entry(&mut out, "../evil", 3, &mut &b"abc"[..]).unwrap();
+        std::fs::write(&plain, &out).unwrap();
+        let err = import_into(&plain, &dir.join("dest"), lookup).unwrap_err();
+        assert!(
+            err.to_string().contains("not a Sagascript model bundle"),
+            "{err}"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn octal_fields_roundtrip() {
+        let mut field = [0u8; 12];
+        write_octal(&mut field, 147_964_211);
+        assert_eq!(parse_octal(&field), Some(147_964_211));
+        assert_eq!(padding(512), 0);
+        assert_eq!(padding(14), 498);
+    }
+}
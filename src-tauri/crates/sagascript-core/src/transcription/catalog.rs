@@ -0,0 +1,441 @@
+//! Whisper model metadata as one list, shared by `sagascript list-models
+//! --json` and the GUI model picker so neither keeps its own copy.
+//!
+//! Where each model is downloaded from lives in `models.json`: the copy
+//! bundled here covers the built-in models, and a `models.json` next to the
+//! settings file adds community models by Hugging Face repo and file, e.g.
+//! a Danish or Finnish fine-tune. Those are fetched with `sagascript
+//! download-model ID` and then used like a custom model.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use super::model;
+use crate::download::DownloadIntegrity;
+use crate::error::DictationError;
+use crate::settings::{Language, WhisperModel};
+
+/// Download sources of the built-in models.
+const BUNDLED_CATALOG: &str = include_str!("models.json");
+
+/// One downloadable GGML model, pinned to a Hugging Face commit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogEntry {
+    /// ID used with `--model`, `download-model` and `custom_model`.
+    pub id: String,
+    /// Name shown in model lists; the ID when empty.
+    #[serde(default)]
+    pub name: String,
+    /// Whisper language codes the model is meant for (informational).
+    #[serde(default)]
+    pub languages: Vec<String>,
+    /// Hugging Face repository, `owner/name`.
+    pub repo: String,
+    /// Full commit hash the file is downloaded from.
+    pub revision: String,
+    /// File in the repository.
+    pub file: String,
+    /// File name in the models directory; `<id>.bin` when empty.
+    #[serde(default)]
+    pub local_file: String,
+    /// git-LFS SHA-256 of the file.
+    pub sha256: String,
+    /// Size of the file in bytes.
+    pub size: u64,
+    /// Other full URLs of the same file, tried in order when the download
+    /// from Hugging Face fails. The SHA-256 still has to match.
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+    #[serde(skip)]
+    url: String,
+}
+
+impl CatalogEntry {
+    /// Download URL at the pinned revision.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// URLs to fall back to after [`url`](Self::url).
+    pub fn mirrors(&self) -> &[String] {
+        &self.mirrors
+    }
+
+    pub fn display_name(&self) -> &str {
+        if self.name.is_empty() {
+            &self.id
+        } else {
+            &self.name
+        }
+    }
+
+    /// File name in the models directory.
+    pub fn local_file(&self) -> String {
+        if self.local_file.is_empty() {
+            format!("{}.bin", self.id)
+        } else {
+            self.local_file.clone()
+        }
+    }
+
+    pub fn integrity(&'static self) -> DownloadIntegrity {
+        DownloadIntegrity {
+            sha256: &self.sha256,
+            size: self.size,
+        }
+    }
+
+    /// Check the fields a download depends on and fill in the URL.
+    fn validate(&mut self) -> Result<(), String> {
+        let hex = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| b.is_ascii_hexdigit());
+        let plain =
+            |s: &str| !s.is_empty() && !s.starts_with('.') && !s.contains(['/', '\\', '?', '#']);
+        let id = &self.id;
+        if self.repo.split('/').count() != 2 || !self.repo.split('/').all(plain) {
+            return Err(format!("'{id}': repo must look like 'owner/name'"));
+        }
+        if !hex(&self.revision, 40) {
+            return Err(format!(
+                "'{id}': revision must be a full commit hash, not a branch, so the file can't change"
+            ));
+        }
+        if !self.file.split('/').all(plain) {
+            return Err(format!(
+                "'{id}': '{}' is not a file path in the repo",
+                self.file
+            ));
+        }
+        if !self.local_file.is_empty() && !plain(&self.local_file) {
+            return Err(format!("'{id}': local_file must be a plain file name"));
+        }
+        if !hex(&self.sha256, 64) {
+            return Err(format!(
+                "'{id}': sha256 must be the file's 64-digit git-LFS SHA-256"
+            ));
+        }
+        if self.size == 0 {
+            return Err(format!("'{id}': size must be the file's size in bytes"));
+        }
+        if let Some(mirror) = self
+            .mirrors
+            .iter()
+            .find(|m| !m.starts_with("https://") && !m.starts_with("http://"))
+        {
+            return Err(format!("'{id}': mirror '{mirror}' is not an http(s) URL"));
+        }
+        self.url = format!(
+            "https://huggingface.co/{}/resolve/{}/{}",
+            self.repo, self.revision, self.file
+        );
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct CatalogFile {
+    models: Vec<CatalogEntry>,
+}
+
+/// Entries of a `models.json`, validated.
+fn parse_catalog(json: &str) -> Result<Vec<CatalogEntry>, String> {
+    let file: CatalogFile = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let mut entries = file.models;
+    let mut ids = HashSet::new();
+    for entry in &mut entries {
+        entry.validate()?;
+        if !ids.insert(entry.id.clone()) {
+            return Err(format!("'{}' is listed twice", entry.id));
+        }
+    }
+    Ok(entries)
+}
+
+/// Download sources of the built-in models, from the bundled `models.json`.
+pub fn builtin_entries() -> &'static [CatalogEntry] {
+    static ENTRIES: OnceLock<Vec<CatalogEntry>> = OnceLock::new();
+    ENTRIES.get_or_init(|| {
+        parse_catalog(BUNDLED_CATALOG).expect("bundled models.json is valid (see tests)")
+    })
+}
+
+/// Download source of built-in model `m`.
+pub fn builtin_entry(m: WhisperModel) -> &'static CatalogEntry {
+    let id = model_id(m);
+    builtin_entries()
+        .iter()
+        .find(|e| e.id == id)
+        .expect("every built-in model is in models.json (see tests)")
+}
+
+/// The user's `models.json`, next to the settings file.
+pub fn user_catalog_path() -> PathBuf {
+    crate::settings::store::settings_path().with_file_name("models.json")
+}
+
+/// Community models listed in the user's `models.json`; empty if there is
+/// none. Read once per process, so the app picks up edits on restart.
+pub fn user_entries() -> Result<&'static [CatalogEntry], DictationError> {
+    static ENTRIES: OnceLock<Result<Vec<CatalogEntry>, String>> = OnceLock::new();
+    ENTRIES
+        .get_or_init(|| {
+            let path = user_catalog_path();
+            let json = match std::fs::read_to_string(&path) {
+                Ok(json) => json,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+                Err(e) => return Err(format!("Can't read {}: {e}", path.display())),
+            };
+            let entries = parse_catalog(&json)
+                .map_err(|why| format!("{} is not a valid model catalog: {why}", path.display()))?;
+            for entry in &entries {
+                model::validate_custom_model_name(&entry.id)
+                    .map_err(|why| format!("{}: {why}", path.display()))?;
+            }
+            Ok(entries)
+        })
+        .as_deref()
+        .map_err(|why| DictationError::SettingsError(why.clone()))
+}
+
+/// Entry `id` of the user's `models.json`, if listed.
+pub fn user_entry(id: &str) -> Result<Option<&'static CatalogEntry>, DictationError> {
+    Ok(user_entries()?.iter().find(|e| e.id == id))
+}
+
+/// Every language, in the order models are listed.
+pub const ALL_LANGUAGES: [Language; 4] = [
+    Language::English,
+    Language::Swedish,
+    Language::Norwegian,
+    Language::Auto,
+];
+
+/// Everything a model picker or script needs to know about one model.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelInfo {
+    /// ID accepted by `--model` and `config set whisper_model`.
+    pub id: String,
+    pub display_name: String,
+    pub description: String,
+    pub size_mb: u32,
+    /// Languages this model is offered for.
+    pub languages: Vec<Language>,
+    /// Recommended default for at least one of `languages`.
+    pub recommended: bool,
+    pub downloaded: bool,
+    /// The model the current settings resolve to.
+    pub active: bool,
+}
+
+/// Which models to list. The default lists everything.
+#[derive(Debug, Clone, Default)]
+pub struct ModelFilter {
+    pub language: Option<Language>,
+    /// Only models already on disk.
+    pub downloaded_only: bool,
+    /// Only models whose download is at most this many MB.
+    pub max_size_mb: Option<u32>,
+}
+
+/// Models matching `filter`, in catalog order, each listed once. `active` is
+/// the model the caller's settings resolve to, if any.
+pub fn list_models(filter: &ModelFilter, active: Option<WhisperModel>) -> Vec<ModelInfo> {
+    list_models_with(filter, active, model::is_model_downloaded)
+}
+
+/// [`list_models`] with the download check injected, for tests.
+fn list_models_with(
+    filter: &ModelFilter,
+    active: Option<WhisperModel>,
+    is_downloaded: impl Fn(WhisperModel) -> bool,
+) -> Vec<ModelInfo> {
+    let languages: &[Language] = match &filter.language {
+        Some(language) => std::slice::from_ref(language),
+        None => &ALL_LANGUAGES,
+    };
+
+    let mut seen = Vec::new();
+    let mut infos = Vec::new();
+    for &language in languages {
+        for &m in WhisperModel::models_for_language(language) {
+            if seen.contains(&m) {
+                continue;
+            }
+            seen.push(m);
+            if filter.max_size_mb.is_some_and(|max| m.size_mb() > max) {
+                continue;
+            }
+            let downloaded = is_downloaded(m);
+            if filter.downloaded_only && !downloaded {
+                continue;
+            }
+            let model_languages: Vec<Language> = ALL_LANGUAGES
+                .into_iter()
+                .filter(|&l| WhisperModel::models_for_language(l).contains(&m))
+                .collect();
+            infos.push(ModelInfo {
+                id: model_id(m),
+                display_name: m.display_name().to_string(),
+                description: m.description().to_string(),
+                size_mb: m.size_mb(),
+                recommended: model_languages
+                    .iter()
+                    .any(|&l| WhisperModel::recommended(l) == m),
+                languages: model_languages,
+                downloaded,
+                active: active == Some(m),
+            });
+        }
+    }
+    infos
+}
+
+/// Every whisper model offered for any language, each once.
+pub fn all_models() -> Vec<WhisperModel> {
+    let mut models = Vec::new();
+    for language in ALL_LANGUAGES {
+        for &m in WhisperModel::models_for_language(language) {
+            if !models.contains(&m) {
+                models.push(m);
+            }
+        }
+    }
+    models
+}
+
+/// The model's serialized ID (e.g. `"kb-whisper-base"`).
+pub fn model_id(m: WhisperModel) -> String {
+    serde_json::to_value(m)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("{m:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMUNITY: &str = r#"{
+        "models": [{
+            "id": "da-whisper-small",
+            "name": "Danish small",
+            "languages": ["da"],
+            "repo": "someone/da-whisper-small",
+            "revision": "0123456789abcdef0123456789abcdef01234567",
+            "file": "ggml/ggml-model-q5_0.bin",
+            "sha256": "aead29b356bca8840e72a8dc2286e2d69e6702639751a1e60cb3c8eacefec546",
+            "size": 55295450
+        }]
+    }"#;
+
+    #[test]
+    fn bundled_catalog_covers_every_builtin_model() {
+        let entries = parse_catalog(BUNDLED_CATALOG).unwrap();
+        assert_eq!(entries.len(), all_models().len());
+        for m in all_models() {
+            let entry = builtin_entry(m);
+            assert_eq!(entry.local_file(), m.ggml_filename());
+            assert!(entry.url().ends_with(&format!("/{}", entry.file)));
+        }
+    }
+
+    #[test]
+    fn community_entry_gets_a_pinned_url_and_default_file_name() {
+        let entries = parse_catalog(COMMUNITY).unwrap();
+        let entry = &entries[0];
+        assert_eq!(
+            entry.url(),
+            "https://huggingface.co/someone/da-whisper-small/resolve/\
+             0123456789abcdef0123456789abcdef01234567/ggml/ggml-model-q5_0.bin"
+        );
+        assert_eq!(entry.local_file(), "da-whisper-small.bin");
+        assert_eq!(entry.display_name(), "Danish small");
+        assert!(entry.mirrors().is_empty());
+    }
+
+    #[test]
+    fn rejects_unpinned_or_unchecked_entries() {
+        let with = |field: &str, value: &str| {
+            let mut json: serde_json::Value = serde_json::from_str(COMMUNITY).unwrap();
+            json["models"][0][field] = serde_json::json!(value);
+            parse_catalog(&json.to_string())
+        };
+        assert!(with("revision", "main")
+            .unwrap_err()
+            .contains("commit hash"));
+        assert!(with("sha256", "abc").unwrap_err().contains("SHA-256"));
+        assert!(with("repo", "no-owner").is_err());
+        assert!(with("file", "../../etc/passwd").is_err());
+        assert!(with("local_file", "../escape.bin").is_err());
+        let mut json: serde_json::Value = serde_json::from_str(COMMUNITY).unwrap();
+        json["models"][0]["mirrors"] = serde_json::json!(["ftp://mirror.lan/model.bin"]);
+        assert!(parse_catalog(&json.to_string())
+            .unwrap_err()
+            .contains("mirror"));
+
+        let mut json: serde_json::Value = serde_json::from_str(COMMUNITY).unwrap();
+        let entry = json["models"][0].clone();
+        json["models"].as_array_mut().unwrap().push(entry);
+        assert!(parse_catalog(&json.to_string())
+            .unwrap_err()
+            .contains("twice"));
+    }
+
+    #[test]
+    fn unfiltered_lists_every_model_once() {
+        let infos = list_models_with(&ModelFilter::default(), None, |_| false);
+        let expected: usize = ALL_LANGUAGES
+            .iter()
+            .map(|&l| WhisperModel::models_for_language(l).len())
+            .sum();
+        assert_eq!(infos.len(), expected);
+        let mut ids: Vec<_> = infos.iter().map(|i| i.id.as_str()).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), infos.len());
+    }
+
+    #[test]
+    fn language_filter_marks_recommended_and_active() {
+        let filter = ModelFilter {
+            language: Some(Language::Swedish),
+            ..Default::default()
+        };
+        let infos = list_models_with(&filter, Some(WhisperModel::KbWhisperSmall), |_| false);
+        assert!(infos.iter().all(|i| i.languages == vec![Language::Swedish]));
+        let recommended: Vec<_> = infos.iter().filter(|i| i.recommended).collect();
+        assert_eq!(recommended.len(), 1);
+        assert_eq!(recommended[0].id, "kb-whisper-base");
+        let active: Vec<_> = infos.iter().filter(|i| i.active).collect();
+        assert_eq!(active[0].id, "kb-whisper-small");
+    }
+
+    #[test]
+    fn size_and_download_filters() {
+        let filter = ModelFilter {
+            downloaded_only: true,
+            max_size_mb: Some(200),
+            ..Default::default()
+        };
+        let infos = list_models_with(&filter, None, |m| {
+            matches!(m, WhisperModel::BaseEn | WhisperModel::KbWhisperLarge)
+        });
+        assert_eq!(infos.len(), 1);
+        assert_eq!(infos[0].id, "base.en");
+        assert!(infos[0].downloaded);
+    }
+
+    #[test]
+    fn serializes_languages_as_codes() {
+        let filter = ModelFilter {
+            language: Some(Language::English),
+            ..Default::default()
+        };
+        let infos = list_models_with(&filter, None, |_| false);
+        let json = serde_json::to_value(&infos[0]).unwrap();
+        assert_eq!(json["languages"], serde_json::json!(["en"]));
+        assert_eq!(json["id"], "tiny.en");
+    }
+}
@@ -0,0 +1,125 @@
+//! Transcribing the channels of a recording separately.
+//!
+//! Call recorders often put each side of the call on its own channel.
+//! Mixed to mono, whisper hears two people talking over each other; with
+//! `split_channels` each channel is transcribed on its own and the segments
+//! are merged back in time order, labelled with the channel they came from.
+
+use serde::Serialize;
+
+use super::{normalize_nonspeech_markers, TranscriptSegment};
+use crate::settings::Language;
+
+/// A stretch of one channel's transcript.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChannelSegment {
+    /// Start time in seconds
+    pub start: f64,
+    /// End time in seconds
+    pub end: f64,
+    /// Channel label (e.g. "CH1")
+    pub channel: String,
+    /// Transcribed text
+    pub text: String,
+}
+
+/// Label for the channel at `index` (0-based): "CH1", "CH2", ...
+pub fn channel_label(index: usize) -> String {
+    format!("CH{}", index + 1)
+}
+
+/// Merge each channel's segments into one timeline, ordered by start time.
+/// Consecutive segments from the same channel are joined into one turn, and
+/// segments with no text are dropped.
+pub fn merge_channels(
+    channels: Vec<Vec<TranscriptSegment>>,
+    language: Language,
+) -> Vec<ChannelSegment> {
+    let mut segments: Vec<ChannelSegment> = channels
+        .into_iter()
+        .enumerate()
+        .flat_map(|(index, segments)| {
+            segments.into_iter().map(move |seg| ChannelSegment {
+                start: seg.start,
+                end: seg.end,
+                channel: channel_label(index),
+                text: seg.text.trim().to_string(),
+            })
+        })
+        .filter(|seg| !seg.text.is_empty())
+        .collect();
+    // Stable, so a tie keeps the lower channel first.
+    segments.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+    let mut merged: Vec<ChannelSegment> = Vec::new();
+    for seg in segments {
+        if let Some(last) = merged.last_mut().filter(|last| last.channel == seg.channel) {
+            last.end = last.end.max(seg.end);
+            last.text.push(' ');
+            last.text.push_str(&seg.text);
+            continue;
+        }
+        merged.push(seg);
+    }
+    for seg in &mut merged {
+        seg.text = normalize_nonspeech_markers(&seg.text, language);
+    }
+    merged
+}
+
+/// Merged segments as `[CH1] text` lines, as copied or pasted.
+pub fn channel_transcript(segments: &[ChannelSegment]) -> String {
+    segments
+        .iter()
+        .map(|s| format!("[{}] {}", s.channel, s.text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seg(start: f64, end: f64, text: &str) -> TranscriptSegment {
+        TranscriptSegment {
+            start,
+            end,
+            text: text.to_string(),
+            avg_logprob: None,
+            no_speech_prob: 0.0,
+            words: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn channels_interleave_by_start_time() {
+        let merged = merge_channels(
+            vec![
+                vec![seg(0.0, 2.0, " Hello, support."), seg(5.0, 7.0, " Let me check.")],
+                vec![seg(2.5, 4.5, " Hi, my order is late.")],
+            ],
+            Language::English,
+        );
+        assert_eq!(
+            channel_transcript(&merged),
+            "[CH1] Hello, support.\n[CH2] Hi, my order is late.\n[CH1] Let me check."
+        );
+        assert_eq!(merged[1].start, 2.5);
+        assert_eq!(merged[1].end, 4.5);
+    }
+
+    #[test]
+    fn a_channels_consecutive_segments_become_one_turn() {
+        let merged = merge_channels(
+            vec![
+                vec![seg(0.0, 2.0, " One."), seg(2.0, 4.0, " Two."), seg(9.0, 10.0, " ")],
+                vec![seg(6.0, 8.0, " Three.")],
+            ],
+            Language::English,
+        );
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].text, "One. Two.");
+        assert_eq!(merged[0].end, 4.0);
+        assert_eq!(merged[1].channel, "CH2");
+    }
+}
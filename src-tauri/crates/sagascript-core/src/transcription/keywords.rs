@@ -0,0 +1,202 @@
+//! Keyword biasing: nudge whisper's decoder toward a user-supplied word list.
+//!
+//! The initial prompt only primes the decoder with context, and it is capped
+//! at whisper's prompt window. Call signs, SKUs and drug names still come out
+//! as their nearest everyday spelling. This layer works on the decoder itself:
+//! whisper.cpp calls a logits filter before every sampled token, and
+//! [`KeywordBias::apply`] raises the logits of keyword tokens there.
+//!
+//! - The first token of every keyword gets a small boost, so a keyword wins
+//!   when the audio makes it a close call, but can't be forced into silence.
+//! - Once the decoder has emitted a keyword's first tokens, the next token of
+//!   that keyword gets a much larger boost, so a started keyword is finished
+//!   as spelled instead of drifting into a similar-sounding word.
+
+use whisper_rs::{WhisperContext, WhisperTokenId};
+
+use crate::error::DictationError;
+
+/// Logit boost for the first token of a keyword.
+const START_BOOST: f32 = 2.0;
+/// Logit boost for the next token of a keyword the decoder has started.
+const CONTINUE_BOOST: f32 = 5.0;
+/// Keywords beyond this are ignored (the filter runs once per token).
+pub const MAX_KEYWORDS: usize = 100;
+/// Longest keyword, in tokens, that is biased as a whole.
+const MAX_KEYWORD_TOKENS: usize = 16;
+
+/// Keyword token sequences for one transcription.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeywordBias {
+    sequences: Vec<Vec<WhisperTokenId>>,
+    /// Vocabulary size of the model the sequences were tokenized with, i.e.
+    /// the length of the logits array whisper.cpp filters.
+    n_vocab: usize,
+}
+
+impl KeywordBias {
+    /// Tokenize `keywords` with the loaded model's vocabulary. Each keyword
+    /// is added twice, with and without a leading space, since whisper
+    /// tokenizes a word differently mid-sentence and at the start of one.
+    /// Blank entries are skipped; keywords that fail to tokenize are logged
+    /// and skipped rather than failing the transcription.
+    pub fn tokenize(ctx: &WhisperContext, keywords: &[String]) -> Self {
+        let mut sequences = Vec::new();
+        for keyword in keywords
+            .iter()
+            .map(|k| k.trim())
+            .filter(|k| !k.is_empty())
+            .take(MAX_KEYWORDS)
+        {
+            for text in [format!(" {keyword}"), keyword.to_string()] {
+                match ctx.tokenize(&text, MAX_KEYWORD_TOKENS) {
+                    Ok(tokens) if !tokens.is_empty() => sequences.push(tokens),
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Could not tokenize keyword {keyword:?}: {e}"),
+                }
+            }
+        }
+        Self::from_sequences(sequences, ctx.n_vocab().max(0) as usize)
+    }
+
+    /// Build from already-tokenized keywords.
+    pub fn from_sequences(mut sequences: Vec<Vec<WhisperTokenId>>, n_vocab: usize) -> Self {
+        sequences.retain(|s| !s.is_empty());
+        sequences.sort();
+        sequences.dedup();
+        Self { sequences, n_vocab }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sequences.is_empty()
+    }
+
+    pub fn n_vocab(&self) -> usize {
+        self.n_vocab
+    }
+
+    /// Raise the logits of keyword tokens, given the tokens decoded so far in
+    /// the current segment. Each token is boosted once, by the largest boost
+    /// any keyword gives it; suppressed (`-inf`) and out-of-vocabulary tokens
+    /// are left alone.
+    pub fn apply(&self, history: &[WhisperTokenId], logits: &mut [f32]) {
+        let mut boosts: Vec<(WhisperTokenId, f32)> = Vec::new();
+        for seq in &self.sequences {
+            let started = (1..seq.len()).rev().find(|&k| history.ends_with(&seq[..k]));
+            let (token, boost) = match started {
+                Some(k) => (seq[k], CONTINUE_BOOST),
+                None => (seq[0], START_BOOST),
+            };
+            match boosts.iter_mut().find(|(t, _)| *t == token) {
+                Some((_, b)) => *b = b.max(boost),
+                None => boosts.push((token, boost)),
+            }
+        }
+        for (token, boost) in boosts {
+            if let Some(logit) = usize::try_from(token).ok().and_then(|i| logits.get_mut(i)) {
+                if logit.is_finite() {
+                    *logit += boost;
+                }
+            }
+        }
+    }
+}
+
+/// Parse a comma-separated keyword list, as stored by `config set keywords`.
+/// Entries are trimmed and blank ones dropped; an empty string is no keywords.
+pub fn parse_keywords(value: &str) -> Result<Vec<String>, DictationError> {
+    let keywords: Vec<String> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .map(str::to_string)
+        .collect();
+    if keywords.len() > MAX_KEYWORDS {
+        return Err(DictationError::SettingsError(format!(
+            "too many keywords ({}, at most {MAX_KEYWORDS})",
+            keywords.len()
+        )));
+    }
+    Ok(keywords)
+}
+
+/// Format keywords in the syntax accepted by [`parse_keywords`].
+pub fn format_keywords(keywords: &[String]) -> String {
+    keywords.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logits() -> Vec<f32> {
+        vec![0.0; 10]
+    }
+
+    #[test]
+    fn boosts_first_token_of_each_keyword() {
+        let bias = KeywordBias::from_sequences(vec![vec![3, 4], vec![7]], 10);
+        let mut l = logits();
+        bias.apply(&[1, 2], &mut l);
+        assert_eq!(l[3], START_BOOST);
+        assert_eq!(l[7], START_BOOST);
+        assert_eq!(l[4], 0.0);
+    }
+
+    #[test]
+    fn started_keyword_continues_with_larger_boost() {
+        let bias = KeywordBias::from_sequences(vec![vec![3, 4, 5]], 10);
+        let mut l = logits();
+        bias.apply(&[1, 3, 4], &mut l);
+        assert_eq!(l[5], CONTINUE_BOOST);
+        assert_eq!(l[3], 0.0);
+    }
+
+    #[test]
+    fn completed_keyword_starts_over() {
+        let bias = KeywordBias::from_sequences(vec![vec![3, 4]], 10);
+        let mut l = logits();
+        bias.apply(&[3, 4], &mut l);
+        assert_eq!(l[3], START_BOOST);
+        assert_eq!(l[4], 0.0);
+    }
+
+    #[test]
+    fn shared_token_is_boosted_once() {
+        let bias = KeywordBias::from_sequences(vec![vec![3, 4], vec![6, 4], vec![4]], 10);
+        let mut l = logits();
+        bias.apply(&[6], &mut l);
+        assert_eq!(l[4], CONTINUE_BOOST);
+    }
+
+    #[test]
+    fn suppressed_and_out_of_range_tokens_untouched() {
+        let bias = KeywordBias::from_sequences(vec![vec![2], vec![42], vec![-1]], 10);
+        let mut l = logits();
+        l[2] = f32::NEG_INFINITY;
+        bias.apply(&[], &mut l);
+        assert_eq!(l[2], f32::NEG_INFINITY);
+        assert!(l.iter().skip(3).all(|&x| x == 0.0));
+    }
+
+    #[test]
+    fn empty_and_duplicate_sequences_dropped() {
+        let bias = KeywordBias::from_sequences(vec![vec![], vec![1, 2], vec![1, 2]], 10);
+        assert_eq!(bias.sequences, vec![vec![1, 2]]);
+        assert!(KeywordBias::from_sequences(vec![vec![]], 10).is_empty());
+    }
+
+    #[test]
+    fn keyword_list_round_trips() {
+        let keywords = parse_keywords(" Alpha-7, ,SKU 4411,Metoprolol ").unwrap();
+        assert_eq!(keywords, vec!["Alpha-7", "SKU 4411", "Metoprolol"]);
+        assert_eq!(format_keywords(&keywords), "Alpha-7,SKU 4411,Metoprolol");
+        assert!(parse_keywords("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn keyword_list_is_capped() {
+        let many = vec!["k"; MAX_KEYWORDS + 1].join(",");
+        assert!(parse_keywords(&many).is_err());
+    }
+}
@@ -0,0 +1,819 @@
+//! Numbers and dates written the way the dictation language writes them.
+//!
+//! Whisper spells out many numbers ("tjugofem komma fem", "twenty-fifth of
+//! March") and sometimes uses the wrong decimal separator for the language.
+//! [`localize_numbers`] rewrites those spans in place and leaves everything
+//! else, including whitespace, byte for byte:
+//!
+//! - Spoken decimals become digits with the language's separator:
+//!   "tjugofem komma fem" → "25,5", "three point one four" → "3.14".
+//! - Spoken whole numbers above twelve become digits ("trehundrafemtio" →
+//!   "350"). Twelve and below stay words, as both Swedish and English style
+//!   guides spell them out, and so articles like "en"/"ett" are never touched.
+//!   Years said as two pairs are one number: "nineteen eighty-four" →
+//!   "1984", "tjue tjuefire" → "2024".
+//! - A spoken day next to a month becomes a date: "tjugofemte mars" →
+//!   "25 mars", "tjuefemte mars" → "25. mars", "March twenty-fifth" →
+//!   "March 25th".
+//! - In Swedish and Norwegian, digit decimals with a point ("25.5") get a
+//!   comma. Three fraction digits are left alone: "1.000" is a thousand.
+//!   So are clock times ("kl. 14.30", "klokka 9.15") and versions
+//!   ("version 2.0", "2.0.1").
+//!
+//! Auto-detect has no known language, so it is a no-op.
+
+use super::postprocess::{token_spans, Token};
+use crate::settings::Language;
+
+/// Whole numbers at or below this stay spelled out.
+const SPELL_OUT_MAX: u64 = 12;
+
+/// Words before a time or a version, where "14.30" keeps its point.
+const POINT_KEEPERS: &[&str] = &[
+    "kl", "klockan", "klokka", "klokken", "version", "versjon", "v",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Morpheme {
+    Zero,
+    Unit(u64),
+    Teen(u64),
+    Tens(u64),
+    Hundred,
+    Thousand,
+    And,
+    /// An ordinal day word ("femte", "fifth", "tjugonde"), by value.
+    Ordinal(u64),
+}
+
+use Morpheme::*;
+
+const EN: &[(&str, Morpheme)] = &[
+    ("zero", Zero),
+    ("one", Unit(1)),
+    ("two", Unit(2)),
+    ("three", Unit(3)),
+    ("four", Unit(4)),
+    ("five", Unit(5)),
+    ("six", Unit(6)),
+    ("seven", Unit(7)),
+    ("eight", Unit(8)),
+    ("nine", Unit(9)),
+    ("ten", Teen(10)),
+    ("eleven", Teen(11)),
+    ("twelve", Teen(12)),
+    ("thirteen", Teen(13)),
+    ("fourteen", Teen(14)),
+    ("fifteen", Teen(15)),
+    ("sixteen", Teen(16)),
+    ("seventeen", Teen(17)),
+    ("eighteen", Teen(18)),
+    ("nineteen", Teen(19)),
+    ("twenty", Tens(20)),
+    ("thirty", Tens(30)),
+    ("forty", Tens(40)),
+    ("fifty", Tens(50)),
+    ("sixty", Tens(60)),
+    ("seventy", Tens(70)),
+    ("eighty", Tens(80)),
+    ("ninety", Tens(90)),
+    ("hundred", Hundred),
+    ("thousand", Thousand),
+    ("and", And),
+    ("first", Ordinal(1)),
+    ("second", Ordinal(2)),
+    ("third", Ordinal(3)),
+    ("fourth", Ordinal(4)),
+    ("fifth", Ordinal(5)),
+    ("sixth", Ordinal(6)),
+    ("seventh", Ordinal(7)),
+    ("eighth", Ordinal(8)),
+    ("ninth", Ordinal(9)),
+    ("tenth", Ordinal(10)),
+    ("eleventh", Ordinal(11)),
+    ("twelfth", Ordinal(12)),
+    ("thirteenth", Ordinal(13)),
+    ("fourteenth", Ordinal(14)),
+    ("fifteenth", Ordinal(15)),
+    ("sixteenth", Ordinal(16)),
+    ("seventeenth", Ordinal(17)),
+    ("eighteenth", Ordinal(18)),
+    ("nineteenth", Ordinal(19)),
+    ("twentieth", Ordinal(20)),
+    ("thirtieth", Ordinal(30)),
+];
+
+const SV: &[(&str, Morpheme)] = &[
+    ("noll", Zero),
+    ("en", Unit(1)),
+    ("ett", Unit(1)),
+    ("två", Unit(2)),
+    ("tre", Unit(3)),
+    ("fyra", Unit(4)),
+    ("fem", Unit(5)),
+    ("sex", Unit(6)),
+    ("sju", Unit(7)),
+    ("åtta", Unit(8)),
+    ("nio", Unit(9)),
+    ("tio", Teen(10)),
+    ("elva", Teen(11)),
+    ("tolv", Teen(12)),
+    ("tretton", Teen(13)),
+    ("fjorton", Teen(14)),
+    ("femton", Teen(15)),
+    ("sexton", Teen(16)),
+    ("sjutton", Teen(17)),
+    ("arton", Teen(18)),
+    ("nitton", Teen(19)),
+    ("tjugo", Tens(20)),
+    ("trettio", Tens(30)),
+    ("fyrtio", Tens(40)),
+    ("femtio", Tens(50)),
+    ("sextio", Tens(60)),
+    ("sjuttio", Tens(70)),
+    ("åttio", Tens(80)),
+    ("nittio", Tens(90)),
+    ("hundra", Hundred),
+    ("tusen", Thousand),
+    ("och", And),
+    ("första", Ordinal(1)),
+    ("förste", Ordinal(1)),
+    ("andra", Ordinal(2)),
+    ("andre", Ordinal(2)),
+    ("tredje", Ordinal(3)),
+    ("fjärde", Ordinal(4)),
+    ("femte", Ordinal(5)),
+    ("sjätte", Ordinal(6)),
+    ("sjunde", Ordinal(7)),
+    ("åttonde", Ordinal(8)),
+    ("nionde", Ordinal(9)),
+    ("tionde", Ordinal(10)),
+    ("elfte", Ordinal(11)),
+    ("tolfte", Ordinal(12)),
+    ("trettonde", Ordinal(13)),
+    ("fjortonde", Ordinal(14)),
+    ("femtonde", Ordinal(15)),
+    ("sextonde", Ordinal(16)),
+    ("sjuttonde", Ordinal(17)),
+    ("artonde", Ordinal(18)),
+    ("nittonde", Ordinal(19)),
+    ("tjugonde", Ordinal(20)),
+    ("trettionde", Ordinal(30)),
+];
+
+const NO: &[(&str, Morpheme)] = &[
+    ("null", Zero),
+    ("en", Unit(1)),
+    ("ett", Unit(1)),
+    ("et", Unit(1)),
+    ("ei", Unit(1)),
+    ("to", Unit(2)),
+    ("tre", Unit(3)),
+    ("fire", Unit(4)),
+    ("fem", Unit(5)),
+    ("seks", Unit(6)),
+    ("sju", Unit(7)),
+    ("syv", Unit(7)),
+    ("åtte", Unit(8)),
+    ("ni", Unit(9)),
+    ("ti", Teen(10)),
+    ("elleve", Teen(11)),
+    ("tolv", Teen(12)),
+    ("tretten", Teen(13)),
+    ("fjorten", Teen(14)),
+    ("femten", Teen(15)),
+    ("seksten", Teen(16)),
+    ("sytten", Teen(17)),
+    ("atten", Teen(18)),
+    ("nitten", Teen(19)),
+    ("tjue", Tens(20)),
+    ("tretti", Tens(30)),
+    ("førti", Tens(40)),
+    ("femti", Tens(50)),
+    ("seksti", Tens(60)),
+    ("sytti", Tens(70)),
+    ("åtti", Tens(80)),
+    ("nitti", Tens(90)),
+    ("hundre", Hundred),
+    ("tusen", Thousand),
+    ("og", And),
+    ("første", Ordinal(1)),
+    ("andre", Ordinal(2)),
+    ("tredje", Ordinal(3)),
+    ("fjerde", Ordinal(4)),
+    ("femte", Ordinal(5)),
+    ("sjette", Ordinal(6)),
+    ("sjuende", Ordinal(7)),
+    ("syvende", Ordinal(7)),
+    ("åttende", Ordinal(8)),
+    ("niende", Ordinal(9)),
+    ("tiende", Ordinal(10)),
+    ("ellevte", Ordinal(11)),
+    ("tolvte", Ordinal(12)),
+    ("trettende", Ordinal(13)),
+    ("fjortende", Ordinal(14)),
+    ("femtende", Ordinal(15)),
+    ("sekstende", Ordinal(16)),
+    ("syttende", Ordinal(17)),
+    ("attende", Ordinal(18)),
+    ("nittende", Ordinal(19)),
+    ("tjuende", Ordinal(20)),
+    ("trettiende", Ordinal(30)),
+];
+
+const EN_MONTHS: &[&str] = &[
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+const SV_MONTHS: &[&str] = &[
+    "januari",
+    "februari",
+    "mars",
+    "april",
+    "maj",
+    "juni",
+    "juli",
+    "augusti",
+    "september",
+    "oktober",
+    "november",
+    "december",
+];
+
+const NO_MONTHS: &[&str] = &[
+    "januar",
+    "februar",
+    "mars",
+    "april",
+    "mai",
+    "juni",
+    "juli",
+    "august",
+    "september",
+    "oktober",
+    "november",
+    "desember",
+];
+
+/// How a date's day is written.
+#[derive(Clone, Copy, PartialEq)]
+enum DayStyle {
+    /// "March 25th", "the 25th of March".
+    EnglishOrdinal,
+    /// "25. mars".
+    Period,
+    /// "25 mars".
+    Plain,
+}
+
+/// Per-language vocabulary and spelling.
+struct Locale {
+    words: &'static [(&'static str, Morpheme)],
+    months: &'static [&'static str],
+    /// The spoken decimal separator ("komma", "point").
+    decimal_word: &'static str,
+    decimal_separator: char,
+    day_style: DayStyle,
+}
+
+impl Locale {
+    fn for_language(language: Language) -> Option<Self> {
+        match language {
+            Language::English => Some(Self {
+                words: EN,
+                months: EN_MONTHS,
+                decimal_word: "point",
+                decimal_separator: '.',
+                day_style: DayStyle::EnglishOrdinal,
+            }),
+            Language::Swedish => Some(Self {
+                words: SV,
+                months: SV_MONTHS,
+                decimal_word: "komma",
+                decimal_separator: ',',
+                day_style: DayStyle::Plain,
+            }),
+            Language::Norwegian => Some(Self {
+                words: NO,
+                months: NO_MONTHS,
+                decimal_word: "komma",
+                decimal_separator: ',',
+                day_style: DayStyle::Period,
+            }),
+            Language::Auto => None,
+        }
+    }
+
+    /// Split a word into number morphemes: hyphenated parts, each a run of
+    /// table entries ("trehundrafemtio" → tre, hundra, femtio). Longest
+    /// match first, so "sjuttio" is not read as "sju" + "ttio".
+    fn morphemes(&self, word: &str) -> Option<Vec<Morpheme>> {
+        let mut out = Vec::new();
+        for part in word.split('-') {
+            let mut rest = part;
+            if rest.is_empty() {
+                return None;
+            }
+            while !rest.is_empty() {
+                let (text, morpheme) = self
+                    .words
+                    .iter()
+                    .filter(|(text, _)| rest.starts_with(text))
+                    .max_by_key(|(text, _)| text.len())?;
+                // "och"/"and" only joins separate words.
+                if *morpheme == And && (rest.len() != text.len() || part.len() != word.len()) {
+                    return None;
+                }
+                out.push(*morpheme);
+                rest = &rest[text.len()..];
+            }
+        }
+        Some(out)
+    }
+
+    /// English months are capitalized, which keeps "you may first…" intact.
+    fn is_month(&self, word: &Word) -> bool {
+        self.months.contains(&word.core.as_str())
+            && (self.day_style != DayStyle::EnglishOrdinal
+                || word.span.text.starts_with(char::is_uppercase))
+    }
+
+    fn day(&self, day: u64) -> String {
+        match self.day_style {
+            DayStyle::EnglishOrdinal => {
+                let suffix = match (day % 10, day % 100) {
+                    (_, 11..=13) => "th",
+                    (1, _) => "st",
+                    (2, _) => "nd",
+                    (3, _) => "rd",
+                    _ => "th",
+                };
+                format!("{day}{suffix}")
+            }
+            DayStyle::Period => format!("{day}."),
+            DayStyle::Plain => day.to_string(),
+        }
+    }
+}
+
+/// One whitespace-separated token, lowercased, with trailing punctuation
+/// split off (it is kept when the token is rewritten).
+struct Word<'a> {
+    span: Token<'a>,
+    core: String,
+    punct: &'a str,
+}
+
+impl<'a> Word<'a> {
+    fn new(span: Token<'a>) -> Self {
+        let core = span
+            .text
+            .trim_end_matches(['.', ',', '!', '?', ';', ':', ')']);
+        Self {
+            punct: &span.text[core.len()..],
+            core: core.to_lowercase(),
+            span,
+        }
+    }
+}
+
+/// Rewrite spoken numbers, decimals and dates in `text` the way `language`
+/// writes them. See the module docs for exactly what changes.
+pub fn localize_numbers(text: &str, language: Language) -> String {
+    let Some(locale) = Locale::for_language(language) else {
+        return text.to_string();
+    };
+    let words: Vec<Word> = token_spans(text).into_iter().map(Word::new).collect();
+
+    let mut rendered = String::with_capacity(text.len());
+    let mut cursor = 0;
+    let mut index = 0;
+    while index < words.len() {
+        let previous = index.checked_sub(1).map(|i| &words[i]);
+        let Some((replacement, consumed)) = rewrite_at(&locale, previous, &words[index..]) else {
+            index += 1;
+            continue;
+        };
+        let first = &words[index];
+        let last = &words[index + consumed - 1];
+        rendered.push_str(&text[cursor..first.span.start]);
+        rendered.push_str(&replacement);
+        rendered.push_str(last.punct);
+        cursor = last.span.end;
+        index += consumed;
+    }
+
+    if cursor == 0 {
+        return text.to_string();
+    }
+    rendered.push_str(&text[cursor..]);
+    rendered
+}
+
+/// The rewrite for the words starting at `words[0]`, if any, and how many
+/// words it replaces. The last replaced word's punctuation is kept;
+/// `previous` is the word before, if there is one.
+fn rewrite_at(locale: &Locale, previous: Option<&Word>, words: &[Word]) -> Option<(String, usize)> {
+    // "March twenty-fifth": the month stays, the day is rewritten.
+    if locale.day_style == DayStyle::EnglishOrdinal
+        && words[0].punct.is_empty()
+        && locale.is_month(&words[0])
+    {
+        if let Some((day, n)) = ordinal_day(locale, &words[1..]) {
+            return Some((format!("{} {}", words[0].span.text, locale.day(day)), n + 1));
+        }
+    }
+
+    // "tjugofemte mars", "twenty-fifth of March".
+    if let Some((day, n)) = ordinal_day(locale, words) {
+        if words[n - 1].punct.is_empty() {
+            let month_at = match words.get(n) {
+                Some(w) if w.core == "of" && w.punct.is_empty() => n + 1,
+                _ => n,
+            };
+            if words.get(month_at).is_some_and(|w| locale.is_month(w)) {
+                return Some((locale.day(day), n));
+            }
+        }
+    }
+
+    if let Some(run) = cardinal_run(locale, words) {
+        // "tjugofem komma fem" → "25,5".
+        let separator_at = run.words;
+        if words[separator_at - 1].punct.is_empty()
+            && words
+                .get(separator_at)
+                .is_some_and(|w| w.core == locale.decimal_word && w.punct.is_empty())
+        {
+            // Digits one word each ("point one four"), or else a single
+            // number ("komma fjorton"). Whatever follows is its own number.
+            let mut fraction = String::new();
+            let mut at = separator_at + 1;
+            while let Some(digit) = words.get(at).and_then(|w| single_digit(locale, w)) {
+                fraction.push_str(&digit.to_string());
+                at += 1;
+                if !words[at - 1].punct.is_empty() {
+                    break;
+                }
+            }
+            if fraction.is_empty() {
+                if let Some(part) = words.get(at..).and_then(|rest| cardinal_run(locale, rest)) {
+                    fraction = part.value.to_string();
+                    at += part.words;
+                }
+            }
+            if !fraction.is_empty() {
+                return Some((
+                    format!("{}{}{}", run.value, locale.decimal_separator, fraction),
+                    at,
+                ));
+            }
+        }
+        // A bare "tusen"/"hundred" is an idiom ("tusen takk"), not a number.
+        let bare_multiplier = run.morphemes == 1 && matches!(run.value, 100 | 1000);
+        if run.value > SPELL_OUT_MAX && !bare_multiplier {
+            return Some((run.value.to_string(), run.words));
+        }
+    }
+
+    // "25.5" → "25,5" where the comma is the decimal separator, but not
+    // "kl. 14.30" or "version 2.0".
+    let keeps_point = previous.is_some_and(|w| POINT_KEEPERS.contains(&w.core.as_str()));
+    if locale.decimal_separator == ',' && !keeps_point {
+        if let Some((int, frac)) = words[0].core.split_once('.') {
+            let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+            if digits(int) && digits(frac) && frac.len() <= 2 {
+                return Some((format!("{int},{frac}"), 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// A whole number spoken over one or more words.
+struct Run {
+    value: u64,
+    words: usize,
+    morphemes: usize,
+}
+
+/// Accumulates morphemes into a value, rejecting orders no one says
+/// ("fem fem", "hundred hundred").
+#[derive(Clone, Copy, Default)]
+struct Cardinal {
+    total: u64,
+    current: u64,
+    last: Option<Morpheme>,
+    seen_thousand: bool,
+    /// Read as a year said in pairs ("nineteen eighty-four"); nothing
+    /// larger can follow.
+    year: bool,
+    morphemes: usize,
+}
+
+impl Cardinal {
+    fn push(&mut self, next: Morpheme) -> bool {
+        let ok = match next {
+            Zero => self.last.is_none(),
+            Unit(v) => {
+                let ok = matches!(self.last, None | Some(Tens(_) | Hundred | Thousand | And));
+                self.current += v;
+                ok
+            }
+            Teen(v) | Tens(v) if self.starts_year() => {
+                self.current = self.current * 100 + v;
+                self.year = true;
+                true
+            }
+            Teen(v) | Tens(v) => {
+                let ok = matches!(self.last, None | Some(Hundred | Thousand | And));
+                self.current += v;
+                ok
+            }
+            Hundred => {
+                let ok = matches!(self.last, None | Some(Unit(_) | Teen(_))) && self.current < 100;
+                self.current = self.current.max(1) * 100;
+                ok
+            }
+            Thousand => {
+                let ok = !self.seen_thousand
+                    && !self.year
+                    && matches!(
+                        self.last,
+                        None | Some(Unit(_) | Teen(_) | Tens(_) | Hundred)
+                    );
+                self.total += self.current.max(1) * 1000;
+                self.current = 0;
+                self.seen_thousand = true;
+                ok
+            }
+            And => matches!(self.last, Some(Hundred | Thousand)),
+            Ordinal(_) => false,
+        };
+        // Nothing follows a lone zero.
+        let ok = ok && self.last != Some(Zero);
+        self.last = Some(next);
+        self.morphemes += 1;
+        ok
+    }
+
+    fn value(&self) -> u64 {
+        self.total + self.current
+    }
+
+    /// Whether the number so far is the first pair of a year from 1300 to
+    /// 2099 ("nineteen", "twenty"). Eleven and twelve are left out, as
+    /// "eleven thirty" is a time.
+    fn starts_year(&self) -> bool {
+        !self.year
+            && self.total == 0
+            && self.current < 100
+            && matches!(self.last, Some(Teen(13..=19) | Tens(20)))
+    }
+}
+
+/// The value of `word` if it is one digit ("fem", "zero").
+fn single_digit(locale: &Locale, word: &Word) -> Option<u64> {
+    match locale.morphemes(&word.core)?.as_slice() {
+        [Zero] => Some(0),
+        [Unit(d)] => Some(*d),
+        _ => None,
+    }
+}
+
+/// The longest whole number spoken at the start of `words`. A word with
+/// trailing punctuation ends the run; a trailing "och"/"and" is not part of
+/// it.
+fn cardinal_run(locale: &Locale, words: &[Word]) -> Option<Run> {
+    let mut state = Cardinal::default();
+    let mut best = None;
+    for (i, word) in words.iter().enumerate() {
+        let Some(morphemes) = locale.morphemes(&word.core) else {
+            break;
+        };
+        let mut next = state;
+        if !morphemes.into_iter().all(|m| next.push(m)) {
+            break;
+        }
+        state = next;
+        if state.last != Some(And) {
+            best = Some(Run {
+                value: state.value(),
+                words: i + 1,
+                morphemes: state.morphemes,
+            });
+        }
+        if !word.punct.is_empty() {
+            break;
+        }
+    }
+    best
+}
+
+/// A day of the month spoken as an ordinal at the start of `words`
+/// ("tjugofemte", "twenty-fifth", "twenty fifth"), and how many words it
+/// takes.
+fn ordinal_day(locale: &Locale, words: &[Word]) -> Option<(u64, usize)> {
+    let first = locale.morphemes(&words.first()?.core)?;
+    // "twenty fifth" as two words.
+    let (morphemes, n) = if matches!(first.as_slice(), [Tens(_)]) && words[0].punct.is_empty() {
+        let mut both = first;
+        both.extend(locale.morphemes(&words.get(1)?.core)?);
+        (both, 2)
+    } else {
+        (first, 1)
+    };
+    let day = match morphemes.as_slice() {
+        [Ordinal(d)] => *d,
+        [Tens(t @ (20 | 30)), Ordinal(d @ 1..=9)] => t + d,
+        _ => return None,
+    };
+    (1..=31).contains(&day).then_some((day, n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sv(text: &str) -> String {
+        localize_numbers(text, Language::Swedish)
+    }
+
+    fn no(text: &str) -> String {
+        localize_numbers(text, Language::Norwegian)
+    }
+
+    fn en(text: &str) -> String {
+        localize_numbers(text, Language::English)
+    }
+
+    #[test]
+    fn swedish_spoken_decimal() {
+        assert_eq!(
+            sv("Det blev tjugofem komma fem grader."),
+            "Det blev 25,5 grader."
+        );
+        assert_eq!(sv("tre komma fjorton"), "3,14");
+        assert_eq!(sv("noll komma noll fem"), "0,05");
+    }
+
+    #[test]
+    fn swedish_whole_numbers() {
+        assert_eq!(sv("Vi var trehundrafemtio personer"), "Vi var 350 personer");
+        assert_eq!(sv("ett tusen två hundra"), "1200");
+        assert_eq!(sv("hundra och fem"), "105");
+        assert_eq!(sv("tjugo fem"), "25");
+    }
+
+    #[test]
+    fn small_numbers_and_articles_stay_words() {
+        assert_eq!(
+            sv("Jag har en katt och tre hundar"),
+            "Jag har en katt och tre hundar"
+        );
+        assert_eq!(sv("Klockan tolv"), "Klockan tolv");
+        assert_eq!(en("one of the two options"), "one of the two options");
+        assert_eq!(
+            no("Jeg har ei bok og fire stoler"),
+            "Jeg har ei bok og fire stoler"
+        );
+    }
+
+    #[test]
+    fn impossible_sequences_are_not_merged() {
+        assert_eq!(sv("fem fem"), "fem fem");
+        assert_eq!(en("five hundred hundred"), "500 hundred");
+        // Not a year: a first pair of 21 or more.
+        assert_eq!(en("thirty forty"), "30 40");
+    }
+
+    #[test]
+    fn years_in_pairs_are_one_number() {
+        assert_eq!(en("back in nineteen eighty-four"), "back in 1984");
+        assert_eq!(en("in twenty twenty-four."), "in 2024.");
+        assert_eq!(en("twenty thirty"), "2030");
+        assert_eq!(en("nineteen ten"), "1910");
+        assert_eq!(sv("år nitton åttiofyra"), "år 1984");
+        assert_eq!(sv("tjugo tjugofyra"), "2024");
+        assert_eq!(sv("nittonhundraåttiofyra"), "1984");
+        assert_eq!(no("i nitten åttifire"), "i 1984");
+        assert_eq!(no("tjue tjuefire"), "2024");
+        // No thousands after a year, and "eleven thirty" is a time.
+        assert_eq!(en("nineteen eighty thousand"), "1980 thousand");
+        assert_eq!(en("eleven thirty"), "eleven 30");
+    }
+
+    #[test]
+    fn swedish_dates() {
+        assert_eq!(sv("Mötet är den tjugofemte mars"), "Mötet är den 25 mars");
+        assert_eq!(sv("första maj"), "1 maj");
+        assert_eq!(sv("den trettioförsta december."), "den 31 december.");
+    }
+
+    #[test]
+    fn swedish_point_decimals_get_a_comma() {
+        assert_eq!(sv("Det kostar 25.5 kronor."), "Det kostar 25,5 kronor.");
+        assert_eq!(sv("Det är 1.000 personer"), "Det är 1.000 personer");
+        assert_eq!(sv("Version 2.0.1"), "Version 2.0.1");
+        assert_eq!(sv("Uppdatera till 2.0.1.5"), "Uppdatera till 2.0.1.5");
+    }
+
+    #[test]
+    fn clock_times_keep_their_point() {
+        assert_eq!(
+            sv("Vi ses kl. 14.30 i morgon."),
+            "Vi ses kl. 14.30 i morgon."
+        );
+        assert_eq!(sv("Klockan 9.15 börjar det"), "Klockan 9.15 börjar det");
+        assert_eq!(no("Møtet er klokka 14.30."), "Møtet er klokka 14.30.");
+        assert_eq!(no("kl. 8.45"), "kl. 8.45");
+    }
+
+    #[test]
+    fn versions_keep_their_point() {
+        assert_eq!(
+            sv("Installera version 2.0 först"),
+            "Installera version 2.0 först"
+        );
+        assert_eq!(no("Versjon 1.5 er ute"), "Versjon 1.5 er ute");
+        assert_eq!(sv("Kör v 3.11"), "Kör v 3.11");
+        // A decimal after any other word still gets a comma.
+        assert_eq!(sv("Det tog 2.5 timmar"), "Det tog 2,5 timmar");
+    }
+
+    #[test]
+    fn norwegian_decimals_and_dates() {
+        assert_eq!(no("tjuefem komma fem prosent"), "25,5 prosent");
+        assert_eq!(no("tjue fem"), "25");
+        assert_eq!(no("Møtet er tjuefemte mars"), "Møtet er 25. mars");
+        assert_eq!(no("syttende mai!"), "17. mai!");
+        assert_eq!(no("Det koster 9.90 kroner"), "Det koster 9,90 kroner");
+    }
+
+    #[test]
+    fn english_decimals_and_numbers() {
+        assert_eq!(
+            en("It was twenty-five point five degrees."),
+            "It was 25.5 degrees."
+        );
+        assert_eq!(en("three point one four"), "3.14");
+        assert_eq!(en("two thousand and twenty-four"), "2024");
+        // The fraction ends with its digits; a later number is its own.
+        assert_eq!(
+            en("three point five and then twenty two"),
+            "3.5 and then 22"
+        );
+        assert_eq!(en("three point five twenty"), "3.5 20");
+        assert_eq!(en("one hundred and"), "100 and");
+        // A point decimal is already right in English.
+        assert_eq!(en("25.5"), "25.5");
+    }
+
+    #[test]
+    fn english_dates() {
+        assert_eq!(en("Due March twenty-fifth."), "Due March 25th.");
+        assert_eq!(en("the twenty first of May"), "the 21st of May");
+        assert_eq!(en("on the third of June"), "on the 3rd of June");
+        assert_eq!(en("the eleventh of May"), "the 11th of May");
+        // An ordinal that isn't a day stays a word.
+        assert_eq!(en("the fifth attempt"), "the fifth attempt");
+        assert_eq!(en("you may first check"), "you may first check");
+    }
+
+    #[test]
+    fn bare_hundred_and_thousand_stay_words() {
+        assert_eq!(no("Tusen takk!"), "Tusen takk!");
+        assert_eq!(sv("tusen tack"), "tusen tack");
+        assert_eq!(sv("tvåtusen"), "2000");
+    }
+
+    #[test]
+    fn punctuation_ends_a_number() {
+        assert_eq!(sv("tjugo, fem"), "20, fem");
+        assert_eq!(sv("Summan blev femtio."), "Summan blev 50.");
+    }
+
+    #[test]
+    fn auto_detect_is_a_no_op() {
+        let text = "tjugofem komma fem";
+        assert_eq!(localize_numbers(text, Language::Auto), text);
+    }
+
+    #[test]
+    fn whitespace_is_preserved() {
+        assert_eq!(sv("  tjugofem\tkomma  fem "), "  25,5 ");
+        assert_eq!(sv(" Inga tal här.\n"), " Inga tal här.\n");
+    }
+}
@@ -1,11 +1,33 @@
+pub mod backend;
+pub mod benchmark;
+pub mod bundle;
+pub mod catalog;
+pub mod channels;
+pub mod keywords;
+mod localize;
 pub mod model;
 mod postprocess;
+mod punctuation;
+pub mod recommend;
+pub mod rtf;
+pub mod runtime;
+pub mod throttle;
+pub mod timing;
+pub mod updates;
+pub mod usage;
+mod voice_commands;
 pub mod whisper_backend;
 
 #[cfg(target_os = "macos")]
 mod metal_preflight;
 
 pub use whisper_backend::{
-    FILE_TRANSCRIBE_BEAM, TranscribeOptions, TranscriptSegment, WhisperBackend,
+    Decoding, FILE_TRANSCRIBE_BEAM, JobAbort, TranscribeOptions, Transcript,
+    TranscriptSegment, WhisperBackend, WordTiming, join_segments,
 };
+pub use localize::localize_numbers;
 pub use postprocess::normalize_nonspeech_markers;
+pub use punctuation::strip_punctuation;
+pub use rtf::{ModelSuggestion, RtfTracker, RTF_SUGGESTION_THRESHOLD};
+pub use runtime::{AccelerationInfo, RuntimeInfo};
+pub use voice_commands::apply_voice_commands;
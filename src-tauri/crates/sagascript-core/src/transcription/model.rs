@@ -5,9 +5,10 @@ use std::time::{Duration, SystemTime};
 use tracing::info;
 
 use crate::download::{
-    DownloadIntegrity, ExistingArtifact, GGML_MAGIC, download_to_path,
+    DownloadIntegrity, ExistingArtifact, GGML_MAGIC, download_to_path, download_with_fallbacks,
     prepare_existing_artifact, verify_file,
 };
+use super::catalog::CatalogEntry;
 use crate::error::DictationError;
 use crate::settings::WhisperModel;
 
@@ -53,9 +54,135 @@ pub fn migrate_legacy_models_dir(base: &Path) -> PathBuf {
     new_dir
 }
 
-/// Get the full path to a model's GGML file
+/// Environment variable naming the shared models directory; set it empty
+/// to use none.
+pub const SHARED_MODELS_DIR_ENV: &str = "SAGASCRIPT_SHARED_MODELS_DIR";
+
+/// A read-only models directory shared by every user of the machine, so a
+/// lab or office stores each model once: `$SAGASCRIPT_SHARED_MODELS_DIR`,
+/// else `/usr/local/share/sagascript/models` (`%ProgramData%\Sagascript\Models`
+/// on Windows). `None` unless it exists. Models are put there by an
+/// administrator, never downloaded into it.
+pub fn shared_models_dir() -> Option<PathBuf> {
+    let dir = match std::env::var_os(SHARED_MODELS_DIR_ENV) {
+        Some(dir) if dir.is_empty() => return None,
+        Some(dir) => PathBuf::from(dir),
+        None => default_shared_models_dir()?,
+    };
+    dir.is_dir().then_some(dir)
+}
+
+#[cfg(unix)]
+fn default_shared_models_dir() -> Option<PathBuf> {
+    Some(PathBuf::from("/usr/local/share/sagascript/models"))
+}
+
+#[cfg(windows)]
+fn default_shared_models_dir() -> Option<PathBuf> {
+    std::env::var_os("ProgramData").map(|dir| PathBuf::from(dir).join("Sagascript").join("Models"))
+}
+
+/// `filename` in `shared`, if it's there with the expected size. A file
+/// of another size is an older revision or a partial copy; the per-user
+/// one is used instead.
+fn shared_copy(shared: Option<PathBuf>, filename: &str, size: u64) -> Option<PathBuf> {
+    let path = shared?.join(filename);
+    std::fs::metadata(&path)
+        .is_ok_and(|metadata| metadata.len() == size)
+        .then_some(path)
+}
+
+/// Get the full path to a model's GGML file: the shared models directory's
+/// copy where it has one (see [`shared_models_dir`]), else the per-user one.
 pub fn model_path(model: WhisperModel) -> PathBuf {
-    models_dir().join(model.ggml_filename())
+    let filename = model.ggml_filename();
+    let size = model.download_integrity().size;
+    shared_copy(shared_models_dir(), &filename, size).unwrap_or_else(|| models_dir().join(filename))
+}
+
+/// Directory `model`'s file is loaded from, which is where its CoreML
+/// encoder is looked for too.
+pub fn model_dir(model: WhisperModel) -> PathBuf {
+    match model_path(model).parent() {
+        Some(dir) => dir.to_path_buf(),
+        None => models_dir(),
+    }
+}
+
+/// Whether `name` can name a custom model: letters, digits, `.`, `_` and
+/// `-`, and not the ID of a built-in model.
+pub fn validate_custom_model_name(name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+    {
+        return Err(format!(
+            "'{name}' can't name a model: use letters, digits, '.', '_' and '-'"
+        ));
+    }
+    if super::catalog::all_models()
+        .into_iter()
+        .any(|m| super::catalog::model_id(m) == name)
+    {
+        return Err(format!("'{name}' is a built-in model; pick another name"));
+    }
+    Ok(())
+}
+
+/// Check that `path` is a GGML file whisper.cpp can be handed. A custom
+/// model has no published checksum, so its header is all there is to check.
+pub fn check_custom_model_file(path: &Path) -> Result<(), DictationError> {
+    use std::io::Read;
+
+    let mut magic = [0u8; 4];
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .map_err(|e| {
+            DictationError::TranscriptionFailed(format!(
+                "Can't read custom model {}: {e}",
+                path.display()
+            ))
+        })?;
+    if magic != GGML_MAGIC {
+        return Err(DictationError::TranscriptionFailed(format!(
+            "{} is not a GGML model file",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Register the GGML file at `path` as custom model `name`, replacing a
+/// model registered under that name. Returns the absolute path saved.
+pub fn add_custom_model(name: &str, path: &Path) -> Result<PathBuf, DictationError> {
+    validate_custom_model_name(name).map_err(DictationError::SettingsError)?;
+    let path = std::fs::canonicalize(path).map_err(|e| {
+        DictationError::SettingsError(format!("Can't find {}: {e}", path.display()))
+    })?;
+    check_custom_model_file(&path)?;
+    crate::settings::store::update(|settings| {
+        settings
+            .custom_models
+            .insert(name.to_string(), path.clone());
+    })
+    .map_err(DictationError::SettingsError)?;
+    info!("Registered custom model '{name}': {}", path.display());
+    Ok(path)
+}
+
+/// Forget custom model `name` (the file stays), deselecting it if it was
+/// the one in use. Returns whether it was registered.
+pub fn remove_custom_model(name: &str) -> Result<bool, DictationError> {
+    let mut removed = false;
+    crate::settings::store::update(|settings| {
+        removed = settings.custom_models.remove(name).is_some();
+        if settings.custom_model == name {
+            settings.custom_model.clear();
+        }
+    })
+    .map_err(DictationError::SettingsError)?;
+    Ok(removed)
 }
 
 /// Check if a model is already downloaded
@@ -64,10 +191,84 @@ pub fn is_model_downloaded(model: WhisperModel) -> bool {
         .is_ok_and(|metadata| metadata.len() == model.download_integrity().size)
 }
 
+/// Delete `model`'s GGML file and its CoreML encoder, where present.
+/// Returns the bytes freed; 0 if nothing of it was on disk. A copy in the
+/// shared models directory is left alone.
+pub fn delete_model(model: WhisperModel) -> Result<u64, DictationError> {
+    let dir = models_dir();
+    let delete_failed = |path: &Path, e: std::io::Error| {
+        DictationError::SettingsError(format!("Failed to delete '{}': {e}", path.display()))
+    };
+    let mut freed = 0;
+    let path = dir.join(model.ggml_filename());
+    if let Ok(metadata) = std::fs::metadata(&path) {
+        std::fs::remove_file(&path).map_err(|e| delete_failed(&path, e))?;
+        freed += metadata.len();
+    }
+    if let Some(encoder) = model.coreml_encoder_dirname().map(|name| dir.join(name)) {
+        if encoder.is_dir() {
+            let size = dir_size(&encoder);
+            std::fs::remove_dir_all(&encoder).map_err(|e| delete_failed(&encoder, e))?;
+            freed += size;
+        }
+    }
+    if freed > 0 {
+        info!("Deleted {} ({freed} bytes)", model.display_name());
+        super::usage::forget(model);
+    }
+    Ok(freed)
+}
+
+/// Bytes `model` takes in the per-user models directory: its GGML file and
+/// CoreML encoder. A copy in the shared models directory isn't counted.
+pub fn disk_usage(model: WhisperModel) -> u64 {
+    let dir = models_dir();
+    let file = std::fs::metadata(dir.join(model.ggml_filename())).map_or(0, |m| m.len());
+    let encoder = model
+        .coreml_encoder_dirname()
+        .map_or(0, |name| dir_size(&dir.join(name)));
+    file + encoder
+}
+
+/// Total size of the files under `dir`.
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            _ => entry.metadata().map(|m| m.len()).unwrap_or(0),
+        })
+        .sum()
+}
+
+/// Refuse `model` if it has a pin (`Settings::model_pins`) that its file,
+/// already verified against the built-in checksum, or this whisper.cpp
+/// build doesn't satisfy.
+pub fn check_model_pin(model: WhisperModel) -> Result<(), DictationError> {
+    let id = super::catalog::model_id(model);
+    let settings = crate::settings::store::load();
+    let Some(pin) = settings.model_pins.get(&id) else {
+        return Ok(());
+    };
+    pin.check(
+        model.download_integrity().sha256,
+        super::runtime::WHISPER_RS_SYS_VERSION,
+    )
+    .map_err(|why| {
+        DictationError::TranscriptionFailed(format!(
+            "Model '{}' doesn't match its pin: {why}. See 'sagascript config model-pin {id}'",
+            model.display_name()
+        ))
+    })
+}
+
 /// Silero VAD model filename (used by whisper.cpp's built-in VAD).
 pub const VAD_MODEL_FILENAME: &str = "ggml-silero-v5.1.2.bin";
 const VAD_MODEL_URL: &str = "https://huggingface.co/ggml-org/whisper-vad/resolve/9ffd54a1e1ee413ddf265af9913beaf518d1639b/ggml-silero-v5.1.2.bin";
-const VAD_MODEL_INTEGRITY: DownloadIntegrity = DownloadIntegrity {
+pub(crate) const VAD_MODEL_INTEGRITY: DownloadIntegrity = DownloadIntegrity {
     sha256: "29940d98d42b91fbd05ce489f3ecf7c72f0a42f027e4875919a28fb4c04ea2cf",
     size: 885_098,
 };
@@ -139,11 +340,28 @@ pub async fn download_model(
     model: WhisperModel,
     progress_callback: impl Fn(u64, u64) + Send + 'static,
 ) -> Result<PathBuf, DictationError> {
+    // An administrator's copy is used in place, without a per-user one.
+    let shared = shared_copy(
+        shared_models_dir(),
+        &model.ggml_filename(),
+        model.download_integrity().size,
+    );
+    if let Some(path) = shared.filter(|path| verify_file(path, model.download_integrity()).is_ok())
+    {
+        info!(
+            "Model {} is in the shared models directory: {}",
+            model.display_name(),
+            path.display()
+        );
+        return Ok(path);
+    }
+
     let dir = models_dir();
     let path = dir.join(model.ggml_filename());
 
     if prepare_existing_artifact(&path, model.download_integrity())? == ExistingArtifact::Verified {
         info!("Model {} already exists at {}", model.display_name(), path.display());
+        super::updates::record_installed(model);
         // Backfill the CoreML encoder for models downloaded before it was added.
         #[cfg(target_os = "macos")]
         if let Err(e) = ensure_coreml_encoder(model, &dir).await {
@@ -152,15 +370,21 @@ pub async fn download_model(
         return Ok(path);
     }
 
+    let (url, fallbacks) = sources_for(
+        &super::catalog::model_id(model),
+        model.download_url(),
+        model.download_mirrors(),
+    );
     info!(
         "Downloading {} from {} (~{}MB)",
         model.display_name(),
-        model.download_url(),
+        url,
         model.size_mb()
     );
 
-    download_to_path(
-        model.download_url(),
+    download_with_fallbacks(
+        &url,
+        &fallbacks,
         &path,
         "bin",
         model.download_integrity(),
@@ -170,6 +394,7 @@ pub async fn download_model(
     .await?;
 
     info!("Model downloaded: {}", path.display());
+    super::updates::record_installed(model);
 
     // Best-effort: fetch the CoreML encoder so whisper.cpp runs the encoder on
     // the Neural Engine instead of falling back to the Metal encoder. Non-fatal
@@ -182,6 +407,47 @@ pub async fn download_model(
     Ok(path)
 }
 
+/// Download a community model listed in the user's `models.json` and
+/// register it as custom model `entry.id`. Returns its path.
+pub async fn download_catalog_model(
+    entry: &'static CatalogEntry,
+    progress_callback: impl Fn(u64, u64) + Send + 'static,
+) -> Result<PathBuf, DictationError> {
+    let path = models_dir().join(entry.local_file());
+    if prepare_existing_artifact(&path, entry.integrity())? == ExistingArtifact::Verified {
+        info!("Model {} already exists at {}", entry.display_name(), path.display());
+    } else {
+        let (url, fallbacks) = sources_for(&entry.id, entry.url(), entry.mirrors());
+        info!("Downloading {} from {}", entry.display_name(), url);
+        download_with_fallbacks(
+            &url,
+            &fallbacks,
+            &path,
+            "bin",
+            entry.integrity(),
+            Some(&GGML_MAGIC),
+            progress_callback,
+        )
+        .await?;
+        info!("Model downloaded: {}", path.display());
+    }
+    add_custom_model(&entry.id, &path)
+}
+
+/// Where to download model `id` from: its `model_urls` entry if it has one,
+/// falling back to `url` and then `mirrors`; otherwise `url` and `mirrors`.
+fn sources_for(id: &str, url: &str, mirrors: &[String]) -> (String, Vec<String>) {
+    match crate::settings::store::load().model_urls.get(id) {
+        Some(custom) => (
+            custom.clone(),
+            std::iter::once(url.to_string())
+                .chain(mirrors.iter().cloned())
+                .collect(),
+        ),
+        None => (url.to_string(), mirrors.to_vec()),
+    }
+}
+
 /// Download and install the CoreML encoder (`ggml-<name>-encoder.mlmodelc`) next
 /// to the GGML file so whisper.cpp uses the Neural Engine for the encoder. The
 /// archive is streamed to a temp file, extracted with macOS' `ditto`, and the
@@ -332,7 +598,7 @@ pub fn quarantine_unverified_coreml_encoder(
     ) else {
         return Ok(());
     };
-    quarantine_unverified_coreml_encoder_at(&models_dir().join(dirname), integrity)
+    quarantine_unverified_coreml_encoder_at(&model_dir(model).join(dirname), integrity)
 }
 
 #[cfg(not(target_os = "macos"))]
@@ -407,6 +673,58 @@ mod migrate_legacy_models_dir_tests {
         std::env::temp_dir().join(format!("sagascript-migrate-test-{}", uuid::Uuid::new_v4()))
     }
 
+    #[test]
+    fn custom_model_names_cannot_shadow_built_in_models() {
+        assert!(validate_custom_model_name("my-finetune_v2.q5").is_ok());
+        assert!(validate_custom_model_name("kb-whisper-base").is_err());
+        assert!(validate_custom_model_name("").is_err());
+        assert!(validate_custom_model_name("my model").is_err());
+    }
+
+    #[test]
+    fn custom_model_file_must_be_ggml() {
+        let base = temp_base();
+        std::fs::create_dir_all(&base).unwrap();
+        let ggml = base.join("model.bin");
+        std::fs::write(&ggml, [&GGML_MAGIC[..], b"tensors"].concat()).unwrap();
+        assert!(check_custom_model_file(&ggml).is_ok());
+
+        let html = base.join("page.bin");
+        std::fs::write(&html, b"<!DOCTYPE html>").unwrap();
+        assert!(check_custom_model_file(&html).is_err());
+        assert!(check_custom_model_file(&base.join("missing.bin")).is_err());
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn dir_size_counts_nested_files() {
+        let base = temp_base();
+        let nested = base.join("ggml-base-encoder.mlmodelc").join("weights");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(base.join("model.mil"), [0; 100]).unwrap();
+        std::fs::write(nested.join("weight.bin"), [0; 2_000]).unwrap();
+        assert_eq!(dir_size(&base), 2_100);
+        assert_eq!(dir_size(&base.join("missing")), 0);
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn shared_copy_is_used_only_when_complete() {
+        let shared = temp_base();
+        std::fs::create_dir_all(&shared).unwrap();
+        std::fs::write(shared.join("ggml-base.bin"), [0; 1_000]).unwrap();
+        let copy = |filename: &str, size| shared_copy(Some(shared.clone()), filename, size);
+        assert_eq!(
+            copy("ggml-base.bin", 1_000),
+            Some(shared.join("ggml-base.bin"))
+        );
+        // An older revision or a partial copy.
+        assert_eq!(copy("ggml-base.bin", 2_000), None);
+        assert_eq!(copy("ggml-tiny.bin", 1_000), None);
+        assert_eq!(shared_copy(None, "ggml-base.bin", 1_000), None);
+        let _ = std::fs::remove_dir_all(&shared);
+    }
+
     #[test]
     fn vad_source_is_immutable_and_manifest_is_plausible() {
         assert!(!VAD_MODEL_URL.contains("/resolve/main/"));
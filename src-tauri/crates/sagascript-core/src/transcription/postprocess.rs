@@ -1,10 +1,11 @@
 use crate::settings::Language;
 
+/// One whitespace-separated token of a transcript, with its byte range.
 #[derive(Clone, Copy)]
-struct Token<'a> {
-    start: usize,
-    end: usize,
-    text: &'a str,
+pub(super) struct Token<'a> {
+    pub(super) start: usize,
+    pub(super) end: usize,
+    pub(super) text: &'a str,
 }
 
 /// Make Whisper's music annotations readable without rewriting ordinary speech.
@@ -55,7 +56,7 @@ pub fn normalize_nonspeech_markers(text: &str, language: Language) -> String {
     rendered
 }
 
-fn token_spans(text: &str) -> Vec<Token<'_>> {
+pub(super) fn token_spans(text: &str) -> Vec<Token<'_>> {
     let mut tokens = Vec::new();
     let mut start = None;
     for (index, character) in text.char_indices() {
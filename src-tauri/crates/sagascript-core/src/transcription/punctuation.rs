@@ -0,0 +1,81 @@
+//! Punctuation-free output.
+//!
+//! With `Settings::punctuate` off, [`strip_punctuation`] removes the sentence
+//! punctuation whisper adds, for dictating commands into a terminal or a
+//! search box: "Git status, please." becomes "Git status please". It runs
+//! before voice commands, so punctuation spoken on purpose ("comma") is
+//! still typed.
+//!
+//! Only prose punctuation goes. Symbols that carry meaning in commands
+//! (`$`, `%`, `/`, `@`, `+`) stay, and so does a mark inside a word or number:
+//! "don't", "e-mail", "3.14", "25,5" and "10:30" are kept as they are.
+
+/// Marks removed between words. A mark between two letters or digits is
+/// part of the word and kept.
+const PROSE_PUNCTUATION: &str = ".,;:!?\"'()[]{}-…–—„“”‘’«»¿¡";
+
+/// `text` without its sentence punctuation, with the spaces it leaves behind
+/// collapsed.
+pub fn strip_punctuation(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut stripped = String::with_capacity(text.len());
+    for (i, &c) in chars.iter().enumerate() {
+        if PROSE_PUNCTUATION.contains(c) {
+            let before = i.checked_sub(1).map(|j| chars[j]);
+            let after = chars.get(i + 1).copied();
+            let inside_word = before.is_some_and(char::is_alphanumeric)
+                && after.is_some_and(char::is_alphanumeric);
+            if !inside_word {
+                continue;
+            }
+        }
+        if c == ' ' && (stripped.is_empty() || stripped.ends_with([' ', '\n'])) {
+            continue;
+        }
+        if c == '\n' && stripped.ends_with(' ') {
+            stripped.pop();
+        }
+        stripped.push(c);
+    }
+    stripped.truncate(stripped.trim_end().len());
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_sentence_punctuation() {
+        assert_eq!(
+            strip_punctuation("Git status, please."),
+            "Git status please"
+        );
+        assert_eq!(
+            strip_punctuation("Hello! How are you? (Fine - thanks…)"),
+            "Hello How are you Fine thanks"
+        );
+        assert_eq!(strip_punctuation("«Hej» sa hon."), "Hej sa hon");
+        assert_eq!(strip_punctuation("..."), "");
+    }
+
+    #[test]
+    fn keeps_marks_inside_words_and_numbers() {
+        assert_eq!(
+            strip_punctuation("Don't e-mail me at 10:30, it's 3.14."),
+            "Don't e-mail me at 10:30 it's 3.14"
+        );
+        assert_eq!(
+            strip_punctuation("Det blev 25,5 grader."),
+            "Det blev 25,5 grader"
+        );
+    }
+
+    #[test]
+    fn keeps_symbols_and_line_breaks() {
+        assert_eq!(
+            strip_punctuation("cd ~/src && ls, 50% done.\nNext line."),
+            "cd ~/src && ls 50% done\nNext line"
+        );
+    }
+}
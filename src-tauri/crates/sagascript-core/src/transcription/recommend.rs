@@ -0,0 +1,192 @@
+//! Picking a model for this machine rather than for the language alone.
+//!
+//! [`WhisperModel::recommended`] is the right size for a typical laptop. A
+//! machine with a GPU, plenty of memory and many cores gets the language's
+//! accurate model instead, and one with little memory or few cores and no
+//! GPU its fastest. Used by onboarding (`get_recommended_model`,
+//! `setup_recommended`) and `sagascript download-model --recommended`.
+
+use serde::Serialize;
+
+use super::runtime::AccelerationInfo;
+use crate::settings::{Language, WhisperModel};
+
+const GIB: u64 = 1 << 30;
+
+/// At or above this much memory, with a GPU, a machine gets the accurate model.
+const LARGE_MEMORY: u64 = 16 * GIB;
+
+/// Below this much memory (the 8 GB class), without a GPU, the fastest model.
+const SMALL_MEMORY: u64 = 12 * GIB;
+
+/// Cores for the accurate model; below [`FEW_CORES`], the fastest.
+const MANY_CORES: usize = 8;
+const FEW_CORES: usize = 4;
+
+/// What a recommendation is based on.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Hardware {
+    /// Logical CPU cores.
+    pub cpu_cores: usize,
+    /// Installed memory, where the system reports it.
+    pub memory_bytes: Option<u64>,
+    /// Whether inference would run on a GPU (see [`AccelerationInfo`]).
+    pub gpu: bool,
+}
+
+impl Hardware {
+    /// This machine, with the `use_gpu` setting.
+    pub fn detect(use_gpu: bool) -> Self {
+        Self {
+            cpu_cores: num_cpus::get(),
+            memory_bytes: total_memory_bytes(),
+            gpu: AccelerationInfo::collect(use_gpu, None).gpu_active,
+        }
+    }
+}
+
+/// A model for `language` on some hardware, and why.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Recommendation {
+    pub model: WhisperModel,
+    pub display_name: String,
+    pub size_mb: u32,
+    pub hardware: Hardware,
+    /// One sentence for the user, e.g. "GPU, 32 GB of memory and 12 cores:
+    /// room for a more accurate model."
+    pub reason: String,
+}
+
+/// The model to recommend for `language` on `hardware`.
+pub fn recommend(language: Language, hardware: Hardware) -> Recommendation {
+    let memory = hardware.memory_bytes;
+    let (model, reason) = if hardware.gpu
+        && memory.is_some_and(|m| m >= LARGE_MEMORY)
+        && hardware.cpu_cores >= MANY_CORES
+    {
+        (
+            WhisperModel::accurate(language),
+            format!(
+                "GPU, {} of memory and {} cores: room for a more accurate model.",
+                gigabytes(memory.unwrap_or(0)),
+                hardware.cpu_cores
+            ),
+        )
+    } else if !hardware.gpu && memory.is_some_and(|m| m < SMALL_MEMORY) {
+        (
+            WhisperModel::draft(language),
+            format!(
+                "{} of memory and no GPU: the fastest model keeps dictation responsive.",
+                gigabytes(memory.unwrap_or(0))
+            ),
+        )
+    } else if !hardware.gpu && hardware.cpu_cores < FEW_CORES {
+        (
+            WhisperModel::draft(language),
+            format!(
+                "{} cores and no GPU: the fastest model keeps dictation responsive.",
+                hardware.cpu_cores
+            ),
+        )
+    } else {
+        (
+            WhisperModel::recommended(language),
+            "The best balance of speed and accuracy on this machine.".to_string(),
+        )
+    };
+    Recommendation {
+        model,
+        display_name: model.display_name().to_string(),
+        size_mb: model.size_mb(),
+        hardware,
+        reason,
+    }
+}
+
+/// `16 GB`, rounded to whole gibibytes as machines are sold.
+fn gigabytes(bytes: u64) -> String {
+    format!("{} GB", (bytes + GIB / 2) / GIB)
+}
+
+#[cfg(target_os = "linux")]
+fn total_memory_bytes() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    parse_meminfo(&meminfo)
+}
+
+/// `MemTotal` from `/proc/meminfo`, which counts in kB.
+#[cfg(any(target_os = "linux", test))]
+fn parse_meminfo(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find(|l| l.starts_with("MemTotal:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(target_os = "macos")]
+fn total_memory_bytes() -> Option<u64> {
+    let mut bytes: u64 = 0;
+    let mut len = std::mem::size_of::<u64>();
+    // SAFETY: hw.memsize is a 64-bit integer and `len` is the size of the
+    // buffer it is written into.
+    let status = unsafe {
+        libc::sysctlbyname(
+            c"hw.memsize".as_ptr(),
+            (&mut bytes as *mut u64).cast(),
+            &mut len,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    (status == 0 && bytes > 0).then_some(bytes)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn total_memory_bytes() -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn machine(cpu_cores: usize, memory_gb: u64, gpu: bool) -> Hardware {
+        Hardware {
+            cpu_cores,
+            memory_bytes: Some(memory_gb * GIB),
+            gpu,
+        }
+    }
+
+    #[test]
+    fn model_follows_the_hardware() {
+        // An M3 Max.
+        let big = recommend(Language::English, machine(14, 36, true));
+        assert_eq!(big.model, WhisperModel::SmallEn);
+        assert!(big.reason.contains("36 GB"), "{}", big.reason);
+        // An 8 GB Intel laptop.
+        let small = recommend(Language::English, machine(4, 8, false));
+        assert_eq!(small.model, WhisperModel::TinyEn);
+        // A typical laptop, and one whose memory isn't known.
+        let typical = recommend(Language::Swedish, machine(8, 16, false));
+        assert_eq!(typical.model, WhisperModel::KbWhisperBase);
+        let unknown = Hardware {
+            memory_bytes: None,
+            ..machine(8, 0, true)
+        };
+        assert_eq!(
+            recommend(Language::Norwegian, unknown).model,
+            WhisperModel::NbWhisperBase
+        );
+        assert_eq!(
+            recommend(Language::English, machine(2, 16, false)).model,
+            WhisperModel::TinyEn
+        );
+    }
+
+    #[test]
+    fn reads_total_memory_from_meminfo() {
+        let meminfo = "MemTotal:       16318412 kB\nMemFree:         1234 kB\n";
+        assert_eq!(parse_meminfo(meminfo), Some(16_318_412 * 1024));
+        assert_eq!(parse_meminfo("MemFree: 1 kB\n"), None);
+    }
+}
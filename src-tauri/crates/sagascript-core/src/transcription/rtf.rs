@@ -0,0 +1,223 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::settings::WhisperModel;
+
+/// Real-time factor above which dictation is considered too slow to keep up.
+pub const RTF_SUGGESTION_THRESHOLD: f32 = 1.5;
+
+/// Number of recent transcriptions kept per model.
+const WINDOW: usize = 5;
+
+/// Samples required before a suggestion is made, so a single cold-start
+/// inference doesn't push the user off a model that is otherwise fine.
+const MIN_SAMPLES: usize = 3;
+
+/// Clips shorter than this are dominated by fixed per-call overhead and
+/// would inflate the ratio, so they are not recorded.
+const MIN_AUDIO_SECS: f32 = 1.0;
+
+/// Processing time divided by audio duration. Values above 1.0 mean
+/// transcription took longer than the recording itself.
+pub fn real_time_factor(audio_secs: f32, elapsed: Duration) -> Option<f32> {
+    if audio_secs <= 0.0 {
+        return None;
+    }
+    Some(elapsed.as_secs_f32() / audio_secs)
+}
+
+/// A recommendation to switch to a lighter model after sustained slow runs.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ModelSuggestion {
+    pub current: WhisperModel,
+    pub suggested: WhisperModel,
+    /// Median real-time factor over the recent window for `current`.
+    pub rtf: f32,
+}
+
+/// Rolling per-model real-time factor history.
+///
+/// Each model keeps its own window so switching models doesn't mix samples.
+/// A suggestion is raised at most once per model until `reset` is called,
+/// which keeps the app from nagging after every dictation.
+#[derive(Debug, Default)]
+pub struct RtfTracker {
+    samples: Vec<(WhisperModel, VecDeque<f32>)>,
+    suggested: Vec<WhisperModel>,
+    pending: Option<ModelSuggestion>,
+}
+
+impl RtfTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one transcription and return a new suggestion if the model has
+    /// consistently been slower than `RTF_SUGGESTION_THRESHOLD`.
+    pub fn record(
+        &mut self,
+        model: WhisperModel,
+        audio_secs: f32,
+        elapsed: Duration,
+    ) -> Option<ModelSuggestion> {
+        if audio_secs < MIN_AUDIO_SECS {
+            return None;
+        }
+        let rtf = real_time_factor(audio_secs, elapsed)?;
+
+        let window = match self.samples.iter().position(|(m, _)| *m == model) {
+            Some(idx) => &mut self.samples[idx].1,
+            None => {
+                self.samples.push((model, VecDeque::with_capacity(WINDOW)));
+                &mut self.samples.last_mut().unwrap().1
+            }
+        };
+        if window.len() == WINDOW {
+            window.pop_front();
+        }
+        window.push_back(rtf);
+
+        if window.len() < MIN_SAMPLES || self.suggested.contains(&model) {
+            return None;
+        }
+        let median = median(window);
+        if median <= RTF_SUGGESTION_THRESHOLD {
+            return None;
+        }
+        let suggested = model.smaller()?;
+        let suggestion = ModelSuggestion {
+            current: model,
+            suggested,
+            rtf: median,
+        };
+        self.suggested.push(model);
+        self.pending = Some(suggestion);
+        Some(suggestion)
+    }
+
+    /// Median real-time factor for `model`, if any samples were recorded.
+    pub fn median_rtf(&self, model: WhisperModel) -> Option<f32> {
+        self.samples
+            .iter()
+            .find(|(m, _)| *m == model)
+            .filter(|(_, w)| !w.is_empty())
+            .map(|(_, w)| median(w))
+    }
+
+    /// Most recent suggestion that hasn't been applied or dismissed.
+    pub fn pending(&self) -> Option<ModelSuggestion> {
+        self.pending
+    }
+
+    /// Take the pending suggestion, clearing it.
+    pub fn take_pending(&mut self) -> Option<ModelSuggestion> {
+        self.pending.take()
+    }
+
+    /// Forget all samples and allow suggestions again.
+    pub fn reset(&mut self) {
+        self.samples.clear();
+        self.suggested.clear();
+        self.pending = None;
+    }
+}
+
+fn median(window: &VecDeque<f32>) -> f32 {
+    let mut sorted: Vec<f32> = window.iter().copied().collect();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 0 {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(s: f32) -> Duration {
+        Duration::from_secs_f32(s)
+    }
+
+    #[test]
+    fn real_time_factor_is_elapsed_over_audio() {
+        assert_eq!(real_time_factor(4.0, secs(2.0)), Some(0.5));
+        assert_eq!(real_time_factor(0.0, secs(2.0)), None);
+    }
+
+    #[test]
+    fn no_suggestion_before_minimum_samples() {
+        let mut t = RtfTracker::new();
+        assert!(t.record(WhisperModel::MediumEn, 4.0, secs(10.0)).is_none());
+        assert!(t.record(WhisperModel::MediumEn, 4.0, secs(10.0)).is_none());
+        let s = t.record(WhisperModel::MediumEn, 4.0, secs(10.0)).unwrap();
+        assert_eq!(s.current, WhisperModel::MediumEn);
+        assert_eq!(s.suggested, WhisperModel::SmallEn);
+        assert!((s.rtf - 2.5).abs() < 0.01);
+        assert_eq!(t.pending(), Some(s));
+    }
+
+    #[test]
+    fn single_slow_outlier_does_not_trigger() {
+        let mut t = RtfTracker::new();
+        t.record(WhisperModel::BaseEn, 4.0, secs(20.0));
+        t.record(WhisperModel::BaseEn, 4.0, secs(1.0));
+        assert!(t.record(WhisperModel::BaseEn, 4.0, secs(1.0)).is_none());
+    }
+
+    #[test]
+    fn suggests_once_per_model_until_reset() {
+        let mut t = RtfTracker::new();
+        for _ in 0..3 {
+            t.record(WhisperModel::Small, 2.0, secs(4.0));
+        }
+        assert!(t.record(WhisperModel::Small, 2.0, secs(4.0)).is_none());
+        t.reset();
+        assert!(t.pending().is_none());
+        for _ in 0..2 {
+            t.record(WhisperModel::Small, 2.0, secs(4.0));
+        }
+        assert!(t.record(WhisperModel::Small, 2.0, secs(4.0)).is_some());
+    }
+
+    #[test]
+    fn short_clips_are_ignored() {
+        let mut t = RtfTracker::new();
+        for _ in 0..5 {
+            assert!(t.record(WhisperModel::Medium, 0.5, secs(3.0)).is_none());
+        }
+        assert!(t.median_rtf(WhisperModel::Medium).is_none());
+    }
+
+    #[test]
+    fn smallest_model_never_suggests() {
+        let mut t = RtfTracker::new();
+        for _ in 0..5 {
+            assert!(t.record(WhisperModel::TinyEn, 2.0, secs(6.0)).is_none());
+        }
+    }
+
+    #[test]
+    fn models_are_tracked_separately() {
+        let mut t = RtfTracker::new();
+        t.record(WhisperModel::Medium, 2.0, secs(6.0));
+        t.record(WhisperModel::Medium, 2.0, secs(6.0));
+        assert!(t.record(WhisperModel::Base, 2.0, secs(6.0)).is_none());
+        assert!((t.median_rtf(WhisperModel::Medium).unwrap() - 3.0).abs() < 0.01);
+        assert!((t.median_rtf(WhisperModel::Base).unwrap() - 3.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn take_pending_clears_suggestion() {
+        let mut t = RtfTracker::new();
+        for _ in 0..3 {
+            t.record(WhisperModel::KbWhisperMedium, 2.0, secs(6.0));
+        }
+        assert!(t.take_pending().is_some());
+        assert!(t.pending().is_none());
+    }
+}
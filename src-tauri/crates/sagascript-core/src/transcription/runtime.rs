@@ -0,0 +1,202 @@
+//! What the whisper.cpp build in this binary can do, for "why is it slow on
+//! my machine" triage. Shared by `get_whisper_runtime_info` and
+//! `sagascript runtime`.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::settings::WhisperModel;
+use crate::transcription::{model, whisper_backend};
+
+/// whisper-rs version this binary was built against (from Cargo.lock).
+pub const WHISPER_RS_VERSION: &str = env!("SAGASCRIPT_WHISPER_RS_VERSION");
+
+/// whisper-rs-sys version, which pins the bundled whisper.cpp sources.
+pub const WHISPER_RS_SYS_VERSION: &str = env!("SAGASCRIPT_WHISPER_RS_SYS_VERSION");
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeInfo {
+    pub whisper_rs_version: String,
+    pub whisper_rs_sys_version: String,
+    /// Compute backends compiled in, e.g. `Metal`, `CoreML`, `CPU`.
+    pub backends: Vec<String>,
+    /// CPU instruction sets whisper.cpp was built to use, e.g. `AVX2`, `NEON`.
+    pub cpu_features: Vec<String>,
+    /// CPU threads handed to each inference.
+    pub threads: i32,
+    /// whisper.cpp's own system info line, verbatim.
+    pub system_info: String,
+    pub model: ModelRuntime,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelRuntime {
+    pub id: WhisperModel,
+    pub name: String,
+    pub path: PathBuf,
+    /// Size of the GGML file on disk; `None` when it isn't downloaded.
+    pub size_bytes: Option<u64>,
+    /// Whether a CoreML encoder sits next to the GGML file.
+    pub coreml_encoder: bool,
+    /// Whether the weights are in memory right now.
+    pub loaded: bool,
+}
+
+impl RuntimeInfo {
+    /// Describe this build and `model`. `loaded` says whether its weights are
+    /// currently in memory; the CLI never has a model loaded.
+    pub fn collect(model: WhisperModel, loaded: bool) -> Self {
+        let system_info = whisper_rs::print_system_info().trim().to_string();
+        let (backends, cpu_features) = parse_system_info(&system_info);
+        Self {
+            whisper_rs_version: WHISPER_RS_VERSION.to_string(),
+            whisper_rs_sys_version: WHISPER_RS_SYS_VERSION.to_string(),
+            backends,
+            cpu_features,
+            threads: whisper_backend::whisper_threads(),
+            system_info,
+            model: ModelRuntime::describe(model, loaded),
+        }
+    }
+}
+
+/// Whether inference runs on a GPU, for `get_acceleration_info` and the
+/// `sagascript runtime` GPU line.
+#[derive(Debug, Clone, Serialize)]
+pub struct AccelerationInfo {
+    /// GPU backend compiled in (`Metal`, `CUDA`, `Vulkan`), if any.
+    pub gpu_backend: Option<String>,
+    /// Whether CoreML encoders can be used (macOS builds).
+    pub coreml: bool,
+    /// The `use_gpu` setting.
+    pub use_gpu: bool,
+    /// Whether inference runs on the GPU. With a model loaded this is how it
+    /// was loaded; otherwise it is what the next load will do.
+    pub gpu_active: bool,
+}
+
+impl AccelerationInfo {
+    /// `loaded_on_gpu` is [`whisper_backend::WhisperBackend::loaded_on_gpu`];
+    /// the CLI, which holds no model, passes `None`.
+    pub fn collect(use_gpu: bool, loaded_on_gpu: Option<bool>) -> Self {
+        let (backends, _) = parse_system_info(&whisper_rs::print_system_info());
+        Self::from_backends(&backends, use_gpu, loaded_on_gpu)
+    }
+
+    fn from_backends(backends: &[String], use_gpu: bool, loaded_on_gpu: Option<bool>) -> Self {
+        let gpu_backend = backends
+            .iter()
+            .find(|b| GPU_BACKENDS.iter().any(|g| b.eq_ignore_ascii_case(g)))
+            .cloned();
+        Self {
+            gpu_active: gpu_backend.is_some() && loaded_on_gpu.unwrap_or(use_gpu),
+            gpu_backend,
+            coreml: backends.iter().any(|b| b == "CoreML"),
+            use_gpu,
+        }
+    }
+}
+
+/// ggml backends that run inference on a GPU.
+const GPU_BACKENDS: [&str; 3] = ["Metal", "CUDA", "Vulkan"];
+
+impl ModelRuntime {
+    fn describe(id: WhisperModel, loaded: bool) -> Self {
+        let path = model::model_path(id);
+        let size_bytes = std::fs::metadata(&path).ok().map(|m| m.len());
+        let coreml_encoder = id
+            .coreml_encoder_dirname()
+            .is_some_and(|dir| model::model_dir(id).join(dir).is_dir());
+        Self {
+            id,
+            name: id.display_name().to_string(),
+            path,
+            size_bytes,
+            coreml_encoder,
+            loaded,
+        }
+    }
+}
+
+/// Split whisper.cpp's system info into backends and CPU features.
+///
+/// The line reads like `WHISPER : COREML = 1 | OPENVINO = 0 | Metal :
+/// EMBED_LIBRARY = 1 | CPU : NEON = 1 | ARM_FMA = 1 |`: each registered ggml
+/// backend is named before a ` : `, followed by its feature flags. The
+/// `WHISPER` group isn't a backend, but its COREML/OPENVINO flags are.
+pub fn parse_system_info(info: &str) -> (Vec<String>, Vec<String>) {
+    let mut backends = Vec::new();
+    let mut cpu_features = Vec::new();
+    let mut group = "";
+    for piece in info.split('|') {
+        let mut parts: Vec<&str> = piece.split(" : ").map(str::trim).collect();
+        let flag = parts.pop().unwrap_or_default();
+        for name in parts {
+            group = name;
+            if name != "WHISPER" && !backends.iter().any(|b| b == name) {
+                backends.push(name.to_string());
+            }
+        }
+        let Some((key, value)) = flag.split_once('=') else {
+            continue;
+        };
+        if value.trim() != "1" {
+            continue;
+        }
+        match (group, key.trim()) {
+            ("WHISPER", "COREML") => backends.push("CoreML".to_string()),
+            ("WHISPER", "OPENVINO") => backends.push("OpenVINO".to_string()),
+            ("CPU", key) => cpu_features.push(key.to_string()),
+            _ => {}
+        }
+    }
+    (backends, cpu_features)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_apple_silicon_system_info() {
+        let info = "WHISPER : COREML = 1 | OPENVINO = 0 | Metal : EMBED_LIBRARY = 1 | \
+                    CPU : NEON = 1 | ARM_FMA = 1 | FP16_VA = 1 | DOTPROD = 1 | SME = 0 |";
+        let (backends, cpu) = parse_system_info(info);
+        assert_eq!(backends, ["CoreML", "Metal", "CPU"]);
+        assert_eq!(cpu, ["NEON", "ARM_FMA", "FP16_VA", "DOTPROD"]);
+    }
+
+    #[test]
+    fn backend_without_features_is_still_listed() {
+        let info =
+            "WHISPER : COREML = 0 | OPENVINO = 0 | BLAS : CPU : SSE3 = 1 | AVX = 1 | AVX2 = 1 |";
+        let (backends, cpu) = parse_system_info(info);
+        assert_eq!(backends, ["BLAS", "CPU"]);
+        assert_eq!(cpu, ["SSE3", "AVX", "AVX2"]);
+    }
+
+    #[test]
+    fn gpu_is_active_only_with_a_gpu_backend_and_the_setting_on() {
+        let metal = ["CoreML".to_string(), "Metal".to_string(), "CPU".to_string()];
+        let info = AccelerationInfo::from_backends(&metal, true, None);
+        assert_eq!(info.gpu_backend.as_deref(), Some("Metal"));
+        assert!(info.coreml && info.gpu_active);
+        assert!(!AccelerationInfo::from_backends(&metal, false, None).gpu_active);
+        // A model loaded before the setting changed keeps its placement.
+        assert!(AccelerationInfo::from_backends(&metal, false, Some(true)).gpu_active);
+
+        let cpu = ["CPU".to_string()];
+        let info = AccelerationInfo::from_backends(&cpu, true, Some(true));
+        assert_eq!(info.gpu_backend, None);
+        assert!(!info.gpu_active);
+    }
+
+    #[test]
+    fn versions_come_from_the_lockfile() {
+        let lock = include_str!("../../../../Cargo.lock");
+        assert!(lock.contains(&format!(
+            "name = \"whisper-rs\"\nversion = \"{WHISPER_RS_VERSION}\""
+        )));
+    }
+}
@@ -0,0 +1,158 @@
+//! Keeping batch transcription out of the way of dictation.
+//!
+//! A `transcribe` batch can keep the CPU or GPU busy for hours, and a
+//! dictation made meanwhile waits for whatever inference is in flight. A
+//! throttled batch checks before each file, and before each chunk of a
+//! long file, whether the app is dictating (its live recovery snapshot, see
+//! [`crate::recovery`], is being written) or the system is under load, and
+//! pauses until it isn't.
+//!
+//! A dictation is waited out however long it takes. Load is only waited out
+//! for [`MAX_LOAD_PAUSE`] at a time: the batch's own inference counts
+//! towards it, and a busy machine shouldn't stall a batch for good.
+
+use std::fmt;
+use std::time::{Duration, Instant, SystemTime};
+
+use tracing::info;
+
+use crate::recovery;
+
+/// How often a paused batch checks again.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Longest pause for load alone.
+pub const MAX_LOAD_PAUSE: Duration = Duration::from_secs(60);
+
+/// A live snapshot untouched for this long was left by a crash, not a
+/// dictation in progress (snapshots are written every few seconds).
+const STALE_SNAPSHOT: Duration = Duration::from_secs(120);
+
+/// Load per core above which a batch pauses, unless told otherwise.
+pub const DEFAULT_MAX_LOAD: f64 = 1.5;
+
+/// When batch inference pauses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Throttle {
+    /// 1-minute load average per core to pause above; 0 or less ignores load.
+    pub max_load: f64,
+}
+
+/// Why a batch paused.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Contention {
+    /// The app is dictating.
+    Dictating,
+    /// The system is loaded; the load average per core.
+    Load(f64),
+}
+
+impl fmt::Display for Contention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Contention::Dictating => write!(f, "a dictation is in progress"),
+            Contention::Load(load) => write!(f, "system load is {load:.1} per core"),
+        }
+    }
+}
+
+impl Default for Throttle {
+    fn default() -> Self {
+        Self {
+            max_load: DEFAULT_MAX_LOAD,
+        }
+    }
+}
+
+impl Throttle {
+    /// What a batch should wait for right now, if anything.
+    pub fn contention(&self) -> Option<Contention> {
+        if dictation_active() {
+            return Some(Contention::Dictating);
+        }
+        self.over_limit(load_per_core()?)
+    }
+
+    fn over_limit(&self, load: f64) -> Option<Contention> {
+        (self.max_load > 0.0 && load > self.max_load).then_some(Contention::Load(load))
+    }
+
+    /// Block while something contends. Returns what first did and how long
+    /// the pause lasted, or `None` if there was no need to pause.
+    pub fn wait(&self) -> Option<(Contention, Duration)> {
+        let first = self.contention()?;
+        info!("Pausing batch transcription: {first}");
+        let started = Instant::now();
+        while let Some(contention) = self.contention() {
+            if contention != Contention::Dictating && started.elapsed() >= MAX_LOAD_PAUSE {
+                break;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        let paused = started.elapsed();
+        info!(
+            "Resuming batch transcription after {:.0}s",
+            paused.as_secs_f64()
+        );
+        Some((first, paused))
+    }
+}
+
+/// Whether the app on this machine is recording or transcribing a dictation.
+pub fn dictation_active() -> bool {
+    let live = recovery::live_dir();
+    recovery::load(&live).is_some() && snapshot_is_fresh(&recovery::audio_path(&live))
+}
+
+fn snapshot_is_fresh(path: &std::path::Path) -> bool {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age < STALE_SNAPSHOT)
+}
+
+/// The 1-minute load average divided by the number of cores, where the
+/// system reports one.
+pub fn load_per_core() -> Option<f64> {
+    #[cfg(unix)]
+    {
+        let mut load = [0.0f64; 3];
+        // SAFETY: getloadavg writes at most `nelem` values into `load`.
+        let n = unsafe { libc::getloadavg(load.as_mut_ptr(), 1) };
+        (n == 1).then(|| load[0] / num_cpus::get().max(1) as f64)
+    }
+    #[cfg(not(unix))]
+    {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pauses_above_the_load_limit_only_when_set() {
+        let throttle = Throttle { max_load: 1.5 };
+        assert_eq!(throttle.over_limit(1.0), None);
+        assert_eq!(throttle.over_limit(2.0), Some(Contention::Load(2.0)));
+        assert_eq!(Throttle { max_load: 0.0 }.over_limit(8.0), None);
+    }
+
+    #[test]
+    fn only_a_recent_snapshot_means_dictating() {
+        let path =
+            std::env::temp_dir().join(format!("sagascript-throttle-{}", uuid::Uuid::new_v4()));
+        assert!(!snapshot_is_fresh(&path));
+        std::fs::write(&path, b"RIFF").unwrap();
+        assert!(snapshot_is_fresh(&path));
+        // Left behind by a crash: not written to for minutes.
+        let old = SystemTime::now() - (STALE_SNAPSHOT + Duration::from_secs(1));
+        let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_times(std::fs::FileTimes::new().set_modified(old))
+            .unwrap();
+        assert!(!snapshot_is_fresh(&path));
+        let _ = std::fs::remove_file(&path);
+    }
+}
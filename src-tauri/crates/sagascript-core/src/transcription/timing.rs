@@ -0,0 +1,101 @@
+//! Where the time of one dictation went, stage by stage: sent to the app's
+//! windows as `transcription-complete` and printed by `sagascript record`.
+
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Milliseconds spent in each stage. A stage that didn't run is 0, e.g.
+/// the model load when the model was already loaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Timing {
+    /// Length of the recording itself.
+    pub capture_ms: u64,
+    /// Stopping the capture and resampling it to 16 kHz.
+    pub decode_ms: u64,
+    /// Loading the model.
+    pub model_load_ms: u64,
+    /// Whisper inference, or the request to a remote backend.
+    pub inference_ms: u64,
+    /// The post-processing model.
+    pub post_process_ms: u64,
+    /// Pasting the text, or holding it for confirmation; 0 for `record`.
+    pub paste_ms: u64,
+    /// From the end of the recording to the pasted text: the sum of the
+    /// stages after `capture_ms`. Set by [`Timing::finish`].
+    pub total_ms: u64,
+}
+
+impl Timing {
+    /// Sum the stages into `total_ms`.
+    pub fn finish(mut self) -> Self {
+        self.total_ms = self.decode_ms
+            + self.model_load_ms
+            + self.inference_ms
+            + self.post_process_ms
+            + self.paste_ms;
+        self
+    }
+
+    /// `3.2s total (inference 2.1s, model load 0.9s, ...)`, listing the
+    /// stages that took at least 0.1s, slowest first.
+    pub fn summary(&self) -> String {
+        let mut stages = [
+            ("decode", self.decode_ms),
+            ("model load", self.model_load_ms),
+            ("inference", self.inference_ms),
+            ("post-processing", self.post_process_ms),
+            ("paste", self.paste_ms),
+        ];
+        stages.sort_by_key(|&(_, ms)| std::cmp::Reverse(ms));
+        let parts: Vec<String> = stages
+            .iter()
+            .filter(|&&(_, ms)| ms >= 50)
+            .map(|(name, ms)| format!("{name} {}", secs(*ms)))
+            .collect();
+        if parts.is_empty() {
+            format!("{} total", secs(self.total_ms))
+        } else {
+            format!("{} total ({})", secs(self.total_ms), parts.join(", "))
+        }
+    }
+}
+
+fn secs(ms: u64) -> String {
+    format!("{:.1}s", ms as f64 / 1000.0)
+}
+
+/// Whole milliseconds in `d`, for a [`Timing`] field.
+pub fn ms(d: Duration) -> u64 {
+    d.as_millis() as u64
+}
+
+/// Whole milliseconds since `started`.
+pub fn ms_since(started: Instant) -> u64 {
+    ms(started.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn total_excludes_the_capture() {
+        let timing = Timing {
+            capture_ms: 5_000,
+            decode_ms: 40,
+            model_load_ms: 900,
+            inference_ms: 2_100,
+            post_process_ms: 0,
+            paste_ms: 160,
+            total_ms: 0,
+        }
+        .finish();
+        assert_eq!(timing.total_ms, 3_200);
+        assert_eq!(
+            timing.summary(),
+            "3.2s total (inference 2.1s, model load 0.9s, paste 0.2s)"
+        );
+        assert_eq!(Timing::default().finish().summary(), "0.0s total");
+    }
+}
@@ -0,0 +1,367 @@
+//! Upstream revision tracking for downloaded whisper models.
+//!
+//! Models are installed only from the revision pinned in
+//! [`WhisperModel::download_url`] and checked against its SHA-256, so a
+//! newer upload on HuggingFace is never picked up silently. This module
+//! records which revision each installed model came from and asks
+//! HuggingFace what its `main` branch currently serves, so `sagascript
+//! update-models` (and the opt-in background check in the app) can say when
+//! the two differ. Moving to the new revision still takes a Sagascript
+//! release that pins it.
+//!
+//! State lives in `model-revisions.json` next to the models.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::{catalog, model};
+use crate::error::DictationError;
+use crate::settings::WhisperModel;
+
+const REVISIONS_FILENAME: &str = "model-revisions.json";
+const CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Revisions of the installed models, keyed by model ID.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RevisionLog {
+    #[serde(default)]
+    pub models: BTreeMap<String, RevisionRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RevisionRecord {
+    /// HuggingFace commit the installed file was downloaded from.
+    pub installed_commit: String,
+    pub installed_sha256: String,
+    /// What the upstream `main` branch served at the last check.
+    #[serde(default)]
+    pub latest_commit: Option<String>,
+    #[serde(default)]
+    pub latest_sha256: Option<String>,
+    /// Unix seconds of the last successful check.
+    #[serde(default)]
+    pub checked_at: Option<u64>,
+}
+
+/// Result of checking one model against upstream.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelUpdate {
+    pub model: String,
+    pub display_name: String,
+    pub installed_commit: String,
+    pub latest_commit: Option<String>,
+    /// The file on `main` differs from the installed one.
+    pub update_available: bool,
+}
+
+/// A pinned HuggingFace `resolve` URL split into its parts.
+#[derive(Debug, Clone, PartialEq)]
+struct PinnedUrl<'a> {
+    repo: &'a str,
+    commit: &'a str,
+    file: &'a str,
+}
+
+/// Split `https://huggingface.co/<owner>/<repo>/resolve/<commit>/<file>`.
+fn split_resolve_url(url: &str) -> Option<PinnedUrl<'_>> {
+    let rest = url.strip_prefix("https://huggingface.co/")?;
+    let (repo, rest) = rest.split_once("/resolve/")?;
+    let (commit, file) = rest.split_once('/')?;
+    (!repo.is_empty() && !commit.is_empty() && !file.is_empty()).then_some(PinnedUrl {
+        repo,
+        commit,
+        file,
+    })
+}
+
+fn revisions_path() -> PathBuf {
+    model::models_dir().join(REVISIONS_FILENAME)
+}
+
+pub fn load_revisions() -> RevisionLog {
+    load_revisions_from(&revisions_path())
+}
+
+fn load_revisions_from(path: &Path) -> RevisionLog {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_revisions_to(path: &Path, log: &RevisionLog) -> Result<(), DictationError> {
+    let json = serde_json::to_string_pretty(log)
+        .map_err(|e| DictationError::ModelDownloadFailed(format!("Serialize error: {e}")))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)
+        .and_then(|()| std::fs::rename(&tmp, path))
+        .map_err(|e| {
+            DictationError::ModelDownloadFailed(format!("Cannot write {}: {e}", path.display()))
+        })
+}
+
+/// Note that `model` is installed from its pinned revision. Called after a
+/// download or verification succeeds; failures only log, since the model
+/// itself is fine.
+pub fn record_installed(m: WhisperModel) {
+    let path = revisions_path();
+    let mut log = load_revisions_from(&path);
+    mark_installed(&mut log, m);
+    if let Err(e) = save_revisions_to(&path, &log) {
+        warn!("Could not record model revision: {e}");
+    }
+}
+
+fn mark_installed(log: &mut RevisionLog, m: WhisperModel) {
+    let commit = split_resolve_url(m.download_url())
+        .map(|u| u.commit.to_string())
+        .unwrap_or_default();
+    let sha256 = m.download_integrity().sha256.to_string();
+    let record = log
+        .models
+        .entry(catalog::model_id(m))
+        .or_insert_with(|| RevisionRecord {
+            installed_commit: commit.clone(),
+            installed_sha256: sha256.clone(),
+            latest_commit: None,
+            latest_sha256: None,
+            checked_at: None,
+        });
+    record.installed_commit = commit;
+    record.installed_sha256 = sha256;
+}
+
+/// Whisper models with a file in the models directory.
+pub fn installed_models() -> Vec<WhisperModel> {
+    catalog::all_models()
+        .into_iter()
+        .filter(|&m| model::model_path(m).exists())
+        .collect()
+}
+
+/// Ask HuggingFace what `main` serves for each installed model and record
+/// the answer. Stops at the first network error.
+pub async fn check_installed() -> Result<Vec<ModelUpdate>, DictationError> {
+    let client = crate::net::client_builder()
+        .timeout(CHECK_TIMEOUT)
+        // The LFS headers are on the redirect itself, not the CDN response.
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| DictationError::ModelDownloadFailed(format!("HTTP client error: {e}")))?;
+
+    let path = revisions_path();
+    let mut log = load_revisions_from(&path);
+    let mut updates = Vec::new();
+    for m in installed_models() {
+        let Some(pinned) = split_resolve_url(m.download_url()) else {
+            continue;
+        };
+        let url = format!(
+            "https://huggingface.co/{}/resolve/main/{}",
+            pinned.repo, pinned.file
+        );
+        let latest = head_latest(&client, &url).await?;
+        mark_installed(&mut log, m);
+        let update = apply_check(&mut log, m, latest, unix_now());
+        updates.push(update);
+    }
+    save_revisions_to(&path, &log)?;
+    Ok(updates)
+}
+
+/// Updates found by the last check, from the revision log alone (no
+/// network), for showing them again after a restart.
+pub fn pending_updates() -> Vec<ModelUpdate> {
+    pending_from(&load_revisions(), &installed_models())
+}
+
+fn pending_from(log: &RevisionLog, installed: &[WhisperModel]) -> Vec<ModelUpdate> {
+    installed
+        .iter()
+        .filter_map(|&m| {
+            let record = log.models.get(&catalog::model_id(m))?;
+            let latest = record.latest_sha256.as_deref()?;
+            (!latest.eq_ignore_ascii_case(&record.installed_sha256)).then(|| ModelUpdate {
+                model: catalog::model_id(m),
+                display_name: m.display_name().to_string(),
+                installed_commit: record.installed_commit.clone(),
+                latest_commit: record.latest_commit.clone(),
+                update_available: true,
+            })
+        })
+        .collect()
+}
+
+/// What `main` currently serves.
+#[derive(Debug, Clone, PartialEq)]
+struct Latest {
+    commit: Option<String>,
+    sha256: Option<String>,
+}
+
+async fn head_latest(client: &reqwest::Client, url: &str) -> Result<Latest, DictationError> {
+    let response =
+        client.head(url).send().await.map_err(|e| {
+            DictationError::ModelDownloadFailed(format!("Update check failed: {e}"))
+        })?;
+    let status = response.status();
+    if !(status.is_success() || status.is_redirection()) {
+        return Err(DictationError::ModelDownloadFailed(format!(
+            "Update check for {url} returned {status}"
+        )));
+    }
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim_matches('"').to_string())
+    };
+    Ok(Latest {
+        commit: header("x-repo-commit"),
+        sha256: header("x-linked-etag"),
+    })
+}
+
+fn apply_check(log: &mut RevisionLog, m: WhisperModel, latest: Latest, now: u64) -> ModelUpdate {
+    let id = catalog::model_id(m);
+    let pinned_sha = m.download_integrity().sha256;
+    let update_available = latest
+        .sha256
+        .as_deref()
+        .is_some_and(|sha| !sha.eq_ignore_ascii_case(pinned_sha));
+    let installed_commit = match log.models.get_mut(&id) {
+        Some(record) => {
+            record.latest_commit = latest.commit.clone();
+            record.latest_sha256 = latest.sha256;
+            record.checked_at = Some(now);
+            record.installed_commit.clone()
+        }
+        None => String::new(),
+    };
+    ModelUpdate {
+        model: id,
+        display_name: m.display_name().to_string(),
+        installed_commit,
+        latest_commit: latest.commit,
+        update_available,
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_pinned_urls() {
+        let url = WhisperModel::BaseEn.download_url();
+        let pinned = split_resolve_url(url).unwrap();
+        assert_eq!(pinned.repo, "ggerganov/whisper.cpp");
+        assert_eq!(pinned.file, "ggml-base.en.bin");
+        assert_eq!(pinned.commit.len(), 40);
+        assert!(split_resolve_url("https://example.com/x/resolve/main/a.bin").is_none());
+        assert!(split_resolve_url("https://huggingface.co/x/y/blob/main/a.bin").is_none());
+    }
+
+    #[test]
+    fn every_model_has_a_pinned_huggingface_url() {
+        for m in catalog::all_models() {
+            assert!(split_resolve_url(m.download_url()).is_some(), "{m:?}");
+        }
+    }
+
+    #[test]
+    fn check_flags_only_a_different_file() {
+        let m = WhisperModel::KbWhisperBase;
+        let mut log = RevisionLog::default();
+        mark_installed(&mut log, m);
+
+        let same = Latest {
+            commit: Some("abc".into()),
+            sha256: Some(m.download_integrity().sha256.to_uppercase()),
+        };
+        assert!(!apply_check(&mut log, m, same, 1).update_available);
+
+        let newer = Latest {
+            commit: Some("def".into()),
+            sha256: Some("f".repeat(64)),
+        };
+        let update = apply_check(&mut log, m, newer, 2);
+        assert!(update.update_available);
+        assert_eq!(update.latest_commit.as_deref(), Some("def"));
+        let record = &log.models["kb-whisper-base"];
+        assert_eq!(record.checked_at, Some(2));
+        assert_eq!(record.installed_sha256, m.download_integrity().sha256);
+
+        let pending = pending_from(&log, &[m, WhisperModel::Tiny]);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].model, "kb-whisper-base");
+
+        let unknown = Latest {
+            commit: None,
+            sha256: None,
+        };
+        assert!(!apply_check(&mut log, m, unknown, 3).update_available);
+        assert!(pending_from(&log, &[m]).is_empty());
+    }
+
+    #[test]
+    fn revision_log_roundtrips_and_tolerates_garbage() {
+        let dir =
+            std::env::temp_dir().join(format!("sagascript-revisions-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(REVISIONS_FILENAME);
+        assert_eq!(load_revisions_from(&path), RevisionLog::default());
+
+        let mut log = RevisionLog::default();
+        mark_installed(&mut log, WhisperModel::Tiny);
+        save_revisions_to(&path, &log).unwrap();
+        assert_eq!(load_revisions_from(&path), log);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(load_revisions_from(&path), RevisionLog::default());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn reads_lfs_headers_from_the_redirect() {
+        use std::io::{Read as _, Write as _};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0_u8; 1024];
+            let _ = stream.read(&mut request);
+            stream
+                .write_all(
+                    b"HTTP/1.1 302 Found\r\nLocation: http://cdn.invalid/x\r\n\
+                      X-Repo-Commit: 0123abcd\r\nX-Linked-Etag: \"beef\"\r\n\
+                      Content-Length: 0\r\nConnection: close\r\n\r\n",
+                )
+                .unwrap();
+        });
+
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap();
+        let latest = head_latest(&client, &format!("http://{address}/m.bin"))
+            .await
+            .unwrap();
+        assert_eq!(latest.commit.as_deref(), Some("0123abcd"));
+        assert_eq!(latest.sha256.as_deref(), Some("beef"));
+    }
+}
@@ -0,0 +1,191 @@
+//! Disk usage of the downloaded models and when each was last used, for
+//! `sagascript models usage` and `sagascript models prune`.
+//!
+//! Last-used times live in `model-usage.json` next to the models. A model
+//! is marked used when it is loaded, and again at most once an hour while
+//! it stays loaded, so a warm model doesn't rewrite the file on every
+//! dictation.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::{catalog, model};
+use crate::error::DictationError;
+use crate::settings::{Settings, WhisperModel};
+
+const USAGE_FILENAME: &str = "model-usage.json";
+
+/// A use within this many seconds of the recorded one isn't written.
+const RECORD_EVERY_SECS: u64 = 3600;
+
+/// Unix seconds each model was last used, keyed by model ID.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageLog {
+    #[serde(default)]
+    pub last_used: BTreeMap<String, u64>,
+}
+
+/// A downloaded model's footprint.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelUsage {
+    pub model: WhisperModel,
+    pub display_name: String,
+    /// Bytes in the per-user models directory (see [`model::disk_usage`]).
+    pub bytes: u64,
+    /// Unix seconds of the last use; `None` if it hasn't been used since
+    /// usage was first tracked.
+    pub last_used: Option<u64>,
+}
+
+fn usage_path() -> PathBuf {
+    model::models_dir().join(USAGE_FILENAME)
+}
+
+pub fn load() -> UsageLog {
+    load_from(&usage_path())
+}
+
+fn load_from(path: &Path) -> UsageLog {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_to(path: &Path, log: &UsageLog) -> Result<(), DictationError> {
+    let json = serde_json::to_string_pretty(log)
+        .map_err(|e| DictationError::SettingsError(format!("Serialize error: {e}")))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)
+        .and_then(|()| std::fs::rename(&tmp, path))
+        .map_err(|e| DictationError::SettingsError(format!("Cannot write {}: {e}", path.display())))
+}
+
+/// Note that `m` is being used. Failures only log: usage is bookkeeping.
+pub fn record_used(m: WhisperModel) {
+    let path = usage_path();
+    let mut log = load_from(&path);
+    if !mark_used(&mut log, m, unix_now()) {
+        return;
+    }
+    if let Err(e) = save_to(&path, &log) {
+        warn!("Could not record model usage: {e}");
+    }
+}
+
+/// Set `m`'s last use to `now`; false if the recorded one is recent enough
+/// to leave as is.
+fn mark_used(log: &mut UsageLog, m: WhisperModel, now: u64) -> bool {
+    let last = log.last_used.entry(catalog::model_id(m)).or_insert(0);
+    if now.saturating_sub(*last) < RECORD_EVERY_SECS {
+        return false;
+    }
+    *last = now;
+    true
+}
+
+/// Drop `m` from the log once its files are deleted.
+pub fn forget(m: WhisperModel) {
+    let path = usage_path();
+    let mut log = load_from(&path);
+    if log.last_used.remove(&catalog::model_id(m)).is_some() {
+        if let Err(e) = save_to(&path, &log) {
+            warn!("Could not update model usage: {e}");
+        }
+    }
+}
+
+/// Every built-in model with files in the per-user models directory.
+pub fn downloaded() -> Vec<ModelUsage> {
+    let log = load();
+    catalog::all_models()
+        .into_iter()
+        .filter_map(|m| {
+            let bytes = model::disk_usage(m);
+            (bytes > 0).then(|| ModelUsage {
+                model: m,
+                display_name: m.display_name().to_string(),
+                bytes,
+                last_used: log.last_used.get(&catalog::model_id(m)).copied(),
+            })
+        })
+        .collect()
+}
+
+/// Models `settings` may dictate or transcribe with: the selected one, and
+/// each profile's; with `adaptive_model` the fast and accurate models of
+/// their languages, and with `draft_pass` the draft one. With
+/// `keep_recommended`, also the recommended model of every configured
+/// language.
+pub fn needed_models(settings: &Settings, keep_recommended: bool) -> Vec<WhisperModel> {
+    let configured = std::iter::once(settings.clone())
+        .chain(settings.profiles.iter().map(|p| settings.with_profile(p)));
+    let mut needed = Vec::new();
+    let mut keep = |m: WhisperModel| {
+        if !needed.contains(&m) {
+            needed.push(m);
+        }
+    };
+    for s in configured {
+        keep(s.effective_model());
+        if s.adaptive_model {
+            keep(WhisperModel::recommended(s.language));
+            keep(WhisperModel::accurate(s.language));
+        }
+        if s.draft_pass {
+            keep(WhisperModel::draft(s.language));
+        }
+        if keep_recommended {
+            keep(WhisperModel::recommended(s.language));
+        }
+    }
+    needed
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{Language, Profile};
+
+    #[test]
+    fn uses_are_written_at_most_hourly() {
+        let mut log = UsageLog::default();
+        assert!(mark_used(&mut log, WhisperModel::BaseEn, 10_000));
+        assert!(!mark_used(&mut log, WhisperModel::BaseEn, 10_600));
+        assert!(mark_used(&mut log, WhisperModel::BaseEn, 13_600));
+        assert_eq!(log.last_used["base.en"], 13_600);
+    }
+
+    #[test]
+    fn needed_models_cover_profiles_and_recommended() {
+        let settings = Settings {
+            language: Language::English,
+            auto_select_model: false,
+            whisper_model: WhisperModel::SmallEn,
+            adaptive_model: false,
+            draft_pass: false,
+            profiles: vec![Profile {
+                name: "Svenska".to_string(),
+                language: Language::Swedish,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let needed = needed_models(&settings, false);
+        assert!(needed.contains(&WhisperModel::SmallEn));
+        assert!(needed.contains(&WhisperModel::KbWhisperBase));
+        assert!(!needed.contains(&WhisperModel::BaseEn));
+        assert!(needed_models(&settings, true).contains(&WhisperModel::BaseEn));
+    }
+}
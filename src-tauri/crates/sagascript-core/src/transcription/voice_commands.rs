@@ -0,0 +1,284 @@
+//! Spoken punctuation and layout commands.
+//!
+//! With `Settings::voice_commands` on, a dictation can say the characters it
+//! wants: "Dear Anna comma new line thanks for the update period" becomes
+//! "Dear Anna,\nThanks for the update." [`apply_voice_commands`] rewrites the
+//! joined dictation text before it is pasted; segment text stays as spoken.
+//!
+//! Whisper often punctuates around a command on its own ("Anna, comma, new
+//! line."). Punctuation attached to a command word is dropped, and so is a
+//! mark Whisper put right before a spoken one, so the result has only what
+//! was said. A word after a sentence end or line break is capitalized.
+//!
+//! Each language has its own words; auto-detect listens for all of them.
+//! Several commands ("period", "punkt", "kolon") are also ordinary words,
+//! which is why this is off by default.
+
+use super::postprocess::{token_spans, Token};
+use crate::settings::Language;
+
+/// What a command types, and how it joins the words around it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Symbol {
+    text: &'static str,
+    /// Keep the space before the command ("say (this").
+    space_before: bool,
+    /// Keep the space after it ("this, and").
+    space_after: bool,
+}
+
+/// A mark that closes what came before: comma, period, close quote.
+const fn mark(text: &'static str) -> Symbol {
+    Symbol {
+        text,
+        space_before: false,
+        space_after: true,
+    }
+}
+
+/// A mark that opens what comes next: open quote, open parenthesis.
+const fn opening(text: &'static str) -> Symbol {
+    Symbol {
+        text,
+        space_before: true,
+        space_after: false,
+    }
+}
+
+/// A line break, with no spaces around it.
+const fn layout(text: &'static str) -> Symbol {
+    Symbol {
+        text,
+        space_before: false,
+        space_after: false,
+    }
+}
+
+const EN: &[(&str, Symbol)] = &[
+    ("comma", mark(",")),
+    ("period", mark(".")),
+    ("full stop", mark(".")),
+    ("question mark", mark("?")),
+    ("exclamation mark", mark("!")),
+    ("exclamation point", mark("!")),
+    ("colon", mark(":")),
+    ("semicolon", mark(";")),
+    ("new line", layout("\n")),
+    ("newline", layout("\n")),
+    ("new paragraph", layout("\n\n")),
+    ("open quote", opening("\"")),
+    ("close quote", mark("\"")),
+    ("end quote", mark("\"")),
+    ("open paren", opening("(")),
+    ("open parenthesis", opening("(")),
+    ("close paren", mark(")")),
+    ("close parenthesis", mark(")")),
+];
+
+// "komma" alone is left out: it is also a verb, and the spoken decimal
+// separator that `localize_numbers` reads.
+const SV: &[(&str, Symbol)] = &[
+    ("kommatecken", mark(",")),
+    ("punkt", mark(".")),
+    ("frågetecken", mark("?")),
+    ("utropstecken", mark("!")),
+    ("kolon", mark(":")),
+    ("semikolon", mark(";")),
+    ("ny rad", layout("\n")),
+    ("nytt stycke", layout("\n\n")),
+    ("öppna citat", opening("\"")),
+    ("stäng citat", mark("\"")),
+    ("öppna parentes", opening("(")),
+    ("stäng parentes", mark(")")),
+];
+
+const NO: &[(&str, Symbol)] = &[
+    ("kommategn", mark(",")),
+    ("punktum", mark(".")),
+    ("spørsmålstegn", mark("?")),
+    ("utropstegn", mark("!")),
+    ("kolon", mark(":")),
+    ("semikolon", mark(";")),
+    ("ny linje", layout("\n")),
+    ("nytt avsnitt", layout("\n\n")),
+    ("åpne sitat", opening("\"")),
+    ("lukk sitat", mark("\"")),
+    ("åpne parentes", opening("(")),
+    ("lukk parentes", mark(")")),
+];
+
+/// Punctuation Whisper may attach to a command word, or put before one.
+const WHISPER_MARKS: [char; 6] = ['.', ',', '!', '?', ';', ':'];
+
+fn commands_for(language: Language) -> Vec<(&'static str, Symbol)> {
+    match language {
+        Language::English => EN.to_vec(),
+        Language::Swedish => SV.to_vec(),
+        Language::Norwegian => NO.to_vec(),
+        Language::Auto => [EN, SV, NO].concat(),
+    }
+}
+
+/// Replace the spoken commands in `text` with the characters they stand for.
+/// Text without a command is returned unchanged, whitespace included.
+pub fn apply_voice_commands(text: &str, language: Language) -> String {
+    let commands = commands_for(language);
+    let tokens = token_spans(text);
+    let words: Vec<String> = tokens
+        .iter()
+        .map(|token| token.text.trim_matches(WHISPER_MARKS).to_lowercase())
+        .collect();
+
+    let mut rendered = String::with_capacity(text.len());
+    let mut cursor = 0;
+    let mut changed = false;
+    // How the previous token left things: no space wanted before the next
+    // word, the next word starts a sentence, the previous token was spoken.
+    let mut glued = false;
+    let mut capitalize = false;
+    let mut after_word = false;
+    let mut index = 0;
+    while index < tokens.len() {
+        let Some((symbol, consumed)) = command_at(&commands, &words[index..]) else {
+            let token = &tokens[index];
+            if !glued {
+                rendered.push_str(&text[cursor..token.start]);
+            }
+            if capitalize {
+                push_capitalized(&mut rendered, token);
+            } else {
+                rendered.push_str(token.text);
+            }
+            cursor = token.end;
+            (glued, capitalize, after_word) = (false, false, true);
+            index += 1;
+            continue;
+        };
+
+        if symbol.space_before && !glued {
+            rendered.push_str(&text[cursor..tokens[index].start]);
+        } else {
+            rendered.truncate(rendered.trim_end().len());
+            if after_word && symbol.text.starts_with(WHISPER_MARKS) {
+                if let Some(stripped) = rendered.strip_suffix(WHISPER_MARKS) {
+                    rendered.truncate(stripped.len());
+                }
+            }
+        }
+        rendered.push_str(symbol.text);
+        cursor = tokens[index + consumed - 1].end;
+        glued = !symbol.space_after;
+        capitalize = matches!(symbol.text, "." | "?" | "!" | "\n" | "\n\n");
+        after_word = false;
+        changed = true;
+        index += consumed;
+    }
+
+    if !changed {
+        return text.to_string();
+    }
+    if !glued {
+        rendered.push_str(&text[cursor..]);
+    }
+    rendered
+}
+
+/// The longest command starting at the first of `words`, and how many words
+/// it spans.
+fn command_at(commands: &[(&str, Symbol)], words: &[String]) -> Option<(Symbol, usize)> {
+    commands
+        .iter()
+        .filter_map(|(phrase, symbol)| {
+            let phrase: Vec<&str> = phrase.split(' ').collect();
+            let spoken = words.get(..phrase.len())?;
+            (spoken == phrase.as_slice()).then_some((*symbol, phrase.len()))
+        })
+        .max_by_key(|(_, len)| *len)
+}
+
+fn push_capitalized(rendered: &mut String, token: &Token<'_>) {
+    let mut chars = token.text.chars();
+    if let Some(first) = chars.next() {
+        rendered.extend(first.to_uppercase());
+        rendered.push_str(chars.as_str());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn en(text: &str) -> String {
+        apply_voice_commands(text, Language::English)
+    }
+
+    #[test]
+    fn punctuation_attaches_to_the_previous_word() {
+        assert_eq!(
+            en("Dear Anna comma new line thanks for the update period"),
+            "Dear Anna,\nThanks for the update."
+        );
+        assert_eq!(en("Is it done question mark"), "Is it done?");
+    }
+
+    #[test]
+    fn whisper_punctuation_around_commands_is_dropped() {
+        assert_eq!(
+            en("Dear Anna, comma, new line. Thanks."),
+            "Dear Anna,\nThanks."
+        );
+        assert_eq!(en("It works. Exclamation mark."), "It works!");
+    }
+
+    #[test]
+    fn quotes_and_parentheses_hug_their_contents() {
+        assert_eq!(
+            en("She said open quote hello close quote and left"),
+            "She said \"hello\" and left"
+        );
+        assert_eq!(
+            en("The fix open paren see above close paren works"),
+            "The fix (see above) works"
+        );
+    }
+
+    #[test]
+    fn new_paragraph_and_capitalization() {
+        assert_eq!(
+            en("First point period new paragraph second point"),
+            "First point.\n\nSecond point"
+        );
+    }
+
+    #[test]
+    fn commands_follow_the_language() {
+        assert_eq!(
+            apply_voice_commands("Hej Anna kommatecken ny rad tack punkt", Language::Swedish),
+            "Hej Anna,\nTack."
+        );
+        assert_eq!(
+            apply_voice_commands("Hei ny linje takk punktum", Language::Norwegian),
+            "Hei\nTakk."
+        );
+        assert_eq!(
+            apply_voice_commands("Hej kommatecken", Language::English),
+            "Hej kommatecken"
+        );
+        assert_eq!(
+            apply_voice_commands("Hi comma ny rad", Language::Auto),
+            "Hi,\n"
+        );
+    }
+
+    #[test]
+    fn swedish_komma_is_left_alone() {
+        let text = "Jag kan komma i morgon.";
+        assert_eq!(apply_voice_commands(text, Language::Swedish), text);
+    }
+
+    #[test]
+    fn text_without_commands_is_unchanged() {
+        let text = "  A  colonist\tnew lines.\n";
+        assert_eq!(en(text), text);
+    }
+}
@@ -1,19 +1,25 @@
+use std::collections::BTreeMap;
 use std::ffi::c_void;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock, TryLockError};
 use std::time::{Duration, Instant};
 
 use tracing::{info, warn};
 use whisper_rs::{
     FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
-    WhisperVadParams,
+    WhisperSysContext, WhisperSysState, WhisperTokenData, WhisperVadParams,
 };
 #[cfg(feature = "diarization")]
 use whisper_rs::{DtwMode, DtwParameters};
 
+use crate::audio::decoder;
+use crate::audio::resample::TARGET_SAMPLE_RATE;
 use crate::error::DictationError;
-use crate::settings::{Language, WhisperModel};
-use crate::transcription::model;
+use crate::settings::{Language, ModelOverrides, WhisperModel};
+use crate::transcription::keywords::KeywordBias;
+use crate::transcription::throttle::Throttle;
+use crate::transcription::{catalog, model, usage};
 
 /// Default beam width for file (non-live) transcription. File transcription
 /// isn't latency-sensitive, so a wider beam trades speed for fewer repetition
@@ -42,6 +48,9 @@ const WARM_STATE_POLL_INTERVAL: Duration = Duration::from_millis(25);
 pub struct TranscribeOptions {
     /// Optional decoder priming prompt (domain vocabulary).
     pub prompt: Option<String>,
+    /// Words the decoder is biased toward token by token (see
+    /// [`crate::transcription::keywords`]). Empty = no biasing.
+    pub keywords: Vec<String>,
     /// Beam width: 0/1 = greedy (fastest); >=2 enables beam search (more
     /// accurate on hard audio, ~3-5x slower).
     pub beam_size: u32,
@@ -55,20 +64,75 @@ pub struct TranscribeOptions {
     /// Request real Whisper segment timestamps for structured outputs such as
     /// CLI JSON. Text decoding remains in no-timestamps mode.
     pub segment_timestamps: bool,
+    /// Also group whisper's per-token timestamps into words
+    /// ([`TranscriptSegment::words`]) for alignment and captioning.
+    pub word_timestamps: bool,
+    /// Write spoken numbers, decimals and dates the way the language does
+    /// ("tjugofem komma fem" → "25,5"); see [`super::localize_numbers`].
+    pub localize_numbers: bool,
+    /// Type spoken punctuation and line breaks ("comma", "new line") as
+    /// characters in the joined text; see [`super::apply_voice_commands`].
+    pub voice_commands: bool,
+    /// Drop sentence punctuation from the joined text (`Settings::punctuate`
+    /// off); see [`super::strip_punctuation`].
+    pub strip_punctuation: bool,
+    /// Per-model overrides from settings, keyed by model ID. Applied to
+    /// whichever model ends up transcribing (see [`Self::decoding_for`]).
+    pub model_overrides: BTreeMap<String, ModelOverrides>,
+}
+
+/// Decoder knobs after merging a model's overrides over the defaults.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Decoding {
+    pub no_speech_threshold: f32,
+    pub beam_size: u32,
+    pub temperature_fallback: bool,
+}
+
+impl TranscribeOptions {
+    /// The settings `model` decodes with: its override where one is set,
+    /// otherwise the built-in no-speech threshold and these options.
+    pub fn decoding_for(&self, model: WhisperModel) -> Decoding {
+        let overrides = self
+            .model_overrides
+            .get(&catalog::model_id(model))
+            .cloned()
+            .unwrap_or_default();
+        Decoding {
+            no_speech_threshold: overrides
+                .no_speech_threshold
+                .unwrap_or_else(|| model.no_speech_threshold()),
+            beam_size: overrides.beam_size.unwrap_or(self.beam_size),
+            temperature_fallback: overrides
+                .temperature_fallback
+                .unwrap_or(self.temperature_fallback),
+        }
+    }
 }
 
 impl Default for TranscribeOptions {
     fn default() -> Self {
         Self {
             prompt: None,
+            keywords: Vec::new(),
             beam_size: 0,
             temperature_fallback: true,
             vad_model_path: None,
             segment_timestamps: false,
+            word_timestamps: false,
+            localize_numbers: false,
+            voice_commands: false,
+            strip_punctuation: false,
+            model_overrides: BTreeMap::new(),
         }
     }
 }
 
+/// A segment whisper rates at least this likely to be non-speech counts as
+/// silence for [`Transcript::is_silent`]; its text is usually a
+/// hallucination such as "Thank you."
+pub const SILENT_SEGMENT_PROB: f32 = 0.8;
+
 /// One whisper output segment with timing and confidence metadata.
 ///
 /// `avg_logprob` is derived (whisper.cpp does not expose its internal
@@ -78,7 +142,7 @@ impl Default for TranscribeOptions {
 /// Typical values: > -0.3 confident, -0.3..-0.8 shaky, < -0.8 suspect.
 /// `no_speech_prob` is whisper's own per-segment estimate that the window
 /// contains no speech (near 1.0 ⇒ likely hallucinated text).
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
 pub struct TranscriptSegment {
     /// Segment start in seconds.
     pub start: f64,
@@ -91,6 +155,89 @@ pub struct TranscriptSegment {
     pub avg_logprob: Option<f32>,
     /// Whisper's probability that the segment window is non-speech.
     pub no_speech_prob: f32,
+    /// Per-word timing, filled only when
+    /// [`TranscribeOptions::word_timestamps`] is set. Words are as decoded,
+    /// before number localization.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<WordTiming>,
+}
+
+/// One word of a segment with its start and end in seconds.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct WordTiming {
+    pub text: String,
+    pub start: f64,
+    pub end: f64,
+}
+
+/// Result of transcribing one clip: whisper's segments with their timing and
+/// confidence, plus the display text they join into. The result may have
+/// been cut short (see `partial`).
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Transcript {
+    /// Segment texts joined and display-normalized (see [`join_segments`]).
+    pub text: String,
+    /// Empty when the text didn't come straight from whisper, e.g. when it
+    /// was stitched together from background chunks.
+    pub segments: Vec<TranscriptSegment>,
+    /// `true` when the inference was aborted (e.g. on timeout) and `text`
+    /// only covers the segments whisper finished before the abort.
+    pub partial: bool,
+}
+
+impl Transcript {
+    pub fn from_segments(
+        segments: Vec<TranscriptSegment>,
+        language: Language,
+        partial: bool,
+    ) -> Self {
+        Self {
+            text: join_segments(&segments, language),
+            segments,
+            partial,
+        }
+    }
+
+    /// Apply the options that rewrite the joined text; segment text stays
+    /// as whisper decoded it.
+    fn rewritten(mut self, opts: &TranscribeOptions, language: Language) -> Self {
+        if opts.strip_punctuation {
+            self.text = super::strip_punctuation(&self.text);
+        }
+        if opts.voice_commands {
+            self.text = super::apply_voice_commands(&self.text, language);
+        }
+        self
+    }
+
+    /// A transcript known only by its text, without segment detail.
+    pub fn from_text(text: String, partial: bool) -> Self {
+        Self {
+            text,
+            segments: Vec::new(),
+            partial,
+        }
+    }
+
+    /// Whether nothing was said: the text is empty, or every segment is
+    /// likely non-speech (see [`SILENT_SEGMENT_PROB`]). A partial result
+    /// only covers part of the clip, so it never counts.
+    pub fn is_silent(&self) -> bool {
+        if self.partial {
+            return false;
+        }
+        self.text.trim().is_empty()
+            || (!self.segments.is_empty()
+                && self
+                    .segments
+                    .iter()
+                    .all(|s| s.no_speech_prob >= SILENT_SEGMENT_PROB))
+    }
+
+    /// The joined text, for callers that only want the string.
+    pub fn into_text(self) -> String {
+        self.text
+    }
 }
 
 /// Mean of per-token log-probabilities; `None` for an empty slice.
@@ -131,6 +278,64 @@ fn sanitize_segment_bounds(
     (start, end)
 }
 
+/// Group a segment's text tokens, given as `(bytes, start, end)`, into words.
+///
+/// A token beginning with a space starts a new word; anything else (word
+/// pieces, punctuation, the second half of a UTF-8 character split across
+/// tokens) extends the current one. Times are kept inside the segment and in
+/// order, since whisper's token-level estimates can overshoot either.
+fn words_from_tokens<B: AsRef<[u8]>>(
+    tokens: impl IntoIterator<Item = (B, f64, f64)>,
+    seg_start: f64,
+    seg_end: f64,
+) -> Vec<WordTiming> {
+    let mut pending: Vec<(Vec<u8>, f64, f64)> = Vec::new();
+    for (bytes, t0, t1) in tokens {
+        let bytes = bytes.as_ref();
+        match pending.last_mut() {
+            Some(word) if !bytes.starts_with(b" ") => {
+                word.0.extend_from_slice(bytes);
+                word.2 = t1;
+            }
+            _ => pending.push((bytes.to_vec(), t0, t1)),
+        }
+    }
+
+    let mut words = Vec::with_capacity(pending.len());
+    let mut previous_end = seg_start;
+    for (bytes, t0, t1) in pending {
+        let text = String::from_utf8_lossy(&bytes).trim().to_string();
+        if text.is_empty() {
+            continue;
+        }
+        let (start, end) = sanitize_segment_bounds(t0, t1, seg_end, previous_end);
+        previous_end = end;
+        words.push(WordTiming { text, start, end });
+    }
+    words
+}
+
+/// Abort handle for one [`WhisperBackend::transcribe_job`] or
+/// [`WhisperBackend::transcribe_file_streaming`] call. Cloning
+/// shares the flag; once aborted it stays aborted, so use a new handle per job.
+#[derive(Debug, Clone, Default)]
+pub struct JobAbort(Arc<AtomicBool>);
+
+impl JobAbort {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop the job at whisper.cpp's next compute step.
+    pub fn abort(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
 /// Local transcription backend using whisper-rs (whisper.cpp bindings)
 /// Uses GGML model files with optional CoreML acceleration on macOS.
 ///
@@ -138,14 +343,29 @@ fn sanitize_segment_bounds(
 /// because transcription is blocking and we must not hold the AppController
 /// lock across async boundaries.
 pub struct WhisperBackend {
-    /// Loaded whisper context (model weights). None until load_model() is called.
-    context: Mutex<Option<WhisperContext>>,
+    /// Loaded whisper context (model weights), shared immutably. None until
+    /// load_model() is called. Inference never holds this lock: callers clone
+    /// the Arc and run on a state of their own, so a model swap just replaces
+    /// the Arc and in-flight jobs finish on the weights they started with.
+    context: RwLock<Option<Arc<WhisperContext>>>,
     /// Reusable inference state, kept warm across utterances so we don't pay
     /// whisper/Metal state-init (kernel compile + GPU buffer alloc) on every
     /// call. Created lazily on first transcription; reset to None on model reload.
     state: Mutex<Option<WhisperState>>,
     /// Currently loaded model
     loaded_model: Mutex<Option<WhisperModel>>,
+    /// Whether models should load onto the GPU (`Settings::use_gpu`).
+    use_gpu: AtomicBool,
+    /// Whether the loaded model was loaded with the GPU on.
+    loaded_on_gpu: AtomicBool,
+    /// Custom GGML file to load in place of the built-in model's
+    /// (`Settings::custom_model`).
+    custom_model: Mutex<Option<PathBuf>>,
+    /// Custom file the loaded model came from, if any.
+    loaded_custom: Mutex<Option<PathBuf>>,
+    /// When streamed file transcription pauses between chunks; `None` for
+    /// never (see [`super::throttle`]).
+    throttle: Mutex<Option<Throttle>>,
     /// Abort flag — set to true to cancel in-progress transcription
     abort_flag: Arc<AtomicBool>,
     /// Serializes model (re)loads so concurrent `ensure_model()` callers — e.g.
@@ -154,8 +374,9 @@ pub struct WhisperBackend {
     load_lock: Mutex<()>,
 }
 
-// WhisperContext is Send+Sync (it wraps a C pointer that's thread-safe)
-// The Mutex handles interior mutability safely
+// WhisperContext is Send+Sync (it wraps a C pointer that's thread-safe) and is
+// only ever read after loading; all mutable inference data lives in a
+// WhisperState, each of which is used by one thread at a time.
 unsafe impl Send for WhisperBackend {}
 unsafe impl Sync for WhisperBackend {}
 
@@ -167,15 +388,57 @@ impl Default for WhisperBackend {
 
 impl WhisperBackend {
     pub fn new() -> Self {
+        Self::with_gpu(true)
+    }
+
+    /// A backend that loads models onto the GPU only if `use_gpu` is set.
+    pub fn with_gpu(use_gpu: bool) -> Self {
         Self {
-            context: Mutex::new(None),
+            context: RwLock::new(None),
             state: Mutex::new(None),
             loaded_model: Mutex::new(None),
+            use_gpu: AtomicBool::new(use_gpu),
+            loaded_on_gpu: AtomicBool::new(false),
+            custom_model: Mutex::new(None),
+            loaded_custom: Mutex::new(None),
+            throttle: Mutex::new(None),
             abort_flag: Arc::new(AtomicBool::new(false)),
             load_lock: Mutex::new(()),
         }
     }
 
+    /// Turn GPU inference on or off. A model loaded the other way is
+    /// reloaded by the next [`Self::ensure_model`].
+    pub fn set_use_gpu(&self, use_gpu: bool) {
+        self.use_gpu.store(use_gpu, Ordering::SeqCst);
+    }
+
+    /// Load the custom GGML file at `path` instead of the built-in models'
+    /// files, or go back to them with `None`. The built-in model asked for
+    /// still supplies the decoding defaults. Takes effect at the next
+    /// [`Self::ensure_model`].
+    pub fn set_custom_model(&self, path: Option<PathBuf>) {
+        *self.custom_model.lock().unwrap() = path;
+    }
+
+    /// Pause streamed file transcription before each chunk while `throttle`
+    /// says to, or never with `None`.
+    pub fn set_throttle(&self, throttle: Option<Throttle>) {
+        *self.throttle.lock().unwrap() = throttle;
+    }
+
+    /// The custom file the loaded model came from, if it is a custom model.
+    pub fn loaded_custom_model(&self) -> Option<PathBuf> {
+        self.loaded_custom.lock().unwrap().clone()
+    }
+
+    /// Whether the loaded model runs on the GPU, or `None` with no model
+    /// loaded. Only meaningful when the build has a GPU backend (see
+    /// [`super::runtime::AccelerationInfo`]).
+    pub fn loaded_on_gpu(&self) -> Option<bool> {
+        self.loaded_model().map(|_| self.loaded_on_gpu.load(Ordering::SeqCst))
+    }
+
     /// Signal any in-progress inference to abort at the next whisper.cpp compute
     /// step.
     ///
@@ -217,45 +480,117 @@ impl WhisperBackend {
         flag.load(Ordering::Relaxed)
     }
 
-    /// Wire the real abort callback into `params`, pointing it at `abort_flag`.
-    /// The flag's lifecycle (clear-when-stale, discard-state-on-abort) is owned
-    /// exclusively by the state-lock holder inside [`Self::with_warm_state_grace`]
-    /// — see the ownership rules documented there.
-    fn install_abort_callback(&self, params: &mut FullParams) {
-        let flag_ptr = Arc::as_ptr(&self.abort_flag) as *mut c_void;
-        // Safety: `flag_ptr` targets the `AtomicBool` owned by `self.abort_flag`,
-        // which the backend keeps alive for its entire lifetime (well past the
-        // `full()` call these params drive). The trampoline only reads the flag
-        // and never touches the whisper context, satisfying the FFI contract.
+    /// Wire the real abort callback into `params`, pointing it at `flag`
+    /// (the backend's `abort_flag` for warm-state calls, or a job's
+    /// [`JobAbort`]). The warm flag's lifecycle (clear-when-stale,
+    /// discard-state-on-abort) is owned exclusively by the state-lock holder
+    /// inside [`Self::with_warm_state_grace`] — see the ownership rules
+    /// documented there.
+    fn install_abort_callback(params: &mut FullParams, flag: &Arc<AtomicBool>) {
+        let flag_ptr = Arc::as_ptr(flag) as *mut c_void;
+        // Safety: `flag_ptr` targets an `AtomicBool` kept alive by an Arc that
+        // the caller holds across the `full()` call these params drive (the
+        // backend's own flag, or the job's handle borrowed for the call). The
+        // trampoline only reads the flag and never touches the whisper
+        // context, satisfying the FFI contract.
         unsafe {
             params.set_abort_callback(Some(Self::abort_trampoline));
             params.set_abort_callback_user_data(flag_ptr);
         }
     }
 
-    /// Load a specific model, replacing any previously loaded model
-    pub fn load_model(&self, whisper_model: WhisperModel) -> Result<(), DictationError> {
-        let model_path = model::model_path(whisper_model);
+    /// FFI logits filter. whisper.cpp calls it before sampling each token
+    /// with the tokens decoded so far in the current segment and the full
+    /// vocabulary's logits; `data` points at the call's [`KeywordBias`].
+    unsafe extern "C" fn keyword_filter_trampoline(
+        ctx: *mut WhisperSysContext,
+        _state: *mut WhisperSysState,
+        tokens: *const WhisperTokenData,
+        n_tokens: std::os::raw::c_int,
+        logits: *mut f32,
+        data: *mut c_void,
+    ) {
+        if ctx.is_null() || data.is_null() || logits.is_null() {
+            return;
+        }
+        // Safety: `ctx` is the context of the model decoding this call, so
+        // its vocabulary size is the length of `logits`.
+        let n_vocab = unsafe { whisper_rs::whisper_rs_sys::whisper_n_vocab(ctx) };
+        let Ok(n_vocab) = usize::try_from(n_vocab) else {
+            return;
+        };
+        // Safety: `data` is the `KeywordBias` borrowed by
+        // `install_keyword_filter`, alive for the whole `full()` call.
+        // whisper.cpp passes `n_tokens` valid entries at `tokens` and
+        // `n_vocab` logits at `logits`, both owned by the decoder for the
+        // duration of this call.
+        let bias = unsafe { &*(data as *const KeywordBias) };
+        // Tokenized against another model (one was swapped in after the
+        // bias was built): its ids name other words here, so leave the
+        // logits alone.
+        if bias.n_vocab() != n_vocab {
+            return;
+        }
+        let history: Vec<_> = if tokens.is_null() || n_tokens <= 0 {
+            Vec::new()
+        } else {
+            unsafe { std::slice::from_raw_parts(tokens, n_tokens as usize) }
+                .iter()
+                .map(|t| t.id)
+                .collect()
+        };
+        let logits = unsafe { std::slice::from_raw_parts_mut(logits, n_vocab) };
+        bias.apply(&history, logits);
+    }
 
-        if !model_path.exists() {
-            return Err(DictationError::TranscriptionFailed(format!(
-                "Model '{}' not downloaded. Please download it from Settings first.",
-                whisper_model.display_name()
-            )));
+    /// Wire the keyword logits filter into `params`. The caller keeps `bias`
+    /// alive until the `full()` call these params drive has returned.
+    fn install_keyword_filter(params: &mut FullParams, bias: &KeywordBias) {
+        let bias_ptr = bias as *const KeywordBias as *mut c_void;
+        // Safety: see keyword_filter_trampoline — it only reads `bias` and
+        // the buffers whisper.cpp hands it.
+        unsafe {
+            params.set_filter_logits_callback(Some(Self::keyword_filter_trampoline));
+            params.set_filter_logits_callback_user_data(bias_ptr);
         }
+    }
 
+    /// Load a specific model, replacing any previously loaded model
+    pub fn load_model(&self, whisper_model: WhisperModel) -> Result<(), DictationError> {
+        let custom = self.custom_model.lock().unwrap().clone();
+        let model_path = match &custom {
+            Some(path) => {
+                // Custom files have no checksum to verify; at least make sure
+                // whisper.cpp's parser gets a GGML file.
+                model::check_custom_model_file(path)?;
+                path.clone()
+            }
+            None => {
+                let model_path = model::model_path(whisper_model);
+                if !model_path.exists() {
+                    return Err(DictationError::TranscriptionFailed(format!(
+                        "Model '{}' not downloaded. Please download it from Settings first.",
+                        whisper_model.display_name()
+                    )));
+                }
 
-        // Never hand an unverified GGML file to whisper.cpp's native parser.
-        // This also performs a one-time compatibility check for files saved by
-        // versions released before download integrity was enforced.
-        crate::download::verify_file(&model_path, whisper_model.download_integrity())?;
-        model::quarantine_unverified_coreml_encoder(whisper_model)?;
+                // Never hand an unverified GGML file to whisper.cpp's native
+                // parser. This also performs a one-time compatibility check for
+                // files saved by versions released before download integrity
+                // was enforced.
+                crate::download::verify_file(&model_path, whisper_model.download_integrity())?;
+                model::check_model_pin(whisper_model)?;
+                model::quarantine_unverified_coreml_encoder(whisper_model)?;
+                model_path
+            }
+        };
+        let label = match &custom {
+            Some(path) => format!("custom model {}", path.display()),
+            None => whisper_model.display_name().to_string(),
+        };
+        let built_in = custom.is_none();
 
-        info!(
-            "Loading whisper model: {} from {}",
-            whisper_model.display_name(),
-            model_path.display()
-        );
+        info!("Loading whisper model: {label} from {}", model_path.display());
 
         // Flash attention is an exact (not approximate) attention kernel that is
         // accelerated on Metal — a free speedup with identical output. It is
@@ -263,17 +598,22 @@ impl WhisperBackend {
         // timestamps when flash_attn is on. So the default (dictation) build
         // turns it ON, and the diarization build leaves it off and uses DTW for
         // attention-based token timestamps (used by --diarize) instead.
+        let use_gpu = self.use_gpu.load(Ordering::SeqCst);
         let ctx_params = {
             let mut p = WhisperContextParameters::default();
+            p.use_gpu(use_gpu);
             #[cfg(not(feature = "diarization"))]
             p.flash_attn(true);
+            // A custom model's alignment heads are unknown, so it gets no DTW.
             #[cfg(feature = "diarization")]
-            p.dtw_parameters(DtwParameters {
-                mode: DtwMode::ModelPreset {
-                    model_preset: whisper_model.dtw_preset(),
-                },
-                ..DtwParameters::default()
-            });
+            if custom.is_none() {
+                p.dtw_parameters(DtwParameters {
+                    mode: DtwMode::ModelPreset {
+                        model_preset: whisper_model.dtw_preset(),
+                    },
+                    ..DtwParameters::default()
+                });
+            }
             p
         };
 
@@ -307,15 +647,32 @@ impl WhisperBackend {
         // switch forever behind a wedged transcription.
         {
             let mut state = self.lock_state_bounded(WARM_STATE_GRACE)?;
-            *self.context.lock().unwrap() = Some(ctx);
+            *self.context.write().unwrap() = Some(Arc::new(ctx));
             *self.loaded_model.lock().unwrap() = Some(whisper_model);
+            self.loaded_on_gpu.store(use_gpu, Ordering::SeqCst);
+            *self.loaded_custom.lock().unwrap() = custom;
             *state = None;
         }
 
-        info!("Model loaded: {}", whisper_model.display_name());
+        info!(
+            "Model loaded: {label} ({})",
+            if use_gpu { "GPU allowed" } else { "CPU only" }
+        );
+        if built_in {
+            usage::record_used(whisper_model);
+        }
         Ok(())
     }
 
+    /// The loaded context, cloned out of the lock so inference never holds it.
+    fn shared_context(&self) -> Result<Arc<WhisperContext>, DictationError> {
+        self.context
+            .read()
+            .unwrap()
+            .clone()
+            .ok_or(DictationError::ModelNotLoaded)
+    }
+
     /// Get the currently loaded model
     pub fn loaded_model(&self) -> Option<WhisperModel> {
         *self.loaded_model.lock().unwrap()
@@ -324,6 +681,8 @@ impl WhisperBackend {
     /// Check if the correct model is loaded for the given settings
     pub fn needs_reload(&self, desired_model: WhisperModel) -> bool {
         self.loaded_model() != Some(desired_model)
+            || self.loaded_on_gpu.load(Ordering::SeqCst) != self.use_gpu.load(Ordering::SeqCst)
+            || *self.loaded_custom.lock().unwrap() != *self.custom_model.lock().unwrap()
     }
 
     /// Ensure the correct model is loaded. Serialized via `load_lock` so two
@@ -334,6 +693,8 @@ impl WhisperBackend {
         if self.needs_reload(desired_model) {
             info!("Loading model: {:?}", desired_model);
             self.load_model(desired_model)?;
+        } else if self.loaded_custom.lock().unwrap().is_none() {
+            usage::record_used(desired_model);
         }
         Ok(())
     }
@@ -407,8 +768,7 @@ impl WhisperBackend {
         let result = (|| {
             if state_guard.is_none() {
                 // Lock order state -> context, matching load_model()'s atomic swap.
-                let ctx_guard = self.context.lock().unwrap();
-                let ctx = ctx_guard.as_ref().ok_or(DictationError::ModelNotLoaded)?;
+                let ctx = self.shared_context()?;
                 let st = ctx.create_state().map_err(|e| {
                     DictationError::TranscriptionFailed(format!(
                         "Failed to create whisper state: {e}"
@@ -465,12 +825,49 @@ impl WhisperBackend {
         }
     }
 
+    /// Run a closure on a fresh whisper state of its own. Unlike
+    /// [`Self::with_warm_state`] nothing is locked during inference, so any
+    /// number of jobs can run alongside each other and alongside the warm
+    /// dictation path. The cost is a state allocation (KV caches, plus kernel
+    /// setup on Metal) per job, which is small next to a multi-second chunk.
+    fn with_job_state<R>(
+        &self,
+        f: impl FnOnce(&mut WhisperState) -> Result<R, DictationError>,
+    ) -> Result<R, DictationError> {
+        let ctx = self.shared_context()?;
+        let mut state = ctx.create_state().map_err(|e| {
+            DictationError::TranscriptionFailed(format!("Failed to create whisper state: {e}"))
+        })?;
+        f(&mut state)
+    }
+
+    /// Transcribe on a dedicated whisper state instead of the shared warm one,
+    /// for work that runs concurrently with dictation (background chunks of a
+    /// long recording, batch jobs). Never returns [`DictationError::ModelBusy`].
+    ///
+    /// [`Self::request_abort`] does not affect jobs; abort one through its
+    /// `abort` handle. As with [`Self::transcribe_sync_allow_partial`], an
+    /// aborted job that already decoded segments returns them marked
+    /// `partial`. Blocking — call from spawn_blocking.
+    pub fn transcribe_job(
+        &self,
+        audio: &[f32],
+        language: Language,
+        opts: &TranscribeOptions,
+        on_progress: impl FnMut(i32) + 'static,
+        abort: &JobAbort,
+    ) -> Result<Transcript, DictationError> {
+        let (segments, partial) =
+            self.transcribe_segments(audio, language, opts, on_progress, true, Some(abort))?;
+        Ok(Transcript::from_segments(segments, language, partial).rewritten(opts, language))
+    }
+
     /// Run transcription on loaded model (blocking — call from spawn_blocking)
     pub fn transcribe_sync(
         &self,
         audio: &[f32],
         language: Language,
-    ) -> Result<String, DictationError> {
+    ) -> Result<Transcript, DictationError> {
         self.transcribe_sync_with_progress(audio, language, |_| {})
     }
 
@@ -481,7 +878,7 @@ impl WhisperBackend {
         audio: &[f32],
         language: Language,
         on_progress: impl FnMut(i32) + 'static,
-    ) -> Result<String, DictationError> {
+    ) -> Result<Transcript, DictationError> {
         self.transcribe_sync_with_progress_and_prompt(audio, language, None, on_progress)
     }
 
@@ -493,7 +890,7 @@ impl WhisperBackend {
         language: Language,
         prompt: Option<&str>,
         on_progress: impl FnMut(i32) + 'static,
-    ) -> Result<String, DictationError> {
+    ) -> Result<Transcript, DictationError> {
         let opts = TranscribeOptions {
             prompt: prompt.map(str::to_string),
             ..Default::default()
@@ -505,23 +902,37 @@ impl WhisperBackend {
     /// (prompt, beam search, temperature fallback, VAD). Blocking — call from
     /// spawn_blocking.
     ///
-    /// Thin wrapper over [`Self::transcribe_sync_with_options_segments`] that
-    /// concatenates the raw segment texts, then applies display-only
-    /// non-speech marker normalization. Timestamped segment text stays raw.
+    /// Returns the segments together with their text concatenated and
+    /// display-normalized (non-speech markers, voice commands when enabled);
+    /// timestamped segment text stays raw. Callers that only want the string take
+    /// [`Transcript::into_text`].
     pub fn transcribe_sync_with_options(
         &self,
         audio: &[f32],
         language: Language,
         opts: &TranscribeOptions,
         on_progress: impl FnMut(i32) + 'static,
-    ) -> Result<String, DictationError> {
+    ) -> Result<Transcript, DictationError> {
         let segments =
             self.transcribe_sync_with_options_segments(audio, language, opts, on_progress)?;
-        let mut transcript = String::new();
-        for seg in &segments {
-            transcript.push_str(&seg.text);
-        }
-        Ok(super::normalize_nonspeech_markers(transcript.trim(), language))
+        Ok(Transcript::from_segments(segments, language, false).rewritten(opts, language))
+    }
+
+    /// Like [`Self::transcribe_sync_with_options`], but an inference aborted
+    /// via [`Self::request_abort`] after whisper finished at least one segment
+    /// returns the text decoded so far, marked `partial`, instead of an error.
+    /// whisper.cpp emits segments per 30-second window, so a dictation that
+    /// overruns the timeout keeps everything up to the last finished window.
+    pub fn transcribe_sync_allow_partial(
+        &self,
+        audio: &[f32],
+        language: Language,
+        opts: &TranscribeOptions,
+        on_progress: impl FnMut(i32) + 'static,
+    ) -> Result<Transcript, DictationError> {
+        let (segments, partial) =
+            self.transcribe_segments(audio, language, opts, on_progress, true, None)?;
+        Ok(Transcript::from_segments(segments, language, partial).rewritten(opts, language))
     }
 
     /// Like [`Self::transcribe_sync_with_options`] but returns the individual
@@ -535,6 +946,89 @@ impl WhisperBackend {
         opts: &TranscribeOptions,
         on_progress: impl FnMut(i32) + 'static,
     ) -> Result<Vec<TranscriptSegment>, DictationError> {
+        self.transcribe_segments(audio, language, opts, on_progress, false, None)
+            .map(|(segments, _)| segments)
+    }
+
+    /// Decode and transcribe a long file a chunk at a time (see
+    /// [`decoder::decode_audio_file_streaming`]), so neither the decoded PCM
+    /// nor whisper's input ever holds the whole file. Segment times are
+    /// offset to the file's timeline. `on_progress` follows decoding, which
+    /// advances as each chunk finishes transcribing. With a throttle set
+    /// (see [`Self::set_throttle`]), each chunk waits for it first. Blocking
+    /// — call from spawn_blocking.
+    ///
+    /// Chunks run on dedicated states like [`Self::transcribe_job`], so
+    /// [`Self::request_abort`] does not affect the file. Aborting `abort`
+    /// stops it whether that lands during a chunk or between two, and the
+    /// result is [`DictationError::Cancelled`].
+    pub fn transcribe_file_streaming(
+        &self,
+        path: &Path,
+        language: Language,
+        opts: &TranscribeOptions,
+        on_progress: impl FnMut(u32),
+        abort: &JobAbort,
+    ) -> Result<Vec<TranscriptSegment>, DictationError> {
+        let mut segments = Vec::new();
+        let mut offset_secs = 0.0;
+        let throttle = *self.throttle.lock().unwrap();
+        let total_secs = decoder::decode_audio_file_streaming(
+            path,
+            |chunk| {
+                if let Some(throttle) = throttle {
+                    throttle.wait();
+                }
+                if abort.is_aborted() {
+                    return Err(DictationError::Cancelled);
+                }
+                let chunk_secs = chunk.len() as f64 / TARGET_SAMPLE_RATE as f64;
+                info!("Transcribing streamed chunk at {offset_secs:.1}s ({chunk_secs:.1}s)");
+                let result =
+                    self.transcribe_segments(&chunk, language, opts, |_| {}, false, Some(abort));
+                let (chunk_segments, _) = match result {
+                    Err(_) if abort.is_aborted() => return Err(DictationError::Cancelled),
+                    result => result?,
+                };
+                segments.extend(chunk_segments.into_iter().map(|mut s| {
+                    s.start += offset_secs;
+                    s.end += offset_secs;
+                    for word in &mut s.words {
+                        word.start += offset_secs;
+                        word.end += offset_secs;
+                    }
+                    s
+                }));
+                offset_secs += chunk_secs;
+                Ok(())
+            },
+            on_progress,
+        )?;
+        if abort.is_aborted() {
+            return Err(DictationError::Cancelled);
+        }
+        info!(
+            "Streamed transcription complete: {total_secs:.1}s, {} segments",
+            segments.len()
+        );
+        Ok(segments)
+    }
+
+    /// Shared body of the segment-returning entry points. With
+    /// `allow_partial`, an aborted inference that already produced segments
+    /// yields `(segments, true)`; otherwise any abort is an error.
+    ///
+    /// `job: None` runs on the shared warm state; `Some` runs on a fresh state
+    /// aborted through that handle (see [`Self::transcribe_job`]).
+    fn transcribe_segments(
+        &self,
+        audio: &[f32],
+        language: Language,
+        opts: &TranscribeOptions,
+        on_progress: impl FnMut(i32) + 'static,
+        allow_partial: bool,
+        job: Option<&JobAbort>,
+    ) -> Result<(Vec<TranscriptSegment>, bool), DictationError> {
         if audio.is_empty() {
             return Err(DictationError::NoAudioCaptured);
         }
@@ -545,24 +1039,22 @@ impl WhisperBackend {
 
         // End-of-text token id, used to exclude special tokens from the
         // avg-logprob computation (matching whisper.cpp's confidence examples:
-        // text tokens have id < eot). Read in a short scope so the context
-        // lock is released before with_warm_state re-acquires it.
-        let token_eot = {
-            let guard = self.context.lock().unwrap();
-            guard
-                .as_ref()
-                .ok_or(DictationError::ModelNotLoaded)?
-                .token_eot()
-        };
+        // text tokens have id < eot).
+        let ctx = self.shared_context()?;
+        let token_eot = ctx.token_eot();
+        // Tokenized once per call; must outlive `full()`, which reads it
+        // through the logits filter's user data.
+        let keyword_bias = KeywordBias::tokenize(&ctx, &opts.keywords);
 
         let n_threads = whisper_threads();
-        let no_speech_thold = model.no_speech_threshold();
+        let decoding = opts.decoding_for(model);
+        let no_speech_thold = decoding.no_speech_threshold;
 
         // Beam search (opt-in) is more accurate on hard audio but several times
         // slower; greedy best_of=1 is the fast default. Clamp to a sane range —
         // an unbounded beam_size from config would overflow the i32 cast or make
         // whisper.cpp unusably slow.
-        let beam_size = opts.beam_size.clamp(0, 8);
+        let beam_size = decoding.beam_size.clamp(0, 8);
         let strategy = if beam_size >= 2 {
             SamplingStrategy::BeamSearch {
                 beam_size: beam_size as i32,
@@ -578,13 +1070,13 @@ impl WhisperBackend {
         params.set_temperature(0.0);
         // Temperature fallback re-decodes hard segments at higher temperature.
         // Disabling it caps worst-case latency at the cost of some robustness.
-        params.set_temperature_inc(if opts.temperature_fallback { 0.2 } else { 0.0 });
+        params.set_temperature_inc(if decoding.temperature_fallback { 0.2 } else { 0.0 });
         params.set_translate(false);
         // Keep text decoding stable: generative timestamp tokens materially
         // change some transcripts. Structured callers get timing from token
         // alignment instead (DTW in the default macOS build).
         params.set_no_timestamps(true);
-        params.set_token_timestamps(opts.segment_timestamps);
+        params.set_token_timestamps(opts.segment_timestamps || opts.word_timestamps);
         params.set_print_progress(false);
         params.set_print_realtime(false);
         params.set_no_speech_thold(no_speech_thold);
@@ -595,6 +1087,10 @@ impl WhisperBackend {
             }
         }
 
+        if !keyword_bias.is_empty() {
+            Self::install_keyword_filter(&mut params, &keyword_bias);
+        }
+
         // VAD (opt-in): skip non-speech regions. The model path MUST be set
         // before enable_vad — whisper-rs panics otherwise. The caller guarantees
         // the file exists.
@@ -623,23 +1119,37 @@ impl WhisperBackend {
         // aborts at its next compute step, so this blocking call returns and
         // releases the warm-state mutex instead of running to completion and
         // wedging the pipeline. The flag's clear/discard lifecycle is handled by
-        // with_warm_state under the state lock — nothing to do here.
-        self.install_abort_callback(&mut params);
+        // with_warm_state under the state lock — nothing to do here. Jobs use
+        // their own flag so aborting one leaves the others running.
+        let abort_flag = job.map_or(&self.abort_flag, |j| &j.0);
+        Self::install_abort_callback(&mut params, abort_flag);
 
         info!(
-            "Starting local transcription: {} samples, {} threads, lang={:?}, beam={}, temp_fallback={}, vad={}",
+            "Starting local transcription: {} samples, {} threads, lang={:?}, beam={}, temp_fallback={}, no_speech={}, vad={}, keywords={}",
             audio.len(),
             n_threads,
             language,
-            opts.beam_size,
-            opts.temperature_fallback,
-            opts.vad_model_path.is_some()
+            beam_size,
+            decoding.temperature_fallback,
+            no_speech_thold,
+            opts.vad_model_path.is_some(),
+            opts.keywords.len()
         );
 
-        self.with_warm_state(|state| {
-            state.full(params, audio).map_err(|e| {
-                DictationError::TranscriptionFailed(format!("Whisper inference failed: {e}"))
-            })?;
+        let run = |state: &mut WhisperState| {
+            // An aborted full() still leaves the segments of every window it
+            // finished in the state, so they can be salvaged. The flag is only
+            // cleared by with_warm_state after this closure returns.
+            let mut aborted = false;
+            if let Err(e) = state.full(params, audio) {
+                if !(allow_partial && abort_flag.load(Ordering::SeqCst)) {
+                    return Err(DictationError::TranscriptionFailed(format!(
+                        "Whisper inference failed: {e}"
+                    )));
+                }
+                warn!("Inference aborted ({e}) — keeping segments decoded so far");
+                aborted = true;
+            }
 
             let n_segments = state.full_n_segments();
             let mut segments: Vec<TranscriptSegment> =
@@ -649,6 +1159,7 @@ impl WhisperBackend {
             for i in 0..n_segments {
                 if let Some(segment) = state.get_segment(i) {
                     let text = match segment.to_str() {
+                        Ok(t) if opts.localize_numbers => super::localize_numbers(t, language),
                         Ok(t) => t.to_string(),
                         Err(e) => {
                             warn!(
@@ -660,10 +1171,19 @@ impl WhisperBackend {
                     // avg_logprob over text tokens only (id < eot excludes
                     // timestamp/control tokens, which would skew the mean).
                     let mut plogs = Vec::with_capacity(segment.n_tokens().max(0) as usize);
+                    let mut tokens = Vec::new();
                     for j in 0..segment.n_tokens() {
                         if let Some(token) = segment.get_token(j) {
                             if token.token_id() < token_eot {
-                                plogs.push(token.token_data().plog);
+                                let data = token.token_data();
+                                plogs.push(data.plog);
+                                if opts.word_timestamps {
+                                    let bytes =
+                                        token.to_bytes().map(<[u8]>::to_vec).unwrap_or_default();
+                                    let t0 = data.t0 as f64 / 100.0;
+                                    let t1 = data.t1 as f64 / 100.0;
+                                    tokens.push((bytes, t0, t1));
+                                }
                             }
                         }
                     }
@@ -705,13 +1225,29 @@ impl WhisperBackend {
                         text,
                         avg_logprob: mean_logprob(&plogs),
                         no_speech_prob: segment.no_speech_probability(),
+                        words: words_from_tokens(tokens, start, end),
                     });
                 }
             }
 
+            if aborted {
+                if segments.is_empty() {
+                    return Err(DictationError::TranscriptionFailed(
+                        "Inference aborted before any segment was decoded".to_string(),
+                    ));
+                }
+                info!("Partial transcription salvaged: {} segment(s)", segments.len());
+                return Ok((segments, true));
+            }
+
             info!("Local transcription complete: {} segment(s)", segments.len());
-            Ok(segments)
-        })
+            Ok((segments, false))
+        };
+
+        match job {
+            None => self.with_warm_state(run),
+            Some(_) => self.with_job_state(run),
+        }
     }
 
     /// Transcribe audio and return per-segment timestamps.
@@ -823,7 +1359,7 @@ impl WhisperBackend {
         // Same real-abort wiring as the dictation path so a timed-out diarization
         // transcription can be aborted instead of pinning the warm-state mutex.
         // The flag's lifecycle is owned by with_warm_state under the state lock.
-        self.install_abort_callback(&mut params);
+        Self::install_abort_callback(&mut params, &self.abort_flag);
 
         self.with_warm_state(|state| {
             state.full(params, audio).map_err(|e| {
@@ -894,6 +1430,15 @@ impl WhisperBackend {
     }
 }
 
+/// Concatenate raw segment texts into the display transcript.
+pub fn join_segments(segments: &[TranscriptSegment], language: Language) -> String {
+    let mut transcript = String::new();
+    for seg in segments {
+        transcript.push_str(&seg.text);
+    }
+    super::normalize_nonspeech_markers(transcript.trim(), language)
+}
+
 /// Adapt whisper.cpp's native progress values to the backend's public
 /// percentage contract.
 fn clamped_progress_callback(
@@ -915,7 +1460,7 @@ enum Granularity {
 /// CPU work onto them hurts latency. Target the performance-core count
 /// (`hw.perflevel0.logicalcpu`) on macOS, falling back to `num_cpus / 2`
 /// elsewhere or if the sysctl is unavailable. Computed once and cached.
-fn whisper_threads() -> i32 {
+pub(crate) fn whisper_threads() -> i32 {
     use std::sync::OnceLock;
     static THREADS: OnceLock<i32> = OnceLock::new();
     *THREADS.get_or_init(|| {
@@ -1369,6 +1914,38 @@ mod segment_confidence_tests {
         assert!(!TranscribeOptions::default().segment_timestamps);
     }
 
+    #[test]
+    fn model_overrides_merge_over_defaults() {
+        let mut opts = TranscribeOptions {
+            beam_size: 2,
+            ..Default::default()
+        };
+        opts.model_overrides.insert(
+            "small.en".to_string(),
+            ModelOverrides {
+                no_speech_threshold: Some(0.45),
+                temperature_fallback: Some(false),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            opts.decoding_for(WhisperModel::SmallEn),
+            Decoding {
+                no_speech_threshold: 0.45,
+                beam_size: 2,
+                temperature_fallback: false,
+            }
+        );
+        assert_eq!(
+            opts.decoding_for(WhisperModel::BaseEn),
+            Decoding {
+                no_speech_threshold: WhisperModel::BaseEn.no_speech_threshold(),
+                beam_size: 2,
+                temperature_fallback: true,
+            }
+        );
+    }
+
     #[test]
     fn mean_logprob_averages() {
         let got = mean_logprob(&[-0.2, -0.4, -0.6]).unwrap();
@@ -1404,6 +1981,7 @@ mod segment_confidence_tests {
             text: " hello".to_string(),
             avg_logprob: Some(-0.42),
             no_speech_prob: 0.01,
+            words: Vec::new(),
         };
         let json = serde_json::to_value(&seg).unwrap();
         assert_eq!(json["start"], 1.25);
@@ -1421,10 +1999,114 @@ mod segment_confidence_tests {
             text: String::new(),
             avg_logprob: None,
             no_speech_prob: 0.9,
+            words: Vec::new(),
         };
         let json = serde_json::to_value(&seg).unwrap();
         assert!(json["avg_logprob"].is_null());
     }
+
+    #[test]
+    fn join_segments_concatenates_raw_text_and_trims() {
+        let seg = |text: &str| TranscriptSegment {
+            start: 0.0,
+            end: 0.0,
+            text: text.to_string(),
+            avg_logprob: None,
+            no_speech_prob: 0.0,
+            words: Vec::new(),
+        };
+        let joined = join_segments(&[seg(" Hello"), seg(" world. ")], Language::English);
+        assert_eq!(joined, "Hello world.");
+    }
+
+    #[test]
+    fn tokens_group_into_words_at_leading_spaces() {
+        let tokens: [(&[u8], f64, f64); 5] = [
+            (b" Hel", 0.5, 0.7),
+            (b"lo", 0.7, 0.9),
+            (b",", 0.9, 0.95),
+            (b" v\xc3", 1.2, 1.3),
+            (b"\xa4rlden", 1.3, 1.8),
+        ];
+        let words: Vec<_> = words_from_tokens(tokens, 0.0, 2.0)
+            .into_iter()
+            .map(|w| (w.text, w.start, w.end))
+            .collect();
+        assert_eq!(
+            words,
+            [("Hello,".into(), 0.5, 0.95), ("världen".into(), 1.2, 1.8)]
+        );
+    }
+
+    #[test]
+    fn word_times_stay_inside_the_segment() {
+        let tokens: [(&[u8], f64, f64); 2] = [(b" early", 0.1, 1.2), (b" late", 1.0, 9.0)];
+        let words = words_from_tokens(tokens, 1.0, 3.0);
+        assert_eq!((words[0].start, words[0].end), (1.0, 1.2));
+        assert_eq!((words[1].start, words[1].end), (1.2, 3.0));
+    }
+
+    #[test]
+    fn word_timestamps_are_opt_in_and_skipped_when_empty() {
+        assert!(!TranscribeOptions::default().word_timestamps);
+        let seg = TranscriptSegment {
+            start: 0.0,
+            end: 1.0,
+            text: " hi".to_string(),
+            avg_logprob: None,
+            no_speech_prob: 0.0,
+            words: Vec::new(),
+        };
+        assert!(serde_json::to_value(&seg).unwrap().get("words").is_none());
+    }
+
+    #[test]
+    fn transcription_serializes_partial_flag() {
+        let t = Transcript::from_text("first half".to_string(), true);
+        let json = serde_json::to_value(&t).unwrap();
+        assert_eq!(json["text"], "first half");
+        assert_eq!(json["partial"], true);
+    }
+
+    #[test]
+    fn transcript_joins_its_segments() {
+        let seg = |start: f64, text: &str| TranscriptSegment {
+            start,
+            end: start + 1.0,
+            text: text.to_string(),
+            avg_logprob: None,
+            no_speech_prob: 0.0,
+            words: Vec::new(),
+        };
+        let t = Transcript::from_segments(
+            vec![seg(0.0, " Hello"), seg(1.0, " world.")],
+            Language::English,
+            false,
+        );
+        assert_eq!(t.text, "Hello world.");
+        assert_eq!(t.segments[1].start, 1.0);
+        let json = serde_json::to_value(&t).unwrap();
+        assert_eq!(json["segments"][0]["text"], " Hello");
+        assert_eq!(t.into_text(), "Hello world.");
+    }
+
+    #[test]
+    fn silence_is_empty_text_or_all_non_speech_segments() {
+        let seg = |text: &str, no_speech_prob: f32| TranscriptSegment {
+            start: 0.0,
+            end: 1.0,
+            text: text.to_string(),
+            avg_logprob: None,
+            no_speech_prob,
+            words: Vec::new(),
+        };
+        let transcript = |segments| Transcript::from_segments(segments, Language::English, false);
+        assert!(Transcript::from_text("  ".to_string(), false).is_silent());
+        assert!(transcript(vec![seg(" Thank you.", 0.95)]).is_silent());
+        assert!(!transcript(vec![seg(" Thank you.", 0.95), seg(" Hi.", 0.1)]).is_silent());
+        assert!(!Transcript::from_text("Hello".to_string(), false).is_silent());
+        assert!(!Transcript::from_text(String::new(), true).is_silent());
+    }
 }
 
 /// Tests for the WP2b hardening: the bounded-wait `ModelBusy` guard and the real
@@ -1497,6 +2179,74 @@ mod warm_state_tests {
         assert!(res.is_ok(), "uncontended lock must be acquired immediately");
     }
 
+    /// Jobs run on their own state, so a held warm-state lock (a dictation in
+    /// progress) must not make them wait or report ModelBusy. Without a model
+    /// the job fails ModelNotLoaded straight away.
+    #[test]
+    fn job_does_not_wait_for_warm_state_lock() {
+        let backend = Arc::new(WhisperBackend::new());
+        let holder = hold_state_lock(&backend, Duration::from_millis(400));
+
+        let start = Instant::now();
+        let res = backend.transcribe_job(
+            &[0.0; 1600],
+            Language::English,
+            &TranscribeOptions::default(),
+            |_| {},
+            &JobAbort::new(),
+        );
+        assert!(
+            matches!(res, Err(DictationError::ModelNotLoaded)),
+            "expected ModelNotLoaded, got {res:?}"
+        );
+        assert!(start.elapsed() < Duration::from_millis(200));
+        holder.join().unwrap();
+    }
+
+    #[test]
+    fn job_abort_is_independent_of_backend_abort() {
+        let backend = WhisperBackend::new();
+        let job = JobAbort::new();
+        let shared = job.clone();
+        backend.request_abort();
+        assert!(!job.is_aborted());
+        shared.abort();
+        assert!(job.is_aborted());
+    }
+
+    /// Aborting one streamed file must stop only that file: the aborted job
+    /// ends Cancelled before its first chunk, while the other gets as far as
+    /// transcribing one (ModelNotLoaded, as there is no model here).
+    #[test]
+    fn streaming_abort_stops_only_its_own_job() {
+        let tmp = std::env::temp_dir().join(format!(
+            "sagascript-streaming-abort-{}.wav",
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::write(&tmp, crate::audio::wav::encode_wav(&[0.1; 16_000])).unwrap();
+        let backend = WhisperBackend::new();
+        let opts = TranscribeOptions::default();
+        let aborted = JobAbort::new();
+        let running = JobAbort::new();
+        aborted.abort();
+
+        let stopped =
+            backend.transcribe_file_streaming(&tmp, Language::English, &opts, |_| {}, &aborted);
+        let other =
+            backend.transcribe_file_streaming(&tmp, Language::English, &opts, |_| {}, &running);
+        let _ = std::fs::remove_file(&tmp);
+
+        assert!(
+            matches!(stopped, Err(DictationError::Cancelled)),
+            "{stopped:?}"
+        );
+        assert!(
+            matches!(other, Err(DictationError::ModelNotLoaded)),
+            "{other:?}"
+        );
+        assert!(!running.is_aborted());
+    }
+
     #[test]
     fn abort_trampoline_polarity_matches_ggml() {
         // ggml contract: the abort callback returns `true` to ABORT. Verify the
@@ -0,0 +1,148 @@
+//! Accumulate mode: successive hotkey dictations are collected in a buffer
+//! (shown in the overlay) and pasted together when the user flushes — with
+//! the flush hotkey or by ending a dictation with the flush phrase.
+
+use serde::Serialize;
+
+/// Buffer contents sent to the overlay and Settings window.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccumulatedSnapshot {
+    pub text: String,
+    pub count: usize,
+}
+
+/// Dictations collected since the last flush.
+#[derive(Debug, Default)]
+pub struct AccumulateBuffer {
+    parts: Vec<String>,
+}
+
+impl AccumulateBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append one dictation. Blank text is ignored.
+    pub fn push(&mut self, text: &str) {
+        let text = text.trim();
+        if !text.is_empty() {
+            self.parts.push(text.to_string());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// Number of dictations currently buffered.
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// The combined text, dictations separated by a space.
+    pub fn text(&self) -> String {
+        self.parts.join(" ")
+    }
+
+    pub fn snapshot(&self) -> AccumulatedSnapshot {
+        AccumulatedSnapshot {
+            text: self.text(),
+            count: self.len(),
+        }
+    }
+
+    /// Take the combined text and clear the buffer.
+    pub fn take(&mut self) -> String {
+        let text = self.text();
+        self.parts.clear();
+        text
+    }
+}
+
+/// If `text` ends with the flush phrase, return the text before it.
+///
+/// Matching ignores case and surrounding punctuation, since whisper tends to
+/// capitalise and punctuate a spoken command ("…and that's all. Paste that.").
+/// An empty phrase never matches.
+pub fn strip_flush_phrase(text: &str, phrase: &str) -> Option<String> {
+    let phrase = normalize(phrase);
+    if phrase.is_empty() {
+        return None;
+    }
+
+    let trimmed = text.trim_end_matches(|c: char| !c.is_alphanumeric());
+    // Compare word-by-word from the end so "send it" can't match "godsend it".
+    let words: Vec<&str> = trimmed.split_whitespace().collect();
+    let phrase_words: Vec<&str> = phrase.split(' ').collect();
+    if words.len() < phrase_words.len() {
+        return None;
+    }
+    let tail = &words[words.len() - phrase_words.len()..];
+    let matches = tail
+        .iter()
+        .zip(&phrase_words)
+        .all(|(w, p)| normalize(w) == *p);
+    if !matches {
+        return None;
+    }
+
+    // Cut at the start of the first phrase word in the original text.
+    let first = tail[0];
+    let cut = first.as_ptr() as usize - trimmed.as_ptr() as usize;
+    Some(trimmed[..cut].trim_end().to_string())
+}
+
+fn normalize(s: &str) -> String {
+    s.split_whitespace()
+        .map(|w| {
+            w.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffer_joins_and_clears() {
+        let mut buf = AccumulateBuffer::new();
+        buf.push(" First sentence. ");
+        buf.push("");
+        buf.push("Second one.");
+        assert_eq!(buf.len(), 2);
+        assert_eq!(buf.snapshot().count, 2);
+        assert_eq!(buf.text(), "First sentence. Second one.");
+        assert_eq!(buf.take(), "First sentence. Second one.");
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn flush_phrase_alone_leaves_nothing() {
+        assert_eq!(strip_flush_phrase("Paste that.", "paste that"), Some(String::new()));
+    }
+
+    #[test]
+    fn flush_phrase_at_end_keeps_preceding_text() {
+        assert_eq!(
+            strip_flush_phrase("And that's all. Paste that!", "paste that"),
+            Some("And that's all.".to_string())
+        );
+    }
+
+    #[test]
+    fn flush_phrase_must_be_whole_words_at_the_end() {
+        assert_eq!(strip_flush_phrase("Paste that into the doc.", "paste that"), None);
+        assert_eq!(strip_flush_phrase("A godsend it was", "send it"), None);
+        assert_eq!(strip_flush_phrase("It was a godsend it.", "send it"), None);
+    }
+
+    #[test]
+    fn empty_flush_phrase_never_matches() {
+        assert_eq!(strip_flush_phrase("Anything.", ""), None);
+        assert_eq!(strip_flush_phrase("", "paste that"), None);
+    }
+}
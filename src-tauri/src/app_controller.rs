@@ -1,15 +1,25 @@
+use std::borrow::Cow;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
 use serde::Serialize;
 use tracing::{info, warn};
 
+use sagascript_core::audio::fingerprint::Fingerprint;
 use sagascript_core::audio::AudioCaptureService;
+use sagascript_core::audio::level::{self, LevelWarning};
 use sagascript_core::error::DictationError;
+use sagascript_core::history::{self, HistoryEntry, HistorySource};
+use sagascript_core::replacements;
+use crate::accumulate::AccumulateBuffer;
 use crate::hotkey::HotkeyService;
 use crate::logging::LoggingService;
 use crate::logging::log_events;
 use crate::paste::PasteService;
-use sagascript_core::settings::{HotkeyMode, Settings};
+use sagascript_core::sessions::{self, SessionOutcome};
+use sagascript_core::settings::{CaptureSource, HotkeyMode, Profile, Settings, WhileBusy};
+use sagascript_core::telemetry::{self, TelemetryEvent};
+use sagascript_core::transcription::catalog;
 
 /// Result of handling a hotkey-down event
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -18,8 +28,17 @@ pub enum HotkeyDownResult {
     StartedRecording,
     /// Toggle mode: recording should be stopped (second press)
     StopRecording,
-    /// No action taken (e.g. already transcribing)
+    /// No action taken
     NoOp,
+    /// A transcription is running and `while_busy` is `reject`: the press
+    /// was ignored and the user should be told why.
+    Busy,
+    /// A transcription is running and `while_busy` is `queue`: recording
+    /// starts once it finishes.
+    Queued,
+    /// A transcription is running and `while_busy` is `cancel-and-restart`:
+    /// the caller should abort it; recording starts once it has unwound.
+    CancelAndRestart,
 }
 
 /// Outcome of a guarded stop-recording request (see
@@ -34,11 +53,18 @@ pub enum StopRecordingOutcome {
     /// the mic produced only silence).
     Stopped(Vec<f32>),
     /// The capture/resample failed. The controller has recorded the error and
-    /// returned to Idle; the message is returned so the caller can surface it
-    /// via the transcription-error event path.
-    Failed(String),
+    /// returned to Idle; the error is returned so the caller can surface it
+    /// (with its suggestion) via the transcription-error event path.
+    Failed(DictationError),
+    /// The audio repeats the previous dictation's (`dedupe_dictations`), so
+    /// it is not transcribed. Carries how long after the previous dictation
+    /// it stopped; the controller is back to Idle.
+    Duplicate(Duration),
 }
 
+/// A repeated recording is only skipped this soon after the previous one.
+const DEDUPE_WINDOW: Duration = Duration::from_secs(10);
+
 /// Application state machine
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "lowercase")]
@@ -71,9 +97,33 @@ pub struct AppController {
     logging: LoggingService,
     settings: Settings,
     recording_start: Option<Instant>,
+    /// Length of the last stopped recording, logged with the session outcome.
+    recorded: Option<Duration>,
+    /// Clipping or near-silence found in the last stopped recording.
+    level_warning: Option<LevelWarning>,
     last_transcription: Option<String>,
     last_error: Option<String>,
     model_ready: bool,
+    /// Dictations buffered in accumulate mode, pasted together on flush.
+    accumulated: AccumulateBuffer,
+    /// A hotkey press arrived during transcription and should start a new
+    /// recording once it ends (`while_busy` queue / cancel-and-restart).
+    queued_start: bool,
+    /// The running transcription was aborted by a cancel-and-restart press;
+    /// whatever it returns is discarded.
+    discard_transcription: bool,
+    /// Profile the current (or last) recording was started with, from its
+    /// hotkey; `None` for the main hotkeys and the GUI.
+    profile: Option<Profile>,
+    /// A transcript over `paste_confirm_chars` waiting for the user to
+    /// confirm or discard the auto-paste.
+    pending_paste: Option<String>,
+    /// Where finished dictations are recorded; `None` keeps no history
+    /// (as in tests).
+    history: Option<PathBuf>,
+    /// Fingerprint of the last stopped recording and when it stopped, to
+    /// spot the same audio coming through twice.
+    last_fingerprint: Option<(Instant, Fingerprint)>,
 }
 
 impl AppController {
@@ -96,12 +146,26 @@ impl AppController {
             logging,
             settings,
             recording_start: None,
+            recorded: None,
+            level_warning: None,
             last_transcription: None,
             last_error: None,
             model_ready: false,
+            accumulated: AccumulateBuffer::new(),
+            queued_start: false,
+            discard_transcription: false,
+            profile: None,
+            pending_paste: None,
+            history: None,
+            last_fingerprint: None,
         }
     }
 
+    /// Record finished dictations in the history at `path`.
+    pub fn set_history_path(&mut self, path: PathBuf) {
+        self.history = Some(path);
+    }
+
     pub fn state(&self) -> AppState {
         self.state
     }
@@ -122,6 +186,17 @@ impl AppController {
         self.last_transcription.as_deref()
     }
 
+    /// Input level problem in the last recording, reported next to its
+    /// transcription result.
+    pub fn level_warning(&self) -> Option<LevelWarning> {
+        self.level_warning
+    }
+
+    /// How long the last dictation recorded.
+    pub fn last_recording(&self) -> Option<Duration> {
+        self.recorded
+    }
+
     pub fn last_error(&self) -> Option<&str> {
         self.last_error.as_deref()
     }
@@ -136,15 +211,29 @@ impl AppController {
 
     /// Handle hotkey down event
     pub fn handle_hotkey_down(&mut self) -> Result<HotkeyDownResult, DictationError> {
-        info!("Hotkey DOWN");
+        self.handle_hotkey_down_as(self.settings.hotkey_mode)
+    }
+
+    /// Handle a hotkey down event with `mode` instead of `hotkey_mode`, for
+    /// `toggle_hotkey`, which toggles even when the main hotkey is
+    /// push-to-talk.
+    pub fn handle_hotkey_down_as(
+        &mut self,
+        mode: HotkeyMode,
+    ) -> Result<HotkeyDownResult, DictationError> {
+        info!("Hotkey DOWN ({})", mode.display_name());
+
+        if self.state == AppState::Transcribing {
+            return Ok(self.handle_busy_press());
+        }
 
-        match self.settings.hotkey_mode {
+        match mode {
             HotkeyMode::PushToTalk => {
                 // Only report StartedRecording if we actually started. Holding
                 // PTT while a prior utterance is still Transcribing must be a
                 // no-op — otherwise the overlay/tray shows a recording that
                 // never happened and never hides (finding 1).
-                if self.start_recording()? {
+                if self.start_recording_as(mode)? {
                     Ok(HotkeyDownResult::StartedRecording)
                 } else {
                     Ok(HotkeyDownResult::NoOp)
@@ -154,7 +243,7 @@ impl AppController {
                 if self.state.is_recording() {
                     Ok(HotkeyDownResult::StopRecording)
                 } else if self.state == AppState::Idle {
-                    self.start_recording()?;
+                    self.start_recording_as(mode)?;
                     Ok(HotkeyDownResult::StartedRecording)
                 } else {
                     Ok(HotkeyDownResult::NoOp)
@@ -163,6 +252,73 @@ impl AppController {
         }
     }
 
+    /// Handle a press of `profile`'s hotkey: like the main hotkey under
+    /// `hotkey_mode`, but a recording it starts is transcribed with the
+    /// profile's language and model.
+    pub fn handle_profile_hotkey_down(
+        &mut self,
+        profile: Profile,
+    ) -> Result<HotkeyDownResult, DictationError> {
+        let result = self.handle_hotkey_down_as(self.settings.hotkey_mode)?;
+        if result == HotkeyDownResult::StartedRecording {
+            info!("Recording with profile '{}'", profile.name);
+            self.profile = Some(profile);
+        }
+        Ok(result)
+    }
+
+    /// The profile the current (or last) recording was started with.
+    pub fn profile(&self) -> Option<&Profile> {
+        self.profile.as_ref()
+    }
+
+    /// Settings to transcribe the current (or last) recording with: the
+    /// stored ones, with its profile's language and model if it has one.
+    pub fn dictation_settings(&self) -> Cow<'_, Settings> {
+        match &self.profile {
+            Some(profile) => Cow::Owned(self.settings.with_profile(profile)),
+            None => Cow::Borrowed(&self.settings),
+        }
+    }
+
+    /// Apply the `while_busy` policy to a press that arrived mid-transcription.
+    fn handle_busy_press(&mut self) -> HotkeyDownResult {
+        match self.settings.while_busy {
+            WhileBusy::Reject => {
+                info!("Hotkey ignored: still transcribing");
+                HotkeyDownResult::Busy
+            }
+            WhileBusy::Queue => {
+                info!("Hotkey queued until transcription finishes");
+                self.queued_start = true;
+                HotkeyDownResult::Queued
+            }
+            WhileBusy::CancelAndRestart => {
+                info!("Hotkey cancels the running transcription");
+                self.queued_start = true;
+                self.discard_transcription = true;
+                HotkeyDownResult::CancelAndRestart
+            }
+        }
+    }
+
+    /// Drop a queued start, e.g. when push-to-talk is released before the
+    /// transcription it was waiting on has finished.
+    pub fn clear_queued_start(&mut self) {
+        self.queued_start = false;
+    }
+
+    /// Take the queued start request, if any, once transcription has ended.
+    pub fn take_queued_start(&mut self) -> bool {
+        std::mem::take(&mut self.queued_start)
+    }
+
+    /// Whether the running transcription was cancelled by a
+    /// cancel-and-restart press; its result should be discarded.
+    pub fn transcription_cancelled(&self) -> bool {
+        self.discard_transcription
+    }
+
     /// Handle hotkey up event
     pub fn should_stop_on_key_up(&self) -> bool {
         self.settings.hotkey_mode == HotkeyMode::PushToTalk && self.state.is_recording()
@@ -175,6 +331,14 @@ impl AppController {
     /// still transcribing). Callers use this to avoid reporting a recording that
     /// never happened (finding 1).
     pub fn start_recording(&mut self) -> Result<bool, DictationError> {
+        self.start_recording_as(HotkeyMode::PushToTalk)
+    }
+
+    /// Start a recording for a press in `mode`, as [`Self::start_recording`].
+    /// Only toggle-mode recordings stop on `silence_stop_secs`: push-to-talk
+    /// ends when the key comes up, and the GUI's record button is stopped by
+    /// hand.
+    pub fn start_recording_as(&mut self, mode: HotkeyMode) -> Result<bool, DictationError> {
         if self.state != AppState::Idle {
             warn!("Cannot start recording: state is {:?}", self.state);
             return Ok(false);
@@ -188,15 +352,50 @@ impl AppController {
             serde_json::json!({ "dictationSessionId": session_id }),
         );
 
+        self.configure_capture();
+        self.profile = None;
+        // Starting over means the held paste is no longer wanted.
+        self.pending_paste = None;
+        self.audio.set_silence_stop(match mode {
+            HotkeyMode::Toggle => self.settings.silence_stop(),
+            HotkeyMode::PushToTalk => None,
+        });
         self.audio.start_capture()?;
         self.state = AppState::Recording;
         self.recording_start = Some(Instant::now());
+        self.recorded = None;
+        self.level_warning = None;
         self.last_error = None;
+        // Whatever was cancelled for this recording has been dealt with.
+        self.discard_transcription = false;
 
         info!("Recording started");
         Ok(true)
     }
 
+    /// Point the capture service at what the settings ask for.
+    fn configure_capture(&mut self) {
+        // Monitoring system audio would play it back into itself.
+        let source = self.settings.capture_source;
+        self.audio.set_monitor(
+            (self.settings.monitor_input && source == CaptureSource::Microphone)
+                .then_some(self.settings.monitor_volume),
+        );
+        self.audio.set_source(source);
+        self.audio.set_max_recording(self.settings.max_recording());
+    }
+
+    /// Open or close the standby input stream to match `standby_stream`,
+    /// reopening it for capture settings that changed since. A recording
+    /// in progress keeps its stream until it stops.
+    pub fn refresh_standby(&mut self) -> Result<(), DictationError> {
+        if self.state.is_recording() {
+            return Ok(());
+        }
+        self.configure_capture();
+        self.audio.set_standby(self.settings.standby_stream)
+    }
+
     /// Stop recording and return the captured 16 kHz samples.
     ///
     /// Propagates a capture/resample failure (finding 4) instead of masking it
@@ -206,15 +405,17 @@ impl AppController {
     /// [`Self::stop_recording_guarded`]).
     pub fn stop_recording(&mut self) -> Result<Vec<f32>, DictationError> {
         let samples = self.audio.stop_capture()?;
-        let duration = self
-            .recording_start
-            .map(|s| s.elapsed().as_millis())
-            .unwrap_or(0);
+        self.recorded = self.recording_start.map(|s| s.elapsed());
+        let duration = self.recorded.map(|d| d.as_millis()).unwrap_or(0);
 
         info!(
             "Recording stopped: {} samples ({duration}ms)",
             samples.len()
         );
+        self.level_warning = level::analyze(&samples);
+        if let Some(warning) = self.level_warning {
+            warn!("{}", warning.message());
+        }
 
         self.state = AppState::Transcribing;
         Ok(samples)
@@ -230,30 +431,137 @@ impl AppController {
             return StopRecordingOutcome::NotRecording;
         }
         match self.stop_recording() {
-            Ok(samples) => StopRecordingOutcome::Stopped(samples),
+            Ok(samples) => match self.repeat_of_last(&samples) {
+                Some(gap) => {
+                    self.on_duplicate();
+                    StopRecordingOutcome::Duplicate(gap)
+                }
+                None => StopRecordingOutcome::Stopped(samples),
+            },
             Err(e) => {
                 warn!("Recording stop failed: {e}");
-                let msg = e.to_string();
                 // Records last_error and returns to Idle.
-                self.on_transcription_error(&msg);
-                StopRecordingOutcome::Failed(msg)
+                self.on_transcription_error(&e.to_string());
+                StopRecordingOutcome::Failed(e)
             }
         }
     }
 
-    /// Called after transcription succeeds
-    pub fn on_transcription_success(&mut self, text: &str) {
-        self.last_transcription = Some(text.to_string());
+    /// How long after the previous recording this one stopped, if its audio
+    /// repeats the previous one's and `dedupe_dictations` is on. Remembers
+    /// `samples` for the next check either way.
+    fn repeat_of_last(&mut self, samples: &[f32]) -> Option<Duration> {
+        let fingerprint = Fingerprint::of(samples);
+        let (stopped, previous) = self
+            .last_fingerprint
+            .replace((Instant::now(), fingerprint.clone()))?;
+        let gap = stopped.elapsed();
+        (self.settings.dedupe_dictations && gap < DEDUPE_WINDOW && fingerprint.matches(&previous))
+            .then_some(gap)
+    }
+
+    /// A recording was skipped as a repeat of the previous one.
+    fn on_duplicate(&mut self) {
         self.audio.clear_last_captured();
         self.state = AppState::Idle;
-        self.logging.end_dictation_session();
+        self.end_session(SessionOutcome::Duplicate, None, None);
+        info!("Dictation skipped: same audio as the previous one");
+    }
+
+    /// Called after transcription succeeds. Applies the user's replacement
+    /// rules and returns the final text, which is what gets pasted and routed.
+    pub fn on_transcription_success(&mut self, text: &str) -> String {
+        let text = replacements::apply(text, &self.settings.replacement_rules);
+        self.last_transcription = Some(text.clone());
+        self.record_history(&text);
+        self.audio.clear_last_captured();
+        self.state = AppState::Idle;
+        let outcome = if text.trim().is_empty() {
+            SessionOutcome::Empty
+        } else {
+            SessionOutcome::Completed
+        };
+        self.end_session(outcome, Some(&text), None);
+        text
+    }
+
+    /// Called when transcription heard no speech: nothing is pasted or
+    /// kept, and the session ends `Empty` rather than failed.
+    pub fn on_no_speech(&mut self) {
+        self.audio.clear_last_captured();
+        self.state = AppState::Idle;
+        self.end_session(SessionOutcome::Empty, None, None);
+        info!("No speech detected; nothing to paste");
+    }
+
+    /// Add a finished dictation to the history, if one is kept.
+    fn record_history(&self, text: &str) {
+        let Some(path) = &self.history else {
+            return;
+        };
+        if text.trim().is_empty() {
+            return;
+        }
+        let clip_secs = self.recorded.map_or(0.0, |d| d.as_secs_f32());
+        let settings = self.dictation_settings();
+        let entry = HistoryEntry::new(
+            HistorySource::Dictation,
+            clip_secs,
+            settings.effective_model_for_clip(clip_secs),
+            settings.language,
+            text,
+        )
+        .with_device(self.audio.device_name());
+        if let Err(e) = history::append(path, &entry) {
+            warn!("Failed to record the dictation in the history: {e}");
+        }
     }
 
     /// Called after transcription fails
     pub fn on_transcription_error(&mut self, error: &str) {
         self.last_error = Some(error.to_string());
         self.state = AppState::Idle;
+        self.end_session(SessionOutcome::Failed, None, Some(error));
+    }
+
+    /// Log how the current dictation session ended (read back by
+    /// `get_session_timeline` and `sagascript sessions`) and close it.
+    fn end_session(&self, outcome: SessionOutcome, text: Option<&str>, error: Option<&str>) {
+        let clip_secs = self.recorded.map_or(0.0, |d| d.as_secs_f32());
+        let model = self
+            .dictation_settings()
+            .effective_model_for_clip(clip_secs);
+        let level = match outcome {
+            SessionOutcome::Failed => "error",
+            _ => "info",
+        };
+        self.logging.log(
+            level,
+            "App",
+            log_events::session::DICTATION_COMPLETE,
+            serde_json::json!({
+                "outcome": outcome,
+                "recordingMs": self.recorded.map(|d| d.as_millis() as u64),
+                "durationMs": self.recording_start.map(|s| s.elapsed().as_millis() as u64),
+                "model": catalog::model_id(model),
+                "chars": text.map(|t| t.chars().count()),
+                "words": text.map(sessions::word_count),
+                "error": error,
+                "levelWarning": self.level_warning,
+            }),
+        );
         self.logging.end_dictation_session();
+        telemetry::record(TelemetryEvent::Dictation { model, outcome });
+    }
+
+    /// Called when a transcription was abandoned for a cancel-and-restart
+    /// press; its output is discarded rather than pasted or reported.
+    pub fn on_transcription_cancelled(&mut self) {
+        self.discard_transcription = false;
+        self.audio.clear_last_captured();
+        self.state = AppState::Idle;
+        self.end_session(SessionOutcome::Cancelled, None, None);
+        info!("Transcription cancelled for a new recording");
     }
 
     /// Complete a transcription attempt and restore the controller to Idle.
@@ -266,10 +574,7 @@ impl AppController {
         result: Result<String, String>,
     ) -> Result<String, String> {
         match result {
-            Ok(text) => {
-                self.on_transcription_success(&text);
-                Ok(text)
-            }
+            Ok(text) => Ok(self.on_transcription_success(&text)),
             Err(error) => {
                 self.on_transcription_error(&error);
                 Err(error)
@@ -280,7 +585,7 @@ impl AppController {
     /// Auto-paste text if enabled
     #[allow(dead_code)]
     pub fn auto_paste(&self, text: &str) -> Result<(), DictationError> {
-        if !self.settings.auto_paste {
+        if !self.settings.auto_pastes() {
             return Ok(());
         }
         self.paste.paste(text)
@@ -291,11 +596,18 @@ impl AppController {
         if self.state.is_recording() {
             let _ = self.audio.stop_capture();
             self.state = AppState::Idle;
-            self.logging.end_dictation_session();
+            self.recorded = self.recording_start.map(|s| s.elapsed());
+            self.end_session(SessionOutcome::Cancelled, None, None);
             info!("Recording cancelled");
         }
     }
 
+    /// Apply a new monitor volume, including to a recording in progress.
+    pub fn set_monitor_volume(&mut self, volume: f32) {
+        self.settings.monitor_volume = volume;
+        self.audio.set_monitor_volume(volume);
+    }
+
     /// How long we've been recording
     pub fn recording_elapsed(&self) -> Duration {
         self.recording_start
@@ -303,11 +615,91 @@ impl AppController {
             .unwrap_or(Duration::ZERO)
     }
 
+    /// The length limit the current recording started under; a later change
+    /// to `max_recording_minutes` applies from the next one.
+    pub fn recording_limit(&self) -> Duration {
+        self.audio.max_recording()
+    }
+
+    /// Whether the current recording has run into its length limit, so its
+    /// end was (or is about to be) cut off.
+    pub fn hit_recording_limit(&self) -> bool {
+        self.state.is_recording()
+            && (self.audio.is_full() || self.recording_elapsed() >= self.recording_limit())
+    }
+
+    /// Whether the current recording has gone quiet for `silence_stop_secs`
+    /// after speech and should be stopped.
+    pub fn silence_timed_out(&self) -> bool {
+        self.state.is_recording() && self.audio.silence_timed_out()
+    }
+
+    /// When the current recording started. Doubles as a session token so a
+    /// background task can tell whether the recording it was started for is
+    /// still the one in progress.
+    pub fn recording_started_at(&self) -> Option<Instant> {
+        if self.state.is_recording() {
+            self.recording_start
+        } else {
+            None
+        }
+    }
+
+    /// Take the next chunk of a recording that is still in progress; see
+    /// [`AudioCaptureService::chunk_since`].
+    pub fn recording_chunk_since(
+        &self,
+        from: usize,
+        min_secs: f32,
+        search_secs: f32,
+    ) -> Result<Option<(Vec<f32>, usize)>, DictationError> {
+        if !self.state.is_recording() {
+            return Ok(None);
+        }
+        self.audio.chunk_since(from, min_secs, search_secs)
+    }
+
+    /// Map a chunk offset onto the 16 kHz samples returned by the last stop.
+    pub fn recording_offset_at_16khz(&self, offset: usize) -> usize {
+        self.audio.offset_at_16khz(offset)
+    }
+
     #[allow(dead_code)]
     pub fn set_model_ready(&mut self, ready: bool) {
         self.model_ready = ready;
     }
 
+    /// Dictations buffered in accumulate mode.
+    pub fn accumulated(&self) -> &AccumulateBuffer {
+        &self.accumulated
+    }
+
+    /// Add a dictation to the accumulate-mode buffer.
+    pub fn accumulate(&mut self, text: &str) {
+        self.accumulated.push(text);
+    }
+
+    /// Take the combined accumulate-mode text, leaving the buffer empty.
+    pub fn take_accumulated(&mut self) -> String {
+        self.accumulated.take()
+    }
+
+    /// Hold `text` back from auto-paste until the user confirms it,
+    /// replacing anything already held.
+    pub fn hold_paste(&mut self, text: String) {
+        self.pending_paste = Some(text);
+    }
+
+    /// The long transcript waiting for paste confirmation, if any.
+    pub fn pending_paste(&self) -> Option<&str> {
+        self.pending_paste.as_deref()
+    }
+
+    /// Take the held transcript, to paste or to drop.
+    pub fn take_pending_paste(&mut self) -> Option<String> {
+        self.pending_paste.take()
+    }
+
     /// Update settings
     pub fn update_settings(&mut self, settings: Settings) {
         self.settings = settings;
@@ -375,6 +767,12 @@ mod tests {
         assert!(ctrl.last_error().is_none());
     }
 
+    #[test]
+    fn initial_no_level_warning() {
+        let ctrl = default_controller();
+        assert!(ctrl.level_warning().is_none());
+    }
+
     #[test]
     fn initial_language_from_settings() {
         let settings = Settings { language: sagascript_core::settings::Language::Swedish, ..Default::default() };
@@ -382,6 +780,36 @@ mod tests {
         assert_eq!(ctrl.language(), sagascript_core::settings::Language::Swedish);
     }
 
+    #[test]
+    fn dictation_settings_follow_the_recording_profile() {
+        use sagascript_core::settings::Language;
+
+        let mut ctrl = default_controller();
+        assert_eq!(ctrl.dictation_settings().language, Language::English);
+        ctrl.profile = Some(Profile {
+            name: "Svenska".into(),
+            language: Language::Swedish,
+            ..Default::default()
+        });
+        assert_eq!(ctrl.dictation_settings().language, Language::Swedish);
+        // The stored settings are untouched.
+        assert_eq!(ctrl.settings().language, Language::English);
+    }
+
+    #[test]
+    fn profile_press_while_transcribing_keeps_the_last_profile() {
+        let mut ctrl = default_controller();
+        ctrl.state = AppState::Transcribing;
+        let result = ctrl
+            .handle_profile_hotkey_down(Profile {
+                name: "Work".into(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(result, HotkeyDownResult::Busy);
+        assert!(ctrl.profile().is_none());
+    }
+
     // -- Settings --
 
     #[test]
@@ -400,9 +828,9 @@ mod tests {
     #[test]
     fn update_settings_replaces() {
         let mut ctrl = default_controller();
-        let new_settings = Settings { auto_paste: false, language: sagascript_core::settings::Language::Swedish, ..Default::default() };
+        let new_settings = Settings { output_sinks: Vec::new(), language: sagascript_core::settings::Language::Swedish, ..Default::default() };
         ctrl.update_settings(new_settings);
-        assert!(!ctrl.settings().auto_paste);
+        assert!(!ctrl.settings().auto_pastes());
         assert_eq!(ctrl.settings().language, sagascript_core::settings::Language::Swedish);
     }
 
@@ -429,6 +857,29 @@ mod tests {
         assert_eq!(ctrl.state(), AppState::Idle);
     }
 
+    #[test]
+    fn on_transcription_success_applies_replacement_rules() {
+        use sagascript_core::replacements::ReplacementRule;
+
+        let mut ctrl = default_controller();
+        ctrl.settings.replacement_rules =
+            vec![ReplacementRule::new("saga script", "Sagascript", false).unwrap()];
+        ctrl.state = AppState::Transcribing;
+        let text = ctrl.on_transcription_success("Open saga script.");
+        assert_eq!(text, "Open Sagascript.");
+        assert_eq!(ctrl.last_transcription(), Some("Open Sagascript."));
+    }
+
+    #[test]
+    fn no_speech_returns_to_idle_without_an_error() {
+        let mut ctrl = default_controller();
+        ctrl.state = AppState::Transcribing;
+        ctrl.on_no_speech();
+        assert_eq!(ctrl.state(), AppState::Idle);
+        assert_eq!(ctrl.last_transcription(), None);
+        assert_eq!(ctrl.last_error(), None);
+    }
+
     #[test]
     fn on_transcription_error_stores_error() {
         let mut ctrl = default_controller();
@@ -520,12 +971,21 @@ mod tests {
     }
 
     #[test]
-    fn toggle_mode_returns_noop_when_transcribing() {
+    fn toggle_hotkey_toggles_while_main_hotkey_is_push_to_talk() {
+        let mut ctrl = default_controller();
+        ctrl.settings_mut().hotkey_mode = HotkeyMode::PushToTalk;
+        ctrl.state = AppState::Recording;
+        let result = ctrl.handle_hotkey_down_as(HotkeyMode::Toggle).unwrap();
+        assert_eq!(result, HotkeyDownResult::StopRecording);
+    }
+
+    #[test]
+    fn toggle_mode_returns_busy_when_transcribing() {
         let mut ctrl = default_controller();
         ctrl.settings_mut().hotkey_mode = HotkeyMode::Toggle;
         ctrl.state = AppState::Transcribing;
         let result = ctrl.handle_hotkey_down().unwrap();
-        assert_eq!(result, HotkeyDownResult::NoOp);
+        assert_eq!(result, HotkeyDownResult::Busy);
     }
 
     // Finding 1: in push-to-talk mode a hotkey-down while a prior utterance is
@@ -533,12 +993,66 @@ mod tests {
     // refuses when state != Idle) — otherwise the overlay/tray shows a recording
     // that never happened and never hides.
     #[test]
-    fn push_to_talk_down_when_transcribing_is_noop() {
+    fn push_to_talk_down_when_transcribing_is_busy() {
         let mut ctrl = default_controller();
         ctrl.settings_mut().hotkey_mode = HotkeyMode::PushToTalk;
         ctrl.state = AppState::Transcribing;
         let result = ctrl.handle_hotkey_down().unwrap();
-        assert_eq!(result, HotkeyDownResult::NoOp);
+        assert_eq!(result, HotkeyDownResult::Busy);
+        assert_eq!(ctrl.state(), AppState::Transcribing);
+        assert!(!ctrl.take_queued_start());
+    }
+
+    #[test]
+    fn queue_policy_starts_after_transcription() {
+        let mut ctrl = default_controller();
+        ctrl.settings_mut().while_busy = WhileBusy::Queue;
+        ctrl.state = AppState::Transcribing;
+        let result = ctrl.handle_hotkey_down().unwrap();
+        assert_eq!(result, HotkeyDownResult::Queued);
+        // A queued press lets the running transcription finish normally.
+        assert!(!ctrl.transcription_cancelled());
+        assert!(ctrl.take_queued_start());
+        assert!(!ctrl.take_queued_start());
+    }
+
+    #[test]
+    fn cancel_and_restart_discards_running_transcription() {
+        let mut ctrl = default_controller();
+        ctrl.settings_mut().while_busy = WhileBusy::CancelAndRestart;
+        ctrl.state = AppState::Transcribing;
+        let result = ctrl.handle_hotkey_down().unwrap();
+        assert_eq!(result, HotkeyDownResult::CancelAndRestart);
+        assert!(ctrl.transcription_cancelled());
+        ctrl.on_transcription_cancelled();
+        assert!(!ctrl.transcription_cancelled());
+        assert_eq!(ctrl.state(), AppState::Idle);
+        assert!(ctrl.last_transcription().is_none());
+        assert!(ctrl.last_error().is_none());
+        assert!(ctrl.take_queued_start());
+    }
+
+    #[test]
+    fn cleared_queue_does_not_start() {
+        let mut ctrl = default_controller();
+        ctrl.settings_mut().while_busy = WhileBusy::Queue;
+        ctrl.state = AppState::Transcribing;
+        ctrl.handle_hotkey_down().unwrap();
+        ctrl.clear_queued_start();
+        assert!(!ctrl.take_queued_start());
+    }
+
+    // Releasing push-to-talk drops the restart, but the aborted run's
+    // output must still be thrown away.
+    #[test]
+    fn cleared_restart_still_discards_result() {
+        let mut ctrl = default_controller();
+        ctrl.settings_mut().while_busy = WhileBusy::CancelAndRestart;
+        ctrl.state = AppState::Transcribing;
+        ctrl.handle_hotkey_down().unwrap();
+        ctrl.clear_queued_start();
+        assert!(ctrl.transcription_cancelled());
+        assert!(!ctrl.take_queued_start());
     }
 
     // -- auto_paste --
@@ -546,7 +1060,7 @@ mod tests {
     #[test]
     fn auto_paste_disabled_is_noop() {
         let mut ctrl = default_controller();
-        ctrl.settings_mut().auto_paste = false;
+        ctrl.settings_mut().set_auto_paste(false);
         // Should return Ok without attempting paste
         assert!(ctrl.auto_paste("test").is_ok());
     }
@@ -577,6 +1091,37 @@ mod tests {
     // Finding 3: a stop that races an in-flight transcription (state !=
     // Recording) must be a no-op — it must not transition state nor set
     // last_error, so the running transcription is not clobbered.
+    #[test]
+    fn accumulate_then_take_clears_buffer() {
+        let mut ctrl = default_controller();
+        ctrl.accumulate("One.");
+        ctrl.accumulate("Two.");
+        assert_eq!(ctrl.accumulated().len(), 2);
+        assert_eq!(ctrl.take_accumulated(), "One. Two.");
+        assert!(ctrl.accumulated().is_empty());
+    }
+
+    #[test]
+    fn held_paste_is_taken_once_and_dropped_by_a_new_recording() {
+        let mut ctrl = default_controller();
+        ctrl.hold_paste("A long transcript.".into());
+        assert_eq!(ctrl.pending_paste(), Some("A long transcript."));
+        assert_eq!(ctrl.take_pending_paste().as_deref(), Some("A long transcript."));
+        assert_eq!(ctrl.take_pending_paste(), None);
+
+        ctrl.hold_paste("Another one.".into());
+        ctrl.state = AppState::Transcribing;
+        ctrl.start_recording().unwrap();
+        assert!(ctrl.pending_paste().is_some(), "a refused start keeps the paste");
+    }
+
+    #[test]
+    fn recording_chunk_when_not_recording_is_none() {
+        let ctrl = default_controller();
+        assert!(ctrl.recording_started_at().is_none());
+        assert!(ctrl.recording_chunk_since(0, 1.0, 0.5).unwrap().is_none());
+    }
+
     #[test]
     fn stop_recording_guarded_when_not_recording_is_noop() {
         let mut ctrl = default_controller();
@@ -600,4 +1145,33 @@ mod tests {
         }
         assert_eq!(ctrl.state(), AppState::Transcribing);
     }
+
+    /// Two seconds of a tone whose loudness rises and falls like syllables.
+    fn speech_like(syllables_per_sec: f32) -> Vec<f32> {
+        (0..32_000)
+            .map(|i| {
+                let t = i as f32 / 16_000.0;
+                let contour = (t * syllables_per_sec * std::f32::consts::PI).sin().abs();
+                contour * (t * 220.0 * std::f32::consts::TAU).sin()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn repeated_audio_is_flagged_as_duplicate() {
+        let mut ctrl = default_controller();
+        let audio = speech_like(3.0);
+        assert!(ctrl.repeat_of_last(&audio).is_none());
+        assert!(ctrl.repeat_of_last(&audio).is_some());
+        assert!(ctrl.repeat_of_last(&speech_like(7.0)).is_none());
+    }
+
+    #[test]
+    fn dedupe_can_be_turned_off() {
+        let mut ctrl = default_controller();
+        ctrl.settings_mut().dedupe_dictations = false;
+        let audio = speech_like(3.0);
+        assert!(ctrl.repeat_of_last(&audio).is_none());
+        assert!(ctrl.repeat_of_last(&audio).is_none());
+    }
 }
@@ -1,3 +1,4 @@
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
@@ -13,11 +14,35 @@ const TRANSCRIPTION_TIMEOUT_SECS: u64 = 60;
 /// compute step or two, so this rarely elapses.
 const ABORT_GRACE_SECS: u64 = 5;
 
+use crate::accumulate::AccumulatedSnapshot;
 use crate::app_controller::{AppController, AppState, StopRecordingOutcome};
 use crate::hotkey::{HotkeyHealth, HotkeyStatus, OperationalHotkey};
-use sagascript_core::audio::decoder;
-use sagascript_core::settings::{HotkeyMode, Language, Settings, WhisperModel};
-use sagascript_core::transcription::{model, FILE_TRANSCRIBE_BEAM, TranscribeOptions, WhisperBackend};
+use sagascript_core::audio::decoder::{self, STREAM_DECODE_AFTER_SECS};
+use sagascript_core::audio::ffmpeg;
+use sagascript_core::audio::level::LevelWarning;
+use sagascript_core::cli_install::{self, CliInstall};
+use sagascript_core::history::{self, HistoryEntry, HistorySource};
+use sagascript_core::output::{self, OutputSink};
+use sagascript_core::paste_format::AppCategory;
+use sagascript_core::progress::{Stage, StageProgress};
+use sagascript_core::recovery::{self, RecoverableSession};
+use sagascript_core::replacements::{self, ReplacementRule};
+use sagascript_core::audio::resample::TARGET_SAMPLE_RATE;
+use sagascript_core::settings::store::{self, LoadIssue};
+use sagascript_core::settings::{
+    format_tray_menu, BackendKind, CaptureSource, HotkeyMode, Language, Profile, Settings, SettingsTab, TrayMenuItem,
+    WhileBusy, WhisperModel,
+};
+use sagascript_core::telemetry::{self, DailyCounts, TelemetryEvent};
+use sagascript_core::transcription::catalog::{self, ModelFilter, ModelInfo};
+use sagascript_core::transcription::backend;
+use sagascript_core::minutes::{self, Turn};
+use sagascript_core::transcription::channels::{channel_transcript, merge_channels};
+use sagascript_core::transcription::recommend::{self, Hardware, Recommendation};
+use sagascript_core::transcription::{
+    join_segments, model, updates, AccelerationInfo, JobAbort, ModelSuggestion, RtfTracker,
+    RuntimeInfo, TranscribeOptions, WhisperBackend, FILE_TRANSCRIBE_BEAM,
+};
 
 /// Build the per-transcription options from the current settings. Resolves the
 /// VAD model path only when VAD is enabled and the model is present (otherwise
@@ -41,21 +66,31 @@ pub(crate) fn build_transcribe_options(settings: &Settings) -> TranscribeOptions
         } else {
             Some(prompt.to_string())
         },
+        keywords: settings.keywords.clone(),
         beam_size: settings.beam_size,
         temperature_fallback: settings.temperature_fallback,
         vad_model_path,
         segment_timestamps: false,
+        word_timestamps: false,
+        localize_numbers: settings.localize_numbers,
+        voice_commands: settings.voice_commands,
+        strip_punctuation: !settings.punctuate,
+        model_overrides: settings.model_overrides.clone(),
     }
 }
 
 /// Like [`build_transcribe_options`] but for file transcription: defaults to
 /// beam search for quality (unless the user explicitly set a beam width), and
 /// uses the file dialog's prompt when provided (otherwise the saved prompt).
+/// Voice commands and stripped punctuation are for dictation: a recording
+/// that says "period" keeps it, and a transcript keeps its punctuation.
 pub(crate) fn build_file_transcribe_options(
     settings: &Settings,
     prompt: Option<String>,
 ) -> TranscribeOptions {
     let mut opts = build_transcribe_options(settings);
+    opts.voice_commands = false;
+    opts.strip_punctuation = false;
     if opts.beam_size < 2 {
         opts.beam_size = FILE_TRANSCRIBE_BEAM;
     }
@@ -67,6 +102,24 @@ pub(crate) fn build_file_transcribe_options(
     opts
 }
 
+/// Model to use for a clip of `audio_len` samples (16 kHz). Adaptive mode can
+/// pick a model that was never downloaded; in that case keep the regular
+/// choice instead of failing the transcription on a missing file.
+pub(crate) fn model_for_clip(settings: &Settings, audio_len: usize) -> WhisperModel {
+    let regular = settings.effective_model();
+    let clip_secs = audio_len as f32 / TARGET_SAMPLE_RATE as f32;
+    let chosen = settings.effective_model_for_clip(clip_secs);
+    if chosen != regular && !model::is_model_downloaded(chosen) {
+        info!(
+            "Adaptive model {} not downloaded — using {}",
+            chosen.display_name(),
+            regular.display_name()
+        );
+        return regular;
+    }
+    chosen
+}
+
 /// Shared app state type — uses std::sync::Mutex (not tokio) because
 /// cpal::Stream is !Send and we need sync access from Tauri commands
 pub type SharedController = Mutex<AppController>;
@@ -75,6 +128,536 @@ pub type SharedController = Mutex<AppController>;
 /// the controller lock during blocking transcription
 pub type SharedWhisper = Arc<WhisperBackend>;
 
+/// Per-model real-time factor history for dictation, used to suggest a lighter
+/// model when the current one can't keep up
+pub type SharedRtfTracker = Mutex<RtfTracker>;
+
+/// A file handed to the app from outside (Finder Services, "Open With"), held
+/// until the main window is up to take it. Only the latest request is kept.
+pub type SharedRequestedFile = Mutex<Option<PathBuf>>;
+
+/// The speaker turns of the last diarized or channel-split file
+/// transcription, kept for `export_minutes`. Any other file transcription
+/// clears it.
+pub type SharedMinutes = Mutex<Option<MinutesSource>>;
+
+pub struct MinutesSource {
+    /// File name of the transcribed file, the minutes' title.
+    title: String,
+    duration: f64,
+    language: Language,
+    turns: Vec<Turn>,
+}
+
+/// Record how long one dictation took to transcribe and emit a
+/// `model-suggestion` event if the model has been consistently too slow.
+pub(crate) fn record_real_time_factor(
+    app: &tauri::AppHandle,
+    model: WhisperModel,
+    audio_len: usize,
+    elapsed: Duration,
+) {
+    use tauri::{Emitter, Manager};
+
+    let audio_secs = audio_len as f32 / TARGET_SAMPLE_RATE as f32;
+    let tracker: State<'_, SharedRtfTracker> = app.state();
+    let suggestion = tracker.lock().unwrap().record(model, audio_secs, elapsed);
+    if let Some(s) = suggestion {
+        info!(
+            "{} is running at {:.2}x real time; suggesting {}",
+            s.current.display_name(),
+            s.rtf,
+            s.suggested.display_name()
+        );
+        let _ = app.emit(crate::events::event::MODEL_SUGGESTION, &s);
+    }
+}
+
+/// The flush shortcut that should be registered for these settings, if any.
+pub(crate) fn flush_shortcut(settings: &Settings) -> Option<&str> {
+    let hotkey = settings.flush_hotkey.trim();
+    (settings.accumulate_mode && !hotkey.is_empty()).then_some(hotkey)
+}
+
+/// Move the flush shortcut registration from `old` to `new`.
+pub(crate) fn swap_flush_hotkey(
+    app: &tauri::AppHandle,
+    old: Option<&str>,
+    new: Option<&str>,
+) -> Result<(), String> {
+    swap_hotkey(app, "flush", old, new)
+}
+
+/// The always-toggle dictation shortcut, if one is set.
+pub(crate) fn toggle_shortcut(settings: &Settings) -> Option<&str> {
+    let hotkey = settings.toggle_hotkey.trim();
+    (!hotkey.is_empty()).then_some(hotkey)
+}
+
+/// Move the toggle shortcut registration from `old` to `new`.
+pub(crate) fn swap_toggle_hotkey(
+    app: &tauri::AppHandle,
+    old: Option<&str>,
+    new: Option<&str>,
+) -> Result<(), String> {
+    swap_hotkey(app, "toggle", old, new)
+}
+
+/// The shortcut that opens the main window, if one is set.
+pub(crate) fn settings_shortcut(settings: &Settings) -> Option<&str> {
+    let hotkey = settings.settings_hotkey.trim();
+    (!hotkey.is_empty()).then_some(hotkey)
+}
+
+/// Move the settings-window shortcut registration from `old` to `new`.
+pub(crate) fn swap_settings_hotkey(
+    app: &tauri::AppHandle,
+    old: Option<&str>,
+    new: Option<&str>,
+) -> Result<(), String> {
+    swap_hotkey(app, "settings", old, new)
+}
+
+/// The dictation profiles' shortcuts, in profile order.
+pub(crate) fn profile_shortcuts(settings: &Settings) -> Vec<&str> {
+    settings
+        .profiles
+        .iter()
+        .filter_map(|p| p.shortcut())
+        .collect()
+}
+
+/// Move the profile shortcut registrations from the `old` set to `new`.
+pub(crate) fn swap_profile_hotkeys(
+    app: &tauri::AppHandle,
+    old: &[&str],
+    new: &[&str],
+) -> Result<(), String> {
+    for hotkey in old.iter().filter(|h| !new.contains(h)) {
+        swap_hotkey(app, "profile", Some(hotkey), None)?;
+    }
+    for hotkey in new {
+        swap_hotkey(app, "profile", None, Some(hotkey))?;
+    }
+    Ok(())
+}
+
+/// Move a secondary shortcut's registration from `old` to `new`. Checks the
+/// current registration first so the settings watcher and a command racing
+/// on the same change don't double-register.
+fn swap_hotkey(
+    app: &tauri::AppHandle,
+    name: &str,
+    old: Option<&str>,
+    new: Option<&str>,
+) -> Result<(), String> {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    let shortcuts = app.global_shortcut();
+    if let Some(old) = old {
+        if new != Some(old) && shortcuts.is_registered(old) {
+            shortcuts
+                .unregister(old)
+                .map_err(|e| format!("Failed to unregister {name} hotkey '{old}': {e}"))?;
+            info!("Unregistered {name} hotkey: {old}");
+        }
+    }
+    if let Some(new) = new {
+        if !shortcuts.is_registered(new) {
+            shortcuts
+                .register(new)
+                .map_err(|e| format!("Failed to register {name} hotkey '{new}': {e}"))?;
+            info!("Registered {name} hotkey: {new}");
+        }
+    }
+    Ok(())
+}
+
+/// Surface a dictation failure, with its suggested fix, to the Settings
+/// window. The command still returns the error string for its own caller.
+pub(crate) fn emit_error(app: &tauri::AppHandle, error: &sagascript_core::error::DictationError) {
+    use tauri::Emitter;
+
+    let _ = app.emit(crate::events::event::ERROR, error);
+    telemetry::record(TelemetryEvent::Error { kind: error.kind() });
+}
+
+/// Report file-transcription progress for one stage (see
+/// [`crate::events::event::TRANSCRIPTION_PROGRESS`]).
+fn emit_stage(app: &tauri::AppHandle, stage: Stage, pct: i64) {
+    use tauri::Emitter;
+
+    let _ = app.emit(
+        crate::events::event::TRANSCRIPTION_PROGRESS,
+        StageProgress::new(stage, pct),
+    );
+}
+
+/// Tell the Settings window the last recording was clipping or nearly
+/// silent, so a poor transcript comes with a likely cause.
+pub(crate) fn emit_level_warning(app: &tauri::AppHandle, warning: Option<LevelWarning>) {
+    use tauri::Emitter;
+
+    if let Some(warning) = warning {
+        let _ = app.emit(
+            crate::events::event::INPUT_LEVEL_WARNING,
+            serde_json::json!({ "kind": warning, "message": warning.message() }),
+        );
+    }
+}
+
+/// Tell listeners a dictation was skipped as a repeat of the previous one,
+/// which stopped `since_last` earlier.
+pub(crate) fn emit_deduped(app: &tauri::AppHandle, since_last: Duration) {
+    use tauri::Emitter;
+
+    let _ = app.emit(
+        crate::events::event::DICTATION_DEDUPED,
+        serde_json::json!({ "sinceLastSecs": since_last.as_secs_f32() }),
+    );
+}
+
+/// Report the speaking pace of `text` over `recorded` of audio. `live` is
+/// set while a long dictation is still recording and `text` is only the
+/// part transcribed so far.
+pub(crate) fn emit_pace(app: &tauri::AppHandle, text: &str, recorded: Duration, live: bool) {
+    use tauri::Emitter;
+
+    let words = sagascript_core::sessions::word_count(text);
+    let wpm = sagascript_core::sessions::words_per_minute(words, recorded.as_millis() as u64);
+    let _ = app.emit(
+        crate::events::event::DICTATION_PACE,
+        serde_json::json!({ "words": words, "wpm": wpm, "live": live }),
+    );
+}
+
+/// Tell the overlay and Settings window what is in the accumulate buffer.
+pub(crate) fn emit_accumulated(app: &tauri::AppHandle) {
+    use tauri::{Emitter, Manager};
+
+    let snapshot = {
+        let controller: State<'_, SharedController> = app.state();
+        let ctrl = controller.lock().unwrap();
+        ctrl.accumulated().snapshot()
+    };
+    let _ = app.emit(crate::events::event::ACCUMULATED_CHANGED, &snapshot);
+}
+
+/// Delay before the clipboard sink writes, so it lands after PasteService
+/// restores the user's previous clipboard (~100 ms after the paste).
+const CLIPBOARD_SINK_DELAY: Duration = Duration::from_millis(250);
+
+/// Send a finished dictation to the configured output sinks, unless this is
+/// a `dry_run`; the paste sink is `auto_paste_or_hold`'s. File and webhook
+/// sinks run in the background; failures are logged and surfaced as `error`
+/// events without affecting the dictation itself.
+pub(crate) fn route_output(app: &tauri::AppHandle, text: &str) {
+    use tauri::{Emitter, Manager};
+
+    let (sinks, mut entry, dry_run) = {
+        let controller: State<'_, SharedController> = app.state();
+        let ctrl = controller.lock().unwrap();
+        let settings = ctrl.dictation_settings();
+        (
+            settings.output_sinks.clone(),
+            output::Entry::new(text, settings.language, settings.effective_model()),
+            settings.dry_run,
+        )
+    };
+    if sinks.is_empty() || text.trim().is_empty() || dry_run {
+        return;
+    }
+    if sinks.iter().any(OutputSink::uses_app) {
+        entry.app = frontmost_app_name();
+    }
+
+    if output::wants_clipboard(&sinks) {
+        let text = text.to_string();
+        std::thread::spawn(move || {
+            std::thread::sleep(CLIPBOARD_SINK_DELAY);
+            match arboard::Clipboard::new().and_then(|mut cb| cb.set_text(text)) {
+                Ok(()) => info!("Output delivered to clipboard"),
+                Err(e) => error!("Output to clipboard failed: {e}"),
+            }
+        });
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        for (sink, e) in output::deliver(&sinks, &entry).await {
+            let msg = format!("Output to {} failed: {e}", sink.label());
+            error!("{msg}");
+            let _ = app.emit(crate::events::event::ERROR, msg);
+        }
+    });
+}
+
+/// Name of the app that has focus, for the `{app}` sink template variable.
+fn frontmost_app_name() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    use crate::platform::linux::frontmost_app;
+    #[cfg(target_os = "macos")]
+    use crate::platform::macos::frontmost_app;
+    #[cfg(target_os = "windows")]
+    use crate::platform::windows::frontmost_app;
+
+    let app = frontmost_app()?;
+    Some(if app.name.is_empty() { app.id } else { app.name })
+}
+
+/// Publish an app state change (`recording`, `transcribing`, `idle`, ...)
+/// to the MQTT sinks in the background. Failures are only logged, so a
+/// broker that is down doesn't raise an error on every hotkey press.
+pub(crate) fn publish_state(app: &tauri::AppHandle, state: &str) {
+    use tauri::Manager;
+
+    let sinks = {
+        let controller: State<'_, SharedController> = app.state();
+        let ctrl = controller.lock().unwrap();
+        ctrl.settings().output_sinks.clone()
+    };
+    if !sinks.iter().any(|sink| matches!(sink, OutputSink::Mqtt { .. })) {
+        return;
+    }
+    let state = state.to_string();
+    tauri::async_runtime::spawn(async move {
+        for (sink, e) in output::publish_state(&sinks, &state).await {
+            warn!("State to {} failed: {e}", sink.label());
+        }
+    });
+}
+
+/// Paste everything buffered in accumulate mode as one piece of text and
+/// clear the buffer. Returns the flushed text, or `None` if it was empty.
+/// Text over `paste_confirm_chars` is held for confirmation instead.
+pub(crate) fn flush_accumulated_text(app: &tauri::AppHandle) -> Option<String> {
+    use tauri::{Emitter, Manager};
+
+    let (text, paste_svc, recording, held) = {
+        let controller: State<'_, SharedController> = app.state();
+        let mut ctrl = controller.lock().unwrap();
+        if ctrl.accumulated().is_empty() {
+            return None;
+        }
+        let text = ctrl.take_accumulated();
+        let pastes = ctrl.dictation_settings().auto_pastes();
+        let held = pastes && ctrl.settings().paste_needs_confirmation(&text);
+        if held {
+            ctrl.hold_paste(text.clone());
+        }
+        let paste_svc = (pastes && !held)
+            .then(|| crate::paste::PasteService::for_settings(ctrl.settings()));
+        (text, paste_svc, ctrl.state().is_recording(), held)
+    };
+    info!("Flushing accumulated dictation: {} chars", text.len());
+
+    // Paste and overlay changes must run on the main thread (enigo/TIS, NSWindow).
+    let text_for_paste = text.clone();
+    if let Err(e) = app.run_on_main_thread({
+        let app = app.clone();
+        move || {
+            if !recording && !held {
+                crate::overlay::hide(&app);
+            }
+            if let Some(paste_svc) = paste_svc {
+                if let Err(e) = paste_svc.paste(&text_for_paste) {
+                    error!("Auto-paste failed: {e}");
+                }
+            }
+        }
+    }) {
+        error!("Failed to dispatch flush to main thread: {e}");
+    }
+    if held {
+        request_paste_confirmation(app, &text);
+    }
+
+    route_output(app, &text);
+    emit_accumulated(app);
+    let _ = app.emit(crate::events::event::ACCUMULATED_FLUSHED, &text);
+    Some(text)
+}
+
+/// Auto-paste a finished transcript when the dictation's output sinks
+/// include `paste`, unless this is a `dry_run`. One over
+/// `paste_confirm_chars` is held for confirmation instead of pasted.
+pub(crate) fn auto_paste_or_hold(app: &tauri::AppHandle, text: &str) {
+    use tauri::Manager;
+
+    let paste_svc = {
+        let controller: State<'_, SharedController> = app.state();
+        let mut ctrl = controller.lock().unwrap();
+        if !ctrl.dictation_settings().auto_pastes() {
+            if ctrl.settings().dry_run {
+                info!("Dry run: not pasting {} chars", text.chars().count());
+            }
+            return;
+        }
+        if ctrl.settings().paste_needs_confirmation(text) {
+            ctrl.hold_paste(text.to_string());
+            None
+        } else {
+            Some(crate::paste::PasteService::for_settings(ctrl.settings()))
+        }
+    };
+
+    let Some(paste_svc) = paste_svc else {
+        request_paste_confirmation(app, text);
+        return;
+    };
+    // Auto-paste MUST run on the main thread — enigo's macOS TIS APIs
+    // crash (SIGABRT) if called from a tokio worker thread.
+    let text_for_paste = text.to_string();
+    if let Err(e) = app.run_on_main_thread(move || {
+        info!("Running auto-paste on main thread...");
+        match paste_svc.paste(&text_for_paste) {
+            Ok(()) => info!("Auto-paste completed successfully"),
+            Err(e) => error!("Auto-paste failed: {e}"),
+        }
+    }) {
+        error!("Failed to dispatch paste to main thread: {e}");
+    }
+}
+
+/// Characters of a held transcript shown in the confirmation prompt.
+const PASTE_PREVIEW_CHARS: usize = 80;
+
+/// How long to wait after hiding the app before pasting, so focus is back
+/// in the app the user was dictating into rather than on the overlay.
+const PASTE_REFOCUS_DELAY: Duration = Duration::from_millis(150);
+
+/// Payload of `paste-confirmation` for a held transcript.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub(crate) struct PasteConfirmation {
+    pub chars: usize,
+    pub preview: String,
+}
+
+impl PasteConfirmation {
+    pub(crate) fn for_text(text: &str) -> Self {
+        let chars = text.chars().count();
+        let mut preview: String = text.chars().take(PASTE_PREVIEW_CHARS).collect();
+        if chars > PASTE_PREVIEW_CHARS {
+            preview.push('…');
+        }
+        Self { chars, preview }
+    }
+}
+
+/// Ask the user to confirm a transcript held back from auto-paste: the
+/// overlay shows it with Paste and Discard buttons, and so does the main
+/// window. The transcript must already be held in the controller.
+pub(crate) fn request_paste_confirmation(app: &tauri::AppHandle, text: &str) {
+    use tauri::Emitter;
+
+    info!("Holding {} chars for paste confirmation", text.chars().count());
+    let _ = app.emit(
+        crate::events::event::PASTE_CONFIRMATION,
+        PasteConfirmation::for_text(text),
+    );
+    if let Err(e) = app.run_on_main_thread({
+        let app = app.clone();
+        move || crate::overlay::show_paste_confirmation(&app)
+    }) {
+        error!("Failed to dispatch paste confirmation to main thread: {e}");
+    }
+}
+
+/// Take the held transcript and put the overlay back the way it was.
+fn resolve_pending_paste(
+    app: &tauri::AppHandle,
+    controller: &State<'_, SharedController>,
+) -> Option<(String, crate::paste::PasteService)> {
+    use tauri::Emitter;
+
+    let (text, paste_svc, recording) = {
+        let mut ctrl = controller.lock().unwrap();
+        let text = ctrl.take_pending_paste()?;
+        let paste_svc = crate::paste::PasteService::for_settings(ctrl.settings());
+        (text, paste_svc, ctrl.state().is_recording())
+    };
+    let _ = app.emit(crate::events::event::PASTE_CONFIRMATION, ());
+    if !recording {
+        dispatch_overlay_hide(app);
+    }
+    Some((text, paste_svc))
+}
+
+fn dispatch_overlay_hide(app: &tauri::AppHandle) {
+    if let Err(e) = app.run_on_main_thread({
+        let app = app.clone();
+        move || crate::overlay::hide(&app)
+    }) {
+        error!("Failed to dispatch overlay hide to main thread: {e}");
+    }
+}
+
+#[cfg(test)]
+mod paste_confirmation_tests {
+    use super::{PasteConfirmation, PASTE_PREVIEW_CHARS};
+
+    #[test]
+    fn preview_is_cut_on_characters_and_marked() {
+        let text = "å".repeat(PASTE_PREVIEW_CHARS + 5);
+        let confirmation = PasteConfirmation::for_text(&text);
+        assert_eq!(confirmation.chars, PASTE_PREVIEW_CHARS + 5);
+        assert_eq!(confirmation.preview.chars().count(), PASTE_PREVIEW_CHARS + 1);
+        assert!(confirmation.preview.ends_with('…'));
+        assert_eq!(PasteConfirmation::for_text("Short.").preview, "Short.");
+    }
+}
+
+/// The transcript held for paste confirmation, for a window that opened
+/// after `paste-confirmation` was sent.
+#[tauri::command]
+pub async fn get_pending_paste(
+    controller: State<'_, SharedController>,
+) -> Result<Option<PasteConfirmation>, String> {
+    let ctrl = controller.lock().unwrap();
+    Ok(ctrl.pending_paste().map(PasteConfirmation::for_text))
+}
+
+/// Paste the transcript held for confirmation. Returns false if nothing
+/// was held (already pasted, discarded, or replaced by a new recording).
+#[tauri::command]
+pub async fn confirm_paste(
+    app: tauri::AppHandle,
+    controller: State<'_, SharedController>,
+) -> Result<bool, String> {
+    let Some((text, paste_svc)) = resolve_pending_paste(&app, &controller) else {
+        return Ok(false);
+    };
+    info!("Paste confirmed: {} chars", text.chars().count());
+
+    // Clicking the overlay brings Sagascript forward; hand focus back to the
+    // app the user dictated into before pasting there.
+    #[cfg(target_os = "macos")]
+    let _ = app.hide();
+    tokio::time::sleep(PASTE_REFOCUS_DELAY).await;
+
+    // Auto-paste MUST run on the main thread (enigo/TIS).
+    app.run_on_main_thread(move || {
+        if let Err(e) = paste_svc.paste(&text) {
+            error!("Auto-paste failed: {e}");
+        }
+    })
+    .map_err(|e| format!("Failed to dispatch paste to main thread: {e}"))?;
+    Ok(true)
+}
+
+/// Drop the transcript held for confirmation without pasting it. It stays
+/// in the history and as the last transcription.
+#[tauri::command]
+pub async fn discard_paste(
+    app: tauri::AppHandle,
+    controller: State<'_, SharedController>,
+) -> Result<(), String> {
+    if let Some((text, _)) = resolve_pending_paste(&app, &controller) {
+        info!("Held paste discarded ({} chars)", text.chars().count());
+    }
+    Ok(())
+}
+
 // -- State queries --
 
 #[tauri::command]
@@ -89,6 +672,13 @@ pub async fn get_settings(controller: State<'_, SharedController>) -> Result<Set
     Ok(ctrl.settings().clone())
 }
 
+/// Why the settings file couldn't be loaded (so defaults are in use), for a
+/// window that opened after `settings-load-warning` was sent.
+#[tauri::command]
+pub async fn get_settings_load_warning() -> Result<Option<LoadIssue>, String> {
+    Ok(store::validate(&store::settings_path()).err())
+}
+
 #[tauri::command]
 pub async fn get_last_transcription(
     controller: State<'_, SharedController>,
@@ -190,54 +780,659 @@ pub async fn set_auto_select_model(
     Ok(())
 }
 
+/// The outstanding "switch to a lighter model" suggestion, if dictation has
+/// been consistently slower than real time on the current model.
 #[tauri::command]
-pub async fn set_hotkey_mode(
+pub async fn get_model_suggestion(
+    tracker: State<'_, SharedRtfTracker>,
+) -> Result<Option<ModelSuggestion>, String> {
+    Ok(tracker.lock().unwrap().pending())
+}
+
+/// One-click apply of the pending suggestion: pins the suggested model (which
+/// turns off auto-select, same as picking it by hand) and returns it.
+#[tauri::command]
+pub async fn apply_model_suggestion(
     controller: State<'_, SharedController>,
-    mode: HotkeyMode,
-) -> Result<(), String> {
+    tracker: State<'_, SharedRtfTracker>,
+) -> Result<WhisperModel, String> {
+    let suggestion = tracker
+        .lock()
+        .unwrap()
+        .take_pending()
+        .ok_or_else(|| "No model suggestion pending".to_string())?;
     let persisted = sagascript_core::settings::store::update(|settings| {
-        settings.hotkey_mode = mode;
+        settings.whisper_model = suggestion.suggested;
+        settings.auto_select_model = false;
     })?;
     let mut ctrl = controller.lock().unwrap();
-    ctrl.settings_mut().hotkey_mode = persisted.hotkey_mode;
-    info!("Hotkey mode set to {:?}", mode);
+    ctrl.settings_mut().whisper_model = persisted.whisper_model;
+    ctrl.settings_mut().auto_select_model = persisted.auto_select_model;
+    info!(
+        "Applied model suggestion: {:?} -> {:?}",
+        suggestion.current, suggestion.suggested
+    );
+    Ok(suggestion.suggested)
+}
+
+#[tauri::command]
+pub async fn dismiss_model_suggestion(tracker: State<'_, SharedRtfTracker>) -> Result<(), String> {
+    tracker.lock().unwrap().take_pending();
     Ok(())
 }
 
+/// Newer upstream model revisions found by the last update check.
 #[tauri::command]
-pub async fn set_hotkey(
-    app: tauri::AppHandle,
-    controller: State<'_, SharedController>,
-    health: State<'_, HotkeyHealth>,
-    shortcut: String,
-) -> Result<(), String> {
-    use tauri::Emitter;
-    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+pub async fn get_model_updates() -> Result<Vec<updates::ModelUpdate>, String> {
+    Ok(updates::pending_updates())
+}
 
-    let _transition = health.transition_guard();
-    let old_shortcut = {
-        let ctrl = controller.lock().unwrap();
-        ctrl.settings().hotkey.clone()
-    };
+/// Default number of sessions returned by `get_session_timeline`.
+const SESSION_TIMELINE_LIMIT: usize = 50;
 
-    let old_operational = health.operational_hotkey();
+/// Recent dictation sessions, newest first, rebuilt from the structured log
+/// so the settings UI can show why a particular dictation failed.
+#[tauri::command]
+pub async fn get_session_timeline(
+    limit: Option<usize>,
+) -> Result<Vec<sagascript_core::sessions::SessionRecord>, String> {
+    let limit = limit.unwrap_or(SESSION_TIMELINE_LIMIT);
+    let dir = sagascript_core::sessions::log_directory();
+    tokio::task::spawn_blocking(move || sagascript_core::sessions::timeline(&dir, limit))
+        .await
+        .map_err(|e| e.to_string())
+}
 
-    // The saved shortcut can differ from the operational fallback after an
-    // external hot-reload failure. Always unregister what is actually active.
-    if old_operational == OperationalHotkey::Unknown {
-        return Err(
-            "Hotkey registration state is unknown after an earlier OS error; restart Sagascript before changing the shortcut"
-                .to_string(),
-        );
+/// Every recorded transcription, newest first.
+#[tauri::command]
+pub async fn get_history() -> Result<Vec<HistoryEntry>, String> {
+    tokio::task::spawn_blocking(|| history::load(&history::history_path()))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Remove one transcription from the history.
+#[tauri::command]
+pub async fn delete_history_entry(id: String) -> Result<(), String> {
+    match history::delete(&history::history_path(), &id) {
+        Ok(true) => {
+            info!("History entry {id} deleted");
+            Ok(())
+        }
+        Ok(false) => Err(format!("No history entry {id}")),
+        Err(e) => Err(format!("Failed to delete history entry {id}: {e}")),
     }
-    if let OperationalHotkey::Registered(old_operational) = &old_operational {
-        if let Err(error) = app.global_shortcut().unregister(old_operational.as_str()) {
-            error!("Failed to unregister operational hotkey '{old_operational}': {error}");
-            let change = health.record(
-                &old_shortcut,
-                Some(format!(
-                    "failed to unregister active hotkey '{old_operational}': {error}; operational state is unknown"
-                )),
+}
+
+/// Remove every transcription from the history.
+#[tauri::command]
+pub async fn clear_history() -> Result<(), String> {
+    history::clear(&history::history_path())
+        .map_err(|e| format!("Failed to clear the history: {e}"))?;
+    info!("History cleared");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_model_update_check(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.model_update_check = enabled;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().model_update_check = persisted.model_update_check;
+    info!("Model update check: {enabled}");
+    Ok(())
+}
+
+/// Input monitoring applies from the next recording; the volume also
+/// changes a recording already in progress.
+#[tauri::command]
+pub async fn set_monitor_input(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+    volume: f32,
+) -> Result<(), String> {
+    if !(0.0..=sagascript_core::settings::MAX_MONITOR_VOLUME).contains(&volume) {
+        return Err(format!("Monitor volume out of range: {volume}"));
+    }
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.monitor_input = enabled;
+        settings.monitor_volume = volume;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().monitor_input = persisted.monitor_input;
+    ctrl.set_monitor_volume(persisted.monitor_volume);
+    refresh_standby(&mut ctrl);
+    info!("Input monitoring: {enabled} (volume {volume})");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_max_recording_minutes(
+    controller: State<'_, SharedController>,
+    minutes: u32,
+) -> Result<(), String> {
+    let max = sagascript_core::settings::MAX_RECORDING_MINUTES;
+    if !(1..=max).contains(&minutes) {
+        return Err(format!(
+            "Recording limit must be 1 to {max} minutes, got {minutes}"
+        ));
+    }
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.max_recording_minutes = minutes;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().max_recording_minutes = persisted.max_recording_minutes;
+    refresh_standby(&mut ctrl);
+    info!("Recording limit: {minutes} min");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_silence_stop_secs(
+    controller: State<'_, SharedController>,
+    secs: u32,
+) -> Result<(), String> {
+    let max = sagascript_core::settings::MAX_SILENCE_STOP_SECS;
+    if secs > max {
+        return Err(format!(
+            "Silence stop must be 0 (off) to {max} seconds, got {secs}"
+        ));
+    }
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.silence_stop_secs = secs;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().silence_stop_secs = persisted.silence_stop_secs;
+    info!("Silence stop: {secs}s");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_paste_confirm_chars(
+    controller: State<'_, SharedController>,
+    chars: u32,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.paste_confirm_chars = chars;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().paste_confirm_chars = persisted.paste_confirm_chars;
+    info!("Paste confirmation above {chars} chars");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_capture_source(app: tauri::AppHandle, source: CaptureSource) -> Result<(), String> {
+    apply_capture_source(&app, source)
+}
+
+/// Persist `source` for the next recording and bring the tray's "Record
+/// System Audio" item and an open settings window in line, whichever of
+/// the two made the change.
+pub(crate) fn apply_capture_source(
+    app: &tauri::AppHandle,
+    source: CaptureSource,
+) -> Result<(), String> {
+    use tauri::{Emitter, Manager};
+
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.capture_source = source;
+    })?;
+    let controller: State<'_, SharedController> = app.state();
+    {
+        let mut ctrl = controller.lock().unwrap();
+        ctrl.settings_mut().capture_source = persisted.capture_source;
+        refresh_standby(&mut ctrl);
+    }
+    info!("Capture source: {}", source.display_name());
+    let _ = app.emit(crate::events::event::CAPTURE_SOURCE_CHANGED, source);
+    apply_tray_settings(app, persisted);
+    Ok(())
+}
+
+/// The standby stream opens (or closes) right away rather than at the
+/// next recording.
+#[tauri::command]
+pub async fn set_standby_stream(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.standby_stream = enabled;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().standby_stream = persisted.standby_stream;
+    ctrl.refresh_standby().map_err(|e| e.to_string())?;
+    info!("Standby input stream: {enabled}");
+    Ok(())
+}
+
+/// Reopen the standby stream for changed capture settings. A device that
+/// won't open now is reported again when recording starts.
+fn refresh_standby(ctrl: &mut AppController) {
+    if let Err(e) = ctrl.refresh_standby() {
+        warn!("Standby input stream unavailable: {e}");
+    }
+}
+
+#[tauri::command]
+pub async fn set_ffmpeg_fallback(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.ffmpeg_fallback = enabled;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().ffmpeg_fallback = persisted.ffmpeg_fallback;
+    info!("ffmpeg decode fallback: {enabled}");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_split_channels(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.split_channels = enabled;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().split_channels = persisted.split_channels;
+    info!("Split channels: {enabled}");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_localize_numbers(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.localize_numbers = enabled;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().localize_numbers = persisted.localize_numbers;
+    info!("Localize numbers: {enabled}");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_voice_commands(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.voice_commands = enabled;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().voice_commands = persisted.voice_commands;
+    info!("Voice commands: {enabled}");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_punctuate(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.punctuate = enabled;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().punctuate = persisted.punctuate;
+    info!("Punctuate: {enabled}");
+    Ok(())
+}
+
+/// Add a replacement rule at the end of the list. Same as
+/// `sagascript config rules add`.
+#[tauri::command]
+pub async fn add_replacement_rule(
+    controller: State<'_, SharedController>,
+    find: String,
+    replace: String,
+    regex: bool,
+) -> Result<(), String> {
+    let rule = ReplacementRule::new(&find, &replace, regex)?;
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.replacement_rules.push(rule.clone());
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().replacement_rules = persisted.replacement_rules;
+    info!("Replacement rule added ({})", rule.kind());
+    Ok(())
+}
+
+/// Remove the replacement rule at `index` (0-based). Same as
+/// `sagascript config rules remove`, which counts from 1.
+#[tauri::command]
+pub async fn remove_replacement_rule(
+    controller: State<'_, SharedController>,
+    index: usize,
+) -> Result<(), String> {
+    let mut removed = false;
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        if index < settings.replacement_rules.len() {
+            settings.replacement_rules.remove(index);
+            removed = true;
+        }
+    })?;
+    if !removed {
+        return Err(format!("No replacement rule {}", index + 1));
+    }
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().replacement_rules = persisted.replacement_rules;
+    info!("Replacement rule {} removed", index + 1);
+    Ok(())
+}
+
+/// Paste a tiny-model draft before the selected model's result.
+#[tauri::command]
+pub async fn set_draft_pass(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.draft_pass = enabled;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().draft_pass = persisted.draft_pass;
+    info!("Draft pass: {enabled}");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_replace_draft(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.replace_draft = enabled;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().replace_draft = persisted.replace_draft;
+    info!("Replace draft: {enabled}");
+    Ok(())
+}
+
+/// Run dictations without pasting or routing them.
+#[tauri::command]
+pub async fn set_dry_run(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.dry_run = enabled;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().dry_run = persisted.dry_run;
+    info!("Dry run: {enabled}");
+    Ok(())
+}
+
+/// Skip dictations whose audio repeats the previous one.
+#[tauri::command]
+pub async fn set_dedupe_dictations(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.dedupe_dictations = enabled;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().dedupe_dictations = persisted.dedupe_dictations;
+    info!("Dedupe dictations: {enabled}");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_paste_formatting(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.paste_formatting = enabled;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().paste_formatting = persisted.paste_formatting;
+    info!("Paste formatting: {enabled}");
+    Ok(())
+}
+
+/// Set the paste format for one app, or remove its rule when `category` is
+/// `None`. Same as `sagascript config paste-rule`.
+#[tauri::command]
+pub async fn set_paste_app_rule(
+    controller: State<'_, SharedController>,
+    app: String,
+    category: Option<AppCategory>,
+) -> Result<(), String> {
+    let app = app.trim().to_string();
+    if app.is_empty() {
+        return Err("App must not be empty".to_string());
+    }
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings
+            .paste_app_rules
+            .retain(|key, _| !key.eq_ignore_ascii_case(&app));
+        if let Some(category) = category {
+            settings.paste_app_rules.insert(app.clone(), category);
+        }
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().paste_app_rules = persisted.paste_app_rules;
+    info!("Paste rule for {app}: {category:?}");
+    Ok(())
+}
+
+/// Re-apply tray settings to the running tray on the main thread.
+fn apply_tray_settings(app: &tauri::AppHandle, settings: Settings) {
+    if let Err(e) = app.run_on_main_thread({
+        let app = app.clone();
+        move || crate::tray::apply(&app, &settings)
+    }) {
+        error!("Failed to dispatch tray update to main thread: {e}");
+    }
+}
+
+#[tauri::command]
+pub async fn set_tray_visibility(
+    app: tauri::AppHandle,
+    controller: State<'_, SharedController>,
+    visible: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.tray_visible = visible;
+    })?;
+    controller.lock().unwrap().settings_mut().tray_visible = persisted.tray_visible;
+    info!("Tray visible: {visible}");
+    apply_tray_settings(&app, persisted);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_tray_status_text(
+    app: tauri::AppHandle,
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.tray_status_text = enabled;
+    })?;
+    controller.lock().unwrap().settings_mut().tray_status_text = persisted.tray_status_text;
+    info!("Tray status text: {enabled}");
+    apply_tray_settings(&app, persisted);
+    Ok(())
+}
+
+/// Choose the optional tray menu items; "Quit" is always shown.
+#[tauri::command]
+pub async fn set_tray_menu(
+    app: tauri::AppHandle,
+    controller: State<'_, SharedController>,
+    items: Vec<TrayMenuItem>,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.tray_menu = items.clone();
+    })?;
+    controller.lock().unwrap().settings_mut().tray_menu = persisted.tray_menu.clone();
+    info!("Tray menu: {}", format_tray_menu(&items));
+    apply_tray_settings(&app, persisted);
+    Ok(())
+}
+
+/// Link the app binary onto PATH as `sagascript` (see
+/// `sagascript_core::cli_install`). On macOS an unwritable `/usr/local/bin`
+/// gets the admin password prompt before falling back to `~/.local/bin`,
+/// which a Mac shell rarely has on its PATH. Blocking.
+pub(crate) fn install_cli_link() -> Result<CliInstall, String> {
+    let target =
+        cli_install::app_binary().map_err(|e| format!("Can't locate the app binary: {e}"))?;
+
+    #[cfg(target_os = "macos")]
+    {
+        let system = std::path::Path::new(cli_install::SYSTEM_BIN_DIR);
+        match cli_install::link_into(system, &target) {
+            Ok(link) => return Ok(CliInstall::new(link, &target)),
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                if crate::platform::macos::link_cli_as_admin(&target)? {
+                    return Ok(CliInstall::new(
+                        system.join(cli_install::COMMAND_NAME),
+                        &target,
+                    ));
+                }
+                info!("Admin prompt cancelled; linking the CLI for this user instead");
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+
+    cli_install::install(&target).map_err(|e| e.to_string())
+}
+
+/// GUI equivalent of `sagascript install-cli`.
+#[tauri::command]
+pub async fn install_cli() -> Result<CliInstall, String> {
+    let installed = tokio::task::spawn_blocking(install_cli_link)
+        .await
+        .map_err(|e| format!("CLI install task failed: {e}"))??;
+    info!(
+        "CLI linked: {} -> {}",
+        installed.link.display(),
+        installed.target.display()
+    );
+    Ok(installed)
+}
+
+#[tauri::command]
+pub async fn set_telemetry(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.telemetry = enabled;
+    })?;
+    controller.lock().unwrap().settings_mut().telemetry = persisted.telemetry;
+    telemetry::set_enabled(persisted.telemetry);
+    info!("Telemetry: {enabled}");
+    Ok(())
+}
+
+/// The queued telemetry counts, exactly as `sagascript telemetry send` would
+/// upload them.
+#[tauri::command]
+pub async fn get_telemetry_queue() -> Result<Vec<DailyCounts>, String> {
+    Ok(telemetry::load_queue(&telemetry::queue_path()))
+}
+
+#[tauri::command]
+pub async fn clear_telemetry_queue() -> Result<(), String> {
+    telemetry::clear_queue(&telemetry::queue_path())
+        .map_err(|e| format!("Failed to clear telemetry queue: {e}"))?;
+    info!("Telemetry queue cleared");
+    Ok(())
+}
+
+/// The bearer token event stream clients must send, created on first use.
+#[tauri::command]
+pub async fn get_event_stream_token() -> Result<String, String> {
+    crate::event_stream::token().map_err(|e| format!("Failed to read event stream token: {e}"))
+}
+
+/// Path of the ffmpeg the decode fallback would use, if one is installed.
+#[tauri::command]
+pub async fn get_ffmpeg_path() -> Result<Option<String>, String> {
+    Ok(ffmpeg::find_ffmpeg().map(|p| p.display().to_string()))
+}
+
+#[tauri::command]
+pub async fn set_hotkey_mode(
+    controller: State<'_, SharedController>,
+    mode: HotkeyMode,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.hotkey_mode = mode;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().hotkey_mode = persisted.hotkey_mode;
+    info!("Hotkey mode set to {:?}", mode);
+    Ok(())
+}
+
+/// What a hotkey press does while a transcription is still running.
+#[tauri::command]
+pub async fn set_while_busy(
+    controller: State<'_, SharedController>,
+    mode: WhileBusy,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.while_busy = mode;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().while_busy = persisted.while_busy;
+    info!("While busy: {:?}", mode);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_hotkey(
+    app: tauri::AppHandle,
+    controller: State<'_, SharedController>,
+    health: State<'_, HotkeyHealth>,
+    shortcut: String,
+) -> Result<(), String> {
+    use tauri::Emitter;
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    let _transition = health.transition_guard();
+    let old_shortcut = {
+        let ctrl = controller.lock().unwrap();
+        ctrl.settings().hotkey.clone()
+    };
+
+    let old_operational = health.operational_hotkey();
+
+    // The saved shortcut can differ from the operational fallback after an
+    // external hot-reload failure. Always unregister what is actually active.
+    if old_operational == OperationalHotkey::Unknown {
+        return Err(
+            "Hotkey registration state is unknown after an earlier OS error; restart Sagascript before changing the shortcut"
+                .to_string(),
+        );
+    }
+    if let OperationalHotkey::Registered(old_operational) = &old_operational {
+        if let Err(error) = app.global_shortcut().unregister(old_operational.as_str()) {
+            error!("Failed to unregister operational hotkey '{old_operational}': {error}");
+            let change = health.record(
+                &old_shortcut,
+                Some(format!(
+                    "failed to unregister active hotkey '{old_operational}': {error}; operational state is unknown"
+                )),
                 OperationalHotkey::Unknown,
             );
             if change.changed {
@@ -452,10 +1647,15 @@ mod gui_recording_tests {
     }
 }
 
+/// Stop the test recording and transcribe it. `language` and `model`
+/// override the saved settings for this recording only.
 #[tauri::command]
 pub async fn stop_and_transcribe(
+    app: tauri::AppHandle,
     controller: State<'_, SharedController>,
     whisper: State<'_, SharedWhisper>,
+    language: Option<Language>,
+    model: Option<WhisperModel>,
 ) -> Result<String, String> {
     let (audio, language, effective_model, opts) = {
         let mut ctrl = controller.lock().unwrap();
@@ -467,13 +1667,20 @@ pub async fn stop_and_transcribe(
             StopRecordingOutcome::NotRecording => return Ok(String::new()),
             // Capture/resample failure (finding 4): the controller already
             // recorded the error and returned to Idle; surface the real error.
-            StopRecordingOutcome::Failed(msg) => return Err(msg),
+            StopRecordingOutcome::Failed(error) => {
+                emit_error(&app, &error);
+                return Err(error.to_string());
+            }
+            StopRecordingOutcome::Duplicate(since_last) => {
+                emit_deduped(&app, since_last);
+                return Ok(String::new());
+            }
             StopRecordingOutcome::Stopped(audio) => audio,
         };
-        let language = ctrl.language();
-        let effective_model = ctrl.settings().effective_model();
-        let opts = build_transcribe_options(ctrl.settings());
-        (audio, language, effective_model, opts)
+        let settings = ctrl.settings().with_overrides(language, model);
+        let effective_model = model_for_clip(&settings, audio.len());
+        let opts = build_transcribe_options(&settings);
+        (audio, settings.language, effective_model, opts)
     };
 
     if audio.is_empty() {
@@ -487,7 +1694,10 @@ pub async fn stop_and_transcribe(
     // `finish_transcription`: stop_recording_guarded has already moved the
     // controller to Transcribing, so returning early would wedge subsequent
     // recording attempts until the app restarts.
+    let audio_len = audio.len();
+    let mut no_speech = false;
     let result = if let Err(error) = whisper.ensure_model(effective_model) {
+        emit_error(&app, &error);
         Err(error.to_string())
     } else {
         // Run blocking transcription on a separate thread with a timeout. On timeout
@@ -498,31 +1708,68 @@ pub async fn stop_and_transcribe(
         // is kept borrowed (`&mut fut`) across the timeout so we can await its actual
         // exit after abort and log whether the lock was released.
         let whisper_ref = whisper.inner().clone();
+        let inference_started = std::time::Instant::now();
         let mut fut = tokio::task::spawn_blocking(move || {
-            whisper_ref.transcribe_sync_with_options(&audio, language, &opts, |_| {})
+            whisper_ref.transcribe_sync_allow_partial(&audio, language, &opts, |_| {})
         });
 
         let timeout = Duration::from_secs(TRANSCRIPTION_TIMEOUT_SECS);
         match tokio::time::timeout(timeout, &mut fut).await {
-            Ok(Ok(result)) => result.map_err(|error| error.to_string()),
+            Ok(Ok(result)) => {
+                if result.is_ok() {
+                    record_real_time_factor(
+                        &app,
+                        effective_model,
+                        audio_len,
+                        inference_started.elapsed(),
+                    );
+                }
+                result
+                    .map(|transcription| {
+                        no_speech = transcription.is_silent();
+                        transcription.text
+                    })
+                    .map_err(|error| {
+                        emit_error(&app, &error);
+                        error.to_string()
+                    })
+            }
             Ok(Err(error)) => Err(format!("Transcription task failed: {error}")),
             Err(_) => {
                 warn!("Transcription timed out after {TRANSCRIPTION_TIMEOUT_SECS}s — requesting abort");
                 whisper.request_abort();
                 // Give the aborted inference a brief grace to unwind, and log which
                 // outcome occurred so a genuine hang is distinguishable from a clean
-                // abort.
+                // abort. Segments whisper finished before the abort are returned as
+                // a partial result instead of discarding the whole dictation.
+                let timed_out = format!(
+                    "Transcription timed out after {TRANSCRIPTION_TIMEOUT_SECS}s (inference aborted)"
+                );
                 match tokio::time::timeout(Duration::from_secs(ABORT_GRACE_SECS), &mut fut).await {
-                    Ok(_) => info!("Aborted transcription task exited — warm-state lock released"),
-                    Err(_) => error!(
-                        "Transcription task still running {ABORT_GRACE_SECS}s after abort — the \
-                         warm state may stay locked until it unwinds; further transcriptions will \
-                         report ModelBusy rather than block forever"
-                    ),
+                    Ok(Ok(Ok(transcription))) => {
+                        info!("Aborted transcription task exited with a partial result — warm-state lock released");
+                        if transcription.partial {
+                            use tauri::Emitter;
+                            let _ = app.emit(
+                                crate::events::event::TRANSCRIPTION_PARTIAL,
+                                &transcription.text,
+                            );
+                        }
+                        Ok(transcription.text)
+                    }
+                    Ok(_) => {
+                        info!("Aborted transcription task exited — warm-state lock released");
+                        Err(timed_out)
+                    }
+                    Err(_) => {
+                        error!(
+                            "Transcription task still running {ABORT_GRACE_SECS}s after abort — the \
+                             warm state may stay locked until it unwinds; further transcriptions will \
+                             report ModelBusy rather than block forever"
+                        );
+                        Err(timed_out)
+                    }
                 }
-                Err(format!(
-                    "Transcription timed out after {TRANSCRIPTION_TIMEOUT_SECS}s (inference aborted)"
-                ))
             }
         }
     };
@@ -531,7 +1778,22 @@ pub async fn stop_and_transcribe(
     // called from a tokio worker thread (SIGTRAP in dispatch_assert_queue).
     // The hotkey path in main.rs handles paste via run_on_main_thread(). This
     // command returns the text to the frontend for display instead.
-    controller.lock().unwrap().finish_transcription(result)
+    let mut ctrl = controller.lock().unwrap();
+    let level_warning = ctrl.level_warning();
+    if no_speech {
+        ctrl.on_no_speech();
+        drop(ctrl);
+        let error = sagascript_core::error::DictationError::NoSpeechDetected;
+        emit_error(&app, &error);
+        emit_level_warning(&app, level_warning);
+        return Err(error.to_string());
+    }
+    let result = ctrl.finish_transcription(result);
+    drop(ctrl);
+    if result.is_ok() {
+        emit_level_warning(&app, level_warning);
+    }
+    result
 }
 
 #[tauri::command]
@@ -542,6 +1804,7 @@ pub async fn cancel_recording(
     let mut ctrl = controller.lock().unwrap();
     ctrl.cancel_recording();
     drop(ctrl);
+    crate::recovery::end(&app);
     crate::overlay::hide(&app);
     Ok(())
 }
@@ -549,39 +1812,120 @@ pub async fn cancel_recording(
 // -- Model management --
 
 #[tauri::command]
-pub async fn is_model_downloaded(whisper_model: WhisperModel) -> Result<bool, String> {
-    Ok(model::is_model_downloaded(whisper_model))
+pub async fn is_model_downloaded(whisper_model: WhisperModel) -> Result<bool, String> {
+    Ok(model::is_model_downloaded(whisper_model))
+}
+
+#[tauri::command]
+pub async fn get_model_info(
+    controller: State<'_, SharedController>,
+) -> Result<Vec<ModelInfo>, String> {
+    let ctrl = controller.lock().unwrap();
+    let filter = ModelFilter {
+        language: Some(ctrl.settings().language),
+        ..Default::default()
+    };
+    Ok(catalog::list_models(&filter, Some(ctrl.settings().effective_model())))
+}
+
+// -- Model download --
+
+#[tauri::command]
+pub async fn download_model(
+    app: tauri::AppHandle,
+    whisper_model: WhisperModel,
+) -> Result<(), String> {
+    use tauri::Emitter;
+    download_with_progress(&app, whisper_model).await?;
+    let _ = app.emit(crate::events::event::MODEL_READY, ());
+    Ok(())
+}
+
+/// Delete a downloaded model's GGML file and CoreML encoder, refusing the
+/// loaded or selected model unless `force`. Returns the bytes freed.
+#[tauri::command]
+pub async fn delete_model(
+    controller: State<'_, SharedController>,
+    whisper: State<'_, SharedWhisper>,
+    whisper_model: WhisperModel,
+    force: bool,
+) -> Result<u64, String> {
+    let in_use = whisper.loaded_model() == Some(whisper_model)
+        || controller
+            .lock()
+            .unwrap()
+            .settings()
+            .uses_model(whisper_model);
+    if in_use && !force {
+        return Err(format!(
+            "{} is the model in use. Select another model first.",
+            whisper_model.display_name()
+        ));
+    }
+    model::delete_model(whisper_model).map_err(|e| e.to_string())
+}
+
+/// The model to suggest for `language` on this machine, going by its
+/// memory, CPU cores and GPU (see `transcription::recommend`). Shown by
+/// onboarding before `setup_recommended` downloads it.
+#[tauri::command]
+pub async fn get_recommended_model(
+    controller: State<'_, SharedController>,
+    language: Language,
+) -> Result<Recommendation, String> {
+    let use_gpu = controller.lock().unwrap().settings().use_gpu;
+    Ok(recommend::recommend(language, Hardware::detect(use_gpu)))
 }
 
+/// Onboarding in one call: save `language` with the model recommended for
+/// this machine (see `get_recommended_model` and
+/// `Settings::apply_recommendation`), download the model if it's missing —
+/// emitting `model-download-progress` as for `download_model` — and load it,
+/// so a model that downloaded but won't load fails here rather than at the
+/// first dictation. Emits `model-ready` once loaded; returns the model.
 #[tauri::command]
-pub async fn get_model_info(
+pub async fn setup_recommended(
+    app: tauri::AppHandle,
     controller: State<'_, SharedController>,
-) -> Result<Vec<ModelInfo>, String> {
-    let ctrl = controller.lock().unwrap();
-    let language = ctrl.settings().language;
-    let effective = ctrl.settings().effective_model();
-    let models = WhisperModel::models_for_language(language);
+    whisper: State<'_, SharedWhisper>,
+    language: Language,
+) -> Result<WhisperModel, String> {
+    use tauri::Emitter;
+    let use_gpu = controller.lock().unwrap().settings().use_gpu;
+    let model = recommend::recommend(language, Hardware::detect(use_gpu)).model;
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.apply_recommendation(language, model);
+    })?;
+    let recommended = persisted.whisper_model;
+    {
+        let mut ctrl = controller.lock().unwrap();
+        ctrl.settings_mut().language = persisted.language;
+        ctrl.settings_mut().whisper_model = persisted.whisper_model;
+        ctrl.settings_mut().auto_select_model = persisted.auto_select_model;
+    }
+    info!("Onboarding preset: {:?} with {:?}", language, recommended);
 
-    Ok(models
-        .iter()
-        .map(|m| ModelInfo {
-            id: serde_json::to_value(m)
-                .and_then(serde_json::from_value::<String>)
-                .unwrap_or_else(|_| format!("{:?}", m)),
-            display_name: m.display_name().to_string(),
-            description: m.description().to_string(),
-            size_mb: m.size_mb(),
-            downloaded: model::is_model_downloaded(*m),
-            active: *m == effective,
-        })
-        .collect())
-}
+    if !model::is_model_downloaded(recommended) {
+        download_with_progress(&app, recommended).await?;
+    }
 
-// -- Model download --
+    let whisper_ref = whisper.inner().clone();
+    tokio::task::spawn_blocking(move || whisper_ref.ensure_model(recommended))
+        .await
+        .map_err(|e| format!("Model load task failed: {e}"))?
+        .map_err(|e| {
+            emit_error(&app, &e);
+            e.to_string()
+        })?;
 
-#[tauri::command]
-pub async fn download_model(
-    app: tauri::AppHandle,
+    let _ = app.emit(crate::events::event::MODEL_READY, ());
+    info!("Onboarding model {:?} ready", recommended);
+    Ok(recommended)
+}
+
+/// Download `whisper_model`, emitting `model-download-progress` as it goes.
+async fn download_with_progress(
+    app: &tauri::AppHandle,
     whisper_model: WhisperModel,
 ) -> Result<(), String> {
     use tauri::Emitter;
@@ -603,10 +1947,7 @@ pub async fn download_model(
         );
     })
     .await
-    .map_err(|e| e.to_string())?;
-
-    let _ = app.emit(crate::events::event::MODEL_READY, ());
-    Ok(())
+    .map_err(|e| e.to_string())
 }
 
 // -- Settings toggles --
@@ -627,10 +1968,10 @@ pub async fn set_auto_paste(
 
     let effective = effective_auto_paste(enabled, permission_granted);
     let persisted = sagascript_core::settings::store::update(|settings| {
-        settings.auto_paste = effective;
+        settings.set_auto_paste(effective);
     })?;
     let mut ctrl = controller.lock().unwrap();
-    ctrl.settings_mut().auto_paste = persisted.auto_paste;
+    ctrl.settings_mut().output_sinks = persisted.output_sinks;
     info!("Auto-paste: {effective}");
     if enabled && !permission_granted {
         Err("Accessibility permission is required before auto-paste can be enabled".to_string())
@@ -639,101 +1980,641 @@ pub async fn set_auto_paste(
     }
 }
 
-#[cfg(test)]
-mod auto_paste_tests {
-    use super::effective_auto_paste;
+#[cfg(test)]
+mod auto_paste_tests {
+    use super::effective_auto_paste;
+
+    #[test]
+    fn enabling_never_persists_true_before_accessibility_is_trusted() {
+        assert!(!effective_auto_paste(true, false));
+        assert!(effective_auto_paste(true, true));
+        assert!(!effective_auto_paste(false, true));
+    }
+}
+
+#[tauri::command]
+pub async fn set_show_overlay(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.show_overlay = enabled;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().show_overlay = persisted.show_overlay;
+    info!("Show overlay: {enabled}");
+    Ok(())
+}
+
+/// Overlay accessibility options; see `overlay::Appearance` and
+/// `Settings::announce_state`.
+#[tauri::command]
+pub async fn set_overlay_accessibility(
+    app: tauri::AppHandle,
+    controller: State<'_, SharedController>,
+    high_contrast: bool,
+    large_text: bool,
+    reduced_motion: bool,
+    announce_state: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.overlay_high_contrast = high_contrast;
+        settings.overlay_large_text = large_text;
+        settings.overlay_reduced_motion = reduced_motion;
+        settings.announce_state = announce_state;
+    })?;
+    {
+        let mut ctrl = controller.lock().unwrap();
+        let settings = ctrl.settings_mut();
+        settings.overlay_high_contrast = persisted.overlay_high_contrast;
+        settings.overlay_large_text = persisted.overlay_large_text;
+        settings.overlay_reduced_motion = persisted.overlay_reduced_motion;
+        settings.announce_state = persisted.announce_state;
+    }
+    crate::overlay::apply(&app, &persisted);
+    info!(
+        "Overlay accessibility: high_contrast={high_contrast}, large_text={large_text}, \
+         reduced_motion={reduced_motion}, announce_state={announce_state}"
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_initial_prompt(
+    controller: State<'_, SharedController>,
+    prompt: String,
+) -> Result<(), String> {
+    // Remembered for the profile of the current (or last) dictation too
+    let profile = controller.lock().unwrap().profile().map(|p| p.name.clone());
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.initial_prompt = prompt.clone();
+        settings.remember_initial_prompt(profile.as_deref(), &prompt);
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().initial_prompt = persisted.initial_prompt;
+    ctrl.settings_mut().prompt_history = persisted.prompt_history;
+    ctrl.settings_mut().profile_prompt_history = persisted.profile_prompt_history;
+    info!("Initial prompt set ({} chars)", prompt.len());
+    Ok(())
+}
+
+/// Replace the keyword-bias list from a comma-separated string (the same
+/// syntax as `config set keywords`).
+#[tauri::command]
+pub async fn set_keywords(
+    controller: State<'_, SharedController>,
+    keywords: String,
+) -> Result<(), String> {
+    let keywords = sagascript_core::transcription::keywords::parse_keywords(&keywords)
+        .map_err(|e| e.to_string())?;
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.keywords = keywords.clone();
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().keywords = persisted.keywords;
+    info!("Keywords set ({})", keywords.len());
+    Ok(())
+}
+
+/// Recently used initial prompts, newest first, for quick re-use: those of
+/// `profile` (by default the profile of the current or last dictation), or
+/// the global ones if it has none.
+#[tauri::command]
+pub async fn get_prompt_history(
+    controller: State<'_, SharedController>,
+    profile: Option<String>,
+) -> Result<Vec<String>, String> {
+    let ctrl = controller.lock().unwrap();
+    let profile = profile.or_else(|| ctrl.profile().map(|p| p.name.clone()));
+    Ok(ctrl.settings().prompt_history_for(profile.as_deref()).to_vec())
+}
+
+#[tauri::command]
+pub async fn set_beam_size(
+    controller: State<'_, SharedController>,
+    beam_size: u32,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.beam_size = beam_size;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().beam_size = persisted.beam_size;
+    info!("Beam size: {beam_size}");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_temperature_fallback(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.temperature_fallback = enabled;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().temperature_fallback = persisted.temperature_fallback;
+    info!("Temperature fallback: {enabled}");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_vad_enabled(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.vad_enabled = enabled;
+    })?;
+    {
+        let mut ctrl = controller.lock().unwrap();
+        ctrl.settings_mut().vad_enabled = persisted.vad_enabled;
+    }
+    // Fetch the Silero VAD model so it's ready for the next dictation. Done
+    // after releasing the lock (no lock held across await).
+    if enabled {
+        info!("Verifying or downloading VAD model...");
+        model::download_vad_model(|_, _| {})
+            .await
+            .map_err(|e| format!("Failed to download VAD model: {e}"))?;
+    }
+    info!("VAD enabled: {enabled}");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_adaptive_model(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.adaptive_model = enabled;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().adaptive_model = persisted.adaptive_model;
+    info!("Adaptive model: {enabled}");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_adaptive_clip_thresholds(
+    controller: State<'_, SharedController>,
+    short_secs: u32,
+    long_secs: u32,
+) -> Result<(), String> {
+    if short_secs > long_secs {
+        return Err(format!(
+            "Short-clip threshold ({short_secs}s) must not exceed long-clip threshold ({long_secs}s)"
+        ));
+    }
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.adaptive_short_clip_secs = short_secs;
+        settings.adaptive_long_clip_secs = long_secs;
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().adaptive_short_clip_secs = persisted.adaptive_short_clip_secs;
+    ctrl.settings_mut().adaptive_long_clip_secs = persisted.adaptive_long_clip_secs;
+    info!("Adaptive clip thresholds: short < {short_secs}s, long >= {long_secs}s");
+    Ok(())
+}
+
+// -- Output routing --
+
+#[tauri::command]
+pub async fn set_output_sinks(
+    controller: State<'_, SharedController>,
+    sinks: Vec<OutputSink>,
+) -> Result<(), String> {
+    // Round-trip through the CLI syntax so the GUI gets the same validation
+    // (http(s) webhooks, non-empty paths, mqtt:// topics) as
+    // `config set output_sinks`.
+    for sink in &sinks {
+        sink.to_string().parse::<OutputSink>()?;
+    }
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.output_sinks = sinks.clone();
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().output_sinks = persisted.output_sinks;
+    info!("Output sinks: [{}]", output::format_sinks(&sinks));
+    Ok(())
+}
+
+/// Store the password MQTT sinks log in with (for a username in the sink
+/// URL) in the credential store; an empty password removes it.
+#[tauri::command]
+pub async fn set_mqtt_password(password: String) -> Result<(), String> {
+    let store = sagascript_core::credentials::default_store()?;
+    if password.is_empty() {
+        store.delete(sagascript_core::mqtt::PASSWORD_SECRET)?;
+        info!("MQTT password removed");
+    } else {
+        store.set(sagascript_core::mqtt::PASSWORD_SECRET, &password)?;
+        info!("MQTT password stored ({})", store.backend());
+    }
+    Ok(())
+}
+
+// -- Post-processing --
+
+/// Set the language-model step each dictation goes through (see
+/// [`sagascript_core::llm`]). An empty command turns it off.
+#[tauri::command]
+pub async fn set_post_process(
+    controller: State<'_, SharedController>,
+    command: String,
+    prompt: String,
+    model: String,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.post_process_command = command.trim().to_string();
+        settings.post_process_prompt = prompt.clone();
+        settings.post_process_model = model.trim().to_string();
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().post_process_command = persisted.post_process_command;
+    ctrl.settings_mut().post_process_prompt = persisted.post_process_prompt;
+    ctrl.settings_mut().post_process_model = persisted.post_process_model;
+    info!("Post-processing: {}", if command.trim().is_empty() { "off" } else { "on" });
+    Ok(())
+}
+
+/// Store the API key a post-processing endpoint needs in the credential
+/// store; an empty key removes it.
+#[tauri::command]
+pub async fn set_post_process_api_key(key: String) -> Result<(), String> {
+    let store = sagascript_core::credentials::default_store()?;
+    if key.is_empty() {
+        store.delete(sagascript_core::llm::API_KEY_SECRET)?;
+        info!("Post-processing API key removed");
+    } else {
+        store.set(sagascript_core::llm::API_KEY_SECRET, &key)?;
+        info!("Post-processing API key stored ({})", store.backend());
+    }
+    Ok(())
+}
+
+// -- Transcription backend --
+
+/// Pick where dictations are transcribed (see
+/// [`sagascript_core::transcription::backend`]) and the OpenAI model.
+#[tauri::command]
+pub async fn set_transcription_backend(
+    controller: State<'_, SharedController>,
+    backend: BackendKind,
+    openai_model: String,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.transcription_backend = backend;
+        settings.openai_model = openai_model.trim().to_string();
+    })?;
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().transcription_backend = persisted.transcription_backend;
+    ctrl.settings_mut().openai_model = persisted.openai_model;
+    info!("Transcription backend: {}", backend.display_name());
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_azure_region(
+    controller: State<'_, SharedController>,
+    region: String,
+) -> Result<(), String> {
+    let region = region.trim().to_ascii_lowercase();
+    if !region.is_empty() {
+        backend::validate_azure_region(&region)?;
+    }
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.azure_region = region;
+    })?;
+    controller.lock().unwrap().settings_mut().azure_region = persisted.azure_region;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_network_proxy(
+    controller: State<'_, SharedController>,
+    proxy: String,
+) -> Result<(), String> {
+    let proxy = proxy.trim().to_string();
+    sagascript_core::net::check_proxy(&proxy)?;
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.network_proxy = proxy;
+    })?;
+    controller.lock().unwrap().settings_mut().network_proxy = persisted.network_proxy;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_model_mirror(
+    controller: State<'_, SharedController>,
+    mirror: String,
+) -> Result<(), String> {
+    let mirror = mirror.trim().trim_end_matches('/').to_string();
+    sagascript_core::download::check_mirror(&mirror)?;
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.model_mirror = mirror;
+    })?;
+    controller.lock().unwrap().settings_mut().model_mirror = persisted.model_mirror;
+    Ok(())
+}
+
+/// The credential holding `kind`'s API key.
+fn api_key_secret(kind: BackendKind) -> Result<&'static str, String> {
+    backend::api_key_secret(kind).ok_or_else(|| "Local transcription needs no API key".to_string())
+}
+
+/// Store a remote backend's API key in the credential store (the OS keyring
+/// where there is one).
+#[tauri::command]
+pub async fn set_api_key(backend: BackendKind, key: String) -> Result<(), String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("API key is empty".to_string());
+    }
+    let store = sagascript_core::credentials::default_store()?;
+    store.set(api_key_secret(backend)?, key)?;
+    info!(
+        "{} key stored ({})",
+        backend.display_name(),
+        store.backend()
+    );
+    Ok(())
+}
+
+/// Whether `backend` has an API key stored. The key itself never leaves
+/// the Rust side.
+#[tauri::command]
+pub async fn has_api_key(backend: BackendKind) -> Result<bool, String> {
+    let store = sagascript_core::credentials::default_store()?;
+    Ok(store.get(api_key_secret(backend)?)?.is_some())
+}
+
+#[tauri::command]
+pub async fn clear_api_key(backend: BackendKind) -> Result<(), String> {
+    sagascript_core::credentials::default_store()?.delete(api_key_secret(backend)?)?;
+    info!("{} key removed", backend.display_name());
+    Ok(())
+}
+
+// -- Accumulate mode --
 
-    #[test]
-    fn enabling_never_persists_true_before_accessibility_is_trusted() {
-        assert!(!effective_auto_paste(true, false));
-        assert!(effective_auto_paste(true, true));
-        assert!(!effective_auto_paste(false, true));
+#[tauri::command]
+pub async fn set_accumulate_mode(
+    app: tauri::AppHandle,
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.accumulate_mode = enabled;
+    })?;
+    let (old, new) = {
+        let mut ctrl = controller.lock().unwrap();
+        let old = flush_shortcut(ctrl.settings()).map(str::to_string);
+        ctrl.settings_mut().accumulate_mode = persisted.accumulate_mode;
+        (old, flush_shortcut(ctrl.settings()).map(str::to_string))
+    };
+    info!("Accumulate mode: {enabled}");
+    // Turning the mode off pastes nothing; whatever was buffered is dropped
+    // so it can't resurface on a later flush.
+    if !enabled {
+        controller.lock().unwrap().take_accumulated();
+        emit_accumulated(&app);
     }
+    swap_flush_hotkey(&app, old.as_deref(), new.as_deref())
 }
 
+/// Set the shortcut that opens the main window at `tab` (empty: none).
 #[tauri::command]
-pub async fn set_show_overlay(
+pub async fn set_settings_hotkey(
+    app: tauri::AppHandle,
     controller: State<'_, SharedController>,
-    enabled: bool,
+    hotkey: String,
+    tab: SettingsTab,
 ) -> Result<(), String> {
+    let hotkey = hotkey.trim().to_string();
+    let (old, taken) = {
+        let ctrl = controller.lock().unwrap();
+        let settings = ctrl.settings();
+        let taken = !hotkey.is_empty()
+            && (hotkey.eq_ignore_ascii_case(&settings.hotkey)
+                || flush_shortcut(settings).is_some_and(|f| hotkey.eq_ignore_ascii_case(f))
+                || toggle_shortcut(settings).is_some_and(|t| hotkey.eq_ignore_ascii_case(t)));
+        (settings_shortcut(settings).map(str::to_string), taken)
+    };
+    if taken {
+        return Err(
+            "The settings hotkey must differ from the dictation, flush and toggle hotkeys"
+                .to_string(),
+        );
+    }
+
+    // Register first so a combo that is already taken is reported, not saved.
+    let new = (!hotkey.is_empty()).then_some(hotkey.as_str());
+    if let Err(e) = swap_settings_hotkey(&app, old.as_deref(), new) {
+        let _ = swap_settings_hotkey(&app, new, old.as_deref());
+        return Err(e);
+    }
+
     let persisted = sagascript_core::settings::store::update(|settings| {
-        settings.show_overlay = enabled;
+        settings.settings_hotkey = hotkey.clone();
+        settings.settings_hotkey_tab = tab;
     })?;
     let mut ctrl = controller.lock().unwrap();
-    ctrl.settings_mut().show_overlay = persisted.show_overlay;
-    info!("Show overlay: {enabled}");
+    ctrl.settings_mut().settings_hotkey = persisted.settings_hotkey;
+    ctrl.settings_mut().settings_hotkey_tab = persisted.settings_hotkey_tab;
+    info!("Settings hotkey: '{hotkey}' -> {}", tab.as_str());
     Ok(())
 }
 
+/// Set the second dictation hotkey, which always toggles (empty: none).
 #[tauri::command]
-pub async fn set_initial_prompt(
+pub async fn set_toggle_hotkey(
+    app: tauri::AppHandle,
     controller: State<'_, SharedController>,
-    prompt: String,
+    hotkey: String,
 ) -> Result<(), String> {
+    let hotkey = hotkey.trim().to_string();
+    let (old, taken) = {
+        let ctrl = controller.lock().unwrap();
+        let settings = ctrl.settings();
+        let taken = !hotkey.is_empty()
+            && (hotkey.eq_ignore_ascii_case(&settings.hotkey)
+                || flush_shortcut(settings).is_some_and(|f| hotkey.eq_ignore_ascii_case(f))
+                || settings_shortcut(settings).is_some_and(|s| hotkey.eq_ignore_ascii_case(s)));
+        (toggle_shortcut(settings).map(str::to_string), taken)
+    };
+    if taken {
+        return Err(
+            "The toggle hotkey must differ from the dictation, flush and settings hotkeys"
+                .to_string(),
+        );
+    }
+
+    // Register first so a combo that is already taken is reported, not saved.
+    let new = (!hotkey.is_empty()).then_some(hotkey.as_str());
+    if let Err(e) = swap_toggle_hotkey(&app, old.as_deref(), new) {
+        let _ = swap_toggle_hotkey(&app, new, old.as_deref());
+        return Err(e);
+    }
+
     let persisted = sagascript_core::settings::store::update(|settings| {
-        settings.initial_prompt = prompt.clone();
+        settings.toggle_hotkey = hotkey.clone();
     })?;
-    let mut ctrl = controller.lock().unwrap();
-    ctrl.settings_mut().initial_prompt = persisted.initial_prompt;
-    info!("Initial prompt set ({} chars)", prompt.len());
+    controller.lock().unwrap().settings_mut().toggle_hotkey = persisted.toggle_hotkey;
+    info!("Toggle hotkey: '{hotkey}'");
     Ok(())
 }
 
+/// Replace the dictation profiles, moving their hotkey registrations along.
 #[tauri::command]
-pub async fn set_beam_size(
+pub async fn set_profiles(
+    app: tauri::AppHandle,
     controller: State<'_, SharedController>,
-    beam_size: u32,
+    profiles: Vec<Profile>,
 ) -> Result<(), String> {
+    sagascript_core::settings::profiles::validate_profiles(&profiles)?;
+    // Same checks as `set_output_sinks`, and the same permission as auto-paste.
+    for sinks in profiles.iter().filter_map(|p| p.output_sinks.as_deref()) {
+        for sink in sinks {
+            sink.to_string().parse::<OutputSink>()?;
+        }
+        #[cfg(target_os = "macos")]
+        if output::wants_paste(sinks) && !crate::platform::macos::is_accessibility_trusted() {
+            return Err(
+                "Accessibility permission is required before a profile can paste".to_string(),
+            );
+        }
+    }
+    let old = {
+        let ctrl = controller.lock().unwrap();
+        let settings = ctrl.settings();
+        let others = [
+            Some(settings.hotkey.as_str()),
+            flush_shortcut(settings),
+            toggle_shortcut(settings),
+            settings_shortcut(settings),
+        ];
+        let clash = profiles
+            .iter()
+            .filter_map(Profile::shortcut)
+            .find(|hotkey| {
+                let taken = |other: &&str| hotkey.eq_ignore_ascii_case(other);
+                others.iter().flatten().any(taken)
+            });
+        if let Some(taken) = clash {
+            return Err(format!(
+                "Profile hotkey '{taken}' is already the dictation, flush, toggle or settings hotkey"
+            ));
+        }
+        profile_shortcuts(settings)
+            .into_iter()
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    // Register first so a combo that is already taken is reported, not saved.
+    let old: Vec<&str> = old.iter().map(String::as_str).collect();
+    let new: Vec<&str> = profiles.iter().filter_map(Profile::shortcut).collect();
+    if let Err(e) = swap_profile_hotkeys(&app, &old, &new) {
+        let _ = swap_profile_hotkeys(&app, &new, &old);
+        return Err(e);
+    }
+
     let persisted = sagascript_core::settings::store::update(|settings| {
-        settings.beam_size = beam_size;
+        settings.profiles = profiles.clone();
     })?;
-    let mut ctrl = controller.lock().unwrap();
-    ctrl.settings_mut().beam_size = persisted.beam_size;
-    info!("Beam size: {beam_size}");
+    controller.lock().unwrap().settings_mut().profiles = persisted.profiles;
+    info!("Dictation profiles: {}", profiles.len());
     Ok(())
 }
 
+/// Open (or focus) the main window at `tab`, for keyboard-only navigation.
 #[tauri::command]
-pub async fn set_temperature_fallback(
+pub async fn open_settings_tab(app: tauri::AppHandle, tab: SettingsTab) -> Result<(), String> {
+    let handle = app.clone();
+    app.run_on_main_thread(move || crate::open_settings_window(&handle, Some(tab.as_str())))
+        .map_err(|e| format!("Failed to open settings: {e}"))
+}
+
+/// Hide the main window; it keeps its state for the next open.
+#[tauri::command]
+pub async fn close_settings(app: tauri::AppHandle) -> Result<(), String> {
+    let handle = app.clone();
+    app.run_on_main_thread(move || crate::close_settings_window(&handle))
+        .map_err(|e| format!("Failed to close settings: {e}"))
+}
+
+#[tauri::command]
+pub async fn set_flush_options(
+    app: tauri::AppHandle,
     controller: State<'_, SharedController>,
-    enabled: bool,
+    hotkey: String,
+    phrase: String,
 ) -> Result<(), String> {
+    let hotkey = hotkey.trim().to_string();
+    let (old, dictation_hotkey, active) = {
+        let ctrl = controller.lock().unwrap();
+        (
+            flush_shortcut(ctrl.settings()).map(str::to_string),
+            ctrl.settings().hotkey.clone(),
+            ctrl.settings().accumulate_mode,
+        )
+    };
+    if hotkey.eq_ignore_ascii_case(&dictation_hotkey) {
+        return Err("The flush hotkey must differ from the dictation hotkey".to_string());
+    }
+
+    // Register the new shortcut before persisting so a combo that is already
+    // taken is reported instead of saved.
+    if active {
+        let new = (!hotkey.is_empty()).then_some(hotkey.as_str());
+        if let Err(e) = swap_flush_hotkey(&app, old.as_deref(), new) {
+            let _ = swap_flush_hotkey(&app, new, old.as_deref());
+            return Err(e);
+        }
+    }
+
     let persisted = sagascript_core::settings::store::update(|settings| {
-        settings.temperature_fallback = enabled;
+        settings.flush_hotkey = hotkey.clone();
+        settings.flush_phrase = phrase.trim().to_string();
     })?;
     let mut ctrl = controller.lock().unwrap();
-    ctrl.settings_mut().temperature_fallback = persisted.temperature_fallback;
-    info!("Temperature fallback: {enabled}");
+    ctrl.settings_mut().flush_hotkey = persisted.flush_hotkey;
+    ctrl.settings_mut().flush_phrase = persisted.flush_phrase;
+    info!("Flush options: hotkey '{hotkey}', phrase '{}'", phrase.trim());
     Ok(())
 }
 
 #[tauri::command]
-pub async fn set_vad_enabled(
+pub async fn get_accumulated(
+    controller: State<'_, SharedController>,
+) -> Result<AccumulatedSnapshot, String> {
+    let ctrl = controller.lock().unwrap();
+    Ok(ctrl.accumulated().snapshot())
+}
+
+#[tauri::command]
+pub async fn flush_accumulated(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    Ok(flush_accumulated_text(&app))
+}
+
+#[tauri::command]
+pub async fn clear_accumulated(
+    app: tauri::AppHandle,
     controller: State<'_, SharedController>,
-    enabled: bool,
 ) -> Result<(), String> {
-    let persisted = sagascript_core::settings::store::update(|settings| {
-        settings.vad_enabled = enabled;
-    })?;
-    {
-        let mut ctrl = controller.lock().unwrap();
-        ctrl.settings_mut().vad_enabled = persisted.vad_enabled;
-    }
-    // Fetch the Silero VAD model so it's ready for the next dictation. Done
-    // after releasing the lock (no lock held across await).
-    if enabled {
-        info!("Verifying or downloading VAD model...");
-        model::download_vad_model(|_, _| {})
-            .await
-            .map_err(|e| format!("Failed to download VAD model: {e}"))?;
-    }
-    info!("VAD enabled: {enabled}");
+    let dropped = controller.lock().unwrap().take_accumulated();
+    info!("Accumulated dictation cleared ({} chars dropped)", dropped.len());
+    emit_accumulated(&app);
     Ok(())
 }
 
 // -- File transcription --
 
+/// Transcribe an audio or video file. `language` and `model` override the
+/// saved settings for this file only.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn transcribe_file(
     app: tauri::AppHandle,
     controller: State<'_, SharedController>,
@@ -741,41 +2622,89 @@ pub async fn transcribe_file(
     file_path: String,
     prompt: Option<String>,
     diarize: Option<bool>,
+    language: Option<Language>,
+    model: Option<WhisperModel>,
 ) -> Result<String, String> {
-    use tauri::Emitter;
+    use tauri::{Emitter, Manager};
 
     let path = std::path::PathBuf::from(&file_path);
+    let title = path
+        .file_name()
+        .map_or_else(|| file_path.clone(), |n| n.to_string_lossy().into_owned());
+    *app.state::<SharedMinutes>().lock().unwrap() = None;
+    // Diarization already tells the speakers apart, so it takes precedence.
+    let split_channels =
+        !diarize.unwrap_or(false) && controller.lock().unwrap().settings().split_channels;
+
+    // Long files are decoded and transcribed chunk by chunk in the standard
+    // path below instead, keeping memory flat; `audio` stays empty. Diarization
+    // and split channels need the whole recording at once, so they never stream.
+    let streamed_secs = if diarize.unwrap_or(false) || split_channels {
+        None
+    } else {
+        decoder::probe_duration(&path).filter(|&secs| secs >= STREAM_DECODE_AFTER_SECS)
+    };
 
-    // Decode audio file
-    let audio = tokio::task::spawn_blocking(move || decoder::decode_audio_file(&path))
+    // Decode audio file. With split channels, a file with more than one
+    // leaves `audio` empty and fills `channels` instead.
+    let (audio, channels) = if streamed_secs.is_some() {
+        (Vec::new(), Vec::new())
+    } else {
+        let path = path.clone();
+        let ffmpeg_fallback = controller.lock().unwrap().settings().ffmpeg_fallback;
+        let app_decode = app.clone();
+        emit_stage(&app, Stage::Decoding, 0);
+        let mut decoded = tokio::task::spawn_blocking(move || {
+            let on_progress = |pct: u32| emit_stage(&app_decode, Stage::Decoding, pct.into());
+            if split_channels {
+                decoder::decode_audio_file_channels(&path, on_progress)
+            } else {
+                decoder::decode_audio_file_with_fallback(&path, ffmpeg_fallback, on_progress)
+                    .map(|audio| vec![audio])
+            }
+        })
         .await
         .map_err(|e| format!("Decode task failed: {e}"))?
         .map_err(|e| e.to_string())?;
 
-    if audio.is_empty() {
-        return Err("No audio decoded from file".to_string());
-    }
+        if decoded.iter().all(Vec::is_empty) {
+            return Err("No audio decoded from file".to_string());
+        }
+        if decoded.len() == 1 {
+            (decoded.remove(0), Vec::new())
+        } else {
+            info!("Transcribing {} channels separately", decoded.len());
+            (Vec::new(), decoded)
+        }
+    };
+    let audio_len = match (streamed_secs, channels.first()) {
+        (Some(secs), _) => (secs * 16_000.0) as usize,
+        (None, Some(channel)) => channel.len(),
+        (None, None) => audio.len(),
+    };
 
     // File transcription (beam search / diarization) is far slower than live
     // dictation, so scale the timeout by the decoded duration rather than using
     // the short live-dictation timeout (which beam search could otherwise hit).
+    // Each split channel is transcribed in turn.
     let file_timeout = Duration::from_secs(
-        ((audio.len() / 16_000) as u64 * 6).max(TRANSCRIPTION_TIMEOUT_SECS),
+        ((audio_len / 16_000) as u64 * 6 * channels.len().max(1) as u64)
+            .max(TRANSCRIPTION_TIMEOUT_SECS),
     );
 
-    // Suppress unused-variable warning on `diarize` when the diarization feature is off
-    #[cfg(not(feature = "diarization"))]
-    let _ = &diarize;
-
-    // Get transcription settings
-    let (language, effective_model) = {
-        let ctrl = controller.lock().unwrap();
-        (ctrl.language(), ctrl.settings().effective_model())
-    };
+    // Get transcription settings, with this file's overrides
+    let settings = controller
+        .lock()
+        .unwrap()
+        .settings()
+        .with_overrides(language, model);
+    let language = settings.language;
+    let effective_model = model_for_clip(&settings, audio_len);
 
     // Show model loading status if needed
     if whisper.needs_reload(effective_model) {
         let _ = app.emit(crate::events::event::STATE_CHANGED, "loading_model");
+        emit_stage(&app, Stage::LoadingModel, 0);
     }
 
     // Ensure model is loaded
@@ -783,8 +2712,10 @@ pub async fn transcribe_file(
         let _ = app.emit(crate::events::event::STATE_CHANGED, "idle");
         return Err(error.to_string());
     }
+    emit_stage(&app, Stage::LoadingModel, 100);
 
     let _ = app.emit(crate::events::event::STATE_CHANGED, "transcribing");
+    emit_stage(&app, Stage::Transcribing, 0);
 
     // Diarization path — runs both diarization and timestamped transcription in parallel,
     // then merges and consolidates speaker-attributed segments.
@@ -883,6 +2814,7 @@ pub async fn transcribe_file(
             }
         };
 
+        emit_stage(&app, Stage::PostProcessing, 0);
         let transcript: Vec<TimestampedSegment> = raw_segments
             .into_iter()
             .map(|(start, end, text)| TimestampedSegment { start, end, text })
@@ -890,10 +2822,14 @@ pub async fn transcribe_file(
 
         let diarized = merge_with_transcript(&speaker_segments, &transcript);
         let mut consolidated = consolidate(&diarized);
+        let rules = controller.lock().unwrap().settings().replacement_rules.clone();
         for segment in &mut consolidated {
-            segment.text = sagascript_core::transcription::normalize_nonspeech_markers(
-                &segment.text,
-                language,
+            segment.text = replacements::apply(
+                &sagascript_core::transcription::normalize_nonspeech_markers(
+                    &segment.text,
+                    language,
+                ),
+                &rules,
             );
         }
 
@@ -904,43 +2840,82 @@ pub async fn transcribe_file(
             .join("\n");
 
         info!("Diarized file transcription complete: {} chars", text.len());
+        emit_stage(&app, Stage::PostProcessing, 100);
+        record_file_history(&title, audio_len, effective_model, language, &text);
+        *app.state::<SharedMinutes>().lock().unwrap() = Some(MinutesSource {
+            title,
+            duration: audio_len as f64 / TARGET_SAMPLE_RATE as f64,
+            language,
+            turns: consolidated.iter().map(Turn::from).collect(),
+        });
 
         let _ = app.emit(crate::events::event::STATE_CHANGED, "idle");
 
-        // Auto-paste if enabled
-        let should_paste = {
-            let c = controller.lock().unwrap();
-            c.settings().auto_paste
-        };
-        if should_paste {
-            let text_for_paste = text.clone();
-            if let Err(e) = app.run_on_main_thread(move || {
-                let paste_svc = crate::paste::PasteService::new();
-                if let Err(e) = paste_svc.paste(&text_for_paste) {
-                    error!("Auto-paste failed: {e}");
-                }
-            }) {
-                error!("Failed to dispatch paste to main thread: {e}");
-            }
-        }
+        auto_paste_or_hold(&app, &text);
 
+        telemetry::record(TelemetryEvent::FileTranscription {
+            model: effective_model,
+        });
         return Ok(text);
     }
 
     // Standard (non-diarize) transcription path. File transcription defaults to
     // beam search (quality over latency).
-    let opts = {
-        let ctrl = controller.lock().unwrap();
-        build_file_transcribe_options(ctrl.settings(), prompt)
-    };
+    let opts = build_file_transcribe_options(&settings, prompt);
     let whisper_ref = whisper.inner().clone();
     let app_progress = app.clone();
+    // A streamed file runs on its own whisper states, so the timeout aborts
+    // it through this handle rather than `request_abort`.
+    let file_abort = JobAbort::new();
+    let job_abort = file_abort.clone();
     // Borrowed handle (`&mut fut`) so the timeout path can await the task's
     // actual exit after requesting an abort — mirrors the live dictation path.
     let mut fut = tokio::task::spawn_blocking(move || {
-        whisper_ref.transcribe_sync_with_options(&audio, language, &opts, move |pct| {
-            let _ = app_progress.emit(crate::events::event::TRANSCRIPTION_PROGRESS, pct);
-        })
+        if streamed_secs.is_some() {
+            // Decoding runs in step with transcription here, so its progress
+            // is the transcription progress.
+            whisper_ref
+                .transcribe_file_streaming(
+                    &path,
+                    language,
+                    &opts,
+                    |pct| {
+                        emit_stage(&app_progress, Stage::Transcribing, pct.into());
+                    },
+                    &job_abort,
+                )
+                .map(|segments| (join_segments(&segments, language), Vec::new()))
+        } else if !channels.is_empty() {
+            let opts = TranscribeOptions {
+                segment_timestamps: true,
+                ..opts
+            };
+            let count = channels.len() as i64;
+            let mut per_channel = Vec::with_capacity(channels.len());
+            for (index, channel) in channels.iter().enumerate() {
+                let app_progress = app_progress.clone();
+                per_channel.push(whisper_ref.transcribe_sync_with_options_segments(
+                    channel,
+                    language,
+                    &opts,
+                    move |pct| {
+                        let pct = (index as i64 * 100 + i64::from(pct)) / count;
+                        emit_stage(&app_progress, Stage::Transcribing, pct);
+                    },
+                )?);
+            }
+            let merged = merge_channels(per_channel, language);
+            Ok((
+                channel_transcript(&merged),
+                merged.iter().map(Turn::from).collect(),
+            ))
+        } else {
+            whisper_ref
+                .transcribe_sync_with_options(&audio, language, &opts, move |pct| {
+                    emit_stage(&app_progress, Stage::Transcribing, pct.into());
+                })
+                .map(|transcript| (transcript.into_text(), Vec::new()))
+        }
     });
 
     let timeout = file_timeout;
@@ -955,6 +2930,7 @@ pub async fn transcribe_file(
                 "File transcription timed out after {}s — requesting abort",
                 timeout.as_secs()
             );
+            file_abort.abort();
             whisper.request_abort();
             // Brief grace for the aborted inference to unwind; log which outcome
             // occurred so a genuine hang is visible.
@@ -977,26 +2953,27 @@ pub async fn transcribe_file(
     let _ = app.emit(crate::events::event::STATE_CHANGED, "idle");
 
     match result {
-        Ok(text) => {
-            info!("File transcription complete: {} chars", text.len());
-
-            // Auto-paste if enabled
-            let should_paste = {
-                let c = controller.lock().unwrap();
-                c.settings().auto_paste
+        Ok((text, turns)) => {
+            let text = {
+                let ctrl = controller.lock().unwrap();
+                replacements::apply(&text, &ctrl.settings().replacement_rules)
             };
-
-            if should_paste {
-                let text_for_paste = text.clone();
-                if let Err(e) = app.run_on_main_thread(move || {
-                    let paste_svc = crate::paste::PasteService::new();
-                    if let Err(e) = paste_svc.paste(&text_for_paste) {
-                        error!("Auto-paste failed: {e}");
-                    }
-                }) {
-                    error!("Failed to dispatch paste to main thread: {e}");
-                }
+            info!("File transcription complete: {} chars", text.len());
+            emit_stage(&app, Stage::PostProcessing, 100);
+            record_file_history(&title, audio_len, effective_model, language, &text);
+            if !turns.is_empty() {
+                *app.state::<SharedMinutes>().lock().unwrap() = Some(MinutesSource {
+                    title,
+                    duration: audio_len as f64 / TARGET_SAMPLE_RATE as f64,
+                    language,
+                    turns,
+                });
             }
+            telemetry::record(TelemetryEvent::FileTranscription {
+                model: effective_model,
+            });
+
+            auto_paste_or_hold(&app, &text);
 
             Ok(text)
         }
@@ -1004,6 +2981,110 @@ pub async fn transcribe_file(
     }
 }
 
+/// Add a transcribed file to the history.
+fn record_file_history(
+    title: &str,
+    audio_len: usize,
+    model: WhisperModel,
+    language: Language,
+    text: &str,
+) {
+    if text.trim().is_empty() {
+        return;
+    }
+    let entry = HistoryEntry::new(
+        HistorySource::File,
+        audio_len as f32 / TARGET_SAMPLE_RATE as f32,
+        model,
+        language,
+        text,
+    )
+    .with_file(title);
+    if let Err(e) = history::append(&history::history_path(), &entry) {
+        warn!("Failed to record {title} in the history: {e}");
+    }
+}
+
+/// Write meeting minutes (Markdown) for the last diarized or channel-split
+/// file transcription to `path`.
+#[tauri::command]
+pub async fn export_minutes(
+    minutes_source: State<'_, SharedMinutes>,
+    path: String,
+) -> Result<(), String> {
+    let markdown = {
+        let source = minutes_source.lock().unwrap();
+        let Some(source) = source.as_ref() else {
+            return Err(
+                "No speaker-separated transcription to export — transcribe a file with \
+                 speaker diarization or split channels first"
+                    .to_string(),
+            );
+        };
+        minutes::render_markdown(&source.title, source.duration, &source.turns, source.language)
+    };
+    std::fs::write(&path, markdown).map_err(|e| format!("Failed to write {path}: {e}"))?;
+    info!("Exported minutes to {path}");
+    Ok(())
+}
+
+// -- Crash recovery --
+
+/// The dictation the last run didn't finish, if there is one to retry.
+#[tauri::command]
+pub async fn get_recoverable_session() -> Result<Option<RecoverableSession>, String> {
+    Ok(recovery::load(&recovery::crashed_dir()))
+}
+
+/// Transcribe the interrupted dictation's audio like a file, then forget
+/// it. It is kept if transcription fails, to try again later.
+#[tauri::command]
+pub async fn retry_recoverable_session(
+    app: tauri::AppHandle,
+    controller: State<'_, SharedController>,
+    whisper: State<'_, SharedWhisper>,
+) -> Result<String, String> {
+    let dir = recovery::crashed_dir();
+    let Some(session) = recovery::load(&dir) else {
+        return Err("There is no interrupted dictation to recover".to_string());
+    };
+    let path = recovery::audio_path(&dir).to_string_lossy().into_owned();
+    // In the language and model the dictation was using, not today's settings.
+    let text = transcribe_file(
+        app,
+        controller,
+        whisper,
+        path,
+        None,
+        None,
+        Some(session.language),
+        Some(session.model),
+    )
+    .await?;
+    if let Err(e) = recovery::clear(&dir) {
+        warn!("Failed to remove the recovered dictation: {e}");
+    }
+    Ok(text)
+}
+
+/// Throw the interrupted dictation away.
+#[tauri::command]
+pub async fn discard_recoverable_session() -> Result<(), String> {
+    recovery::clear(&recovery::crashed_dir()).map_err(|e| e.to_string())
+}
+
+/// Take the file waiting to be transcribed in the main window, if any.
+#[tauri::command]
+pub async fn take_requested_file(
+    requested: State<'_, SharedRequestedFile>,
+) -> Result<Option<String>, String> {
+    Ok(requested
+        .lock()
+        .unwrap()
+        .take()
+        .map(|p| p.display().to_string()))
+}
+
 #[tauri::command]
 pub async fn get_supported_formats() -> Result<Vec<String>, String> {
     Ok(decoder::SUPPORTED_EXTENSIONS
@@ -1023,6 +3104,13 @@ pub async fn get_build_info() -> Result<BuildInfo, String> {
     })
 }
 
+/// Event names, payload types and the schema version, for windows and
+/// tools to check at startup that they expect the events this build sends.
+#[tauri::command]
+pub async fn get_event_schema() -> Result<crate::events::Schema, String> {
+    Ok(crate::events::schema())
+}
+
 #[derive(serde::Serialize)]
 pub struct BuildInfo {
     version: String,
@@ -1030,14 +3118,120 @@ pub struct BuildInfo {
     build_date: String,
 }
 
-#[derive(serde::Serialize)]
-pub struct ModelInfo {
-    id: String,
-    display_name: String,
-    description: String,
-    size_mb: u32,
-    downloaded: bool,
-    active: bool,
+/// whisper.cpp build, backends, thread count and the model in memory (or the
+/// one that would be loaded), for slow-transcription reports.
+#[tauri::command]
+pub async fn get_whisper_runtime_info(
+    controller: State<'_, SharedController>,
+    whisper: State<'_, SharedWhisper>,
+) -> Result<RuntimeInfo, String> {
+    let info = match whisper.loaded_model() {
+        Some(loaded) => RuntimeInfo::collect(loaded, true),
+        None => {
+            let effective = controller.lock().unwrap().settings().effective_model();
+            RuntimeInfo::collect(effective, false)
+        }
+    };
+    Ok(info)
+}
+
+/// Run local inference on the GPU or CPU only. A loaded model is reloaded
+/// the new way before the next dictation.
+#[tauri::command]
+pub async fn set_use_gpu(
+    controller: State<'_, SharedController>,
+    whisper: State<'_, SharedWhisper>,
+    draft_whisper: State<'_, crate::draft_pass::DraftWhisper>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.use_gpu = enabled;
+    })?;
+    controller.lock().unwrap().settings_mut().use_gpu = persisted.use_gpu;
+    whisper.set_use_gpu(persisted.use_gpu);
+    draft_whisper.set_use_gpu(persisted.use_gpu);
+    info!("GPU inference: {}", persisted.use_gpu);
+    Ok(())
+}
+
+/// Load the model when the app starts; takes effect on the next launch.
+#[tauri::command]
+pub async fn set_preload_model(
+    controller: State<'_, SharedController>,
+    enabled: bool,
+) -> Result<(), String> {
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        settings.preload_model = enabled;
+    })?;
+    controller.lock().unwrap().settings_mut().preload_model = persisted.preload_model;
+    info!("Model preload at startup: {enabled}");
+    Ok(())
+}
+
+/// Register a local GGML file as custom model `name` (`sagascript models
+/// add`). Returns the absolute path saved.
+#[tauri::command]
+pub async fn add_custom_model(
+    controller: State<'_, SharedController>,
+    name: String,
+    path: String,
+) -> Result<String, String> {
+    let name = name.trim();
+    let saved = model::add_custom_model(name, Path::new(&path)).map_err(|e| e.to_string())?;
+    let persisted = sagascript_core::settings::store::load();
+    controller.lock().unwrap().settings_mut().custom_models = persisted.custom_models;
+    Ok(saved.display().to_string())
+}
+
+/// Forget custom model `name`, going back to the built-in models if it was
+/// in use.
+#[tauri::command]
+pub async fn remove_custom_model(
+    controller: State<'_, SharedController>,
+    whisper: State<'_, SharedWhisper>,
+    name: String,
+) -> Result<(), String> {
+    model::remove_custom_model(&name).map_err(|e| e.to_string())?;
+    let persisted = sagascript_core::settings::store::load();
+    whisper.set_custom_model(persisted.custom_model_path());
+    let mut ctrl = controller.lock().unwrap();
+    ctrl.settings_mut().custom_models = persisted.custom_models;
+    ctrl.settings_mut().custom_model = persisted.custom_model;
+    Ok(())
+}
+
+/// Transcribe with registered custom model `name`; empty goes back to the
+/// built-in models.
+#[tauri::command]
+pub async fn set_custom_model(
+    controller: State<'_, SharedController>,
+    whisper: State<'_, SharedWhisper>,
+    name: String,
+) -> Result<(), String> {
+    let name = name.trim().to_string();
+    let persisted = sagascript_core::settings::store::update(|settings| {
+        if name.is_empty() || settings.custom_models.contains_key(&name) {
+            settings.custom_model = name.clone();
+        }
+    })?;
+    if persisted.custom_model != name {
+        return Err(format!("No custom model named '{name}'"));
+    }
+    whisper.set_custom_model(persisted.custom_model_path());
+    controller.lock().unwrap().settings_mut().custom_model = persisted.custom_model;
+    info!("Custom model: {}", if name.is_empty() { "none" } else { &name });
+    Ok(())
+}
+
+/// Whether this build has a GPU backend (Metal, CUDA) and whether the
+/// loaded model, or the next one, runs on it.
+#[tauri::command]
+pub async fn get_acceleration_info(
+    controller: State<'_, SharedController>,
+    whisper: State<'_, SharedWhisper>,
+) -> Result<AccelerationInfo, String> {
+    let use_gpu = controller.lock().unwrap().settings().use_gpu;
+    Ok(AccelerationInfo::collect(use_gpu, whisper.loaded_on_gpu()))
 }
 
 // -- Permission / platform queries (for onboarding) --
@@ -0,0 +1,157 @@
+//! Two-stage dictation (`draft_pass`).
+//!
+//! On stop, the language's tiny model transcribes the recording first and
+//! its text is pasted straight away; the selected model then transcribes as
+//! usual. With `replace_draft` the final text takes the draft's place once
+//! it's ready (see [`PasteService::replace_draft`]). The draft model has a
+//! backend of its own so the selected model stays loaded between the passes.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use sagascript_core::replacements;
+use sagascript_core::settings::{BackendKind, WhisperModel};
+use sagascript_core::transcription::{model, JobAbort, WhisperBackend};
+use tauri::{AppHandle, Manager};
+use tracing::{error, info, warn};
+
+use crate::commands::{self, SharedController};
+use crate::paste::PasteService;
+
+/// A draft that takes longer than this is no faster than the real result;
+/// it is abandoned.
+const DRAFT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Whisper backend the draft model is loaded in.
+pub struct DraftWhisper(Arc<WhisperBackend>);
+
+impl DraftWhisper {
+    pub fn new(use_gpu: bool) -> Self {
+        Self(Arc::new(WhisperBackend::with_gpu(use_gpu)))
+    }
+
+    pub fn set_use_gpu(&self, use_gpu: bool) {
+        self.0.set_use_gpu(use_gpu);
+    }
+}
+
+/// Draft text that was pasted for the current dictation.
+pub struct Draft {
+    text: String,
+    replace: bool,
+}
+
+/// Transcribe `audio` with the draft model and paste the text. Returns the
+/// pasted draft, or `None` if the draft pass is off, doesn't apply (a remote
+/// backend, a custom model, accumulate mode, or a selected model that is
+/// already the tiny one) or produced nothing.
+pub async fn paste(app: &AppHandle, audio: &[f32], final_model: WhisperModel) -> Option<Draft> {
+    let (language, draft_model, opts, rules, replace) = {
+        let ctrl: tauri::State<'_, SharedController> = app.state();
+        let c = ctrl.lock().unwrap();
+        let settings = c.dictation_settings();
+        let draft_model = WhisperModel::draft(settings.language);
+        let applies = settings.draft_pass
+            && settings.auto_pastes()
+            && !settings.accumulate_mode
+            && settings.transcription_backend == BackendKind::Local
+            && settings.custom_model_path().is_none()
+            && draft_model != final_model;
+        if !applies {
+            return None;
+        }
+        (
+            settings.language,
+            draft_model,
+            commands::build_transcribe_options(&settings),
+            settings.replacement_rules.clone(),
+            settings.replace_draft,
+        )
+    };
+    // Downloading the draft model would take longer than the real pass.
+    if !model::is_model_downloaded(draft_model) {
+        info!(
+            "Draft model {} not downloaded — skipping the draft",
+            draft_model.display_name()
+        );
+        return None;
+    }
+
+    let whisper = Arc::clone(&app.state::<DraftWhisper>().0);
+    let abort = JobAbort::new();
+    let job_abort = abort.clone();
+    let audio = audio.to_vec();
+    let job = tokio::task::spawn_blocking(move || {
+        whisper.ensure_model(draft_model)?;
+        whisper.transcribe_job(&audio, language, &opts, |_| {}, &job_abort)
+    });
+    let text = match tokio::time::timeout(DRAFT_TIMEOUT, job).await {
+        Ok(Ok(Ok(transcript))) => replacements::apply(&transcript.text, &rules),
+        Ok(Ok(Err(e))) => {
+            warn!("Draft transcription failed: {e}");
+            return None;
+        }
+        Ok(Err(e)) => {
+            warn!("Draft task failed: {e}");
+            return None;
+        }
+        Err(_) => {
+            warn!("Draft took over {}s — abandoned", DRAFT_TIMEOUT.as_secs());
+            abort.abort();
+            return None;
+        }
+    };
+    if text.trim().is_empty() {
+        return None;
+    }
+
+    let paste_svc = {
+        let ctrl: tauri::State<'_, SharedController> = app.state();
+        let c = ctrl.lock().unwrap();
+        // A draft long enough to need confirmation waits for the real text.
+        if c.settings().paste_needs_confirmation(&text) {
+            return None;
+        }
+        PasteService::for_settings(c.settings())
+    };
+    info!(
+        "Pasting {} draft ({} chars)",
+        draft_model.display_name(),
+        text.len()
+    );
+    let draft = text.clone();
+    // Pasting must run on the main thread (see `auto_paste_or_hold`).
+    if let Err(e) = app.run_on_main_thread(move || {
+        if let Err(e) = paste_svc.paste(&draft) {
+            error!("Draft paste failed: {e}");
+        }
+    }) {
+        error!("Failed to dispatch draft paste to main thread: {e}");
+        return None;
+    }
+    Some(Draft { text, replace })
+}
+
+/// Put the final `text` in place of a pasted draft, if `replace_draft` is on.
+/// Where it can't be replaced the draft stays; the final text is still in
+/// the history, the events and the output sinks.
+pub fn replace(app: &AppHandle, draft: Draft, text: &str) {
+    if !draft.replace {
+        return;
+    }
+    let paste_svc = {
+        let ctrl: tauri::State<'_, SharedController> = app.state();
+        let c = ctrl.lock().unwrap();
+        PasteService::for_settings(c.settings())
+    };
+    let text = text.to_string();
+    if let Err(e) =
+        app.run_on_main_thread(move || match paste_svc.replace_draft(&draft.text, &text) {
+            Ok(true) => info!("Draft replaced with the final text ({} chars)", text.len()),
+            Ok(false) => info!("Draft left in place — it could not be replaced"),
+            Err(e) => warn!("Draft replacement failed: {e}"),
+        })
+    {
+        error!("Failed to dispatch draft replacement to main thread: {e}");
+    }
+}
@@ -0,0 +1,357 @@
+//! Server-sent events for external tools.
+//!
+//! With `event_stream_port` set, the app serves `GET /events` on 127.0.0.1
+//! at that port as a `text/event-stream`. State changes, transcription
+//! results and errors go out as SSE events named like the Tauri events in
+//! [`crate::events`], with the same JSON payloads, so dashboards and scripts
+//! can follow dictations (`curl -N http://127.0.0.1:PORT/events`) instead of
+//! polling the CLI. `GET /events/schema` answers with the event schema
+//! (see [`crate::events::schema`]) for a tool to check its version first.
+//!
+//! The stream is read-only and bound to loopback. Every request must carry
+//! `Authorization: Bearer TOKEN`, with the random token the app writes to
+//! `event-stream-token` in its data directory (readable by the user only)
+//! the first time it's needed, so other accounts and sandboxed programs on
+//! the machine can't read transcripts:
+//! `curl -N -H "Authorization: Bearer $(cat .../event-stream-token)" ...`.
+//! Settings shows the token. Requests naming another host are refused too,
+//! and no CORS header is sent, so a web page can't read the stream.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use sagascript_core::settings::store;
+use tauri::{AppHandle, Listener};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::events::{self, event};
+
+const TOKEN_FILENAME: &str = "event-stream-token";
+
+/// Events forwarded to subscribers.
+const FORWARDED: [&str; 3] = [
+    event::STATE_CHANGED,
+    event::TRANSCRIPTION_RESULT,
+    event::ERROR,
+];
+
+/// How long a client may take to send its request headers.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A comment is sent after this long without events, so clients can tell a
+/// quiet stream from a dead connection.
+const KEEPALIVE: Duration = Duration::from_secs(15);
+
+const NOT_FOUND: &[u8] =
+    b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const FORBIDDEN: &[u8] =
+    b"HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const UNAUTHORIZED: &[u8] = b"HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Bearer\r\n\
+Content-Length: 0\r\nConnection: close\r\n\r\n";
+const STREAM_HEADERS: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+Cache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n";
+
+/// One channel per connected client; a closed one is dropped on the next
+/// event.
+type Subscribers = Arc<Mutex<Vec<Sender<String>>>>;
+
+/// Start serving the stream on `port`, unless it is 0. A port that can't be
+/// bound, or a token that can't be stored, is logged and the app carries on
+/// without the stream.
+pub fn start(app: &AppHandle, port: u16) {
+    if port == 0 {
+        return;
+    }
+    let token = match token() {
+        Ok(token) => Arc::new(token),
+        Err(e) => {
+            warn!("Event stream not started: no token: {e}");
+            return;
+        }
+    };
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            warn!("Event stream not started on port {port}: {e}");
+            return;
+        }
+    };
+
+    let subscribers: Subscribers = Arc::default();
+    for name in FORWARDED {
+        let subscribers = Arc::clone(&subscribers);
+        app.listen_any(name, move |e| {
+            let frame = sse_frame(name, e.payload());
+            subscribers
+                .lock()
+                .unwrap()
+                .retain(|tx| tx.send(frame.clone()).is_ok());
+        });
+    }
+
+    info!("Event stream at http://127.0.0.1:{port}/events");
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            let subscribers = Arc::clone(&subscribers);
+            let token = Arc::clone(&token);
+            thread::spawn(move || {
+                if let Err(e) = serve(stream, &subscribers, &token) {
+                    debug!("Event stream client left: {e}");
+                }
+            });
+        }
+    });
+}
+
+/// The token clients must send, created on first use.
+pub fn token() -> io::Result<String> {
+    load_or_create_token(&token_path())
+}
+
+fn token_path() -> PathBuf {
+    store::app_data_dir().join(TOKEN_FILENAME)
+}
+
+/// The token stored at `path`, or a new random one written there, readable
+/// by the user only.
+fn load_or_create_token(path: &Path) -> io::Result<String> {
+    match std::fs::read_to_string(path) {
+        Ok(token) if !token.trim().is_empty() => return Ok(token.trim().to_string()),
+        Ok(_) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    write_private(path, token.as_bytes())?;
+    Ok(token)
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, data: &[u8]) -> io::Result<()> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    // `mode` only applies to a new file; an emptied one keeps its old bits.
+    file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    file.write_all(data)
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, data: &[u8]) -> io::Result<()> {
+    std::fs::write(path, data)
+}
+
+/// What a request asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Route {
+    /// `GET /events`: the stream itself.
+    Stream,
+    /// `GET /events/schema`: event names, payload types and the version.
+    Schema,
+}
+
+/// Answer one connection: stream events to a `GET /events` from a local
+/// tool holding the token until the client goes away, or send the schema.
+fn serve(mut stream: TcpStream, subscribers: &Subscribers, token: &str) -> io::Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut host = None;
+    let mut authorization = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("host") {
+                host = Some(value.trim().to_string());
+            } else if name.trim().eq_ignore_ascii_case("authorization") {
+                authorization = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    let Some(route) = route(&request_line) else {
+        return stream.write_all(NOT_FOUND);
+    };
+    if !host.as_deref().is_some_and(is_loopback_host) {
+        return stream.write_all(FORBIDDEN);
+    }
+    if !authorization.is_some_and(|a| is_authorized(&a, token)) {
+        return stream.write_all(UNAUTHORIZED);
+    }
+    if route == Route::Schema {
+        return stream.write_all(schema_response().as_bytes());
+    }
+
+    let (tx, rx) = mpsc::channel();
+    subscribers.lock().unwrap().push(tx);
+    stream.write_all(STREAM_HEADERS)?;
+    stream.flush()?;
+    loop {
+        let frame = match rx.recv_timeout(KEEPALIVE) {
+            Ok(frame) => frame,
+            Err(RecvTimeoutError::Timeout) => ": keepalive\n\n".to_string(),
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        };
+        stream.write_all(frame.as_bytes())?;
+        stream.flush()?;
+    }
+}
+
+/// The route of a `GET /events` or `GET /events/schema` request line, with
+/// or without a query; `None` for anything else.
+fn route(request_line: &str) -> Option<Route> {
+    let mut parts = request_line.split_whitespace();
+    let (Some("GET"), Some(target)) = (parts.next(), parts.next()) else {
+        return None;
+    };
+    match target.split('?').next() {
+        Some("/events") => Some(Route::Stream),
+        Some("/events/schema") => Some(Route::Schema),
+        _ => None,
+    }
+}
+
+/// The schema as a complete JSON response.
+fn schema_response() -> String {
+    let body = serde_json::to_string(&events::schema()).unwrap_or_default();
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Whether a `Host` header names this machine. Anything else is a page on
+/// another site reaching the port through a rebound DNS name.
+fn is_loopback_host(host: &str) -> bool {
+    let name = match host.rsplit_once(':') {
+        Some((name, port)) if port.bytes().all(|b| b.is_ascii_digit()) => name,
+        _ => host,
+    };
+    matches!(name, "127.0.0.1" | "localhost" | "[::1]")
+}
+
+/// Whether an `Authorization` header carries `token` as a bearer token. The
+/// comparison takes as long for a near miss as for a wild guess.
+fn is_authorized(authorization: &str, token: &str) -> bool {
+    let Some((scheme, sent)) = authorization.split_once(' ') else {
+        return false;
+    };
+    let sent = sent.trim().as_bytes();
+    scheme.eq_ignore_ascii_case("bearer")
+        && !token.is_empty()
+        && sent.len() == token.len()
+        && sent
+            .iter()
+            .zip(token.as_bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// An SSE event named `name` carrying `data`, one `data:` line per line.
+fn sse_frame(name: &str, data: &str) -> String {
+    let mut frame = format!("event: {name}\n");
+    for line in data.lines() {
+        frame.push_str("data: ");
+        frame.push_str(line);
+        frame.push('\n');
+    }
+    frame.push('\n');
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_get_events_is_served() {
+        assert_eq!(route("GET /events HTTP/1.1\r\n"), Some(Route::Stream));
+        assert_eq!(
+            route("GET /events?since=now HTTP/1.1\r\n"),
+            Some(Route::Stream)
+        );
+        assert_eq!(
+            route("GET /events/schema HTTP/1.1\r\n"),
+            Some(Route::Schema)
+        );
+        assert_eq!(route("POST /events HTTP/1.1\r\n"), None);
+        assert_eq!(route("GET /events/x HTTP/1.1\r\n"), None);
+        assert_eq!(route(""), None);
+    }
+
+    #[test]
+    fn schema_response_carries_the_version() {
+        let response = schema_response();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.contains(&format!("Content-Length: {}", body.len())));
+        let json: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(json["version"], events::SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn only_loopback_hosts_are_accepted() {
+        assert!(is_loopback_host("127.0.0.1:7890"));
+        assert!(is_loopback_host("localhost"));
+        assert!(is_loopback_host("[::1]:7890"));
+        assert!(!is_loopback_host("attacker.example:7890"));
+    }
+
+    #[test]
+    fn only_the_bearer_token_is_accepted() {
+        assert!(is_authorized("Bearer s3cret", "s3cret"));
+        assert!(is_authorized("bearer s3cret", "s3cret"));
+        assert!(!is_authorized("Bearer s3cre", "s3cret"));
+        assert!(!is_authorized("Bearer s3creT", "s3cret"));
+        assert!(!is_authorized("Basic s3cret", "s3cret"));
+        assert!(!is_authorized("s3cret", "s3cret"));
+        assert!(!is_authorized("Bearer ", ""));
+    }
+
+    #[test]
+    fn the_token_is_created_once_and_private() {
+        let dir = std::env::temp_dir().join(format!("sagascript-events-{}", Uuid::new_v4()));
+        let path = dir.join(TOKEN_FILENAME);
+        let token = load_or_create_token(&path).unwrap();
+        assert_eq!(token.len(), 64);
+        assert_eq!(load_or_create_token(&path).unwrap(), token);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn frames_carry_the_event_name_and_payload() {
+        assert_eq!(
+            sse_frame("state-changed", "\"recording\""),
+            "event: state-changed\ndata: \"recording\"\n\n"
+        );
+        assert_eq!(
+            sse_frame("error", "a\nb"),
+            "event: error\ndata: a\ndata: b\n\n"
+        );
+    }
+}
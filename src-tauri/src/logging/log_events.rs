@@ -46,10 +46,11 @@ pub mod paste {
     pub const FAILED: &str = "paste_failed";
 }
 
+/// Shared with `sagascript_core::sessions`, which rebuilds the dictation
+/// timeline from these two events.
 pub mod session {
-    pub const DICTATION_STARTED: &str = "dictation_session_started";
-    #[allow(dead_code)]
-    pub const DICTATION_COMPLETE: &str = "dictation_session_complete";
+    pub const DICTATION_STARTED: &str = sagascript_core::sessions::STARTED_EVENT;
+    pub const DICTATION_COMPLETE: &str = sagascript_core::sessions::COMPLETE_EVENT;
     #[allow(dead_code)]
     pub const STATE_CHANGED: &str = "state_changed";
 }
@@ -9,7 +9,8 @@ use tracing::warn;
 use uuid::Uuid;
 
 const MAX_FILE_SIZE: u64 = 5_000_000; // 5MB
-const MAX_FILES: u32 = 5;
+// The session timeline reads back the same rotated set.
+const MAX_FILES: u32 = sagascript_core::sessions::MAX_ROTATED_FILES;
 
 /// Structured JSONL logging service matching the Swift app's format
 pub struct LoggingService {
@@ -36,7 +37,7 @@ pub struct LogEntry {
 impl LoggingService {
     pub fn new() -> Self {
         let log_dir = Self::log_directory();
-        let log_path = log_dir.join("sagascript.log");
+        let log_path = log_dir.join(sagascript_core::sessions::LOG_FILE_NAME);
         Self::new_with_path(log_path)
     }
 
@@ -82,26 +83,7 @@ impl LoggingService {
     }
 
     fn log_directory() -> PathBuf {
-        #[cfg(target_os = "macos")]
-        {
-            dirs::home_dir()
-                .unwrap_or_else(|| PathBuf::from("."))
-                .join("Library/Logs/Sagascript")
-        }
-
-        #[cfg(target_os = "windows")]
-        {
-            dirs::data_local_dir()
-                .unwrap_or_else(|| PathBuf::from("."))
-                .join("Sagascript/Logs")
-        }
-
-        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-        {
-            dirs::data_dir()
-                .unwrap_or_else(|| PathBuf::from("."))
-                .join("Sagascript/logs")
-        }
+        sagascript_core::sessions::log_directory()
     }
 
     /// Start a new dictation session, returns session ID
@@ -0,0 +1,199 @@
+//! Background transcription of long hotkey dictations.
+//!
+//! Once a push-to-talk/toggle recording runs past [`CHUNK_AFTER`], finished
+//! stretches of audio are transcribed while the user keeps talking. On stop
+//! only the untranscribed tail is left for the final pass, so stop-to-paste
+//! latency stays roughly constant instead of growing with dictation length.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use sagascript_core::audio::resample::TARGET_SAMPLE_RATE;
+use sagascript_core::settings::BackendKind;
+use sagascript_core::transcription::JobAbort;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+use crate::commands::{self, SharedController, SharedWhisper};
+
+/// Recordings shorter than this are transcribed in a single pass on stop.
+const CHUNK_AFTER: Duration = Duration::from_secs(60);
+
+/// Minimum new audio per background chunk. whisper.cpp decodes in 30-second
+/// windows, so smaller chunks would only add per-call overhead.
+const CHUNK_SECS: f32 = 30.0;
+
+/// How far back from the live edge to look for a pause to split at.
+const SPLIT_SEARCH_SECS: f32 = 3.0;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Text transcribed in the background for one recording.
+#[derive(Debug)]
+pub struct ChunkProgress {
+    /// Recording this progress belongs to (see `recording_started_at`).
+    started: Instant,
+    /// Device-rate offset up to which audio has been transcribed.
+    pub consumed: usize,
+    pub texts: Vec<String>,
+    closed: bool,
+}
+
+pub type SharedLongDictation = Mutex<Option<Arc<tokio::sync::Mutex<ChunkProgress>>>>;
+
+/// Start the background chunker for the recording that just began.
+pub fn begin(app: &AppHandle) {
+    let (started, remote) = {
+        let ctrl: tauri::State<'_, SharedController> = app.state();
+        let c = ctrl.lock().unwrap();
+        (
+            c.recording_started_at(),
+            c.settings().transcription_backend != BackendKind::Local,
+        )
+    };
+    // A remote backend gets the whole recording on stop; chunks would be
+    // transcribed locally.
+    let Some(started) = started.filter(|_| !remote) else { return };
+
+    let progress = Arc::new(tokio::sync::Mutex::new(ChunkProgress {
+        started,
+        consumed: 0,
+        texts: Vec::new(),
+        closed: false,
+    }));
+    {
+        let slot: tauri::State<'_, SharedLongDictation> = app.state();
+        *slot.lock().unwrap() = Some(Arc::clone(&progress));
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        // Audio covered by the chunks so far, for the live pace.
+        let mut transcribed = Duration::ZERO;
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            // Held across the chunk's inference so `finish` waits for its text
+            // instead of re-transcribing the same audio in the final pass.
+            let mut p = progress.lock().await;
+            if p.closed {
+                break;
+            }
+
+            let (chunk, language, model, opts) = {
+                let ctrl: tauri::State<'_, SharedController> = app.state();
+                let c = ctrl.lock().unwrap();
+                if c.recording_started_at() != Some(started) {
+                    break;
+                }
+                if started.elapsed() < CHUNK_AFTER {
+                    continue;
+                }
+                let chunk = match c.recording_chunk_since(p.consumed, CHUNK_SECS, SPLIT_SEARCH_SECS)
+                {
+                    Ok(Some(chunk)) => chunk,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        warn!("Could not take background chunk: {e}");
+                        break;
+                    }
+                };
+                // Pick the model for the whole recording so far, not the chunk,
+                // so adaptive selection doesn't swap models mid-dictation.
+                let recorded = started.elapsed().as_secs() as usize * TARGET_SAMPLE_RATE as usize;
+                let settings = c.dictation_settings();
+                (
+                    chunk,
+                    settings.language,
+                    commands::model_for_clip(&settings, recorded),
+                    commands::build_transcribe_options(&settings),
+                )
+            };
+
+            let (samples, end) = chunk;
+            let chunk_secs = samples.len() as f32 / TARGET_SAMPLE_RATE as f32;
+            let whisper = app.state::<SharedWhisper>().inner().clone();
+            let result = tokio::task::spawn_blocking(move || {
+                whisper.ensure_model(model)?;
+                // A job state of its own, so a chunk never holds the warm state
+                // a concurrent GUI or file transcription needs.
+                whisper
+                    .transcribe_job(&samples, language, &opts, |_| {}, &JobAbort::new())
+                    .map(|t| t.text)
+            })
+            .await;
+
+            match result {
+                Ok(Ok(text)) => {
+                    info!("Background chunk transcribed: {chunk_secs:.1}s, {} chars", text.len());
+                    p.consumed = end;
+                    p.texts.push(text);
+                    transcribed += Duration::from_secs_f32(chunk_secs);
+                    commands::emit_pace(&app, &p.texts.join(" "), transcribed, true);
+                }
+                // Anything not transcribed here is picked up by the final pass.
+                Ok(Err(e)) => {
+                    warn!("Background chunk transcription failed: {e}");
+                    break;
+                }
+                Err(e) => {
+                    warn!("Background chunk task failed: {e}");
+                    break;
+                }
+            }
+        }
+    });
+}
+
+/// Stop the chunker for the recording that started at `started` and return
+/// what it transcribed, or `None` if nothing was chunked.
+///
+/// Waits for a chunk that is mid-inference, so its text is kept rather than
+/// that audio being transcribed again by the final pass.
+pub async fn finish(app: &AppHandle, started: Option<Instant>) -> Option<ChunkProgress> {
+    let progress = {
+        let slot: tauri::State<'_, SharedLongDictation> = app.state();
+        let taken = slot.lock().unwrap().take();
+        taken
+    }?;
+    let mut p = progress.lock().await;
+    p.closed = true;
+    if Some(p.started) != started || p.texts.is_empty() {
+        return None;
+    }
+    Some(ChunkProgress {
+        started: p.started,
+        consumed: p.consumed,
+        texts: std::mem::take(&mut p.texts),
+        closed: true,
+    })
+}
+
+/// Join background chunk texts with the final tail's text.
+pub fn join_texts(chunks: Vec<String>, tail: &str) -> String {
+    chunks
+        .iter()
+        .map(|t| t.trim())
+        .chain(std::iter::once(tail.trim()))
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn join_texts_separates_chunks_with_spaces() {
+        let chunks = vec!["First part.".to_string(), " second part ".to_string()];
+        assert_eq!(join_texts(chunks, "and the end."), "First part. second part and the end.");
+    }
+
+    #[test]
+    fn join_texts_skips_empty_pieces() {
+        let chunks = vec!["Only chunk.".to_string(), String::new()];
+        assert_eq!(join_texts(chunks, "  "), "Only chunk.");
+        assert_eq!(join_texts(Vec::new(), "tail"), "tail");
+    }
+}
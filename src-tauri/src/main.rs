@@ -14,13 +14,21 @@ extern crate objc;
 // tracing_subscriber to stderr).
 mod logging;
 
+mod accumulate;
 mod app_controller;
 mod commands;
-mod events;
+mod draft_pass;
+mod event_stream;
 mod hotkey;
+mod long_dictation;
 mod overlay;
 mod paste;
 mod platform;
+mod profile_schedule;
+mod recording_limit;
+mod recovery;
+mod silence_stop;
+mod tray;
 
 use tracing_subscriber::EnvFilter;
 
@@ -35,20 +43,40 @@ const TRANSCRIPTION_TIMEOUT_SECS: u64 = 60;
 const ABORT_GRACE_SECS: u64 = 5;
 
 use tauri::{
-    menu::{Menu, MenuItem},
+    menu::MenuItem,
     tray::TrayIconBuilder,
     Emitter, Manager,
 };
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 use tracing::{error, info, warn};
 
 use app_controller::{AppController, HotkeyDownResult, StopRecordingOutcome};
-use commands::{SharedController, SharedWhisper};
-use sagascript_core::transcription::WhisperBackend;
+use commands::{
+    SharedController, SharedMinutes, SharedRequestedFile, SharedRtfTracker, SharedWhisper,
+};
+use long_dictation::SharedLongDictation;
+use recovery::SharedLiveSnapshot;
+use sagascript_core::audio::decoder;
+use sagascript_core::audio::resample::TARGET_SAMPLE_RATE;
+use sagascript_core::events;
+use sagascript_core::llm;
+use sagascript_core::settings::{CaptureSource, HotkeyMode, Profile, SettingsTab};
+use sagascript_core::transcription::timing::{self, Timing};
+use sagascript_core::transcription::{backend, RtfTracker, Transcript, WhisperBackend};
 
 /// Minimum recording duration before we allow stop (300ms)
 const MIN_RECORDING_MS: u64 = 300;
 
+/// How long the overlay flashes when the hotkey is pressed mid-transcription.
+const BUSY_FLASH: Duration = Duration::from_millis(1200);
+
+/// How long the overlay shows the no-speech hint after a silent dictation.
+const NO_SPEECH_FLASH: Duration = Duration::from_millis(2500);
+
+/// The opt-in model update check runs this long after launch, then daily.
+const MODEL_UPDATE_FIRST_CHECK: Duration = Duration::from_secs(60);
+const MODEL_UPDATE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
 /// Shared tray status menu item for updating from anywhere
 type SharedStatusItem = Mutex<Option<MenuItem<tauri::Wry>>>;
 
@@ -66,15 +94,15 @@ fn load_settings_with_permission_gate() -> sagascript_core::settings::Settings {
     {
         let mut settings = settings;
         if !auto_paste_permitted(
-            settings.auto_paste,
+            settings.requests_paste(),
             crate::platform::macos::is_accessibility_trusted(),
         ) {
-            warn!("Auto-paste was enabled without Accessibility permission; disabling it");
-            match sagascript_core::settings::store::update(|latest| latest.auto_paste = false) {
+            warn!("A paste sink was set up without Accessibility permission; removing it");
+            match sagascript_core::settings::store::update(|latest| latest.revoke_paste()) {
                 Ok(persisted) => settings = persisted,
                 Err(error) => {
                     error!("Failed to persist permission-gated auto-paste setting: {error}");
-                    settings.auto_paste = false;
+                    settings.revoke_paste();
                 }
             }
         }
@@ -88,8 +116,15 @@ fn load_settings_with_permission_gate() -> sagascript_core::settings::Settings {
 fn main() {
     // CLI mode: if a subcommand is given, run CLI and exit. The desktop
     // binary is a full CLI (CLI-first design) — the GUI only launches on a
-    // bare invocation.
-    if let Some(parsed) = sagascript_cli::try_parse() {
+    // bare invocation, or with a single audio file (Explorer/Linux file
+    // managers launch "Open with" that way).
+    let opened_file = opened_file_argument(std::env::args_os().skip(1).collect());
+    let cli = if opened_file.is_none() {
+        sagascript_cli::try_parse()
+    } else {
+        None
+    };
+    if let Some(parsed) = cli {
         // CLI mode uses warn-level logging to keep stdout clean
         tracing_subscriber::fmt()
             .with_env_filter(
@@ -113,8 +148,18 @@ fn main() {
     let settings = load_settings_with_permission_gate();
     info!("Loaded settings: language={:?}, model={:?}, hotkey={}", settings.language, settings.whisper_model, settings.hotkey);
     let initial_hotkey = settings.hotkey.clone();
-    let controller = Mutex::new(AppController::new(settings));
-    let whisper: SharedWhisper = Arc::new(WhisperBackend::new());
+    sagascript_core::telemetry::set_enabled(settings.telemetry);
+    let tray_status_text = tray::StatusTextEnabled::new(settings.tray_status_text);
+    let overlay_accessibility = overlay::Accessibility::new(&settings);
+    let mut controller = AppController::new(settings);
+    controller.set_history_path(sagascript_core::history::history_path());
+    if let Err(e) = controller.refresh_standby() {
+        warn!("Standby input stream unavailable: {e}");
+    }
+    let whisper: SharedWhisper = Arc::new(WhisperBackend::with_gpu(controller.settings().use_gpu));
+    whisper.set_custom_model(controller.settings().custom_model_path());
+    let draft_whisper = draft_pass::DraftWhisper::new(controller.settings().use_gpu);
+    let controller = Mutex::new(controller);
     // Process-wide hotkey registration health (see hotkey::health for why this
     // is deliberately independent of the AppController mutex). Assumed healthy
     // until the first real registration attempt in `.setup()` below proves
@@ -128,12 +173,43 @@ fn main() {
                 .with_handler(move |app, shortcut, event| {
                     let ctrl: tauri::State<'_, SharedController> = app.state();
 
+                    // The accumulate-mode flush shortcut shares this handler
+                    // with the dictation hotkey.
+                    if is_flush_shortcut(&ctrl, shortcut) {
+                        if matches!(event.state, ShortcutState::Pressed) {
+                            info!("Flush hotkey pressed: {shortcut}");
+                            commands::flush_accumulated_text(app);
+                        }
+                        return;
+                    }
+                    if let Some(tab) = settings_shortcut_tab(&ctrl, shortcut) {
+                        if matches!(event.state, ShortcutState::Pressed) {
+                            info!("Settings hotkey pressed: {shortcut}");
+                            open_settings_window(app, Some(tab.as_str()));
+                        }
+                        return;
+                    }
+
+                    // The toggle hotkey starts and stops dictation on press
+                    // whatever `hotkey_mode` says, and ignores release.
+                    // Profile hotkeys behave like the main one.
+                    let profile = profile_for_shortcut(&ctrl, shortcut);
+                    let toggle = profile.is_none() && is_toggle_shortcut(&ctrl, shortcut);
                     match event.state {
                         ShortcutState::Pressed => {
                             info!("Hotkey pressed: {shortcut}");
                             let result = {
                                 let mut c = ctrl.lock().unwrap();
-                                match c.handle_hotkey_down() {
+                                let mode = if toggle {
+                                    HotkeyMode::Toggle
+                                } else {
+                                    c.settings().hotkey_mode
+                                };
+                                let result = match profile {
+                                    Some(profile) => c.handle_profile_hotkey_down(profile),
+                                    None => c.handle_hotkey_down_as(mode),
+                                };
+                                match result {
                                     Ok(r) => r,
                                     Err(e) => {
                                         error!("Hotkey down error: {e}");
@@ -148,17 +224,31 @@ fn main() {
                                         c.settings().show_overlay
                                     };
                                     let _ = app.emit(events::event::STATE_CHANGED, "recording");
+                                    announce_profile(app, &ctrl);
                                     update_tray_status(app, "recording");
                                     if show_overlay {
                                         overlay::show(app);
                                     }
+                                    long_dictation::begin(app);
+                                    recording_limit::begin(app);
+                                    recovery::begin(app);
+                                    silence_stop::begin(app);
                                 }
                                 HotkeyDownResult::StopRecording => {
                                     stop_recording_and_transcribe(app, &ctrl);
                                 }
+                                HotkeyDownResult::CancelAndRestart => {
+                                    let whisper: tauri::State<'_, SharedWhisper> = app.state();
+                                    whisper.request_abort();
+                                    show_busy_feedback(app, &ctrl);
+                                }
+                                HotkeyDownResult::Busy | HotkeyDownResult::Queued => {
+                                    show_busy_feedback(app, &ctrl);
+                                }
                                 HotkeyDownResult::NoOp => {}
                             }
                         }
+                        ShortcutState::Released if toggle => {}
                         ShortcutState::Released => {
                             info!("Hotkey released: {shortcut}");
                             handle_hotkey_release(app, &ctrl);
@@ -174,8 +264,16 @@ fn main() {
         .plugin(tauri_plugin_dialog::init())
         .manage(controller)
         .manage(whisper)
+        .manage(draft_whisper)
         .manage(hotkey_health)
+        .manage(Mutex::new(RtfTracker::new()) as SharedRtfTracker)
+        .manage(Mutex::new(None) as SharedLongDictation)
+        .manage(Mutex::new(None) as SharedLiveSnapshot)
         .manage(Mutex::new(None::<MenuItem<tauri::Wry>>) as SharedStatusItem)
+        .manage(tray_status_text)
+        .manage(overlay_accessibility)
+        .manage(Mutex::new(None) as SharedRequestedFile)
+        .manage(Mutex::new(None) as SharedMinutes)
         .setup(|app| {
             // Hide from dock on macOS (tray-only app)
             #[cfg(target_os = "macos")]
@@ -221,26 +319,57 @@ fn main() {
                 }
             }
 
-            // Build tray menu
-            let quit = MenuItem::with_id(app, "quit", "Quit Sagascript", true, None::<&str>)?;
-            let settings_item =
-                MenuItem::with_id(app, "settings", "Open Sagascript...", true, None::<&str>)?;
-            let transcribe_file_item =
-                MenuItem::with_id(app, "transcribe_file", "Transcribe File...", true, None::<&str>)?;
-            let status =
-                MenuItem::with_id(app, "status", "Sagascript - Idle", false, None::<&str>)?;
+            // Accumulate-mode flush shortcut. Failure only disables the
+            // hotkey flush; the flush phrase and Settings button still work.
+            {
+                let ctrl: tauri::State<'_, SharedController> = app.state();
+                let flush = commands::flush_shortcut(ctrl.lock().unwrap().settings()).map(str::to_string);
+                if let Err(e) = commands::swap_flush_hotkey(app.handle(), None, flush.as_deref()) {
+                    error!("{e}");
+                }
+            }
 
-            // Store status item so we can update it after transcription
+            // Second dictation hotkey that always toggles, if the user set one.
             {
-                let status_state: tauri::State<'_, SharedStatusItem> = app.state();
-                *status_state.lock().unwrap() = Some(status.clone());
+                let ctrl: tauri::State<'_, SharedController> = app.state();
+                let toggle = commands::toggle_shortcut(ctrl.lock().unwrap().settings()).map(str::to_string);
+                if let Err(e) = commands::swap_toggle_hotkey(app.handle(), None, toggle.as_deref()) {
+                    error!("{e}");
+                }
             }
 
-            let menu = Menu::with_items(app, &[&status, &settings_item, &transcribe_file_item, &quit])?;
+            // Dictation profiles' own hotkeys.
+            {
+                let ctrl: tauri::State<'_, SharedController> = app.state();
+                let settings = ctrl.lock().unwrap().settings().clone();
+                let hotkeys = commands::profile_shortcuts(&settings);
+                if let Err(e) = commands::swap_profile_hotkeys(app.handle(), &[], &hotkeys) {
+                    error!("{e}");
+                }
+            }
+
+            // Keyboard shortcut to the main window, if the user set one.
+            {
+                let ctrl: tauri::State<'_, SharedController> = app.state();
+                let hotkey = commands::settings_shortcut(ctrl.lock().unwrap().settings()).map(str::to_string);
+                if let Err(e) = commands::swap_settings_hotkey(app.handle(), None, hotkey.as_deref()) {
+                    error!("{e}");
+                }
+            }
+
+            // Build tray menu from the user's tray settings (the status item
+            // is stored by build_menu so it can be updated after transcription)
+            let tray_settings = {
+                let ctrl: tauri::State<'_, SharedController> = app.state();
+                let c = ctrl.lock().unwrap();
+                c.settings().clone()
+            };
+            let tray_visible = tray_settings.tray_visible;
+            let menu = tray::build_menu(app.handle(), &tray_settings)?;
 
             let tray_icon = tauri::image::Image::from_bytes(include_bytes!("../icons/tray-icon.png"))?;
 
-            let _tray = TrayIconBuilder::with_id("main")
+            let tray = TrayIconBuilder::with_id(tray::TRAY_ID)
                 .menu(&menu)
                 .tooltip("Sagascript")
                 .icon(tray_icon)
@@ -256,11 +385,27 @@ fn main() {
                     "transcribe_file" => {
                         open_settings_window(app, Some("transcribe"));
                     }
+                    "capture_source" => {
+                        let source = {
+                            let ctrl: tauri::State<'_, SharedController> = app.state();
+                            match ctrl.lock().unwrap().settings().capture_source {
+                                CaptureSource::Microphone => CaptureSource::System,
+                                CaptureSource::System => CaptureSource::Microphone,
+                            }
+                        };
+                        if let Err(e) = commands::apply_capture_source(app, source) {
+                            error!("Failed to switch capture source: {e}");
+                        }
+                    }
+                    "install_cli" => tray::install_cli(app),
                     _ => {}
                 })
                 .build(app)?;
+            if !tray_visible {
+                tray.set_visible(false)?;
+            }
 
-            info!("Tray icon created");
+            info!("Tray icon created (visible: {tray_visible})");
 
             // Render the initial tray state through the same path as later
             // updates — the hotkey-health flag was recorded above, before the
@@ -281,6 +426,28 @@ fn main() {
             // Watch settings file for external changes (e.g. `sagascript config set`)
             start_settings_watcher(app.handle().clone());
 
+            // Server-sent events for external tools, if the user turned them on.
+            {
+                let ctrl: tauri::State<'_, SharedController> = app.state();
+                let port = ctrl.lock().unwrap().settings().event_stream_port;
+                event_stream::start(app.handle(), port);
+            }
+
+            // A settings file that doesn't parse was loaded as defaults; say
+            // why. A window opened later asks with get_settings_load_warning.
+            if let Err(issue) = sagascript_core::settings::store::validate(
+                &sagascript_core::settings::store::settings_path(),
+            ) {
+                warn!("Settings file could not be loaded, using defaults: {issue}");
+                let _ = app.emit(events::event::SETTINGS_LOAD_WARNING, Some(&issue));
+            }
+
+            // Opt-in daily check for newer upstream model revisions
+            start_model_update_checks(app.handle().clone());
+
+            // Profiles with a schedule take over the language and model
+            profile_schedule::start(app.handle().clone());
+
             // Auto-open onboarding on first launch
             {
                 let settings = sagascript_core::settings::store::load();
@@ -290,33 +457,61 @@ fn main() {
                 }
             }
 
+            // The last run crashed mid-dictation: offer its audio back.
+            if recovery::take_over().is_some() {
+                open_settings_window(app.handle(), Some("dictate"));
+            }
+
+            // Launched to open a file (see opened_file_argument).
+            if let Some(path) = opened_file.clone() {
+                request_file_transcription(app.handle(), vec![path]);
+            }
+
+            // Finder's "Transcribe with Sagascript" service (NSServices in
+            // Info.plist). Finder calls it on the main thread.
+            #[cfg(target_os = "macos")]
+            {
+                let handle = app.handle().clone();
+                platform::macos::register_services_provider(move |paths| {
+                    request_file_transcription(&handle, paths);
+                });
+            }
+
             // Preload + warm the whisper model in the background so the first
             // dictation of the session doesn't pay model-load and Metal/CoreML
-            // kernel-compile latency. Best-effort: if the model isn't downloaded
-            // yet (fresh install) we just skip and load lazily on first use.
+            // kernel-compile latency (`preload_model`). Best-effort: if the model
+            // isn't downloaded yet (fresh install) we just skip and load lazily
+            // on first use.
             {
                 let whisper: tauri::State<'_, SharedWhisper> = app.state();
                 let whisper = whisper.inner().clone();
-                let (model, language, vad_enabled) = {
+                let (model, language, vad_enabled, preload) = {
                     let ctrl: tauri::State<'_, SharedController> = app.state();
                     let c = ctrl.lock().unwrap();
+                    // Dictation clips are short, so with adaptive model
+                    // selection on this warms the fast model.
                     (
-                        c.settings().effective_model(),
+                        commands::model_for_clip(c.settings(), 0),
                         c.language(),
                         c.settings().vad_enabled,
+                        c.settings().preload_model,
                     )
                 };
-                std::thread::spawn(move || {
-                    if let Err(e) = whisper.ensure_model(model) {
-                        warn!("Model preload skipped: {e}");
-                        return;
-                    }
-                    if let Err(e) = whisper.warmup(language) {
-                        warn!("Model warmup failed: {e}");
-                    } else {
-                        info!("Model preloaded and warmed: {}", model.display_name());
-                    }
-                });
+                if preload {
+                    std::thread::spawn(move || {
+                        if let Err(e) = whisper.ensure_model(model) {
+                            warn!("Model preload skipped: {e}");
+                            return;
+                        }
+                        if let Err(e) = whisper.warmup(language) {
+                            warn!("Model warmup failed: {e}");
+                        } else {
+                            info!("Model preloaded and warmed: {}", model.display_name());
+                        }
+                    });
+                } else {
+                    info!("Model preload off; loading on first dictation");
+                }
 
                 // Startup is verification-only: model repair/download remains
                 // tied to an explicit GUI enable action or CLI transcription.
@@ -355,6 +550,7 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             commands::get_state,
             commands::get_settings,
+            commands::get_settings_load_warning,
             commands::get_last_transcription,
             commands::get_last_error,
             commands::is_model_ready,
@@ -362,6 +558,48 @@ fn main() {
             commands::set_language,
             commands::set_whisper_model,
             commands::set_auto_select_model,
+            commands::get_model_suggestion,
+            commands::apply_model_suggestion,
+            commands::dismiss_model_suggestion,
+            commands::get_model_updates,
+            commands::get_session_timeline,
+            commands::get_history,
+            commands::delete_history_entry,
+            commands::clear_history,
+            commands::set_monitor_input,
+            commands::set_max_recording_minutes,
+            commands::set_silence_stop_secs,
+            commands::set_paste_confirm_chars,
+            commands::set_capture_source,
+            commands::set_standby_stream,
+            commands::set_split_channels,
+            commands::export_minutes,
+            commands::get_pending_paste,
+            commands::confirm_paste,
+            commands::discard_paste,
+            commands::set_ffmpeg_fallback,
+            commands::set_localize_numbers,
+            commands::set_voice_commands,
+            commands::set_punctuate,
+            commands::add_replacement_rule,
+            commands::remove_replacement_rule,
+            commands::set_paste_formatting,
+            commands::set_draft_pass,
+            commands::set_replace_draft,
+            commands::set_dedupe_dictations,
+            commands::set_dry_run,
+            commands::set_paste_app_rule,
+            commands::set_tray_visibility,
+            commands::set_tray_status_text,
+            commands::set_tray_menu,
+            commands::install_cli,
+            commands::set_telemetry,
+            commands::get_telemetry_queue,
+            commands::clear_telemetry_queue,
+            commands::get_event_stream_token,
+            commands::get_ffmpeg_path,
+            commands::set_while_busy,
+            commands::set_model_update_check,
             commands::set_hotkey_mode,
             commands::set_hotkey,
             commands::hotkey_status,
@@ -371,15 +609,56 @@ fn main() {
             commands::is_model_downloaded,
             commands::get_model_info,
             commands::download_model,
+            commands::delete_model,
+            commands::get_recommended_model,
+            commands::setup_recommended,
             commands::set_auto_paste,
             commands::set_show_overlay,
+            commands::set_overlay_accessibility,
             commands::set_initial_prompt,
+            commands::set_keywords,
+            commands::get_prompt_history,
             commands::set_beam_size,
             commands::set_temperature_fallback,
             commands::set_vad_enabled,
+            commands::set_adaptive_model,
+            commands::set_adaptive_clip_thresholds,
+            commands::set_accumulate_mode,
+            commands::set_flush_options,
+            commands::set_settings_hotkey,
+            commands::set_toggle_hotkey,
+            commands::set_profiles,
+            commands::open_settings_tab,
+            commands::close_settings,
+            commands::get_accumulated,
+            commands::flush_accumulated,
+            commands::clear_accumulated,
+            commands::set_output_sinks,
+            commands::set_mqtt_password,
+            commands::set_post_process,
+            commands::set_post_process_api_key,
+            commands::set_transcription_backend,
+            commands::set_azure_region,
+            commands::set_network_proxy,
+            commands::set_model_mirror,
+            commands::set_api_key,
+            commands::has_api_key,
+            commands::clear_api_key,
             commands::get_build_info,
+            commands::get_event_schema,
+            commands::get_whisper_runtime_info,
+            commands::set_use_gpu,
+            commands::set_preload_model,
+            commands::add_custom_model,
+            commands::remove_custom_model,
+            commands::set_custom_model,
+            commands::get_acceleration_info,
             commands::transcribe_file,
             commands::get_supported_formats,
+            commands::take_requested_file,
+            commands::get_recoverable_session,
+            commands::retry_recoverable_session,
+            commands::discard_recoverable_session,
             commands::check_accessibility_permission,
             commands::request_accessibility_permission,
             commands::microphone_status,
@@ -390,14 +669,28 @@ fn main() {
         ])
         .build(tauri::generate_context!())
         .expect("error while building Sagascript")
-        .run(|_app_handle, event| {
+        .run(|app_handle, event| match event {
             // Prevent app from exiting when all windows are closed (tray-only app),
             // but allow explicit exit requests (e.g. from tray "Quit" menu)
-            if let tauri::RunEvent::ExitRequested { api, code, .. } = event {
+            tauri::RunEvent::ExitRequested { api, code, .. } => {
                 if code.is_none() {
                     api.prevent_exit();
                 }
             }
+            // Finder "Open With" or a file dropped on the app icon. macOS
+            // delivers these as events rather than launch arguments.
+            #[cfg(target_os = "macos")]
+            tauri::RunEvent::Opened { urls } => {
+                let paths = urls.iter().filter_map(|u| u.to_file_path().ok()).collect();
+                request_file_transcription(app_handle, paths);
+            }
+            // Launching the app again while it runs. With the tray icon
+            // hidden this is the way back into Settings.
+            #[cfg(target_os = "macos")]
+            tauri::RunEvent::Reopen { .. } => {
+                open_settings_window(app_handle, None);
+            }
+            _ => {}
         });
 }
 
@@ -432,13 +725,17 @@ fn tray_label(state: &str, hotkey_failed: bool) -> (&'static str, &'static str,
 fn update_tray_status(app: &tauri::AppHandle, state: &str) {
     let hotkey_failed = app.state::<hotkey::HotkeyHealth>().is_failed();
     let (tooltip, title, menu_text) = tray_label(state, hotkey_failed);
+    // The hotkey warning is shown even with the status text turned off.
+    let show_title = hotkey_failed || app.state::<tray::StatusTextEnabled>().get();
 
-    if let Some(tray) = app.tray_by_id("main") {
+    if let Some(tray) = app.tray_by_id(tray::TRAY_ID) {
         let _ = tray.set_tooltip(Some(tooltip));
-        let _ = tray.set_title(Some(title));
+        let _ = tray.set_title(Some(if show_title { title } else { "" }));
     }
 
     set_status_menu_text(app, &format!("Sagascript - {menu_text}"));
+    overlay::announce_state(app, state);
+    commands::publish_state(app, state);
 }
 
 /// Migrate the legacy FlowDictate settings file to the new Sagascript path, if
@@ -494,10 +791,13 @@ fn update_tray_last_result(app: &tauri::AppHandle, text: &str) {
         update_tray_status(app, "idle");
         return;
     }
+    if !app.state::<tray::StatusTextEnabled>().get() {
+        return;
+    }
 
     let display = truncate_for_tray(text);
 
-    if let Some(tray) = app.tray_by_id("main") {
+    if let Some(tray) = app.tray_by_id(tray::TRAY_ID) {
         let _ = tray.set_tooltip(Some(&format!("Sagascript\nLast: {display}")));
     }
 
@@ -526,7 +826,7 @@ fn open_settings_window(app: &tauri::AppHandle, tab: Option<&str>) {
     if let Some(window) = app.get_webview_window("settings") {
         // If switching tab on existing window, emit an event
         if let Some(t) = tab {
-            let _ = window.emit("navigate_tab", t);
+            let _ = window.emit(events::event::NAVIGATE_TAB, t);
         }
         let _ = window.show();
         let _ = window.set_focus();
@@ -554,13 +854,108 @@ fn open_settings_window(app: &tauri::AppHandle, tab: Option<&str>) {
     }
 }
 
+/// Hide the main window, as its close button does.
+fn close_settings_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("settings") {
+        let _ = window.hide();
+        info!("Window hidden (not closed)");
+    }
+}
+
+/// Hand a file from outside the app to the Transcribe tab, which runs it
+/// through `transcribe_file` like a picked or dropped file. Only the first
+/// path is used, as with drag and drop.
+fn request_file_transcription(app: &tauri::AppHandle, paths: Vec<std::path::PathBuf>) {
+    let Some(path) = paths.into_iter().next() else {
+        return;
+    };
+    info!("File requested for transcription: {}", path.display());
+    *app.state::<SharedRequestedFile>().lock().unwrap() = Some(path);
+    open_settings_window(app, Some("transcribe"));
+    // An open window takes the file now; a new one takes it once loaded.
+    let _ = app.emit(events::event::TRANSCRIBE_FILE_REQUESTED, ());
+}
+
+/// The audio file the app was launched to open, if its only argument is one.
+/// Anything else falls through to CLI parsing, so `sagascript transcribe x.wav`
+/// and friends are unaffected.
+fn opened_file_argument(args: Vec<std::ffi::OsString>) -> Option<std::path::PathBuf> {
+    let [arg] = <[_; 1]>::try_from(args).ok()?;
+    let path = std::path::PathBuf::from(arg);
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    (decoder::SUPPORTED_EXTENSIONS.contains(&ext.as_str()) && path.is_file()).then_some(path)
+}
+
+/// Whether `shortcut` is the accumulate-mode flush hotkey (and the mode is on).
+fn is_flush_shortcut(ctrl: &tauri::State<'_, SharedController>, shortcut: &Shortcut) -> bool {
+    let c = ctrl.lock().unwrap();
+    commands::flush_shortcut(c.settings())
+        .and_then(|flush| flush.parse::<Shortcut>().ok())
+        .is_some_and(|flush| flush.id() == shortcut.id())
+}
+
+/// Whether `shortcut` is the always-toggle dictation hotkey.
+fn is_toggle_shortcut(ctrl: &tauri::State<'_, SharedController>, shortcut: &Shortcut) -> bool {
+    let c = ctrl.lock().unwrap();
+    commands::toggle_shortcut(c.settings())
+        .and_then(|toggle| toggle.parse::<Shortcut>().ok())
+        .is_some_and(|toggle| toggle.id() == shortcut.id())
+}
+
+/// The dictation profile whose hotkey `shortcut` is, if any.
+fn profile_for_shortcut(
+    ctrl: &tauri::State<'_, SharedController>,
+    shortcut: &Shortcut,
+) -> Option<Profile> {
+    let c = ctrl.lock().unwrap();
+    c.settings()
+        .profiles
+        .iter()
+        .find(|p| {
+            p.shortcut()
+                .and_then(|hotkey| hotkey.parse::<Shortcut>().ok())
+                .is_some_and(|hotkey| hotkey.id() == shortcut.id())
+        })
+        .cloned()
+}
+
+/// Tell the overlay which profile (if any) the new recording uses, and play
+/// its sound cue.
+fn announce_profile(app: &tauri::AppHandle, ctrl: &tauri::State<'_, SharedController>) {
+    let profile = ctrl.lock().unwrap().profile().cloned();
+    let payload = profile
+        .as_ref()
+        .map(|p| serde_json::json!({ "name": p.name, "overlayColor": p.overlay_color }));
+    let _ = app.emit(events::event::DICTATION_PROFILE, payload);
+    if let Some(profile) = profile {
+        sagascript_core::audio::cue::play(profile.sound_cue);
+    }
+}
+
+/// The tab to open if `shortcut` is the settings-window hotkey.
+fn settings_shortcut_tab(
+    ctrl: &tauri::State<'_, SharedController>,
+    shortcut: &Shortcut,
+) -> Option<SettingsTab> {
+    let c = ctrl.lock().unwrap();
+    commands::settings_shortcut(c.settings())
+        .and_then(|hotkey| hotkey.parse::<Shortcut>().ok())
+        .filter(|hotkey| hotkey.id() == shortcut.id())
+        .map(|_| c.settings().settings_hotkey_tab)
+}
+
 /// Handle hotkey release: stop recording for push-to-talk mode
 fn handle_hotkey_release(
     app: &tauri::AppHandle,
     ctrl: &tauri::State<'_, SharedController>,
 ) {
     let should_stop = {
-        let c = ctrl.lock().unwrap();
+        let mut c = ctrl.lock().unwrap();
+        // A push-to-talk press queued behind a transcription only counts
+        // while it is held.
+        if c.settings().hotkey_mode == HotkeyMode::PushToTalk {
+            c.clear_queued_start();
+        }
         c.should_stop_on_key_up()
     };
 
@@ -584,6 +979,87 @@ where
     }
 }
 
+/// Tell the user a hotkey press landed while transcribing: a `busy` event
+/// plus a short overlay flash.
+fn show_busy_feedback(app: &tauri::AppHandle, ctrl: &tauri::State<'_, SharedController>) {
+    let (while_busy, show_overlay) = {
+        let c = ctrl.lock().unwrap();
+        (c.settings().while_busy, c.settings().show_overlay)
+    };
+    let _ = app.emit(
+        events::event::BUSY,
+        serde_json::json!({ "whileBusy": while_busy }),
+    );
+    if show_overlay {
+        flash_overlay(app, BUSY_FLASH);
+    }
+}
+
+/// Tell the user a dictation heard no speech: a `NoSpeechDetected` error,
+/// which the overlay flashes with a hint to check the microphone.
+fn show_no_speech_feedback(app: &tauri::AppHandle, ctrl: &tauri::State<'_, SharedController>) {
+    commands::emit_error(
+        app,
+        &sagascript_core::error::DictationError::NoSpeechDetected,
+    );
+    if ctrl.lock().unwrap().settings().show_overlay {
+        dispatch_to_main(app, |app| flash_overlay(app, NO_SPEECH_FLASH));
+    }
+}
+
+/// Show the overlay for `duration`, then hide it unless a recording started
+/// in the meantime or accumulate mode is showing its buffer.
+fn flash_overlay(app: &tauri::AppHandle, duration: Duration) {
+    overlay::show(app);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(duration).await;
+        let keep = {
+            let ctrl: tauri::State<'_, SharedController> = app.state();
+            let c = ctrl.lock().unwrap();
+            c.state().is_recording() || !c.accumulated().is_empty()
+        };
+        if !keep {
+            dispatch_to_main(&app, overlay::hide);
+        }
+    });
+}
+
+/// Start the recording a hotkey press queued during the last transcription
+/// (`while_busy` queue or cancel-and-restart), if there is one.
+fn start_queued_recording(app: &tauri::AppHandle) {
+    let ctrl: tauri::State<'_, SharedController> = app.state();
+    let mut c = ctrl.lock().unwrap();
+    if !c.take_queued_start() {
+        return;
+    }
+    info!("Starting queued recording");
+    let mode = c.settings().hotkey_mode;
+    match c.start_recording_as(mode) {
+        Ok(true) => {
+            let show_overlay = c.settings().show_overlay;
+            drop(c);
+            let _ = app.emit(events::event::STATE_CHANGED, "recording");
+            dispatch_to_main(app, move |app| {
+                update_tray_status(app, "recording");
+                if show_overlay {
+                    overlay::show(app);
+                }
+            });
+            long_dictation::begin(app);
+            recording_limit::begin(app);
+            recovery::begin(app);
+            silence_stop::begin(app);
+        }
+        Ok(false) => {}
+        Err(e) => {
+            drop(c);
+            error!("Queued recording failed to start: {e}");
+            commands::emit_error(app, &e);
+        }
+    }
+}
+
 /// Stop recording, enforce minimum duration, and spawn transcription.
 /// Shared by both push-to-talk (on key-up) and toggle (on second key-down).
 fn stop_recording_and_transcribe(
@@ -622,25 +1098,54 @@ fn stop_recording_and_transcribe(
         // controller State can't be moved into the task). Guarded so a stop that
         // races an already-stopped session is a no-op, and a capture/resample
         // failure surfaces as a real error (findings 3 & 4).
-        let outcome = {
+        let stop_started = std::time::Instant::now();
+        let (started, limit, outcome) = {
             let ctrl: tauri::State<'_, SharedController> = app_handle.state();
             let mut c = ctrl.lock().unwrap();
-            c.stop_recording_guarded()
+            let limit = c.hit_recording_limit().then(|| c.recording_limit());
+            (c.recording_started_at(), limit, c.stop_recording_guarded())
         };
-        let audio = match outcome {
+        let mut timing = Timing {
+            decode_ms: timing::ms_since(stop_started),
+            ..Timing::default()
+        };
+        let mut audio = match outcome {
             StopRecordingOutcome::NotRecording => return,
-            StopRecordingOutcome::Failed(msg) => {
-                error!("Recording stop failed: {msg}");
+            StopRecordingOutcome::Failed(e) => {
+                error!("Recording stop failed: {e}");
+                recovery::end(&app_handle);
+                dispatch_to_main(&app_handle, |app| {
+                    overlay::hide(app);
+                    update_tray_status(app, "idle");
+                });
+                commands::emit_error(&app_handle, &e);
+                let _ = app_handle.emit(events::event::STATE_CHANGED, "idle");
+                return;
+            }
+            StopRecordingOutcome::Duplicate(since_last) => {
+                recovery::end(&app_handle);
                 dispatch_to_main(&app_handle, |app| {
                     overlay::hide(app);
                     update_tray_status(app, "idle");
                 });
-                let _ = app_handle.emit(events::event::ERROR, msg);
+                commands::emit_deduped(&app_handle, since_last);
                 let _ = app_handle.emit(events::event::STATE_CHANGED, "idle");
+                start_queued_recording(&app_handle);
                 return;
             }
             StopRecordingOutcome::Stopped(audio) => audio,
         };
+        // What was captured is still transcribed; the error says why it ends
+        // early.
+        if let Some(limit) = limit {
+            commands::emit_error(
+                &app_handle,
+                &sagascript_core::error::DictationError::BufferFull(format!(
+                    "stopped at the {}-minute limit",
+                    limit.as_secs() / 60
+                )),
+            );
+        }
 
         // Hide overlay + show the transcribing state — re-dispatched to the main
         // thread now that this runs on a worker.
@@ -651,43 +1156,106 @@ fn stop_recording_and_transcribe(
         let _ = app_handle.emit(events::event::STATE_CHANGED, "transcribing");
 
         if audio.is_empty() {
+            recovery::end(&app_handle);
             {
                 let ctrl: tauri::State<'_, SharedController> = app_handle.state();
                 ctrl.lock().unwrap().on_transcription_error("No audio captured");
             }
             dispatch_to_main(&app_handle, |app| update_tray_status(app, "idle"));
             let _ = app_handle.emit(events::event::STATE_CHANGED, "idle");
+            start_queued_recording(&app_handle);
             return;
         }
 
+        recovery::transcribing(&app_handle, started, &audio);
+
+        // Long dictations were partly transcribed while recording; wait for
+        // any chunk still in flight, then only the tail is left to decode.
+        let chunked = long_dictation::finish(&app_handle, started).await;
+
         // Transcribe (timeout/cancellation logic is owned by a separate work
         // package — left unchanged). Runs in this same task, which is already
         // off the UI thread.
         let ctrl: tauri::State<'_, SharedController> = app_handle.state();
         let whisper: tauri::State<'_, SharedWhisper> = app_handle.state();
 
-        // Extract what we need for transcription (lock briefly)
-        let (language, effective_model, opts) = {
+        // Extract what we need for transcription (lock briefly). The model is
+        // picked for the full recording so it matches the chunks' model.
+        let (language, effective_model, opts, remote, post_process, tail_start) = {
             let c = ctrl.lock().unwrap();
+            let settings = c.dictation_settings();
             (
-                c.language(),
-                c.settings().effective_model(),
-                commands::build_transcribe_options(c.settings()),
+                settings.language,
+                commands::model_for_clip(&settings, audio.len()),
+                commands::build_transcribe_options(&settings),
+                backend::from_settings(&settings),
+                llm::PostProcess::from_settings(&settings),
+                chunked
+                    .as_ref()
+                    .map(|p| c.recording_offset_at_16khz(p.consumed).min(audio.len()))
+                    .unwrap_or(0),
             )
         };
+        if tail_start > 0 {
+            audio = audio.split_off(tail_start);
+            info!(
+                "Transcribing {:.1}s tail after {} background chunks",
+                audio.len() as f32 / TARGET_SAMPLE_RATE as f32,
+                chunked.as_ref().map_or(0, |p| p.texts.len())
+            );
+        }
+        // A tail this short after chunking is the pause the last chunk was
+        // split at; skip it rather than ask whisper to decode silence.
+        let skip_tail = chunked.is_some() && audio.len() < TARGET_SAMPLE_RATE as usize / 4;
 
-        info!("Transcribing with model: {}", effective_model.display_name());
+        match &remote {
+            Ok(Some(remote)) => info!("Transcribing with {}", remote.name()),
+            _ => info!("Transcribing with model: {}", effective_model.display_name()),
+        }
+
+        // Two-stage mode pastes a tiny-model draft while the selected model
+        // runs. Chunked dictations are mostly transcribed already.
+        let draft = if chunked.is_none() && matches!(remote, Ok(None)) {
+            draft_pass::paste(&app_handle, &audio, effective_model).await
+        } else {
+            None
+        };
 
         // Show model loading status in tray
-        if whisper.needs_reload(effective_model) {
+        if matches!(remote, Ok(None)) && whisper.needs_reload(effective_model) {
             let _ = app_handle.emit(events::event::STATE_CHANGED, "loading_model");
             dispatch_to_main(&app_handle, |app| update_tray_status(app, "loading_model"));
         }
 
-        // Ensure model is loaded
-        let result = if let Err(e) = whisper.ensure_model(effective_model) {
+        // Ensure model is loaded. Inference time is measured separately from
+        // loading so a cold model load doesn't count against its speed.
+        let audio_len = audio.len();
+        let mut inference_time = None;
+        let transcribe_started = std::time::Instant::now();
+        let result = if skip_tail {
+            Ok(Transcript::from_text(String::new(), false))
+        } else if let Err(e) = &remote {
+            Err(e.clone())
+        } else if let Ok(Some(remote)) = &remote {
+            // A remote backend has no abort to request; on timeout the
+            // request is simply dropped. Uploads take longer than local
+            // inference, so the backend sizes its own timeout.
+            let timeout = remote.timeout(audio.len());
+            tokio::time::timeout(timeout, remote.transcribe(&audio, language))
+                .await
+                .unwrap_or_else(|_| {
+                    Err(sagascript_core::error::DictationError::TranscriptionFailed(format!(
+                        "{} did not answer within {}s",
+                        remote.name(),
+                        timeout.as_secs()
+                    )))
+                })
+                .map(|t| backend::apply_text_options(t, &opts, language))
+        } else if let Err(e) = whisper.ensure_model(effective_model) {
             Err(e)
         } else {
+            timing.model_load_ms = timing::ms_since(transcribe_started);
+            let inference_started = std::time::Instant::now();
             // Run blocking transcription on a separate thread with a timeout. On
             // timeout we trigger a REAL abort (whisper-rs abort callback wired in
             // WhisperBackend): request_abort() flips the flag whisper.cpp checks
@@ -695,12 +1263,15 @@ fn stop_recording_and_transcribe(
             // warm state instead of running to completion and wedging the pipeline.
             let whisper_ref = whisper.inner().clone();
             let mut fut = tokio::task::spawn_blocking(move || {
-                whisper_ref.transcribe_sync_with_options(&audio, language, &opts, |_| {})
+                whisper_ref.transcribe_sync_allow_partial(&audio, language, &opts, |_| {})
             });
 
             let timeout = Duration::from_secs(TRANSCRIPTION_TIMEOUT_SECS);
             match tokio::time::timeout(timeout, &mut fut).await {
-                Ok(Ok(r)) => r,
+                Ok(Ok(r)) => {
+                    inference_time = Some(inference_started.elapsed());
+                    r
+                }
                 Ok(Err(e)) => Err(sagascript_core::error::DictationError::TranscriptionFailed(
                     format!("Task join error: {e}"),
                 )),
@@ -708,55 +1279,167 @@ fn stop_recording_and_transcribe(
                     warn!("Transcription timed out after {TRANSCRIPTION_TIMEOUT_SECS}s — requesting abort");
                     whisper.request_abort();
                     // Brief grace for the aborted inference to unwind; log which
-                    // outcome occurred so a genuine hang is visible.
+                    // outcome occurred so a genuine hang is visible. Whatever
+                    // whisper finished before the abort is kept as a partial
+                    // result rather than losing the whole dictation.
+                    let timed_out = || {
+                        Err(sagascript_core::error::DictationError::TranscriptionFailed(
+                            format!("Transcription timed out after {TRANSCRIPTION_TIMEOUT_SECS}s (inference aborted)"),
+                        ))
+                    };
                     match tokio::time::timeout(Duration::from_secs(ABORT_GRACE_SECS), &mut fut).await
                     {
-                        Ok(_) => info!("Aborted transcription task exited — warm-state lock released"),
-                        Err(_) => error!(
-                            "Transcription task still running {ABORT_GRACE_SECS}s after abort — \
-                             warm state may stay locked until it unwinds; further transcriptions \
-                             will report ModelBusy rather than block forever"
-                        ),
+                        Ok(Ok(Ok(transcription))) => {
+                            info!("Aborted transcription task exited with a partial result — warm-state lock released");
+                            Ok(transcription)
+                        }
+                        Ok(_) => {
+                            info!("Aborted transcription task exited — warm-state lock released");
+                            timed_out()
+                        }
+                        Err(_) => {
+                            error!(
+                                "Transcription task still running {ABORT_GRACE_SECS}s after abort — \
+                                 warm state may stay locked until it unwinds; further transcriptions \
+                                 will report ModelBusy rather than block forever"
+                            );
+                            timed_out()
+                        }
                     }
-                    Err(sagascript_core::error::DictationError::TranscriptionFailed(
-                        format!("Transcription timed out after {TRANSCRIPTION_TIMEOUT_SECS}s (inference aborted)"),
-                    ))
                 }
             }
         };
 
-        match result {
-            Ok(text) => {
-                info!("Transcription complete: {} chars", text.len());
+        timing.inference_ms = timing::ms_since(transcribe_started) - timing.model_load_ms;
+
+        // Put the background chunks back in front of the tail. If the tail
+        // itself failed, the chunks are still worth keeping as a partial result.
+        let result = match (chunked, result) {
+            (Some(progress), Ok(tail)) => Ok(Transcript::from_text(
+                long_dictation::join_texts(progress.texts, &tail.text),
+                tail.partial,
+            )),
+            (Some(progress), Err(e)) => {
+                warn!("Final pass failed ({e}) — keeping {} background chunks", progress.texts.len());
+                Ok(Transcript::from_text(
+                    long_dictation::join_texts(progress.texts, ""),
+                    true,
+                ))
+            }
+            (None, result) => result,
+        };
 
-                // Check if auto-paste is enabled (lock briefly)
-                let should_paste = {
-                    let c = ctrl.lock().unwrap();
-                    c.settings().auto_paste
+        // A cancel-and-restart press aborted this run; drop whatever it
+        // produced instead of pasting a partial result or reporting the abort.
+        let cancelled = ctrl.lock().unwrap().transcription_cancelled();
+        match result {
+            _ if cancelled => {
+                info!("Transcription discarded for a new recording");
+                ctrl.lock().unwrap().on_transcription_cancelled();
+                let _ = app_handle.emit(events::event::STATE_CHANGED, "idle");
+                dispatch_to_main(&app_handle, |app| update_tray_status(app, "idle"));
+            }
+            // Pasting nothing would only confuse; say no speech was heard.
+            Ok(transcription) if transcription.is_silent() => {
+                let level_warning = {
+                    let mut c = ctrl.lock().unwrap();
+                    let warning = c.level_warning();
+                    c.on_no_speech();
+                    warning
                 };
-
-                if should_paste {
-                    // Auto-paste MUST run on the main thread — enigo's macOS TIS APIs
-                    // crash (SIGABRT) if called from a tokio worker thread.
-                    let text_for_paste = text.clone();
-                    if let Err(e) = app_handle.run_on_main_thread(move || {
-                        info!("Running auto-paste on main thread...");
-                        let paste_svc = crate::paste::PasteService::new();
-                        match paste_svc.paste(&text_for_paste) {
-                            Ok(()) => info!("Auto-paste completed successfully"),
-                            Err(e) => error!("Auto-paste failed: {e}"),
+                show_no_speech_feedback(&app_handle, &ctrl);
+                commands::emit_level_warning(&app_handle, level_warning);
+                let _ = app_handle.emit(events::event::STATE_CHANGED, "idle");
+                dispatch_to_main(&app_handle, |app| update_tray_status(app, "idle"));
+            }
+            Ok(transcription) => {
+                // The model may take seconds, so it runs before the controller
+                // is locked. A failure keeps the raw transcript.
+                let post_process_started = std::time::Instant::now();
+                let cleaned = match &post_process {
+                    Some(step) if !transcription.text.trim().is_empty() => {
+                        match step.run(&transcription.text).await {
+                            Ok(cleaned) => Some(cleaned),
+                            Err(e) => {
+                                warn!("{e} — keeping the raw transcript");
+                                commands::emit_error(&app_handle, &e);
+                                None
+                            }
                         }
-                    }) {
-                        error!("Failed to dispatch paste to main thread: {e}");
                     }
+                    _ => None,
+                };
+                timing.post_process_ms = timing::ms_since(post_process_started);
+                let raw_or_cleaned = cleaned.as_deref().unwrap_or(&transcription.text);
+                let (text, level_warning, recorded) = {
+                    let mut c = ctrl.lock().unwrap();
+                    (
+                        c.on_transcription_success(raw_or_cleaned),
+                        c.level_warning(),
+                        c.last_recording(),
+                    )
+                };
+                if transcription.partial {
+                    warn!("Transcription timed out — keeping partial result ({} chars)", text.len());
+                } else {
+                    info!("Transcription complete: {} chars", text.len());
                 }
 
-                let mut c = ctrl.lock().unwrap();
-                c.on_transcription_success(&text);
-                drop(c);
+                // In accumulate mode the dictation is buffered instead of
+                // pasted; ending it with the flush phrase pastes the whole
+                // buffer.
+                let accumulated = {
+                    let mut c = ctrl.lock().unwrap();
+                    c.settings().accumulate_mode.then(|| {
+                        let flush = accumulate::strip_flush_phrase(&text, &c.settings().flush_phrase);
+                        c.accumulate(flush.as_deref().unwrap_or(&text));
+                        (flush.is_some(), c.settings().show_overlay)
+                    })
+                };
+                let paste_started = std::time::Instant::now();
+                match draft {
+                    Some(draft) => draft_pass::replace(&app_handle, draft, &text),
+                    None if accumulated.is_none() => {
+                        commands::auto_paste_or_hold(&app_handle, &text)
+                    }
+                    None => {}
+                }
+                timing.paste_ms = timing::ms_since(paste_started);
+
+                if let Some(elapsed) = inference_time {
+                    commands::record_real_time_factor(&app_handle, effective_model, audio_len, elapsed);
+                }
 
                 let _ = app_handle.emit(events::event::TRANSCRIPTION_RESULT, &text);
+                let _ = app_handle.emit(events::event::TRANSCRIPT, &transcription);
+                commands::emit_level_warning(&app_handle, level_warning);
+                if let Some(recorded) = recorded {
+                    commands::emit_pace(&app_handle, &text, recorded, false);
+                    timing.capture_ms = timing::ms(recorded);
+                }
+                let timing = timing.finish();
+                info!("Dictation took {}", timing.summary());
+                let _ = app_handle.emit(events::event::TRANSCRIPTION_COMPLETE, timing);
+                if transcription.partial {
+                    let _ = app_handle.emit(events::event::TRANSCRIPTION_PARTIAL, &text);
+                }
                 let _ = app_handle.emit(events::event::STATE_CHANGED, "idle");
+                // Buffered dictations are routed once, as a whole, on flush.
+                if accumulated.is_none() {
+                    commands::route_output(&app_handle, &text);
+                }
+                match accumulated {
+                    Some((true, _)) => {
+                        commands::flush_accumulated_text(&app_handle);
+                    }
+                    Some((false, show_overlay)) => {
+                        commands::emit_accumulated(&app_handle);
+                        if show_overlay {
+                            dispatch_to_main(&app_handle, overlay::show);
+                        }
+                    }
+                    None => {}
+                }
                 let text_for_tray = text.clone();
                 dispatch_to_main(&app_handle, move |app| {
                     update_tray_status(app, "idle");
@@ -769,12 +1452,14 @@ fn stop_recording_and_transcribe(
                 let mut c = ctrl.lock().unwrap();
                 c.on_transcription_error(&e.to_string());
                 drop(c);
-                let _ = app_handle.emit(events::event::ERROR, e.to_string());
+                commands::emit_error(&app_handle, &e);
                 let _ = app_handle.emit(events::event::STATE_CHANGED, "idle");
                 dispatch_to_main(&app_handle, |app| update_tray_status(app, "idle"));
                 info!("Error flow complete, app should remain running");
             }
         }
+        recovery::end(&app_handle);
+        start_queued_recording(&app_handle);
     });
 }
 
@@ -795,6 +1480,34 @@ fn settings_event_may_affect(
             .any(|path| path == settings_path)
 }
 
+/// Check HuggingFace for newer revisions of the downloaded models once a day
+/// while `model_update_check` is on, and tell the frontend about any. The
+/// setting is re-read before every check, so toggling it takes effect
+/// without a restart.
+fn start_model_update_checks(app: tauri::AppHandle) {
+    use sagascript_core::transcription::updates;
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(MODEL_UPDATE_FIRST_CHECK).await;
+        loop {
+            if sagascript_core::settings::store::load().model_update_check {
+                match updates::check_installed().await {
+                    Ok(results) => {
+                        let available: Vec<_> =
+                            results.into_iter().filter(|u| u.update_available).collect();
+                        if !available.is_empty() {
+                            info!("Newer upstream revisions for {} model(s)", available.len());
+                            let _ = app.emit(events::event::MODEL_UPDATES_AVAILABLE, &available);
+                        }
+                    }
+                    Err(e) => warn!("Model update check failed: {e}"),
+                }
+            }
+            tokio::time::sleep(MODEL_UPDATE_INTERVAL).await;
+        }
+    });
+}
+
 /// Watch the settings file for external changes and hot-reload into the running app.
 /// Handles hotkey re-registration and emits a settings-changed event to the frontend.
 fn start_settings_watcher(app: tauri::AppHandle) {
@@ -863,6 +1576,15 @@ fn start_settings_watcher(app: tauri::AppHandle) {
             // Small delay to let atomic rename complete
             std::thread::sleep(Duration::from_millis(50));
 
+            // A broken or half-saved file would load as defaults. Keep the
+            // running settings and say where the file is wrong instead.
+            let issue = sagascript_core::settings::store::validate(&settings_path).err();
+            let _ = app.emit(events::event::SETTINGS_LOAD_WARNING, &issue);
+            if let Some(issue) = issue {
+                warn!("Settings file not reloaded, {issue}");
+                continue;
+            }
+
             let health: tauri::State<'_, hotkey::HotkeyHealth> = app.state();
             let _transition = health.transition_guard();
             let new_settings = load_settings_with_permission_gate();
@@ -973,6 +1695,53 @@ fn start_settings_watcher(app: tauri::AppHandle) {
                 }
             }
 
+            // Follow accumulate mode being toggled or the flush shortcut rebound
+            let old_flush = commands::flush_shortcut(&old_settings);
+            let new_flush = commands::flush_shortcut(&new_settings);
+            if old_flush != new_flush {
+                if let Err(e) = commands::swap_flush_hotkey(&app, old_flush, new_flush) {
+                    error!("Settings watcher: {e}");
+                }
+            }
+            let old_toggle = commands::toggle_shortcut(&old_settings);
+            let new_toggle = commands::toggle_shortcut(&new_settings);
+            if old_toggle != new_toggle {
+                if let Err(e) = commands::swap_toggle_hotkey(&app, old_toggle, new_toggle) {
+                    error!("Settings watcher: {e}");
+                }
+            }
+            let old_profiles = commands::profile_shortcuts(&old_settings);
+            let new_profiles = commands::profile_shortcuts(&new_settings);
+            if old_profiles != new_profiles {
+                if let Err(e) = commands::swap_profile_hotkeys(&app, &old_profiles, &new_profiles) {
+                    error!("Settings watcher: {e}");
+                }
+            }
+            let old_open = commands::settings_shortcut(&old_settings);
+            let new_open = commands::settings_shortcut(&new_settings);
+            if old_open != new_open {
+                if let Err(e) = commands::swap_settings_hotkey(&app, old_open, new_open) {
+                    error!("Settings watcher: {e}");
+                }
+            }
+
+            sagascript_core::telemetry::set_enabled(new_settings.telemetry);
+            if new_settings.use_gpu != old_settings.use_gpu {
+                let whisper: tauri::State<'_, SharedWhisper> = app.state();
+                whisper.set_use_gpu(new_settings.use_gpu);
+                app.state::<draft_pass::DraftWhisper>().set_use_gpu(new_settings.use_gpu);
+            }
+            if new_settings.custom_model_path() != old_settings.custom_model_path() {
+                let whisper: tauri::State<'_, SharedWhisper> = app.state();
+                whisper.set_custom_model(new_settings.custom_model_path());
+            }
+            if overlay::Appearance::from_settings(&new_settings)
+                != overlay::Appearance::from_settings(&old_settings)
+                || new_settings.announce_state != old_settings.announce_state
+            {
+                overlay::apply(&app, &new_settings);
+            }
+
             // Update controller with all new settings
             {
                 let mut c = ctrl.lock().unwrap();
@@ -991,6 +1760,25 @@ fn start_settings_watcher(app: tauri::AppHandle) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn opened_file_argument_accepts_a_single_audio_file() {
+        let path = std::env::temp_dir().join(format!("sagascript-open-{}.WAV", std::process::id()));
+        std::fs::write(&path, b"").unwrap();
+        assert_eq!(
+            opened_file_argument(vec![path.clone().into()]),
+            Some(path.clone())
+        );
+        assert_eq!(
+            opened_file_argument(vec!["transcribe".into(), path.clone().into()]),
+            None
+        );
+        std::fs::remove_file(&path).unwrap();
+        // Gone, or never an audio file: leave it to the CLI parser.
+        assert_eq!(opened_file_argument(vec![path.into()]), None);
+        assert_eq!(opened_file_argument(vec!["list-models".into()]), None);
+        assert_eq!(opened_file_argument(Vec::new()), None);
+    }
+
     #[cfg(target_os = "macos")]
     fn wait_for_settings_event(
         rx: &std::sync::mpsc::Receiver<notify::Result<notify::Event>>,
@@ -1,10 +1,124 @@
-use tauri::Manager;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use sagascript_core::settings::Settings;
+use serde::Serialize;
+use tauri::{Emitter, Manager};
 use tracing::info;
 #[cfg(not(target_os = "linux"))]
 use tracing::error;
 
 const OVERLAY_LABEL: &str = "overlay";
 
+/// Overlay window size in logical pixels, and with `overlay_large_text`.
+const SIZE: (f64, f64) = (220.0, 60.0);
+const LARGE_SIZE: (f64, f64) = (330.0, 90.0);
+
+/// How the overlay draws itself; sent with `OVERLAY_APPEARANCE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Appearance {
+    pub high_contrast: bool,
+    pub large_text: bool,
+    pub reduced_motion: bool,
+}
+
+impl Appearance {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            high_contrast: settings.overlay_high_contrast,
+            large_text: settings.overlay_large_text,
+            reduced_motion: settings.overlay_reduced_motion,
+        }
+    }
+}
+
+/// The accessibility settings needed where the controller may be locked
+/// (showing the overlay, tray state updates), mirrored like
+/// `tray::StatusTextEnabled`. Also remembers the last announcement so
+/// repeated updates to the same state are only read out once.
+pub struct Accessibility {
+    large_text: AtomicBool,
+    announce_state: AtomicBool,
+    last_announced: Mutex<Option<&'static str>>,
+}
+
+impl Accessibility {
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            large_text: AtomicBool::new(settings.overlay_large_text),
+            announce_state: AtomicBool::new(settings.announce_state),
+            last_announced: Mutex::new(None),
+        }
+    }
+
+    pub fn set(&self, settings: &Settings) {
+        self.large_text
+            .store(settings.overlay_large_text, Ordering::Relaxed);
+        self.announce_state
+            .store(settings.announce_state, Ordering::Relaxed);
+    }
+
+    fn size(&self) -> (f64, f64) {
+        if self.large_text.load(Ordering::Relaxed) {
+            LARGE_SIZE
+        } else {
+            SIZE
+        }
+    }
+}
+
+/// Apply changed overlay accessibility settings: mirror them and restyle an
+/// open overlay. The window is resized the next time it's shown.
+pub fn apply(app: &tauri::AppHandle, settings: &Settings) {
+    app.state::<Accessibility>().set(settings);
+    let _ = app.emit(
+        crate::events::event::OVERLAY_APPEARANCE,
+        Appearance::from_settings(settings),
+    );
+}
+
+/// What a state change is announced as; `None` for states that aren't.
+fn announcement(state: &str) -> Option<&'static str> {
+    match state {
+        "recording" => Some("Recording"),
+        "loading_model" => Some("Loading model"),
+        "transcribing" => Some("Transcribing"),
+        "idle" => Some("Ready"),
+        _ => None,
+    }
+}
+
+/// Read a state change out to screen readers when `announce_state` is on.
+/// Must run on the main thread.
+pub fn announce_state(app: &tauri::AppHandle, state: &str) {
+    if let Some(text) = announcement(state) {
+        announce(app, text);
+    }
+}
+
+/// Read `text` out to screen readers when `announce_state` is on, unless it
+/// was the last thing announced. macOS posts a VoiceOver announcement;
+/// elsewhere the overlay shows the text in a live region. Must run on the
+/// main thread.
+pub fn announce(app: &tauri::AppHandle, text: &'static str) {
+    let accessibility = app.state::<Accessibility>();
+    if !accessibility.announce_state.load(Ordering::Relaxed) {
+        return;
+    }
+    {
+        let mut last = accessibility.last_announced.lock().unwrap();
+        if *last == Some(text) {
+            return;
+        }
+        *last = Some(text);
+    }
+
+    #[cfg(target_os = "macos")]
+    crate::platform::macos::announce(text);
+    #[cfg(not(target_os = "macos"))]
+    let _ = app.emit(crate::events::event::ANNOUNCEMENT, text);
+}
+
 /// Show the recording overlay window (create lazily on first call).
 ///
 /// Disabled on Linux: creating the transparent, always-on-top overlay window
@@ -20,6 +134,10 @@ pub fn show(app: &tauri::AppHandle) {
     #[cfg(not(target_os = "linux"))]
     {
         if let Some(window) = app.get_webview_window(OVERLAY_LABEL) {
+            let (width, height) = app.state::<Accessibility>().size();
+            let _ = window.set_size(tauri::LogicalSize::new(width, height));
+            // Back to click-through after a paste confirmation.
+            let _ = window.set_ignore_cursor_events(true);
             let _ = window.show();
             #[cfg(target_os = "macos")]
             macos_order_front(&window);
@@ -33,6 +151,22 @@ pub fn show(app: &tauri::AppHandle) {
     }
 }
 
+/// Show the overlay with Paste and Discard buttons for a transcript held
+/// back from auto-paste. Unlike the recording indicator it takes clicks
+/// until the next [`show`]. On Linux, where there is no overlay, the main
+/// window shows the prompt instead.
+pub fn show_paste_confirmation(app: &tauri::AppHandle) {
+    #[cfg(target_os = "linux")]
+    crate::open_settings_window(app, Some("dictate"));
+    #[cfg(not(target_os = "linux"))]
+    {
+        show(app);
+        if let Some(window) = app.get_webview_window(OVERLAY_LABEL) {
+            let _ = window.set_ignore_cursor_events(false);
+        }
+    }
+}
+
 /// Hide the recording overlay window
 pub fn hide(app: &tauri::AppHandle) {
     if let Some(window) = app.get_webview_window(OVERLAY_LABEL) {
@@ -43,12 +177,14 @@ pub fn hide(app: &tauri::AppHandle) {
 
 #[cfg(not(target_os = "linux"))]
 fn create_overlay(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let (width, height) = app.state::<Accessibility>().size();
+
     // Calculate horizontal center position
     let (x, _screen_width) = if let Some(monitor) = app.primary_monitor()? {
         let size = monitor.size();
         let scale = monitor.scale_factor();
         let logical_width = size.width as f64 / scale;
-        ((logical_width / 2.0 - 110.0), logical_width)
+        ((logical_width / 2.0 - width / 2.0), logical_width)
     } else {
         (500.0, 1200.0)
     };
@@ -59,7 +195,7 @@ fn create_overlay(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Erro
         tauri::WebviewUrl::App("index.html?overlay=true".into()),
     )
     .title("")
-    .inner_size(220.0, 60.0)
+    .inner_size(width, height)
     .position(x, 80.0)
     .decorations(false)
     .transparent(true)
@@ -119,3 +255,32 @@ fn macos_order_front(window: &tauri::WebviewWindow) {
         let _: () = objc::msg_send![ns_window, orderFront: nil];
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_dictation_states_are_announced() {
+        assert_eq!(announcement("recording"), Some("Recording"));
+        assert_eq!(announcement("idle"), Some("Ready"));
+        assert_eq!(announcement("settings_reloaded"), None);
+    }
+
+    #[test]
+    fn appearance_follows_settings() {
+        let settings = Settings {
+            overlay_high_contrast: true,
+            overlay_reduced_motion: true,
+            ..Settings::default()
+        };
+        assert_eq!(
+            Appearance::from_settings(&settings),
+            Appearance {
+                high_contrast: true,
+                large_text: false,
+                reduced_motion: true,
+            }
+        );
+    }
+}
@@ -12,20 +12,45 @@ use tracing::info;
 #[cfg(target_os = "macos")]
 use tracing::warn;
 
+use std::collections::BTreeMap;
+
+#[cfg(target_os = "linux")]
+use crate::platform::linux::frontmost_app;
+#[cfg(target_os = "macos")]
+use crate::platform::macos::frontmost_app;
+#[cfg(target_os = "windows")]
+use crate::platform::windows::frontmost_app;
 use sagascript_core::error::DictationError;
+use sagascript_core::paste_format::{self, AppCategory};
+use sagascript_core::settings::Settings;
 
 /// Service for pasting transcribed text into the active application
 /// Uses clipboard + simulated Cmd+V (macOS) or Ctrl+V (Windows/Linux)
-pub struct PasteService;
+pub struct PasteService {
+    /// App rules for per-app formatting; `None` pastes text unchanged.
+    app_rules: Option<BTreeMap<String, AppCategory>>,
+}
 
 impl PasteService {
     pub fn new() -> Self {
-        Self
+        Self { app_rules: None }
+    }
+
+    /// A paste service that formats text for the frontmost app when
+    /// `paste_formatting` is on.
+    pub fn for_settings(settings: &Settings) -> Self {
+        Self {
+            app_rules: settings
+                .paste_formatting
+                .then(|| settings.paste_app_rules.clone()),
+        }
     }
 
     /// Paste text into the currently active application
     /// Saves and restores previous clipboard contents
     pub fn paste(&self, text: &str) -> Result<(), DictationError> {
+        let formatted = self.format_for_frontmost(text);
+        let text = formatted.as_str();
         if text.is_empty() {
             return Ok(());
         }
@@ -117,6 +142,47 @@ impl PasteService {
 
         Ok(())
     }
+
+    /// Replace `draft`, pasted just before, with `text` in the focused text
+    /// field. Goes through the accessibility API rather than the clipboard,
+    /// and only when the draft still sits right before the caret, so text the
+    /// user typed or moved to in the meantime is never overwritten. Returns
+    /// whether the draft was replaced; it is left as it is otherwise.
+    pub fn replace_draft(&self, draft: &str, text: &str) -> Result<bool, DictationError> {
+        let draft = self.format_for_frontmost(draft);
+        let text = self.format_for_frontmost(text);
+        if draft == text {
+            return Ok(true);
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            validate_accessibility(crate::platform::macos::is_accessibility_trusted())?;
+            Ok(crate::platform::macos::replace_before_caret(&draft, &text))
+        }
+
+        // No accessibility text API is wired up on Windows/Linux; deleting
+        // the draft with simulated keystrokes could eat the user's own text.
+        #[cfg(not(target_os = "macos"))]
+        {
+            Ok(false)
+        }
+    }
+
+    /// `text` formatted for the app that will receive the paste.
+    fn format_for_frontmost(&self, text: &str) -> String {
+        let Some(rules) = &self.app_rules else {
+            return text.to_string();
+        };
+        let Some(app) = frontmost_app() else {
+            return text.to_string();
+        };
+        let category = paste_format::category_for(&app, rules);
+        if category != AppCategory::Other {
+            info!("Formatting paste for {} ({category})", app.id);
+        }
+        paste_format::format_for(text, category)
+    }
 }
 
 #[cfg(target_os = "macos")]
@@ -24,3 +24,21 @@ pub fn request_accessibility_permission() {
 pub fn set_activation_policy_accessory() {
     // Nothing to do
 }
+
+/// The app owning the active X11 window, identified by its WM_CLASS. Uses
+/// `xdotool`, which auto-paste already requires; `None` without it (and on
+/// Wayland sessions xdotool can't see).
+pub fn frontmost_app() -> Option<sagascript_core::paste_format::FrontmostApp> {
+    let output = std::process::Command::new("xdotool")
+        .args(["getactivewindow", "getwindowclassname"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let class = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!class.is_empty()).then(|| sagascript_core::paste_format::FrontmostApp {
+        id: class.clone(),
+        name: class,
+    })
+}
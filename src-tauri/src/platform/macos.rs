@@ -1,9 +1,13 @@
-use core_foundation::base::TCFType;
+use core_foundation::base::{CFIndex, CFRange, CFType, CFTypeRef, TCFType};
 use core_foundation::boolean::CFBoolean;
 use core_foundation::dictionary::CFDictionary;
-use core_foundation::string::CFString;
+use core_foundation::string::{CFString, CFStringRef};
+use std::ffi::{c_char, c_void, CStr};
 use std::io;
+use std::path::{Path, PathBuf};
 use std::process::{Command, ExitStatus};
+use std::sync::OnceLock;
+use tracing::{info, warn};
 
 const ACCESSIBILITY_SETTINGS_URL: &str =
     "x-apple.systempreferences:com.apple.settings.PrivacySecurity.extension?Privacy_Accessibility";
@@ -11,8 +15,26 @@ const ACCESSIBILITY_SETTINGS_URL: &str =
 extern "C" {
     fn AXIsProcessTrusted() -> bool;
     fn AXIsProcessTrustedWithOptions(options: core_foundation::base::CFTypeRef) -> bool;
+    fn AXUIElementCreateSystemWide() -> CFTypeRef;
+    fn AXUIElementCopyAttributeValue(
+        element: CFTypeRef,
+        attribute: CFStringRef,
+        value: *mut CFTypeRef,
+    ) -> i32;
+    fn AXUIElementSetAttributeValue(
+        element: CFTypeRef,
+        attribute: CFStringRef,
+        value: CFTypeRef,
+    ) -> i32;
+    fn AXValueCreate(value_type: u32, value: *const c_void) -> CFTypeRef;
+    fn AXValueGetValue(value: CFTypeRef, value_type: u32, out: *mut c_void) -> bool;
 }
 
+/// kAXErrorSuccess
+const AX_SUCCESS: i32 = 0;
+/// kAXValueTypeCFRange
+const AX_VALUE_CF_RANGE: u32 = 4;
+
 /// Check if the process has accessibility (AX) permissions
 pub fn is_accessibility_trusted() -> bool {
     unsafe { AXIsProcessTrusted() }
@@ -64,14 +86,287 @@ pub fn set_activation_policy_accessory() {
     }
 }
 
+type ServiceHandler = Box<dyn Fn(Vec<PathBuf>) + Send + Sync>;
+
+static SERVICE_HANDLER: OnceLock<ServiceHandler> = OnceLock::new();
+
+#[link(name = "AppKit", kind = "framework")]
+extern "C" {
+    fn NSUpdateDynamicServices();
+}
+
+/// Register the app as the provider for its Finder Services entry (see
+/// `NSServices` in Info.plist). `on_files` receives the selected files and
+/// is called on the main thread. Must be called once, from the main thread.
+///
+/// The `transcribeFile:userData:error:` method must match `NSMessage` in
+/// Info.plist.
+pub fn register_services_provider(on_files: impl Fn(Vec<PathBuf>) + Send + Sync + 'static) {
+    use cocoa::appkit::NSApp;
+    use objc::declare::ClassDecl;
+    use objc::runtime::{Object, Sel};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    if SERVICE_HANDLER.set(Box::new(on_files)).is_err() {
+        warn!("Services provider already registered");
+        return;
+    }
+
+    extern "C" fn transcribe_file(
+        _this: &Object,
+        _sel: Sel,
+        pboard: *mut Object,
+        _user_data: *mut Object,
+        _error: *mut *mut Object,
+    ) {
+        let paths = unsafe { pasteboard_file_paths(pboard) };
+        info!("Services request for {} file(s)", paths.len());
+        if let Some(handler) = SERVICE_HANDLER.get() {
+            handler(paths);
+        }
+    }
+
+    let Some(mut decl) = ClassDecl::new("SagascriptServiceProvider", class!(NSObject)) else {
+        warn!("SagascriptServiceProvider class already exists");
+        return;
+    };
+    unsafe {
+        decl.add_method(
+            sel!(transcribeFile:userData:error:),
+            transcribe_file
+                as extern "C" fn(&Object, Sel, *mut Object, *mut Object, *mut *mut Object),
+        );
+        let provider: *mut Object = msg_send![decl.register(), new];
+        let () = msg_send![NSApp(), setServicesProvider: provider];
+        NSUpdateDynamicServices();
+    }
+    info!("Registered Services provider");
+}
+
+/// File paths on a Services pasteboard. Finder still publishes the legacy
+/// `NSFilenamesPboardType` property list alongside file URLs.
+unsafe fn pasteboard_file_paths(pboard: *mut objc::runtime::Object) -> Vec<PathBuf> {
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    if pboard.is_null() {
+        return Vec::new();
+    }
+    let kind: *mut Object =
+        msg_send![class!(NSString), stringWithUTF8String: c"NSFilenamesPboardType".as_ptr()];
+    let list: *mut Object = msg_send![pboard, propertyListForType: kind];
+    if list.is_null() {
+        return Vec::new();
+    }
+    let count: usize = msg_send![list, count];
+    (0..count)
+        .filter_map(|i| {
+            let name: *mut Object = msg_send![list, objectAtIndex: i];
+            let utf8: *const c_char = msg_send![name, UTF8String];
+            (!utf8.is_null())
+                .then(|| PathBuf::from(CStr::from_ptr(utf8).to_string_lossy().as_ref()))
+        })
+        .collect()
+}
+
+/// The app that currently has focus, as seen by `NSWorkspace`. Called on the
+/// main thread just before a paste.
+pub fn frontmost_app() -> Option<sagascript_core::paste_format::FrontmostApp> {
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    unsafe fn ns_string(s: *mut Object) -> Option<String> {
+        if s.is_null() {
+            return None;
+        }
+        let utf8: *const c_char = msg_send![s, UTF8String];
+        (!utf8.is_null()).then(|| CStr::from_ptr(utf8).to_string_lossy().into_owned())
+    }
+
+    unsafe {
+        let workspace: *mut Object = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let app: *mut Object = msg_send![workspace, frontmostApplication];
+        if app.is_null() {
+            return None;
+        }
+        let id = ns_string(msg_send![app, bundleIdentifier]).unwrap_or_default();
+        let name = ns_string(msg_send![app, localizedName]).unwrap_or_default();
+        (!id.is_empty() || !name.is_empty())
+            .then_some(sagascript_core::paste_format::FrontmostApp { id, name })
+    }
+}
+
+/// An accessibility attribute of `element`, or `None` if it has none.
+unsafe fn copy_ax_attribute(element: CFTypeRef, attribute: &str) -> Option<CFType> {
+    let mut value: CFTypeRef = std::ptr::null();
+    let attribute = CFString::new(attribute);
+    let error = AXUIElementCopyAttributeValue(element, attribute.as_concrete_TypeRef(), &mut value);
+    (error == AX_SUCCESS && !value.is_null()).then(|| CFType::wrap_under_create_rule(value))
+}
+
+/// Replace `old`, which must sit right before the caret of the focused text
+/// field, with `new`: the range is selected and the selection set, as
+/// dictation in AppKit does. Returns false, leaving the field untouched, when
+/// the field doesn't expose its text or `old` isn't there any more because
+/// the user typed, selected or moved the caret. Must run on the main thread.
+pub fn replace_before_caret(old: &str, new: &str) -> bool {
+    unsafe {
+        let system = AXUIElementCreateSystemWide();
+        if system.is_null() {
+            return false;
+        }
+        let system = CFType::wrap_under_create_rule(system);
+        let Some(focused) = copy_ax_attribute(system.as_CFTypeRef(), "AXFocusedUIElement") else {
+            return false;
+        };
+        let field = focused.as_CFTypeRef();
+        let Some(value) =
+            copy_ax_attribute(field, "AXValue").and_then(|v| v.downcast::<CFString>())
+        else {
+            return false;
+        };
+        let Some(selection) = copy_ax_attribute(field, "AXSelectedTextRange") else {
+            return false;
+        };
+        let mut caret = CFRange {
+            location: 0,
+            length: 0,
+        };
+        let caret_ptr = &mut caret as *mut CFRange as *mut c_void;
+        if !AXValueGetValue(selection.as_CFTypeRef(), AX_VALUE_CF_RANGE, caret_ptr)
+            || caret.length != 0
+        {
+            return false;
+        }
+
+        // Accessibility ranges count UTF-16 code units.
+        let content: Vec<u16> = value.to_string().encode_utf16().collect();
+        let old: Vec<u16> = old.encode_utf16().collect();
+        let end = caret.location as usize;
+        let Some(start) = end.checked_sub(old.len()) else {
+            return false;
+        };
+        if content.get(start..end) != Some(old.as_slice()) {
+            return false;
+        }
+
+        let range = CFRange {
+            location: start as CFIndex,
+            length: old.len() as CFIndex,
+        };
+        let range = AXValueCreate(AX_VALUE_CF_RANGE, &range as *const CFRange as *const c_void);
+        if range.is_null() {
+            return false;
+        }
+        let range = CFType::wrap_under_create_rule(range);
+        let selected_range = CFString::new("AXSelectedTextRange");
+        let selected_text = CFString::new("AXSelectedText");
+        let replaced = AXUIElementSetAttributeValue(
+            field,
+            selected_range.as_concrete_TypeRef(),
+            range.as_CFTypeRef(),
+        ) == AX_SUCCESS
+            && AXUIElementSetAttributeValue(
+                field,
+                selected_text.as_concrete_TypeRef(),
+                CFString::new(new).as_CFTypeRef(),
+            ) == AX_SUCCESS;
+        if !replaced {
+            warn!("Focused field refused the accessibility text replacement");
+        }
+        replaced
+    }
+}
+
+#[link(name = "AppKit", kind = "framework")]
+extern "C" {
+    static NSAccessibilityAnnouncementRequestedNotification: *mut objc::runtime::Object;
+    static NSAccessibilityAnnouncementKey: *mut objc::runtime::Object;
+    static NSAccessibilityPriorityKey: *mut objc::runtime::Object;
+    fn NSAccessibilityPostNotificationWithUserInfo(
+        element: *mut objc::runtime::Object,
+        notification: *mut objc::runtime::Object,
+        user_info: *mut objc::runtime::Object,
+    );
+}
+
+/// Have VoiceOver read `text` out, interrupting lower-priority speech.
+/// Must run on the main thread.
+pub fn announce(text: &str) {
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    // NSAccessibilityPriorityHigh
+    const PRIORITY_HIGH: i64 = 90;
+
+    let Ok(text) = std::ffi::CString::new(text) else {
+        return;
+    };
+    unsafe {
+        let app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+        let message: *mut Object = msg_send![class!(NSString), stringWithUTF8String: text.as_ptr()];
+        let priority: *mut Object = msg_send![class!(NSNumber), numberWithInteger: PRIORITY_HIGH];
+        let keys = [NSAccessibilityAnnouncementKey, NSAccessibilityPriorityKey];
+        let values = [message, priority];
+        let user_info: *mut Object = msg_send![
+            class!(NSDictionary),
+            dictionaryWithObjects: values.as_ptr()
+            forKeys: keys.as_ptr()
+            count: keys.len()
+        ];
+        NSAccessibilityPostNotificationWithUserInfo(
+            app,
+            NSAccessibilityAnnouncementRequestedNotification,
+            user_info,
+        );
+    }
+}
+
+/// AppleScript that links `target` into `/usr/local/bin` as root. The path
+/// is single-quoted for the shell, then escaped for the AppleScript string.
+fn admin_link_script(target: &Path) -> String {
+    use sagascript_core::cli_install::{COMMAND_NAME, SYSTEM_BIN_DIR};
+
+    let quoted = format!("'{}'", target.display().to_string().replace('\'', "'\\''"));
+    let shell =
+        format!("mkdir -p {SYSTEM_BIN_DIR} && ln -sfn {quoted} {SYSTEM_BIN_DIR}/{COMMAND_NAME}");
+    format!(
+        "do shell script \"{}\" with administrator privileges",
+        shell.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+/// Link the CLI into `/usr/local/bin` behind the system's admin password
+/// prompt, for when the app can't write there itself. `Ok(false)` means the
+/// user cancelled the prompt.
+pub fn link_cli_as_admin(target: &Path) -> Result<bool, String> {
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(admin_link_script(target))
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {e}"))?;
+    if output.status.success() {
+        info!("Linked CLI as admin -> {}", target.display());
+        return Ok(true);
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // -128 is AppleScript's "User canceled."
+    if stderr.contains("(-128)") {
+        return Ok(false);
+    }
+    Err(format!("Admin link failed: {}", stderr.trim()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        accessibility_settings_command, interpret_open_result, ACCESSIBILITY_SETTINGS_URL,
+        accessibility_settings_command, admin_link_script, interpret_open_result,
+        ACCESSIBILITY_SETTINGS_URL,
     };
     use std::ffi::OsStr;
     use std::io;
     use std::os::unix::process::ExitStatusExt;
+    use std::path::Path;
     use std::process::ExitStatus;
 
     #[test]
@@ -107,4 +402,15 @@ mod tests {
     fn accessibility_settings_success_is_accepted() {
         assert!(interpret_open_result(Ok(ExitStatus::from_raw(0))).is_ok());
     }
+
+    #[test]
+    fn admin_link_script_quotes_the_target() {
+        let script = admin_link_script(Path::new("/Users/o'neil/Apps/Sagascript.app/sagascript"));
+        assert_eq!(
+            script,
+            "do shell script \"mkdir -p /usr/local/bin && ln -sfn \
+             '/Users/o'\\\\''neil/Apps/Sagascript.app/sagascript' /usr/local/bin/sagascript\" \
+             with administrator privileges"
+        );
+    }
 }
@@ -17,3 +17,54 @@ pub fn is_accessibility_trusted() -> bool {
 pub fn request_accessibility_permission() {
     // Nothing to do
 }
+
+#[link(name = "user32")]
+extern "system" {
+    fn GetForegroundWindow() -> *mut std::ffi::c_void;
+    fn GetWindowThreadProcessId(hwnd: *mut std::ffi::c_void, process_id: *mut u32) -> u32;
+}
+
+#[link(name = "kernel32")]
+extern "system" {
+    fn OpenProcess(access: u32, inherit: i32, process_id: u32) -> *mut std::ffi::c_void;
+    fn QueryFullProcessImageNameW(
+        process: *mut std::ffi::c_void,
+        flags: u32,
+        name: *mut u16,
+        size: *mut u32,
+    ) -> i32;
+    fn CloseHandle(handle: *mut std::ffi::c_void) -> i32;
+}
+
+const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+
+/// The app that owns the foreground window, identified by its executable
+/// name (e.g. `WindowsTerminal.exe`).
+pub fn frontmost_app() -> Option<sagascript_core::paste_format::FrontmostApp> {
+    let path = unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_null() {
+            return None;
+        }
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, &mut pid);
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            return None;
+        }
+        let mut buf = [0u16; 1024];
+        let mut len = buf.len() as u32;
+        let ok = QueryFullProcessImageNameW(process, 0, buf.as_mut_ptr(), &mut len);
+        CloseHandle(process);
+        if ok == 0 {
+            return None;
+        }
+        String::from_utf16_lossy(&buf[..len as usize])
+    };
+    let exe = std::path::Path::new(&path).file_name()?.to_string_lossy();
+    let name = exe.strip_suffix(".exe").unwrap_or(&exe).to_string();
+    Some(sagascript_core::paste_format::FrontmostApp {
+        id: exe.to_string(),
+        name,
+    })
+}
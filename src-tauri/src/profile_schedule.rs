@@ -0,0 +1,85 @@
+//! Switching profiles on a schedule.
+//!
+//! Once a minute this checks which profile's `schedule` is due (see
+//! `sagascript_core::settings::schedule`) and, when that changes, makes its
+//! language and model the global ones and emits `settings-changed`. Only the
+//! switch itself writes the settings, so a language or model picked by hand
+//! in between stays until the next one. A switch that falls during a
+//! dictation waits for it to finish.
+
+use std::time::Duration;
+
+use chrono::Local;
+use sagascript_core::settings::Profile;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{error, info};
+
+use crate::commands::SharedController;
+use crate::events;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Apply scheduled profiles for as long as the app runs.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        // Name of the profile last due, so each one is applied once per turn.
+        let mut last_due: Option<String> = None;
+        loop {
+            let (due, busy) = {
+                let ctrl: tauri::State<'_, SharedController> = app.state();
+                let c = ctrl.lock().unwrap();
+                let due = c
+                    .settings()
+                    .scheduled_profile(Local::now().naive_local())
+                    .cloned();
+                (due, c.state().is_busy())
+            };
+            let name = due.as_ref().map(|p| p.name.clone());
+            if name != last_due && !busy {
+                if let Some(profile) = due {
+                    match apply(&app, &profile) {
+                        Ok(()) => last_due = name,
+                        Err(e) => {
+                            error!("Scheduled switch to profile {} failed: {e}", profile.name)
+                        }
+                    }
+                } else {
+                    last_due = None;
+                }
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// Make `profile`'s language and model the global ones.
+fn apply(app: &AppHandle, profile: &Profile) -> Result<(), String> {
+    let ctrl: tauri::State<'_, SharedController> = app.state();
+    let current = ctrl.lock().unwrap().settings().clone();
+    let applied = current.with_profile(profile);
+    if applied.language == current.language
+        && applied.whisper_model == current.whisper_model
+        && applied.auto_select_model == current.auto_select_model
+    {
+        return Ok(());
+    }
+
+    let persisted = sagascript_core::settings::store::update(|s| s.apply_profile(profile))?;
+    {
+        let mut c = ctrl.lock().unwrap();
+        c.settings_mut().language = persisted.language;
+        c.settings_mut().whisper_model = persisted.whisper_model;
+        c.settings_mut().auto_select_model = persisted.auto_select_model;
+    }
+    info!(
+        "Scheduled profile {}: {:?} with {}",
+        profile.name,
+        persisted.language,
+        persisted.effective_model().display_name()
+    );
+    let _ = app.emit(
+        events::event::SETTINGS_CHANGED,
+        serde_json::json!({ "reason": "schedule", "profile": profile.name }),
+    );
+    Ok(())
+}
@@ -0,0 +1,114 @@
+//! Countdown to the recording length limit.
+//!
+//! The capture buffer stops taking audio at `max_recording_minutes`. Rather
+//! than let the end of a long dictation vanish, the last minute is counted
+//! down with `recording-limit` events (shown in the overlay), and the
+//! recording is stopped and transcribed when the limit is reached, with a
+//! `BufferFull` error saying why.
+
+use std::time::Duration;
+
+use sagascript_core::audio::capture;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::warn;
+
+use crate::commands::SharedController;
+use crate::events;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LimitPayload {
+    remaining_secs: u64,
+    limit_secs: u64,
+}
+
+/// Watch the recording that just began until it ends or hits the limit.
+pub fn begin(app: &AppHandle) {
+    let started = {
+        let ctrl: tauri::State<'_, SharedController> = app.state();
+        let c = ctrl.lock().unwrap();
+        c.recording_started_at()
+    };
+    let Some(started) = started else { return };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut last_sent = None;
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let (elapsed, max) = {
+                let ctrl: tauri::State<'_, SharedController> = app.state();
+                let c = ctrl.lock().unwrap();
+                if c.recording_started_at() != Some(started) {
+                    break;
+                }
+                (c.recording_elapsed(), c.recording_limit())
+            };
+            let Some(remaining) = capture::limit_remaining(elapsed, max) else {
+                continue;
+            };
+
+            let remaining_secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+            if last_sent != Some(remaining_secs) {
+                last_sent = Some(remaining_secs);
+                let payload = LimitPayload {
+                    remaining_secs,
+                    limit_secs: max.as_secs(),
+                };
+                let _ = app.emit(events::event::RECORDING_LIMIT, payload);
+                if let Some(text) = announcement(remaining_secs) {
+                    crate::dispatch_to_main(&app, move |app| crate::overlay::announce(app, text));
+                }
+            }
+
+            if remaining.is_zero() {
+                warn!(
+                    "Recording reached the {}-minute limit, stopping",
+                    max.as_secs() / 60
+                );
+                let ctrl: tauri::State<'_, SharedController> = app.state();
+                crate::stop_recording_and_transcribe(&app, &ctrl);
+                break;
+            }
+        }
+    });
+}
+
+/// What a point in the countdown is read out as; most seconds aren't.
+fn announcement(remaining_secs: u64) -> Option<&'static str> {
+    match remaining_secs {
+        60 => Some("One minute of recording left"),
+        30 => Some("30 seconds of recording left"),
+        10 => Some("10 seconds of recording left"),
+        0 => Some("Recording limit reached"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn countdown_payload_is_camel_case() {
+        let payload = LimitPayload {
+            remaining_secs: 42,
+            limit_secs: 900,
+        };
+        assert_eq!(
+            serde_json::to_value(payload).unwrap(),
+            serde_json::json!({ "remainingSecs": 42, "limitSecs": 900 })
+        );
+    }
+
+    #[test]
+    fn only_milestones_are_announced() {
+        assert!(announcement(60).is_some());
+        assert!(announcement(0).is_some());
+        assert!(announcement(59).is_none());
+    }
+}
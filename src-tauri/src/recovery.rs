@@ -0,0 +1,139 @@
+//! Crash recovery for hotkey dictations.
+//!
+//! From the moment a recording starts, the audio captured so far is
+//! appended to `recovery/live` every few seconds, and the rest once the
+//! recording stops, so a crash while recording or transcribing loses at
+//! most the last few seconds. The snapshot is removed when the dictation
+//! ends. On launch a snapshot the last run left behind is moved aside and
+//! offered back through `get_recoverable_session`.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use sagascript_core::recovery::{self, RecoverableSession, RecoveryStage};
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+use crate::commands::SharedController;
+
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The dictation being snapshotted: its recording's start and the
+/// device-rate offset captured up to. Held while writing, so a snapshot
+/// can't land after the dictation was cleared.
+pub struct LiveSnapshot {
+    started: Instant,
+    consumed: usize,
+}
+
+pub type SharedLiveSnapshot = Mutex<Option<LiveSnapshot>>;
+
+/// At launch: move a dictation the last run didn't finish out of the way
+/// of this run's snapshots.
+pub fn take_over() -> Option<RecoverableSession> {
+    match recovery::take_over(&recovery::live_dir(), &recovery::crashed_dir()) {
+        Ok(Some(session)) => {
+            info!(
+                "Found an interrupted dictation ({:.1}s of audio)",
+                session.audio_secs
+            );
+            Some(session)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            warn!("Failed to set aside the interrupted dictation: {e}");
+            None
+        }
+    }
+}
+
+/// Start snapshotting the recording that just began.
+pub fn begin(app: &AppHandle) {
+    let (started, session) = {
+        let ctrl: tauri::State<'_, SharedController> = app.state();
+        let c = ctrl.lock().unwrap();
+        let settings = c.dictation_settings();
+        let session = RecoverableSession::new(
+            RecoveryStage::Recording,
+            settings.language,
+            settings.effective_model(),
+        );
+        (c.recording_started_at(), session)
+    };
+    let Some(started) = started else { return };
+
+    {
+        let slot: tauri::State<'_, SharedLiveSnapshot> = app.state();
+        let mut slot = slot.lock().unwrap();
+        if let Err(e) = recovery::start(&recovery::live_dir(), &session) {
+            warn!("Crash recovery unavailable for this dictation: {e}");
+            return;
+        }
+        *slot = Some(LiveSnapshot {
+            started,
+            consumed: 0,
+        });
+    }
+
+    // Plain thread: each snapshot is blocking file I/O.
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(SNAPSHOT_INTERVAL);
+
+        let slot: tauri::State<'_, SharedLiveSnapshot> = app.state();
+        let mut slot = slot.lock().unwrap();
+        let Some(live) = slot.as_mut().filter(|l| l.started == started) else {
+            break;
+        };
+        let chunk = {
+            let ctrl: tauri::State<'_, SharedController> = app.state();
+            let c = ctrl.lock().unwrap();
+            if c.recording_started_at() != Some(started) || !c.state().is_recording() {
+                break;
+            }
+            c.recording_chunk_since(live.consumed, 1.0, 0.0)
+        };
+        match chunk {
+            Ok(Some((samples, end))) => {
+                if let Err(e) = recovery::append(&recovery::live_dir(), &samples) {
+                    warn!("Crash recovery snapshot failed: {e}");
+                }
+                live.consumed = end;
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Crash recovery snapshot failed: {e}"),
+        }
+    });
+}
+
+/// The recording stopped with `audio` (16 kHz): save what the snapshots
+/// haven't covered yet and mark the dictation as transcribing.
+pub fn transcribing(app: &AppHandle, started: Option<Instant>, audio: &[f32]) {
+    let slot: tauri::State<'_, SharedLiveSnapshot> = app.state();
+    let slot = slot.lock().unwrap();
+    let Some(live) = slot.as_ref().filter(|l| Some(l.started) == started) else {
+        return;
+    };
+    let from = {
+        let ctrl: tauri::State<'_, SharedController> = app.state();
+        let c = ctrl.lock().unwrap();
+        c.recording_offset_at_16khz(live.consumed).min(audio.len())
+    };
+    let dir = recovery::live_dir();
+    if let Err(e) = recovery::append(&dir, &audio[from..])
+        .and_then(|()| recovery::set_stage(&dir, RecoveryStage::Transcribing))
+    {
+        warn!("Crash recovery snapshot failed: {e}");
+    }
+}
+
+/// The dictation ended one way or another; its snapshot isn't needed.
+pub fn end(app: &AppHandle) {
+    let slot: tauri::State<'_, SharedLiveSnapshot> = app.state();
+    let mut slot = slot.lock().unwrap();
+    if slot.take().is_some() {
+        if let Err(e) = recovery::clear(&recovery::live_dir()) {
+            warn!("Failed to remove the crash recovery snapshot: {e}");
+        }
+    }
+}
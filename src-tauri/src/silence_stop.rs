@@ -0,0 +1,55 @@
+//! Stopping a toggle-mode dictation once the speaker goes quiet.
+//!
+//! With `silence_stop_secs` set, the capture callback tracks how long the
+//! input has been silent since the last speech. This polls it for the
+//! recording that just began and, once the limit is reached, stops and
+//! transcribes as if the hotkey had been pressed again.
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+use crate::commands::SharedController;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Watch the recording that just began until it ends or goes quiet.
+pub fn begin(app: &AppHandle) {
+    let (started, silence) = {
+        let ctrl: tauri::State<'_, SharedController> = app.state();
+        let c = ctrl.lock().unwrap();
+        (c.recording_started_at(), c.settings().silence_stop())
+    };
+    let (Some(started), Some(silence)) = (started, silence) else {
+        return;
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let timed_out = {
+                let ctrl: tauri::State<'_, SharedController> = app.state();
+                let c = ctrl.lock().unwrap();
+                if c.recording_started_at() != Some(started) {
+                    break;
+                }
+                c.silence_timed_out()
+            };
+            if timed_out {
+                info!(
+                    "No speech for {}s, stopping the recording",
+                    silence.as_secs()
+                );
+                crate::dispatch_to_main(&app, |app| {
+                    crate::overlay::announce(app, "Stopped after silence")
+                });
+                let ctrl: tauri::State<'_, SharedController> = app.state();
+                crate::stop_recording_and_transcribe(&app, &ctrl);
+                break;
+            }
+        }
+    });
+}
@@ -0,0 +1,157 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use sagascript_core::settings::{CaptureSource, Settings, TrayMenuItem};
+use tauri::menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem};
+use tauri::Manager;
+use tracing::{error, info};
+
+use crate::SharedStatusItem;
+
+pub const TRAY_ID: &str = "main";
+
+/// `Settings::tray_status_text`, mirrored outside the controller: tray
+/// updates are sometimes made while the controller lock is held.
+pub struct StatusTextEnabled(AtomicBool);
+
+impl StatusTextEnabled {
+    pub fn new(enabled: bool) -> Self {
+        Self(AtomicBool::new(enabled))
+    }
+
+    pub fn get(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Build the tray menu: the items chosen in `tray_menu` in their fixed
+/// order, then "Quit". Stores the status item (or `None` when it's hidden)
+/// so status updates have something to write to.
+pub fn build_menu(
+    app: &tauri::AppHandle,
+    settings: &Settings,
+) -> tauri::Result<Menu<tauri::Wry>> {
+    let mut entries: Vec<Box<dyn IsMenuItem<tauri::Wry>>> = Vec::new();
+    let mut status_item = None;
+    for item in TrayMenuItem::ALL
+        .iter()
+        .filter(|i| settings.tray_menu.contains(i))
+    {
+        // The CLI can't be symlinked onto PATH on Windows.
+        if cfg!(target_os = "windows") && *item == TrayMenuItem::InstallCli {
+            continue;
+        }
+        let entry: Box<dyn IsMenuItem<tauri::Wry>> = match item {
+            TrayMenuItem::Status => {
+                let status =
+                    MenuItem::with_id(app, "status", "Sagascript - Idle", false, None::<&str>)?;
+                status_item = Some(status.clone());
+                Box::new(status)
+            }
+            TrayMenuItem::Settings => Box::new(MenuItem::with_id(
+                app,
+                "settings",
+                "Open Sagascript...",
+                true,
+                None::<&str>,
+            )?),
+            TrayMenuItem::TranscribeFile => Box::new(MenuItem::with_id(
+                app,
+                "transcribe_file",
+                "Transcribe File...",
+                true,
+                None::<&str>,
+            )?),
+            TrayMenuItem::CaptureSource => Box::new(CheckMenuItem::with_id(
+                app,
+                "capture_source",
+                "Record System Audio",
+                true,
+                settings.capture_source == CaptureSource::System,
+                None::<&str>,
+            )?),
+            TrayMenuItem::InstallCli => Box::new(MenuItem::with_id(
+                app,
+                "install_cli",
+                "Install Command Line Tool...",
+                true,
+                None::<&str>,
+            )?),
+        };
+        entries.push(entry);
+    }
+    entries.push(Box::new(MenuItem::with_id(
+        app,
+        "quit",
+        "Quit Sagascript",
+        true,
+        None::<&str>,
+    )?));
+
+    *app.state::<SharedStatusItem>().lock().unwrap() = status_item;
+
+    let refs: Vec<&dyn IsMenuItem<tauri::Wry>> = entries.iter().map(|e| e.as_ref()).collect();
+    Menu::with_items(app, &refs)
+}
+
+/// Tray "Install Command Line Tool...": link the CLI off the main thread
+/// (it may wait on an admin password prompt), then report the outcome in a
+/// dialog, since the tray has nowhere else to show it.
+pub fn install_cli(app: &tauri::AppHandle) {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let (kind, message) = match crate::commands::install_cli_link() {
+            Ok(installed) => {
+                info!("CLI linked from tray: {}", installed.link.display());
+                let mut message = format!(
+                    "The sagascript command is installed at {}.",
+                    installed.link.display()
+                );
+                if !installed.on_path {
+                    message.push_str(" If your shell can't find it, add that folder to your PATH.");
+                }
+                (MessageDialogKind::Info, message)
+            }
+            Err(e) => {
+                error!("CLI install from tray failed: {e}");
+                (
+                    MessageDialogKind::Error,
+                    format!("Couldn't install the sagascript command: {e}"),
+                )
+            }
+        };
+        app.dialog()
+            .message(message)
+            .title("Command Line Tool")
+            .kind(kind)
+            .show(|_| {});
+    });
+}
+
+/// Apply tray settings to the running tray. Must run on the main thread.
+pub fn apply(app: &tauri::AppHandle, settings: &Settings) {
+    app.state::<StatusTextEnabled>()
+        .0
+        .store(settings.tray_status_text, Ordering::Relaxed);
+
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app, settings) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => error!("Failed to rebuild tray menu: {e}"),
+    }
+    if !settings.tray_status_text {
+        let _ = tray.set_title(None::<&str>);
+    }
+    if let Err(e) = tray.set_visible(settings.tray_visible) {
+        error!("Failed to set tray visibility: {e}");
+    }
+    info!(
+        "Tray applied (visible: {}, status text: {}, menu: {:?})",
+        settings.tray_visible, settings.tray_status_text, settings.tray_menu
+    );
+}
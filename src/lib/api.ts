@@ -1,29 +1,158 @@
 import { invoke } from "@tauri-apps/api/core";
+import { EVENT_SCHEMA_VERSION } from "./events";
 
 export type Language = "en" | "sv" | "no" | "auto";
 export type HotkeyMode = "push" | "toggle";
+/** What a hotkey press does while a transcription is still running. */
+export type WhileBusy = "reject" | "queue" | "cancel-and-restart";
+/** Where hotkey dictations are transcribed. */
+export type BackendKind = "local" | "openai" | "azure";
 
+/** Catalog entry, same shape as `sagascript list-models --json`. */
 export interface WhisperModel {
   id: string;
   display_name: string;
   description: string;
   size_mb: number;
+  languages: Language[];
+  recommended: boolean;
   downloaded: boolean;
   active: boolean;
 }
 
+/** One entry in the output routing table (`output_sinks`). */
+/** File, pipe and webhook sinks take an optional entry template with
+ * `{text}`, `{timestamp}`, `{language}`, `{model}` and `{app}`. */
+export type OutputSink =
+  | { type: "clipboard" }
+  | { type: "paste" }
+  | { type: "file"; path: string; template?: string }
+  | { type: "pipe"; path: string; template?: string }
+  | { type: "daily_note"; vault: string; file: string; heading: string }
+  | { type: "webhook"; url: string; template?: string }
+  | { type: "mqtt"; url: string };
+
+/** Tone played when a profile's recording starts. */
+export type SoundCue = "none" | "low" | "mid" | "high";
+
+/** A named language and model with its own dictation hotkey (`config profile`). */
+export interface Profile {
+  name: string;
+  language: Language;
+  /** Model id; null follows the language's recommended model. */
+  whisper_model: string | null;
+  /** Empty for none (CLI-only profile). */
+  hotkey: string;
+  /** Overlay accent while recording, `#rrggbb`; empty for the default. */
+  overlay_color: string;
+  sound_cue: SoundCue;
+  /** When the profile becomes the default, e.g. "mon-fri 09:00-17:00" or
+   * "otherwise"; empty for never. */
+  schedule: string;
+  /** Where the profile's dictations go; null follows `output_sinks`. */
+  output_sinks: OutputSink[] | null;
+}
+
 export interface Settings {
   language: Language;
   whisper_model: string;
   hotkey_mode: HotkeyMode;
   show_overlay: boolean;
-  auto_paste: boolean;
   auto_select_model: boolean;
   hotkey: string;
   initial_prompt: string;
   beam_size: number;
   temperature_fallback: boolean;
   vad_enabled: boolean;
+  adaptive_model: boolean;
+  adaptive_short_clip_secs: number;
+  adaptive_long_clip_secs: number;
+  accumulate_mode: boolean;
+  flush_hotkey: string;
+  flush_phrase: string;
+  output_sinks: OutputSink[];
+  prompt_history: string[];
+  profile_prompt_history: Record<string, string[]>;
+  model_update_check: boolean;
+  /** Per-model decoding overrides, keyed by model id (`config model-override`). */
+  model_overrides: Record<string, ModelOverrides>;
+  /** Local GGML files registered by name (`sagascript models add`). */
+  custom_models: Record<string, string>;
+  /** Registered custom model in use; "" = the built-in models. */
+  custom_model: string;
+  /** Paste a tiny-model draft first, then the selected model's text. */
+  draft_pass: boolean;
+  /** Swap the draft for the final text (macOS, via Accessibility). */
+  replace_draft: boolean;
+  /** Skip a dictation whose audio repeats the previous one (double press). */
+  dedupe_dictations: boolean;
+  /** Run dictations without pasting or routing them; history and events only. */
+  dry_run: boolean;
+  monitor_input: boolean;
+  /** Linear gain, 0.0–2.0. */
+  monitor_volume: number;
+  ffmpeg_fallback: boolean;
+  while_busy: WhileBusy;
+  /** Words decoding is biased toward (`config set keywords`). */
+  keywords: string[];
+  localize_numbers: boolean;
+  voice_commands: boolean;
+  /** false = strip punctuation from dictations. */
+  punctuate: boolean;
+  /** Find-and-replace rules run over every transcript, in order (`config rules`). */
+  replacement_rules: ReplacementRule[];
+  /** Language-model cleanup of each dictation: a shell command or an
+   * OpenAI-compatible endpoint URL; empty is off. */
+  post_process_command: string;
+  /** Instructions for the model; empty uses the built-in grammar fix. */
+  post_process_prompt: string;
+  /** Model an endpoint is asked for. */
+  post_process_model: string;
+  /** Dictations only; files are always transcribed locally. */
+  transcription_backend: BackendKind;
+  /** Model the OpenAI backend asks for, e.g. `whisper-1`. */
+  openai_model: string;
+  azure_region: string;
+  event_stream_port: number;
+  use_gpu: boolean;
+  /** Load the model when the app starts; read at startup. */
+  preload_model: boolean;
+  paste_formatting: boolean;
+  /** Paste format per app, keyed by bundle ID, executable, WM_CLASS or
+   * display name (`config paste-rule`). */
+  paste_app_rules: Record<string, AppCategory>;
+  tray_visible: boolean;
+  tray_status_text: boolean;
+  /** Optional tray menu items; "Quit" is always shown. */
+  tray_menu: TrayMenuItem[];
+  telemetry: boolean;
+  telemetry_endpoint: string;
+  network_proxy: string;
+  model_mirror: string;
+  /** Download URL per model ID, tried first (`sagascript models url`). */
+  model_urls: Record<string, string>;
+  overlay_high_contrast: boolean;
+  overlay_large_text: boolean;
+  overlay_reduced_motion: boolean;
+  /** Read recording/transcribing/ready out to screen readers. */
+  announce_state: boolean;
+  /** Global shortcut that opens this window; empty for none. */
+  settings_hotkey: string;
+  settings_hotkey_tab: SettingsTab;
+  /** Second dictation hotkey that always toggles; empty for none. */
+  toggle_hotkey: string;
+  /** Longest dictation, 1–120 minutes. */
+  max_recording_minutes: number;
+  /** Toggle mode: seconds of silence after speech that stop a dictation, 0–60; 0 is off. */
+  silence_stop_secs: number;
+  /** Auto-paste asks first for transcripts longer than this; 0 never asks. */
+  paste_confirm_chars: number;
+  capture_source: CaptureSource;
+  /** Keep the input open between recordings so capture starts instantly. */
+  standby_stream: boolean;
+  /** Transcribe each channel of a file separately, labelled CH1, CH2, … */
+  split_channels: boolean;
+  profiles: Profile[];
   has_completed_onboarding: boolean;
 }
 
@@ -33,6 +162,63 @@ export interface BuildInfo {
   build_date: string;
 }
 
+export interface TranscriptSegment {
+  start: number;
+  end: number;
+  text: string;
+  avg_logprob: number | null;
+  no_speech_prob: number;
+}
+
+/** Payload of the `transcript` event emitted after each dictation. */
+export interface Transcript {
+  text: string;
+  segments: TranscriptSegment[];
+  partial: boolean;
+}
+
+/** What the whisper.cpp engine is running with (`sagascript runtime --json`). */
+export interface WhisperRuntimeInfo {
+  whisper_rs_version: string;
+  whisper_rs_sys_version: string;
+  backends: string[];
+  cpu_features: string[];
+  threads: number;
+  system_info: string;
+  model: {
+    id: string;
+    name: string;
+    path: string;
+    size_bytes: number | null;
+    coreml_encoder: boolean;
+    loaded: boolean;
+  };
+}
+
+/** Whether local inference runs on a GPU (Metal, CUDA). */
+export interface AccelerationInfo {
+  /** GPU backend in this build, e.g. "Metal"; null = CPU only. */
+  gpu_backend: string | null;
+  coreml: boolean;
+  use_gpu: boolean;
+  gpu_active: boolean;
+}
+
+/** A model for a language on this machine (`get_recommended_model`). */
+export interface Recommendation {
+  model: string;
+  display_name: string;
+  size_mb: number;
+  hardware: {
+    cpu_cores: number;
+    /** null where the system doesn't report it. */
+    memory_bytes: number | null;
+    gpu: boolean;
+  };
+  /** One sentence on why this model suits the machine. */
+  reason: string;
+}
+
 export interface LoadedModelInfo {
   effective_model: string;
   effective_model_id: string;
@@ -41,6 +227,158 @@ export interface LoadedModelInfo {
   is_downloaded: boolean;
 }
 
+/** Emitted as `model-suggestion` when dictation on `current` has been
+ * consistently slower than real time (`rtf` = processing time / audio length). */
+export interface ModelSuggestion {
+  current: string;
+  suggested: string;
+  rtf: number;
+}
+
+export type TrayMenuItem =
+  | "status"
+  | "settings"
+  | "transcribe-file"
+  | "capture-source"
+  | "install-cli";
+
+/** Record the microphone, or what the machine is playing (calls, videos). */
+export type CaptureSource = "microphone" | "system";
+
+/** Result of linking the app binary onto PATH as `sagascript`. */
+export interface CliInstall {
+  link: string;
+  target: string;
+  /** Whether the link's folder is on the app's PATH (not the shell's, on macOS). */
+  on_path: boolean;
+}
+
+/** One day's queued telemetry counts, exactly as they would be sent. */
+export interface TelemetryDay {
+  day: string;
+  dictations: Record<string, number>;
+  models: Record<string, number>;
+  file_transcriptions: number;
+  errors: Record<string, number>;
+}
+
+/** How the overlay draws itself (the `overlay-appearance` payload). */
+export interface OverlayAppearance {
+  high_contrast: boolean;
+  large_text: boolean;
+  reduced_motion: boolean;
+}
+
+/** How auto-paste formats text for an app. */
+export type AppCategory = "terminal" | "email" | "other";
+
+export interface ReplacementRule {
+  find: string;
+  replace: string;
+  regex: boolean;
+}
+
+export interface ModelOverrides {
+  no_speech_threshold?: number;
+  beam_size?: number;
+  temperature_fallback?: boolean;
+}
+
+/** A downloaded model whose upstream revision differs from the installed
+ * one; emitted as `model-updates-available` by the opt-in daily check. */
+export interface ModelUpdate {
+  model: string;
+  display_name: string;
+  installed_commit: string;
+  latest_commit: string | null;
+  update_available: boolean;
+}
+
+/** Payload of the `error` event: a serialized `DictationError`. */
+export interface DictationErrorPayload {
+  kind: string;
+  message?: string;
+  suggestion: string;
+}
+
+export type TranscriptionStage =
+  | "decoding"
+  | "loading_model"
+  | "transcribing"
+  | "post_processing";
+
+/** Payload of the `transcription-progress` event. */
+export interface StageProgress {
+  stage: TranscriptionStage;
+  pct: number;
+}
+
+/** Payload of the `input-level-warning` event. */
+export interface InputLevelWarning {
+  kind: "clipping" | "quiet";
+  message: string;
+}
+
+/** Where a dictation's time went (`transcription-complete`), in milliseconds. */
+export interface TranscriptionTiming {
+  capture_ms: number;
+  decode_ms: number;
+  model_load_ms: number;
+  inference_ms: number;
+  post_process_ms: number;
+  paste_ms: number;
+  /** From the end of the recording to the pasted text. */
+  total_ms: number;
+}
+
+export type SessionOutcome =
+  | "completed"
+  | "empty"
+  | "failed"
+  | "cancelled"
+  | "duplicate"
+  | "unfinished";
+
+/** One dictation on the session timeline, same shape as `sagascript sessions --json`. */
+export interface SessionRecord {
+  id: string;
+  started_at: string;
+  ended_at: string | null;
+  outcome: SessionOutcome;
+  recording_ms: number | null;
+  duration_ms: number | null;
+  model: string | null;
+  chars: number | null;
+  words: number | null;
+  /** Words per minute of recording; null for very short recordings. */
+  wpm: number | null;
+  error: string | null;
+}
+
+/** Hotkey dictation, a file from the Transcribe tab, or `sagascript record`/`transcribe`. */
+export type HistorySource = "dictation" | "file" | "cli";
+
+/** A finished transcription, as kept in the on-disk history. */
+export interface HistoryEntry {
+  id: string;
+  /** Seconds since the Unix epoch. */
+  recorded_at: number;
+  source: HistorySource;
+  file?: string;
+  /** Input device a recording was made with. */
+  device?: string;
+  duration_secs: number;
+  model: string;
+  language: Language;
+  text: string;
+}
+
+/** Accumulate-mode buffer, also emitted as `accumulated-changed`. */
+export interface AccumulatedSnapshot {
+  text: string;
+  count: number;
+}
+
 export type AppState = "idle" | "recording" | "transcribing" | "error";
 
 export interface HotkeyStatus {
@@ -57,6 +395,20 @@ export async function getSettings(): Promise<Settings> {
   return invoke("get_settings");
 }
 
+/** Why the settings file didn't load, payload of `settings-load-warning`. */
+export interface SettingsLoadIssue {
+  /** 1-based; 0 when the file couldn't be read at all. */
+  line: number;
+  column: number;
+  /** The setting with the wrong value, when the JSON itself is valid. */
+  key: string | null;
+  message: string;
+}
+
+export async function getSettingsLoadWarning(): Promise<SettingsLoadIssue | null> {
+  return invoke("get_settings_load_warning");
+}
+
 export async function setLanguage(language: Language): Promise<void> {
   return invoke("set_language", { language });
 }
@@ -69,6 +421,51 @@ export async function setAutoSelectModel(enabled: boolean): Promise<void> {
   return invoke("set_auto_select_model", { enabled });
 }
 
+export async function getModelSuggestion(): Promise<ModelSuggestion | null> {
+  return invoke("get_model_suggestion");
+}
+
+/** Switch to the suggested lighter model; returns its id. */
+export async function applyModelSuggestion(): Promise<string> {
+  return invoke("apply_model_suggestion");
+}
+
+export async function dismissModelSuggestion(): Promise<void> {
+  return invoke("dismiss_model_suggestion");
+}
+
+export async function getModelUpdates(): Promise<ModelUpdate[]> {
+  return invoke("get_model_updates");
+}
+
+export async function getSessionTimeline(limit?: number): Promise<SessionRecord[]> {
+  return invoke("get_session_timeline", { limit });
+}
+
+export async function getHistory(): Promise<HistoryEntry[]> {
+  return invoke("get_history");
+}
+
+export async function deleteHistoryEntry(id: string): Promise<void> {
+  return invoke("delete_history_entry", { id });
+}
+
+export async function clearHistory(): Promise<void> {
+  return invoke("clear_history");
+}
+
+export async function setModelUpdateCheck(enabled: boolean): Promise<void> {
+  return invoke("set_model_update_check", { enabled });
+}
+
+export async function setToggleHotkey(hotkey: string): Promise<void> {
+  return invoke("set_toggle_hotkey", { hotkey });
+}
+
+export async function setProfiles(profiles: Profile[]): Promise<void> {
+  return invoke("set_profiles", { profiles });
+}
+
 export async function setHotkeyMode(mode: HotkeyMode): Promise<void> {
   return invoke("set_hotkey_mode", { mode });
 }
@@ -91,10 +488,32 @@ export async function setInitialPrompt(prompt: string): Promise<void> {
   return invoke("set_initial_prompt", { prompt });
 }
 
+/** Comma-separated, like `config set keywords`. */
+export async function setKeywords(keywords: string): Promise<void> {
+  return invoke("set_keywords", { keywords });
+}
+
+/**
+ * Recently used initial prompts, newest first: those of `profile` (by default
+ * the profile of the last dictation), or the global ones if it has none.
+ */
+export async function getPromptHistory(profile?: string): Promise<string[]> {
+  return invoke("get_prompt_history", { profile: profile ?? null });
+}
+
 export async function setShowOverlay(enabled: boolean): Promise<void> {
   return invoke("set_show_overlay", { enabled });
 }
 
+export async function setOverlayAccessibility(options: OverlayAppearance & { announce_state: boolean }): Promise<void> {
+  return invoke("set_overlay_accessibility", {
+    highContrast: options.high_contrast,
+    largeText: options.large_text,
+    reducedMotion: options.reduced_motion,
+    announceState: options.announce_state,
+  });
+}
+
 export async function setBeamSize(beamSize: number): Promise<void> {
   return invoke("set_beam_size", { beamSize });
 }
@@ -107,6 +526,235 @@ export async function setVadEnabled(enabled: boolean): Promise<void> {
   return invoke("set_vad_enabled", { enabled });
 }
 
+export async function setMonitorInput(enabled: boolean, volume: number): Promise<void> {
+  return invoke("set_monitor_input", { enabled, volume });
+}
+
+export async function setMaxRecordingMinutes(minutes: number): Promise<void> {
+  return invoke("set_max_recording_minutes", { minutes });
+}
+
+export async function setSilenceStopSecs(secs: number): Promise<void> {
+  return invoke("set_silence_stop_secs", { secs });
+}
+
+export async function setPasteConfirmChars(chars: number): Promise<void> {
+  return invoke("set_paste_confirm_chars", { chars });
+}
+
+export async function setCaptureSource(source: CaptureSource): Promise<void> {
+  return invoke("set_capture_source", { source });
+}
+
+export async function setStandbyStream(enabled: boolean): Promise<void> {
+  return invoke("set_standby_stream", { enabled });
+}
+
+export async function setSplitChannels(enabled: boolean): Promise<void> {
+  return invoke("set_split_channels", { enabled });
+}
+
+/** A transcript held back from auto-paste, emitted as `paste-confirmation`. */
+export interface PasteConfirmation {
+  chars: number;
+  preview: string;
+}
+
+export async function getPendingPaste(): Promise<PasteConfirmation | null> {
+  return invoke("get_pending_paste");
+}
+
+/** Paste the held transcript; false if nothing was waiting. */
+export async function confirmPaste(): Promise<boolean> {
+  return invoke("confirm_paste");
+}
+
+export async function discardPaste(): Promise<void> {
+  return invoke("discard_paste");
+}
+
+export async function setWhileBusy(mode: WhileBusy): Promise<void> {
+  return invoke("set_while_busy", { mode });
+}
+
+export async function setLocalizeNumbers(enabled: boolean): Promise<void> {
+  return invoke("set_localize_numbers", { enabled });
+}
+
+export async function setVoiceCommands(enabled: boolean): Promise<void> {
+  return invoke("set_voice_commands", { enabled });
+}
+
+export async function setPunctuate(enabled: boolean): Promise<void> {
+  return invoke("set_punctuate", { enabled });
+}
+
+/** Add a rule after the existing ones; `regex` makes `find` a pattern. */
+export async function addReplacementRule(find: string, replace: string, regex: boolean): Promise<void> {
+  return invoke("add_replacement_rule", { find, replace, regex });
+}
+
+/** Remove the rule at `index` (0-based). */
+export async function removeReplacementRule(index: number): Promise<void> {
+  return invoke("remove_replacement_rule", { index });
+}
+
+export async function setPostProcess(command: string, prompt: string, model: string): Promise<void> {
+  return invoke("set_post_process", { command, prompt, model });
+}
+
+/** Store the post-processing endpoint's API key in the credential store; empty removes it. */
+export async function setPostProcessApiKey(key: string): Promise<void> {
+  return invoke("set_post_process_api_key", { key });
+}
+
+export async function setTranscriptionBackend(backend: BackendKind, openaiModel: string): Promise<void> {
+  return invoke("set_transcription_backend", { backend, openaiModel });
+}
+
+export async function setAzureRegion(region: string): Promise<void> {
+  return invoke("set_azure_region", { region });
+}
+
+/** Proxy URL for downloads, the cloud backends and update checks; empty uses HTTPS_PROXY. */
+export async function setNetworkProxy(proxy: string): Promise<void> {
+  return invoke("set_network_proxy", { proxy });
+}
+
+/** Base URL of a Hugging Face mirror to download models from first; empty for none. */
+export async function setModelMirror(mirror: string): Promise<void> {
+  return invoke("set_model_mirror", { mirror });
+}
+
+/** Store a remote backend's API key in the OS keyring (or the encrypted credential file). */
+export async function setApiKey(backend: BackendKind, key: string): Promise<void> {
+  return invoke("set_api_key", { backend, key });
+}
+
+export async function hasApiKey(backend: BackendKind): Promise<boolean> {
+  return invoke("has_api_key", { backend });
+}
+
+export async function clearApiKey(backend: BackendKind): Promise<void> {
+  return invoke("clear_api_key", { backend });
+}
+
+export async function setDraftPass(enabled: boolean): Promise<void> {
+  return invoke("set_draft_pass", { enabled });
+}
+
+export async function setReplaceDraft(enabled: boolean): Promise<void> {
+  return invoke("set_replace_draft", { enabled });
+}
+
+export async function setDedupeDictations(enabled: boolean): Promise<void> {
+  return invoke("set_dedupe_dictations", { enabled });
+}
+
+export async function setDryRun(enabled: boolean): Promise<void> {
+  return invoke("set_dry_run", { enabled });
+}
+
+export async function setPasteFormatting(enabled: boolean): Promise<void> {
+  return invoke("set_paste_formatting", { enabled });
+}
+
+/** Set the paste format for `app`; `null` removes its rule. */
+export async function setPasteAppRule(app: string, category: AppCategory | null): Promise<void> {
+  return invoke("set_paste_app_rule", { app, category });
+}
+
+export async function setTrayVisibility(visible: boolean): Promise<void> {
+  return invoke("set_tray_visibility", { visible });
+}
+
+export async function setTrayStatusText(enabled: boolean): Promise<void> {
+  return invoke("set_tray_status_text", { enabled });
+}
+
+export async function setTrayMenu(items: TrayMenuItem[]): Promise<void> {
+  return invoke("set_tray_menu", { items });
+}
+
+export async function setFfmpegFallback(enabled: boolean): Promise<void> {
+  return invoke("set_ffmpeg_fallback", { enabled });
+}
+
+export async function getFfmpegPath(): Promise<string | null> {
+  return invoke("get_ffmpeg_path");
+}
+
+export async function installCli(): Promise<CliInstall> {
+  return invoke("install_cli");
+}
+
+export async function setTelemetry(enabled: boolean): Promise<void> {
+  return invoke("set_telemetry", { enabled });
+}
+
+export async function getTelemetryQueue(): Promise<TelemetryDay[]> {
+  return invoke("get_telemetry_queue");
+}
+
+export async function clearTelemetryQueue(): Promise<void> {
+  return invoke("clear_telemetry_queue");
+}
+
+export async function getEventStreamToken(): Promise<string> {
+  return invoke("get_event_stream_token");
+}
+
+export async function setAdaptiveModel(enabled: boolean): Promise<void> {
+  return invoke("set_adaptive_model", { enabled });
+}
+
+export async function setAdaptiveClipThresholds(shortSecs: number, longSecs: number): Promise<void> {
+  return invoke("set_adaptive_clip_thresholds", { shortSecs, longSecs });
+}
+
+export async function setOutputSinks(sinks: OutputSink[]): Promise<void> {
+  return invoke("set_output_sinks", { sinks });
+}
+
+/** Store the MQTT broker password in the credential store; empty removes it. */
+export async function setMqttPassword(password: string): Promise<void> {
+  return invoke("set_mqtt_password", { password });
+}
+
+export async function setAccumulateMode(enabled: boolean): Promise<void> {
+  return invoke("set_accumulate_mode", { enabled });
+}
+
+export async function setFlushOptions(hotkey: string, phrase: string): Promise<void> {
+  return invoke("set_flush_options", { hotkey, phrase });
+}
+
+export type SettingsTab = "dictate" | "transcribe" | "settings" | "models" | "history" | "diagnostics";
+
+export async function setSettingsHotkey(hotkey: string, tab: SettingsTab): Promise<void> {
+  return invoke("set_settings_hotkey", { hotkey, tab });
+}
+
+export async function openSettingsTab(tab: SettingsTab): Promise<void> {
+  return invoke("open_settings_tab", { tab });
+}
+
+export async function closeSettings(): Promise<void> {
+  return invoke("close_settings");
+}
+
+export async function getAccumulated(): Promise<AccumulatedSnapshot> {
+  return invoke("get_accumulated");
+}
+
+export async function flushAccumulated(): Promise<string | null> {
+  return invoke("flush_accumulated");
+}
+
+export async function clearAccumulated(): Promise<void> {
+  return invoke("clear_accumulated");
+}
+
 export async function getModelInfo(): Promise<WhisperModel[]> {
   return invoke("get_model_info");
 }
@@ -127,21 +775,141 @@ export async function downloadModel(whisperModel: string): Promise<void> {
   return invoke("download_model", { whisperModel });
 }
 
+/** Delete a downloaded model's files. Rejects for the model in use unless
+ * `force`. Resolves to the bytes freed. */
+export async function deleteModel(whisperModel: string, force = false): Promise<number> {
+  return invoke("delete_model", { whisperModel, force });
+}
+
+/** The model to suggest for `language`, going by this machine's memory,
+ * CPU cores and GPU. */
+export async function getRecommendedModel(language: string): Promise<Recommendation> {
+  return invoke("get_recommended_model", { language });
+}
+
+/** Save the language with the model recommended for this machine, download
+ * it (emitting model-download-progress) and load it. Resolves to the model
+ * ID. */
+export async function setupRecommended(language: string): Promise<string> {
+  return invoke("setup_recommended", { language });
+}
+
 export async function getBuildInfo(): Promise<BuildInfo> {
   return invoke("get_build_info");
 }
 
+/** An event the backend emits and the TypeScript type of its payload. */
+export interface EventSchemaEntry {
+  name: string;
+  payload: string;
+}
+
+/** The backend's events (`sagascript event-schema`). */
+export interface EventSchema {
+  version: number;
+  events: EventSchemaEntry[];
+}
+
+export async function getEventSchema(): Promise<EventSchema> {
+  return invoke("get_event_schema");
+}
+
+/** Warn when the backend sends a different event schema than `events.ts`
+ * was generated from, e.g. a stale frontend build. */
+export async function checkEventSchema(): Promise<boolean> {
+  const schema = await getEventSchema();
+  if (schema.version !== EVENT_SCHEMA_VERSION) {
+    console.warn(
+      `Event schema ${schema.version} from the backend, ${EVENT_SCHEMA_VERSION} expected`,
+    );
+    return false;
+  }
+  return true;
+}
+
+export async function getWhisperRuntimeInfo(): Promise<WhisperRuntimeInfo> {
+  return invoke("get_whisper_runtime_info");
+}
+
+/** Register a local GGML file as a custom model; resolves to its saved path. */
+export async function addCustomModel(name: string, path: string): Promise<string> {
+  return invoke("add_custom_model", { name, path });
+}
+
+export async function removeCustomModel(name: string): Promise<void> {
+  return invoke("remove_custom_model", { name });
+}
+
+/** Transcribe with a registered custom model; "" = the built-in models. */
+export async function setCustomModel(name: string): Promise<void> {
+  return invoke("set_custom_model", { name });
+}
+
+export async function getAccelerationInfo(): Promise<AccelerationInfo> {
+  return invoke("get_acceleration_info");
+}
+
+export async function setUseGpu(enabled: boolean): Promise<void> {
+  return invoke("set_use_gpu", { enabled });
+}
+
+export async function setPreloadModel(enabled: boolean): Promise<void> {
+  return invoke("set_preload_model", { enabled });
+}
+
+/** Language and model for one transcription, in place of the saved ones. */
+export interface TranscribeOverrides {
+  language?: Language;
+  /** Model id, e.g. "base.en". */
+  model?: string;
+}
+
 export async function transcribeFile(
   filePath: string,
-  options?: { prompt?: string; diarize?: boolean }
+  options?: { prompt?: string; diarize?: boolean } & TranscribeOverrides
 ): Promise<string> {
   return invoke("transcribe_file", {
     filePath,
     prompt: options?.prompt ?? null,
     diarize: options?.diarize ?? false,
+    language: options?.language ?? null,
+    model: options?.model ?? null,
   });
 }
 
+/** Write Markdown minutes of the last diarized or channel-split file transcription to `path`. */
+export async function exportMinutes(path: string): Promise<void> {
+  return invoke("export_minutes", { path });
+}
+
+/** A dictation the last run didn't finish because the app crashed. */
+export interface RecoverableSession {
+  stage: "recording" | "transcribing";
+  /** Seconds since the Unix epoch. */
+  started_at: number;
+  language: Language;
+  model: string;
+  audio_secs: number;
+}
+
+export async function getRecoverableSession(): Promise<RecoverableSession | null> {
+  return invoke("get_recoverable_session");
+}
+
+/** Transcribe the interrupted dictation's audio; forgotten once it succeeds. */
+export async function retryRecoverableSession(): Promise<string> {
+  return invoke("retry_recoverable_session");
+}
+
+export async function discardRecoverableSession(): Promise<void> {
+  return invoke("discard_recoverable_session");
+}
+
+/** File handed to the app from outside (Services, "Open With"), if any. */
+export async function takeRequestedFile(): Promise<string | null> {
+  return invoke("take_requested_file");
+}
+
 export async function getSupportedFormats(): Promise<string[]> {
   return invoke("get_supported_formats");
 }
@@ -150,8 +918,11 @@ export async function startRecording(): Promise<void> {
   return invoke("start_recording");
 }
 
-export async function stopAndTranscribe(): Promise<string> {
-  return invoke("stop_and_transcribe");
+export async function stopAndTranscribe(overrides?: TranscribeOverrides): Promise<string> {
+  return invoke("stop_and_transcribe", {
+    language: overrides?.language ?? null,
+    model: overrides?.model ?? null,
+  });
 }
 
 // -- Permission / platform queries (for onboarding) --
@@ -0,0 +1,86 @@
+// Generated from sagascript-core's events module by
+// `sagascript event-schema --typescript`. Do not edit.
+
+import type {
+  AccumulatedSnapshot,
+  CaptureSource,
+  DictationErrorPayload,
+  HotkeyStatus,
+  InputLevelWarning,
+  ModelSuggestion,
+  ModelUpdate,
+  OverlayAppearance,
+  PasteConfirmation,
+  SettingsLoadIssue,
+  SettingsTab,
+  StageProgress,
+  Transcript,
+  TranscriptionTiming,
+  WhileBusy,
+} from "./api";
+
+export const EVENT_SCHEMA_VERSION = 1;
+
+export const EVENTS = {
+  STATE_CHANGED: "state-changed",
+  TRANSCRIPTION_RESULT: "transcription-result",
+  ERROR: "error",
+  MODEL_DOWNLOAD_PROGRESS: "model-download-progress",
+  MODEL_READY: "model-ready",
+  TRANSCRIPTION_PROGRESS: "transcription-progress",
+  HOTKEY_REGISTRATION_CHANGED: "hotkey-registration-changed",
+  TRANSCRIPTION_PARTIAL: "transcription-partial",
+  MODEL_SUGGESTION: "model-suggestion",
+  ACCUMULATED_CHANGED: "accumulated-changed",
+  ACCUMULATED_FLUSHED: "accumulated-flushed",
+  MODEL_UPDATES_AVAILABLE: "model-updates-available",
+  INPUT_LEVEL_WARNING: "input-level-warning",
+  BUSY: "busy",
+  TRANSCRIBE_FILE_REQUESTED: "transcribe-file-requested",
+  OVERLAY_APPEARANCE: "overlay-appearance",
+  ANNOUNCEMENT: "announcement",
+  RECORDING_LIMIT: "recording-limit",
+  TRANSCRIPT: "transcript",
+  DICTATION_PROFILE: "dictation-profile",
+  PASTE_CONFIRMATION: "paste-confirmation",
+  CAPTURE_SOURCE_CHANGED: "capture-source-changed",
+  SETTINGS_LOAD_WARNING: "settings-load-warning",
+  DICTATION_PACE: "dictation-pace",
+  DICTATION_DEDUPED: "dictation-deduped",
+  SETTINGS_CHANGED: "settings-changed",
+  TRANSCRIPTION_COMPLETE: "transcription-complete",
+  NAVIGATE_TAB: "navigate-tab",
+} as const;
+
+export type EventName = (typeof EVENTS)[keyof typeof EVENTS];
+
+export interface EventPayloads {
+  "state-changed": "idle" | "loading_model" | "recording" | "transcribing" | "settings_reloaded";
+  "transcription-result": string;
+  "error": DictationErrorPayload | string;
+  "model-download-progress": { model: string; downloaded: number; total: number; progress: number };
+  "model-ready": null;
+  "transcription-progress": StageProgress;
+  "hotkey-registration-changed": HotkeyStatus;
+  "transcription-partial": string;
+  "model-suggestion": ModelSuggestion;
+  "accumulated-changed": AccumulatedSnapshot;
+  "accumulated-flushed": string;
+  "model-updates-available": ModelUpdate[];
+  "input-level-warning": InputLevelWarning;
+  "busy": { whileBusy: WhileBusy };
+  "transcribe-file-requested": null;
+  "overlay-appearance": OverlayAppearance;
+  "announcement": string;
+  "recording-limit": { remainingSecs: number; limitSecs: number };
+  "transcript": Transcript;
+  "dictation-profile": { name: string; overlayColor: string | null } | null;
+  "paste-confirmation": PasteConfirmation | null;
+  "capture-source-changed": CaptureSource;
+  "settings-load-warning": SettingsLoadIssue | null;
+  "dictation-pace": { words: number; wpm: number | null; live: boolean };
+  "dictation-deduped": { sinceLastSecs: number };
+  "settings-changed": { reason: "schedule"; profile: string };
+  "transcription-complete": TranscriptionTiming;
+  "navigate-tab": SettingsTab;
+}